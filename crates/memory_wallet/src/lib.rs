@@ -8,6 +8,7 @@ use derive_more::derive::DerefMut;
 use futures::future::try_join_all;
 use indexmap::Equivalent;
 use indexmap::IndexSet;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use solana_sdk::signature::Signature;
@@ -19,14 +20,18 @@ use wallet_standard::SOLANA_SIGN_TRANSACTION;
 use wallet_standard::STANDARD_CONNECT;
 use wallet_standard::STANDARD_DISCONNECT;
 use wallet_standard::STANDARD_EVENTS;
+use wallet_standard::SolanaSignAndSendTransactionMode;
+use wallet_standard::SolanaSignAndSendTransactionOptions;
 use wallet_standard::SolanaSignAndSendTransactionProps;
 use wallet_standard::SolanaSignInInput;
 use wallet_standard::SolanaSignTransactionProps;
 use wallet_standard::StandardConnectInput;
 use wallet_standard::create_sign_in_message_text;
 use wallet_standard::prelude::*;
+use wasm_client_solana::RpcSendTransactionConfig;
 use wasm_client_solana::SolanaRpcClient;
 use wasm_client_solana::prelude::*;
+use wasm_client_solana::solana_transaction_status::UiTransactionEncoding;
 
 #[derive(Debug, Deref, DerefMut)]
 pub struct MemoryWalletAccountInfo {
@@ -285,10 +290,18 @@ impl WalletStandardDisconnect for MemoryWallet {
 impl WalletSolanaSignAndSendTransaction for MemoryWallet {
 	type Output = Signature;
 
+	/// Sign the transaction with the connected account and send it with the
+	/// rpc client of the wallet.
+	///
+	/// A fresh blockhash is attached when the transaction has the default
+	/// blockhash. The transaction is confirmed before returning when a
+	/// `commitment` is set in the options.
 	async fn sign_and_send_transaction(
 		&self,
 		SolanaSignAndSendTransactionProps {
-			mut transaction, ..
+			mut transaction,
+			options,
+			..
 		}: SolanaSignAndSendTransactionProps,
 	) -> WalletResult<Self::Output> {
 		let Some(ref account) = self.account else {
@@ -305,20 +318,73 @@ impl WalletSolanaSignAndSendTransaction for MemoryWallet {
 				None
 			},
 		)?;
-		let signature = self.rpc.send_transaction(&transaction).await?;
+
+		let SolanaSignAndSendTransactionOptions {
+			preflight_commitment,
+			min_context_slot,
+			commitment,
+			skip_preflight,
+			max_retries,
+			..
+		} = options.unwrap_or_default();
+		let config = RpcSendTransactionConfig {
+			skip_preflight: skip_preflight.unwrap_or_default(),
+			preflight_commitment: preflight_commitment.or(Some(self.rpc.commitment())),
+			encoding: Some(UiTransactionEncoding::Base64),
+			max_retries: max_retries.map(usize::from),
+			min_context_slot,
+			..Default::default()
+		};
+
+		let signature = match commitment {
+			Some(commitment) => {
+				self.rpc
+					.send_and_confirm_transaction_with_config(
+						&transaction,
+						CommitmentConfig { commitment },
+						config,
+					)
+					.await?
+			}
+			None => {
+				self.rpc
+					.send_transaction_with_config(&transaction, config)
+					.await?
+			}
+		};
 
 		Ok(signature)
 	}
 
+	/// Sign and send every transaction.
+	///
+	/// When any of the inputs uses [`SolanaSignAndSendTransactionMode::Serial`]
+	/// the transactions are sent one after the other in the order provided,
+	/// otherwise they are sent concurrently.
 	async fn sign_and_send_transactions(
 		&self,
 		inputs: Vec<SolanaSignAndSendTransactionProps>,
 	) -> WalletResult<Vec<Self::Output>> {
-		let futures = inputs
-			.into_iter()
-			.map(|input| self.sign_and_send_transaction(input));
+		let serial = inputs.iter().any(|input| {
+			input.options.as_ref().and_then(|options| options.mode)
+				== Some(SolanaSignAndSendTransactionMode::Serial)
+		});
 
-		try_join_all(futures).await
+		if !serial {
+			let futures = inputs
+				.into_iter()
+				.map(|input| self.sign_and_send_transaction(input));
+
+			return try_join_all(futures).await;
+		}
+
+		let mut signatures = Vec::with_capacity(inputs.len());
+
+		for input in inputs {
+			signatures.push(self.sign_and_send_transaction(input).await?);
+		}
+
+		Ok(signatures)
 	}
 }
 
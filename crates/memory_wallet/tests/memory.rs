@@ -5,6 +5,7 @@ use assert2::check;
 use memory_wallet::MemoryWallet;
 use solana_sdk::account::Account;
 use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::commitment_config::CommitmentLevel;
 use solana_sdk::hash::Hash;
 use solana_sdk::native_token::sol_to_lamports;
 use solana_sdk::pubkey::Pubkey;
@@ -18,6 +19,8 @@ use test_utils_solana::ProgramTestContext;
 use test_utils_solana::TestValidatorRunner;
 use test_utils_solana::TestValidatorRunnerProps;
 use test_utils_solana::prelude::*;
+use wallet_standard::SolanaSignAndSendTransactionMode;
+use wallet_standard::SolanaSignAndSendTransactionOptions;
 use wallet_standard::SolanaSignAndSendTransactionProps;
 use wallet_standard::SolanaSignTransactionProps;
 use wasm_client_solana::LOCALNET;
@@ -75,6 +78,50 @@ async fn sign_and_send_transaction() -> Result<()> {
 	Ok(())
 }
 
+#[test(tokio::test(flavor = "multi_thread"))]
+async fn sign_and_send_transactions_serially() -> Result<()> {
+	let runner = create_runner().await;
+	let keypair = get_wallet_keypair();
+	let pubkey = keypair.pubkey();
+	let rpc = runner.rpc().clone();
+	let mut memory_wallet = MemoryWallet::new(rpc.clone(), &[keypair]);
+
+	memory_wallet.connect().await?;
+
+	let options = SolanaSignAndSendTransactionOptions::builder()
+		.mode(SolanaSignAndSendTransactionMode::Serial)
+		.commitment(CommitmentLevel::Confirmed)
+		.build();
+	let inputs = (1..=2)
+		.map(|lamports| {
+			let instruction = transfer(
+				&pubkey,
+				&Pubkey::new_unique(),
+				sol_to_lamports(0.1) + lamports,
+			);
+			let transaction = VersionedTransaction::new_unsigned_v0(
+				&pubkey,
+				&[instruction],
+				&[],
+				Hash::default(),
+			)?;
+
+			Ok(SolanaSignAndSendTransactionProps::builder()
+				.transaction(transaction)
+				.options(options.clone())
+				.build())
+		})
+		.collect::<Result<Vec<_>>>()?;
+	let signatures = memory_wallet.sign_and_send_transactions(inputs).await?;
+
+	check!(signatures.len() == 2);
+
+	let statuses = rpc.get_signature_statuses(&signatures).await?;
+	check!(statuses.iter().all(Option::is_some));
+
+	Ok(())
+}
+
 #[test(tokio::test)]
 async fn banks_client_process_transaction() -> Result<()> {
 	let keypair = get_wallet_keypair();
@@ -54,6 +54,33 @@ impl TestValidatorGenesisExtensions for test_utils_solana::TestValidatorGenesis
 	}
 }
 
+#[cfg(feature = "test_validator")]
+#[async_trait(?Send)]
+pub trait TestValidatorRunnerAnchorExtension {
+	/// Overwrite the account at `pubkey` in the running validator with the
+	/// anchor `data` and wait until it is visible through the runner's rpc.
+	async fn set_anchor_account<T: AnchorSerialize + Discriminator>(
+		&self,
+		pubkey: &Pubkey,
+		owner: &Pubkey,
+		data: T,
+	) -> anyhow::Result<solana_sdk::account::Account>;
+}
+
+#[cfg(feature = "test_validator")]
+#[async_trait(?Send)]
+impl TestValidatorRunnerAnchorExtension for test_utils_solana::TestValidatorRunner {
+	async fn set_anchor_account<T: AnchorSerialize + Discriminator>(
+		&self,
+		pubkey: &Pubkey,
+		owner: &Pubkey,
+		data: T,
+	) -> anyhow::Result<solana_sdk::account::Account> {
+		self.set_account(pubkey, AccountSharedData::from_anchor_data(data, *owner))
+			.await
+	}
+}
+
 #[async_trait(?Send)]
 pub trait BanksClientAsyncAnchorExtension {
 	async fn get_anchor_account<T: AccountDeserialize>(
@@ -204,6 +231,8 @@ pub mod prelude {
 	pub use super::ProgramTestContextAnchorExtension;
 	#[cfg(feature = "test_validator")]
 	pub use super::TestValidatorGenesisExtensions;
+	#[cfg(feature = "test_validator")]
+	pub use super::TestValidatorRunnerAnchorExtension;
 }
 
 #[doc(hidden)]
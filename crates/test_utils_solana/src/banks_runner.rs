@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use async_trait::async_trait;
+use solana_program_test::ProgramTest;
+use solana_sdk::account::Account;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::account::ReadableAccount;
+use solana_sdk::account::WritableAccount;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::bpf_loader_upgradeable::UpgradeableLoaderState;
+use solana_sdk::clock::Clock;
+use solana_sdk::clock::Slot;
+use solana_sdk::clock::UnixTimestamp;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::Keypair;
+use wasm_client_solana::SolanaRpcClient;
+
+use crate::TestProgramInfo;
+use crate::TestRpcProvider;
+use crate::TestRunner;
+use crate::TestValidatorRunnerProps;
+use crate::runner_props::fetch_cloned_accounts;
+use crate::runner_props::load_account_fixtures;
+
+/// A lightweight alternative to the
+/// [`TestValidatorRunner`](crate::TestValidatorRunner) which executes
+/// transactions with the banks client of [`ProgramTest`] instead of booting a
+/// validator.
+///
+/// It is started from the same [`TestValidatorRunnerProps`] and both runners
+/// implement [`TestRunner`], so a test written against the trait can switch
+/// between them by replacing [`TestValidatorRunnerProps::run`] with
+/// [`TestValidatorRunnerProps::run_banks`].
+///
+/// The [`rpc`](BanksRunner::rpc) client only supports the methods of the
+/// [`TestRpcProvider`] and there is no pubsub. The `ports`, `port_range`,
+/// `epoch_schedule`, `fee_rate_governor`, `rent`, `faucet`, `ledger_path`
+/// and `keep_ledger_on_drop` props are ignored.
+#[derive(Clone)]
+pub struct BanksRunner {
+	provider: TestRpcProvider,
+	/// The keypair funded at genesis which pays for the runner's
+	/// transactions.
+	payer: Arc<Keypair>,
+	rpc: SolanaRpcClient,
+}
+
+impl BanksRunner {
+	async fn run_internal(
+		TestValidatorRunnerProps {
+			programs,
+			pubkeys,
+			initial_lamports,
+			commitment,
+			accounts,
+			warp_slot,
+			deactivate_features,
+			clone_from,
+			clone_accounts,
+			clone_programs,
+			clone_program_accounts,
+			account_fixtures,
+			account_fixture_dirs,
+			..
+		}: TestValidatorRunnerProps,
+	) -> Result<Self> {
+		let mut program_test = ProgramTest::default();
+		let cloned_accounts = match clone_from {
+			Some(url) => {
+				fetch_cloned_accounts(
+					&SolanaRpcClient::new(&url),
+					&clone_accounts,
+					&clone_programs,
+					&clone_program_accounts,
+				)
+				.await?
+			}
+			None if clone_accounts.is_empty()
+				&& clone_programs.is_empty()
+				&& clone_program_accounts.is_empty() =>
+			{
+				vec![]
+			}
+			None => bail!("`clone_from` must be set to clone accounts into the banks runner"),
+		};
+		let fixture_accounts = load_account_fixtures(&account_fixtures, &account_fixture_dirs)?;
+		let funded_accounts = pubkeys.iter().map(|pubkey| {
+			(
+				*pubkey,
+				AccountSharedData::new(initial_lamports, 0, &Pubkey::default()),
+			)
+		});
+
+		for feature_id in deactivate_features {
+			program_test.deactivate_feature(feature_id);
+		}
+
+		for program in &programs {
+			for (pubkey, account) in get_program_accounts(program)? {
+				program_test.add_account(pubkey, account.into());
+			}
+		}
+
+		for (pubkey, account) in cloned_accounts
+			.into_iter()
+			.chain(fixture_accounts)
+			.chain(funded_accounts)
+			.chain(accounts)
+		{
+			program_test.add_account(pubkey, account.into());
+		}
+
+		let mut context = program_test.start_with_context().await;
+
+		if warp_slot > context.banks_client.get_root_slot().await? {
+			context
+				.warp_to_slot(warp_slot)
+				.with_context(|| format!("failed to warp to slot {warp_slot}"))?;
+		}
+
+		let payer = Arc::new(context.payer.insecure_clone());
+		let provider = TestRpcProvider::new(context);
+		let rpc = SolanaRpcClient::new_with_provider_and_commitment(
+			provider.clone(),
+			CommitmentConfig { commitment },
+		);
+
+		Ok(Self {
+			provider,
+			payer,
+			rpc,
+		})
+	}
+
+	/// Start a banks client with the accounts and programs of the `props`.
+	pub async fn run(props: TestValidatorRunnerProps) -> Self {
+		Self::run_internal(props).await.unwrap()
+	}
+
+	pub fn rpc(&self) -> &SolanaRpcClient {
+		&self.rpc
+	}
+
+	/// The provider wrapping the
+	/// [`ProgramTestContext`](crate::ProgramTestContext) for direct access to
+	/// the banks client.
+	pub fn provider(&self) -> &TestRpcProvider {
+		&self.provider
+	}
+
+	pub fn payer(&self) -> &Keypair {
+		&self.payer
+	}
+}
+
+#[async_trait(?Send)]
+impl TestRunner for BanksRunner {
+	fn rpc(&self) -> &SolanaRpcClient {
+		&self.rpc
+	}
+
+	fn payer(&self) -> &Keypair {
+		&self.payer
+	}
+
+	async fn set_account(&self, pubkey: &Pubkey, account: AccountSharedData) -> Result<Account> {
+		let mut context = self.provider.lock().await;
+		let rent = context.banks_client.get_rent().await?;
+		let minimum_balance = rent.minimum_balance(account.data().len());
+
+		if account.lamports() < minimum_balance {
+			bail!(
+				"cannot set the account {pubkey} with {} lamports which is below the rent-exempt \
+				 minimum of {minimum_balance} lamports for {} bytes of data",
+				account.lamports(),
+				account.data().len()
+			);
+		}
+
+		context.set_account(pubkey, &account);
+
+		Ok(account.into())
+	}
+
+	async fn get_clock(&self) -> Result<Clock> {
+		let mut context = self.provider.lock().await;
+
+		Ok(context.banks_client.get_sysvar::<Clock>().await?)
+	}
+
+	async fn warp_to_slot(&mut self, slot: Slot) -> Result<Clock> {
+		self.provider
+			.lock()
+			.await
+			.warp_to_slot(slot)
+			.with_context(|| format!("failed to warp to slot {slot}"))?;
+
+		self.get_clock().await
+	}
+
+	async fn set_clock_timestamp(&self, unix_timestamp: UnixTimestamp) -> Result<Clock> {
+		let clock = self.get_clock().await?;
+
+		if clock.unix_timestamp >= unix_timestamp {
+			bail!(
+				"cannot move the clock back to {unix_timestamp} from {}",
+				clock.unix_timestamp
+			);
+		}
+
+		self.provider.lock().await.set_sysvar(&Clock {
+			unix_timestamp,
+			..clock
+		});
+
+		self.get_clock().await
+	}
+}
+
+/// The accounts which deploy the `program` at genesis.
+fn get_program_accounts(program: &TestProgramInfo) -> Result<Vec<(Pubkey, AccountSharedData)>> {
+	let rent = Rent::default();
+	let program_bytes = std::fs::read(&program.program_path).with_context(|| {
+		format!(
+			"failed to read the program {}",
+			program.program_path.display()
+		)
+	})?;
+
+	if program.loader != bpf_loader_upgradeable::ID {
+		let mut account = AccountSharedData::new(
+			rent.minimum_balance(program_bytes.len()),
+			0,
+			&program.loader,
+		);
+		account.set_data_from_slice(&program_bytes);
+		account.set_executable(true);
+
+		return Ok(vec![(program.program_id, account)]);
+	}
+
+	let (programdata_address, _) =
+		Pubkey::find_program_address(&[program.program_id.as_ref()], &bpf_loader_upgradeable::ID);
+	let program_data = bincode::serialize(&UpgradeableLoaderState::Program {
+		programdata_address,
+	})?;
+	let programdata_data = [
+		bincode::serialize(&UpgradeableLoaderState::ProgramData {
+			slot: 0,
+			upgrade_authority_address: Some(program.upgrade_authority),
+		})?,
+		program_bytes,
+	]
+	.concat();
+	let mut program_account = AccountSharedData::new(
+		rent.minimum_balance(program_data.len()),
+		0,
+		&bpf_loader_upgradeable::ID,
+	);
+	program_account.set_data_from_slice(&program_data);
+	program_account.set_executable(true);
+	let mut programdata_account = AccountSharedData::new(
+		rent.minimum_balance(programdata_data.len()),
+		0,
+		&bpf_loader_upgradeable::ID,
+	);
+	programdata_account.set_data_from_slice(&programdata_data);
+
+	Ok(vec![
+		(program.program_id, program_account),
+		(programdata_address, programdata_account),
+	])
+}
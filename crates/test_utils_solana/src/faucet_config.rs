@@ -0,0 +1,59 @@
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+/// The lamports held by the faucet of a runner by default.
+pub const DEFAULT_FAUCET_LAMPORTS: u64 = 1_000_000 * LAMPORTS_PER_SOL;
+
+/// The lamports airdropped to the mint while the validator warms up.
+#[cfg(feature = "test_validator")]
+const WARM_UP_LAMPORTS: u64 = 500 * LAMPORTS_PER_SOL;
+
+/// The faucet which serves the airdrops of a
+/// [`TestValidatorRunner`](crate::TestValidatorRunner).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaucetConfig {
+	/// Run a faucet funded with `lamports` at genesis.
+	Enabled {
+		lamports: u64,
+		/// The most lamports a single airdrop can request.
+		per_request_cap: Option<u64>,
+		/// The most lamports which can be airdropped to an address, or
+		/// requested from an ip address, every minute.
+		per_time_cap: Option<u64>,
+	},
+	/// Run without a faucet so every airdrop fails.
+	Disabled,
+}
+
+impl Default for FaucetConfig {
+	fn default() -> Self {
+		Self::Enabled {
+			lamports: DEFAULT_FAUCET_LAMPORTS,
+			per_request_cap: None,
+			per_time_cap: None,
+		}
+	}
+}
+
+#[cfg(feature = "test_validator")]
+impl FaucetConfig {
+	/// The lamports airdropped to warm up the validator, limited to the caps
+	/// of the faucet. The airdrop comes from a loopback address so it only
+	/// counts against the time cap of the mint. The warm-up is skipped when
+	/// the faucet can't afford it.
+	pub(crate) fn warm_up_lamports(self) -> Option<u64> {
+		let Self::Enabled {
+			lamports,
+			per_request_cap,
+			per_time_cap,
+		} = self
+		else {
+			return None;
+		};
+		let warm_up_lamports = [per_request_cap, per_time_cap]
+			.into_iter()
+			.flatten()
+			.fold(WARM_UP_LAMPORTS, u64::min);
+
+		(warm_up_lamports > 0 && lamports > 2 * warm_up_lamports).then_some(warm_up_lamports)
+	}
+}
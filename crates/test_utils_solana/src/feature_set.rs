@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use agave_feature_set::FEATURE_NAMES;
+use anyhow::Result;
+use solana_feature_gate_interface::from_account;
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+use wasm_client_solana::MAINNET;
+use wasm_client_solana::SolanaRpcClient;
+
+/// The runtime features of a cluster, used to start a
+/// [`TestValidatorRunner`](crate::TestValidatorRunner) with the same
+/// features active.
+///
+/// The features which are checked are the ones known to the validator
+/// version this crate was built with.
+///
+/// ```rust
+/// use test_utils_solana::FeatureSet;
+/// use test_utils_solana::TestValidatorRunnerProps;
+///
+/// async fn run() -> anyhow::Result<()> {
+/// 	let features = FeatureSet::mainnet_current().await?;
+/// 	let runner = TestValidatorRunnerProps::builder()
+/// 		.deactivate_features(features.inactive())
+/// 		.build()
+/// 		.run()
+/// 		.await;
+///
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeatureSet {
+	active: BTreeMap<Pubkey, Slot>,
+	inactive: BTreeSet<Pubkey>,
+}
+
+impl FeatureSet {
+	/// Read the feature accounts of the cluster behind the `rpc`. A feature is
+	/// inactive when its account is missing or the activation is still
+	/// pending.
+	pub async fn fetch(rpc: &SolanaRpcClient) -> Result<Self> {
+		let feature_ids = FEATURE_NAMES.keys().copied().collect::<Vec<_>>();
+		let accounts = rpc.get_multiple_accounts(&feature_ids).await?;
+		let mut feature_set = Self::default();
+
+		for (feature_id, account) in feature_ids.into_iter().zip(accounts) {
+			let activated_at = account
+				.as_ref()
+				.and_then(from_account)
+				.and_then(|feature| feature.activated_at);
+
+			match activated_at {
+				Some(slot) => {
+					feature_set.active.insert(feature_id, slot);
+				}
+				None => {
+					feature_set.inactive.insert(feature_id);
+				}
+			}
+		}
+
+		Ok(feature_set)
+	}
+
+	/// The features currently active on
+	/// [`MAINNET`](wasm_client_solana::MAINNET).
+	pub async fn mainnet_current() -> Result<Self> {
+		Self::fetch(&SolanaRpcClient::new(MAINNET)).await
+	}
+
+	/// The active features with the slot they were activated in.
+	pub fn active(&self) -> &BTreeMap<Pubkey, Slot> {
+		&self.active
+	}
+
+	/// The inactive features, ready to be passed to
+	/// [`TestValidatorRunnerProps::deactivate_features`](crate::TestValidatorRunnerProps::deactivate_features).
+	pub fn inactive(&self) -> Vec<Pubkey> {
+		self.inactive.iter().copied().collect()
+	}
+
+	pub fn is_active(&self, feature_id: &Pubkey) -> bool {
+		self.active.contains_key(feature_id)
+	}
+}
@@ -1,3 +1,10 @@
+pub use banks_runner::*;
+pub use faucet_config::*;
+#[cfg(feature = "test_validator")]
+pub use feature_set::*;
+pub use mock_rpc_server::*;
+pub use pubsub_expectation::*;
+pub use runner_props::*;
 pub use solana_banks_client::BanksClientExt;
 pub use solana_banks_interface::BanksTransactionResultWithSimulation;
 pub use solana_program_runtime;
@@ -10,16 +17,41 @@ pub use solana_program_test::ProgramTestError;
 pub use solana_program_test::processor;
 pub use solana_program_test::programs;
 pub use solana_sdk;
+#[cfg(feature = "test_validator")]
+pub use state_snapshot::*;
+#[cfg(feature = "test_validator")]
+pub use test_cluster_runner::*;
 pub use test_rpc_provider::*;
+pub use test_runner::*;
 #[cfg(feature = "test_validator")]
 pub use test_validator_runner::*;
 pub use utils::*;
+pub use validator_ports::*;
 
+mod banks_runner;
+mod faucet_config;
+#[cfg(feature = "test_validator")]
+mod feature_set;
 mod macros;
+mod mock_rpc_server;
+mod pubsub_expectation;
+mod runner_props;
+#[cfg(feature = "test_validator")]
+mod state_snapshot;
+#[cfg(feature = "test_validator")]
+mod test_cluster_runner;
 mod test_rpc_provider;
+mod test_runner;
 #[cfg(feature = "test_validator")]
 mod test_validator_runner;
 mod utils;
+#[cfg(feature = "test_validator")]
+mod validator_faucet;
+#[cfg(feature = "test_validator")]
+mod validator_ledger;
+#[cfg(feature = "test_validator")]
+mod validator_logs;
+mod validator_ports;
 
 pub mod prelude {
 	pub use wallet_standard::prelude::*;
@@ -29,6 +61,7 @@ pub mod prelude {
 	pub use super::ProgramTestBanksClientExt;
 	pub use super::ProgramTestContextExtension;
 	pub use super::ProgramTestExtension;
+	pub use super::TestRunner;
 }
 
 #[doc(hidden)]
@@ -44,3 +44,17 @@ macro_rules! assert_banks_client_metadata_error {
 		);
 	}};
 }
+
+/// Assert that the transaction with the `signature` succeeded on the
+/// [`TestValidatorRunner`](crate::TestValidatorRunner). On failure this panics
+/// with the transaction's log messages and the validator logs captured around
+/// it.
+#[cfg(feature = "test_validator")]
+#[macro_export]
+macro_rules! assert_transaction_ok {
+	($runner:expr, $signature:expr $(,)?) => {{
+		if let Some(report) = $runner.transaction_failure(&$signature).await {
+			panic!("{report}");
+		}
+	}};
+}
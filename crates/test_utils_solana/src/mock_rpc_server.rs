@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_tungstenite::tokio::accept_async;
+use async_tungstenite::tungstenite::Message;
+use futures::SinkExt;
+use futures::StreamExt;
+use serde_json::Value;
+use serde_json::json;
+use solana_sdk::commitment_config::CommitmentConfig;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use wasm_client_solana::SolanaRpcClient;
+
+/// The JSON-RPC error code used when no response has been registered for a
+/// method.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// A canned response for a JSON-RPC method served by the [`MockRpcServer`].
+#[derive(Debug, Clone)]
+pub enum MockRpcResponse {
+	/// Always respond with this `result`.
+	Value(Value),
+	/// Respond with a JSON-RPC error.
+	Error { code: i64, message: String },
+	/// Respond with each response in turn. The last response is repeated once
+	/// the sequence has been exhausted.
+	Sequence(Vec<MockRpcResponse>),
+	/// Wait for the `delay` before responding.
+	Delayed {
+		delay: Duration,
+		response: Box<MockRpcResponse>,
+	},
+}
+
+impl MockRpcResponse {
+	/// Respond with the serialized `result`.
+	pub fn value(result: impl serde::Serialize) -> Self {
+		Self::Value(serde_json::to_value(result).unwrap_or(Value::Null))
+	}
+
+	/// Respond with a JSON-RPC error.
+	pub fn error(code: i64, message: impl Into<String>) -> Self {
+		Self::Error {
+			code,
+			message: message.into(),
+		}
+	}
+
+	/// Respond with each response in order, repeating the final one.
+	pub fn sequence(responses: impl IntoIterator<Item = impl Into<MockRpcResponse>>) -> Self {
+		Self::Sequence(responses.into_iter().map(Into::into).collect())
+	}
+
+	/// Delay the current response.
+	#[must_use]
+	pub fn delayed(self, delay: Duration) -> Self {
+		Self::Delayed {
+			delay,
+			response: Box::new(self),
+		}
+	}
+}
+
+impl From<Value> for MockRpcResponse {
+	fn from(value: Value) -> Self {
+		Self::Value(value)
+	}
+}
+
+/// A request received by the [`MockRpcServer`], either over http or the
+/// pubsub websocket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRpcRequest {
+	pub method: String,
+	pub params: Value,
+}
+
+#[derive(Debug, Default)]
+struct MockRpcState {
+	/// The registered responses and the number of times each method has been
+	/// called.
+	responses: HashMap<String, (MockRpcResponse, usize)>,
+	requests: Vec<MockRpcRequest>,
+	next_subscription_id: u64,
+}
+
+impl MockRpcState {
+	/// Resolve the response for the method, returning the delay to wait and the
+	/// JSON-RPC `result` or `error`.
+	fn resolve(&mut self, request: MockRpcRequest) -> (Duration, Result<Value, Value>) {
+		let entry = self.responses.get_mut(&request.method).map(|(response, calls)| {
+			let index = *calls;
+			*calls += 1;
+			(response.clone(), index)
+		});
+
+		let Some((mut response, index)) = entry else {
+			let error = json!({
+				"code": METHOD_NOT_FOUND,
+				"message": format!("Method not found: `{}`", request.method),
+			});
+			self.requests.push(request);
+
+			return (Duration::ZERO, Err(error));
+		};
+
+		self.requests.push(request);
+		let mut delay = Duration::ZERO;
+
+		loop {
+			response = match response {
+				MockRpcResponse::Value(value) => return (delay, Ok(value)),
+				MockRpcResponse::Error { code, message } => {
+					return (delay, Err(json!({ "code": code, "message": message })));
+				}
+				MockRpcResponse::Sequence(responses) => {
+					let Some(last) = responses.len().checked_sub(1) else {
+						return (delay, Ok(Value::Null));
+					};
+
+					responses[index.min(last)].clone()
+				}
+				MockRpcResponse::Delayed {
+					delay: extra,
+					response,
+				} => {
+					delay += extra;
+					*response
+				}
+			};
+		}
+	}
+}
+
+/// A local JSON-RPC server which serves canned responses.
+///
+/// This is a lightweight alternative to the `TestValidatorRunner` for testing
+/// client logic like retries and timeouts. The http server is bound to a random
+/// local port and the pubsub websocket server is bound to the next port, which
+/// matches the convention used by [`SolanaRpcClient::new`].
+///
+/// ```rust
+/// use serde_json::json;
+/// use test_utils_solana::MockRpcResponse;
+/// use test_utils_solana::MockRpcServer;
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let server = MockRpcServer::start().await?;
+/// server.mock(
+/// 	"getSlot",
+/// 	MockRpcResponse::sequence([
+/// 		MockRpcResponse::error(-32005, "Node is behind"),
+/// 		MockRpcResponse::value(100),
+/// 	]),
+/// );
+///
+/// assert!(server.rpc().get_slot().await.is_err());
+/// assert_eq!(server.rpc().get_slot().await?, 100);
+/// assert_eq!(server.requests_for("getSlot").len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(derive_more::Debug)]
+pub struct MockRpcServer {
+	rpc_addr: SocketAddr,
+	pubsub_addr: SocketAddr,
+	#[debug(skip)]
+	rpc: SolanaRpcClient,
+	state: Arc<Mutex<MockRpcState>>,
+	#[debug(skip)]
+	notifications: broadcast::Sender<Value>,
+	handles: Vec<JoinHandle<()>>,
+}
+
+impl MockRpcServer {
+	/// Start the server on random local ports.
+	pub async fn start() -> Result<Self> {
+		let (rpc_listener, pubsub_listener) = bind_listeners().await?;
+		let rpc_addr = rpc_listener.local_addr()?;
+		let pubsub_addr = pubsub_listener.local_addr()?;
+		let state = Arc::new(Mutex::new(MockRpcState::default()));
+		let (notifications, _) = broadcast::channel(256);
+		let handles = vec![
+			tokio::spawn(serve_rpc(rpc_listener, state.clone())),
+			tokio::spawn(serve_pubsub(
+				pubsub_listener,
+				state.clone(),
+				notifications.clone(),
+			)),
+		];
+		let rpc = SolanaRpcClient::new_with_ws_and_commitment(
+			&format!("http://{rpc_addr}"),
+			&format!("ws://{pubsub_addr}"),
+			CommitmentConfig::confirmed(),
+		);
+
+		Ok(Self {
+			rpc_addr,
+			pubsub_addr,
+			rpc,
+			state,
+			notifications,
+			handles,
+		})
+	}
+
+	pub fn rpc_url(&self) -> String {
+		format!("http://{}", self.rpc_addr)
+	}
+
+	pub fn pubsub_url(&self) -> String {
+		format!("ws://{}", self.pubsub_addr)
+	}
+
+	/// A client connected to this server.
+	pub fn rpc(&self) -> &SolanaRpcClient {
+		&self.rpc
+	}
+
+	/// Register the response for a JSON-RPC `method`. This replaces any
+	/// previously registered response and resets its call count.
+	pub fn mock(&self, method: impl Into<String>, response: impl Into<MockRpcResponse>) {
+		self.lock()
+			.responses
+			.insert(method.into(), (response.into(), 0));
+	}
+
+	/// All requests received, in order.
+	pub fn requests(&self) -> Vec<MockRpcRequest> {
+		self.lock().requests.clone()
+	}
+
+	/// All requests received for the `method`, in order.
+	pub fn requests_for(&self, method: &str) -> Vec<MockRpcRequest> {
+		self.lock()
+			.requests
+			.iter()
+			.filter(|request| request.method == method)
+			.cloned()
+			.collect()
+	}
+
+	/// Remove all recorded requests.
+	pub fn clear_requests(&self) {
+		self.lock().requests.clear();
+	}
+
+	/// Push a pubsub notification to every connected websocket client.
+	///
+	/// The `method` is the notification name, e.g. `logsNotification`, and the
+	/// `subscription` is the id returned when the subscription was created.
+	/// Subscription ids are allocated sequentially starting from `0`.
+	pub fn notify(&self, method: &str, subscription: u64, result: impl serde::Serialize) {
+		let notification = json!({
+			"jsonrpc": "2.0",
+			"method": method,
+			"params": {
+				"result": serde_json::to_value(result).unwrap_or(Value::Null),
+				"subscription": subscription,
+			},
+		});
+
+		// there may be no connected clients yet.
+		let _ = self.notifications.send(notification);
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, MockRpcState> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+}
+
+impl Drop for MockRpcServer {
+	fn drop(&mut self) {
+		for handle in &self.handles {
+			handle.abort();
+		}
+	}
+}
+
+/// Bind the http listener to a random port and the pubsub listener to the
+/// following port.
+async fn bind_listeners() -> Result<(TcpListener, TcpListener)> {
+	for _ in 0..100 {
+		let rpc_listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+		let Some(pubsub_port) = rpc_listener.local_addr()?.port().checked_add(1) else {
+			continue;
+		};
+
+		if let Ok(pubsub_listener) = TcpListener::bind((Ipv4Addr::LOCALHOST, pubsub_port)).await {
+			return Ok((rpc_listener, pubsub_listener));
+		}
+	}
+
+	Err(anyhow::anyhow!("unable to find two consecutive free ports"))
+}
+
+async fn serve_rpc(listener: TcpListener, state: Arc<Mutex<MockRpcState>>) {
+	while let Ok((stream, _)) = listener.accept().await {
+		tokio::spawn(handle_http_connection(stream, state.clone()));
+	}
+}
+
+/// Serve http requests on the connection until it is closed.
+async fn handle_http_connection(stream: TcpStream, state: Arc<Mutex<MockRpcState>>) -> Result<()> {
+	let mut reader = BufReader::new(stream);
+
+	loop {
+		let mut content_length = 0;
+		let mut line = String::new();
+
+		if reader.read_line(&mut line).await? == 0 {
+			return Ok(());
+		}
+
+		loop {
+			line.clear();
+			reader.read_line(&mut line).await?;
+			let header = line.trim();
+
+			if header.is_empty() {
+				break;
+			}
+
+			if let Some((_, value)) = header
+				.split_once(':')
+				.filter(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+			{
+				content_length = value.trim().parse()?;
+			}
+		}
+
+		let mut body = vec![0; content_length];
+		reader.read_exact(&mut body).await?;
+
+		let response = match serde_json::from_slice::<Value>(&body) {
+			Ok(request) => respond(&state, &request).await,
+			Err(error) => {
+				json!({
+					"jsonrpc": "2.0",
+					"id": null,
+					"error": { "code": -32700, "message": error.to_string() },
+				})
+			}
+		};
+		let body = serde_json::to_vec(&response)?;
+		let head = format!(
+			"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n",
+			body.len()
+		);
+
+		let stream = reader.get_mut();
+		stream.write_all(head.as_bytes()).await?;
+		stream.write_all(&body).await?;
+	}
+}
+
+/// Build the JSON-RPC response for a single request.
+async fn respond(state: &Mutex<MockRpcState>, request: &Value) -> Value {
+	let id = request.get("id").cloned().unwrap_or(Value::Null);
+	let request = MockRpcRequest {
+		method: request
+			.get("method")
+			.and_then(Value::as_str)
+			.unwrap_or_default()
+			.to_string(),
+		params: request.get("params").cloned().unwrap_or(Value::Null),
+	};
+	let (delay, result) = state
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.resolve(request);
+
+	if !delay.is_zero() {
+		tokio::time::sleep(delay).await;
+	}
+
+	match result {
+		Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+		Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+	}
+}
+
+async fn serve_pubsub(
+	listener: TcpListener,
+	state: Arc<Mutex<MockRpcState>>,
+	notifications: broadcast::Sender<Value>,
+) {
+	while let Ok((stream, _)) = listener.accept().await {
+		tokio::spawn(handle_pubsub_connection(
+			stream,
+			state.clone(),
+			notifications.subscribe(),
+		));
+	}
+}
+
+/// Acknowledge subscriptions and forward notifications until the socket is
+/// closed.
+async fn handle_pubsub_connection(
+	stream: TcpStream,
+	state: Arc<Mutex<MockRpcState>>,
+	mut notifications: broadcast::Receiver<Value>,
+) -> Result<()> {
+	let (mut sink, mut stream) = accept_async(stream).await?.split();
+
+	loop {
+		tokio::select! {
+			message = stream.next() => {
+				let Some(message) = message.transpose()? else {
+					return Ok(());
+				};
+				let Ok(text) = message.to_text() else {
+					continue;
+				};
+				let Ok(request) = serde_json::from_str::<Value>(text) else {
+					continue;
+				};
+
+				let response = respond_pubsub(&state, &request).await;
+				sink.send(Message::text(response.to_string())).await?;
+			}
+			notification = notifications.recv() => {
+				match notification {
+					Ok(notification) => {
+						sink.send(Message::text(notification.to_string())).await?;
+					}
+					Err(broadcast::error::RecvError::Lagged(_)) => {}
+					Err(broadcast::error::RecvError::Closed) => return Ok(()),
+				}
+			}
+		}
+	}
+}
+
+/// Respond to `*Subscribe` methods with a new subscription id and
+/// `*Unsubscribe` methods with `true` unless a response has been registered.
+async fn respond_pubsub(state: &Mutex<MockRpcState>, request: &Value) -> Value {
+	let method = request
+		.get("method")
+		.and_then(Value::as_str)
+		.unwrap_or_default();
+
+	let default_result = {
+		let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+
+		if state.responses.contains_key(method) {
+			None
+		} else if method.ends_with("Unsubscribe") {
+			Some(json!(true))
+		} else if method.ends_with("Subscribe") {
+			let id = state.next_subscription_id;
+			state.next_subscription_id += 1;
+			Some(json!(id))
+		} else {
+			None
+		}
+	};
+
+	let Some(result) = default_result else {
+		return respond(state, request).await;
+	};
+
+	state
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.requests
+		.push(MockRpcRequest {
+			method: method.to_string(),
+			params: request.get("params").cloned().unwrap_or(Value::Null),
+		});
+
+	json!({
+		"jsonrpc": "2.0",
+		"id": request.get("id").cloned().unwrap_or(Value::Null),
+		"result": result,
+	})
+}
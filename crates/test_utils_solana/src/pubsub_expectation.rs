@@ -0,0 +1,180 @@
+use std::fmt::Debug;
+use std::time::Duration;
+
+use anyhow::Result;
+use anyhow::bail;
+use futures::StreamExt;
+use solana_sdk::pubkey::Pubkey;
+use wasm_client_solana::GetAccountInfoRequest;
+use wasm_client_solana::GetAccountInfoResponse;
+use wasm_client_solana::SolanaRpcClient;
+use wasm_client_solana::Subscription;
+use wasm_client_solana::WebSocketNotification;
+use wasm_client_solana::rpc_config::LogsSubscribeRequest;
+use wasm_client_solana::rpc_config::RpcAccountInfoConfig;
+use wasm_client_solana::rpc_config::RpcTransactionLogsConfig;
+use wasm_client_solana::rpc_config::RpcTransactionLogsFilter;
+use wasm_client_solana::rpc_response::LogsNotificationResponse;
+use wasm_client_solana::runtime;
+
+/// A pubsub subscription which is expected to receive a matching
+/// notification before the `timeout`.
+///
+/// The subscription is created with the expectation, so the change it waits
+/// for can be triggered before calling [`PubsubExpectation::wait`]. The
+/// timers come from [`wasm_client_solana::runtime`] so expectations also
+/// work when compiled to wasm.
+pub struct PubsubExpectation<T: WebSocketNotification> {
+	subscription: Subscription<T>,
+	description: String,
+	timeout: Duration,
+	matches: Box<dyn Fn(&T) -> bool + Send>,
+}
+
+impl<T: WebSocketNotification + Debug> PubsubExpectation<T> {
+	/// Expect a notification of the `subscription` which `matches`. The
+	/// `description` of the expected notification is used in errors.
+	pub fn new(
+		subscription: Subscription<T>,
+		description: impl Into<String>,
+		timeout: Duration,
+		matches: impl Fn(&T) -> bool + Send + 'static,
+	) -> Self {
+		Self {
+			subscription,
+			description: description.into(),
+			timeout,
+			matches: Box::new(matches),
+		}
+	}
+
+	/// Wait for the first matching notification and unsubscribe.
+	///
+	/// # Errors
+	///
+	/// Fails with every notification which was received when no matching
+	/// notification arrives before the timeout or the subscription closes.
+	pub async fn wait(self) -> Result<T> {
+		let Self {
+			mut subscription,
+			description,
+			timeout,
+			matches,
+		} = self;
+		let unsubscription = subscription.get_unsubscription();
+		let mut seen = vec![];
+		let result = runtime::timeout(timeout, async {
+			while let Some(notification) = subscription.next().await {
+				let notification = notification.params.result;
+
+				if matches(&notification) {
+					return Some(notification);
+				}
+
+				seen.push(notification);
+			}
+
+			None
+		})
+		.await;
+
+		let _ = runtime::timeout(timeout, unsubscription.run()).await;
+
+		match result {
+			Ok(Some(notification)) => Ok(notification),
+			Ok(None) => {
+				bail!(
+					"the subscription closed before {description} was received. The {} other \
+					 notifications were: {seen:#?}",
+					seen.len()
+				)
+			}
+			Err(_) => {
+				bail!(
+					"{description} wasn't received within {timeout:?}. The {} other notifications \
+					 were: {seen:#?}",
+					seen.len()
+				)
+			}
+		}
+	}
+}
+
+/// Subscribe to the account of the `pubkey` and expect it to change within
+/// the `timeout`.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use solana_sdk::pubkey::Pubkey;
+/// use test_utils_solana::expect_account_change;
+/// use wasm_client_solana::SolanaRpcClient;
+///
+/// async fn run(rpc: &SolanaRpcClient, pubkey: &Pubkey) -> anyhow::Result<()> {
+/// 	let expectation = expect_account_change(rpc, pubkey, Duration::from_secs(10)).await?;
+///
+/// 	// Change the account...
+///
+/// 	let notification = expectation.wait().await?;
+///
+/// 	Ok(())
+/// }
+/// ```
+pub async fn expect_account_change(
+	rpc: &SolanaRpcClient,
+	pubkey: &Pubkey,
+	timeout: Duration,
+) -> Result<PubsubExpectation<GetAccountInfoResponse>> {
+	let subscription = rpc
+		.account_subscribe(
+			GetAccountInfoRequest::builder()
+				.pubkey(*pubkey)
+				.config(
+					RpcAccountInfoConfig::builder()
+						.commitment(rpc.commitment_config())
+						.build(),
+				)
+				.build(),
+		)
+		.await?;
+
+	Ok(PubsubExpectation::new(
+		subscription,
+		format!("a change to the account {pubkey}"),
+		timeout,
+		|_| true,
+	))
+}
+
+/// Subscribe to the logs of every transaction and expect a log line
+/// containing the `pattern` within the `timeout`.
+pub async fn expect_logs_containing(
+	rpc: &SolanaRpcClient,
+	pattern: impl Into<String>,
+	timeout: Duration,
+) -> Result<PubsubExpectation<LogsNotificationResponse>> {
+	let pattern = pattern.into();
+	let subscription = rpc
+		.logs_subscribe(
+			LogsSubscribeRequest::builder()
+				.filter(RpcTransactionLogsFilter::All)
+				.config(RpcTransactionLogsConfig {
+					commitment: Some(rpc.commitment_config()),
+				})
+				.build(),
+		)
+		.await?;
+
+	Ok(PubsubExpectation::new(
+		subscription,
+		format!("a transaction log containing `{pattern}`"),
+		timeout,
+		move |notification: &LogsNotificationResponse| {
+			notification
+				.value
+				.logs
+				.iter()
+				.any(|log| log.contains(&pattern))
+		},
+	))
+}
@@ -0,0 +1,512 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use serde::Deserialize;
+use solana_program::epoch_schedule::EpochSchedule;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::account::ReadableAccount;
+use solana_sdk::account::WritableAccount;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::bpf_loader_upgradeable::UpgradeableLoaderState;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::fee_calculator::FeeRateGovernor;
+use solana_sdk::native_token::sol_to_lamports;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use typed_builder::TypedBuilder;
+use wasm_client_solana::SolanaRpcClient;
+use wasm_client_solana::rpc_config::RpcProgramAccountsConfig;
+use wasm_client_solana::rpc_filter::RpcFilterType;
+use wasm_client_solana::solana_account_decoder::UiAccount;
+
+use crate::BanksRunner;
+use crate::DEFAULT_PORT_RANGE;
+use crate::FaucetConfig;
+use crate::TestValidatorPorts;
+#[cfg(feature = "test_validator")]
+use crate::TestValidatorRunner;
+
+/// The props used to start a [`TestValidatorRunner`].
+///
+/// The programs of an anchor workspace can be added with
+/// [`TestProgramInfo::from_anchor_workspace`].
+///
+/// ```rust
+/// use test_utils_solana::TestProgramInfo;
+/// use test_utils_solana::TestValidatorRunnerProps;
+///
+/// async fn run() -> anyhow::Result<()> {
+/// 	let runner = TestValidatorRunnerProps::builder()
+/// 		.programs(TestProgramInfo::from_anchor_workspace("../..")?)
+/// 		.build()
+/// 		.run()
+/// 		.await;
+///
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(mutators(
+	/// Clone every account owned by `program_id` which matches the `filters`
+	/// from [`TestValidatorRunnerProps::clone_from`]. This can be added
+	/// multiple times in the builder.
+	pub fn clone_accounts_with_filters(
+		&mut self,
+		program_id: Pubkey,
+		filters: Vec<RpcFilterType>
+	) {
+		self.clone_program_accounts.push((program_id, filters));
+	}
+	/// Set the [`TestValidatorRunnerProps::fee_rate_governor`].
+	pub fn fee_rate_governor(&mut self, fee_rate_governor: FeeRateGovernor) {
+		self.fee_rate_governor = Some(fee_rate_governor);
+	}
+	/// Charge no fees for transactions so accounts only need to hold the
+	/// lamports they transfer.
+	pub fn zero_fees(&mut self) {
+		self.fee_rate_governor = Some(FeeRateGovernor::new(0, 0));
+	}
+))]
+pub struct TestValidatorRunnerProps {
+	/// The ports to use for this runner. Defaults to a block of free ports
+	/// from the [`TestValidatorRunnerProps::port_range`].
+	#[builder(default, setter(strip_option))]
+	pub ports: Option<TestValidatorPorts>,
+	/// The range of ports which are chosen from when no
+	/// [`TestValidatorRunnerProps::ports`] are set. This can be narrowed for
+	/// environments which restrict the ports available to tests.
+	#[builder(default = DEFAULT_PORT_RANGE)]
+	pub port_range: RangeInclusive<u16>,
+	/// The programs to add to the validator.
+	#[builder(default)]
+	pub programs: Vec<TestProgramInfo>,
+	/// The funded pubkeys to fund with an amount of sol each. The amount can be
+	/// overriden via [`TestValidatorRunnerProps::initial_lamports`]. For more
+	/// custom control on funded accounts you can use the `accounts` field.
+	#[builder(default)]
+	pub pubkeys: Vec<Pubkey>,
+	/// The initial lamports to add to the defined
+	/// [`TestValidatorRunnerProps::pubkeys`].
+	///
+	/// The default amount is `5.0 SOL`.
+	#[builder(default = sol_to_lamports(5.0))]
+	pub initial_lamports: u64,
+	/// The default commitment level to use for the validator client rpc.
+	#[builder(default, setter(into))]
+	pub commitment: CommitmentLevel,
+	/// Custom accounts to add during genesis. These accounts can include custom
+	/// data and state.
+	#[builder(default)]
+	pub accounts: HashMap<Pubkey, AccountSharedData>,
+	/// Warp the ledger to `warp_slot` after starting the validator.
+	#[builder(default = 1000, setter(into))]
+	pub warp_slot: Slot,
+	/// Override the epoch schedule.
+	#[builder(default)]
+	pub epoch_schedule: EpochSchedule,
+	/// Runtime features which are inactive at genesis. Use
+	/// [`FeatureSet::inactive`](crate::FeatureSet::inactive) to match the
+	/// features of a cluster.
+	#[builder(default)]
+	pub deactivate_features: Vec<Pubkey>,
+	/// The fee rate governor of the genesis config. Set it with
+	/// `fee_rate_governor` or `zero_fees` on the builder.
+	///
+	/// This only switches fees off. The runtime charges no fee for signatures
+	/// when the `lamports_per_signature` of the governor is `0`, and the fixed
+	/// fee of its fee structure, `5000` lamports per signature, for any other
+	/// value.
+	#[builder(via_mutators(init = None))]
+	pub fee_rate_governor: Option<FeeRateGovernor>,
+	/// Override the rent charged for accounts.
+	#[builder(default, setter(strip_option))]
+	pub rent: Option<Rent>,
+	/// The rpc url of the cluster which the `clone_*` accounts are fetched
+	/// from before genesis, e.g. [`MAINNET`](wasm_client_solana::MAINNET).
+	#[builder(default, setter(into, strip_option))]
+	pub clone_from: Option<String>,
+	/// Accounts to clone from [`TestValidatorRunnerProps::clone_from`].
+	#[builder(default)]
+	pub clone_accounts: Vec<Pubkey>,
+	/// Programs to clone from [`TestValidatorRunnerProps::clone_from`].
+	/// Upgradeable programs are cloned with their programdata account so
+	/// they can be invoked.
+	#[builder(default)]
+	pub clone_programs: Vec<Pubkey>,
+	/// The programs whose accounts are cloned with their filters. Add them
+	/// with `clone_accounts_with_filters` on the builder.
+	#[builder(via_mutators(init = vec![]))]
+	pub clone_program_accounts: Vec<(Pubkey, Vec<RpcFilterType>)>,
+	/// Account fixtures to add during genesis, in the format written by
+	/// `solana account <ADDRESS> --output json-compact -o <FILE>`.
+	#[builder(default)]
+	pub account_fixtures: Vec<PathBuf>,
+	/// Directories with account fixtures to add during genesis. Every `.json`
+	/// file in each directory is loaded as one of the
+	/// [`TestValidatorRunnerProps::account_fixtures`].
+	#[builder(default)]
+	pub account_fixture_dirs: Vec<PathBuf>,
+	/// The maximum number of transaction logs kept by
+	/// [`TestValidatorRunner::logs`]. The oldest logs are dropped once it is
+	/// reached and `0` disables the capture.
+	#[builder(default = DEFAULT_LOG_CAPACITY)]
+	pub log_capacity: usize,
+	/// The faucet which serves airdrops. Disable it to test how code behaves
+	/// when airdrops are unavailable.
+	#[builder(default)]
+	pub faucet: FaucetConfig,
+	/// The directory of the validator ledger, which must be empty or not exist
+	/// yet. Defaults to a temporary directory.
+	#[builder(default, setter(into, strip_option))]
+	pub ledger_path: Option<PathBuf>,
+	/// Keep the ledger once the runner is dropped so it can be inspected with
+	/// `solana-ledger-tool`. It is always kept, and its path printed, when the
+	/// runner is dropped while a test panics.
+	#[builder(default)]
+	pub keep_ledger_on_drop: bool,
+}
+
+impl Default for TestValidatorRunnerProps {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl TestValidatorRunnerProps {
+	/// Defers to the [`TestValidatorRunner::run`] method with the props
+	/// defined in this struct.
+	///
+	/// ```rust
+	/// use solana_sdk::native_token::sol_to_lamports;
+	/// use solana_sdk::pubkey;
+	/// use test_utils_solana::TestValidatorRunnerProps;
+	///
+	/// async fn run() {
+	/// 	let user = pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+	/// 	let runner = TestValidatorRunnerProps::builder()
+	/// 		.pubkeys(vec![user])
+	/// 		.initial_lamports(sol_to_lamports(2.0))
+	/// 		.build()
+	/// 		.run()
+	/// 		.await;
+	/// }
+	/// ```
+	#[cfg(feature = "test_validator")]
+	pub async fn run(self) -> TestValidatorRunner {
+		TestValidatorRunner::run(self).await
+	}
+
+	/// Defers to the [`TestValidatorRunner::try_run`] method with the props
+	/// defined in this struct.
+	#[cfg(feature = "test_validator")]
+	pub async fn try_run(self) -> Result<TestValidatorRunner> {
+		TestValidatorRunner::try_run(self).await
+	}
+
+	/// Start a [`BanksRunner`] with the props defined in this struct instead
+	/// of a validator.
+	pub async fn run_banks(self) -> BanksRunner {
+		BanksRunner::run(self).await
+	}
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct TestProgramInfo {
+	pub program_id: Pubkey,
+	#[builder(setter(into))]
+	pub program_path: PathBuf,
+	#[builder(default = Pubkey::default())]
+	pub upgrade_authority: Pubkey,
+	#[builder(default = bpf_loader_upgradeable::ID)]
+	pub loader: Pubkey,
+}
+
+impl TestProgramInfo {
+	/// Discover the programs of the anchor workspace at `workspace_root` from
+	/// the `[programs.localnet]` section of its `Anchor.toml`. Each program is
+	/// loaded from `target/deploy/<program_name>.so` so `anchor build` must be
+	/// run first.
+	///
+	/// ```rust
+	/// use test_utils_solana::TestProgramInfo;
+	///
+	/// fn programs() -> anyhow::Result<Vec<TestProgramInfo>> {
+	/// 	TestProgramInfo::from_anchor_workspace(env!("CARGO_MANIFEST_DIR"))
+	/// }
+	/// ```
+	pub fn from_anchor_workspace(workspace_root: impl AsRef<Path>) -> Result<Vec<Self>> {
+		Self::from_anchor_workspace_with_overrides(workspace_root, &HashMap::new())
+	}
+
+	/// The same as [`TestProgramInfo::from_anchor_workspace`] with the program
+	/// ids in `overrides`, keyed by program name, used in place of the ids in
+	/// the `Anchor.toml`. This is useful when tests deploy a program with a
+	/// different keypair.
+	pub fn from_anchor_workspace_with_overrides(
+		workspace_root: impl AsRef<Path>,
+		overrides: &HashMap<String, Pubkey>,
+	) -> Result<Vec<Self>> {
+		let workspace_root = workspace_root.as_ref();
+		let anchor_toml_path = workspace_root.join("Anchor.toml");
+		let contents = fs::read_to_string(&anchor_toml_path)
+			.with_context(|| format!("failed to read {}", anchor_toml_path.display()))?;
+		let anchor_toml = toml::from_str::<AnchorToml>(&contents)
+			.with_context(|| format!("failed to parse {}", anchor_toml_path.display()))?;
+		let localnet = anchor_toml.programs.get("localnet").with_context(|| {
+			format!(
+				"no `[programs.localnet]` section in {}",
+				anchor_toml_path.display()
+			)
+		})?;
+
+		if let Some(name) = overrides.keys().find(|name| !localnet.contains_key(*name)) {
+			bail!(
+				"the overridden program `{name}` is not in the `[programs.localnet]` section of {}",
+				anchor_toml_path.display()
+			);
+		}
+
+		let deploy_dir = workspace_root.join("target").join("deploy");
+		let mut programs = vec![];
+		let mut missing = vec![];
+
+		for (name, entry) in localnet {
+			let program_id = match overrides.get(name) {
+				Some(program_id) => *program_id,
+				None => {
+					entry.address().parse::<Pubkey>().with_context(|| {
+						format!(
+							"invalid program id for `{name}` in {}",
+							anchor_toml_path.display()
+						)
+					})?
+				}
+			};
+			let program_path = deploy_dir.join(format!("{}.so", name.replace('-', "_")));
+
+			if !program_path.is_file() {
+				missing.push(program_path.display().to_string());
+				continue;
+			}
+
+			programs.push(
+				Self::builder()
+					.program_id(program_id)
+					.program_path(program_path)
+					.build(),
+			);
+		}
+
+		if !missing.is_empty() {
+			bail!(
+				"missing program artifacts, run `anchor build` first:\n{}",
+				missing.join("\n")
+			);
+		}
+
+		Ok(programs)
+	}
+}
+
+/// The parts of an `Anchor.toml` needed to discover the workspace programs.
+#[derive(Deserialize)]
+struct AnchorToml {
+	#[serde(default)]
+	programs: HashMap<String, BTreeMap<String, AnchorProgramEntry>>,
+}
+
+/// A program in the `[programs.<cluster>]` section of an `Anchor.toml`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnchorProgramEntry {
+	Address(String),
+	Detailed { address: String },
+}
+
+impl AnchorProgramEntry {
+	fn address(&self) -> &str {
+		match self {
+			Self::Address(address) | Self::Detailed { address } => address,
+		}
+	}
+}
+
+/// The default [`TestValidatorRunnerProps::log_capacity`].
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
+/// Fetch the accounts to clone into the validator.
+///
+/// The programdata account of every upgradeable program is fetched as well
+/// and its deployment slot is reset to `0`. Otherwise the program would only
+/// become visible once the local ledger reaches the slot it was deployed at
+/// on the remote cluster.
+pub(crate) async fn fetch_cloned_accounts(
+	rpc: &SolanaRpcClient,
+	accounts: &[Pubkey],
+	programs: &[Pubkey],
+	program_accounts: &[(Pubkey, Vec<RpcFilterType>)],
+) -> Result<Vec<(Pubkey, AccountSharedData)>> {
+	let mut cloned = fetch_accounts(rpc, accounts).await?;
+	let programs = fetch_accounts(rpc, programs).await?;
+	let mut programdata_addresses = vec![];
+
+	for (program_id, program) in &programs {
+		if program.owner() != &bpf_loader_upgradeable::ID {
+			continue;
+		}
+
+		let Ok(UpgradeableLoaderState::Program {
+			programdata_address,
+		}) = bincode::deserialize(program.data())
+		else {
+			bail!("failed to read the upgradeable program account {program_id}");
+		};
+
+		programdata_addresses.push(programdata_address);
+	}
+
+	cloned.extend(programs);
+
+	for (address, mut programdata) in fetch_accounts(rpc, &programdata_addresses).await? {
+		reset_deployment_slot(&address, &mut programdata)?;
+		cloned.push((address, programdata));
+	}
+
+	for (program_id, filters) in program_accounts {
+		let config = RpcProgramAccountsConfig {
+			filters: Some(filters.clone()),
+			..Default::default()
+		};
+		let accounts = rpc
+			.get_program_accounts_with_config(program_id, config)
+			.await
+			.with_context(|| format!("failed to fetch the accounts of program {program_id}"))?;
+
+		cloned.extend(
+			accounts
+				.into_iter()
+				.map(|(pubkey, account)| (pubkey, AccountSharedData::from(account))),
+		);
+	}
+
+	Ok(cloned)
+}
+
+/// Fetch every account in `pubkeys`. Fails with the first pubkey which
+/// doesn't exist on the cluster.
+async fn fetch_accounts(
+	rpc: &SolanaRpcClient,
+	pubkeys: &[Pubkey],
+) -> Result<Vec<(Pubkey, AccountSharedData)>> {
+	let accounts = rpc
+		.get_multiple_accounts(pubkeys)
+		.await
+		.with_context(|| format!("failed to fetch the accounts to clone from {}", rpc.url()))?;
+
+	pubkeys
+		.iter()
+		.zip(accounts)
+		.map(|(pubkey, account)| {
+			let account = account.ok_or_else(|| {
+				anyhow!(
+					"the account {pubkey} to clone doesn't exist on {}",
+					rpc.url()
+				)
+			})?;
+
+			Ok((*pubkey, AccountSharedData::from(account)))
+		})
+		.collect()
+}
+
+/// Rewrite the metadata of an upgradeable `programdata` account to be
+/// deployed at slot `0`.
+fn reset_deployment_slot(address: &Pubkey, programdata: &mut AccountSharedData) -> Result<()> {
+	let metadata_size = UpgradeableLoaderState::size_of_programdata_metadata();
+	let Some(Ok(UpgradeableLoaderState::ProgramData {
+		upgrade_authority_address,
+		..
+	})) = programdata
+		.data()
+		.get(..metadata_size)
+		.map(bincode::deserialize::<UpgradeableLoaderState>)
+	else {
+		bail!("failed to read the upgradeable programdata account {address}");
+	};
+
+	bincode::serialize_into(
+		programdata.data_as_mut_slice(),
+		&UpgradeableLoaderState::ProgramData {
+			slot: 0,
+			upgrade_authority_address,
+		},
+	)
+	.with_context(|| format!("failed to write the upgradeable programdata account {address}"))
+}
+
+/// An account fixture written by `solana account <ADDRESS> --output
+/// json-compact`.
+#[derive(Deserialize)]
+struct AccountFixture {
+	pubkey: String,
+	account: UiAccount,
+}
+
+/// Load an account fixture written by
+/// `solana account <ADDRESS> --output json-compact -o <FILE>`.
+///
+/// The error names the `path` of the fixture which couldn't be read.
+pub fn load_account_fixture(path: impl AsRef<Path>) -> Result<(Pubkey, AccountSharedData)> {
+	let path = path.as_ref();
+	let contents = fs::read_to_string(path)
+		.with_context(|| format!("failed to read the account fixture {}", path.display()))?;
+	let AccountFixture { pubkey, account } = serde_json::from_str(&contents)
+		.with_context(|| format!("failed to parse the account fixture {}", path.display()))?;
+	let pubkey = pubkey.parse::<Pubkey>().with_context(|| {
+		format!(
+			"invalid pubkey `{pubkey}` in the account fixture {}",
+			path.display()
+		)
+	})?;
+	let account = account.decode::<AccountSharedData>().ok_or_else(|| {
+		anyhow!(
+			"failed to decode the account data in the account fixture {}",
+			path.display()
+		)
+	})?;
+
+	Ok((pubkey, account))
+}
+
+/// Load the account fixtures from `files` and every `.json` file in `dirs`.
+/// The files in each directory are loaded in alphabetical order.
+pub(crate) fn load_account_fixtures(
+	files: &[PathBuf],
+	dirs: &[PathBuf],
+) -> Result<Vec<(Pubkey, AccountSharedData)>> {
+	let mut paths = files.to_vec();
+
+	for dir in dirs {
+		let entries = fs::read_dir(dir)
+			.with_context(|| format!("failed to read the fixture directory {}", dir.display()))?;
+		let mut dir_paths = entries
+			.map(|entry| entry.map(|entry| entry.path()))
+			.collect::<std::io::Result<Vec<_>>>()
+			.with_context(|| format!("failed to read the fixture directory {}", dir.display()))?;
+
+		dir_paths.retain(|path| path.is_file() && path.extension() == Some("json".as_ref()));
+		dir_paths.sort();
+		paths.extend(dir_paths);
+	}
+
+	paths.iter().map(load_account_fixture).collect()
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::clock::UnixTimestamp;
+use solana_sdk::pubkey::Pubkey;
+use wasm_client_solana::solana_account_decoder::UiAccount;
+use wasm_client_solana::solana_account_decoder::UiAccountEncoding;
+
+/// The accounts of a validator captured by
+/// [`TestValidatorRunner::snapshot`](crate::TestValidatorRunner::snapshot) or
+/// [`TestValidatorRunner::snapshot_all`](crate::TestValidatorRunner::snapshot_all).
+///
+/// A snapshot can be saved to disk with [`StateSnapshot::save`] and shared
+/// with other test binaries which [`StateSnapshot::load`] it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateSnapshot {
+	accounts: BTreeMap<Pubkey, AccountSharedData>,
+	complete: bool,
+	unix_timestamp: UnixTimestamp,
+}
+
+impl StateSnapshot {
+	pub(crate) fn new(
+		accounts: BTreeMap<Pubkey, AccountSharedData>,
+		complete: bool,
+		unix_timestamp: UnixTimestamp,
+	) -> Self {
+		Self {
+			accounts,
+			complete,
+			unix_timestamp,
+		}
+	}
+
+	/// The captured accounts. An account which didn't exist when it was
+	/// captured is stored with zero lamports.
+	pub fn accounts(&self) -> &BTreeMap<Pubkey, AccountSharedData> {
+		&self.accounts
+	}
+
+	/// Whether every account apart from builtins, sysvars and vote accounts
+	/// was captured. Restoring a complete snapshot also removes the accounts
+	/// created after it was taken.
+	pub fn is_complete(&self) -> bool {
+		self.complete
+	}
+
+	/// The `unix_timestamp` of the [`Clock`](solana_sdk::clock::Clock) sysvar
+	/// when the snapshot was taken.
+	pub fn unix_timestamp(&self) -> UnixTimestamp {
+		self.unix_timestamp
+	}
+
+	/// Write the snapshot as json to the `path`.
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+		let path = path.as_ref();
+		let file = SnapshotFile {
+			complete: self.complete,
+			unix_timestamp: self.unix_timestamp,
+			accounts: self
+				.accounts
+				.iter()
+				.map(|(pubkey, account)| {
+					SnapshotAccount {
+						pubkey: pubkey.to_string(),
+						account: UiAccount::encode(
+							pubkey,
+							account,
+							UiAccountEncoding::Base64,
+							None,
+							None,
+						),
+					}
+				})
+				.collect(),
+		};
+		let contents = serde_json::to_string(&file)?;
+
+		fs::write(path, contents)
+			.with_context(|| format!("failed to write the snapshot {}", path.display()))
+	}
+
+	/// Read a snapshot written by [`StateSnapshot::save`] from the `path`.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+		let path = path.as_ref();
+		let contents = fs::read_to_string(path)
+			.with_context(|| format!("failed to read the snapshot {}", path.display()))?;
+		let file = serde_json::from_str::<SnapshotFile>(&contents)
+			.with_context(|| format!("failed to parse the snapshot {}", path.display()))?;
+		let accounts = file
+			.accounts
+			.into_iter()
+			.map(|SnapshotAccount { pubkey, account }| {
+				let pubkey = pubkey.parse::<Pubkey>().with_context(|| {
+					format!(
+						"invalid pubkey `{pubkey}` in the snapshot {}",
+						path.display()
+					)
+				})?;
+				let account = account.decode::<AccountSharedData>().ok_or_else(|| {
+					anyhow!(
+						"failed to decode the account {pubkey} in the snapshot {}",
+						path.display()
+					)
+				})?;
+
+				Ok((pubkey, account))
+			})
+			.collect::<Result<_>>()?;
+
+		Ok(Self::new(accounts, file.complete, file.unix_timestamp))
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile {
+	complete: bool,
+	unix_timestamp: UnixTimestamp,
+	accounts: Vec<SnapshotAccount>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotAccount {
+	pubkey: String,
+	account: UiAccount,
+}
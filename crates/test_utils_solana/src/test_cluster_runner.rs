@@ -0,0 +1,553 @@
+use std::collections::HashMap;
+use std::fs;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use anyhow::bail;
+use solana_accounts_db::utils::create_accounts_run_and_snapshot_dirs;
+use solana_core::validator::Validator;
+use solana_core::validator::ValidatorConfig;
+use solana_core::validator::ValidatorTpuConfig;
+use solana_gossip::cluster_info::Node;
+use solana_gossip::contact_info::ContactInfo;
+use solana_rpc::rpc::JsonRpcConfig;
+use solana_runtime::snapshot_config::SnapshotConfig;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::native_token::sol_to_lamports;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_streamer::socket::SocketAddrSpace;
+use solana_test_validator::TestValidator;
+use solana_test_validator::TestValidatorGenesis;
+use tokio::time::sleep;
+use typed_builder::TypedBuilder;
+use wasm_client_solana::SolanaRpcClient;
+
+use crate::DEFAULT_PORT_RANGE;
+use crate::TestValidatorPorts;
+use crate::test_validator_runner::MAX_POLL_ATTEMPTS;
+use crate::test_validator_runner::POLL_INTERVAL;
+use crate::test_validator_runner::configure_node;
+use crate::test_validator_runner::wait_for_blockhash;
+use crate::validator_ledger::LedgerDir;
+use crate::validator_ports::PortClaim;
+use crate::validator_ports::release_ports;
+
+/// The number of validators a [`TestClusterRunner`] can run.
+pub const CLUSTER_NODE_COUNTS: RangeInclusive<usize> = 2..=3;
+
+/// The genesis files which are copied from the ledger of the bootstrap
+/// validator so every node of the cluster shares its genesis.
+const GENESIS_FILES: [&str; 2] = ["genesis.bin", "genesis.tar.bz2"];
+
+/// The props used to start a [`TestClusterRunner`].
+///
+/// ```rust
+/// use test_utils_solana::TestClusterRunnerProps;
+///
+/// async fn run() -> anyhow::Result<()> {
+/// 	let cluster = TestClusterRunnerProps::builder()
+/// 		.node_count(3)
+/// 		.build()
+/// 		.run()
+/// 		.await?;
+///
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct TestClusterRunnerProps {
+	/// The number of validators in the cluster, including the bootstrap
+	/// validator. It must be within [`CLUSTER_NODE_COUNTS`].
+	#[builder(default = 2)]
+	pub node_count: usize,
+	/// The range of ports which the block of ports of every node is chosen
+	/// from.
+	#[builder(default = DEFAULT_PORT_RANGE)]
+	pub port_range: RangeInclusive<u16>,
+	/// The pubkeys to fund with the
+	/// [`TestClusterRunnerProps::initial_lamports`] at genesis.
+	#[builder(default)]
+	pub pubkeys: Vec<Pubkey>,
+	/// The initial lamports to add to the defined
+	/// [`TestClusterRunnerProps::pubkeys`].
+	///
+	/// The default amount is `5.0 SOL`.
+	#[builder(default = sol_to_lamports(5.0))]
+	pub initial_lamports: u64,
+	/// The default commitment level of the rpc clients of every node.
+	#[builder(default, setter(into))]
+	pub commitment: CommitmentLevel,
+	/// Custom accounts to add during genesis.
+	#[builder(default)]
+	pub accounts: HashMap<Pubkey, AccountSharedData>,
+}
+
+impl Default for TestClusterRunnerProps {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl TestClusterRunnerProps {
+	/// Defers to the [`TestClusterRunner::run`] method with the props
+	/// defined in this struct.
+	pub async fn run(self) -> Result<TestClusterRunner> {
+		TestClusterRunner::run(self).await
+	}
+}
+
+/// A small local cluster of validators which share a genesis and find each
+/// other through gossip.
+///
+/// The first node is the bootstrap validator which holds all of the stake,
+/// so it is the leader of every slot. The other nodes follow it without
+/// voting and replay the blocks it produces, which makes them useful for
+/// testing how clients handle nodes which fall behind or stop answering.
+/// Killing the bootstrap validator halts the cluster until it is restarted.
+///
+/// The cluster has no faucet so accounts should be funded at genesis with
+/// [`TestClusterRunnerProps::pubkeys`], or by the
+/// [`mint_keypair`](TestClusterRunner::mint_keypair).
+pub struct TestClusterRunner {
+	nodes: Vec<ClusterNode>,
+	/// The keypair of the account which is funded at genesis.
+	mint_keypair: Keypair,
+	commitment: CommitmentConfig,
+	/// The index of the node whose client is returned next by
+	/// [`TestClusterRunner::any_rpc`].
+	next_node: AtomicUsize,
+}
+
+impl TestClusterRunner {
+	/// Start the bootstrap validator followed by the other nodes and wait
+	/// until every node is visible in the gossip of the bootstrap validator.
+	pub async fn run(
+		TestClusterRunnerProps {
+			node_count,
+			port_range,
+			pubkeys,
+			initial_lamports,
+			commitment,
+			accounts,
+		}: TestClusterRunnerProps,
+	) -> Result<Self> {
+		if !CLUSTER_NODE_COUNTS.contains(&node_count) {
+			bail!(
+				"a test cluster runs between {} and {} nodes but {node_count} were requested",
+				CLUSTER_NODE_COUNTS.start(),
+				CLUSTER_NODE_COUNTS.end()
+			);
+		}
+
+		let commitment = CommitmentConfig { commitment };
+		let ledger = LedgerDir::new(None, false)?;
+		let ports = TestValidatorPorts::try_random_ports_in(port_range.clone())?;
+		let port_claim = PortClaim::new(ports);
+		let funded_accounts = pubkeys.iter().map(|pubkey| {
+			(
+				*pubkey,
+				AccountSharedData::new(initial_lamports, 0, &Pubkey::default()),
+			)
+		});
+		let mut genesis = TestValidatorGenesis::default();
+
+		configure_node(&mut genesis, ports, ledger.path(), false)
+			.add_accounts(funded_accounts)
+			.add_accounts(accounts);
+
+		release_ports(ports.iter());
+		let (validator, mint_keypair) = genesis.start_async().await;
+		let identity = validator.cluster_info().keypair().clone();
+		let bootstrap = ClusterNode::new(
+			identity,
+			ports,
+			ledger,
+			port_claim,
+			NodeService::Bootstrap(validator),
+			commitment,
+		);
+
+		wait_for_blockhash(&bootstrap.rpc).await?;
+
+		let entrypoint = gossip_address(bootstrap.ports);
+		let mut nodes = vec![bootstrap];
+
+		for _ in 1..node_count {
+			let ledger = LedgerDir::new(None, false)?;
+
+			for file in GENESIS_FILES {
+				fs::copy(nodes[0].ledger.path().join(file), ledger.path().join(file))?;
+			}
+
+			let ports = TestValidatorPorts::try_random_ports_in(port_range.clone())?;
+			let port_claim = PortClaim::new(ports);
+			let identity = Arc::new(Keypair::new());
+			release_ports(ports.iter());
+			let validator = start_follower(identity.clone(), ports, ledger.path(), entrypoint)?;
+			let node = ClusterNode::new(
+				identity,
+				ports,
+				ledger,
+				port_claim,
+				NodeService::Follower(validator),
+				commitment,
+			);
+
+			wait_for_blockhash(&node.rpc).await?;
+			nodes.push(node);
+		}
+
+		let runner = Self {
+			nodes,
+			mint_keypair,
+			commitment,
+			next_node: AtomicUsize::new(0),
+		};
+
+		runner.wait_for_gossip().await?;
+
+		Ok(runner)
+	}
+
+	/// The number of nodes in the cluster, including nodes which have been
+	/// killed.
+	pub fn node_count(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// The rpc client of the node at `index`. The bootstrap validator is the
+	/// node at index `0`. Requests fail while the node is killed.
+	///
+	/// # Panics
+	///
+	/// Panics when the `index` is out of bounds.
+	pub fn rpc(&self, index: usize) -> &SolanaRpcClient {
+		&self.node(index).rpc
+	}
+
+	/// The rpc client of the next running node, going round the nodes of the
+	/// cluster in turn so consecutive calls spread requests across them.
+	///
+	/// # Panics
+	///
+	/// Panics when every node has been killed.
+	pub fn any_rpc(&self) -> &SolanaRpcClient {
+		let len = self.nodes.len();
+		let start = self.next_node.fetch_add(1, Ordering::Relaxed);
+
+		(0..len)
+			.map(|offset| &self.nodes[(start + offset) % len])
+			.find(|node| node.is_running())
+			.map(|node| &node.rpc)
+			.expect("every node of the cluster has been killed")
+	}
+
+	pub fn rpc_url(&self, index: usize) -> String {
+		self.node(index).rpc.url()
+	}
+
+	/// The identity of the node at `index`, which it is listed under in
+	/// `getClusterNodes`.
+	pub fn identity(&self, index: usize) -> Pubkey {
+		self.node(index).identity.pubkey()
+	}
+
+	pub fn ports(&self, index: usize) -> TestValidatorPorts {
+		self.node(index).ports
+	}
+
+	/// The directory of the ledger of the node at `index`.
+	pub fn ledger_path(&self, index: usize) -> PathBuf {
+		self.node(index).ledger.path().to_owned()
+	}
+
+	pub fn mint_keypair(&self) -> &Keypair {
+		&self.mint_keypair
+	}
+
+	/// Whether the node at `index` is running.
+	pub fn is_running(&self, index: usize) -> bool {
+		self.node(index).is_running()
+	}
+
+	/// Stop the node at `index` and keep its ledger and ports so it can be
+	/// restarted with [`TestClusterRunner::restart_node`]. This fails when the
+	/// node has already been killed.
+	pub fn kill_node(&mut self, index: usize) -> Result<()> {
+		let Some(service) = self.node_mut(index).service.take() else {
+			bail!("the node {index} of the cluster has already been killed");
+		};
+
+		service.stop();
+
+		Ok(())
+	}
+
+	/// Start the killed node at `index` again from its ledger. A follower
+	/// rejoins the cluster through the gossip of the bootstrap validator and
+	/// catches up from the slot it was killed at, so it lags behind the
+	/// cluster until it has replayed the missing slots.
+	///
+	/// The rpc client of the node is replaced since its websocket is closed
+	/// when the node is killed.
+	pub async fn restart_node(&mut self, index: usize) -> Result<()> {
+		let entrypoint = gossip_address(self.node(0).ports);
+		let commitment = self.commitment;
+		let node = self.node_mut(index);
+
+		if node.is_running() {
+			bail!("the node {index} of the cluster is still running");
+		}
+
+		release_ports(node.ports.iter());
+
+		let service = if index == 0 {
+			let mut genesis = TestValidatorGenesis::default();
+			configure_node(&mut genesis, node.ports, node.ledger.path(), false);
+			let (validator, _) = genesis.start_async().await;
+
+			NodeService::Bootstrap(validator)
+		} else {
+			let validator = start_follower(
+				node.identity.clone(),
+				node.ports,
+				node.ledger.path(),
+				entrypoint,
+			)?;
+
+			NodeService::Follower(validator)
+		};
+
+		node.service = Some(service);
+		node.rpc = node_rpc(node.ports, commitment);
+		wait_for_blockhash(&node.rpc).await?;
+
+		Ok(())
+	}
+
+	/// How many slots the node at `index` is behind the bootstrap validator,
+	/// by the latest slot each of them has processed.
+	pub async fn slots_behind(&self, index: usize) -> Result<Slot> {
+		let processed = CommitmentConfig::processed();
+		let leader_slot = self.rpc(0).get_slot_with_commitment(processed).await?;
+		let slot = self.rpc(index).get_slot_with_commitment(processed).await?;
+
+		Ok(leader_slot.saturating_sub(slot))
+	}
+
+	/// Wait until the node at `index` has processed the latest slot of the
+	/// bootstrap validator at the time of the call.
+	pub async fn wait_for_catch_up(&self, index: usize) -> Result<Slot> {
+		let target = self
+			.rpc(0)
+			.get_slot_with_commitment(CommitmentConfig::processed())
+			.await?;
+
+		for _ in 0..MAX_POLL_ATTEMPTS {
+			match self
+				.rpc(index)
+				.get_slot_with_commitment(CommitmentConfig::processed())
+				.await
+			{
+				Ok(slot) if slot >= target => return Ok(slot),
+				_ => {}
+			}
+
+			sleep(POLL_INTERVAL).await;
+		}
+
+		bail!(
+			"the node {index} of the cluster didn't reach slot {target} after {MAX_POLL_ATTEMPTS} \
+			 attempts"
+		)
+	}
+
+	/// Wait until the bootstrap validator lists every node of the cluster in
+	/// `getClusterNodes`.
+	async fn wait_for_gossip(&self) -> Result<()> {
+		let identities = (0..self.nodes.len())
+			.map(|index| self.identity(index))
+			.collect::<Vec<_>>();
+
+		for _ in 0..MAX_POLL_ATTEMPTS {
+			let visible = self.rpc(0).get_cluster_nodes().await?;
+
+			if identities
+				.iter()
+				.all(|identity| visible.iter().any(|node| node.pubkey == *identity))
+			{
+				return Ok(());
+			}
+
+			sleep(POLL_INTERVAL).await;
+		}
+
+		bail!("the nodes of the cluster didn't find each other after {MAX_POLL_ATTEMPTS} attempts")
+	}
+
+	fn node(&self, index: usize) -> &ClusterNode {
+		self.nodes.get(index).unwrap_or_else(|| {
+			panic!(
+				"the cluster has {} nodes but node {index} was requested",
+				self.nodes.len()
+			)
+		})
+	}
+
+	fn node_mut(&mut self, index: usize) -> &mut ClusterNode {
+		let len = self.nodes.len();
+
+		self.nodes
+			.get_mut(index)
+			.unwrap_or_else(|| panic!("the cluster has {len} nodes but node {index} was requested"))
+	}
+}
+
+/// A validator of the cluster along with the resources which outlive it
+/// when it is killed.
+struct ClusterNode {
+	identity: Arc<Keypair>,
+	ports: TestValidatorPorts,
+	rpc: SolanaRpcClient,
+	/// The running validator, which is `None` once the node is killed. It is
+	/// stopped before the ledger and the port claim are released.
+	service: Option<NodeService>,
+	ledger: LedgerDir,
+	_port_claim: PortClaim,
+}
+
+impl ClusterNode {
+	fn new(
+		identity: Arc<Keypair>,
+		ports: TestValidatorPorts,
+		ledger: LedgerDir,
+		port_claim: PortClaim,
+		service: NodeService,
+		commitment: CommitmentConfig,
+	) -> Self {
+		Self {
+			identity,
+			ports,
+			rpc: node_rpc(ports, commitment),
+			service: Some(service),
+			ledger,
+			_port_claim: port_claim,
+		}
+	}
+
+	fn is_running(&self) -> bool {
+		self.service.is_some()
+	}
+}
+
+impl Drop for ClusterNode {
+	fn drop(&mut self) {
+		if let Some(service) = self.service.take() {
+			service.stop();
+		}
+	}
+}
+
+enum NodeService {
+	/// The bootstrap validator, which created the genesis of the cluster.
+	Bootstrap(TestValidator),
+	/// A validator which joined the cluster through gossip.
+	Follower(Validator),
+}
+
+impl NodeService {
+	/// Stop the validator and wait until its services have exited.
+	fn stop(self) {
+		match self {
+			Self::Bootstrap(validator) => drop(validator),
+			Self::Follower(validator) => validator.close(),
+		}
+	}
+}
+
+/// Start a non-voting validator from the genesis in the `ledger_path` which
+/// joins the cluster through the gossip `entrypoint`. This mirrors how the
+/// test validator starts, with the rpc and gossip bound to the `ports`.
+fn start_follower(
+	identity: Arc<Keypair>,
+	ports: TestValidatorPorts,
+	ledger_path: &Path,
+	entrypoint: SocketAddr,
+) -> Result<Validator> {
+	let mut node = Node::new_single_bind(
+		&identity.pubkey(),
+		&gossip_address(ports),
+		ports.gossip_range,
+		IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+	);
+	node.info.set_rpc((Ipv4Addr::LOCALHOST, ports.rpc))?;
+	node.info
+		.set_rpc_pubsub((Ipv4Addr::LOCALHOST, ports.pubsub))?;
+
+	let (account_path, _) = create_accounts_run_and_snapshot_dirs(ledger_path.join("accounts"))?;
+	let config = ValidatorConfig {
+		rpc_addrs: Some((
+			SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), ports.rpc),
+			SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), ports.pubsub),
+		)),
+		rpc_config: JsonRpcConfig {
+			enable_rpc_transaction_history: true,
+			..JsonRpcConfig::default_for_test()
+		},
+		account_paths: vec![account_path],
+		run_verification: false,
+		snapshot_config: SnapshotConfig {
+			full_snapshot_archive_interval_slots: 100,
+			incremental_snapshot_archive_interval_slots: Slot::MAX,
+			bank_snapshots_dir: ledger_path.join("snapshot"),
+			full_snapshot_archives_dir: ledger_path.to_path_buf(),
+			incremental_snapshot_archives_dir: ledger_path.to_path_buf(),
+			..SnapshotConfig::default()
+		},
+		voting_disabled: true,
+		..ValidatorConfig::default_for_test()
+	};
+
+	Validator::new(
+		node,
+		identity,
+		ledger_path,
+		&Pubkey::new_unique(),
+		Arc::new(RwLock::new(vec![])),
+		vec![ContactInfo::new_gossip_entry_point(&entrypoint)],
+		&config,
+		true,
+		None,
+		Arc::default(),
+		SocketAddrSpace::new(true),
+		ValidatorTpuConfig::new_for_tests(false),
+		Arc::default(),
+	)
+}
+
+/// The gossip address of a node bound to the `ports`.
+fn gossip_address(ports: TestValidatorPorts) -> SocketAddr {
+	SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), ports.gossip_range.0)
+}
+
+fn node_rpc(ports: TestValidatorPorts, commitment: CommitmentConfig) -> SolanaRpcClient {
+	SolanaRpcClient::new_with_ws_and_commitment(
+		&format!("http://{}:{}", Ipv4Addr::LOCALHOST, ports.rpc),
+		&format!("ws://{}:{}/", Ipv4Addr::LOCALHOST, ports.pubsub),
+		commitment,
+	)
+}
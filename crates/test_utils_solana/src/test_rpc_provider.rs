@@ -65,7 +65,7 @@ impl TestRpcProvider {
 
 	/// Create a new instance of the [`SolanaRpcClient`].
 	pub fn to_rpc_client(&self) -> SolanaRpcClient {
-		SolanaRpcClient::new_with_provider(self.arc(), CommitmentConfig::finalized())
+		SolanaRpcClient::new_with_provider_and_commitment(self.clone(), CommitmentConfig::finalized())
 	}
 }
 
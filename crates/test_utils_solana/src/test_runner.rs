@@ -0,0 +1,72 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use solana_sdk::account::Account;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::clock::Clock;
+use solana_sdk::clock::Slot;
+use solana_sdk::clock::UnixTimestamp;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_system_interface::instruction::transfer;
+use wasm_client_solana::SolanaRpcClient;
+use wasm_client_solana::rpc_config::SendOptions;
+
+/// The api shared by the [`TestValidatorRunner`](crate::TestValidatorRunner)
+/// and the [`BanksRunner`](crate::BanksRunner).
+///
+/// Tests which only use this trait can switch between a full validator and
+/// the faster banks client without any other changes. The trait and the
+/// [`BanksRunner`](crate::BanksRunner) are available without the
+/// `test_validator` feature.
+///
+/// ```rust
+/// use solana_sdk::pubkey::Pubkey;
+/// use test_utils_solana::TestRunner;
+/// use test_utils_solana::TestValidatorRunnerProps;
+///
+/// async fn fund(runner: &impl TestRunner) -> anyhow::Result<()> {
+/// 	runner.airdrop(&Pubkey::new_unique(), 1_000_000_000).await?;
+///
+/// 	Ok(())
+/// }
+///
+/// async fn run() -> anyhow::Result<()> {
+/// 	fund(&TestValidatorRunnerProps::default().run_banks().await).await
+/// }
+/// ```
+#[async_trait(?Send)]
+pub trait TestRunner {
+	/// The rpc client connected to the runner.
+	fn rpc(&self) -> &SolanaRpcClient;
+	/// The keypair funded at genesis which pays for the runner's
+	/// transactions.
+	fn payer(&self) -> &Keypair;
+	/// Overwrite the `account` at the `pubkey`. An account below the
+	/// rent-exempt minimum balance is rejected.
+	async fn set_account(&self, pubkey: &Pubkey, account: AccountSharedData) -> Result<Account>;
+	/// The current [`Clock`] sysvar.
+	async fn get_clock(&self) -> Result<Clock>;
+	/// Advance the runner to the `slot`.
+	async fn warp_to_slot(&mut self, slot: Slot) -> Result<Clock>;
+	/// Move the `unix_timestamp` of the [`Clock`] sysvar forward.
+	async fn set_clock_timestamp(&self, unix_timestamp: UnixTimestamp) -> Result<Clock>;
+
+	/// Transfer `lamports` from the [`TestRunner::payer`] to the `pubkey`.
+	async fn airdrop(&self, pubkey: &Pubkey, lamports: u64) -> Result<Signature> {
+		let payer = self.payer();
+		let instruction = transfer(&payer.pubkey(), pubkey, lamports);
+		let signature = self
+			.rpc()
+			.send_and_confirm_transaction_with_options(
+				&[instruction],
+				&payer.pubkey(),
+				&[payer],
+				SendOptions::default(),
+			)
+			.await?;
+
+		Ok(signature)
+	}
+}
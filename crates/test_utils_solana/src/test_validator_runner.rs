@@ -1,112 +1,92 @@
-use std::collections::HashMap;
-use std::collections::HashSet;
+use std::collections::BTreeMap;
 use std::net::IpAddr;
 use std::net::Ipv4Addr;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::sync::Mutex;
+use std::sync::RwLock;
+use std::time::Duration;
 
+use agave_feature_set::FEATURE_NAMES;
+use anyhow::Context;
 use anyhow::Result;
-use crossbeam_channel::unbounded;
-use port_check::is_local_ipv4_port_free;
-use rand::Rng;
-use solana_faucet::faucet::run_local_faucet_with_port;
-use solana_program::epoch_schedule::EpochSchedule;
+use anyhow::anyhow;
+use anyhow::bail;
+use async_trait::async_trait;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use futures::stream;
+use solana_feature_gate_interface as feature;
+use solana_feature_gate_interface::Feature;
+use solana_loader_v3_interface::get_program_data_address;
+use solana_loader_v3_interface::instruction as loader_instruction;
 use solana_rpc::rpc::JsonRpcConfig;
+use solana_runtime::bank::Bank;
+use solana_runtime::bank_forks::BankForks;
+use solana_runtime::snapshot_bank_utils::bank_to_full_snapshot_archive;
+use solana_runtime::snapshot_config::SnapshotConfig;
+use solana_sdk::account::Account;
 use solana_sdk::account::AccountSharedData;
+use solana_sdk::account::ReadableAccount;
+use solana_sdk::bpf_loader_upgradeable::UpgradeableLoaderState;
+use solana_sdk::clock::Clock;
+use solana_sdk::clock::DEFAULT_MS_PER_SLOT;
+use solana_sdk::clock::Epoch;
 use solana_sdk::clock::Slot;
+use solana_sdk::clock::UnixTimestamp;
 use solana_sdk::commitment_config::CommitmentConfig;
-use solana_sdk::commitment_config::CommitmentLevel;
-use solana_sdk::native_token::sol_to_lamports;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program_pack::Pack;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
+use solana_sdk::signature::Signature;
 use solana_sdk::signer::Signer;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_system_interface::MAX_PERMITTED_DATA_LENGTH;
+use solana_system_interface::instruction::create_account;
 use solana_system_interface::program as system_program;
 use solana_test_validator::TestValidator;
 pub use solana_test_validator::TestValidatorGenesis;
 use solana_test_validator::UpgradeableProgramInfo;
-use tempfile::TempDir;
-use tempfile::tempdir;
-use typed_builder::TypedBuilder;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::instruction::initialize_mint2;
+use spl_token_2022::instruction::mint_to_checked;
+use spl_token_2022::state::Mint;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::time::Instant;
+use tokio::time::sleep;
+use wasm_client_solana::GetAccountInfoResponse;
+use wasm_client_solana::PACKET_DATA_SIZE;
 use wasm_client_solana::SolanaRpcClient;
+use wasm_client_solana::VersionedTransactionExtension;
+use wasm_client_solana::rpc_config::RpcTransactionConfig;
+use wasm_client_solana::rpc_config::SendOptions;
+use wasm_client_solana::rpc_response::LogsNotificationResponse;
 
-#[derive(Debug, Clone, TypedBuilder)]
-pub struct TestValidatorRunnerProps {
-	/// The ports to use for this runner. Defaults to all three ports being
-	/// random. Can be overriden.
-	#[builder(default = TestValidatorPorts::random_ports())]
-	pub ports: TestValidatorPorts,
-	/// The programs to add to the validator.
-	#[builder(default)]
-	pub programs: Vec<TestProgramInfo>,
-	/// The funded pubkeys to fund with an amount of sol each. The amount can be
-	/// overriden via [`TestValidatorRunnerProps::initial_lamports`]. For more
-	/// custom control on funded accounts you can use the `accounts` field.
-	#[builder(default)]
-	pub pubkeys: Vec<Pubkey>,
-	/// The initial lamports to add to the defined
-	/// [`TestValidatorRunnerProps::pubkeys`].
-	///
-	/// The default amount is `5.0 SOL`.
-	#[builder(default = sol_to_lamports(5.0))]
-	pub initial_lamports: u64,
-	/// The default commitment level to use for the validator client rpc.
-	#[builder(default, setter(into))]
-	pub commitment: CommitmentLevel,
-	/// Custom accounts to add during genesis. These accounts can include custom
-	/// data and state.
-	#[builder(default)]
-	pub accounts: HashMap<Pubkey, AccountSharedData>,
-	/// Warp the ledger to `warp_slot` after starting the validator.
-	#[builder(default = 1000, setter(into))]
-	pub warp_slot: Slot,
-	/// Override the epoch schedule.
-	#[builder(default)]
-	pub epoch_schedule: EpochSchedule,
-}
-
-impl Default for TestValidatorRunnerProps {
-	fn default() -> Self {
-		Self::builder().build()
-	}
-}
-
-impl TestValidatorRunnerProps {
-	/// Defers to the [`TestValidatorRunner::run`] method with the props
-	/// defined in this struct.
-	///
-	/// ```rust
-	/// use solana_sdk::native_token::sol_to_lamports;
-	/// use solana_sdk::pubkey;
-	/// use test_utils_solana::TestValidatorRunnerProps;
-	///
-	/// async fn run() {
-	/// 	let user = pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
-	/// 	let runner = TestValidatorRunnerProps::builder()
-	/// 		.pubkeys(vec![user])
-	/// 		.initial_lamports(sol_to_lamports(2.0))
-	/// 		.build()
-	/// 		.run()
-	/// 		.await;
-	/// }
-	/// ```
-	pub async fn run(self) -> TestValidatorRunner {
-		TestValidatorRunner::run(self).await
-	}
-}
-
-#[derive(Debug, Clone, TypedBuilder)]
-pub struct TestProgramInfo {
-	pub program_id: Pubkey,
-	#[builder(setter(into))]
-	pub program_path: PathBuf,
-	#[builder(default = Pubkey::default())]
-	pub upgrade_authority: Pubkey,
-	#[builder(default = solana_sdk::bpf_loader_upgradeable::ID)]
-	pub loader: Pubkey,
-}
+use crate::FaucetConfig;
+use crate::PubsubExpectation;
+use crate::StateSnapshot;
+use crate::TestProgramInfo;
+use crate::TestRunner;
+use crate::TestValidatorPorts;
+use crate::TestValidatorRunnerProps;
+use crate::expect_account_change;
+use crate::expect_logs_containing;
+use crate::runner_props::fetch_cloned_accounts;
+use crate::runner_props::load_account_fixtures;
+use crate::validator_faucet::LocalFaucet;
+use crate::validator_ledger::LedgerDir;
+use crate::validator_logs::LogBuffer;
+use crate::validator_logs::format_log_entries;
+use crate::validator_ports::PortClaim;
+use crate::validator_ports::release_ports;
 
 impl From<TestProgramInfo> for UpgradeableProgramInfo {
 	fn from(
@@ -126,41 +106,6 @@ impl From<TestProgramInfo> for UpgradeableProgramInfo {
 	}
 }
 
-#[derive(Debug, Copy, Clone, TypedBuilder)]
-pub struct TestValidatorPorts {
-	#[builder(default = 8899)]
-	pub rpc: u16,
-	#[builder(default = 8900)]
-	pub pubsub: u16,
-	#[builder(default = 9900)]
-	pub faucet: u16,
-	#[builder(default = (8001, 8021))]
-	pub gossip_range: (u16, u16),
-}
-
-impl Default for TestValidatorPorts {
-	fn default() -> Self {
-		Self::builder().build()
-	}
-}
-
-impl TestValidatorPorts {
-	pub fn try_random_ports() -> Option<Self> {
-		find_ports().map(|(rpc, pubsub, faucet, gossip_range)| {
-			Self {
-				rpc,
-				pubsub,
-				faucet,
-				gossip_range,
-			}
-		})
-	}
-
-	pub fn random_ports() -> Self {
-		Self::try_random_ports().unwrap()
-	}
-}
-
 /// A local test validator runner which can be used for the test validator.
 #[derive(Clone)]
 pub struct TestValidatorRunner {
@@ -169,20 +114,37 @@ pub struct TestValidatorRunner {
 	/// The first port is the `rpc_port`, the second is the `pubsub_port`, and
 	/// the third is the `faucet_port` to allow for airdrops.
 	ports: TestValidatorPorts,
-	/// The original wrapped test validator
-	validator: Arc<TestValidator>,
+	/// The original wrapped test validator. This is only `None` while the
+	/// validator is being restarted.
+	validator: Option<Arc<TestValidator>>,
 	/// This is the keypair for the mint account and is funded with 500 SOL.
 	mint_keypair: Arc<Keypair>,
 	/// The rpc client for the validator.
 	rpc: SolanaRpcClient,
 	/// This can be RAM intensive so use a tempdir when required.
-	ledger_path: Arc<TempDir>,
+	ledger: Arc<LedgerDir>,
+	/// Held while the clock or an account is changed, or a transaction is in
+	/// flight, so they don't interleave.
+	state_lock: Arc<AsyncMutex<()>>,
+	/// The transaction logs captured from the validator.
+	log_buffer: Arc<LogBuffer>,
+	/// The faucet which serves airdrops for the validator, unless it is
+	/// disabled.
+	faucet: Option<Arc<LocalFaucet>>,
+	/// Keeps the ports of the validator claimed until the last clone of the
+	/// runner is dropped.
+	port_claim: Arc<PortClaim>,
 }
 
 impl TestValidatorRunner {
-	async fn run_internal(
+	/// Create a new runner for the solana test validator, returning the error
+	/// when it can't start. A
+	/// [`PortAllocationError`](crate::PortAllocationError) is returned when no
+	/// free ports are left in the `port_range`.
+	pub async fn try_run(
 		TestValidatorRunnerProps {
 			ports,
+			port_range,
 			programs,
 			pubkeys,
 			initial_lamports,
@@ -190,32 +152,73 @@ impl TestValidatorRunner {
 			accounts,
 			warp_slot,
 			epoch_schedule,
+			deactivate_features,
+			fee_rate_governor,
+			rent,
+			clone_from,
+			clone_accounts,
+			clone_programs,
+			clone_program_accounts,
+			account_fixtures,
+			account_fixture_dirs,
+			log_capacity,
+			faucet,
+			ledger_path,
+			keep_ledger_on_drop,
 		}: TestValidatorRunnerProps,
 	) -> Result<Self> {
+		let cloned_accounts = match clone_from {
+			Some(url) => {
+				fetch_cloned_accounts(
+					&SolanaRpcClient::new(&url),
+					&clone_accounts,
+					&clone_programs,
+					&clone_program_accounts,
+				)
+				.await?
+			}
+			None if clone_accounts.is_empty()
+				&& clone_programs.is_empty()
+				&& clone_program_accounts.is_empty() =>
+			{
+				vec![]
+			}
+			None => bail!("`clone_from` must be set to clone accounts into the test validator"),
+		};
+		let fixture_accounts = load_account_fixtures(&account_fixtures, &account_fixture_dirs)?;
 		let mut genesis = TestValidatorGenesis::default();
-		let faucet_keypair = Keypair::new();
-		let faucet_pubkey = faucet_keypair.pubkey();
 		let programs = programs.into_iter().map(Into::into).collect::<Vec<_>>();
-		let ledger_path = Arc::new(tempdir()?);
-
-		mark_port_used(ports.rpc);
-		mark_port_used(ports.pubsub);
-		mark_port_used(ports.faucet);
+		let ledger = Arc::new(LedgerDir::new(ledger_path, keep_ledger_on_drop)?);
 
-		for port in ports.gossip_range.0..=ports.gossip_range.1 {
-			mark_port_used(port);
-		}
+		let ports = match ports {
+			Some(ports) => ports,
+			None => TestValidatorPorts::try_random_ports_in(port_range)?,
+		};
 
-		let (sender, receiver) = unbounded();
-		let faucet_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), ports.faucet);
-		// run the faucet in a seperate thread
-		run_local_faucet_with_port(faucet_keypair, sender, None, None, None, ports.faucet);
+		let port_claim = PortClaim::new(ports);
+		release_ports([ports.faucet]);
 
-		let _ = receiver
-			.recv()
-			.expect("run solana faucet")
-			.expect("there was an error running the solana faucet");
+		let local_faucet = match faucet {
+			FaucetConfig::Enabled {
+				lamports,
+				per_request_cap,
+				per_time_cap,
+			} => {
+				let faucet_keypair = Keypair::new();
+				genesis.add_account(
+					faucet_keypair.pubkey(),
+					AccountSharedData::new(lamports, 0, &system_program::ID),
+				);
 
+				Some(LocalFaucet::start(
+					faucet_keypair,
+					ports.faucet,
+					per_request_cap,
+					per_time_cap,
+				)?)
+			}
+			FaucetConfig::Disabled => None,
+		};
 		let funded_accounts = pubkeys.iter().map(|pubkey| {
 			(
 				*pubkey,
@@ -223,28 +226,28 @@ impl TestValidatorRunner {
 			)
 		});
 
+		configure_node(&mut genesis, ports, ledger.path(), local_faucet.is_some());
 		genesis
-			.rpc_port(ports.rpc)
-			.gossip_port(ports.gossip_range.0)
-			.port_range(ports.gossip_range)
-			.ledger_path(ledger_path.path())
-			.rpc_config(JsonRpcConfig {
-				faucet_addr: Some(faucet_addr),
-				enable_rpc_transaction_history: true,
-				..JsonRpcConfig::default_for_test()
-			})
 			// Needed to prevent all account transactions from failing with this error:
 			// `Attempt to debit an account but found no record of a prior credit.`
 			.warp_slot(warp_slot)
 			.epoch_schedule(epoch_schedule)
+			.deactivate_features(&deactivate_features)
 			.add_upgradeable_programs_with_path(&programs)
-			.add_account(
-				faucet_pubkey,
-				AccountSharedData::new(sol_to_lamports(1_000_000.0), 0, &system_program::ID),
-			)
+			.add_accounts(cloned_accounts)
+			.add_accounts(fixture_accounts)
 			.add_accounts(funded_accounts)
 			.add_accounts(accounts);
 
+		if let Some(fee_rate_governor) = fee_rate_governor {
+			genesis.fee_rate_governor(fee_rate_governor);
+		}
+
+		if let Some(rent) = rent {
+			genesis.rent(rent);
+		}
+
+		release_ports(ports.iter());
 		let (validator, mint_keypair) = genesis.start_async().await;
 
 		let rpc = SolanaRpcClient::new_with_ws_and_commitment(
@@ -252,20 +255,29 @@ impl TestValidatorRunner {
 			&validator.rpc_pubsub_url(),
 			CommitmentConfig { commitment },
 		);
+		let log_buffer = LogBuffer::start(&rpc, log_capacity).await?;
 
 		// waiting for fees to stablize doesn't seem to work, so here waiting for this
 		// random airdrop to succeed seems to work. An alternative is a 15 second daily.
 		// The validator to be warmed up.
-		rpc.request_airdrop(&mint_keypair.pubkey(), sol_to_lamports(500.0))
-			.await?;
+		if let Some(lamports) = faucet.warm_up_lamports() {
+			rpc.request_airdrop(&mint_keypair.pubkey(), lamports)
+				.await?;
+		} else {
+			wait_for_blockhash(&rpc).await?;
+		}
 
 		let runner = Self {
 			genesis: Arc::new(genesis),
 			ports,
-			validator: Arc::new(validator),
+			validator: Some(Arc::new(validator)),
 			mint_keypair: Arc::new(mint_keypair),
 			rpc,
-			ledger_path,
+			ledger,
+			state_lock: Arc::default(),
+			log_buffer: Arc::new(log_buffer),
+			faucet: local_faucet.map(Arc::new),
+			port_claim: Arc::new(port_claim),
 		};
 
 		Ok(runner)
@@ -281,16 +293,21 @@ impl TestValidatorRunner {
 	/// 	TestValidatorRunner::run(TestValidatorRunnerProps::default()).await
 	/// }
 	/// ```
+	///
+	/// # Panics
+	///
+	/// Panics when the validator can't start. Use
+	/// [`TestValidatorRunner::try_run`] to handle the error.
 	pub async fn run(props: TestValidatorRunnerProps) -> Self {
-		Self::run_internal(props).await.unwrap()
+		Self::try_run(props).await.unwrap()
 	}
 
 	pub fn rpc_url(&self) -> String {
-		self.validator.rpc_url()
+		self.validator().rpc_url()
 	}
 
 	pub fn pubsub_url(&self) -> String {
-		self.validator.rpc_pubsub_url()
+		self.validator().rpc_pubsub_url()
 	}
 
 	pub fn rpc(&self) -> &SolanaRpcClient {
@@ -298,7 +315,9 @@ impl TestValidatorRunner {
 	}
 
 	pub fn validator(&self) -> &TestValidator {
-		&self.validator
+		self.validator
+			.as_deref()
+			.expect("the validator failed to restart")
 	}
 
 	pub fn genesis(&self) -> &TestValidatorGenesis {
@@ -313,64 +332,1127 @@ impl TestValidatorRunner {
 		&self.mint_keypair
 	}
 
+	/// The directory of the validator ledger, to attach to the artifacts of a
+	/// failed test.
 	pub fn ledger_path(&self) -> PathBuf {
-		self.ledger_path.path().to_owned()
+		self.ledger.path().to_owned()
 	}
-}
 
-impl Drop for TestValidatorRunner {
-	fn drop(&mut self) {
-		free_port(self.ports.rpc);
-		free_port(self.ports.pubsub);
-		free_port(self.ports.faucet);
+	/// Stop the validator and the faucet, then release the ports of the
+	/// runner.
+	///
+	/// Unlike dropping the runner this waits until every service has stopped,
+	/// so the ports can be reused as soon as it returns. This fails when the
+	/// runner has been cloned.
+	pub async fn shutdown(self) -> Result<()> {
+		let Self {
+			validator,
+			faucet,
+			log_buffer,
+			port_claim,
+			..
+		} = self;
+		let validator = validator
+			.and_then(Arc::into_inner)
+			.context("the runner can't be shut down while it has been cloned")?;
+
+		drop(log_buffer);
+		drop(validator);
 
-		for port in self.ports.gossip_range.0..=self.ports.gossip_range.1 {
-			free_port(port);
+		if let Some(faucet) = faucet.and_then(Arc::into_inner) {
+			faucet.shutdown().await?;
 		}
+
+		drop(port_claim);
+
+		Ok(())
 	}
-}
 
-static USED_PORTS: LazyLock<Arc<Mutex<HashSet<u16>>>> =
-	LazyLock::new(|| Arc::new(Mutex::new(HashSet::new())));
+	/// Stop the validator and start it again from the same ledger, so the
+	/// state of accounts changed by transactions survives. Accounts written
+	/// directly to the bank, with [`TestValidatorRunner::set_account`] for
+	/// example, are lost unless they've been rooted.
+	///
+	/// The validator listens on the same ports once this returns and the rpc
+	/// answers `getHealth`. The [`rpc`](TestValidatorRunner::rpc) client is
+	/// replaced since its websocket is closed by the restart, so subscriptions
+	/// created with it before the restart need to be created again. The
+	/// transaction logs captured by the runner are kept and their subscription
+	/// resumes on the new client. This fails when the runner has been cloned.
+	pub async fn restart(&mut self) -> Result<()> {
+		self.restart_validator(None).await
+	}
+
+	/// Restart the validator from the same ledger, warping to the `warp_slot`
+	/// from a snapshot of the latest complete bank when it is set.
+	async fn restart_validator(&mut self, warp_slot: Option<Slot>) -> Result<()> {
+		if self
+			.validator
+			.as_ref()
+			.is_some_and(|validator| Arc::strong_count(validator) > 1)
+		{
+			bail!("the runner can't be restarted while it has been cloned");
+		}
+
+		let state_lock = self.state_lock.clone();
+		let _guard = state_lock.lock().await;
+		let validator = self.validator.take();
+		let bank_forks = validator.as_deref().map(TestValidator::bank_forks);
+
+		self.log_buffer.stop();
+		drop(validator);
 
-fn is_port_available(port: u16) -> bool {
-	let used_ports = USED_PORTS.lock().unwrap();
+		let mut genesis = TestValidatorGenesis::default();
+		configure_node(
+			&mut genesis,
+			self.ports,
+			self.ledger.path(),
+			self.faucet.is_some(),
+		);
+
+		if let Some(warp_slot) = warp_slot {
+			let bank_forks = bank_forks
+				.as_deref()
+				.context("the validator can't be warped since it failed to restart")?;
+
+			write_ledger_snapshot(self.ledger.path(), bank_forks)?;
+			genesis.warp_slot(warp_slot);
+		}
+
+		// The banks of the stopped validator must be released before its
+		// accounts directory is reused.
+		drop(bank_forks);
+		let (validator, _) = genesis.start_async().await;
+		self.rpc = SolanaRpcClient::new_with_ws_and_commitment(
+			&validator.rpc_url(),
+			&validator.rpc_pubsub_url(),
+			self.rpc.commitment_config(),
+		);
+		self.validator = Some(Arc::new(validator));
+		self.wait_for_health().await?;
+		self.log_buffer.reconnect(&self.rpc).await?;
+
+		Ok(())
+	}
 
-	is_local_ipv4_port_free(port) && !used_ports.contains(&port)
+	/// Subscribe to the account of the `pubkey` over the pubsub url of the
+	/// validator and expect it to change within the `timeout`. Trigger the
+	/// change before waiting on the returned expectation.
+	///
+	/// ```rust
+	/// use std::time::Duration;
+	///
+	/// use solana_sdk::pubkey::Pubkey;
+	/// use test_utils_solana::TestValidatorRunner;
+	/// use test_utils_solana::prelude::*;
+	///
+	/// async fn run(runner: &TestValidatorRunner) -> anyhow::Result<()> {
+	/// 	let pubkey = Pubkey::new_unique();
+	/// 	let expectation = runner
+	/// 		.expect_account_change(&pubkey, Duration::from_secs(10))
+	/// 		.await?;
+	///
+	/// 	runner.airdrop(&pubkey, 1_000_000_000).await?;
+	/// 	expectation.wait().await?;
+	///
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub async fn expect_account_change(
+		&self,
+		pubkey: &Pubkey,
+		timeout: Duration,
+	) -> Result<PubsubExpectation<GetAccountInfoResponse>> {
+		expect_account_change(&self.rpc, pubkey, timeout).await
+	}
+
+	/// Subscribe to the transaction logs of the validator and expect a log
+	/// line containing the `pattern` within the `timeout`.
+	pub async fn expect_logs_containing(
+		&self,
+		pattern: impl Into<String>,
+		timeout: Duration,
+	) -> Result<PubsubExpectation<LogsNotificationResponse>> {
+		expect_logs_containing(&self.rpc, pattern, timeout).await
+	}
+
+	/// Wait until the rpc of the validator answers `getHealth`.
+	async fn wait_for_health(&self) -> Result<()> {
+		for _ in 0..MAX_POLL_ATTEMPTS {
+			if self.rpc.get_health().await.is_ok() {
+				return Ok(());
+			}
+
+			sleep(POLL_INTERVAL).await;
+		}
+
+		bail!("the validator wasn't healthy after {MAX_POLL_ATTEMPTS} attempts")
+	}
+
+	/// Create and initialize a new mint owned by the `token_program` with the
+	/// `mint_authority`. The runner's
+	/// [`mint_keypair`](TestValidatorRunner::mint_keypair) pays for the
+	/// transaction and the rent.
+	pub async fn create_mint(
+		&self,
+		decimals: u8,
+		mint_authority: &Pubkey,
+		token_program: TokenProgram,
+	) -> Result<Pubkey> {
+		let mint = Keypair::new();
+		let token_program = token_program.id();
+		let lamports = self
+			.rpc
+			.get_minimum_balance_for_rent_exemption(Mint::LEN)
+			.await?;
+		let instructions = [
+			create_account(
+				&self.mint_keypair.pubkey(),
+				&mint.pubkey(),
+				lamports,
+				Mint::LEN as u64,
+				&token_program,
+			),
+			initialize_mint2(
+				&token_program,
+				&mint.pubkey(),
+				mint_authority,
+				None,
+				decimals,
+			)?,
+		];
+
+		self.process_instructions(&instructions, &[&mint])
+			.await
+			.context("failed to create the mint")?;
+
+		Ok(mint.pubkey())
+	}
+
+	/// Create the associated token account of the `owner` for the `mint` when
+	/// it doesn't exist and return its address. The token program is read from
+	/// the owner of the `mint` account.
+	pub async fn create_token_account(&self, owner: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+		let token_program = self.get_token_program(mint).await?;
+		let instruction = create_associated_token_account_idempotent(
+			&self.mint_keypair.pubkey(),
+			owner,
+			mint,
+			&token_program,
+		);
+
+		self.process_instructions(&[instruction], &[])
+			.await
+			.context("failed to create the token account")?;
+
+		Ok(get_associated_token_address_with_program_id(
+			owner,
+			mint,
+			&token_program,
+		))
+	}
+
+	/// Mint `amount` tokens to the associated token account of the `owner`,
+	/// creating it when needed, and return its address.
+	///
+	/// The mint authority must be the runner's
+	/// [`mint_keypair`](TestValidatorRunner::mint_keypair). Use
+	/// [`TestValidatorRunner::mint_to_with_authority`] for other mint
+	/// authorities.
+	pub async fn mint_to(&self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Result<Pubkey> {
+		self.mint_to_with_authority(mint, owner, amount, &self.mint_keypair)
+			.await
+	}
+
+	/// Mint `amount` tokens to the associated token account of the `owner`
+	/// with the `mint_authority`, creating the token account when needed, and
+	/// return its address.
+	pub async fn mint_to_with_authority(
+		&self,
+		mint: &Pubkey,
+		owner: &Pubkey,
+		amount: u64,
+		mint_authority: &Keypair,
+	) -> Result<Pubkey> {
+		let account = self.rpc.get_account(mint).await?;
+		let token_program = account.owner;
+		let decimals = StateWithExtensions::<Mint>::unpack(&account.data)
+			.with_context(|| format!("invalid mint {mint}"))?
+			.base
+			.decimals;
+		let token_account =
+			get_associated_token_address_with_program_id(owner, mint, &token_program);
+		let instructions = [
+			create_associated_token_account_idempotent(
+				&self.mint_keypair.pubkey(),
+				owner,
+				mint,
+				&token_program,
+			),
+			mint_to_checked(
+				&token_program,
+				mint,
+				&token_account,
+				&mint_authority.pubkey(),
+				&[],
+				amount,
+				decimals,
+			)?,
+		];
+
+		self.process_instructions(&instructions, &[mint_authority])
+			.await
+			.with_context(|| format!("failed to mint {amount} tokens of {mint} to {owner}"))?;
+
+		Ok(token_account)
+	}
+
+	/// Deploy the `elf` as an upgradeable program at the address of the
+	/// `program` keypair with the same steps as `solana program deploy`.
+	///
+	/// The `elf` is written to a buffer in chunks which fit in a transaction,
+	/// with a bounded number of them in flight at once. The
+	/// programdata account has room for an upgrade to twice the length of the
+	/// `elf` and [`TestValidatorRunner::upgrade_program`] extends it for
+	/// larger programs.
+	///
+	/// Returns the signature of every confirmed transaction, ending with the
+	/// deployment. The program can be invoked once this resolves.
+	pub async fn deploy_program(
+		&self,
+		program: &Keypair,
+		elf: &[u8],
+		upgrade_authority: &Keypair,
+	) -> Result<Vec<Signature>> {
+		let _guard = self.state_lock.lock().await;
+		let program_id = program.pubkey();
+		let (mut signatures, buffer) = self.write_program_buffer(elf, upgrade_authority).await?;
+		let program_lamports = self
+			.rpc
+			.get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_program())
+			.await?;
+		let max_data_len = (elf.len() * 2).min(MAX_PROGRAM_LEN);
+		#[allow(deprecated)]
+		let instructions = loader_instruction::deploy_with_max_program_len(
+			&self.mint_keypair.pubkey(),
+			&program_id,
+			&buffer,
+			&upgrade_authority.pubkey(),
+			program_lamports,
+			max_data_len,
+		)?;
+		let signature = self
+			.send_instructions(&instructions, &[program, upgrade_authority])
+			.await
+			.with_context(|| format!("failed to deploy the program {program_id}"))?;
+
+		signatures.push(signature);
+		self.wait_for_program_slot(&program_id).await?;
+
+		Ok(signatures)
+	}
+
+	/// Replace the program at `program_id` with the `elf`, signed by its
+	/// upgrade `authority`.
+	///
+	/// The programdata account is extended first when the `elf` is larger
+	/// than it can hold. Returns the signature of every confirmed
+	/// transaction, ending with the upgrade. The new program can be invoked
+	/// once this resolves.
+	pub async fn upgrade_program(
+		&self,
+		program_id: &Pubkey,
+		elf: &[u8],
+		authority: &Keypair,
+	) -> Result<Vec<Signature>> {
+		let _guard = self.state_lock.lock().await;
+		let programdata = self
+			.rpc
+			.get_account(&get_program_data_address(program_id))
+			.await
+			.with_context(|| format!("the program {program_id} isn't upgradeable"))?;
+		let capacity = programdata
+			.data
+			.len()
+			.saturating_sub(UpgradeableLoaderState::size_of_programdata_metadata());
+		let mut signatures = vec![];
+
+		self.wait_for_program_slot(program_id).await?;
+
+		if elf.len() > capacity {
+			let additional_bytes = u32::try_from(elf.len() - capacity)?;
+			let instruction = loader_instruction::extend_program_checked(
+				program_id,
+				&authority.pubkey(),
+				Some(&self.mint_keypair.pubkey()),
+				additional_bytes,
+			);
+			let signature = self
+				.send_instructions(&[instruction], &[authority])
+				.await
+				.with_context(|| {
+					format!("failed to extend the program {program_id} by {additional_bytes} bytes")
+				})?;
+
+			signatures.push(signature);
+			self.wait_for_program_slot(program_id).await?;
+		}
+
+		let (buffer_signatures, buffer) = self.write_program_buffer(elf, authority).await?;
+		let instruction = loader_instruction::upgrade(
+			program_id,
+			&buffer,
+			&authority.pubkey(),
+			&self.mint_keypair.pubkey(),
+		);
+		let signature = self
+			.send_instructions(&[instruction], &[authority])
+			.await
+			.with_context(|| format!("failed to upgrade the program {program_id}"))?;
+
+		signatures.extend(buffer_signatures);
+		signatures.push(signature);
+		self.wait_for_program_slot(program_id).await?;
+
+		Ok(signatures)
+	}
+
+	/// The transaction logs captured since the validator started, oldest
+	/// first. At most [`TestValidatorRunnerProps::log_capacity`] are kept.
+	pub fn logs(&self) -> Vec<LogsNotificationResponse> {
+		self.log_buffer.logs()
+	}
+
+	/// The captured logs of the transaction with the `signature`, if they
+	/// are still in the buffer.
+	pub fn logs_for_signature(&self, signature: &Signature) -> Option<LogsNotificationResponse> {
+		self.log_buffer.logs_for_signature(signature)
+	}
+
+	/// A report of why the transaction with the `signature` failed, or `None`
+	/// when it succeeded. The report includes the log messages of the
+	/// transaction and the validator logs captured around it.
+	///
+	/// Used by [`assert_transaction_ok!`](crate::assert_transaction_ok).
+	pub async fn transaction_failure(&self, signature: &Signature) -> Option<String> {
+		let config = RpcTransactionConfig {
+			commitment: Some(CommitmentConfig::confirmed()),
+			max_supported_transaction_version: Some(0),
+			..RpcTransactionConfig::default()
+		};
+		let mut attempts = 1;
+		let result = loop {
+			let result = self
+				.rpc
+				.get_transaction_with_config(signature, config)
+				.await;
+
+			if result.is_ok() || attempts >= MAX_POLL_ATTEMPTS {
+				break result;
+			}
+
+			attempts += 1;
+			sleep(POLL_INTERVAL).await;
+		};
+
+		let mut report = match result {
+			Ok(transaction) => {
+				let meta = transaction.transaction.meta?;
+				let error = meta.err?;
+				let logs = meta.log_messages.unwrap_or_default();
+
+				format!(
+					"transaction {signature} failed in slot {}: {error}\n\ntransaction logs:\n{}\n",
+					transaction.slot,
+					logs.iter()
+						.map(|line| format!("    {line}"))
+						.collect::<Vec<_>>()
+						.join("\n")
+				)
+			}
+			Err(error) => format!("transaction {signature} was not found: {error}\n"),
+		};
+
+		let surrounding = self
+			.log_buffer
+			.logs_around_signature(signature, SURROUNDING_LOG_COUNT);
+		report.push_str("\nvalidator logs:\n");
+		report.push_str(&format_log_entries(&surrounding));
+
+		Some(report)
+	}
+
+	/// The [`Clock`] sysvar as seen through the runner's
+	/// [`rpc`](TestValidatorRunner::rpc).
+	pub async fn get_clock(&self) -> Result<Clock> {
+		let account = self.rpc.get_account(&sysvar::clock::ID).await?;
+		let clock = bincode::deserialize::<Clock>(&account.data)
+			.context("failed to deserialize the clock sysvar")?;
+
+		Ok(clock)
+	}
+
+	/// Warp the ledger to the `slot` and wait until the [`Clock`] sysvar for
+	/// it is visible through the runner's [`rpc`](TestValidatorRunner::rpc).
+	///
+	/// A validator can only warp while it starts, like `solana-test-validator
+	/// --warp-slot`. The latest complete bank is written to a snapshot in the
+	/// ledger and the validator is restarted from it at the `slot`, so the
+	/// state of every account is kept while the slots in between are skipped.
+	/// Subscriptions are affected in the same way as by
+	/// [`TestValidatorRunner::restart`], which also explains why this fails
+	/// when the runner has been cloned.
+	pub async fn warp_to_slot(&mut self, slot: Slot) -> Result<Clock> {
+		let clock = self.get_clock().await?;
+
+		if clock.slot >= slot {
+			bail!(
+				"cannot warp to slot {slot} since the validator is already at slot {}",
+				clock.slot
+			);
+		}
+
+		self.restart_validator(Some(slot)).await?;
+
+		self.wait_for_clock(|clock| clock.slot >= slot).await
+	}
+
+	/// Warp the ledger to the first slot of the `epoch`. See
+	/// [`TestValidatorRunner::warp_to_slot`].
+	pub async fn warp_to_epoch(&mut self, epoch: Epoch) -> Result<Clock> {
+		let epoch_schedule = self.rpc.get_epoch_schedule().await?;
+
+		self.warp_to_slot(epoch_schedule.get_first_slot_in_epoch(epoch))
+			.await
+	}
+
+	/// The latest slot processed by the validator.
+	pub async fn current_slot(&self) -> Result<Slot> {
+		let slot = self
+			.rpc
+			.get_slot_with_commitment(CommitmentConfig::processed())
+			.await?;
+
+		Ok(slot)
+	}
+
+	/// The epoch of the latest slot processed by the validator.
+	pub async fn current_epoch(&self) -> Result<Epoch> {
+		let epoch_info = self
+			.rpc
+			.get_epoch_info_with_commitment(CommitmentConfig::processed())
+			.await?;
+
+		Ok(epoch_info.epoch)
+	}
+
+	/// Wait until the validator has processed the `slot` and return the
+	/// current slot.
+	///
+	/// This times out after twice the expected time to reach the `slot`, and
+	/// never in less than 30 seconds.
+	pub async fn wait_for_slot(&self, slot: Slot) -> Result<Slot> {
+		let remaining = slot.saturating_sub(self.current_slot().await?);
+		let timeout = Duration::from_millis(remaining * DEFAULT_MS_PER_SLOT * 2);
+
+		self.wait_for_slot_with_timeout(slot, timeout.max(MIN_WAIT_TIMEOUT))
+			.await
+	}
+
+	/// Wait until the validator has processed the `slot` and return the
+	/// current slot, failing once the `timeout` has elapsed.
+	pub async fn wait_for_slot_with_timeout(&self, slot: Slot, timeout: Duration) -> Result<Slot> {
+		let deadline = Instant::now() + timeout;
+
+		loop {
+			let current_slot = self.current_slot().await?;
+
+			if current_slot >= slot {
+				return Ok(current_slot);
+			}
+
+			if Instant::now() >= deadline {
+				bail!(
+					"timed out after {timeout:?} waiting for slot {slot}, the validator is at \
+					 slot {current_slot}"
+				);
+			}
+
+			sleep(POLL_INTERVAL).await;
+		}
+	}
+
+	/// Wait until the validator reaches the first slot of the next epoch and
+	/// return the new epoch.
+	pub async fn wait_for_next_epoch(&self) -> Result<Epoch> {
+		let epoch = self.current_epoch().await? + 1;
+		let epoch_schedule = self.rpc.get_epoch_schedule().await?;
+
+		self.wait_for_slot(epoch_schedule.get_first_slot_in_epoch(epoch))
+			.await?;
+
+		Ok(epoch)
+	}
+
+	/// Move the `unix_timestamp` of the [`Clock`] sysvar forward to the
+	/// `unix_timestamp` and wait until it is visible through the runner's
+	/// [`rpc`](TestValidatorRunner::rpc).
+	///
+	/// The validator never moves the clock backwards, so each following slot
+	/// keeps a timestamp of at least `unix_timestamp` until a snapshot taken
+	/// earlier is [restored](TestValidatorRunner::restore).
+	pub async fn set_clock_timestamp(&self, unix_timestamp: UnixTimestamp) -> Result<Clock> {
+		let _guard = self.state_lock.lock().await;
+		let clock = self.get_clock().await?;
+
+		if clock.unix_timestamp >= unix_timestamp {
+			bail!(
+				"cannot move the clock back to {unix_timestamp} from {}",
+				clock.unix_timestamp
+			);
+		}
+
+		self.write_to_working_bank(|bank| {
+			bank.set_sysvar_for_tests(&Clock {
+				unix_timestamp,
+				..bank.clock()
+			});
+		})
+		.await
+		.with_context(|| format!("failed to set the clock timestamp to {unix_timestamp}"))?;
+
+		self.wait_for_clock(|clock| clock.unix_timestamp >= unix_timestamp)
+			.await
+	}
+
+	/// Overwrite the `account` at the `pubkey` in the running validator and
+	/// wait until the new state is visible through the runner's
+	/// [`rpc`](TestValidatorRunner::rpc).
+	///
+	/// The `account` must hold at least the rent-exempt minimum balance for
+	/// its data. An account below the minimum is rejected with an error
+	/// before anything is written.
+	pub async fn set_account(
+		&self,
+		pubkey: &Pubkey,
+		account: AccountSharedData,
+	) -> Result<Account> {
+		let minimum_balance = self
+			.rpc
+			.get_minimum_balance_for_rent_exemption(account.data().len())
+			.await?;
+
+		if account.lamports() < minimum_balance {
+			bail!(
+				"cannot set the account {pubkey} with {} lamports which is below the rent-exempt \
+				 minimum of {minimum_balance} lamports for {} bytes of data",
+				account.lamports(),
+				account.data().len()
+			);
+		}
+
+		let _guard = self.state_lock.lock().await;
+
+		self.write_to_working_bank(|bank| bank.store_account(pubkey, &account))
+			.await
+			.with_context(|| format!("failed to set the account {pubkey}"))?;
+
+		for _ in 0..MAX_POLL_ATTEMPTS {
+			let current = self
+				.rpc
+				.get_account_with_commitment(pubkey, self.rpc.commitment_config())
+				.await?
+				.filter(|current| {
+					current.lamports == account.lamports()
+						&& current.owner == *account.owner()
+						&& current.executable == account.executable()
+						&& current.data == account.data()
+				});
+
+			if let Some(current) = current {
+				return Ok(current);
+			}
+
+			sleep(POLL_INTERVAL).await;
+		}
+
+		bail!("the account {pubkey} wasn't updated after {MAX_POLL_ATTEMPTS} attempts")
+	}
+
+	/// Request the activation of the runtime feature with the `feature_id`,
+	/// such as one of the
+	/// [`TestValidatorRunnerProps::deactivate_features`].
+	///
+	/// The validator has no admin api for features, so the pending feature
+	/// account is written directly in the same way as `solana feature
+	/// activate`. The runtime activates it at the start of the returned
+	/// epoch, which can be reached with
+	/// [`TestValidatorRunner::warp_to_epoch`].
+	pub async fn activate_feature(&self, feature_id: &Pubkey) -> Result<Epoch> {
+		if !FEATURE_NAMES.contains_key(feature_id) {
+			bail!("the feature {feature_id} isn't known to this validator");
+		}
+
+		let account = self
+			.rpc
+			.get_account_with_commitment(feature_id, self.rpc.commitment_config())
+			.await?;
+
+		if let Some(feature) = account.as_ref().and_then(feature::from_account) {
+			match feature.activated_at {
+				Some(slot) => {
+					bail!("the feature {feature_id} was already activated in slot {slot}")
+				}
+				None => bail!("the feature {feature_id} is already pending activation"),
+			}
+		}
+
+		let lamports = self
+			.rpc
+			.get_minimum_balance_for_rent_exemption(Feature::size_of())
+			.await?;
+
+		self.set_account(
+			feature_id,
+			feature::create_account(&Feature::default(), lamports),
+		)
+		.await?;
+
+		Ok(self.get_clock().await?.epoch + 1)
+	}
+
+	/// Capture the current state of the `pubkeys` accounts and the clock so
+	/// they can be written back with [`TestValidatorRunner::restore`].
+	pub async fn snapshot(&self, pubkeys: &[Pubkey]) -> Result<StateSnapshot> {
+		let _guard = self.state_lock.lock().await;
+		let bank = self.working_bank();
+		let accounts = pubkeys
+			.iter()
+			.map(|pubkey| (*pubkey, bank.get_account(pubkey).unwrap_or_default()))
+			.collect();
+
+		Ok(StateSnapshot::new(
+			accounts,
+			false,
+			bank.clock().unix_timestamp,
+		))
+	}
+
+	/// Capture the current state of every account apart from builtins,
+	/// sysvars and vote accounts, and the clock, so it can be written back
+	/// with [`TestValidatorRunner::restore`].
+	pub async fn snapshot_all(&self) -> Result<StateSnapshot> {
+		let _guard = self.state_lock.lock().await;
+		let unix_timestamp = self.working_bank().clock().unix_timestamp;
+
+		Ok(StateSnapshot::new(
+			self.get_snapshot_accounts()?,
+			true,
+			unix_timestamp,
+		))
+	}
+
+	/// Write the accounts of the `snapshot` back into the running validator
+	/// and wait until they are visible through the runner's
+	/// [`rpc`](TestValidatorRunner::rpc). Accounts created after a
+	/// [complete](StateSnapshot::is_complete) snapshot was taken are removed.
+	///
+	/// The `unix_timestamp` of the [`Clock`] sysvar is reset as well, which
+	/// undoes [`TestValidatorRunner::set_clock_timestamp`]. Following slots
+	/// take the later of the restored timestamp and the timestamp voted by
+	/// the validator. The slot and epoch of the clock and the sysvars derived
+	/// from them keep moving forward since the validator can't go back to an
+	/// earlier slot.
+	///
+	/// Transactions sent outside of the runner should be confirmed before
+	/// restoring since they may be applied on top of the restored state.
+	pub async fn restore(&self, snapshot: &StateSnapshot) -> Result<()> {
+		let _guard = self.state_lock.lock().await;
+		let mut accounts = snapshot.accounts().clone();
+
+		if snapshot.is_complete() {
+			for pubkey in self.get_snapshot_accounts()?.into_keys() {
+				accounts.entry(pubkey).or_default();
+			}
+		}
+
+		let slot = self
+			.write_to_working_bank(|bank| {
+				for (pubkey, account) in &accounts {
+					bank.store_account(pubkey, account);
+				}
+
+				bank.set_sysvar_for_tests(&Clock {
+					unix_timestamp: snapshot.unix_timestamp(),
+					..bank.clock()
+				});
+			})
+			.await
+			.context("failed to restore the snapshot")?;
+
+		for _ in 0..MAX_POLL_ATTEMPTS {
+			if self.rpc.get_slot().await? >= slot {
+				return Ok(());
+			}
+
+			sleep(POLL_INTERVAL).await;
+		}
+
+		bail!("the restored snapshot wasn't visible after {MAX_POLL_ATTEMPTS} attempts")
+	}
+
+	/// Every account of the working bank which is captured by
+	/// [`TestValidatorRunner::snapshot_all`].
+	fn get_snapshot_accounts(&self) -> Result<BTreeMap<Pubkey, AccountSharedData>> {
+		let accounts = self
+			.working_bank()
+			.get_all_accounts(false)
+			.map_err(|error| anyhow!("failed to read the accounts of the validator: {error}"))?;
+
+		Ok(accounts
+			.into_iter()
+			.filter(|(_, account, _)| !SNAPSHOT_EXCLUDED_OWNERS.contains(account.owner()))
+			.map(|(pubkey, account, _)| (pubkey, account))
+			.collect())
+	}
+
+	fn working_bank(&self) -> Arc<Bank> {
+		self.validator().bank_forks().read().unwrap().working_bank()
+	}
+
+	/// Run `write` on the working bank of the validator, retrying on the next
+	/// bank when the working bank has already started freezing. The slot of
+	/// the bank which was written to is returned.
+	async fn write_to_working_bank(&self, write: impl Fn(&Bank)) -> Result<Slot> {
+		for _ in 0..MAX_POLL_ATTEMPTS {
+			let bank = self.working_bank();
+
+			{
+				// The freeze lock keeps the bank from starting to freeze until the write
+				// is done, in the same way as the banking stage commits transactions.
+				let hash = bank.freeze_lock();
+
+				if *hash == Hash::default() && !bank.freeze_started() {
+					write(&bank);
+
+					return Ok(bank.slot());
+				}
+			}
+
+			sleep(POLL_INTERVAL).await;
+		}
+
+		bail!("no working bank of the validator could be written to")
+	}
+
+	/// Poll the [`Clock`] sysvar until it matches the `predicate`, giving up
+	/// after [`MAX_POLL_ATTEMPTS`].
+	async fn wait_for_clock(&self, predicate: impl Fn(&Clock) -> bool) -> Result<Clock> {
+		let mut attempts = 0;
+
+		loop {
+			let clock = self.get_clock().await?;
+
+			if predicate(&clock) {
+				return Ok(clock);
+			}
+
+			attempts += 1;
+
+			if attempts >= MAX_POLL_ATTEMPTS {
+				bail!(
+					"the clock sysvar wasn't updated after {attempts} attempts, the latest clock \
+					 is {clock:?}"
+				);
+			}
+
+			sleep(POLL_INTERVAL).await;
+		}
+	}
+
+	/// Create a buffer owned by the `authority` and write the `elf` to it.
+	/// Returns the signatures of the transactions and the buffer address.
+	async fn write_program_buffer(
+		&self,
+		elf: &[u8],
+		authority: &Keypair,
+	) -> Result<(Vec<Signature>, Pubkey)> {
+		if elf.is_empty() || elf.len() > MAX_PROGRAM_LEN {
+			bail!(
+				"the program must be between 1 and {MAX_PROGRAM_LEN} bytes but is {} bytes",
+				elf.len()
+			);
+		}
+
+		let buffer = Keypair::new();
+		let buffer_pubkey = buffer.pubkey();
+		let authority_pubkey = authority.pubkey();
+		let lamports = self
+			.rpc
+			.get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_buffer(
+				elf.len(),
+			))
+			.await?;
+		let instructions = loader_instruction::create_buffer(
+			&self.mint_keypair.pubkey(),
+			&buffer_pubkey,
+			&authority_pubkey,
+			lamports,
+			elf.len(),
+		)?;
+		let signature = self
+			.send_instructions(&instructions, &[&buffer])
+			.await
+			.context("failed to create the program buffer")?;
+		let chunk_len = self.max_write_chunk_len(&buffer_pubkey, &authority_pubkey)?;
+		let writes =
+			elf.chunks(chunk_len)
+				.zip((0_u32..).step_by(chunk_len))
+				.map(|(bytes, offset)| {
+					let len = bytes.len();
+					let instruction = loader_instruction::write(
+						&buffer_pubkey,
+						&authority_pubkey,
+						offset,
+						bytes.to_vec(),
+					);
+
+					async move {
+						self.send_instructions(&[instruction], &[authority])
+							.await
+							.with_context(|| {
+								format!(
+									"failed to write {len} bytes at offset {offset} of the \
+									 program buffer"
+								)
+							})
+					}
+				});
+		let write_signatures = stream::iter(writes)
+			.buffered(MAX_CONCURRENT_WRITES)
+			.try_collect::<Vec<_>>()
+			.await?;
+		let signatures = [signature].into_iter().chain(write_signatures).collect();
+
+		Ok((signatures, buffer_pubkey))
+	}
+
+	/// The largest chunk of program data which fits in a buffer write
+	/// transaction paid for by the runner.
+	fn max_write_chunk_len(&self, buffer: &Pubkey, authority: &Pubkey) -> Result<usize> {
+		let instruction = loader_instruction::write(buffer, authority, 0, vec![]);
+		let transaction = VersionedTransaction::new_unsigned_v0(
+			&self.mint_keypair.pubkey(),
+			&[instruction],
+			&[],
+			Hash::default(),
+		)?;
+
+		// The length prefix of the instruction data grows by a byte once the
+		// chunk is added.
+		Ok(PACKET_DATA_SIZE - transaction.serialized_size() - 1)
+	}
+
+	/// Wait for the slot after the program was last deployed, upgraded or
+	/// extended. The loader rejects changes to a program within that slot and
+	/// the program can't be invoked until it has passed.
+	async fn wait_for_program_slot(&self, program_id: &Pubkey) -> Result<Clock> {
+		let programdata = self
+			.rpc
+			.get_account(&get_program_data_address(program_id))
+			.await?;
+		let UpgradeableLoaderState::ProgramData { slot, .. } =
+			bincode::deserialize(&programdata.data)?
+		else {
+			bail!("the program {program_id} has no programdata");
+		};
+
+		self.wait_for_clock(|clock| clock.slot > slot).await
+	}
+
+	async fn get_token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
+		let account = self.rpc.get_account(mint).await?;
+
+		if account.owner != spl_token::ID && account.owner != spl_token_2022::ID {
+			bail!("the mint {mint} is not owned by a token program");
+		}
+
+		Ok(account.owner)
+	}
+
+	/// Send the `instructions` with the runner's
+	/// [`mint_keypair`](TestValidatorRunner::mint_keypair) as the fee payer and
+	/// wait for them to be confirmed. A failed preflight returns an error with
+	/// the logs of the transaction.
+	async fn process_instructions(
+		&self,
+		instructions: &[Instruction],
+		signers: &[&Keypair],
+	) -> Result<Signature> {
+		let _guard = self.state_lock.lock().await;
+
+		self.send_instructions(instructions, signers).await
+	}
+
+	/// The same as [`TestValidatorRunner::process_instructions`] for callers
+	/// which already hold the `state_lock`.
+	async fn send_instructions(
+		&self,
+		instructions: &[Instruction],
+		signers: &[&Keypair],
+	) -> Result<Signature> {
+		let payer = self.mint_keypair.pubkey();
+		let signers = [&*self.mint_keypair]
+			.into_iter()
+			.chain(signers.iter().copied())
+			.collect::<Vec<_>>();
+		let signature = self
+			.rpc
+			.send_and_confirm_transaction_with_options(
+				instructions,
+				&payer,
+				signers.as_slice(),
+				SendOptions::default(),
+			)
+			.await?;
+
+		Ok(signature)
+	}
 }
 
-fn mark_port_used(port: u16) {
-	let mut used_ports = USED_PORTS.lock().unwrap();
-	used_ports.insert(port);
+#[async_trait(?Send)]
+impl TestRunner for TestValidatorRunner {
+	fn rpc(&self) -> &SolanaRpcClient {
+		TestValidatorRunner::rpc(self)
+	}
+
+	fn payer(&self) -> &Keypair {
+		self.mint_keypair()
+	}
+
+	async fn set_account(&self, pubkey: &Pubkey, account: AccountSharedData) -> Result<Account> {
+		TestValidatorRunner::set_account(self, pubkey, account).await
+	}
+
+	async fn get_clock(&self) -> Result<Clock> {
+		TestValidatorRunner::get_clock(self).await
+	}
+
+	async fn warp_to_slot(&mut self, slot: Slot) -> Result<Clock> {
+		TestValidatorRunner::warp_to_slot(self, slot).await
+	}
+
+	async fn set_clock_timestamp(&self, unix_timestamp: UnixTimestamp) -> Result<Clock> {
+		TestValidatorRunner::set_clock_timestamp(self, unix_timestamp).await
+	}
 }
 
-fn free_port(port: u16) {
-	let mut used_ports = USED_PORTS.lock().unwrap();
-	used_ports.remove(&port);
+/// The token program of the mints created by
+/// [`TestValidatorRunner::create_mint`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TokenProgram {
+	/// The original spl-token program.
+	#[default]
+	Token,
+	/// The token-2022 program with support for extensions.
+	Token2022,
 }
 
-fn find_ports() -> Option<(u16, u16, u16, (u16, u16))> {
-	let mut rng = rand::rng();
-	let max = u16::MAX - 25;
-	let mut attempts = 100;
-
-	loop {
-		attempts -= 1;
-		let port: u16 = rng.random_range(1000..max);
-		let range_start = port + 3;
-		let range_end = range_start + 20;
-		let ports = (port, port + 1, port + 2, (range_start, range_end));
-
-		if is_port_available(ports.0)
-			&& is_port_available(ports.1)
-			&& is_port_available(ports.2)
-			&& (range_start..=range_end).all(is_port_available)
-		{
-			return Some(ports);
+impl TokenProgram {
+	pub fn id(self) -> Pubkey {
+		match self {
+			Self::Token => spl_token::ID,
+			Self::Token2022 => spl_token_2022::ID,
 		}
+	}
+}
+
+/// Bind the validator started from the `genesis` to the `ports` and the
+/// `ledger_path`. Airdrops are served by the faucet of the runner when
+/// `with_faucet` is set.
+pub(crate) fn configure_node<'a>(
+	genesis: &'a mut TestValidatorGenesis,
+	ports: TestValidatorPorts,
+	ledger_path: &Path,
+	with_faucet: bool,
+) -> &'a mut TestValidatorGenesis {
+	genesis
+		.rpc_port(ports.rpc)
+		.gossip_port(ports.gossip_range.0)
+		.port_range(ports.gossip_range)
+		.ledger_path(ledger_path)
+		.rpc_config(JsonRpcConfig {
+			faucet_addr: with_faucet
+				.then(|| SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), ports.faucet)),
+			enable_rpc_transaction_history: true,
+			..JsonRpcConfig::default_for_test()
+		})
+}
 
-		if attempts <= 0 {
-			return None;
+/// Write the highest complete bank of a stopped validator to a full snapshot
+/// archive in the ledger, so the next start loads it instead of the older
+/// snapshot and the validator can warp from it.
+fn write_ledger_snapshot(ledger_path: &Path, bank_forks: &RwLock<BankForks>) -> Result<()> {
+	let bank = bank_forks
+		.read()
+		.map_err(|_| anyhow!("the banks of the validator are poisoned"))?
+		.frozen_banks()
+		.filter(|(_, bank)| bank.is_complete())
+		.max_by_key(|(slot, _)| *slot)
+		.map(|(_, bank)| bank)
+		.context("the validator has no complete bank to snapshot")?;
+
+	bank_to_full_snapshot_archive(
+		ledger_path.join("snapshot"),
+		&bank,
+		None,
+		ledger_path,
+		ledger_path,
+		SnapshotConfig::default().archive_format,
+	)
+	.with_context(|| format!("failed to snapshot the bank at slot {}", bank.slot()))?;
+
+	Ok(())
+}
+
+/// Wait until the rpc serves a blockhash, so the validator accepts
+/// transactions.
+pub(crate) async fn wait_for_blockhash(rpc: &SolanaRpcClient) -> Result<()> {
+	for _ in 0..MAX_POLL_ATTEMPTS {
+		if rpc.get_latest_blockhash().await.is_ok() {
+			return Ok(());
 		}
+
+		sleep(POLL_INTERVAL).await;
 	}
+
+	bail!("the validator didn't serve a blockhash after {MAX_POLL_ATTEMPTS} attempts")
 }
+
+/// The number of times the validator is polled for a change to become
+/// visible. This allows for the lag of the `finalized` commitment.
+pub(crate) const MAX_POLL_ATTEMPTS: u32 = 300;
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// The shortest timeout of [`TestValidatorRunner::wait_for_slot`].
+const MIN_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The number of program buffer writes sent at once by
+/// [`TestValidatorRunner::deploy_program`] and
+/// [`TestValidatorRunner::upgrade_program`].
+const MAX_CONCURRENT_WRITES: usize = 8;
+/// The largest program which fits in a programdata account.
+const MAX_PROGRAM_LEN: usize =
+	MAX_PERMITTED_DATA_LENGTH as usize - UpgradeableLoaderState::size_of_programdata_metadata();
+
+/// The number of captured transaction logs on either side of a failed
+/// transaction which are included in its failure report.
+const SURROUNDING_LOG_COUNT: usize = 5;
+
+/// The owners of the accounts which aren't captured by
+/// [`TestValidatorRunner::snapshot_all`]. Overwriting them could break the
+/// validator.
+const SNAPSHOT_EXCLUDED_OWNERS: [Pubkey; 3] = [
+	solana_sdk_ids::native_loader::ID,
+	solana_sdk_ids::sysvar::ID,
+	solana_sdk_ids::vote::ID,
+];
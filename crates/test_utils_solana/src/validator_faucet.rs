@@ -0,0 +1,86 @@
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::JoinHandle;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use crossbeam_channel::unbounded;
+use solana_faucet::faucet::Faucet;
+use solana_faucet::faucet::run_faucet;
+use solana_sdk::signature::Keypair;
+use tokio::runtime::Builder;
+use tokio::sync::oneshot;
+
+/// The faucet which serves the airdrops of the validator. It runs on its own
+/// thread until it is shut down or dropped.
+#[derive(Debug)]
+pub(crate) struct LocalFaucet {
+	shutdown: Option<oneshot::Sender<()>>,
+	thread: Option<JoinHandle<()>>,
+}
+
+impl LocalFaucet {
+	/// Start the faucet on the `port` and wait until it is listening.
+	pub(crate) fn start(
+		keypair: Keypair,
+		port: u16,
+		per_request_cap: Option<u64>,
+		per_time_cap: Option<u64>,
+	) -> Result<Self> {
+		let (sender, receiver) = unbounded();
+		let (shutdown, shutdown_receiver) = oneshot::channel();
+		let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port);
+		let faucet = Arc::new(Mutex::new(Faucet::new(
+			keypair,
+			None,
+			per_time_cap,
+			per_request_cap,
+		)));
+		let thread = thread::spawn(move || {
+			let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+
+			runtime.block_on(async {
+				tokio::select! {
+					() = run_faucet(faucet, address, Some(sender)) => {}
+					_ = shutdown_receiver => {}
+				}
+			});
+		});
+
+		receiver.recv()?.map_err(|error| anyhow!(error))?;
+
+		Ok(Self {
+			shutdown: Some(shutdown),
+			thread: Some(thread),
+		})
+	}
+
+	/// Stop the faucet and wait until its port is closed.
+	pub(crate) async fn shutdown(mut self) -> Result<()> {
+		self.stop();
+
+		if let Some(thread) = self.thread.take() {
+			tokio::task::spawn_blocking(move || thread.join())
+				.await?
+				.map_err(|_| anyhow!("the faucet thread panicked"))?;
+		}
+
+		Ok(())
+	}
+
+	fn stop(&mut self) {
+		if let Some(shutdown) = self.shutdown.take() {
+			let _ = shutdown.send(());
+		}
+	}
+}
+
+impl Drop for LocalFaucet {
+	fn drop(&mut self) {
+		self.stop();
+	}
+}
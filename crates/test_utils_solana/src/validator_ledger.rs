@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::thread;
+
+use anyhow::Result;
+use anyhow::bail;
+use tempfile::tempdir;
+
+/// The ledger directory of a validator. It is removed when dropped unless it
+/// should be kept, or a test is panicking in which case its path is printed
+/// so the ledger can be inspected.
+#[derive(Debug)]
+pub(crate) struct LedgerDir {
+	path: PathBuf,
+	keep: bool,
+}
+
+impl LedgerDir {
+	/// Use the `path` for the ledger or a new temporary directory when it's
+	/// `None`. The `path` must be empty or not exist yet.
+	pub(crate) fn new(path: Option<PathBuf>, keep: bool) -> Result<Self> {
+		let path = match path {
+			Some(path) => {
+				if fs::read_dir(&path).is_ok_and(|mut entries| entries.next().is_some()) {
+					bail!("the ledger path {} isn't empty", path.display());
+				}
+
+				fs::create_dir_all(&path)?;
+				path
+			}
+			None => tempdir()?.keep(),
+		};
+
+		Ok(Self { path, keep })
+	}
+
+	pub(crate) fn path(&self) -> &Path {
+		&self.path
+	}
+}
+
+impl Drop for LedgerDir {
+	fn drop(&mut self) {
+		if thread::panicking() {
+			eprintln!(
+				"the ledger of the failed test validator was kept at {}",
+				self.path.display()
+			);
+		} else if !self.keep {
+			let _ = fs::remove_dir_all(&self.path);
+		}
+	}
+}
@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use futures::StreamExt;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use tokio::task::JoinHandle;
+use wasm_client_solana::SolanaRpcClient;
+use wasm_client_solana::rpc_config::LogsSubscribeRequest;
+use wasm_client_solana::rpc_config::RpcTransactionLogsConfig;
+use wasm_client_solana::rpc_config::RpcTransactionLogsFilter;
+use wasm_client_solana::rpc_response::LogsNotificationResponse;
+
+/// The transaction logs of the validator, captured from a `logsSubscribe`
+/// subscription into a ring buffer which drops the oldest entries once the
+/// `capacity` is reached.
+#[derive(Debug)]
+pub(crate) struct LogBuffer {
+	entries: Arc<Mutex<VecDeque<LogsNotificationResponse>>>,
+	capacity: usize,
+	task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl LogBuffer {
+	/// Subscribe to the logs of every non-vote transaction. A `capacity` of
+	/// `0` disables the capture.
+	pub(crate) async fn start(rpc: &SolanaRpcClient, capacity: usize) -> Result<Self> {
+		let buffer = Self {
+			entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+			capacity,
+			task: Mutex::default(),
+		};
+		buffer.reconnect(rpc).await?;
+
+		Ok(buffer)
+	}
+
+	/// Replace the subscription with a new one created by the `rpc`, keeping
+	/// the entries captured so far.
+	pub(crate) async fn reconnect(&self, rpc: &SolanaRpcClient) -> Result<()> {
+		self.stop();
+
+		if self.capacity == 0 {
+			return Ok(());
+		}
+
+		let mut subscription = rpc
+			.logs_subscribe(
+				LogsSubscribeRequest::builder()
+					.filter(RpcTransactionLogsFilter::All)
+					.config(RpcTransactionLogsConfig {
+						commitment: Some(CommitmentConfig::processed()),
+					})
+					.build(),
+			)
+			.await?;
+		let capacity = self.capacity;
+		let task_entries = self.entries.clone();
+		let task = tokio::spawn(async move {
+			while let Some(notification) = subscription.next().await {
+				let mut entries = task_entries.lock().unwrap();
+
+				if entries.len() == capacity {
+					entries.pop_front();
+				}
+
+				entries.push_back(notification.params.result);
+			}
+		});
+		*self.task.lock().unwrap() = Some(task);
+
+		Ok(())
+	}
+
+	/// Drop the subscription so the validator it is connected to can shut
+	/// down. The captured entries are kept.
+	pub(crate) fn stop(&self) {
+		if let Some(task) = self.task.lock().unwrap().take() {
+			task.abort();
+		}
+	}
+
+	pub(crate) fn logs(&self) -> Vec<LogsNotificationResponse> {
+		self.entries.lock().unwrap().iter().cloned().collect()
+	}
+
+	pub(crate) fn logs_for_signature(
+		&self,
+		signature: &Signature,
+	) -> Option<LogsNotificationResponse> {
+		self.entries
+			.lock()
+			.unwrap()
+			.iter()
+			.find(|entry| entry.value.signature == *signature)
+			.cloned()
+	}
+
+	/// Up to `count` entries either side of the transaction with the
+	/// `signature`, or the latest `count` entries when it wasn't captured.
+	pub(crate) fn logs_around_signature(
+		&self,
+		signature: &Signature,
+		count: usize,
+	) -> Vec<LogsNotificationResponse> {
+		let entries = self.entries.lock().unwrap();
+		let (start, end) = match entries
+			.iter()
+			.position(|entry| entry.value.signature == *signature)
+		{
+			Some(index) => (index.saturating_sub(count), index + count + 1),
+			None => (entries.len().saturating_sub(count), entries.len()),
+		};
+
+		entries
+			.range(start..end.min(entries.len()))
+			.cloned()
+			.collect()
+	}
+}
+
+impl Drop for LogBuffer {
+	fn drop(&mut self) {
+		self.stop();
+	}
+}
+
+/// Render the captured `entries` for a failure report.
+pub(crate) fn format_log_entries(entries: &[LogsNotificationResponse]) -> String {
+	let mut output = String::new();
+
+	for entry in entries {
+		let _ = writeln!(
+			output,
+			"[slot {}] {}{}",
+			entry.context.slot,
+			entry.value.signature,
+			entry
+				.value
+				.err
+				.as_ref()
+				.map(|error| format!(" failed: {error}"))
+				.unwrap_or_default()
+		);
+
+		for line in &entry.value.logs {
+			let _ = writeln!(output, "    {line}");
+		}
+	}
+
+	output
+}
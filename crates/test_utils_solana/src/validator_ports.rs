@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::net::TcpListener;
+use std::net::UdpSocket;
+use std::ops::RangeInclusive;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use rand::Rng;
+use typed_builder::TypedBuilder;
+
+/// The range which [`TestValidatorPorts::try_random_ports_in`] chooses ports
+/// from unless the runner sets a `port_range`.
+pub const DEFAULT_PORT_RANGE: RangeInclusive<u16> = 1000..=u16::MAX;
+
+/// The number of consecutive ports used by a validator: the `rpc`, `pubsub`
+/// and `faucet` ports followed by the `gossip_range`.
+const PORT_BLOCK_LEN: u16 = 24;
+
+/// The ports claimed by the runners of this process. Ports handed out by
+/// [`TestValidatorPorts::try_random_ports_in`] map to the sockets which keep
+/// other processes from claiming them until the validator starts.
+static USED_PORTS: LazyLock<Mutex<HashMap<u16, Option<PortReservation>>>> =
+	LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Copy, Clone, TypedBuilder)]
+pub struct TestValidatorPorts {
+	#[builder(default = 8899)]
+	pub rpc: u16,
+	#[builder(default = 8900)]
+	pub pubsub: u16,
+	#[builder(default = 9900)]
+	pub faucet: u16,
+	#[builder(default = (8001, 8021))]
+	pub gossip_range: (u16, u16),
+}
+
+impl Default for TestValidatorPorts {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl TestValidatorPorts {
+	/// Reserve a block of consecutive free ports at a random position in the
+	/// `range`.
+	///
+	/// The ports are held by sockets bound on every interface, so test
+	/// binaries running in parallel can't claim them, until the
+	/// [`TestValidatorRunner`](crate::TestValidatorRunner) using them starts
+	/// its validator. They stay reserved for this process until that runner
+	/// is dropped.
+	pub fn try_random_ports_in(range: RangeInclusive<u16>) -> Result<Self, PortAllocationError> {
+		let (start, end) = (*range.start(), *range.end());
+		let Some(last_start) = end
+			.checked_sub(PORT_BLOCK_LEN - 1)
+			.filter(|last_start| *last_start >= start)
+		else {
+			return Err(PortAllocationError::RangeTooSmall { start, end });
+		};
+		let candidates = u32::from(last_start - start) + 1;
+		let offset = rand::rng().random_range(0..candidates);
+		let mut used_ports = USED_PORTS.lock().unwrap();
+
+		for index in 0..candidates {
+			let port = start + u16::try_from((offset + index) % candidates).unwrap();
+			let ports = Self::from_first_port(port);
+			let reservations = ports
+				.iter()
+				.map(|port| {
+					if used_ports.contains_key(&port) {
+						return None;
+					}
+
+					PortReservation::bind(port).map(|reservation| (port, Some(reservation)))
+				})
+				.collect::<Option<Vec<_>>>();
+
+			if let Some(reservations) = reservations {
+				used_ports.extend(reservations);
+				return Ok(ports);
+			}
+		}
+
+		Err(PortAllocationError::Exhausted { start, end })
+	}
+
+	/// Reserve a block of free ports from the [`DEFAULT_PORT_RANGE`], or
+	/// `None` when every port in the range is in use.
+	#[deprecated(note = "Use `TestValidatorPorts::try_random_ports_in` to choose the range")]
+	pub fn try_random_ports() -> Option<Self> {
+		Self::try_random_ports_in(DEFAULT_PORT_RANGE).ok()
+	}
+
+	/// Reserve a block of free ports from the [`DEFAULT_PORT_RANGE`].
+	///
+	/// # Panics
+	///
+	/// Panics when every port in the range is in use.
+	#[deprecated(note = "Use `TestValidatorPorts::try_random_ports_in` to handle the error")]
+	pub fn random_ports() -> Self {
+		Self::try_random_ports_in(DEFAULT_PORT_RANGE).unwrap_or_else(|error| panic!("{error}"))
+	}
+
+	fn from_first_port(port: u16) -> Self {
+		Self {
+			rpc: port,
+			pubsub: port + 1,
+			faucet: port + 2,
+			gossip_range: (port + 3, port + PORT_BLOCK_LEN - 1),
+		}
+	}
+
+	/// Every port used by the validator.
+	pub(crate) fn iter(self) -> impl Iterator<Item = u16> {
+		[self.rpc, self.pubsub, self.faucet]
+			.into_iter()
+			.chain(self.gossip_range.0..=self.gossip_range.1)
+	}
+}
+
+/// The error returned when [`TestValidatorPorts::try_random_ports_in`] can't
+/// reserve a block of ports.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum PortAllocationError {
+	#[error(
+		"the port range {start}..={end} can't fit the {PORT_BLOCK_LEN} consecutive ports used by \
+		 the test validator"
+	)]
+	RangeTooSmall { start: u16, end: u16 },
+	#[error(
+		"no {PORT_BLOCK_LEN} consecutive free ports are available in the range {start}..={end}"
+	)]
+	Exhausted { start: u16, end: u16 },
+}
+
+/// The sockets which hold a port until the validator binds it.
+#[derive(Debug)]
+struct PortReservation {
+	_tcp: TcpListener,
+	_udp: UdpSocket,
+}
+
+impl PortReservation {
+	fn bind(port: u16) -> Option<Self> {
+		let address = (Ipv4Addr::UNSPECIFIED, port);
+
+		Some(Self {
+			_tcp: TcpListener::bind(address).ok()?,
+			_udp: UdpSocket::bind(address).ok()?,
+		})
+	}
+}
+
+/// Claims the ports of a runner for this process so they aren't handed out
+/// again, until it is dropped.
+#[cfg(feature = "test_validator")]
+#[derive(Debug)]
+pub(crate) struct PortClaim(TestValidatorPorts);
+
+#[cfg(feature = "test_validator")]
+impl PortClaim {
+	pub(crate) fn new(ports: TestValidatorPorts) -> Self {
+		let mut used_ports = USED_PORTS.lock().unwrap();
+
+		for port in ports.iter() {
+			used_ports.entry(port).or_insert(None);
+		}
+
+		Self(ports)
+	}
+}
+
+#[cfg(feature = "test_validator")]
+impl Drop for PortClaim {
+	fn drop(&mut self) {
+		let mut used_ports = USED_PORTS.lock().unwrap();
+
+		for port in self.0.iter() {
+			used_ports.remove(&port);
+		}
+	}
+}
+
+/// Close the sockets reserving the `ports` so the validator can bind them.
+/// The ports remain claimed by this process.
+#[cfg(feature = "test_validator")]
+pub(crate) fn release_ports(ports: impl IntoIterator<Item = u16>) {
+	let mut used_ports = USED_PORTS.lock().unwrap();
+
+	for port in ports {
+		if let Some(reservation) = used_ports.get_mut(&port) {
+			*reservation = None;
+		}
+	}
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "test_validator")]
+
+use std::fs;
+use std::path::Path;
+
+use assert2::check;
+use serde_json::json;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::account::ReadableAccount;
+use solana_sdk::account::WritableAccount;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::pubkey::Pubkey;
+use test_utils_solana::TestValidatorRunnerProps;
+use test_utils_solana::load_account_fixture;
+use wasm_client_solana::solana_account_decoder::UiAccount;
+use wasm_client_solana::solana_account_decoder::UiAccountEncoding;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn set_account_at_runtime() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::builder()
+		.commitment(CommitmentLevel::Processed)
+		.build()
+		.run()
+		.await;
+	let pubkey = Pubkey::new_unique();
+	let owner = Pubkey::new_unique();
+	let data = vec![1, 2, 3, 4];
+	let lamports = runner
+		.rpc()
+		.get_minimum_balance_for_rent_exemption(data.len())
+		.await?;
+	let mut account = AccountSharedData::new(lamports, data.len(), &owner);
+	account.set_data_from_slice(&data);
+
+	let stored = runner.set_account(&pubkey, account.clone()).await?;
+
+	check!(stored.data == data);
+	check!(stored.owner == owner);
+	check!(runner.rpc().get_account(&pubkey).await?.lamports == lamports);
+
+	account.set_lamports(lamports - 1);
+	let error = runner.set_account(&pubkey, account).await.unwrap_err();
+
+	check!(error.to_string().contains("rent-exempt minimum"));
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn load_account_fixtures() -> anyhow::Result<()> {
+	let dir = tempfile::tempdir()?;
+	let fixture_dir = dir.path().join("fixtures");
+	let owner = Pubkey::new_unique();
+	let file_pubkey = Pubkey::new_unique();
+	let dir_pubkey = Pubkey::new_unique();
+	let mut file_account = AccountSharedData::new(1_000_000_000, 4, &owner);
+	file_account.set_data_from_slice(&[1, 2, 3, 4]);
+	let mut dir_account = AccountSharedData::new(2_000_000_000, 3, &owner);
+	dir_account.set_data_from_slice(&[5, 6, 7]);
+	let file_path = dir.path().join("account.json");
+
+	fs::create_dir(&fixture_dir)?;
+	write_fixture(&file_path, &file_pubkey, &file_account)?;
+	write_fixture(&fixture_dir.join("account.json"), &dir_pubkey, &dir_account)?;
+	fs::write(fixture_dir.join("readme.md"), "not a fixture")?;
+
+	check!(load_account_fixture(&file_path)? == (file_pubkey, file_account.clone()));
+
+	let runner = TestValidatorRunnerProps::builder()
+		.account_fixtures(vec![file_path])
+		.account_fixture_dirs(vec![fixture_dir])
+		.build()
+		.run()
+		.await;
+
+	for (pubkey, account) in [(file_pubkey, file_account), (dir_pubkey, dir_account)] {
+		let stored = runner.rpc().get_account(&pubkey).await?;
+
+		check!(stored.lamports == account.lamports());
+		check!(stored.owner == *account.owner());
+		check!(stored.data == account.data());
+	}
+
+	Ok(())
+}
+
+/// Write an account fixture in the format of
+/// `solana account <ADDRESS> --output json-compact`.
+fn write_fixture(path: &Path, pubkey: &Pubkey, account: &AccountSharedData) -> anyhow::Result<()> {
+	let fixture = json!({
+		"pubkey": pubkey.to_string(),
+		"account": UiAccount::encode(pubkey, account, UiAccountEncoding::Base64, None, None),
+	});
+
+	fs::write(path, serde_json::to_string(&fixture)?)?;
+
+	Ok(())
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fs;
+
+use assert2::check;
+use assert2::let_assert;
+use solana_sdk::pubkey::Pubkey;
+use test_utils_solana::TestProgramInfo;
+
+const ANCHOR_TOML: &str = r#"
+[toolchain]
+anchor_version = "0.31.1"
+
+[programs.localnet]
+token_swap = "SwapsVeCiPHMUAtzQWZw7RjsKjgCjhwU55QGu4U1Szw"
+price-oracle = { address = "PriceFeed7fpE6JwZV6r7ASBRcxzkQGrMTBmkhpLEn2" }
+
+[programs.devnet]
+token_swap = "11111111111111111111111111111111"
+
+[provider]
+cluster = "localnet"
+wallet = "~/.config/solana/id.json"
+"#;
+
+#[test]
+fn discover_anchor_workspace_programs() -> anyhow::Result<()> {
+	let workspace = tempfile::tempdir()?;
+	let deploy_dir = workspace.path().join("target").join("deploy");
+	fs::write(workspace.path().join("Anchor.toml"), ANCHOR_TOML)?;
+
+	let_assert!(Err(error) = TestProgramInfo::from_anchor_workspace(workspace.path()));
+	check!(error.to_string().contains("run `anchor build` first"));
+	check!(error.to_string().contains("token_swap.so"));
+	check!(error.to_string().contains("price_oracle.so"));
+
+	fs::create_dir_all(&deploy_dir)?;
+	fs::write(deploy_dir.join("token_swap.so"), b"token swap")?;
+	fs::write(deploy_dir.join("price_oracle.so"), b"price oracle")?;
+
+	let programs = TestProgramInfo::from_anchor_workspace(workspace.path())?;
+	let programs = programs
+		.into_iter()
+		.map(|program| (program.program_id, program.program_path))
+		.collect::<HashMap<_, _>>();
+	let token_swap = "SwapsVeCiPHMUAtzQWZw7RjsKjgCjhwU55QGu4U1Szw".parse::<Pubkey>()?;
+	let price_oracle = "PriceFeed7fpE6JwZV6r7ASBRcxzkQGrMTBmkhpLEn2".parse::<Pubkey>()?;
+
+	check!(programs.len() == 2);
+	check!(programs[&token_swap] == deploy_dir.join("token_swap.so"));
+	check!(programs[&price_oracle] == deploy_dir.join("price_oracle.so"));
+
+	let override_id = Pubkey::new_unique();
+	let programs = TestProgramInfo::from_anchor_workspace_with_overrides(
+		workspace.path(),
+		&HashMap::from([("token_swap".to_string(), override_id)]),
+	)?;
+	check!(
+		programs
+			.iter()
+			.any(|program| program.program_id == override_id)
+	);
+	check!(
+		programs
+			.iter()
+			.all(|program| program.program_id != token_swap)
+	);
+
+	let_assert!(
+		Err(error) = TestProgramInfo::from_anchor_workspace_with_overrides(
+			workspace.path(),
+			&HashMap::from([("unknown".to_string(), override_id)]),
+		)
+	);
+	check!(error.to_string().contains("`unknown`"));
+
+	Ok(())
+}
@@ -0,0 +1,38 @@
+use assert2::check;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::pubkey::Pubkey;
+use test_utils_solana::TestRunner;
+use test_utils_solana::TestValidatorRunnerProps;
+
+async fn exercise_runner(runner: &mut impl TestRunner) -> anyhow::Result<()> {
+	let pubkey = Pubkey::new_unique();
+	let owner = Pubkey::new_unique();
+
+	runner.airdrop(&pubkey, 1_000_000_000).await?;
+	check!(runner.rpc().get_balance(&pubkey).await? == 1_000_000_000);
+
+	let account = runner
+		.set_account(&owner, AccountSharedData::new(1_000_000_000, 0, &owner))
+		.await?;
+	check!(account.owner == owner);
+
+	let clock = runner.get_clock().await?;
+	let warped = runner.warp_to_slot(clock.slot + 5).await?;
+	check!(warped.slot >= clock.slot + 5);
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn banks_runner() -> anyhow::Result<()> {
+	let pubkey = Pubkey::new_unique();
+	let mut runner = TestValidatorRunnerProps::builder()
+		.pubkeys(vec![pubkey])
+		.build()
+		.run_banks()
+		.await;
+
+	check!(runner.rpc().get_balance(&pubkey).await? > 0);
+
+	exercise_runner(&mut runner).await
+}
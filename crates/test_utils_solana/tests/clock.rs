@@ -0,0 +1,61 @@
+#![cfg(feature = "test_validator")]
+
+use std::time::Duration;
+
+use assert2::check;
+use solana_program::epoch_schedule::EpochSchedule;
+use solana_sdk::commitment_config::CommitmentLevel;
+use test_utils_solana::TestValidatorRunnerProps;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn warp_clock() -> anyhow::Result<()> {
+	let mut runner = TestValidatorRunnerProps::builder()
+		.commitment(CommitmentLevel::Processed)
+		.build()
+		.run()
+		.await;
+	let clock = runner.get_clock().await?;
+	let unix_timestamp = clock.unix_timestamp + 86_400;
+	let updated = runner.set_clock_timestamp(unix_timestamp).await?;
+
+	check!(updated.unix_timestamp >= unix_timestamp);
+	check!(
+		runner
+			.set_clock_timestamp(clock.unix_timestamp)
+			.await
+			.is_err()
+	);
+
+	let warped = runner.warp_to_slot(updated.slot + 5).await?;
+
+	check!(warped.slot >= updated.slot + 5);
+	check!(warped.unix_timestamp >= unix_timestamp);
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn wait_for_slots_and_epochs() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::builder()
+		.epoch_schedule(EpochSchedule::custom(32, 32, false))
+		.build()
+		.run()
+		.await;
+	let slot = runner.current_slot().await?;
+
+	check!(runner.wait_for_slot(slot + 3).await? >= slot + 3);
+
+	let error = runner
+		.wait_for_slot_with_timeout(slot + 10_000, Duration::from_millis(200))
+		.await
+		.unwrap_err();
+
+	check!(error.to_string().contains("the validator is at slot"));
+
+	let epoch = runner.current_epoch().await?;
+
+	check!(runner.wait_for_next_epoch().await? == epoch + 1);
+	check!(runner.current_epoch().await? >= epoch + 1);
+
+	Ok(())
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "test_validator")]
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use assert2::check;
+use assert2::let_assert;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use test_utils_solana::TestClusterRunner;
+use test_utils_solana::TestClusterRunnerProps;
+use tokio::time::sleep;
+use wasm_client_solana::RpcErrorCode;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn nodes_share_the_cluster() -> anyhow::Result<()> {
+	let cluster = TestClusterRunnerProps::builder().build().run().await?;
+	let genesis_hash = cluster.rpc(0).get_genesis_hash().await?;
+
+	check!(cluster.node_count() == 2);
+	check!(cluster.rpc(1).get_genesis_hash().await? == genesis_hash);
+	check!(cluster.any_rpc().url() != cluster.any_rpc().url());
+
+	let nodes = cluster.rpc(1).get_cluster_nodes().await?;
+
+	check!(nodes.iter().any(|node| node.pubkey == cluster.identity(0)));
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn restarted_node_catches_up() -> anyhow::Result<()> {
+	let mut cluster = TestClusterRunnerProps::builder().build().run().await?;
+
+	cluster.kill_node(1)?;
+
+	check!(!cluster.is_running(1));
+	check!(cluster.kill_node(1).is_err());
+	check!(cluster.rpc(1).get_slot().await.is_err());
+	check!(cluster.any_rpc().url() == cluster.rpc_url(0));
+
+	let slot = cluster.rpc(0).get_slot().await?;
+	sleep(Duration::from_secs(5)).await;
+	cluster.restart_node(1).await?;
+
+	check!(cluster.is_running(1));
+	check!(cluster.wait_for_catch_up(1).await? > slot);
+	check!(cluster.slots_behind(1).await? < 10);
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn any_rpc_fails_over_to_running_nodes() -> anyhow::Result<()> {
+	let mut cluster = TestClusterRunnerProps::builder().build().run().await?;
+	let urls = any_rpc_urls(&cluster, 4).await?;
+
+	check!(urls.contains(&cluster.rpc_url(0)));
+	check!(urls.contains(&cluster.rpc_url(1)));
+
+	cluster.kill_node(1)?;
+
+	let urls = any_rpc_urls(&cluster, 4).await?;
+
+	check!(urls == HashSet::from([cluster.rpc_url(0)]));
+
+	cluster.restart_node(1).await?;
+	cluster.wait_for_catch_up(1).await?;
+
+	let urls = any_rpc_urls(&cluster, 4).await?;
+
+	check!(urls.contains(&cluster.rpc_url(1)));
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn lagging_node_rejects_newer_reads() -> anyhow::Result<()> {
+	let mut cluster = TestClusterRunnerProps::builder().build().run().await?;
+	let processed = CommitmentConfig::processed();
+	let pubkey = Pubkey::new_unique();
+
+	cluster.kill_node(1)?;
+	sleep(Duration::from_secs(10)).await;
+	cluster.restart_node(1).await?;
+
+	let leader_slot = cluster.rpc(0).get_slot_with_commitment(processed).await?;
+	let behind = cluster.slots_behind(1).await?;
+	let result = cluster
+		.rpc(1)
+		.with_min_context_slot(leader_slot)
+		.get_balance_with_commitment(&pubkey, processed)
+		.await;
+
+	check!(behind > 0);
+	let_assert!(Err(error) = result);
+	check!(error.rpc_error_code() == Some(RpcErrorCode::MinContextSlotNotReached));
+
+	cluster.wait_for_catch_up(1).await?;
+
+	check!(
+		cluster
+			.rpc(1)
+			.with_min_context_slot(leader_slot)
+			.get_balance_with_commitment(&pubkey, processed)
+			.await? == 0
+	);
+	check!(cluster.rpc(1).get_health().await.is_ok());
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn invalid_node_count() {
+	let result = TestClusterRunnerProps::builder()
+		.node_count(1)
+		.build()
+		.run()
+		.await;
+
+	check!(result.is_err());
+}
+
+/// The urls of the nodes which served `count` requests sent through
+/// [`TestClusterRunner::any_rpc`].
+async fn any_rpc_urls(
+	cluster: &TestClusterRunner,
+	count: usize,
+) -> anyhow::Result<HashSet<String>> {
+	let mut urls = HashSet::new();
+
+	for _ in 0..count {
+		let rpc = cluster.any_rpc();
+		rpc.get_slot().await?;
+		urls.insert(rpc.url());
+	}
+
+	Ok(urls)
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "test_validator")]
+
+use assert2::check;
+use solana_sdk::pubkey::Pubkey;
+use test_utils_solana::FaucetConfig;
+use test_utils_solana::TestValidatorRunnerProps;
+use wasm_client_solana::RpcErrorCode;
+
+const LAMPORTS: u64 = 1_000_000_000;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn airdrop_fails_without_faucet() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::builder()
+		.faucet(FaucetConfig::Disabled)
+		.build()
+		.run()
+		.await;
+
+	check!(runner.rpc().get_latest_blockhash().await.is_ok());
+	check!(
+		runner
+			.rpc()
+			.request_airdrop(&Pubkey::new_unique(), LAMPORTS)
+			.await
+			.is_err()
+	);
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn airdrop_respects_request_cap() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::builder()
+		.faucet(FaucetConfig::Enabled {
+			lamports: 10 * LAMPORTS,
+			per_request_cap: Some(LAMPORTS),
+			per_time_cap: None,
+		})
+		.build()
+		.run()
+		.await;
+	let pubkey = Pubkey::new_unique();
+
+	let error = runner
+		.rpc()
+		.request_airdrop(&pubkey, 2 * LAMPORTS)
+		.await
+		.unwrap_err();
+
+	// The node reports any airdrop rejected by the faucet as an internal error.
+	check!(error.rpc_error_code() == Some(RpcErrorCode::InternalError));
+	check!(runner.rpc().get_balance(&pubkey).await? == 0);
+	check!(
+		runner
+			.rpc()
+			.request_airdrop(&pubkey, LAMPORTS)
+			.await
+			.is_ok()
+	);
+
+	Ok(())
+}
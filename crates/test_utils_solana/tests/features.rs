@@ -0,0 +1,42 @@
+#![cfg(feature = "test_validator")]
+
+use assert2::check;
+use solana_program::epoch_schedule::EpochSchedule;
+use test_utils_solana::FeatureSet;
+use test_utils_solana::TestValidatorRunnerProps;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn deactivate_and_activate_feature() -> anyhow::Result<()> {
+	let feature_id = agave_feature_set::enable_extend_program_checked::id();
+	let mut runner = TestValidatorRunnerProps::builder()
+		.deactivate_features(vec![feature_id])
+		.epoch_schedule(EpochSchedule::custom(64, 64, false))
+		.build()
+		.run()
+		.await;
+	let commitment = runner.rpc().commitment_config();
+
+	check!(
+		runner
+			.rpc()
+			.get_account_with_commitment(&feature_id, commitment)
+			.await?
+			.is_none()
+	);
+	check!(
+		!FeatureSet::fetch(runner.rpc())
+			.await?
+			.is_active(&feature_id)
+	);
+
+	let epoch = runner.activate_feature(&feature_id).await?;
+	runner.warp_to_epoch(epoch).await?;
+
+	check!(
+		FeatureSet::fetch(runner.rpc())
+			.await?
+			.is_active(&feature_id)
+	);
+
+	Ok(())
+}
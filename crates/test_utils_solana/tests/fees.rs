@@ -0,0 +1,85 @@
+#![cfg(feature = "test_validator")]
+
+use assert2::check;
+use solana_sdk::fee::FeeStructure;
+use solana_sdk::fee_calculator::FeeRateGovernor;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_system_interface::instruction::transfer;
+use test_utils_solana::TestValidatorRunner;
+use test_utils_solana::TestValidatorRunnerProps;
+use test_utils_solana::prelude::*;
+use wasm_client_solana::ClientResult;
+use wasm_client_solana::rpc_config::SendOptions;
+
+const LAMPORTS: u64 = 1_000_000_000;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn default_fee_per_signature() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::default().run().await;
+
+	check!(transfer_fee(&runner).await? == FeeStructure::default().lamports_per_signature);
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn non_zero_fee_rate_governor_keeps_the_default_fee() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::builder()
+		.fee_rate_governor(FeeRateGovernor::new(10_000, 0))
+		.build()
+		.run()
+		.await;
+
+	check!(transfer_fee(&runner).await? == FeeStructure::default().lamports_per_signature);
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn transfer_succeeds_with_zero_fees() -> anyhow::Result<()> {
+	let sender = Keypair::new();
+	let runner = TestValidatorRunnerProps::builder()
+		.pubkeys(vec![sender.pubkey()])
+		.initial_lamports(LAMPORTS)
+		.zero_fees()
+		.build()
+		.run()
+		.await;
+
+	check!(transfer_fee(&runner).await? == 0);
+	check!(transfer_all(&runner, &sender).await.is_ok());
+
+	Ok(())
+}
+
+/// The fee reported by the cluster for a transfer with a single signature.
+async fn transfer_fee(runner: &TestValidatorRunner) -> anyhow::Result<u64> {
+	let payer = runner.mint_keypair().pubkey();
+	let blockhash = runner.rpc().get_latest_blockhash().await?;
+	let message = Message::new_with_blockhash(
+		&[transfer(&payer, &Pubkey::new_unique(), 1)],
+		Some(&payer),
+		&blockhash,
+	);
+
+	Ok(runner.rpc().get_fee_for_message(&message).await?)
+}
+
+/// Transfer every lamport of the `sender`, which also pays the fees.
+async fn transfer_all(runner: &TestValidatorRunner, sender: &Keypair) -> ClientResult<()> {
+	let instruction = transfer(&sender.pubkey(), &Pubkey::new_unique(), LAMPORTS);
+
+	runner
+		.rpc()
+		.send_and_confirm_transaction_with_options(
+			&[instruction],
+			&sender.pubkey(),
+			&[sender],
+			SendOptions::default(),
+		)
+		.await?;
+
+	Ok(())
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "test_validator")]
+
+use assert2::check;
+use tempfile::tempdir;
+use test_utils_solana::TestValidatorRunnerProps;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn keep_ledger_on_drop() -> anyhow::Result<()> {
+	let dir = tempdir()?;
+	let ledger_path = dir.path().join("ledger");
+	let runner = TestValidatorRunnerProps::builder()
+		.ledger_path(&ledger_path)
+		.keep_ledger_on_drop(true)
+		.build()
+		.run()
+		.await;
+
+	check!(runner.ledger_path() == ledger_path);
+
+	drop(runner);
+
+	check!(ledger_path.join("genesis.bin").exists());
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn remove_ledger_on_drop() {
+	let runner = TestValidatorRunnerProps::default().run().await;
+	let ledger_path = runner.ledger_path();
+
+	check!(ledger_path.exists());
+
+	drop(runner);
+
+	check!(!ledger_path.exists());
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "test_validator")]
+
+use std::net::Ipv4Addr;
+use std::net::TcpListener;
+use std::time::Duration;
+
+use assert2::check;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use test_utils_solana::TestValidatorRunner;
+use test_utils_solana::TestValidatorRunnerProps;
+use test_utils_solana::assert_transaction_ok;
+use test_utils_solana::prelude::*;
+use tokio::time::sleep;
+
+const LAMPORTS: u64 = 1_000_000_000;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn restart_preserves_ledger() -> anyhow::Result<()> {
+	let mut runner = TestValidatorRunnerProps::default().run().await;
+	let pubkey = Pubkey::new_unique();
+	let signature = runner.airdrop(&pubkey, LAMPORTS).await?;
+	assert_transaction_ok!(runner, signature);
+
+	runner.restart().await?;
+
+	check!(runner.rpc().get_balance(&pubkey).await? == LAMPORTS);
+
+	let signature = runner.airdrop(&pubkey, LAMPORTS).await?;
+	assert_transaction_ok!(runner, signature);
+
+	check!(runner.rpc().get_balance(&pubkey).await? == 2 * LAMPORTS);
+
+	check!(wait_for_logs(&runner, &signature).await);
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn log_subscription_resumes_after_restart() -> anyhow::Result<()> {
+	let mut runner = TestValidatorRunnerProps::default().run().await;
+	let pubkey = Pubkey::new_unique();
+	let before = runner.airdrop(&pubkey, LAMPORTS).await?;
+	assert_transaction_ok!(runner, before);
+	check!(wait_for_logs(&runner, &before).await);
+
+	runner.restart().await?;
+
+	let after = runner.airdrop(&pubkey, LAMPORTS).await?;
+	assert_transaction_ok!(runner, after);
+
+	check!(wait_for_logs(&runner, &after).await);
+	check!(runner.logs_for_signature(&before).is_some());
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn shutdown_releases_ports() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::default().run().await;
+	let ports = runner.ports();
+	let cloned = runner.clone();
+
+	check!(cloned.shutdown().await.is_err());
+
+	runner.shutdown().await?;
+
+	check!(TcpListener::bind((Ipv4Addr::UNSPECIFIED, ports.rpc)).is_ok());
+	check!(TcpListener::bind((Ipv4Addr::UNSPECIFIED, ports.faucet)).is_ok());
+
+	Ok(())
+}
+
+/// Whether the logs of the transaction with the `signature` are captured by
+/// the runner within five seconds.
+async fn wait_for_logs(runner: &TestValidatorRunner, signature: &Signature) -> bool {
+	for _ in 0..50 {
+		if runner.logs_for_signature(signature).is_some() {
+			return true;
+		}
+
+		sleep(Duration::from_millis(100)).await;
+	}
+
+	false
+}
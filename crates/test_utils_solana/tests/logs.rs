@@ -0,0 +1,73 @@
+#![cfg(feature = "test_validator")]
+
+use std::time::Duration;
+
+use assert2::check;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use test_utils_solana::TestValidatorRunnerProps;
+use test_utils_solana::assert_transaction_ok;
+use test_utils_solana::prelude::*;
+use tokio::time::sleep;
+use wasm_client_solana::rpc_config::RpcSendTransactionConfig;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn capture_transaction_logs() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::builder()
+		.log_capacity(2)
+		.build()
+		.run()
+		.await;
+	let mut signatures = vec![];
+
+	for _ in 0..3 {
+		let signature = runner.airdrop(&Pubkey::new_unique(), 1_000_000_000).await?;
+		assert_transaction_ok!(runner, signature);
+		signatures.push(signature);
+	}
+
+	for _ in 0..50 {
+		if runner.logs_for_signature(&signatures[2]).is_some() {
+			break;
+		}
+
+		sleep(Duration::from_millis(100)).await;
+	}
+
+	check!(runner.logs().len() == 2);
+	check!(runner.logs_for_signature(&signatures[0]).is_none());
+	check!(runner.logs_for_signature(&signatures[2]).is_some());
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn report_failed_transaction() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::default().run().await;
+	let payer = runner.mint_keypair();
+	let instruction = solana_system_interface::instruction::transfer(
+		&payer.pubkey(),
+		&Pubkey::new_unique(),
+		u64::MAX,
+	);
+	let blockhash = runner.rpc().get_latest_blockhash().await?;
+	let transaction = Transaction::new_signed_with_payer(
+		&[instruction],
+		Some(&payer.pubkey()),
+		&[payer],
+		blockhash,
+	);
+	let signature = runner
+		.rpc()
+		.send_transaction_with_config(
+			&transaction.into(),
+			RpcSendTransactionConfig::builder().skip_preflight().build(),
+		)
+		.await?;
+	let report = runner.transaction_failure(&signature).await;
+
+	check!(report.is_some());
+	check!(report.unwrap().contains(&signature.to_string()));
+
+	Ok(())
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use assert2::check;
+use futures::StreamExt;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use test_utils_solana::MockRpcResponse;
+use test_utils_solana::MockRpcServer;
+use tokio::time::timeout;
+use wasm_client_solana::ClientError;
+use wasm_client_solana::rpc_config::LogsSubscribeRequest;
+use wasm_client_solana::rpc_config::RpcTransactionLogsFilter;
+
+#[tokio::test]
+async fn static_value() -> anyhow::Result<()> {
+	let server = MockRpcServer::start().await?;
+	let pubkey = Pubkey::new_unique();
+	server.mock(
+		"getBalance",
+		MockRpcResponse::value(json!({ "context": { "slot": 1 }, "value": 100 })),
+	);
+
+	check!(server.rpc().get_balance(&pubkey).await? == 100);
+	check!(server.rpc().get_balance(&pubkey).await? == 100);
+
+	let requests = server.requests_for("getBalance");
+	check!(requests.len() == 2);
+	check!(requests[0].params[0] == json!(pubkey.to_string()));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn sequence_of_responses() -> anyhow::Result<()> {
+	let server = MockRpcServer::start().await?;
+	server.mock(
+		"getSlot",
+		MockRpcResponse::sequence([
+			MockRpcResponse::error(-32005, "Node is behind"),
+			MockRpcResponse::value(10),
+			MockRpcResponse::value(11),
+		]),
+	);
+
+	let error = server.rpc().get_slot().await.unwrap_err();
+	check!(let ClientError::Rpc(_) = error);
+	check!(server.rpc().get_slot().await? == 10);
+	check!(server.rpc().get_slot().await? == 11);
+	check!(server.rpc().get_slot().await? == 11);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn unknown_methods_are_errors() -> anyhow::Result<()> {
+	let server = MockRpcServer::start().await?;
+	let result = server.rpc().get_slot().await;
+
+	check!(result.is_err());
+	check!(server.requests().len() == 1);
+
+	server.clear_requests();
+	check!(server.requests().is_empty());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn delayed_response() -> anyhow::Result<()> {
+	let server = MockRpcServer::start().await?;
+	server.mock(
+		"getSlot",
+		MockRpcResponse::value(1).delayed(Duration::from_millis(500)),
+	);
+
+	let result = timeout(Duration::from_millis(50), server.rpc().get_slot()).await;
+	check!(result.is_err());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn pubsub_notifications() -> anyhow::Result<()> {
+	let server = MockRpcServer::start().await?;
+	let subscription = server
+		.rpc()
+		.logs_subscribe(
+			LogsSubscribeRequest::builder()
+				.filter(RpcTransactionLogsFilter::All)
+				.build(),
+		)
+		.await?;
+
+	check!(subscription.subscription_id() == 0);
+	check!(server.requests_for("logsSubscribe").len() == 1);
+
+	server.notify(
+		"logsNotification",
+		subscription.subscription_id(),
+		json!({
+			"context": { "slot": 5 },
+			"value": {
+				"signature": "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv",
+				"err": null,
+				"logs": ["Program log: hello"],
+			},
+		}),
+	);
+
+	let mut stream = subscription.clone().take(1);
+	let notification = timeout(Duration::from_secs(5), stream.next()).await?;
+	check!(let Some(_) = notification);
+
+	subscription.unsubscribe().await?;
+	check!(server.requests_for("logsUnsubscribe").len() == 1);
+
+	Ok(())
+}
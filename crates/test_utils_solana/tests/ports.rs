@@ -0,0 +1,74 @@
+#![cfg(feature = "test_validator")]
+
+use std::net::Ipv4Addr;
+use std::net::TcpListener;
+
+use assert2::check;
+use test_utils_solana::DEFAULT_PORT_RANGE;
+use test_utils_solana::PortAllocationError;
+use test_utils_solana::TestValidatorPorts;
+use test_utils_solana::TestValidatorRunnerProps;
+
+#[test]
+fn random_ports_are_reserved() -> anyhow::Result<()> {
+	let ports = TestValidatorPorts::try_random_ports_in(DEFAULT_PORT_RANGE)?;
+	let range = ports.rpc..=ports.gossip_range.1;
+
+	check!(ports.pubsub == ports.rpc + 1);
+	check!(ports.faucet == ports.rpc + 2);
+	check!(ports.gossip_range == (ports.rpc + 3, ports.rpc + 23));
+	check!(TcpListener::bind((Ipv4Addr::LOCALHOST, ports.rpc)).is_err());
+	check!(
+		TestValidatorPorts::try_random_ports_in(range.clone())
+			== Err(PortAllocationError::Exhausted {
+				start: *range.start(),
+				end: *range.end(),
+			})
+	);
+
+	Ok(())
+}
+
+#[test]
+fn port_range_too_small() {
+	check!(
+		TestValidatorPorts::try_random_ports_in(9000..=9010)
+			== Err(PortAllocationError::RangeTooSmall {
+				start: 9000,
+				end: 9010,
+			})
+	);
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn run_returns_the_port_allocation_error() {
+	let error = TestValidatorRunnerProps::builder()
+		.port_range(9000..=9010)
+		.build()
+		.try_run()
+		.await
+		.unwrap_err();
+
+	check!(
+		error.downcast_ref::<PortAllocationError>()
+			== Some(&PortAllocationError::RangeTooSmall {
+				start: 9000,
+				end: 9010,
+			})
+	);
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn run_within_port_range() {
+	let port_range = 20_000..=30_000;
+	let runner = TestValidatorRunnerProps::builder()
+		.port_range(port_range.clone())
+		.build()
+		.run()
+		.await;
+	let ports = runner.ports();
+
+	check!(port_range.contains(&ports.rpc));
+	check!(port_range.contains(&ports.gossip_range.1));
+	check!(runner.rpc().get_health().await.is_ok());
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "test_validator")]
+
+use assert2::check;
+use assert2::let_assert;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::bpf_loader_upgradeable::UpgradeableLoaderState;
+use solana_sdk::bpf_loader_upgradeable::get_program_data_address;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use test_utils_solana::TestValidatorRunner;
+use test_utils_solana::TestValidatorRunnerProps;
+use test_utils_solana::prelude::*;
+use wasm_client_solana::rpc_config::SendOptions;
+
+const MEMO_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn deploy_and_upgrade_program() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::default().run().await;
+	let payer = runner.mint_keypair();
+	let program = Keypair::new();
+	let program_id = program.pubkey();
+	let memo_elf = get_program_elf(&runner, &MEMO_PROGRAM_ID).await?;
+	let token_elf = get_program_elf(&runner, &spl_token_2022::ID).await?;
+
+	let signatures = runner.deploy_program(&program, &memo_elf, payer).await?;
+	check!(signatures.len() > 2);
+
+	runner
+		.rpc()
+		.send_and_confirm_transaction_with_options(
+			&[Instruction::new_with_bytes(program_id, b"deployed", vec![])],
+			&payer.pubkey(),
+			&[payer],
+			SendOptions::default(),
+		)
+		.await?;
+
+	// The programdata account only has room for twice the memo program so it
+	// is extended before the upgrade.
+	check!(token_elf.len() > memo_elf.len() * 2);
+
+	let signatures = runner
+		.upgrade_program(&program_id, &token_elf, payer)
+		.await?;
+	check!(signatures.len() > 2);
+	check!(
+		get_program_elf(&runner, &program_id)
+			.await?
+			.starts_with(&token_elf)
+	);
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn cloned_program_can_be_redeployed() -> anyhow::Result<()> {
+	let remote = TestValidatorRunnerProps::default().run().await;
+	let authority = remote.mint_keypair().insecure_clone();
+	let program = Keypair::new();
+	let program_id = program.pubkey();
+	let memo_elf = get_program_elf(&remote, &MEMO_PROGRAM_ID).await?;
+
+	remote
+		.deploy_program(&program, &memo_elf, &authority)
+		.await?;
+
+	let runner = TestValidatorRunnerProps::builder()
+		.clone_from(remote.rpc().url())
+		.clone_programs(vec![program_id])
+		.pubkeys(vec![authority.pubkey()])
+		.build()
+		.run()
+		.await;
+	let payer = runner.mint_keypair();
+	let programdata = runner
+		.rpc()
+		.get_account(&get_program_data_address(&program_id))
+		.await?;
+	let metadata_size = UpgradeableLoaderState::size_of_programdata_metadata();
+
+	let_assert!(
+		Ok(UpgradeableLoaderState::ProgramData {
+			slot,
+			upgrade_authority_address,
+		}) = bincode::deserialize(&programdata.data[..metadata_size])
+	);
+	check!(slot == 0);
+	check!(upgrade_authority_address == Some(authority.pubkey()));
+
+	runner
+		.upgrade_program(&program_id, &memo_elf, &authority)
+		.await?;
+	runner
+		.rpc()
+		.send_and_confirm_transaction_with_options(
+			&[Instruction::new_with_bytes(
+				program_id,
+				b"redeployed",
+				vec![],
+			)],
+			&payer.pubkey(),
+			&[payer],
+			SendOptions::default(),
+		)
+		.await?;
+
+	Ok(())
+}
+
+/// The elf of a program loaded by the validator.
+async fn get_program_elf(
+	runner: &TestValidatorRunner,
+	program_id: &Pubkey,
+) -> anyhow::Result<Vec<u8>> {
+	let account = runner.rpc().get_account(program_id).await?;
+
+	if account.owner != bpf_loader_upgradeable::ID {
+		return Ok(account.data);
+	}
+
+	let programdata_address = bpf_loader_upgradeable::get_program_data_address(program_id);
+	let programdata = runner.rpc().get_account(&programdata_address).await?;
+
+	Ok(programdata.data[UpgradeableLoaderState::size_of_programdata_metadata()..].to_vec())
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "test_validator")]
+
+use std::time::Duration;
+
+use assert2::check;
+use solana_sdk::pubkey::Pubkey;
+use test_utils_solana::TestValidatorRunnerProps;
+use test_utils_solana::prelude::*;
+
+const LAMPORTS: u64 = 1_000_000_000;
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn expect_account_change() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::default().run().await;
+	let pubkey = Pubkey::new_unique();
+	let expectation = runner.expect_account_change(&pubkey, TIMEOUT).await?;
+
+	runner.airdrop(&pubkey, LAMPORTS).await?;
+
+	let notification = expectation.wait().await?;
+
+	check!(notification.value.map(|account| account.lamports) == Some(LAMPORTS));
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn expect_logs_containing() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::default().run().await;
+	let expectation = runner
+		.expect_logs_containing("Program 11111111111111111111111111111111 success", TIMEOUT)
+		.await?;
+	let signature = runner.airdrop(&Pubkey::new_unique(), LAMPORTS).await?;
+	let notification = expectation.wait().await?;
+
+	check!(notification.value.signature == signature);
+
+	Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn expect_logs_containing_times_out() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::default().run().await;
+	let expectation = runner
+		.expect_logs_containing("this log is never written", Duration::from_secs(2))
+		.await?;
+
+	runner.airdrop(&Pubkey::new_unique(), LAMPORTS).await?;
+
+	let error = expectation.wait().await.unwrap_err().to_string();
+
+	check!(error.contains("wasn't received within 2s"));
+	check!(error.contains("Program 11111111111111111111111111111111 success"));
+
+	Ok(())
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "test_validator")]
+
+use assert2::check;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::pubkey::Pubkey;
+use test_utils_solana::StateSnapshot;
+use test_utils_solana::TestValidatorRunnerProps;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn snapshot_and_restore() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::builder()
+		.commitment(CommitmentLevel::Processed)
+		.build()
+		.run()
+		.await;
+	let owner = Pubkey::new_unique();
+	let lamports = runner
+		.rpc()
+		.get_minimum_balance_for_rent_exemption(8)
+		.await?;
+	let existing = Pubkey::new_unique();
+	let created = Pubkey::new_unique();
+
+	runner
+		.set_account(&existing, AccountSharedData::new(lamports, 8, &owner))
+		.await?;
+
+	let snapshot = runner.snapshot_all().await?;
+	let dir = tempfile::tempdir()?;
+	let path = dir.path().join("snapshot.json");
+	snapshot.save(&path)?;
+
+	check!(StateSnapshot::load(&path)? == snapshot);
+
+	runner
+		.set_account(&existing, AccountSharedData::new(lamports * 2, 8, &owner))
+		.await?;
+	runner
+		.set_account(&created, AccountSharedData::new(lamports, 8, &owner))
+		.await?;
+	let future = snapshot.unix_timestamp() + 365 * 24 * 60 * 60;
+	runner.set_clock_timestamp(future).await?;
+	runner.restore(&snapshot).await?;
+
+	check!(runner.rpc().get_account(&existing).await?.lamports == lamports);
+	check!(runner.rpc().get_account(&created).await.is_err());
+	check!(runner.get_clock().await?.unix_timestamp < future);
+
+	Ok(())
+}
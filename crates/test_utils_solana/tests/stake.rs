@@ -0,0 +1,136 @@
+#![cfg(feature = "test_validator")]
+
+use assert2::check;
+use assert2::let_assert;
+use solana_sdk::clock::Epoch;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::stake_history::StakeHistory;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_stake_interface::instruction;
+use test_utils_solana::TestValidatorRunnerProps;
+use test_utils_solana::prelude::*;
+use wasm_client_solana::ClientError;
+use wasm_client_solana::stake::NEW_WARMUP_COOLDOWN_RATE_EPOCH;
+use wasm_client_solana::stake::StakeError;
+
+const LAMPORTS: u64 = 1_000_000_000;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn delegate_split_deactivate_and_withdraw() -> anyhow::Result<()> {
+	let staker = Keypair::new();
+	let mut runner = TestValidatorRunnerProps::builder()
+		.pubkeys(vec![staker.pubkey()])
+		.initial_lamports(10 * LAMPORTS)
+		.build()
+		.run()
+		.await;
+	let rpc = runner.rpc();
+	let vote_account = runner.validator().vote_account_address();
+	let stake = Keypair::new();
+	let reserve = rpc.get_minimum_balance_for_rent_exemption(200).await?;
+
+	let mut transaction = rpc
+		.create_and_delegate_stake(
+			&staker.pubkey(),
+			&stake.pubkey(),
+			&vote_account,
+			3 * LAMPORTS,
+		)
+		.await?;
+	transaction.try_sign(&[&staker, &stake], None)?;
+	rpc.send_and_confirm_transaction(&transaction).await?;
+
+	let state = rpc.get_stake_account_state(&stake.pubkey()).await?;
+	let_assert!(Some(delegation) = state.delegation());
+	check!(delegation.voter_pubkey == vote_account);
+	check!(delegation.stake == 3 * LAMPORTS);
+
+	let split = Keypair::new();
+	let below_minimum = rpc
+		.split_stake(&stake.pubkey(), &staker.pubkey(), &split.pubkey(), reserve)
+		.await;
+	check!(let Err(ClientError::Stake(StakeError::BelowMinimum { .. })) = below_minimum);
+
+	let mut transaction = rpc
+		.split_stake(
+			&stake.pubkey(),
+			&staker.pubkey(),
+			&split.pubkey(),
+			LAMPORTS + reserve,
+		)
+		.await?;
+	transaction.try_sign(&[&staker, &split], None)?;
+	rpc.send_and_confirm_transaction(&transaction).await?;
+
+	let split_state = rpc.get_stake_account_state(&split.pubkey()).await?;
+	let_assert!(Some(split_delegation) = split_state.delegation());
+	check!(split_delegation.stake == LAMPORTS);
+
+	let mut transaction = rpc
+		.deactivate_stake(&split.pubkey(), &staker.pubkey())
+		.await?;
+	transaction.try_sign(&[&staker], None)?;
+	rpc.send_and_confirm_transaction(&transaction).await?;
+
+	let split_state = rpc.get_stake_account_state(&split.pubkey()).await?;
+	let_assert!(Some(split_delegation) = split_state.delegation());
+	check!(split_delegation.deactivation_epoch != Epoch::MAX);
+
+	// The stake stays effective for the rest of the deactivation epoch, and
+	// the client must reject the same withdrawal as the stake program.
+	let recipient = Pubkey::new_unique();
+	let balance = rpc.get_balance(&split.pubkey()).await?;
+	let epoch = rpc.get_epoch_info().await?.epoch;
+	let history: StakeHistory =
+		bincode::deserialize(&rpc.get_account(&sysvar::stake_history::id()).await?.data)?;
+	let effective = split_delegation.stake(epoch, &history, NEW_WARMUP_COOLDOWN_RATE_EPOCH);
+	check!(effective == LAMPORTS);
+
+	let cooling_down = rpc
+		.withdraw_stake(&split.pubkey(), &staker.pubkey(), &recipient, balance, None)
+		.await;
+	let_assert!(
+		Err(ClientError::Stake(StakeError::InsufficientFunds {
+			available,
+			..
+		})) = cooling_down
+	);
+	check!(available == balance - effective - reserve);
+
+	let mut transaction = VersionedTransaction::new_unsigned_v0(
+		&staker.pubkey(),
+		&[instruction::withdraw(
+			&split.pubkey(),
+			&staker.pubkey(),
+			&recipient,
+			available + 1,
+			None,
+		)],
+		&[],
+		rpc.get_latest_blockhash().await?,
+	)?;
+	transaction.try_sign(&[&staker], None)?;
+	check!(
+		rpc.send_and_confirm_transaction(&transaction)
+			.await
+			.is_err()
+	);
+
+	runner
+		.warp_to_epoch(split_delegation.deactivation_epoch + 1)
+		.await?;
+	let rpc = runner.rpc();
+
+	let mut transaction = rpc
+		.withdraw_stake(&split.pubkey(), &staker.pubkey(), &recipient, balance, None)
+		.await?;
+	transaction.try_sign(&[&staker], None)?;
+	rpc.send_and_confirm_transaction(&transaction).await?;
+
+	check!(rpc.get_balance(&recipient).await? == balance);
+	check!(rpc.get_balance(&split.pubkey()).await? == 0);
+
+	Ok(())
+}
@@ -0,0 +1,32 @@
+#![cfg(feature = "test_validator")]
+
+use assert2::check;
+use solana_sdk::pubkey::Pubkey;
+use test_utils_solana::TestValidatorRunnerProps;
+use test_utils_solana::TokenProgram;
+use test_utils_solana::prelude::*;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn mint_tokens_to_owners() -> anyhow::Result<()> {
+	let runner = TestValidatorRunnerProps::builder().build().run().await;
+	let authority = runner.mint_keypair().pubkey();
+	let owner = Pubkey::new_unique();
+
+	for token_program in [TokenProgram::Token, TokenProgram::Token2022] {
+		let mint = runner.create_mint(6, &authority, token_program).await?;
+		let token_account = runner.mint_to(&mint, &owner, 1_000_000).await?;
+		let account = runner.rpc().get_account(&token_account).await?;
+
+		check!(account.owner == token_program.id());
+		check!(runner.create_token_account(&owner, &mint).await? == token_account);
+
+		let balance = runner
+			.rpc()
+			.get_token_account_balance(&token_account)
+			.await?;
+
+		check!(balance.amount == "1000000");
+	}
+
+	Ok(())
+}
@@ -0,0 +1,194 @@
+//! Compare the peak memory of parsing a 50 MB `getProgramAccounts` response
+//! through an intermediate `Value`, deserializing it directly and streaming
+//! it in 64 KiB chunks.
+//!
+//! The response is generated with the shape of a captured mainnet response:
+//! token program sized accounts with `base64` data.
+//!
+//! Run with `cargo bench -p wasm_client_solana --features ssr`.
+
+// Counting the allocations requires a `GlobalAlloc` implementation.
+#![allow(unsafe_code)]
+
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use futures::StreamExt;
+use futures::executor::block_on;
+use futures::stream;
+use serde_json::Value;
+use serde_json::json;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use wasm_client_solana::ClientError;
+use wasm_client_solana::ClientResponse;
+use wasm_client_solana::ClientResult;
+use wasm_client_solana::GetProgramAccountsResponse;
+use wasm_client_solana::ResponseChunks;
+use wasm_client_solana::RpcProvider;
+use wasm_client_solana::SolanaRpcClient;
+
+const RESPONSE_SIZE: usize = 50 * 1024 * 1024;
+const DATA_LENGTH: usize = 2048;
+const CHUNK_SIZE: usize = 64 * 1024;
+
+struct PeakAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+		PEAK.fetch_max(current, Ordering::Relaxed);
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+		unsafe { System.dealloc(ptr, layout) }
+	}
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// Serves the fixture body from memory.
+struct FixtureProvider(Arc<Vec<u8>>);
+
+#[async_trait]
+impl RpcProvider for FixtureProvider {
+	async fn send(&self, _method: &'static str, _request: Value) -> ClientResult<Value> {
+		serde_json::from_slice(&self.0).map_err(|error| ClientError::Other(error.to_string()))
+	}
+
+	async fn send_raw(&self, _method: &'static str, _request: Value) -> ClientResult<Vec<u8>> {
+		Ok(self.0.to_vec())
+	}
+
+	async fn send_streaming(
+		&self,
+		_method: &'static str,
+		_request: Value,
+	) -> ClientResult<ResponseChunks> {
+		let body = self.0.clone();
+		let chunks = (0..body.len()).step_by(CHUNK_SIZE).map(move |start| {
+			let end = (start + CHUNK_SIZE).min(body.len());
+			Ok(body[start..end].to_vec())
+		});
+
+		Ok(stream::iter(chunks).boxed())
+	}
+
+	fn url(&self) -> String {
+		"http://localhost".into()
+	}
+}
+
+fn main() {
+	let owner = Pubkey::new_unique();
+	let body = Arc::new(fixture(&owner));
+	let rpc = SolanaRpcClient::new_with_provider(FixtureProvider(body.clone()));
+
+	println!("response: {:.1} MB", body.len() as f64 / 1024.0 / 1024.0);
+
+	let (count, before_peak, before_time) = measure(|| {
+		// The previous path parsed the body into a `Value` and cloned it before
+		// deserializing the response.
+		let value: Value = serde_json::from_slice(&body.to_vec()).unwrap();
+		let response: ClientResponse<GetProgramAccountsResponse> =
+			serde_json::from_value(value.clone()).unwrap();
+		let accounts: Vec<(Pubkey, Account)> = response
+			.result
+			.keyed_accounts()
+			.unwrap()
+			.iter()
+			.map(|keyed| (keyed.pubkey, keyed.account.decode().unwrap()))
+			.collect();
+
+		black_box(&accounts).len()
+	});
+	report("value", count, before_peak, before_time);
+
+	let (count, direct_peak, direct_time) = measure(|| {
+		let accounts = block_on(rpc.get_program_accounts(&owner)).unwrap();
+		black_box(&accounts).len()
+	});
+	report("direct", count, direct_peak, direct_time);
+
+	let (count, stream_peak, stream_time) = measure(|| {
+		block_on(async {
+			let accounts = rpc.stream_program_accounts(&owner).await.unwrap();
+			accounts
+				.fold(0, |count, account| {
+					black_box(account.unwrap());
+					async move { count + 1 }
+				})
+				.await
+		})
+	});
+	report("stream", count, stream_peak, stream_time);
+
+	println!(
+		"direct uses {:.0}% and stream uses {:.1}% of the peak memory of value",
+		direct_peak as f64 * 100.0 / before_peak as f64,
+		stream_peak as f64 * 100.0 / before_peak as f64
+	);
+}
+
+/// Run the parser and return the number of accounts with the peak memory
+/// allocated while it ran.
+fn measure(parse: impl FnOnce() -> usize) -> (usize, usize, Duration) {
+	let start = CURRENT.load(Ordering::Relaxed);
+	PEAK.store(start, Ordering::Relaxed);
+
+	let now = Instant::now();
+	let count = parse();
+	let elapsed = now.elapsed();
+
+	(count, PEAK.load(Ordering::Relaxed) - start, elapsed)
+}
+
+fn report(name: &str, count: usize, peak: usize, time: Duration) {
+	println!(
+		"{name:<7} {count:>6} accounts {:>8.1} MB peak {time:>10.2?}",
+		peak as f64 / 1024.0 / 1024.0
+	);
+}
+
+fn fixture(owner: &Pubkey) -> Vec<u8> {
+	let mut accounts = Vec::new();
+	let mut size = 0;
+
+	while size < RESPONSE_SIZE {
+		let data: Vec<u8> = (0..DATA_LENGTH)
+			.map(|byte| (byte + accounts.len()) as u8)
+			.collect();
+		let account = json!({
+			"pubkey": Pubkey::new_unique().to_string(),
+			"account": {
+				"lamports": 2_039_280,
+				"data": [BASE64_STANDARD.encode(data), "base64"],
+				"owner": owner.to_string(),
+				"executable": false,
+				"rentEpoch": u64::MAX,
+				"space": DATA_LENGTH,
+			},
+		});
+
+		size += account.to_string().len() + 1;
+		accounts.push(account);
+	}
+
+	serde_json::to_vec(&json!({ "jsonrpc": "2.0", "result": accounts, "id": 1 })).unwrap()
+}
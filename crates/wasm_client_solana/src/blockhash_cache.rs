@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::hash::Hash;
+use typed_builder::TypedBuilder;
+
+use crate::DEFAULT_BLOCKHASH_MAX_AGE;
+use crate::DEFAULT_BLOCKHASH_REFRESH_INTERVAL;
+
+/// The options of a [`BlockhashCache`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TypedBuilder)]
+pub struct BlockhashCacheConfig {
+	/// A cached blockhash older than this is fetched again before it is
+	/// used.
+	#[builder(default = DEFAULT_BLOCKHASH_MAX_AGE)]
+	pub max_age: Duration,
+	/// How often the blockhash is fetched by
+	/// [`SolanaRpcClient::spawn_blockhash_refresh`](crate::SolanaRpcClient::spawn_blockhash_refresh).
+	#[builder(default = DEFAULT_BLOCKHASH_REFRESH_INTERVAL)]
+	pub refresh_interval: Duration,
+}
+
+impl Default for BlockhashCacheConfig {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+/// A blockhash stored in a [`BlockhashCache`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedBlockhash {
+	pub blockhash: Hash,
+	pub last_valid_block_height: u64,
+	/// The url of the rpc endpoint the blockhash was fetched from.
+	pub endpoint: String,
+	pub fetched_at: DateTime<Utc>,
+}
+
+impl CachedBlockhash {
+	/// Whether the blockhash was fetched at most `max_age` ago.
+	pub fn is_fresh(&self, max_age: Duration) -> bool {
+		(Utc::now() - self.fetched_at)
+			.to_std()
+			.is_ok_and(|age| age <= max_age)
+	}
+}
+
+/// The latest blockhash for each commitment level, shared between clones of
+/// the [`SolanaRpcClient`](crate::SolanaRpcClient) it is enabled on.
+///
+/// A blockhash is only returned for the commitment level it was fetched
+/// with, so a `processed` blockhash is never used by a `finalized` flow. It
+/// is also bound to the endpoint it was fetched from and dropped once the
+/// client's provider points to another url, since a fallback node may lag
+/// behind and not know the blockhash yet.
+#[derive(Clone, Debug, Default)]
+pub struct BlockhashCache {
+	config: BlockhashCacheConfig,
+	entries: Arc<Mutex<HashMap<CommitmentLevel, CachedBlockhash>>>,
+}
+
+impl BlockhashCache {
+	pub fn new(config: BlockhashCacheConfig) -> Self {
+		Self {
+			config,
+			entries: Arc::default(),
+		}
+	}
+
+	pub fn config(&self) -> BlockhashCacheConfig {
+		self.config
+	}
+
+	/// The blockhash and last valid block height cached for `commitment`
+	/// when it was fetched from `endpoint` at most
+	/// [`BlockhashCacheConfig::max_age`] ago.
+	pub fn get(&self, commitment: CommitmentLevel, endpoint: &str) -> Option<(Hash, u64)> {
+		let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+
+		if entries.values().any(|cached| cached.endpoint != endpoint) {
+			entries.clear();
+		}
+
+		entries
+			.get(&commitment)
+			.filter(|cached| cached.is_fresh(self.config.max_age))
+			.map(|cached| (cached.blockhash, cached.last_valid_block_height))
+	}
+
+	/// Store the blockhash fetched from `endpoint` with `commitment`.
+	pub fn insert(
+		&self,
+		commitment: CommitmentLevel,
+		endpoint: &str,
+		(blockhash, last_valid_block_height): (Hash, u64),
+	) {
+		let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+
+		entries.retain(|_, cached| cached.endpoint == endpoint);
+		entries.insert(
+			commitment,
+			CachedBlockhash {
+				blockhash,
+				last_valid_block_height,
+				endpoint: endpoint.to_string(),
+				fetched_at: Utc::now(),
+			},
+		);
+	}
+
+	/// Remove every cached blockhash so the next one is fetched.
+	pub fn invalidate(&self) {
+		self.entries
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+
+	use super::*;
+
+	const ENDPOINT: &str = "http://localhost:8899";
+
+	#[test]
+	fn blockhash_cache() {
+		let cache = BlockhashCache::default();
+		let entry = (Hash::new_unique(), 100);
+
+		cache.insert(CommitmentLevel::Processed, ENDPOINT, entry);
+
+		check!(cache.get(CommitmentLevel::Processed, ENDPOINT) == Some(entry));
+		check!(cache.get(CommitmentLevel::Finalized, ENDPOINT).is_none());
+		check!(
+			cache
+				.get(CommitmentLevel::Processed, "http://fallback:8899")
+				.is_none()
+		);
+		check!(cache.get(CommitmentLevel::Processed, ENDPOINT).is_none());
+
+		cache.insert(CommitmentLevel::Processed, ENDPOINT, entry);
+		cache.invalidate();
+
+		check!(cache.get(CommitmentLevel::Processed, ENDPOINT).is_none());
+	}
+
+	#[test]
+	fn blockhash_cache_expires() {
+		let cache = BlockhashCache::new(
+			BlockhashCacheConfig::builder()
+				.max_age(Duration::ZERO)
+				.build(),
+		);
+
+		cache.insert(
+			CommitmentLevel::Confirmed,
+			ENDPOINT,
+			(Hash::new_unique(), 100),
+		);
+		std::thread::sleep(Duration::from_millis(5));
+
+		check!(cache.get(CommitmentLevel::Confirmed, ENDPOINT).is_none());
+	}
+}
@@ -51,6 +51,8 @@ pub type UnsubscriptionResult = ClientResponse<bool>;
 
 pub const MAX_RETRIES: usize = 25;
 pub const SLEEP_MS: u64 = 400; // solana block time
+/// The number of times a transaction is resent after a retryable error.
+pub const MAX_SEND_RETRIES: usize = 5;
 
 fn is_null(v: &Value) -> bool {
 	match v {
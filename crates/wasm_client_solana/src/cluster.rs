@@ -0,0 +1,109 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::DEVNET;
+use crate::LOCALNET;
+use crate::MAINNET;
+use crate::TESTNET;
+use crate::derive_websocket_url;
+
+/// A solana cluster and the urls of the rpc node used to reach it.
+///
+/// Use [`SolanaRpcClient::new_for_cluster`](crate::SolanaRpcClient::new_for_cluster)
+/// to create a client connected to both urls.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Cluster {
+	MainnetBeta,
+	Devnet,
+	Testnet,
+	/// A local test validator on the default ports.
+	Localnet,
+	/// A custom rpc node, like a hosted provider.
+	Custom {
+		http: String,
+		ws: String,
+	},
+}
+
+impl Cluster {
+	/// A custom cluster with the websocket url derived from `http` by
+	/// [`derive_websocket_url`].
+	pub fn custom(http: impl Into<String>) -> Self {
+		let http = http.into();
+		let ws = derive_websocket_url(&http);
+
+		Self::Custom { http, ws }
+	}
+
+	pub fn http_url(&self) -> &str {
+		match self {
+			Self::MainnetBeta => MAINNET,
+			Self::Devnet => DEVNET,
+			Self::Testnet => TESTNET,
+			Self::Localnet => LOCALNET,
+			Self::Custom { http, .. } => http,
+		}
+	}
+
+	pub fn ws_url(&self) -> String {
+		match self {
+			Self::Custom { ws, .. } => ws.clone(),
+			cluster => derive_websocket_url(cluster.http_url()),
+		}
+	}
+}
+
+impl fmt::Display for Cluster {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::MainnetBeta => f.write_str("mainnet-beta"),
+			Self::Devnet => f.write_str("devnet"),
+			Self::Testnet => f.write_str("testnet"),
+			Self::Localnet => f.write_str("localnet"),
+			Self::Custom { http, .. } => f.write_str(http),
+		}
+	}
+}
+
+/// Parse the name of a cluster, or an http url which is read as a
+/// [`Cluster::custom`].
+impl FromStr for Cluster {
+	type Err = std::convert::Infallible;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		let cluster = match value {
+			"mainnet-beta" | "mainnet" | "m" => Self::MainnetBeta,
+			"devnet" | "d" => Self::Devnet,
+			"testnet" | "t" => Self::Testnet,
+			"localnet" | "localhost" | "l" => Self::Localnet,
+			url => Self::custom(url),
+		};
+
+		Ok(cluster)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+
+	use super::*;
+
+	#[test]
+	fn cluster_urls() {
+		check!(Cluster::MainnetBeta.ws_url() == "wss://api.mainnet-beta.solana.com");
+		check!(Cluster::Localnet.http_url() == "http://127.0.0.1:8899");
+		check!(Cluster::Localnet.ws_url() == "ws://127.0.0.1:8900");
+
+		let helius = "https://mainnet.helius-rpc.com/?api-key=abc";
+		check!(
+			helius.parse::<Cluster>()
+				== Ok(Cluster::Custom {
+					http: helius.to_string(),
+					ws: "wss://mainnet.helius-rpc.com/?api-key=abc".to_string(),
+				})
+		);
+		check!("devnet".parse::<Cluster>() == Ok(Cluster::Devnet));
+		check!(Cluster::Devnet.to_string() == "devnet");
+	}
+}
@@ -0,0 +1,277 @@
+use std::future::Future;
+
+use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::RpcError;
+use crate::RpcErrorCode;
+use crate::SolanaRpcClient;
+use crate::rpc_config::RpcAccountInfoConfig;
+use crate::rpc_config::RpcKeyedAccount;
+use crate::rpc_filter::TokenAccountsFilter;
+use crate::rpc_response::Response;
+use crate::runtime::RetryPolicy;
+use crate::runtime::retry_with_backoff_if;
+use crate::solana_account_decoder::UiAccountEncoding;
+
+/// A sequence of reads which all observe the cluster at or after the same
+/// slot.
+///
+/// The context slot of the first read is recorded and sent as the
+/// `minContextSlot` of every following read, so a node which is behind
+/// can't answer with older state. Reads rejected because the node hasn't
+/// reached that slot yet are retried as described by the
+/// [`RetryPolicy`].
+///
+/// Only reads which accept a `minContextSlot` and return the context slot
+/// are available on the session.
+///
+/// ```no_run
+/// # use solana_sdk::pubkey::Pubkey;
+/// # use wasm_client_solana::ClientResult;
+/// # use wasm_client_solana::SolanaRpcClient;
+/// # async fn quote(rpc: &SolanaRpcClient, pool: Pubkey, vault: Pubkey) -> ClientResult<()> {
+/// let mut session = rpc.consistent_read_session();
+/// let pool = session.get_account(&pool).await?;
+/// let vault = session.get_balance(&vault).await?;
+/// let slot = session.slot();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConsistentReadSession {
+	rpc: SolanaRpcClient,
+	retry_policy: RetryPolicy,
+	slot: Option<Slot>,
+}
+
+impl ConsistentReadSession {
+	pub fn new(rpc: &SolanaRpcClient) -> Self {
+		Self {
+			rpc: rpc.clone(),
+			retry_policy: RetryPolicy::default(),
+			slot: None,
+		}
+	}
+
+	/// Set how reads rejected with [`RpcErrorCode::MinContextSlotNotReached`]
+	/// are retried.
+	#[must_use]
+	pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+		Self {
+			retry_policy,
+			..self
+		}
+	}
+
+	/// The context slot of the first read, which every later read is at least
+	/// as fresh as. `None` until the first read succeeds.
+	pub fn slot(&self) -> Option<Slot> {
+		self.slot
+	}
+
+	pub async fn get_account_with_config(
+		&mut self,
+		pubkey: &Pubkey,
+		config: RpcAccountInfoConfig,
+	) -> ClientResult<Option<Account>> {
+		self.read(|rpc| {
+			let config = config.clone();
+
+			async move { rpc.get_account_with_context(pubkey, config).await }
+		})
+		.await
+	}
+
+	pub async fn get_account(&mut self, pubkey: &Pubkey) -> ClientResult<Account> {
+		let config = RpcAccountInfoConfig {
+			commitment: Some(self.rpc.commitment_config()),
+			encoding: Some(UiAccountEncoding::Base64),
+			..Default::default()
+		};
+		let account = self
+			.get_account_with_config(pubkey, config)
+			.await?
+			.ok_or_else(|| RpcError::new(format!("Account {pubkey} not found.")))?;
+
+		Ok(account)
+	}
+
+	/// Get the accounts of the `pubkeys`. When they are split into multiple
+	/// requests the lowest context slot is recorded.
+	pub async fn get_multiple_accounts_with_config(
+		&mut self,
+		pubkeys: &[Pubkey],
+		config: RpcAccountInfoConfig,
+	) -> ClientResult<Vec<Option<Account>>> {
+		self.read(|rpc| {
+			let config = config.clone();
+
+			async move {
+				rpc.get_multiple_accounts_with_context(pubkeys, config)
+					.await
+			}
+		})
+		.await
+	}
+
+	pub async fn get_multiple_accounts(
+		&mut self,
+		pubkeys: &[Pubkey],
+	) -> ClientResult<Vec<Option<Account>>> {
+		let config = RpcAccountInfoConfig {
+			commitment: Some(self.rpc.commitment_config()),
+			encoding: Some(UiAccountEncoding::Base64),
+			..Default::default()
+		};
+
+		self.get_multiple_accounts_with_config(pubkeys, config)
+			.await
+	}
+
+	pub async fn get_balance(&mut self, pubkey: &Pubkey) -> ClientResult<u64> {
+		let commitment_config = self.rpc.commitment_config();
+
+		self.read(|rpc| {
+			async move {
+				rpc.get_balance_with_context(pubkey, commitment_config)
+					.await
+			}
+		})
+		.await
+	}
+
+	pub async fn get_token_accounts_by_owner(
+		&mut self,
+		owner: &Pubkey,
+		token_account_filter: TokenAccountsFilter,
+	) -> ClientResult<Vec<RpcKeyedAccount>> {
+		let commitment_config = self.rpc.commitment_config();
+
+		self.read(|rpc| {
+			async move {
+				rpc.get_token_accounts_by_owner_with_context(
+					owner,
+					token_account_filter,
+					commitment_config,
+				)
+				.await
+			}
+		})
+		.await
+	}
+
+	/// Run `read` with a client which sends the recorded slot as the
+	/// `minContextSlot` and record the context slot of the first response.
+	async fn read<T, F, Fut>(&mut self, read: F) -> ClientResult<T>
+	where
+		F: Fn(SolanaRpcClient) -> Fut,
+		Fut: Future<Output = ClientResult<Response<T>>>,
+	{
+		let rpc = match self.slot {
+			Some(slot) => self.rpc.with_min_context_slot(slot),
+			None => self.rpc.clone(),
+		};
+		let response =
+			retry_with_backoff_if(self.retry_policy, is_min_context_slot_not_reached, || {
+				read(rpc.clone())
+			})
+			.await?;
+
+		self.slot.get_or_insert(response.context.slot);
+
+		Ok(response.value)
+	}
+}
+
+impl SolanaRpcClient {
+	/// Start a [`ConsistentReadSession`] using the commitment of this client.
+	pub fn consistent_read_session(&self) -> ConsistentReadSession {
+		ConsistentReadSession::new(self)
+	}
+}
+
+fn is_min_context_slot_not_reached(error: &ClientError) -> bool {
+	error.rpc_error_code() == Some(RpcErrorCode::MinContextSlotNotReached)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+	use std::time::Duration;
+
+	use assert2::check;
+	use futures::executor::block_on;
+	use serde_json::json;
+
+	use super::*;
+	use crate::MockRpcProvider;
+
+	#[test]
+	fn reads_after_the_first_slot() -> anyhow::Result<()> {
+		let pubkey = Pubkey::new_unique();
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getBalance",
+				json!({ "context": { "slot": 120 }, "value": 5 }),
+			)
+			.with_response(
+				"getMultipleAccounts",
+				json!({
+					"jsonrpc": "2.0",
+					"id": 0,
+					"error": {
+						"code": -32016,
+						"message": "Minimum context slot has not been reached",
+						"data": { "contextSlot": 118 },
+					},
+				}),
+			)
+			.with_result(
+				"getMultipleAccounts",
+				json!({ "context": { "slot": 125 }, "value": [null] }),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let mut session = rpc.consistent_read_session().with_retry_policy(
+			RetryPolicy::builder()
+				.initial_delay(Duration::from_millis(1))
+				.build(),
+		);
+		check!(session.slot() == None);
+
+		check!(block_on(session.get_balance(&pubkey))? == 5);
+		check!(session.slot() == Some(120));
+
+		check!(block_on(session.get_multiple_accounts(&[pubkey]))? == vec![None]);
+		check!(session.slot() == Some(120));
+
+		let requests = provider.requests();
+		check!(requests.len() == 3);
+		check!(requests[0].1[1].get("minContextSlot") == None);
+		check!(requests[1].1[1]["minContextSlot"] == 120);
+		check!(requests[2].1[1]["minContextSlot"] == 120);
+		check!(rpc.min_context_slot() == None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn other_errors_are_not_retried() {
+		let provider = MockRpcProvider::new().with_response(
+			"getBalance",
+			json!({
+				"jsonrpc": "2.0",
+				"id": 0,
+				"error": { "code": -32005, "message": "Node is unhealthy" },
+			}),
+		);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let mut session = rpc.consistent_read_session();
+
+		check!(block_on(session.get_balance(&Pubkey::new_unique())).is_err());
+		check!(session.slot() == None);
+		check!(provider.requests().len() == 1);
+	}
+}
@@ -1,3 +1,50 @@
+use std::time::Duration;
+
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+pub const DEVNET: &str = "https://api.devnet.solana.com";
+pub const TESTNET: &str = "https://api.testnet.solana.com";
+pub const MAINNET: &str = "https://api.mainnet-beta.solana.com";
+pub const LOCALNET: &str = "http://127.0.0.1:8899";
+pub const DEBUG: &str = "http://34.90.18.145:8899";
 pub const COMPUTE_UNIT_MAX_LIMIT: usize = 1_400_000;
 pub const COMPUTE_UNIT_DEFAULT_LIMIT: usize = 200_000;
+/// The percentage added to the simulated compute units by
+/// [`SolanaRpcClient::prepare_transaction`](crate::SolanaRpcClient::prepare_transaction).
+pub const DEFAULT_COMPUTE_UNIT_MARGIN_PERCENT: u64 = 10;
 pub const MAX_LOOKUP_ADDRESSES_PER_TRANSACTION: usize = 30;
+/// The program which owns the associated token accounts derived by
+/// [`derive_associated_token_address`](crate::derive_associated_token_address).
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+	pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+/// The maximum size in bytes of a serialized transaction. This is the IPv6
+/// minimum MTU without the IP and UDP headers.
+pub use solana_sdk::packet::PACKET_DATA_SIZE;
+/// The maximum number of accounts requested by a single `getMultipleAccounts`
+/// call. Larger requests are split into chunks of this size.
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+/// The maximum number of signatures requested by a single
+/// `getSignatureStatuses` call.
+pub const MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS: usize = 256;
+/// The maximum number of signatures returned by a single
+/// `getSignaturesForAddress` call.
+pub const MAX_SIGNATURES_FOR_ADDRESS_LIMIT: usize = 1000;
+/// The default number of requests the [`SolanaRpcClient`] runs at once when a
+/// method is split into multiple requests.
+///
+/// [`SolanaRpcClient`]: crate::SolanaRpcClient
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+/// How long a blockhash cached by a
+/// [`BlockhashCache`](crate::BlockhashCache) is used before it is fetched
+/// again. A blockhash expires after 150 blocks, roughly a minute.
+pub const DEFAULT_BLOCKHASH_MAX_AGE: Duration = Duration::from_secs(30);
+/// How often the blockhash is fetched by
+/// [`SolanaRpcClient::spawn_blockhash_refresh`](crate::SolanaRpcClient::spawn_blockhash_refresh).
+pub const DEFAULT_BLOCKHASH_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+/// The number of slots returned by `getRecentPrioritizationFees`, which is
+/// the number sampled by a [`PriorityFeeOracle`](crate::PriorityFeeOracle).
+pub const DEFAULT_PRIORITY_FEE_WINDOW: usize = 150;
+/// How often the fees are fetched by
+/// [`PriorityFeeOracle::spawn_refresh`](crate::PriorityFeeOracle::spawn_refresh).
+pub const DEFAULT_PRIORITY_FEE_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
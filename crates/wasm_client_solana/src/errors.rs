@@ -2,19 +2,174 @@ use std::fmt;
 
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use solana_sdk::clock::Slot;
 use solana_sdk::message::CompileError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 use solana_sdk::signer::SignerError;
+use solana_sdk::transaction::TransactionError;
 use wallet_standard::IntoWalletError;
 use wallet_standard::WalletError;
 
+use crate::lookup_table::LookupTableError;
 use crate::nonce_utils::NonceError;
+use crate::rpc_response::RpcSimulateTransactionResult;
+use crate::solana_account_decoder_client_types::UiAccountDeserializeError;
+use crate::solana_transaction_status::TransactionConfirmationStatus;
+use crate::stake::StakeError;
+use crate::transaction_errors::TransactionErrorKind;
 
 pub const DEFAULT_ERROR_CODE: u16 = 500u16;
 
+/// The json rpc error codes returned by solana nodes.
+///
+/// Codes which aren't known are kept as [`RpcErrorCode::Unknown`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RpcErrorCode {
+	/// `-32001`: the block was cleaned up and is no longer available.
+	BlockCleanedUp,
+	/// `-32002`: the preflight simulation of `sendTransaction` failed.
+	SendTransactionPreflightFailure,
+	/// `-32003`: the signatures of the transaction couldn't be verified.
+	TransactionSignatureVerificationFailure,
+	/// `-32004`: the block isn't available yet.
+	BlockNotAvailable,
+	/// `-32005`: the node is behind the cluster.
+	NodeUnhealthy,
+	/// `-32006`: the precompile instructions of the transaction failed
+	/// verification.
+	TransactionPrecompileVerificationFailure,
+	/// `-32007`: the slot was skipped or is missing due to a ledger jump.
+	SlotSkipped,
+	/// `-32008`: the node has no snapshot.
+	NoSnapshot,
+	/// `-32009`: the slot was skipped or is missing in long term storage.
+	LongTermStorageSlotSkipped,
+	/// `-32010`: the key is excluded from the account secondary indexes.
+	KeyExcludedFromSecondaryIndex,
+	/// `-32011`: the transaction history isn't available from the node.
+	TransactionHistoryNotAvailable,
+	/// `-32012`: the scan of the accounts failed.
+	ScanError,
+	/// `-32013`: the number of signatures doesn't match the message.
+	TransactionSignatureLenMismatch,
+	/// `-32014`: the status of the block isn't available yet.
+	BlockStatusNotAvailableYet,
+	/// `-32015`: the transaction version isn't supported by the request.
+	UnsupportedTransactionVersion,
+	/// `-32016`: the node hasn't reached the requested `minContextSlot`.
+	MinContextSlotNotReached,
+	/// `-32017`: the epoch rewards are still being distributed.
+	EpochRewardsPeriodActive,
+	/// `-32018`: the slot isn't the first slot of an epoch.
+	SlotNotEpochBoundary,
+	/// `-32019`: long term storage couldn't be reached.
+	LongTermStorageUnreachable,
+	/// `-32700`: the request wasn't valid json.
+	ParseError,
+	/// `-32600`: the request wasn't a valid json rpc request.
+	InvalidRequest,
+	/// `-32601`: the method doesn't exist.
+	MethodNotFound,
+	/// `-32602`: the params of the request are invalid.
+	InvalidParams,
+	/// `-32603`: the node failed with an internal error.
+	InternalError,
+	Unknown(i64),
+}
+
+impl RpcErrorCode {
+	pub fn from_code(code: i64) -> Self {
+		match code {
+			-32001 => Self::BlockCleanedUp,
+			-32002 => Self::SendTransactionPreflightFailure,
+			-32003 => Self::TransactionSignatureVerificationFailure,
+			-32004 => Self::BlockNotAvailable,
+			-32005 => Self::NodeUnhealthy,
+			-32006 => Self::TransactionPrecompileVerificationFailure,
+			-32007 => Self::SlotSkipped,
+			-32008 => Self::NoSnapshot,
+			-32009 => Self::LongTermStorageSlotSkipped,
+			-32010 => Self::KeyExcludedFromSecondaryIndex,
+			-32011 => Self::TransactionHistoryNotAvailable,
+			-32012 => Self::ScanError,
+			-32013 => Self::TransactionSignatureLenMismatch,
+			-32014 => Self::BlockStatusNotAvailableYet,
+			-32015 => Self::UnsupportedTransactionVersion,
+			-32016 => Self::MinContextSlotNotReached,
+			-32017 => Self::EpochRewardsPeriodActive,
+			-32018 => Self::SlotNotEpochBoundary,
+			-32019 => Self::LongTermStorageUnreachable,
+			-32700 => Self::ParseError,
+			-32600 => Self::InvalidRequest,
+			-32601 => Self::MethodNotFound,
+			-32602 => Self::InvalidParams,
+			-32603 => Self::InternalError,
+			code => Self::Unknown(code),
+		}
+	}
+
+	pub fn code(self) -> i64 {
+		match self {
+			Self::BlockCleanedUp => -32001,
+			Self::SendTransactionPreflightFailure => -32002,
+			Self::TransactionSignatureVerificationFailure => -32003,
+			Self::BlockNotAvailable => -32004,
+			Self::NodeUnhealthy => -32005,
+			Self::TransactionPrecompileVerificationFailure => -32006,
+			Self::SlotSkipped => -32007,
+			Self::NoSnapshot => -32008,
+			Self::LongTermStorageSlotSkipped => -32009,
+			Self::KeyExcludedFromSecondaryIndex => -32010,
+			Self::TransactionHistoryNotAvailable => -32011,
+			Self::ScanError => -32012,
+			Self::TransactionSignatureLenMismatch => -32013,
+			Self::BlockStatusNotAvailableYet => -32014,
+			Self::UnsupportedTransactionVersion => -32015,
+			Self::MinContextSlotNotReached => -32016,
+			Self::EpochRewardsPeriodActive => -32017,
+			Self::SlotNotEpochBoundary => -32018,
+			Self::LongTermStorageUnreachable => -32019,
+			Self::ParseError => -32700,
+			Self::InvalidRequest => -32600,
+			Self::MethodNotFound => -32601,
+			Self::InvalidParams => -32602,
+			Self::InternalError => -32603,
+			Self::Unknown(code) => code,
+		}
+	}
+
+	/// Whether the same request may succeed when it is sent again, usually
+	/// because the node was behind the cluster or hadn't processed the
+	/// requested slot yet.
+	pub fn is_retryable(self) -> bool {
+		matches!(
+			self,
+			Self::BlockNotAvailable
+				| Self::NodeUnhealthy
+				| Self::BlockStatusNotAvailableYet
+				| Self::MinContextSlotNotReached
+				| Self::EpochRewardsPeriodActive
+				| Self::LongTermStorageUnreachable
+				| Self::InternalError
+		)
+	}
+}
+
+impl From<i64> for RpcErrorCode {
+	fn from(code: i64) -> Self {
+		Self::from_code(code)
+	}
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct RpcErrorDetails {
 	pub(crate) code: i32,
 	pub(crate) message: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub(crate) data: Option<Value>,
 }
 
 impl Default for RpcErrorDetails {
@@ -22,7 +177,11 @@ impl Default for RpcErrorDetails {
 		let message = "Internal Server Error".into();
 		let code = DEFAULT_ERROR_CODE.into();
 
-		Self { code, message }
+		Self {
+			code,
+			message,
+			data: None,
+		}
 	}
 }
 
@@ -49,13 +208,63 @@ impl RpcError {
 	pub fn new(message: impl Into<String>) -> Self {
 		let message = message.into();
 		let code = 303;
-		let error = RpcErrorDetails { code, message };
+		let error = RpcErrorDetails {
+			code,
+			message,
+			data: None,
+		};
+
+		RpcError {
+			error,
+			..Default::default()
+		}
+	}
+
+	/// The error returned by a node whose `context_slot` is older than the
+	/// `minContextSlot` of a request.
+	pub(crate) fn min_context_slot_not_reached(context_slot: Slot) -> Self {
+		let error = RpcErrorDetails {
+			code: -32016,
+			message: "Minimum context slot has not been reached".into(),
+			data: Some(json!({ "contextSlot": context_slot })),
+		};
 
 		RpcError {
 			error,
 			..Default::default()
 		}
 	}
+
+	/// The raw json rpc error code.
+	pub fn raw_code(&self) -> i64 {
+		self.error.code.into()
+	}
+
+	pub fn code(&self) -> RpcErrorCode {
+		RpcErrorCode::from_code(self.raw_code())
+	}
+
+	pub fn message(&self) -> &str {
+		&self.error.message
+	}
+
+	/// The [`TransactionError`] which caused the rpc to reject a transaction,
+	/// for example when the preflight simulation of `sendTransaction` fails.
+	pub fn transaction_error(&self) -> Option<TransactionError> {
+		let error = self.error.data.as_ref()?.get("err")?;
+		serde_json::from_value(error.clone()).ok()
+	}
+
+	/// The simulation result included when the preflight check of
+	/// `sendTransaction` fails. This is `None` for any other error or when the
+	/// `data` can't be deserialized.
+	pub fn preflight_failure(&self) -> Option<RpcSimulateTransactionResult> {
+		if self.code() != RpcErrorCode::SendTransactionPreflightFailure {
+			return None;
+		}
+
+		serde_json::from_value(self.error.data.clone()?).ok()
+	}
 }
 
 impl fmt::Display for RpcError {
@@ -70,7 +279,12 @@ pub type ClientResult<T> = Result<T, ClientError>;
 pub enum ClientError {
 	/// An rpc client error.
 	#[error("{0}")]
-	Rpc(#[from] RpcError),
+	Rpc(RpcError),
+	/// The preflight simulation of `sendTransaction` failed. The simulation
+	/// result includes the error, logs, consumed compute units and return
+	/// data.
+	#[error("Transaction preflight failed: {}", preflight_message(.0))]
+	PreflightFailure(Box<RpcSimulateTransactionResult>),
 	#[error("Websocket Error: {0}")]
 	WebSocket(#[from] ClientWebSocketError),
 	/// The wallet error.
@@ -79,11 +293,180 @@ pub enum ClientError {
 	/// The nonce error.
 	#[error("{0}")]
 	Nonce(#[from] NonceError),
+	/// The stake error.
+	#[error("{0}")]
+	Stake(#[from] StakeError),
+	/// The address lookup table error.
+	#[error("{0}")]
+	LookupTable(#[from] LookupTableError),
+	/// The account data couldn't be deserialized into the requested type.
+	#[error("{0}")]
+	AccountDeserialize(#[from] UiAccountDeserializeError),
+	/// The simulation used to prepare a transaction failed.
+	#[error("{0}")]
+	Simulation(#[from] SimulationError),
+	/// The serialized transaction is too large to be sent.
+	#[error("{0}")]
+	TransactionTooLarge(#[from] TransactionTooLarge),
+	/// The transaction was processed and failed.
+	#[error("Transaction failed: {0}")]
+	TransactionFailed(TransactionError),
+	/// The blockhash of the transaction expired before it was processed.
+	#[error("Transaction expired at block height {last_valid_block_height}")]
+	TransactionExpired { last_valid_block_height: u64 },
+	/// The node doesn't know a transaction with the signature.
+	#[error("Transaction {signature} not found")]
+	TransactionNotFound { signature: Signature },
+	/// The transaction was processed but hasn't reached the commitment of the
+	/// request yet.
+	#[error("Transaction {signature} in slot {slot} is not confirmed yet")]
+	TransactionNotConfirmed {
+		signature: Signature,
+		slot: u64,
+		confirmation_status: Option<TransactionConfirmationStatus>,
+	},
 	/// The string of any unsupported errors.
 	#[error("Other: {0}")]
 	Other(String),
 }
 
+impl ClientError {
+	/// The [`TransactionError`] returned by the rpc when a transaction was
+	/// rejected.
+	pub fn transaction_error(&self) -> Option<TransactionError> {
+		match self {
+			Self::Rpc(error) => error.transaction_error(),
+			Self::PreflightFailure(result) => result.err.clone(),
+			Self::Simulation(error) => error.err.clone(),
+			Self::TransactionFailed(error) => Some(error.clone()),
+			_ => None,
+		}
+	}
+
+	/// The json rpc error code returned by the node.
+	pub fn rpc_error_code(&self) -> Option<RpcErrorCode> {
+		match self {
+			Self::Rpc(error) => Some(error.code()),
+			Self::PreflightFailure(_) => Some(RpcErrorCode::SendTransactionPreflightFailure),
+			_ => None,
+		}
+	}
+
+	/// Whether the rpc rejected a request with an error which may not happen
+	/// when it is sent again. This covers a retryable [`RpcErrorCode`] and a
+	/// transaction rejected with a retryable [`TransactionError`].
+	pub fn is_retryable(&self) -> bool {
+		self.rpc_error_code()
+			.is_some_and(RpcErrorCode::is_retryable)
+			|| self
+				.transaction_error()
+				.is_some_and(|error| TransactionErrorKind::from(&error).is_retryable())
+	}
+}
+
+impl From<RpcError> for ClientError {
+	fn from(error: RpcError) -> Self {
+		match error.preflight_failure() {
+			Some(result) => Self::PreflightFailure(Box::new(result)),
+			None => Self::Rpc(error),
+		}
+	}
+}
+
+fn preflight_message(result: &RpcSimulateTransactionResult) -> String {
+	let mut message = result
+		.err
+		.as_ref()
+		.map_or_else(|| "unknown error".to_string(), ToString::to_string);
+
+	for log in result.logs.iter().flatten() {
+		message.push_str("\n  ");
+		message.push_str(log);
+	}
+
+	message
+}
+
+/// The error returned when a transaction fails during simulation. The logs of
+/// the simulation are included to show why the transaction failed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub struct SimulationError {
+	/// The error of the simulated transaction. This is `None` when the
+	/// simulation succeeded without reporting the consumed compute units.
+	pub err: Option<TransactionError>,
+	pub logs: Vec<String>,
+}
+
+impl fmt::Display for SimulationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.err {
+			Some(error) => write!(f, "Transaction simulation failed: {error}")?,
+			None => {
+				write!(
+					f,
+					"Transaction simulation didn't report the consumed compute units"
+				)?;
+			}
+		}
+
+		for log in &self.logs {
+			write!(f, "\n  {log}")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// The error returned when a serialized transaction is larger than the
+/// packet limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error(
+	"Transaction of {size} bytes exceeds the limit of {limit} bytes ({signatures_size} bytes of \
+	 signatures and {message_size} bytes of message)"
+)]
+pub struct TransactionTooLarge {
+	pub size: usize,
+	pub limit: usize,
+	/// The bytes used by the signatures, including the length prefix.
+	pub signatures_size: usize,
+	pub message_size: usize,
+}
+
+/// The error returned when decompiling a message into instructions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum DecompileError {
+	/// A v0 message which uses address lookup tables was decompiled without
+	/// its loaded addresses.
+	#[error("the loaded addresses are required to decompile a message with address lookup tables")]
+	MissingLoadedAddresses,
+	/// The number of writable loaded addresses doesn't match the address table
+	/// lookups of the message.
+	#[error("expected {expected} writable loaded addresses but found {actual}")]
+	WritableAddressesMismatch { expected: usize, actual: usize },
+	/// The number of readonly loaded addresses doesn't match the address table
+	/// lookups of the message.
+	#[error("expected {expected} readonly loaded addresses but found {actual}")]
+	ReadonlyAddressesMismatch { expected: usize, actual: usize },
+	/// An instruction references an account index which is out of bounds.
+	#[error("account index {0} is out of bounds")]
+	InvalidAccountIndex(usize),
+}
+
+/// The error returned when verifying the signatures of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum SignatureVerificationError {
+	/// The number of signatures doesn't match the `num_required_signatures`
+	/// of the message header.
+	#[error("expected {expected} signatures but the transaction has {actual}")]
+	SignatureCountMismatch { expected: usize, actual: usize },
+	/// The message has fewer static account keys than required signers.
+	#[error("the message has fewer account keys than required signers")]
+	InvalidMessage,
+	/// The signature at `index` wasn't created by `pubkey` for this message.
+	#[error("invalid signature at index {index} for {pubkey}")]
+	InvalidSignature { index: usize, pubkey: Pubkey },
+}
+
 impl IntoWalletError for ClientError {}
 impl IntoWalletError for ClientWebSocketError {}
 impl IntoWalletError for RpcError {}
@@ -140,3 +523,146 @@ impl From<&gloo_net::websocket::WebSocketError> for ClientWebSocketError {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use serde_json::json;
+
+	use super::*;
+
+	#[test]
+	fn rpc_error_codes() -> anyhow::Result<()> {
+		let payloads = [
+			(
+				json!({ "code": -32004, "message": "Block not available for slot 286412371" }),
+				RpcErrorCode::BlockNotAvailable,
+				true,
+			),
+			(
+				json!({
+					"code": -32005,
+					"message": "Node is behind by 42 slots",
+					"data": { "numSlotsBehind": 42 },
+				}),
+				RpcErrorCode::NodeUnhealthy,
+				true,
+			),
+			(
+				json!({
+					"code": -32007,
+					"message": "Slot 286412371 was skipped, or missing due to ledger jump to recent snapshot",
+				}),
+				RpcErrorCode::SlotSkipped,
+				false,
+			),
+			(
+				json!({
+					"code": -32009,
+					"message": "Slot 1000 was skipped, or missing in long-term storage",
+				}),
+				RpcErrorCode::LongTermStorageSlotSkipped,
+				false,
+			),
+			(
+				json!({
+					"code": -32010,
+					"message": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA excluded from account secondary indexes; this RPC method unavailable for key",
+				}),
+				RpcErrorCode::KeyExcludedFromSecondaryIndex,
+				false,
+			),
+			(
+				json!({
+					"code": -32015,
+					"message": "Transaction version (0) is not supported by the requesting client. Please try the request again with the following configuration parameter: \"maxSupportedTransactionVersion\": 0",
+				}),
+				RpcErrorCode::UnsupportedTransactionVersion,
+				false,
+			),
+			(
+				json!({
+					"code": -32016,
+					"message": "Minimum context slot has not been reached",
+					"data": { "contextSlot": 286_412_300 },
+				}),
+				RpcErrorCode::MinContextSlotNotReached,
+				true,
+			),
+			(
+				json!({ "code": -32602, "message": "Invalid param: unrecognized filter" }),
+				RpcErrorCode::InvalidParams,
+				false,
+			),
+		];
+
+		for (payload, code, retryable) in payloads {
+			let message = payload["message"].clone();
+			let error: RpcError = serde_json::from_value(json!({
+				"jsonrpc": "2.0",
+				"id": 1,
+				"error": payload,
+			}))?;
+
+			check!(error.code() == code);
+			check!(error.raw_code() == code.code());
+			check!(error.message() == message);
+
+			let error = ClientError::from(error);
+			check!(error.rpc_error_code() == Some(code));
+			check!(error.is_retryable() == retryable);
+		}
+
+		check!(RpcErrorCode::from_code(-1) == RpcErrorCode::Unknown(-1));
+		check!(RpcErrorCode::Unknown(-1).code() == -1);
+		check!(RpcError::new("custom").code() == RpcErrorCode::Unknown(303));
+
+		Ok(())
+	}
+
+	#[test]
+	fn structured_preflight_failure() -> anyhow::Result<()> {
+		let rpc_error = |code: i32, data: Value| {
+			serde_json::from_value::<RpcError>(json!({
+				"jsonrpc": "2.0",
+				"id": 0,
+				"error": { "code": code, "message": "Transaction simulation failed", "data": data },
+			}))
+			.map(ClientError::from)
+		};
+
+		let error = rpc_error(
+			-32002,
+			json!({
+				"err": { "InstructionError": [0, { "Custom": 1 }] },
+				"logs": ["Program log: insufficient balance"],
+				"accounts": null,
+				"unitsConsumed": 1_200,
+				"returnData": null,
+			}),
+		)?;
+		let ClientError::PreflightFailure(result) = &error else {
+			panic!("expected a preflight failure: {error:?}");
+		};
+		check!(result.units_consumed == Some(1_200));
+		check!(result.logs == Some(vec!["Program log: insufficient balance".to_string()]));
+		check!(error.transaction_error() == result.err);
+		check!(error.rpc_error_code() == Some(RpcErrorCode::SendTransactionPreflightFailure));
+		check!(!error.is_retryable());
+		check!(
+			error
+				.to_string()
+				.ends_with("\n  Program log: insufficient balance")
+		);
+
+		let error = rpc_error(-32002, json!({ "err": "BlockhashNotFound", "logs": [] }))?;
+		check!(let ClientError::PreflightFailure(_) = &error);
+		check!(error.is_retryable());
+
+		check!(let ClientError::Rpc(_) = rpc_error(-32002, json!("unexpected"))?);
+		check!(let ClientError::Rpc(_) = rpc_error(-32002, Value::Null)?);
+		check!(let ClientError::Rpc(_) = rpc_error(-32005, json!({ "err": "BlockhashNotFound" }))?);
+
+		Ok(())
+	}
+}
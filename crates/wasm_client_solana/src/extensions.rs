@@ -9,15 +9,20 @@ use solana_sdk::address_lookup_table::instruction::create_lookup_table;
 use solana_sdk::address_lookup_table::instruction::extend_lookup_table;
 use solana_sdk::compute_budget::ComputeBudgetInstruction;
 use solana_sdk::hash::Hash;
+use solana_sdk::instruction::AccountMeta;
 use solana_sdk::instruction::Instruction;
 use solana_sdk::message::CompileError;
 use solana_sdk::message::VersionedMessage;
+use solana_sdk::message::compiled_instruction::CompiledInstruction;
 use solana_sdk::message::v0;
+use solana_sdk::message::v0::LoadedAddresses;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
 use solana_sdk::signer::SignerError;
+use solana_sdk::signer::presigner::PresignerError;
 use solana_sdk::signers::Signers;
 use solana_sdk::transaction::VersionedTransaction;
+use solana_system_interface::instruction::advance_nonce_account;
 use wallet_standard::SolanaSignTransactionOptions;
 use wallet_standard::SolanaSignTransactionOutput;
 use wallet_standard::SolanaSignTransactionProps;
@@ -27,11 +32,16 @@ use wallet_standard::WalletSolanaPubkey;
 use wallet_standard::WalletSolanaSignMessage;
 use wallet_standard::WalletSolanaSignTransaction;
 
+use crate::AsyncSigner;
 use crate::COMPUTE_UNIT_MAX_LIMIT;
 use crate::ClientError;
 use crate::ClientResult;
+use crate::DecompileError;
 use crate::MAX_LOOKUP_ADDRESSES_PER_TRANSACTION;
+use crate::PACKET_DATA_SIZE;
+use crate::SignatureVerificationError;
 use crate::SolanaRpcClient;
+use crate::TransactionTooLarge;
 
 /// Add extensions which make it possible to partially sign a versioned
 /// transaction.
@@ -91,6 +101,82 @@ pub trait VersionedTransactionExtension {
 	) -> Result<Vec<Option<usize>>, SignerError>;
 	/// Check whether the transaction is fully signed with valid signatures.
 	fn is_signed(&self) -> bool;
+	/// Sign the transaction with the provided signers, leaving the signatures
+	/// of every other required signer untouched. Slots which haven't been
+	/// signed yet keep the default signature.
+	///
+	/// This mirrors `Transaction::try_partial_sign` from the native sdk. Any
+	/// existing signatures are cleared when `recent_blockhash` differs from
+	/// the blockhash of the message.
+	///
+	/// # Errors
+	///
+	/// Returns [`SignerError::KeypairPubkeyMismatch`] when one of the signers
+	/// isn't required by the message.
+	fn try_partial_sign<T: Signers + ?Sized>(
+		&mut self,
+		signers: &T,
+		recent_blockhash: Option<Hash>,
+	) -> Result<&mut Self, SignerError>;
+	/// Check whether every required signer has provided a signature. The
+	/// signatures themselves are not verified.
+	fn is_fully_signed(&self) -> bool {
+		self.is_signed()
+	}
+	/// Verify every populated signature against the message and the required
+	/// signer at the same position.
+	///
+	/// Default signatures are treated as missing rather than invalid, which
+	/// makes it possible to check a partially signed transaction before adding
+	/// the remaining signatures. Use
+	/// [`VersionedTransactionExtension::missing_signers`] to find the signers
+	/// which still need to sign and
+	/// [`VersionedTransaction::verify_with_results`] for the result of each
+	/// signature.
+	///
+	/// # Errors
+	///
+	/// Returns an error when the number of signatures doesn't match the
+	/// message header or when any populated signature is invalid.
+	fn verify_signatures(&self) -> Result<(), SignatureVerificationError>;
+	/// The required signers which haven't signed the transaction yet, in the
+	/// order they appear in the message.
+	fn missing_signers(&self) -> Vec<Pubkey>;
+	/// Add a signature created elsewhere, for example by a remote signer, for
+	/// the provided `pubkey`.
+	///
+	/// # Errors
+	///
+	/// Returns [`SignerError::KeypairPubkeyMismatch`] when the `pubkey` isn't a
+	/// required signer and [`PresignerError::VerificationFailure`] when the
+	/// signature isn't valid for the current message.
+	fn add_signature(
+		&mut self,
+		pubkey: &Pubkey,
+		signature: Signature,
+	) -> Result<&mut Self, SignerError>;
+	/// Sign the transaction with a mix of local signers, [`Presigner`]s and
+	/// remote [`AsyncSigner`]s like wallets.
+	///
+	/// The signers are asked to sign one after the other and every returned
+	/// signature is verified against the message before it's added. As with
+	/// [`VersionedTransactionExtension::try_sign`], any existing signatures
+	/// are cleared when `recent_blockhash` differs from the blockhash of the
+	/// message.
+	///
+	/// # Errors
+	///
+	/// Returns [`SignerError::KeypairPubkeyMismatch`] when one of the signers
+	/// isn't required by the message and
+	/// [`PresignerError::VerificationFailure`] when a returned signature is
+	/// invalid.
+	///
+	/// [`Presigner`]: crate::Presigner
+	fn try_sign_with_async_signers(
+		&mut self,
+		signers: &[&dyn AsyncSigner],
+		recent_blockhash: Option<Hash>,
+	) -> impl Future<Output = Result<&mut Self, SignerError>>;
 	/// Sign the transaction with a subset of required keys, panicking when an
 	/// error is met.
 	fn sign<T: Signers + ?Sized>(
@@ -111,6 +197,33 @@ pub trait VersionedTransactionExtension {
 		wallet: &W,
 		options: Option<SolanaSignTransactionOptions>,
 	) -> impl Future<Output = WalletResult<VersionedTransaction>>;
+	/// Set the compute unit limit of the transaction. See
+	/// [`VersionedMessageExtension::with_compute_unit_limit`].
+	///
+	/// Any existing signatures are cleared when the message changes.
+	fn with_compute_unit_limit(&mut self, units: u32) -> Result<&mut Self, CompileError>;
+	/// Set the compute unit price of the transaction. See
+	/// [`VersionedMessageExtension::with_compute_unit_price`].
+	///
+	/// Any existing signatures are cleared when the message changes.
+	fn with_compute_unit_price(&mut self, micro_lamports: u64) -> Result<&mut Self, CompileError>;
+	/// Create an unsigned copy of the transaction with the recent blockhash of
+	/// the message replaced by `blockhash`.
+	///
+	/// The blockhash of a durable nonce transaction is the nonce value and
+	/// shouldn't be replaced. Check
+	/// [`VersionedMessageExtension::uses_durable_nonce`] before calling this.
+	#[must_use]
+	fn with_new_blockhash(&self, blockhash: Hash) -> Self;
+	/// The size in bytes of the serialized transaction.
+	fn serialized_size(&self) -> usize;
+	/// Check that the serialized transaction fits within [`PACKET_DATA_SIZE`].
+	///
+	/// # Errors
+	///
+	/// Returns the computed size with a breakdown of the signature and message
+	/// bytes when the transaction is too large.
+	fn fits_in_packet(&self) -> Result<(), TransactionTooLarge>;
 }
 
 impl VersionedTransactionExtension for VersionedTransaction {
@@ -228,6 +341,131 @@ impl VersionedTransactionExtension for VersionedTransaction {
 				.all(|signature| *signature != Signature::default())
 	}
 
+	fn try_partial_sign<T: Signers + ?Sized>(
+		&mut self,
+		signers: &T,
+		recent_blockhash: Option<Hash>,
+	) -> Result<&mut Self, SignerError> {
+		self.try_sign(signers, recent_blockhash)
+	}
+
+	fn verify_signatures(&self) -> Result<(), SignatureVerificationError> {
+		let expected = self.message.header().num_required_signatures as usize;
+		let actual = self.signatures.len();
+
+		if actual != expected {
+			return Err(SignatureVerificationError::SignatureCountMismatch { expected, actual });
+		}
+
+		let Some(signers) = self.message.static_account_keys().get(..expected) else {
+			return Err(SignatureVerificationError::InvalidMessage);
+		};
+		let message = self.message.serialize();
+
+		for (index, (signature, pubkey)) in self.signatures.iter().zip(signers).enumerate() {
+			if *signature != Signature::default() && !signature.verify(pubkey.as_ref(), &message) {
+				return Err(SignatureVerificationError::InvalidSignature {
+					index,
+					pubkey: *pubkey,
+				});
+			}
+		}
+
+		Ok(())
+	}
+
+	fn missing_signers(&self) -> Vec<Pubkey> {
+		let num_required_signatures = self.message.header().num_required_signatures as usize;
+
+		self.message
+			.static_account_keys()
+			.iter()
+			.take(num_required_signatures)
+			.enumerate()
+			.filter(|(position, _)| {
+				self.signatures
+					.get(*position)
+					.is_none_or(|signature| *signature == Signature::default())
+			})
+			.map(|(_, pubkey)| *pubkey)
+			.collect()
+	}
+
+	fn add_signature(
+		&mut self,
+		pubkey: &Pubkey,
+		signature: Signature,
+	) -> Result<&mut Self, SignerError> {
+		let Some(position) = self
+			.get_signing_keypair_positions(std::slice::from_ref(pubkey))?
+			.first()
+			.copied()
+			.flatten()
+		else {
+			return Err(SignerError::KeypairPubkeyMismatch);
+		};
+
+		if !signature.verify(pubkey.as_ref(), &self.message.serialize()) {
+			return Err(SignerError::PresignerError(
+				PresignerError::VerificationFailure,
+			));
+		}
+
+		let Some(slot) = self.signatures.get_mut(position) else {
+			return Err(SignerError::InvalidInput(
+				"missing signature slot".to_string(),
+			));
+		};
+		*slot = signature;
+
+		Ok(self)
+	}
+
+	fn try_sign_with_async_signers(
+		&mut self,
+		signers: &[&dyn AsyncSigner],
+		recent_blockhash: Option<Hash>,
+	) -> impl Future<Output = Result<&mut Self, SignerError>> {
+		async move {
+			let pubkeys = signers
+				.iter()
+				.map(|signer| signer.signer_pubkey())
+				.collect::<Vec<_>>();
+			let positions = self
+				.get_signing_keypair_positions(&pubkeys)?
+				.iter()
+				.map(|pos| pos.ok_or(SignerError::KeypairPubkeyMismatch))
+				.collect::<Result<Vec<_>, _>>()?;
+			let message_blockhash = *self.message.recent_blockhash();
+			let recent_blockhash = recent_blockhash.unwrap_or(message_blockhash);
+
+			if recent_blockhash != message_blockhash {
+				self.message.set_recent_blockhash(recent_blockhash);
+
+				// reset signatures if blockhash has changed
+				self.signatures
+					.iter_mut()
+					.for_each(|signature| *signature = Signature::default());
+			}
+
+			let message = self.message.serialize();
+
+			for ((signer, pubkey), position) in signers.iter().zip(&pubkeys).zip(positions) {
+				let signature = signer.try_sign_message_async(&message).await?;
+
+				if !signature.verify(pubkey.as_ref(), &message) {
+					return Err(SignerError::PresignerError(
+						PresignerError::VerificationFailure,
+					));
+				}
+
+				self.signatures[position] = signature;
+			}
+
+			Ok(self)
+		}
+	}
+
 	fn try_sign_async<W: WalletSolanaSignMessage + WalletSolanaPubkey>(
 		&mut self,
 		wallet: &W,
@@ -302,16 +540,488 @@ impl VersionedTransactionExtension for VersionedTransaction {
 			Ok(transaction)
 		}
 	}
+
+	fn with_compute_unit_limit(&mut self, units: u32) -> Result<&mut Self, CompileError> {
+		let message = self.message.clone();
+		self.message.with_compute_unit_limit(units)?;
+		clear_signatures_when_changed(self, &message);
+
+		Ok(self)
+	}
+
+	fn with_compute_unit_price(&mut self, micro_lamports: u64) -> Result<&mut Self, CompileError> {
+		let message = self.message.clone();
+		self.message.with_compute_unit_price(micro_lamports)?;
+		clear_signatures_when_changed(self, &message);
+
+		Ok(self)
+	}
+
+	fn with_new_blockhash(&self, blockhash: Hash) -> Self {
+		let mut message = self.message.clone();
+		message.set_recent_blockhash(blockhash);
+
+		Self::new_unsigned(message)
+	}
+
+	fn serialized_size(&self) -> usize {
+		self.message.size_with_signatures(self.signatures.len())
+	}
+
+	fn fits_in_packet(&self) -> Result<(), TransactionTooLarge> {
+		let message_size = self.message.serialize().len();
+		let signatures_size = signatures_size(self.signatures.len());
+		let size = signatures_size + message_size;
+
+		if size > PACKET_DATA_SIZE {
+			return Err(TransactionTooLarge {
+				size,
+				limit: PACKET_DATA_SIZE,
+				signatures_size,
+				message_size,
+			});
+		}
+
+		Ok(())
+	}
+}
+
+/// The result of [`VersionedMessageExtension::try_compile_with_lookup_tables`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LookupTableReport {
+	/// The bytes saved compared to compiling the message without any lookup
+	/// tables.
+	pub saved_bytes: usize,
+	/// The addresses of the lookup tables used by the message.
+	pub tables: Vec<Pubkey>,
+	/// The static account keys of the message with the reason each one
+	/// couldn't be loaded from a lookup table.
+	pub static_keys: Vec<(Pubkey, StaticKeyReason)>,
+}
+
+impl LookupTableReport {
+	fn new(message: &v0::Message, tables: &[AddressLookupTableAccount]) -> Self {
+		let loaded = message
+			.address_table_lookups
+			.iter()
+			.map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+			.sum::<usize>();
+		let lookups_size = compact_u16_size(message.address_table_lookups.len())
+			+ message
+				.address_table_lookups
+				.iter()
+				.map(|lookup| {
+					size_of::<Pubkey>()
+						+ compact_u16_size(lookup.writable_indexes.len())
+						+ lookup.writable_indexes.len()
+						+ compact_u16_size(lookup.readonly_indexes.len())
+						+ lookup.readonly_indexes.len()
+				})
+				.sum::<usize>();
+		let num_static_keys = message.account_keys.len();
+		let saved_bytes = (loaded * size_of::<Pubkey>()
+			+ compact_u16_size(num_static_keys + loaded)
+			+ compact_u16_size(0))
+		.saturating_sub(lookups_size + compact_u16_size(num_static_keys));
+
+		let static_keys = message
+			.account_keys
+			.iter()
+			.enumerate()
+			.map(|(index, pubkey)| {
+				let reason = if index < usize::from(message.header.num_required_signatures) {
+					StaticKeyReason::Signer
+				} else if message.is_key_called_as_program(index) {
+					StaticKeyReason::Program
+				} else if tables.iter().any(|table| table.addresses.contains(pubkey)) {
+					StaticKeyReason::TableNotUsed
+				} else {
+					StaticKeyReason::NotInTable
+				};
+
+				(*pubkey, reason)
+			})
+			.collect();
+
+		Self {
+			saved_bytes,
+			tables: message
+				.address_table_lookups
+				.iter()
+				.map(|lookup| lookup.account_key)
+				.collect(),
+			static_keys,
+		}
+	}
+}
+
+/// The reason a static account key wasn't loaded from a lookup table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaticKeyReason {
+	/// Signers must always be static account keys.
+	Signer,
+	/// Invoked programs must always be static account keys.
+	Program,
+	/// The key isn't in any of the provided lookup tables.
+	NotInTable,
+	/// The key is in a lookup table which was left out because it didn't
+	/// reduce the size of the message.
+	TableNotUsed,
+}
+
+fn v0_message_size(message: &v0::Message) -> usize {
+	VersionedMessage::V0(message.clone()).serialize().len()
+}
+
+/// The serialized size of `count` signatures with their compact-u16 length
+/// prefix.
+fn signatures_size(count: usize) -> usize {
+	compact_u16_size(count) + count * size_of::<Signature>()
+}
+
+/// The serialized size of a compact-u16 length prefix.
+fn compact_u16_size(length: usize) -> usize {
+	match length {
+		0..0x80 => 1,
+		0x80..0x4000 => 2,
+		_ => 3,
+	}
+}
+
+fn clear_signatures_when_changed(
+	transaction: &mut VersionedTransaction,
+	message: &VersionedMessage,
+) {
+	if transaction.message != *message {
+		transaction
+			.signatures
+			.iter_mut()
+			.for_each(|signature| *signature = Signature::default());
+	}
 }
 
 pub trait VersionedMessageExtension {
 	fn into_versioned_transaction(self) -> VersionedTransaction;
+	/// Compile a v0 message using only the provided lookup `tables` which
+	/// reduce the size of the message.
+	///
+	/// Tables are dropped when compiling without them results in a message
+	/// which is the same size or smaller. The returned [`LookupTableReport`]
+	/// includes the bytes saved compared to a message without lookup tables
+	/// and the reason each remaining static account key couldn't be loaded
+	/// from a table.
+	///
+	/// # Errors
+	///
+	/// Returns an error when the message can't be compiled, for example when
+	/// it uses too many accounts.
+	fn try_compile_with_lookup_tables(
+		payer: &Pubkey,
+		instructions: &[Instruction],
+		tables: &[AddressLookupTableAccount],
+		recent_blockhash: Hash,
+	) -> Result<(Self, LookupTableReport), CompileError>
+	where
+		Self: Sized;
+	/// Set the compute unit limit of the message.
+	///
+	/// An existing `SetComputeUnitLimit` instruction is updated in place and
+	/// any duplicates are removed. Otherwise the instruction is inserted
+	/// before all other instructions. The order of the other instructions is
+	/// preserved.
+	///
+	/// # Errors
+	///
+	/// Returns an error when the compute budget program needs to be added to
+	/// a message which already uses the maximum number of accounts.
+	fn with_compute_unit_limit(&mut self, units: u32) -> Result<&mut Self, CompileError>;
+	/// Set the compute unit price of the message in micro-lamports. Existing
+	/// instructions are replaced in the same way as
+	/// [`VersionedMessageExtension::with_compute_unit_limit`].
+	fn with_compute_unit_price(&mut self, micro_lamports: u64) -> Result<&mut Self, CompileError>;
+	/// Check whether the message uses a durable nonce, in which case the first
+	/// instruction advances the nonce account.
+	fn uses_durable_nonce(&self) -> bool;
+	/// Decompile the message back into the instructions it was compiled from.
+	///
+	/// The signer and writable flags of each [`AccountMeta`] are derived from
+	/// the message header for static account keys and from the section of
+	/// `loaded_addresses` for keys loaded from lookup tables.
+	///
+	/// # Errors
+	///
+	/// Returns an error when a v0 message with address table lookups is
+	/// decompiled without the matching `loaded_addresses`, or when an
+	/// instruction references an account which doesn't exist.
+	fn decompile(
+		&self,
+		loaded_addresses: Option<&LoadedAddresses>,
+	) -> Result<Vec<Instruction>, DecompileError>;
+	/// The size in bytes of a transaction containing this message and
+	/// `num_signers` signatures. This can be checked before the message is
+	/// signed.
+	fn size_with_signatures(&self, num_signers: usize) -> usize;
 }
 
 impl VersionedMessageExtension for VersionedMessage {
 	fn into_versioned_transaction(self) -> VersionedTransaction {
 		VersionedTransaction::new_unsigned(self)
 	}
+
+	fn try_compile_with_lookup_tables(
+		payer: &Pubkey,
+		instructions: &[Instruction],
+		tables: &[AddressLookupTableAccount],
+		recent_blockhash: Hash,
+	) -> Result<(Self, LookupTableReport), CompileError> {
+		let compile = |tables: &[AddressLookupTableAccount]| {
+			v0::Message::try_compile(payer, instructions, tables, recent_blockhash)
+		};
+		let mut selected = tables.to_vec();
+		let mut message = compile(&selected)?;
+
+		// Keep dropping the first table which doesn't reduce the size until every
+		// remaining table is worth its overhead.
+		'tables: loop {
+			for lookup in &message.address_table_lookups {
+				let candidate_tables = selected
+					.iter()
+					.filter(|table| table.key != lookup.account_key)
+					.cloned()
+					.collect::<Vec<_>>();
+
+				let candidate = compile(&candidate_tables)
+					.ok()
+					.filter(|candidate| v0_message_size(candidate) <= v0_message_size(&message));
+
+				if let Some(candidate) = candidate {
+					selected = candidate_tables;
+					message = candidate;
+					continue 'tables;
+				}
+			}
+
+			break;
+		}
+
+		let report = LookupTableReport::new(&message, tables);
+
+		Ok((Self::V0(message), report))
+	}
+
+	fn with_compute_unit_limit(&mut self, units: u32) -> Result<&mut Self, CompileError> {
+		let instruction = ComputeBudgetInstruction::set_compute_unit_limit(units);
+		set_compute_budget_instruction(self, instruction)?;
+
+		Ok(self)
+	}
+
+	fn with_compute_unit_price(&mut self, micro_lamports: u64) -> Result<&mut Self, CompileError> {
+		let instruction = ComputeBudgetInstruction::set_compute_unit_price(micro_lamports);
+		set_compute_budget_instruction(self, instruction)?;
+
+		Ok(self)
+	}
+
+	fn uses_durable_nonce(&self) -> bool {
+		let Some(instruction) = self.instructions().first() else {
+			return false;
+		};
+		let advance_nonce = advance_nonce_account(&Pubkey::default(), &Pubkey::default());
+
+		self.static_account_keys()
+			.get(usize::from(instruction.program_id_index))
+			.is_some_and(|program_id| *program_id == advance_nonce.program_id)
+			&& instruction.data == advance_nonce.data
+	}
+
+	fn size_with_signatures(&self, num_signers: usize) -> usize {
+		signatures_size(num_signers) + self.serialize().len()
+	}
+
+	fn decompile(
+		&self,
+		loaded_addresses: Option<&LoadedAddresses>,
+	) -> Result<Vec<Instruction>, DecompileError> {
+		let lookups = self.address_table_lookups().unwrap_or_default();
+		let expected_writable = lookups
+			.iter()
+			.map(|lookup| lookup.writable_indexes.len())
+			.sum::<usize>();
+		let expected_readonly = lookups
+			.iter()
+			.map(|lookup| lookup.readonly_indexes.len())
+			.sum::<usize>();
+		let empty = LoadedAddresses::default();
+		let loaded_addresses = match loaded_addresses {
+			Some(loaded_addresses) => loaded_addresses,
+			None if lookups.is_empty() => &empty,
+			None => return Err(DecompileError::MissingLoadedAddresses),
+		};
+
+		if loaded_addresses.writable.len() != expected_writable {
+			return Err(DecompileError::WritableAddressesMismatch {
+				expected: expected_writable,
+				actual: loaded_addresses.writable.len(),
+			});
+		}
+
+		if loaded_addresses.readonly.len() != expected_readonly {
+			return Err(DecompileError::ReadonlyAddressesMismatch {
+				expected: expected_readonly,
+				actual: loaded_addresses.readonly.len(),
+			});
+		}
+
+		let header = self.header();
+		let static_keys = self.static_account_keys();
+		let num_signers = usize::from(header.num_required_signatures);
+		let num_writable_signers =
+			num_signers.saturating_sub(usize::from(header.num_readonly_signed_accounts));
+		let num_writable_unsigned = static_keys
+			.len()
+			.saturating_sub(num_signers + usize::from(header.num_readonly_unsigned_accounts));
+		let account_meta = |index: u8| {
+			let position = usize::from(index);
+
+			if let Some(pubkey) = static_keys.get(position) {
+				let is_signer = position < num_signers;
+				let is_writable = if is_signer {
+					position < num_writable_signers
+				} else {
+					position - num_signers < num_writable_unsigned
+				};
+
+				return Ok(AccountMeta {
+					pubkey: *pubkey,
+					is_signer,
+					is_writable,
+				});
+			}
+
+			let position = position - static_keys.len();
+			let (pubkey, is_writable) =
+				if let Some(pubkey) = loaded_addresses.writable.get(position) {
+					(pubkey, true)
+				} else {
+					let pubkey = loaded_addresses
+						.readonly
+						.get(position - loaded_addresses.writable.len())
+						.ok_or(DecompileError::InvalidAccountIndex(usize::from(index)))?;
+					(pubkey, false)
+				};
+
+			Ok(AccountMeta {
+				pubkey: *pubkey,
+				is_signer: false,
+				is_writable,
+			})
+		};
+
+		self.instructions()
+			.iter()
+			.map(|instruction| {
+				Ok(Instruction {
+					program_id: account_meta(instruction.program_id_index)?.pubkey,
+					accounts: instruction
+						.accounts
+						.iter()
+						.map(|index| account_meta(*index))
+						.collect::<Result<_, _>>()?,
+					data: instruction.data.clone(),
+				})
+			})
+			.collect()
+	}
+}
+
+/// Insert or replace the compute budget instruction with the same variant as
+/// the `instruction` directly in the compiled message.
+fn set_compute_budget_instruction(
+	message: &mut VersionedMessage,
+	instruction: Instruction,
+) -> Result<(), CompileError> {
+	let Instruction {
+		program_id, data, ..
+	} = instruction;
+	let (header, account_keys, instructions, loaded_addresses) = match message {
+		VersionedMessage::Legacy(message) => {
+			(
+				&mut message.header,
+				&mut message.account_keys,
+				&mut message.instructions,
+				0,
+			)
+		}
+		VersionedMessage::V0(message) => {
+			let loaded_addresses = message
+				.address_table_lookups
+				.iter()
+				.map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+				.sum();
+
+			(
+				&mut message.header,
+				&mut message.account_keys,
+				&mut message.instructions,
+				loaded_addresses,
+			)
+		}
+	};
+	let program_id_index =
+		if let Some(index) = account_keys.iter().position(|key| *key == program_id) {
+			index
+		} else {
+			let index = account_keys.len();
+
+			if index + loaded_addresses > usize::from(u8::MAX) {
+				return Err(CompileError::AccountIndexOverflow);
+			}
+
+			// Append the program as a readonly unsigned static key. Indexes
+			// beyond the static keys refer to lookup table addresses which
+			// are shifted by the new key.
+			for instruction in instructions.iter_mut() {
+				let indexes = instruction
+					.accounts
+					.iter_mut()
+					.chain([&mut instruction.program_id_index]);
+
+				for account_index in indexes {
+					if usize::from(*account_index) >= index {
+						*account_index += 1;
+					}
+				}
+			}
+
+			account_keys.push(program_id);
+			header.num_readonly_unsigned_accounts += 1;
+			index
+		};
+	let program_id_index =
+		u8::try_from(program_id_index).map_err(|_| CompileError::AccountIndexOverflow)?;
+	let variant = data.first().copied();
+	let is_same_variant = |instruction: &CompiledInstruction| {
+		instruction.program_id_index == program_id_index
+			&& instruction.data.first().copied() == variant
+	};
+
+	if let Some(position) = instructions.iter().position(is_same_variant) {
+		instructions[position].data = data;
+		let mut index = 0;
+		instructions.retain(|instruction| {
+			let keep = index <= position || !is_same_variant(instruction);
+			index += 1;
+			keep
+		});
+	} else {
+		instructions.insert(
+			0,
+			CompiledInstruction::new_from_raw_parts(program_id_index, data, vec![]),
+		);
+	}
+
+	Ok(())
 }
 
 /// Initialize a lookup table that can be used with versioned transactions.
@@ -442,3 +1152,566 @@ fn wasm_safe_instruction_clone(instruction: &Instruction) -> Instruction {
 		instruction.clone()
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use solana_sdk::message::Message;
+	use solana_sdk::signature::Keypair;
+	use solana_sdk::signer::Signer;
+	use solana_system_interface::instruction::transfer;
+
+	use super::*;
+	use crate::Presigner;
+
+	/// The program, accounts and data of every instruction in the message.
+	fn decompile(
+		message: &VersionedMessage,
+		loaded_addresses: &[Pubkey],
+	) -> Vec<(Pubkey, Vec<Pubkey>, Vec<u8>)> {
+		let account_keys = message
+			.static_account_keys()
+			.iter()
+			.chain(loaded_addresses)
+			.collect::<Vec<_>>();
+		let key = |index: &u8| *account_keys[usize::from(*index)];
+
+		message
+			.instructions()
+			.iter()
+			.map(|instruction| {
+				(
+					key(&instruction.program_id_index),
+					instruction.accounts.iter().map(key).collect(),
+					instruction.data.clone(),
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn replace_existing_compute_unit_price() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let transfer = transfer(&payer, &Pubkey::new_unique(), 100);
+		let blockhash = Hash::new_unique();
+		let compile = |instructions: &[Instruction]| {
+			VersionedMessage::Legacy(Message::new_with_blockhash(
+				instructions,
+				Some(&payer),
+				&blockhash,
+			))
+		};
+		let mut message = compile(&[
+			transfer.clone(),
+			ComputeBudgetInstruction::set_compute_unit_price(1),
+			ComputeBudgetInstruction::set_compute_unit_price(2),
+		]);
+
+		message
+			.with_compute_unit_price(5)?
+			.with_compute_unit_limit(200_000)?;
+
+		assert_eq!(
+			message,
+			compile(&[
+				ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+				transfer,
+				ComputeBudgetInstruction::set_compute_unit_price(5),
+			])
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn add_compute_budget_to_v0_message() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let recipient = Pubkey::new_unique();
+		let table = AddressLookupTableAccount {
+			key: Pubkey::new_unique(),
+			addresses: vec![recipient],
+		};
+		let transfer = transfer(&payer, &recipient, 100);
+		let mut message = VersionedMessage::V0(v0::Message::try_compile(
+			&payer,
+			std::slice::from_ref(&transfer),
+			&[table],
+			Hash::new_unique(),
+		)?);
+		let header = *message.header();
+
+		message
+			.with_compute_unit_price(10)?
+			.with_compute_unit_limit(50_000)?;
+
+		assert_eq!(
+			message.header().num_readonly_unsigned_accounts,
+			header.num_readonly_unsigned_accounts + 1
+		);
+		assert_eq!(
+			decompile(&message, &[recipient]),
+			[
+				ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+				ComputeBudgetInstruction::set_compute_unit_price(10),
+				transfer,
+			]
+			.into_iter()
+			.map(|instruction| {
+				(
+					instruction.program_id,
+					instruction
+						.accounts
+						.iter()
+						.map(|account| account.pubkey)
+						.collect(),
+					instruction.data,
+				)
+			})
+			.collect::<Vec<_>>()
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn clear_signatures_when_compute_budget_changes() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let instructions = [
+			ComputeBudgetInstruction::set_compute_unit_limit(1000),
+			transfer(&payer.pubkey(), &Pubkey::new_unique(), 1),
+		];
+		let mut transaction = VersionedTransaction::new_unsigned_v0(
+			&payer.pubkey(),
+			&instructions,
+			&[],
+			Hash::new_unique(),
+		)?;
+		transaction.try_sign(&[&payer], None)?;
+
+		transaction.with_compute_unit_limit(1000)?;
+		assert!(transaction.is_signed());
+
+		transaction.with_compute_unit_limit(2000)?;
+		assert!(!transaction.is_signed());
+
+		Ok(())
+	}
+
+	#[test]
+	fn partially_sign_transaction() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let authority = Keypair::new();
+		let mut instruction = transfer(&authority.pubkey(), &Pubkey::new_unique(), 1);
+		instruction.accounts[0].is_signer = true;
+		let mut transaction = VersionedTransaction::new_unsigned_v0(
+			&payer.pubkey(),
+			&[instruction],
+			&[],
+			Hash::new_unique(),
+		)?;
+
+		transaction.try_partial_sign(&[&payer], None)?;
+		assert!(!transaction.is_fully_signed());
+		assert_eq!(transaction.missing_signers(), vec![authority.pubkey()]);
+		assert_eq!(transaction.signatures[1], Signature::default());
+
+		let signature = authority.sign_message(&transaction.message.serialize());
+		assert!(matches!(
+			transaction.add_signature(&Pubkey::new_unique(), signature),
+			Err(SignerError::KeypairPubkeyMismatch)
+		));
+		assert!(matches!(
+			transaction.add_signature(&payer.pubkey(), signature),
+			Err(SignerError::PresignerError(
+				PresignerError::VerificationFailure
+			))
+		));
+
+		transaction.add_signature(&authority.pubkey(), signature)?;
+		assert!(transaction.is_fully_signed());
+		assert!(transaction.missing_signers().is_empty());
+		assert!(transaction.verify_with_results().iter().all(|valid| *valid));
+
+		Ok(())
+	}
+
+	#[test]
+	fn clear_partial_signatures_when_blockhash_changes() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let authority = Keypair::new();
+		let mut instruction = transfer(&authority.pubkey(), &Pubkey::new_unique(), 1);
+		instruction.accounts[0].is_signer = true;
+		let mut transaction = VersionedTransaction::new_unsigned_v0(
+			&payer.pubkey(),
+			&[instruction],
+			&[],
+			Hash::new_unique(),
+		)?;
+
+		transaction.try_partial_sign(&[&payer], None)?;
+		assert_eq!(transaction.missing_signers(), vec![authority.pubkey()]);
+
+		let blockhash = Hash::new_unique();
+		transaction.try_partial_sign(&[&authority], Some(blockhash))?;
+		assert_eq!(*transaction.message.recent_blockhash(), blockhash);
+		assert_eq!(transaction.signatures[0], Signature::default());
+		assert_eq!(transaction.missing_signers(), vec![payer.pubkey()]);
+
+		transaction.try_partial_sign(&[&payer], Some(blockhash))?;
+		assert!(transaction.is_fully_signed());
+
+		Ok(())
+	}
+
+	/// A signer which holds its keypair out of reach, like a remote service.
+	struct RemoteSigner {
+		keypair: Keypair,
+		corrupt: bool,
+	}
+
+	#[async_trait::async_trait(?Send)]
+	impl AsyncSigner for RemoteSigner {
+		fn signer_pubkey(&self) -> Pubkey {
+			self.keypair.pubkey()
+		}
+
+		async fn try_sign_message_async(&self, message: &[u8]) -> Result<Signature, SignerError> {
+			let mut message = message.to_vec();
+
+			if self.corrupt {
+				message.push(0);
+			}
+
+			Ok(self.keypair.sign_message(&message))
+		}
+	}
+
+	#[test]
+	fn sign_with_mixed_async_signers() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let authority = Keypair::new();
+		let remote = RemoteSigner {
+			keypair: Keypair::new(),
+			corrupt: false,
+		};
+		let mut authority_instruction = transfer(&authority.pubkey(), &Pubkey::new_unique(), 1);
+		authority_instruction.accounts[0].is_signer = true;
+		let mut remote_instruction = transfer(&remote.signer_pubkey(), &Pubkey::new_unique(), 1);
+		remote_instruction.accounts[0].is_signer = true;
+		let mut transaction = VersionedTransaction::new_unsigned_v0(
+			&payer.pubkey(),
+			&[authority_instruction, remote_instruction],
+			&[],
+			Hash::new_unique(),
+		)?;
+
+		let message = transaction.message.serialize();
+		let presigner = Presigner::new(&authority.pubkey(), &authority.sign_message(&message));
+		futures::executor::block_on(
+			transaction.try_sign_with_async_signers(&[&payer, &presigner, &remote], None),
+		)?;
+
+		assert!(transaction.is_fully_signed());
+		assert!(transaction.verify_signatures().is_ok());
+
+		// a presigned signature is invalid once the blockhash changes
+		let result = futures::executor::block_on(
+			transaction.try_sign_with_async_signers(&[&presigner], Some(Hash::new_unique())),
+		);
+		assert!(matches!(
+			result,
+			Err(SignerError::PresignerError(
+				PresignerError::VerificationFailure
+			))
+		));
+
+		let corrupt = RemoteSigner {
+			keypair: remote.keypair.insecure_clone(),
+			corrupt: true,
+		};
+		let result =
+			futures::executor::block_on(transaction.try_sign_with_async_signers(&[&corrupt], None));
+		assert!(matches!(
+			result,
+			Err(SignerError::PresignerError(
+				PresignerError::VerificationFailure
+			))
+		));
+		assert!(!transaction.is_fully_signed());
+
+		Ok(())
+	}
+
+	#[test]
+	fn verify_partially_signed_transaction() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let authority = Keypair::new();
+		let mut instruction = transfer(&authority.pubkey(), &Pubkey::new_unique(), 1);
+		instruction.accounts[0].is_signer = true;
+		let legacy = VersionedMessage::Legacy(Message::new_with_blockhash(
+			std::slice::from_ref(&instruction),
+			Some(&payer.pubkey()),
+			&Hash::new_unique(),
+		));
+		let v0 = VersionedMessage::V0(v0::Message::try_compile(
+			&payer.pubkey(),
+			&[instruction],
+			&[],
+			Hash::new_unique(),
+		)?);
+
+		for message in [legacy, v0] {
+			let mut transaction = VersionedTransaction::new_unsigned(message);
+			transaction.try_partial_sign(&[&authority], None)?;
+
+			assert_eq!(transaction.verify_signatures(), Ok(()));
+			assert_eq!(transaction.verify_with_results(), vec![false, true]);
+
+			transaction.signatures[1] = payer.sign_message(&transaction.message.serialize());
+			assert_eq!(
+				transaction.verify_signatures(),
+				Err(SignatureVerificationError::InvalidSignature {
+					index: 1,
+					pubkey: authority.pubkey(),
+				})
+			);
+
+			transaction.signatures.pop();
+			assert_eq!(
+				transaction.verify_signatures(),
+				Err(SignatureVerificationError::SignatureCountMismatch {
+					expected: 2,
+					actual: 1,
+				})
+			);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn replace_blockhash_of_signed_transaction() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let nonce = Pubkey::new_unique();
+		let instruction = transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+		let legacy = VersionedMessage::Legacy(Message::new_with_blockhash(
+			std::slice::from_ref(&instruction),
+			Some(&payer.pubkey()),
+			&Hash::new_unique(),
+		));
+		let v0 = VersionedMessage::V0(v0::Message::try_compile(
+			&payer.pubkey(),
+			std::slice::from_ref(&instruction),
+			&[],
+			Hash::new_unique(),
+		)?);
+
+		for message in [legacy, v0] {
+			let mut transaction = VersionedTransaction::new_unsigned(message);
+			transaction.try_sign(&[&payer], None)?;
+			let blockhash = Hash::new_unique();
+			let replaced = transaction.with_new_blockhash(blockhash);
+
+			assert_eq!(*replaced.message.recent_blockhash(), blockhash);
+			assert_eq!(replaced.missing_signers(), vec![payer.pubkey()]);
+			assert_eq!(
+				replaced.message.instructions(),
+				transaction.message.instructions()
+			);
+			assert!(transaction.is_signed());
+			assert!(!transaction.message.uses_durable_nonce());
+		}
+
+		let message = Message::new_with_nonce(
+			vec![instruction],
+			Some(&payer.pubkey()),
+			&nonce,
+			&payer.pubkey(),
+		);
+		assert!(VersionedMessage::Legacy(message).uses_durable_nonce());
+
+		Ok(())
+	}
+
+	#[test]
+	fn transaction_at_packet_limit() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let mut instruction = transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+		let compile = |instruction: &Instruction| {
+			VersionedTransaction::new_unsigned_v0(
+				&payer.pubkey(),
+				std::slice::from_ref(instruction),
+				&[],
+				Hash::new_unique(),
+			)
+		};
+		instruction.data = vec![0; 200];
+		let size = compile(&instruction)?.serialized_size();
+		instruction.data = vec![0; 200 + PACKET_DATA_SIZE - size];
+
+		let mut transaction = compile(&instruction)?;
+		let unsigned_size = transaction.message.size_with_signatures(1);
+		transaction.try_sign(&[&payer], None)?;
+
+		assert_eq!(unsigned_size, PACKET_DATA_SIZE);
+		assert_eq!(transaction.serialized_size(), PACKET_DATA_SIZE);
+		assert_eq!(
+			transaction.serialized_size(),
+			bincode::serialize(&transaction)?.len()
+		);
+		assert_eq!(transaction.fits_in_packet(), Ok(()));
+
+		instruction.data.push(0);
+		let transaction = compile(&instruction)?;
+		let message_size = transaction.message.serialize().len();
+		assert_eq!(
+			transaction.fits_in_packet(),
+			Err(TransactionTooLarge {
+				size: PACKET_DATA_SIZE + 1,
+				limit: PACKET_DATA_SIZE,
+				signatures_size: 65,
+				message_size,
+			})
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn compile_with_worthwhile_lookup_tables() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let program_id = Pubkey::new_unique();
+		let accounts = [(); 4].map(|()| Pubkey::new_unique());
+		let lone = Pubkey::new_unique();
+		let missing = Pubkey::new_unique();
+		let instruction = Instruction::new_with_bytes(
+			program_id,
+			&[1, 2, 3],
+			accounts
+				.iter()
+				.chain([&lone, &missing])
+				.map(|pubkey| AccountMeta::new(*pubkey, false))
+				.collect(),
+		);
+		let shared = AddressLookupTableAccount {
+			key: Pubkey::new_unique(),
+			addresses: [program_id].into_iter().chain(accounts).collect(),
+		};
+		let lone_table = AddressLookupTableAccount {
+			key: Pubkey::new_unique(),
+			addresses: vec![lone],
+		};
+		let blockhash = Hash::new_unique();
+		let tables = [lone_table, shared.clone()];
+
+		let (message, report) = VersionedMessage::try_compile_with_lookup_tables(
+			&payer,
+			std::slice::from_ref(&instruction),
+			&tables,
+			blockhash,
+		)?;
+		let expected = v0::Message::try_compile(
+			&payer,
+			std::slice::from_ref(&instruction),
+			std::slice::from_ref(&shared),
+			blockhash,
+		)?;
+		let unoptimized =
+			v0::Message::try_compile(&payer, std::slice::from_ref(&instruction), &[], blockhash)?;
+
+		assert_eq!(message, VersionedMessage::V0(expected.clone()));
+		assert_eq!(report.tables, vec![shared.key]);
+		assert_eq!(
+			report.saved_bytes,
+			v0_message_size(&unoptimized) - v0_message_size(&expected)
+		);
+		assert_eq!(
+			report.static_keys,
+			vec![
+				(payer, StaticKeyReason::Signer),
+				(lone, StaticKeyReason::TableNotUsed),
+				(missing, StaticKeyReason::NotInTable),
+				(program_id, StaticKeyReason::Program),
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn decompile_round_trip() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let authority = Pubkey::new_unique();
+		let program_id = Pubkey::new_unique();
+		let writable = Pubkey::new_unique();
+		let readonly = Pubkey::new_unique();
+		let instructions = [
+			ComputeBudgetInstruction::set_compute_unit_limit(100_000),
+			Instruction::new_with_bytes(
+				program_id,
+				&[1, 2, 3],
+				vec![
+					AccountMeta::new(writable, false),
+					AccountMeta::new_readonly(authority, true),
+					AccountMeta::new_readonly(readonly, false),
+				],
+			),
+			transfer(&payer, &writable, 10),
+		];
+		let blockhash = Hash::new_unique();
+
+		let legacy = VersionedMessage::Legacy(Message::new_with_blockhash(
+			&instructions,
+			Some(&payer),
+			&blockhash,
+		));
+		let decompiled = legacy.decompile(None)?;
+		let recompiled = VersionedMessage::Legacy(Message::new_with_blockhash(
+			&decompiled,
+			Some(&payer),
+			&blockhash,
+		));
+		assert_eq!(
+			decompiled[1].accounts[1],
+			AccountMeta::new_readonly(authority, true)
+		);
+		assert_eq!(recompiled.serialize(), legacy.serialize());
+
+		let table = AddressLookupTableAccount {
+			key: Pubkey::new_unique(),
+			addresses: vec![readonly, writable, program_id],
+		};
+		let v0 = VersionedMessage::V0(v0::Message::try_compile(
+			&payer,
+			&instructions,
+			std::slice::from_ref(&table),
+			blockhash,
+		)?);
+		let loaded_addresses = LoadedAddresses {
+			writable: vec![writable],
+			readonly: vec![readonly],
+		};
+		let decompiled = v0.decompile(Some(&loaded_addresses))?;
+		let recompiled = VersionedMessage::V0(v0::Message::try_compile(
+			&payer,
+			&decompiled,
+			std::slice::from_ref(&table),
+			blockhash,
+		)?);
+		assert_eq!(decompiled[1].accounts[0], AccountMeta::new(writable, false));
+		assert_eq!(recompiled.serialize(), v0.serialize());
+
+		assert_eq!(
+			v0.decompile(None),
+			Err(DecompileError::MissingLoadedAddresses)
+		);
+		assert_eq!(
+			v0.decompile(Some(&LoadedAddresses::default())),
+			Err(DecompileError::WritableAddressesMismatch {
+				expected: 1,
+				actual: 0,
+			})
+		);
+
+		Ok(())
+	}
+}
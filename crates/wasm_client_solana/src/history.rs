@@ -0,0 +1,660 @@
+//! Export the transaction history of an address as CSV or JSON lines.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io;
+
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use futures::future;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_with::DisplayFromStr;
+use serde_with::serde_as;
+use solana_sdk::clock::Slot;
+use solana_sdk::clock::UnixTimestamp;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use typed_builder::TypedBuilder;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::SolanaRpcClient;
+use crate::rpc_config::GetConfirmedSignaturesForAddress2Config;
+use crate::rpc_config::RpcTransactionConfig;
+use crate::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use crate::solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use crate::solana_transaction_status::UiTransactionEncoding;
+use crate::solana_transaction_status::UiTransactionStatusMeta;
+use crate::solana_transaction_status::extract_and_fmt_memos;
+
+/// The number of decimals of a lamport balance in SOL.
+const SOL_DECIMALS: u8 = 9;
+
+/// The columns written by [`HistoryExportFormat::Csv`].
+const CSV_HEADER: &str = "timestamp,signature,slot,status,fee,sol_delta,token_deltas,memo,error";
+
+/// The transactions exported by [`SolanaRpcClient::export_address_history`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, TypedBuilder)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct AddressHistoryConfig {
+	/// Start from the transaction before this signature instead of the
+	/// newest transaction.
+	pub before: Option<Signature>,
+	/// Stop at this signature, which isn't exported.
+	pub until: Option<Signature>,
+	/// Skip transactions with a block time before this unix timestamp.
+	pub start_time: Option<UnixTimestamp>,
+	/// Skip transactions with a block time after this unix timestamp.
+	pub end_time: Option<UnixTimestamp>,
+	/// The maximum number of transactions read, including those skipped by
+	/// the time range.
+	pub limit: Option<usize>,
+	/// The commitment of the signatures and transactions, which must be at
+	/// least `confirmed`.
+	pub commitment: Option<CommitmentConfig>,
+}
+
+/// The format of the lines written by
+/// [`SolanaRpcClient::export_address_history`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryExportFormat {
+	/// Comma separated values with a header line. Amounts are written in
+	/// whole units of SOL and of each token.
+	Csv,
+	/// One [`AddressHistoryRow`] serialized as json per line. Amounts are
+	/// written in lamports and raw token units.
+	JsonLines,
+}
+
+/// Whether the transaction of an [`AddressHistoryRow`] succeeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AddressHistoryStatus {
+	Success,
+	Failed,
+	/// The transaction couldn't be fetched, usually because the node pruned
+	/// it. Only the fields from the signature are set.
+	Unavailable,
+}
+
+impl AddressHistoryStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Success => "success",
+			Self::Failed => "failed",
+			Self::Unavailable => "unavailable",
+		}
+	}
+}
+
+/// The change of the balance of one mint held by the address.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenDelta {
+	#[serde_as(as = "DisplayFromStr")]
+	pub mint: Pubkey,
+	pub decimals: u8,
+	/// The change summed over every token account of the mint owned by the
+	/// address.
+	pub delta_raw: i128,
+}
+
+impl TokenDelta {
+	/// The change in whole tokens, such as `-1.5`.
+	pub fn ui_delta(&self) -> String {
+		format_amount(self.delta_raw, self.decimals)
+	}
+}
+
+/// A transaction reduced to the columns of an address history export.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressHistoryRow {
+	#[serde_as(as = "DisplayFromStr")]
+	pub signature: Signature,
+	pub slot: Slot,
+	pub block_time: Option<UnixTimestamp>,
+	pub status: AddressHistoryStatus,
+	/// The fee paid by the fee payer in lamports, which may not be the
+	/// address.
+	pub fee: Option<u64>,
+	/// The change of the lamport balance of the address, which includes the
+	/// fee when the address paid it.
+	pub lamport_delta: Option<i128>,
+	/// The changes of the token balances owned by the address, ordered by
+	/// mint.
+	pub token_deltas: Vec<TokenDelta>,
+	/// The memos of the transaction formatted as `[len] text` and joined by
+	/// `; `.
+	pub memo: Option<String>,
+	/// The error of a failed transaction, or why an unavailable transaction
+	/// couldn't be fetched.
+	pub error: Option<String>,
+}
+
+impl AddressHistoryRow {
+	/// Reduce the `transaction` to the changes it made to the `address`.
+	pub fn from_transaction(
+		address: &Pubkey,
+		signature: Signature,
+		transaction: &EncodedConfirmedTransactionWithStatusMeta,
+	) -> Self {
+		let encoded = &transaction.transaction;
+		let meta = encoded.meta.as_ref();
+		let error = meta.and_then(|meta| meta.err.as_ref());
+		let lamport_delta = meta.and_then(|meta| {
+			let index = encoded
+				.account_keys()?
+				.iter()
+				.position(|account| account.pubkey == *address)?;
+			let pre = meta.pre_balances.get(index)?;
+			let post = meta.post_balances.get(index)?;
+
+			Some(i128::from(*post) - i128::from(*pre))
+		});
+		let mut token_deltas = BTreeMap::<Pubkey, TokenDelta>::new();
+
+		for change in meta
+			.map(UiTransactionStatusMeta::token_balance_changes)
+			.unwrap_or_default()
+			.into_iter()
+			.filter(|change| change.owner.as_ref() == Some(address))
+		{
+			let decimals = change
+				.post
+				.as_ref()
+				.or(change.pre.as_ref())
+				.map_or(0, |amount| amount.decimals);
+			token_deltas
+				.entry(change.mint)
+				.or_insert(TokenDelta {
+					mint: change.mint,
+					decimals,
+					delta_raw: 0,
+				})
+				.delta_raw += change.delta_raw;
+		}
+
+		Self {
+			signature,
+			slot: transaction.slot,
+			block_time: transaction.block_time,
+			status: if error.is_some() {
+				AddressHistoryStatus::Failed
+			} else {
+				AddressHistoryStatus::Success
+			},
+			fee: meta.map(|meta| meta.fee),
+			lamport_delta,
+			token_deltas: token_deltas.into_values().collect(),
+			memo: extract_and_fmt_memos(encoded),
+			error: error.map(ToString::to_string),
+		}
+	}
+
+	/// A row for a transaction which couldn't be fetched, filled from its
+	/// `status` in the signature list.
+	pub fn unavailable(
+		status: RpcConfirmedTransactionStatusWithSignature,
+		error: &ClientError,
+	) -> Self {
+		Self {
+			signature: status.signature,
+			slot: status.slot,
+			block_time: status.block_time,
+			status: AddressHistoryStatus::Unavailable,
+			fee: None,
+			lamport_delta: None,
+			token_deltas: vec![],
+			memo: status.memo,
+			error: Some(error.to_string()),
+		}
+	}
+
+	/// The row as a line of [`HistoryExportFormat::Csv`].
+	pub fn to_csv(&self) -> String {
+		let timestamp = self
+			.block_time
+			.and_then(|time| DateTime::from_timestamp(time, 0))
+			.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true));
+		let token_deltas = self
+			.token_deltas
+			.iter()
+			.map(|delta| format!("{}:{}", delta.mint, delta.ui_delta()))
+			.collect::<Vec<_>>()
+			.join(" ");
+		let columns = [
+			timestamp.unwrap_or_default(),
+			self.signature.to_string(),
+			self.slot.to_string(),
+			self.status.as_str().to_string(),
+			self.fee
+				.map(|fee| format_amount(i128::from(fee), SOL_DECIMALS))
+				.unwrap_or_default(),
+			self.lamport_delta
+				.map(|delta| format_amount(delta, SOL_DECIMALS))
+				.unwrap_or_default(),
+			token_deltas,
+			self.memo.clone().unwrap_or_default(),
+			self.error.clone().unwrap_or_default(),
+		];
+
+		let mut line = String::new();
+
+		for (index, column) in columns.iter().enumerate() {
+			if index > 0 {
+				line.push(',');
+			}
+
+			write_csv_field(&mut line, column);
+		}
+
+		line
+	}
+
+	/// The row as a line of [`HistoryExportFormat::JsonLines`].
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(self).unwrap_or_default()
+	}
+}
+
+/// Receives each line of an export without the line break.
+///
+/// Closures receiving the line work on every target, including the browser
+/// where there is no file to write to. Wrap a [`std::io::Write`] in an
+/// [`IoSink`] to write the lines to a file or buffer.
+pub trait HistorySink {
+	/// Write the `line`.
+	///
+	/// # Errors
+	///
+	/// An error stops the export and is returned by
+	/// [`SolanaRpcClient::export_address_history`].
+	fn write_line(&mut self, line: &str) -> io::Result<()>;
+}
+
+impl<F: FnMut(&str) -> io::Result<()>> HistorySink for F {
+	fn write_line(&mut self, line: &str) -> io::Result<()> {
+		self(line)
+	}
+}
+
+/// A [`HistorySink`] which writes every line followed by `\n` to the
+/// wrapped writer.
+#[derive(Debug, Clone, Default)]
+pub struct IoSink<W>(pub W);
+
+impl<W: io::Write> HistorySink for IoSink<W> {
+	fn write_line(&mut self, line: &str) -> io::Result<()> {
+		writeln!(self.0, "{line}")
+	}
+}
+
+impl SolanaRpcClient {
+	/// Stream a row for every transaction which used the `address`, newest
+	/// first.
+	///
+	/// The signatures are read page by page and up to
+	/// [`SolanaRpcClient::max_concurrent_requests`] transactions are fetched
+	/// at once. A transaction which can't be fetched, for example because
+	/// the node pruned it, yields an [`AddressHistoryStatus::Unavailable`]
+	/// row instead of an error. Only a failure to read the signatures ends
+	/// the stream with an error.
+	pub fn address_history(
+		&self,
+		address: &Pubkey,
+		config: AddressHistoryConfig,
+	) -> impl Stream<Item = ClientResult<AddressHistoryRow>> + use<> {
+		let AddressHistoryConfig {
+			before,
+			until,
+			start_time,
+			end_time,
+			limit,
+			commitment,
+		} = config;
+		let signatures_config = GetConfirmedSignaturesForAddress2Config {
+			before,
+			until,
+			limit,
+			commitment,
+		};
+		let transaction_config = RpcTransactionConfig {
+			encoding: Some(UiTransactionEncoding::Base64),
+			commitment,
+			max_supported_transaction_version: Some(0),
+		};
+		let rpc = self.clone();
+		let address = *address;
+
+		self.stream_signatures_for_address(&address, signatures_config)
+			.try_take_while(move |status| {
+				future::ready(Ok(status
+					.block_time
+					.zip(start_time)
+					.is_none_or(|(block_time, start_time)| block_time >= start_time)))
+			})
+			.try_filter(move |status| {
+				future::ready(
+					status
+						.block_time
+						.zip(end_time)
+						.is_none_or(|(block_time, end_time)| block_time <= end_time),
+				)
+			})
+			.map_ok(move |status| {
+				let rpc = rpc.clone();
+
+				async move {
+					let row = match rpc
+						.get_transaction_with_config(&status.signature, transaction_config)
+						.await
+					{
+						Ok(transaction) => {
+							AddressHistoryRow::from_transaction(
+								&address,
+								status.signature,
+								&transaction,
+							)
+						}
+						Err(error) => AddressHistoryRow::unavailable(status, &error),
+					};
+
+					Ok(row)
+				}
+			})
+			.try_buffered(self.max_concurrent_requests().max(1))
+	}
+
+	/// Write the transaction history of the `address` to the `sink` in the
+	/// `format` and return the number of transactions written.
+	///
+	/// The rows are the ones yielded by [`SolanaRpcClient::address_history`]
+	/// so unavailable transactions are written with an error instead of
+	/// stopping the export.
+	///
+	/// # Errors
+	///
+	/// Returns an error when the signatures can't be read or the `sink`
+	/// fails. The lines written before the error are kept.
+	pub async fn export_address_history(
+		&self,
+		address: &Pubkey,
+		config: AddressHistoryConfig,
+		format: HistoryExportFormat,
+		sink: &mut impl HistorySink,
+	) -> ClientResult<usize> {
+		let write = |sink: &mut dyn HistorySink, line: &str| {
+			sink.write_line(line)
+				.map_err(|error| ClientError::Other(error.to_string()))
+		};
+
+		if format == HistoryExportFormat::Csv {
+			write(sink, CSV_HEADER)?;
+		}
+
+		let mut rows = self.address_history(address, config).boxed_local();
+		let mut count = 0;
+
+		while let Some(row) = rows.try_next().await? {
+			let line = match format {
+				HistoryExportFormat::Csv => row.to_csv(),
+				HistoryExportFormat::JsonLines => row.to_json(),
+			};
+
+			write(sink, &line)?;
+			count += 1;
+		}
+
+		Ok(count)
+	}
+}
+
+/// Format the `raw` amount with `decimals` places, trimming trailing zeros.
+fn format_amount(raw: i128, decimals: u8) -> String {
+	let decimals = usize::from(decimals);
+	let sign = if raw < 0 { "-" } else { "" };
+	let digits = format!("{:0>width$}", raw.unsigned_abs(), width = decimals + 1);
+	let (integer, fraction) = digits.split_at(digits.len() - decimals);
+	let fraction = fraction.trim_end_matches('0');
+
+	if fraction.is_empty() {
+		format!("{sign}{integer}")
+	} else {
+		format!("{sign}{integer}.{fraction}")
+	}
+}
+
+/// Append the `field` to the `line`, quoting it when it contains a comma,
+/// quote or line break.
+fn write_csv_field(line: &mut String, field: &str) {
+	if field.contains([',', '"', '\n', '\r']) {
+		let _ = write!(line, "\"{}\"", field.replace('"', "\"\""));
+	} else {
+		line.push_str(field);
+	}
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+	use assert2::check;
+	use base64::Engine;
+	use base64::prelude::BASE64_STANDARD;
+	use futures::executor::block_on;
+	use serde_json::Value;
+	use serde_json::json;
+	use solana_sdk::hash::Hash;
+	use solana_sdk::signature::Keypair;
+	use solana_sdk::signer::Signer;
+	use solana_sdk::transaction::VersionedTransaction;
+	use solana_system_interface::instruction::transfer;
+
+	use super::*;
+	use crate::MockRpcProvider;
+	use crate::VersionedTransactionExtension;
+
+	fn token_balance(account_index: usize, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Value {
+		json!({
+			"accountIndex": account_index,
+			"mint": mint.to_string(),
+			"owner": owner.to_string(),
+			"programId": spl_token::id().to_string(),
+			"uiTokenAmount": {
+				"amount": amount.to_string(),
+				"decimals": 6,
+				"uiAmount": null,
+				"uiAmountString": "",
+			},
+		})
+	}
+
+	#[test]
+	fn format_amounts() {
+		check!(format_amount(0, 9) == "0");
+		check!(format_amount(-1_500_000_000, 9) == "-1.5");
+		check!(format_amount(5000, 9) == "0.000005");
+		check!(format_amount(42, 0) == "42");
+	}
+
+	#[test]
+	fn csv_fields_are_quoted() {
+		let mut line = String::new();
+		write_csv_field(&mut line, "[12] pay, \"now\"");
+
+		check!(line == "\"[12] pay, \"\"now\"\"\"");
+	}
+
+	#[test]
+	fn export_address_history() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let address = payer.pubkey();
+		let token_account = Pubkey::new_unique();
+		let mint = Pubkey::new_unique();
+		let mut transaction = VersionedTransaction::new_unsigned_v0(
+			&address,
+			&[
+				transfer(&address, &Pubkey::new_unique(), 1_000_000_000),
+				transfer(&address, &token_account, 1),
+				spl_memo::build_memo(b"invoice, 42", &[]),
+			],
+			&[],
+			Hash::new_unique(),
+		)?;
+		transaction.try_sign(&[&payer], None)?;
+		let keys = transaction.message.static_account_keys();
+		let token_index = keys.iter().position(|key| *key == token_account).unwrap();
+		let mut pre_balances = vec![0_u64; keys.len()];
+		let mut post_balances = vec![0_u64; keys.len()];
+		pre_balances[0] = 3_000_000_000;
+		post_balances[0] = 1_999_994_999;
+		let signatures = [transaction.signatures[0], Signature::new_unique()];
+		let signature_statuses = json!([
+			{
+				"signature": signatures[0].to_string(),
+				"slot": 10,
+				"err": null,
+				"memo": "[11] invoice, 42",
+				"blockTime": 1_700_000_000,
+				"confirmationStatus": "finalized",
+			},
+			{
+				"signature": signatures[1].to_string(),
+				"slot": 5,
+				"err": null,
+				"memo": null,
+				"blockTime": 1_600_000_000,
+				"confirmationStatus": "finalized",
+			},
+		]);
+		let confirmed_transaction = json!({
+			"slot": 10,
+			"blockTime": 1_700_000_000,
+			"version": 0,
+			"transaction": [
+				BASE64_STANDARD.encode(bincode::serialize(&transaction)?),
+				"base64",
+			],
+			"meta": {
+				"err": null,
+				"status": { "Ok": null },
+				"fee": 5001,
+				"preBalances": pre_balances,
+				"postBalances": post_balances,
+				"preTokenBalances": [token_balance(token_index, &mint, &address, 2_000_000)],
+				"postTokenBalances": [token_balance(token_index, &mint, &address, 500_000)],
+				"loadedAddresses": { "writable": [], "readonly": [] },
+			},
+		});
+		let provider = MockRpcProvider::new()
+			.with_result("getSignaturesForAddress", &signature_statuses)
+			.with_result("getTransaction", &confirmed_transaction)
+			.with_result("getTransaction", Value::Null);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+
+		let mut lines = Vec::new();
+		let count = block_on(rpc.export_address_history(
+			&address,
+			AddressHistoryConfig::default(),
+			HistoryExportFormat::Csv,
+			&mut |line: &str| {
+				lines.push(line.to_string());
+				Ok(())
+			},
+		))?;
+
+		check!(count == 2);
+		check!(lines[0] == CSV_HEADER);
+		check!(
+			lines[1]
+				== format!(
+					"2023-11-14T22:13:20Z,{},10,success,0.000005001,-1.000005001,{mint}:-1.5,\"\
+					 [11] invoice, 42\",",
+					signatures[0]
+				)
+		);
+		check!(lines[2].starts_with(&format!(
+			"2020-09-13T12:26:40Z,{},5,unavailable,,,,,",
+			signatures[1]
+		)));
+		check!(lines[2].contains("not found"));
+
+		let provider = MockRpcProvider::new()
+			.with_result("getSignaturesForAddress", &signature_statuses)
+			.with_result("getTransaction", &confirmed_transaction);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+		let mut buffer = IoSink(Vec::new());
+		let config = AddressHistoryConfig::builder()
+			.start_time(1_650_000_000)
+			.build();
+		block_on(rpc.export_address_history(
+			&address,
+			config,
+			HistoryExportFormat::JsonLines,
+			&mut buffer,
+		))?;
+		let rows = String::from_utf8(buffer.0)?
+			.lines()
+			.map(serde_json::from_str)
+			.collect::<Result<Vec<AddressHistoryRow>, _>>()?;
+
+		check!(rows.len() == 1);
+		check!(rows[0].lamport_delta == Some(-1_000_005_001));
+		check!(
+			rows[0].token_deltas
+				== [TokenDelta {
+					mint,
+					decimals: 6,
+					delta_raw: -1_500_000,
+				}]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn signatures_are_paginated() -> anyhow::Result<()> {
+		let page = |count: usize| {
+			(0..count)
+				.map(|slot| {
+					json!({
+						"signature": Signature::new_unique().to_string(),
+						"slot": slot,
+						"err": null,
+						"memo": null,
+						"blockTime": null,
+						"confirmationStatus": "finalized",
+					})
+				})
+				.collect::<Vec<_>>()
+		};
+		let provider = MockRpcProvider::new()
+			.with_result("getSignaturesForAddress", page(1000))
+			.with_result("getSignaturesForAddress", page(3));
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let config = GetConfirmedSignaturesForAddress2Config {
+			limit: Some(1500),
+			..Default::default()
+		};
+
+		let signatures = block_on(
+			rpc.stream_signatures_for_address(&Pubkey::new_unique(), config)
+				.try_collect::<Vec<_>>(),
+		)?;
+		check!(signatures.len() == 1003);
+
+		let requests = provider.requests();
+		check!(requests.len() == 2);
+		check!(requests[0].1[1]["limit"] == 1000);
+		check!(requests[1].1[1]["limit"] == 500);
+		check!(requests[1].1[1]["before"] == signatures[999].signature.to_string());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,173 @@
+// The shims generated by `wasm_bindgen` for async methods trip the lint.
+#![allow(tail_expr_drop_order)]
+
+use std::str::FromStr;
+
+use js_sys::Reflect;
+use serde::Serialize;
+use serde_wasm_bindgen::Serializer;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use wasm_bindgen::prelude::*;
+
+use crate::ClientError;
+use crate::SolanaRpcClient;
+use crate::solana_account_decoder::UiAccountEncoding;
+use crate::solana_account_decoder::encode_ui_account;
+
+/// The [`SolanaRpcClient`] for javascript and typescript.
+///
+/// Pubkeys and signatures are passed as base58 strings and responses are
+/// returned as plain json values. A failed request rejects with an `Error`
+/// whose `code` names the kind of failure, for example `"Rpc"` or
+/// `"InvalidPubkey"`. Errors returned by the node also carry the json rpc
+/// error code as `rpcCode`.
+///
+/// ```js
+/// import { JsSolanaClient } from "wasm_client_solana";
+///
+/// const client = new JsSolanaClient("https://api.devnet.solana.com");
+/// const lamports = await client.getBalance("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+/// ```
+#[wasm_bindgen]
+pub struct JsSolanaClient {
+	rpc: SolanaRpcClient,
+}
+
+#[wasm_bindgen]
+impl JsSolanaClient {
+	/// Create a client for the rpc at the `url`.
+	#[wasm_bindgen(constructor)]
+	pub fn new(url: &str) -> Self {
+		Self {
+			rpc: SolanaRpcClient::new(url),
+		}
+	}
+
+	/// The url of the rpc.
+	#[wasm_bindgen(getter)]
+	pub fn url(&self) -> String {
+		self.rpc.url()
+	}
+
+	/// The account of the `pubkey` with its data encoded as base64, or
+	/// `null` when it doesn't exist.
+	#[wasm_bindgen(js_name = getAccountInfo)]
+	pub async fn get_account_info(&self, pubkey: String) -> Result<JsValue, JsValue> {
+		let pubkey = parse_pubkey(&pubkey)?;
+		let account = self
+			.rpc
+			.get_account_with_commitment(&pubkey, self.rpc.commitment_config())
+			.await
+			.map_err(|error| into_js_error(&error))?;
+		let account = account.map(|account| {
+			encode_ui_account(&pubkey, &account, UiAccountEncoding::Base64, None, None)
+		});
+
+		to_json_value(&account)
+	}
+
+	/// The lamports held by the account of the `pubkey`.
+	#[wasm_bindgen(js_name = getBalance)]
+	pub async fn get_balance(&self, pubkey: String) -> Result<u64, JsValue> {
+		let pubkey = parse_pubkey(&pubkey)?;
+
+		self.rpc
+			.get_balance(&pubkey)
+			.await
+			.map_err(|error| into_js_error(&error))
+	}
+
+	/// Send the bincode `transaction`, as returned by `serialize()` in
+	/// `@solana/web3.js`, and return its base58 signature.
+	#[wasm_bindgen(js_name = sendTransaction)]
+	pub async fn send_transaction(&self, transaction: Vec<u8>) -> Result<String, JsValue> {
+		let transaction = bincode::deserialize::<VersionedTransaction>(&transaction)
+			.map_err(|error| js_error("InvalidTransaction", &error.to_string()))?;
+		let signature = self
+			.rpc
+			.send_transaction(&transaction)
+			.await
+			.map_err(|error| into_js_error(&error))?;
+
+		Ok(signature.to_string())
+	}
+
+	/// The confirmed transaction with the `signature` in the json format of
+	/// the `getTransaction` rpc method.
+	#[wasm_bindgen(js_name = getTransaction)]
+	pub async fn get_transaction(&self, signature: String) -> Result<JsValue, JsValue> {
+		let signature = Signature::from_str(&signature)
+			.map_err(|error| js_error("InvalidSignature", &error.to_string()))?;
+		let transaction = self
+			.rpc
+			.get_transaction(&signature)
+			.await
+			.map_err(|error| into_js_error(&error))?;
+
+		to_json_value(&transaction)
+	}
+}
+
+impl From<SolanaRpcClient> for JsSolanaClient {
+	fn from(rpc: SolanaRpcClient) -> Self {
+		Self { rpc }
+	}
+}
+
+/// The `code` of the javascript error for each kind of [`ClientError`].
+fn error_code(error: &ClientError) -> &'static str {
+	match error {
+		ClientError::Rpc(_) => "Rpc",
+		ClientError::PreflightFailure(_) => "PreflightFailure",
+		ClientError::WebSocket(_) => "WebSocket",
+		ClientError::Wallet(_) => "Wallet",
+		ClientError::Nonce(_) => "Nonce",
+		ClientError::Stake(_) => "Stake",
+		ClientError::LookupTable(_) => "LookupTable",
+		ClientError::AccountDeserialize(_) => "AccountDeserialize",
+		ClientError::Simulation(_) => "Simulation",
+		ClientError::TransactionTooLarge(_) => "TransactionTooLarge",
+		ClientError::TransactionFailed(_) => "TransactionFailed",
+		ClientError::TransactionExpired { .. } => "TransactionExpired",
+		ClientError::TransactionNotFound { .. } => "TransactionNotFound",
+		ClientError::TransactionNotConfirmed { .. } => "TransactionNotConfirmed",
+		ClientError::Other(_) => "Other",
+	}
+}
+
+fn into_js_error(error: &ClientError) -> JsValue {
+	let value = js_error(error_code(error), &error.to_string());
+
+	if let Some(code) = error.rpc_error_code() {
+		// The code fits in an `f64` since json rpc codes are 32 bit integers.
+		#[allow(clippy::cast_precision_loss)]
+		let code = code.code() as f64;
+		let _ = Reflect::set(&value, &"rpcCode".into(), &code.into());
+	}
+
+	let _ = Reflect::set(&value, &"retryable".into(), &error.is_retryable().into());
+
+	value
+}
+
+/// A javascript `Error` with the `message` and the `code`.
+fn js_error(code: &str, message: &str) -> JsValue {
+	let value = JsValue::from(JsError::new(message));
+	let _ = Reflect::set(&value, &"code".into(), &code.into());
+
+	value
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey, JsValue> {
+	Pubkey::from_str(pubkey).map_err(|error| js_error("InvalidPubkey", &error.to_string()))
+}
+
+/// Convert the `value` into plain javascript objects, the same as
+/// `JSON.parse` would produce for the rpc response.
+fn to_json_value(value: &impl Serialize) -> Result<JsValue, JsValue> {
+	value
+		.serialize(&Serializer::json_compatible())
+		.map_err(|error| js_error("Other", &error.to_string()))
+}
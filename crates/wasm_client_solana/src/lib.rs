@@ -5,28 +5,52 @@ pub use solana_account_decoder_wasm as solana_account_decoder;
 pub use solana_transaction_status_client_types_wasm as solana_transaction_status_client_types;
 pub use solana_transaction_status_wasm as solana_transaction_status;
 
+pub use crate::blockhash_cache::*;
 pub use crate::client::*;
+pub use crate::cluster::*;
+pub use crate::consistent_read::*;
 pub use crate::constants::*;
 pub use crate::errors::*;
 pub use crate::extensions::*;
+#[cfg(feature = "js")]
+pub use crate::js_client::*;
 pub use crate::methods::*;
+pub use crate::priority_fees::*;
 pub use crate::providers::*;
 pub use crate::rpc_config::*;
+pub use crate::signer::*;
 pub use crate::solana_client::*;
+pub use crate::utils::TaskSet;
+pub use crate::utils::derive_associated_token_address;
+pub use crate::utils::derive_websocket_url;
 pub use crate::utils::spawn_local;
+pub use crate::utils::spawn_local_with_handle;
 
+mod blockhash_cache;
 mod client;
+mod cluster;
+mod consistent_read;
 mod constants;
 mod errors;
 mod extensions;
+pub mod history;
+#[cfg(feature = "js")]
+mod js_client;
+pub mod lookup_table;
 mod methods;
 pub mod nonce_utils;
+mod priority_fees;
 mod providers;
 pub mod rpc_config;
 pub mod rpc_filter;
 pub mod rpc_response;
 pub mod runtime;
+mod signature_watcher;
+mod signer;
+pub mod siws;
 mod solana_client;
+pub mod stake;
+pub mod transaction_errors;
 pub mod utils;
 
 pub mod prelude {
@@ -37,6 +61,7 @@ pub mod prelude {
 	pub use futures::TryStreamExt;
 	pub use wallet_standard::prelude::*;
 
+	pub use crate::AsyncSigner;
 	pub use crate::RpcProvider;
 	pub use crate::extensions::VersionedMessageExtension;
 	pub use crate::extensions::VersionedTransactionExtension;
@@ -0,0 +1,506 @@
+//! Address lookup table lifecycle helpers.
+//!
+//! A lookup table is created at an address derived from a recent slot,
+//! extended with up to [`LOOKUP_TABLE_MAX_ADDRESSES`] addresses, deactivated
+//! and finally closed once the deactivation slot has left the slot hashes
+//! sysvar. Each helper checks the parsed table state first and confirms its
+//! transactions before returning.
+
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::address_lookup_table::instruction;
+use solana_sdk::address_lookup_table::state::LOOKUP_TABLE_MAX_ADDRESSES;
+use solana_sdk::address_lookup_table::state::LookupTableMeta;
+use solana_sdk::address_lookup_table::state::LookupTableStatus;
+use solana_sdk::clock::Slot;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::slot_hashes::SlotHashes;
+use solana_sdk::sysvar;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::MAX_LOOKUP_ADDRESSES_PER_TRANSACTION;
+use crate::SolanaRpcClient;
+use crate::rpc_config::SendOptions;
+use crate::solana_account_decoder::parse_address_lookup_table::LookupTableAccountType;
+use crate::solana_account_decoder::parse_address_lookup_table::UiLookupTable;
+
+#[derive(Clone, Debug, Serialize, Deserialize, thiserror::Error, PartialEq, Eq)]
+pub enum LookupTableError {
+	#[error("the lookup table is not initialized")]
+	Uninitialized,
+	#[error("the lookup table is frozen")]
+	Frozen,
+	#[error("provided authority ({provided}) does not match lookup table authority ({expected})")]
+	InvalidAuthority { provided: Pubkey, expected: Pubkey },
+	#[error("the lookup table was deactivated in slot {slot}")]
+	Deactivated { slot: Slot },
+	#[error("the lookup table is not deactivated")]
+	NotDeactivated,
+	#[error(
+		"the lookup table was deactivated in slot {deactivation_slot} and can be closed in \
+		 {remaining_blocks} blocks"
+	)]
+	CoolingDown {
+		deactivation_slot: Slot,
+		remaining_blocks: usize,
+	},
+	#[error("the lookup table can't hold {addresses} addresses, the maximum is {max}")]
+	TooManyAddresses { addresses: usize, max: usize },
+}
+
+/// The lookup table changed by one of the lookup table helpers with the
+/// signatures of its confirmed transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupTableSignatures {
+	pub table: Pubkey,
+	pub signatures: Vec<Signature>,
+}
+
+fn deactivation_slot(table: &UiLookupTable) -> Option<Slot> {
+	table
+		.deactivation_slot
+		.parse::<Slot>()
+		.ok()
+		.filter(|slot| *slot != Slot::MAX)
+}
+
+impl SolanaRpcClient {
+	/// Create a lookup table owned by the `authority` and paid for by the
+	/// `payer`. The address is derived from a recent slot.
+	pub async fn create_lookup_table<P: Signer>(
+		&self,
+		authority: &Pubkey,
+		payer: &P,
+	) -> ClientResult<LookupTableSignatures> {
+		let recent_slot = self.get_slot().await?.saturating_sub(1);
+		let (instruction, table) =
+			instruction::create_lookup_table(*authority, payer.pubkey(), recent_slot);
+		let signature = self
+			.send_lookup_table_instructions(&[instruction], &payer.pubkey(), &[payer])
+			.await?;
+
+		Ok(LookupTableSignatures {
+			table,
+			signatures: vec![signature],
+		})
+	}
+
+	/// Add the `addresses` to the `table`, skipping those which it already
+	/// holds.
+	///
+	/// The addresses are added in chunks of
+	/// [`MAX_LOOKUP_ADDRESSES_PER_TRANSACTION`], one transaction each, and
+	/// this waits for a new block after the last one so the addresses can be
+	/// used straight away.
+	///
+	/// # Errors
+	///
+	/// Returns a [`LookupTableError`] when the `authority` can't extend the
+	/// table, it is deactivated or it can't hold every address.
+	pub async fn extend_lookup_table<A: Signer, P: Signer>(
+		&self,
+		table: &Pubkey,
+		authority: &A,
+		payer: &P,
+		addresses: &[Pubkey],
+	) -> ClientResult<LookupTableSignatures> {
+		let state = self
+			.get_lookup_table_state(table, &authority.pubkey())
+			.await?;
+
+		if let Some(slot) = deactivation_slot(&state) {
+			return Err(LookupTableError::Deactivated { slot }.into());
+		}
+
+		let mut new_addresses: Vec<Pubkey> = vec![];
+
+		for address in addresses {
+			if !state.addresses.contains(address) && !new_addresses.contains(address) {
+				new_addresses.push(*address);
+			}
+		}
+
+		let total = state.addresses.len() + new_addresses.len();
+
+		if total > LOOKUP_TABLE_MAX_ADDRESSES {
+			return Err(LookupTableError::TooManyAddresses {
+				addresses: total,
+				max: LOOKUP_TABLE_MAX_ADDRESSES,
+			}
+			.into());
+		}
+
+		let mut signatures = vec![];
+		let signers: [&dyn Signer; 2] = [payer, authority];
+
+		for chunk in new_addresses.chunks(MAX_LOOKUP_ADDRESSES_PER_TRANSACTION) {
+			let instruction = instruction::extend_lookup_table(
+				*table,
+				authority.pubkey(),
+				Some(payer.pubkey()),
+				chunk.to_vec(),
+			);
+			let signature = self
+				.send_lookup_table_instructions(&[instruction], &payer.pubkey(), &signers)
+				.await?;
+
+			signatures.push(signature);
+		}
+
+		if !signatures.is_empty() {
+			self.wait_for_new_block(1).await?;
+		}
+
+		Ok(LookupTableSignatures {
+			table: *table,
+			signatures,
+		})
+	}
+
+	/// Deactivate the `table`. It can no longer be extended or used by new
+	/// transactions and can be closed with
+	/// [`SolanaRpcClient::close_lookup_table`] after the cooldown.
+	///
+	/// # Errors
+	///
+	/// Returns a [`LookupTableError`] when the `authority` can't deactivate
+	/// the table or it is already deactivated.
+	pub async fn deactivate_lookup_table<A: Signer>(
+		&self,
+		table: &Pubkey,
+		authority: &A,
+	) -> ClientResult<LookupTableSignatures> {
+		let state = self
+			.get_lookup_table_state(table, &authority.pubkey())
+			.await?;
+
+		if let Some(slot) = deactivation_slot(&state) {
+			return Err(LookupTableError::Deactivated { slot }.into());
+		}
+
+		let instruction = instruction::deactivate_lookup_table(*table, authority.pubkey());
+		let signature = self
+			.send_lookup_table_instructions(&[instruction], &authority.pubkey(), &[authority])
+			.await?;
+
+		Ok(LookupTableSignatures {
+			table: *table,
+			signatures: vec![signature],
+		})
+	}
+
+	/// Close the deactivated `table` and send its lamports to the
+	/// `recipient`.
+	///
+	/// # Errors
+	///
+	/// Returns [`LookupTableError::CoolingDown`] while the deactivation slot
+	/// is still in the slot hashes sysvar and
+	/// [`LookupTableError::NotDeactivated`] when the table is still active.
+	pub async fn close_lookup_table<A: Signer>(
+		&self,
+		table: &Pubkey,
+		authority: &A,
+		recipient: &Pubkey,
+	) -> ClientResult<LookupTableSignatures> {
+		let state = self
+			.get_lookup_table_state(table, &authority.pubkey())
+			.await?;
+		let deactivation_slot =
+			deactivation_slot(&state).ok_or(LookupTableError::NotDeactivated)?;
+		let meta = LookupTableMeta {
+			deactivation_slot,
+			..LookupTableMeta::default()
+		};
+		let slot_hashes = self.get_slot_hashes().await?;
+
+		match meta.status(self.get_slot().await?, &slot_hashes) {
+			LookupTableStatus::Deactivated => {}
+			LookupTableStatus::Deactivating { remaining_blocks } => {
+				return Err(LookupTableError::CoolingDown {
+					deactivation_slot,
+					remaining_blocks,
+				}
+				.into());
+			}
+			LookupTableStatus::Activated => return Err(LookupTableError::NotDeactivated.into()),
+		}
+
+		let instruction = instruction::close_lookup_table(*table, authority.pubkey(), *recipient);
+		let signature = self
+			.send_lookup_table_instructions(&[instruction], &authority.pubkey(), &[authority])
+			.await?;
+
+		Ok(LookupTableSignatures {
+			table: *table,
+			signatures: vec![signature],
+		})
+	}
+
+	/// The parsed state of the `table`, checking that it can be changed by the
+	/// `authority`.
+	async fn get_lookup_table_state(
+		&self,
+		table: &Pubkey,
+		authority: &Pubkey,
+	) -> ClientResult<UiLookupTable> {
+		let LookupTableAccountType::LookupTable(state) =
+			self.get_address_lookup_table(table).await?
+		else {
+			return Err(LookupTableError::Uninitialized.into());
+		};
+		let expected = state.authority.ok_or(LookupTableError::Frozen)?;
+
+		if expected != *authority {
+			return Err(LookupTableError::InvalidAuthority {
+				provided: *authority,
+				expected,
+			}
+			.into());
+		}
+
+		Ok(state)
+	}
+
+	async fn get_slot_hashes(&self) -> ClientResult<SlotHashes> {
+		let account = self.get_account(&sysvar::slot_hashes::id()).await?;
+
+		bincode::deserialize(&account.data).map_err(|error| ClientError::Other(error.to_string()))
+	}
+
+	async fn send_lookup_table_instructions<T: Signer + ?Sized>(
+		&self,
+		instructions: &[Instruction],
+		payer: &Pubkey,
+		signers: &[&T],
+	) -> ClientResult<Signature> {
+		self.send_and_confirm_transaction_with_options(
+			instructions,
+			payer,
+			signers,
+			SendOptions::default(),
+		)
+		.await
+	}
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+	use std::borrow::Cow;
+
+	use assert2::check;
+	use assert2::let_assert;
+	use base64::Engine;
+	use base64::prelude::BASE64_STANDARD;
+	use futures::executor::block_on;
+	use serde_json::Value;
+	use serde_json::json;
+	use solana_sdk::address_lookup_table::state::AddressLookupTable;
+	use solana_sdk::address_lookup_table::state::LookupTableMeta;
+	use solana_sdk::hash::Hash;
+	use solana_sdk::signature::Keypair;
+	use solana_sdk::transaction::VersionedTransaction;
+
+	use super::*;
+	use crate::MockRpcProvider;
+	use crate::VersionedTransactionExtension;
+
+	fn table_account(
+		authority: Option<Pubkey>,
+		deactivation_slot: Slot,
+		addresses: &[Pubkey],
+	) -> Value {
+		let table = AddressLookupTable {
+			meta: LookupTableMeta {
+				deactivation_slot,
+				authority,
+				..LookupTableMeta::default()
+			},
+			addresses: Cow::Borrowed(addresses),
+		};
+		let data = table.serialize_for_tests().unwrap();
+
+		json!({
+			"context": { "slot": 1 },
+			"value": {
+				"lamports": 1_000_000,
+				"data": [BASE64_STANDARD.encode(&data), "base64"],
+				"owner": solana_sdk::address_lookup_table::program::id().to_string(),
+				"executable": false,
+				"rentEpoch": 0,
+				"space": data.len(),
+			},
+		})
+	}
+
+	fn slot_hashes_account(slots: impl Iterator<Item = Slot>) -> Value {
+		let slot_hashes = slots
+			.map(|slot| (slot, Hash::new_unique()))
+			.collect::<Vec<_>>();
+		let data = bincode::serialize(&SlotHashes::new(&slot_hashes)).unwrap();
+
+		json!({
+			"context": { "slot": 1 },
+			"value": {
+				"lamports": 1_000_000,
+				"data": [BASE64_STANDARD.encode(&data), "base64"],
+				"owner": sysvar::id().to_string(),
+				"executable": false,
+				"rentEpoch": 0,
+				"space": data.len(),
+			},
+		})
+	}
+
+	fn latest_blockhash(blockhash: Hash, last_valid_block_height: u64) -> Value {
+		json!({
+			"context": { "slot": 1 },
+			"value": {
+				"blockhash": blockhash.to_string(),
+				"lastValidBlockHeight": last_valid_block_height,
+			},
+		})
+	}
+
+	#[test]
+	fn extend_in_chunks() -> anyhow::Result<()> {
+		let authority = Keypair::new();
+		let table = Pubkey::new_unique();
+		let existing = Pubkey::new_unique();
+		let mut addresses = vec![existing];
+		addresses.extend((0..44).map(|_| Pubkey::new_unique()));
+		addresses.push(addresses[1]);
+		let blockhash = Hash::new_unique();
+		let signatures = [&addresses[1..31], &addresses[31..45]].map(|chunk| {
+			let instruction = instruction::extend_lookup_table(
+				table,
+				authority.pubkey(),
+				Some(authority.pubkey()),
+				chunk.to_vec(),
+			);
+			let mut transaction = VersionedTransaction::new_unsigned_v0(
+				&authority.pubkey(),
+				&[instruction],
+				&[],
+				blockhash,
+			)
+			.unwrap();
+			transaction.try_sign(&[&authority], None).unwrap();
+
+			transaction.signatures[0]
+		});
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getAccountInfo",
+				table_account(Some(authority.pubkey()), Slot::MAX, &[existing]),
+			)
+			.with_result("getLatestBlockhash", latest_blockhash(blockhash, 100))
+			.with_result("getLatestBlockhash", latest_blockhash(blockhash, 100))
+			.with_result("getLatestBlockhash", latest_blockhash(blockhash, 100))
+			.with_result("getLatestBlockhash", latest_blockhash(blockhash, 101))
+			.with_result("sendTransaction", signatures[0].to_string())
+			.with_result("sendTransaction", signatures[1].to_string())
+			.with_result(
+				"getSignatureStatuses",
+				json!({
+					"context": { "slot": 1 },
+					"value": [{
+						"slot": 1,
+						"confirmations": null,
+						"err": null,
+						"status": { "Ok": null },
+						"confirmationStatus": "finalized",
+					}],
+				}),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+
+		let result = block_on(rpc.extend_lookup_table(&table, &authority, &authority, &addresses))?;
+
+		check!(result.table == table);
+		check!(result.signatures == signatures);
+
+		let sent = provider
+			.requests()
+			.into_iter()
+			.filter(|(method, _)| method == "sendTransaction")
+			.count();
+		check!(sent == 2);
+
+		Ok(())
+	}
+
+	#[test]
+	fn extend_checks_the_table() {
+		let authority = Keypair::new();
+		let other = Pubkey::new_unique();
+		let table = Pubkey::new_unique();
+		let full: Vec<Pubkey> = (0..250).map(|_| Pubkey::new_unique()).collect();
+		let addresses: Vec<Pubkey> = (0..10).map(|_| Pubkey::new_unique()).collect();
+		let provider = MockRpcProvider::new()
+			.with_result("getAccountInfo", table_account(Some(other), Slot::MAX, &[]))
+			.with_result("getAccountInfo", table_account(None, Slot::MAX, &[]))
+			.with_result(
+				"getAccountInfo",
+				table_account(Some(authority.pubkey()), 5, &[]),
+			)
+			.with_result(
+				"getAccountInfo",
+				table_account(Some(authority.pubkey()), Slot::MAX, &full),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+		let extend =
+			|| block_on(rpc.extend_lookup_table(&table, &authority, &authority, &addresses));
+
+		let_assert!(
+			Err(ClientError::LookupTable(
+				LookupTableError::InvalidAuthority { expected, .. }
+			)) = extend()
+		);
+		check!(expected == other);
+		check!(let Err(ClientError::LookupTable(LookupTableError::Frozen)) = extend());
+		check!(
+			let Err(ClientError::LookupTable(LookupTableError::Deactivated { slot: 5 })) = extend()
+		);
+		check!(
+			let Err(ClientError::LookupTable(LookupTableError::TooManyAddresses {
+				addresses: 260,
+				max: 256,
+			})) = extend()
+		);
+	}
+
+	#[test]
+	fn close_waits_for_the_cooldown() {
+		let authority = Keypair::new();
+		let table = Pubkey::new_unique();
+		let recipient = Pubkey::new_unique();
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getAccountInfo",
+				table_account(Some(authority.pubkey()), Slot::MAX, &[]),
+			)
+			.with_result(
+				"getAccountInfo",
+				table_account(Some(authority.pubkey()), 1000, &[]),
+			)
+			.with_result("getAccountInfo", slot_hashes_account(689..1200))
+			.with_result("getSlot", 1200);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		check!(
+			let Err(ClientError::LookupTable(LookupTableError::NotDeactivated)) =
+				block_on(rpc.close_lookup_table(&table, &authority, &recipient))
+		);
+		check!(
+			let Err(ClientError::LookupTable(LookupTableError::CoolingDown {
+				deactivation_slot: 1000,
+				remaining_blocks: 313,
+			})) = block_on(rpc.close_lookup_table(&table, &authority, &recipient))
+		);
+	}
+}
@@ -5,11 +5,11 @@ use serde_tuple::Serialize_tuple;
 use serde_with::DisplayFromStr;
 use serde_with::serde_as;
 use serde_with::skip_serializing_none;
-use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 
 use super::Context;
 use crate::impl_http_method;
+use crate::rpc_config::RpcContextConfig;
 
 #[serde_as]
 #[skip_serializing_none]
@@ -17,7 +17,7 @@ use crate::impl_http_method;
 pub struct GetBalanceRequest {
 	#[serde_as(as = "DisplayFromStr")]
 	pub pubkey: Pubkey,
-	pub config: Option<CommitmentConfig>,
+	pub config: Option<RpcContextConfig>,
 }
 
 impl_http_method!(GetBalanceRequest, "getBalance");
@@ -30,10 +30,10 @@ impl GetBalanceRequest {
 		}
 	}
 
-	pub fn new_with_config(pubkey: Pubkey, config: CommitmentConfig) -> Self {
+	pub fn new_with_config(pubkey: Pubkey, config: impl Into<RpcContextConfig>) -> Self {
 		Self {
 			pubkey,
-			config: Some(config),
+			config: Some(config.into()),
 		}
 	}
 }
@@ -3,14 +3,14 @@ use serde::Serialize;
 use serde_tuple::Deserialize_tuple;
 use serde_tuple::Serialize_tuple;
 use serde_with::skip_serializing_none;
-use solana_sdk::commitment_config::CommitmentConfig;
 
 use crate::impl_http_method;
+use crate::rpc_config::RpcContextConfig;
 
 #[skip_serializing_none]
 #[derive(Debug, Default, Serialize_tuple, Deserialize_tuple)]
 pub struct GetBlockHeightRequest {
-	pub config: Option<CommitmentConfig>,
+	pub config: Option<RpcContextConfig>,
 }
 
 impl_http_method!(GetBlockHeightRequest, "getBlockHeight");
@@ -20,9 +20,9 @@ impl GetBlockHeightRequest {
 		Self::default()
 	}
 
-	pub fn new_with_config(config: CommitmentConfig) -> Self {
+	pub fn new_with_config(config: impl Into<RpcContextConfig>) -> Self {
 		Self {
-			config: Some(config),
+			config: Some(config.into()),
 		}
 	}
 }
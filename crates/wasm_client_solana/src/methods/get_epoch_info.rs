@@ -3,15 +3,15 @@ use serde::Serialize;
 use serde_tuple::Deserialize_tuple;
 use serde_tuple::Serialize_tuple;
 use serde_with::skip_serializing_none;
-use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::epoch_info::EpochInfo;
 
 use crate::impl_http_method;
+use crate::rpc_config::RpcContextConfig;
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize_tuple, Deserialize_tuple, Default)]
 pub struct GetEpochInfoRequest {
-	pub config: Option<CommitmentConfig>,
+	pub config: Option<RpcContextConfig>,
 }
 
 impl_http_method!(GetEpochInfoRequest, "getEpochInfo");
@@ -21,9 +21,9 @@ impl GetEpochInfoRequest {
 		Self::default()
 	}
 
-	pub fn new_with_config(config: CommitmentConfig) -> Self {
+	pub fn new_with_config(config: impl Into<RpcContextConfig>) -> Self {
 		Self {
-			config: Some(config),
+			config: Some(config.into()),
 		}
 	}
 }
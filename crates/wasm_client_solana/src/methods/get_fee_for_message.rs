@@ -2,11 +2,11 @@ use serde::Deserialize;
 use serde::Serializer;
 use serde_tuple::Serialize_tuple;
 use serde_with::skip_serializing_none;
-use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::message::Message;
 
 use super::Context;
 use crate::impl_http_method;
+use crate::rpc_config::RpcContextConfig;
 use crate::rpc_config::serialize_and_encode;
 use crate::solana_transaction_status::UiTransactionEncoding;
 
@@ -15,7 +15,7 @@ use crate::solana_transaction_status::UiTransactionEncoding;
 pub struct GetFeeForMessageRequest {
 	#[serde(serialize_with = "ser_message")]
 	pub message: Message,
-	pub config: Option<CommitmentConfig>,
+	pub config: Option<RpcContextConfig>,
 }
 
 impl_http_method!(GetFeeForMessageRequest, "getFeeForMessage");
@@ -28,10 +28,10 @@ impl GetFeeForMessageRequest {
 		}
 	}
 
-	pub fn new_with_config(message: Message, config: CommitmentConfig) -> Self {
+	pub fn new_with_config(message: Message, config: impl Into<RpcContextConfig>) -> Self {
 		Self {
 			message,
-			config: Some(config),
+			config: Some(config.into()),
 		}
 	}
 }
@@ -45,6 +45,14 @@ fn ser_message<S: Serializer>(msg: &Message, ser: S) -> Result<S::Ok, S::Error>
 #[derive(Debug, Deserialize, PartialEq, Eq)]
 pub struct FeeForMessageValue(Option<u64>);
 
+impl FeeForMessageValue {
+	/// The fee in lamports, or `None` when the blockhash of the message has
+	/// expired.
+	pub fn fee(&self) -> Option<u64> {
+		self.0
+	}
+}
+
 #[derive(Debug, Deserialize, PartialEq, Eq)]
 pub struct GetFeeForMessageResponse {
 	pub context: Context,
@@ -62,6 +70,7 @@ mod tests {
 	use assert2::check;
 	use base64::Engine;
 	use base64::prelude::BASE64_STANDARD;
+	use solana_sdk::commitment_config::CommitmentConfig;
 
 	use super::*;
 	use crate::ClientRequest;
@@ -63,7 +63,10 @@ mod tests {
 		let expected = ClientResponse {
 			jsonrpc: String::from("2.0"),
 			result: GetLargestAccountsResponse {
-				context: Context { slot: 54 },
+				context: Context {
+					slot: 54,
+					api_version: None,
+				},
 				value: vec![
 					RpcAccountBalance {
 						address: pubkey!("99P8ZgtJYe1buSK8JXkvpLh8xPsCFuLYhz9hQFNw93WJ"),
@@ -3,16 +3,16 @@ use serde::Serialize;
 use serde_tuple::Deserialize_tuple;
 use serde_tuple::Serialize_tuple;
 use serde_with::skip_serializing_none;
-use solana_sdk::commitment_config::CommitmentConfig;
 
 use super::Context;
 use crate::impl_http_method;
+use crate::rpc_config::RpcContextConfig;
 use crate::rpc_response::RpcBlockhash;
 
 #[skip_serializing_none]
 #[derive(Debug, Default, Serialize_tuple, Deserialize_tuple)]
 pub struct GetLatestBlockhashRequest {
-	pub config: Option<CommitmentConfig>,
+	pub config: Option<RpcContextConfig>,
 }
 
 impl_http_method!(GetLatestBlockhashRequest, "getLatestBlockhash");
@@ -22,9 +22,9 @@ impl GetLatestBlockhashRequest {
 		Self::default()
 	}
 
-	pub fn new_with_config(config: CommitmentConfig) -> Self {
+	pub fn new_with_config(config: impl Into<RpcContextConfig>) -> Self {
 		Self {
-			config: Some(config),
+			config: Some(config.into()),
 		}
 	}
 }
@@ -38,6 +38,7 @@ pub struct GetLatestBlockhashResponse {
 #[cfg(test)]
 mod tests {
 	use assert2::check;
+	use solana_sdk::commitment_config::CommitmentConfig;
 
 	use super::*;
 	use crate::ClientRequest;
@@ -65,7 +66,10 @@ mod tests {
 		let expected = ClientResponse {
 			jsonrpc: String::from("2.0"),
 			result: GetLatestBlockhashResponse {
-				context: Context { slot: 2_792 },
+				context: Context {
+					slot: 2_792,
+					api_version: None,
+				},
 				value: RpcBlockhash {
 					blockhash: "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N"
 						.parse()
@@ -0,0 +1,122 @@
+use serde::Deserialize;
+use serde::Serialize;
+use serde_tuple::Serialize_tuple;
+use serde_with::DisplayFromStr;
+use serde_with::serde_as;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::impl_http_method;
+
+/// The `getPriorityFeeEstimate` method of the Helius rpc. This isn't part of
+/// the standard rpc api and other providers will reject it.
+#[derive(Debug, Serialize_tuple)]
+pub struct GetPriorityFeeEstimateRequest {
+	config: PriorityFeeEstimateConfig,
+}
+
+impl_http_method!(GetPriorityFeeEstimateRequest, "getPriorityFeeEstimate");
+
+impl GetPriorityFeeEstimateRequest {
+	pub fn new(account_keys: Vec<Pubkey>, priority_level: PriorityLevel) -> Self {
+		Self {
+			config: PriorityFeeEstimateConfig {
+				account_keys,
+				options: PriorityFeeEstimateOptions { priority_level },
+			},
+		}
+	}
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PriorityFeeEstimateConfig {
+	#[serde_as(as = "Vec<DisplayFromStr>")]
+	account_keys: Vec<Pubkey>,
+	options: PriorityFeeEstimateOptions,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PriorityFeeEstimateOptions {
+	priority_level: PriorityLevel,
+}
+
+/// The percentile of the recent fees returned by `getPriorityFeeEstimate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriorityLevel {
+	Min,
+	Low,
+	Medium,
+	High,
+	VeryHigh,
+	UnsafeMax,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPriorityFeeEstimateResponse {
+	/// The estimated compute unit price in micro-lamports.
+	pub priority_fee_estimate: f64,
+}
+
+impl From<GetPriorityFeeEstimateResponse> for u64 {
+	/// The estimate rounded up to a whole micro-lamport.
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	fn from(value: GetPriorityFeeEstimateResponse) -> Self {
+		value.priority_fee_estimate.max(0.0).ceil() as u64
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use solana_sdk::pubkey;
+
+	use super::*;
+	use crate::ClientRequest;
+	use crate::ClientResponse;
+	use crate::methods::HttpMethod;
+
+	#[test]
+	fn request() {
+		let request = ClientRequest::builder()
+			.method(GetPriorityFeeEstimateRequest::NAME)
+			.id(1)
+			.params(GetPriorityFeeEstimateRequest::new(
+				vec![pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4")],
+				PriorityLevel::High,
+			))
+			.build();
+
+		insta::assert_compact_json_snapshot!(request, @r###"
+  {
+    "jsonrpc": "2.0",
+    "id": 1,
+    "method": "getPriorityFeeEstimate",
+    "params": [
+      {
+        "accountKeys": [
+          "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"
+        ],
+        "options": {
+          "priorityLevel": "High"
+        }
+      }
+    ]
+  }
+  "###);
+	}
+
+	#[test]
+	fn response() {
+		let raw_json = r#"{"jsonrpc":"2.0","result":{"priorityFeeEstimate":1200.5},"id":1}"#;
+
+		let response: ClientResponse<GetPriorityFeeEstimateResponse> =
+			serde_json::from_str(raw_json).unwrap();
+
+		check!(response.id == 1);
+		check!(response.jsonrpc == "2.0");
+		check!(u64::from(response.result) == 1201);
+	}
+}
@@ -5,6 +5,7 @@ use serde_with::serde_as;
 use serde_with::skip_serializing_none;
 use solana_sdk::pubkey::Pubkey;
 
+use super::Context;
 use super::impl_websocket_notification;
 use crate::impl_http_method;
 use crate::rpc_config::RpcKeyedAccount;
@@ -48,6 +49,14 @@ impl GetProgramAccountsResponse {
 	}
 }
 
+/// The response of `getProgramAccounts` when the `with_context` option is
+/// set.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct GetProgramAccountsWithContextResponse {
+	pub context: Context,
+	pub value: Vec<RpcKeyedAccount>,
+}
+
 #[cfg(test)]
 mod tests {
 	use assert2::check;
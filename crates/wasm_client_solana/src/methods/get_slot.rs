@@ -2,14 +2,14 @@ use serde::Deserialize;
 use serde_tuple::Serialize_tuple;
 use serde_with::skip_serializing_none;
 use solana_sdk::clock::Slot;
-use solana_sdk::commitment_config::CommitmentConfig;
 
 use crate::impl_http_method;
+use crate::rpc_config::RpcContextConfig;
 
 #[skip_serializing_none]
 #[derive(Debug, Default, Serialize_tuple)]
 pub struct GetSlotRequest {
-	config: Option<CommitmentConfig>,
+	config: Option<RpcContextConfig>,
 }
 
 impl_http_method!(GetSlotRequest, "getSlot");
@@ -19,9 +19,9 @@ impl GetSlotRequest {
 		Self::default()
 	}
 
-	pub fn new_with_config(config: CommitmentConfig) -> Self {
+	pub fn new_with_config(config: impl Into<RpcContextConfig>) -> Self {
 		Self {
-			config: Some(config),
+			config: Some(config.into()),
 		}
 	}
 }
@@ -2,15 +2,15 @@ use serde::Deserialize;
 use serde_tuple::Serialize_tuple;
 use serde_with::DisplayFromStr;
 use serde_with::serde_as;
-use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 
 use crate::impl_http_method;
+use crate::rpc_config::RpcContextConfig;
 
 #[derive(Debug, Default, Serialize_tuple)]
 pub struct GetSlotLeaderRequest {
 	#[serde(skip_serializing_if = "Option::is_none")]
-	pub config: Option<CommitmentConfig>,
+	pub config: Option<RpcContextConfig>,
 }
 
 impl_http_method!(GetSlotLeaderRequest, "getSlotLeader");
@@ -20,9 +20,9 @@ impl GetSlotLeaderRequest {
 		Self::default()
 	}
 
-	pub fn new_with_config(config: CommitmentConfig) -> Self {
+	pub fn new_with_config(config: impl Into<RpcContextConfig>) -> Self {
 		Self {
-			config: Some(config),
+			config: Some(config.into()),
 		}
 	}
 }
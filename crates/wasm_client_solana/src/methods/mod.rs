@@ -1,7 +1,6 @@
 use serde::Deserialize;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use solana_sdk::clock::Slot;
 
 pub use self::get_account_info::*;
 pub use self::get_balance::*;
@@ -30,6 +29,7 @@ pub use self::get_leader_schedule::*;
 pub use self::get_max_retransmit_slot::*;
 pub use self::get_minimum_balance_for_rent_exemption::*;
 pub use self::get_multiple_accounts::*;
+pub use self::get_priority_fee_estimate::*;
 pub use self::get_program_accounts::*;
 pub use self::get_recent_performance_samples::*;
 pub use self::get_recent_prioritization_fees::*;
@@ -55,6 +55,7 @@ pub use self::minimum_ledger_slot::*;
 pub use self::request_airdrop::*;
 pub use self::send_transaction::*;
 pub use self::simulate_transaction::*;
+use crate::rpc_response::RpcResponseContext;
 
 mod get_account_info;
 mod get_balance;
@@ -83,6 +84,7 @@ mod get_leader_schedule;
 mod get_max_retransmit_slot;
 mod get_minimum_balance_for_rent_exemption;
 mod get_multiple_accounts;
+mod get_priority_fee_estimate;
 mod get_program_accounts;
 mod get_recent_performance_samples;
 mod get_recent_prioritization_fees;
@@ -109,10 +111,8 @@ mod request_airdrop;
 mod send_transaction;
 mod simulate_transaction;
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
-pub struct Context {
-	pub slot: Slot,
-}
+/// The context included with the value of many responses.
+pub type Context = RpcResponseContext;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
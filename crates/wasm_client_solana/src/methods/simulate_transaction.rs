@@ -2,6 +2,7 @@ use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serialize;
 use serde::ser::SerializeTuple;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::transaction::TransactionError;
 use solana_sdk::transaction::VersionedTransaction;
 
@@ -108,6 +109,15 @@ pub struct SimulateTransactionResponseValue {
 	pub return_data: Option<UiTransactionReturnData>,
 }
 
+impl SimulateTransactionResponseValue {
+	/// The return data of the simulation when it was set by `program_id`.
+	pub fn return_data_for(&self, program_id: &Pubkey) -> Option<&UiTransactionReturnData> {
+		self.return_data
+			.as_ref()
+			.filter(|return_data| return_data.program_id == *program_id)
+	}
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SimulateTransactionResponse {
 	pub context: Context,
@@ -190,5 +200,16 @@ mod tests {
 					units_consumed: Some(2366)
 				}
 		);
+
+		let program_id = pubkey!("83astBRguLMdt2h5U1Tpdq5tjFoJ6noeGwaY3mDLVcri");
+		let return_data = response.result.value.return_data_for(&program_id).unwrap();
+		check!(return_data.decode_borsh::<u64>() == Ok(42));
+		check!(
+			response
+				.result
+				.value
+				.return_data_for(&Pubkey::default())
+				.is_none()
+		);
 	}
 }
@@ -31,6 +31,8 @@ pub enum NonceError {
 	InvalidAuthority { provided: Pubkey, expected: Pubkey },
 	#[error("invalid state for requested operation")]
 	InvalidStateForOperation,
+	#[error("the blockhash of a durable nonce transaction can't be replaced")]
+	DurableNonceTransaction,
 	#[error("client error: {0}")]
 	Client(String),
 }
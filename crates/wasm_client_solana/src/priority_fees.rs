@@ -0,0 +1,577 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use futures::future::AbortHandle;
+use futures::future::join_all;
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+use typed_builder::TypedBuilder;
+
+use crate::ClientResult;
+use crate::DEFAULT_PRIORITY_FEE_REFRESH_INTERVAL;
+use crate::DEFAULT_PRIORITY_FEE_WINDOW;
+use crate::PriorityLevel;
+use crate::SolanaRpcClient;
+use crate::TaskSet;
+use crate::rpc_response::RpcPrioritizationFee;
+use crate::runtime::interval;
+
+/// How soon a transaction should land. A higher urgency suggests a higher
+/// percentile of the recent prioritization fees.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Urgency {
+	Low,
+	#[default]
+	Medium,
+	High,
+}
+
+impl From<Urgency> for PriorityLevel {
+	fn from(urgency: Urgency) -> Self {
+		match urgency {
+			Urgency::Low => PriorityLevel::Low,
+			Urgency::Medium => PriorityLevel::Medium,
+			Urgency::High => PriorityLevel::High,
+		}
+	}
+}
+
+/// The options of a [`PriorityFeeOracle`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TypedBuilder)]
+pub struct PriorityFeeOracleConfig {
+	/// The number of most recent slots sampled for the network and for each
+	/// account.
+	#[builder(default = DEFAULT_PRIORITY_FEE_WINDOW)]
+	pub window: usize,
+	/// The number of writable accounts sampled. The account which was least
+	/// recently suggested for is dropped once there are more.
+	#[builder(default = 32)]
+	pub max_accounts: usize,
+	/// How often the samples are fetched by
+	/// [`PriorityFeeOracle::spawn_refresh`].
+	#[builder(default = DEFAULT_PRIORITY_FEE_REFRESH_INTERVAL)]
+	pub refresh_interval: Duration,
+	/// The percentile suggested for [`Urgency::Low`].
+	#[builder(default = 25)]
+	pub low_percentile: u8,
+	/// The percentile suggested for [`Urgency::Medium`].
+	#[builder(default = 50)]
+	pub medium_percentile: u8,
+	/// The percentile suggested for [`Urgency::High`].
+	#[builder(default = 90)]
+	pub high_percentile: u8,
+}
+
+impl Default for PriorityFeeOracleConfig {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl PriorityFeeOracleConfig {
+	pub fn percentile(&self, urgency: Urgency) -> u8 {
+		match urgency {
+			Urgency::Low => self.low_percentile,
+			Urgency::Medium => self.medium_percentile,
+			Urgency::High => self.high_percentile,
+		}
+	}
+}
+
+macro_rules! priority_fee_estimator {
+	($($bounds:tt)*) => {
+		/// A source of compute unit prices, such as the native fee estimation
+		/// endpoint of an rpc provider, used by a [`PriorityFeeOracle`] before it
+		/// falls back to the standard `getRecentPrioritizationFees` method.
+		///
+		/// The estimator must also be `Send` and `Sync` when the `ssr` feature is
+		/// enabled on native targets.
+		#[cfg_attr(all(feature = "ssr", not(target_arch = "wasm32")), async_trait)]
+		#[cfg_attr(not(all(feature = "ssr", not(target_arch = "wasm32"))), async_trait(?Send))]
+		pub trait PriorityFeeEstimator $($bounds)* {
+			/// The compute unit price in micro-lamports for a transaction which
+			/// writes to the `accounts` and should land with the `urgency`.
+			async fn estimate(&self, accounts: &[Pubkey], urgency: Urgency) -> ClientResult<u64>;
+		}
+	};
+}
+
+#[cfg(all(feature = "ssr", not(target_arch = "wasm32")))]
+priority_fee_estimator!(: Send + Sync);
+#[cfg(not(all(feature = "ssr", not(target_arch = "wasm32"))))]
+priority_fee_estimator!();
+
+/// A [`PriorityFeeEstimator`] which uses the `getPriorityFeeEstimate` method
+/// of the Helius rpc.
+#[derive(Debug, Clone)]
+pub struct HeliusPriorityFeeEstimator {
+	rpc: SolanaRpcClient,
+}
+
+impl HeliusPriorityFeeEstimator {
+	pub fn new(rpc: &SolanaRpcClient) -> Self {
+		Self { rpc: rpc.clone() }
+	}
+}
+
+#[cfg_attr(all(feature = "ssr", not(target_arch = "wasm32")), async_trait)]
+#[cfg_attr(not(all(feature = "ssr", not(target_arch = "wasm32"))), async_trait(?Send))]
+impl PriorityFeeEstimator for HeliusPriorityFeeEstimator {
+	async fn estimate(&self, accounts: &[Pubkey], urgency: Urgency) -> ClientResult<u64> {
+		self.rpc
+			.get_priority_fee_estimate(accounts.to_vec(), urgency.into())
+			.await
+	}
+}
+
+/// Suggests compute unit prices from a rolling window of the prioritization
+/// fees paid in recent slots.
+///
+/// The network wide fees are sampled along with the fees paid to write to
+/// each account in a hot set, which holds the accounts most recently passed
+/// to [`PriorityFeeOracle::suggest`]. The suggestion for a transaction is the
+/// highest percentile among the network and its writable accounts, since a
+/// single contested account decides the price of the whole transaction.
+///
+/// Samples are fetched by [`PriorityFeeOracle::refresh`], in the background
+/// with [`PriorityFeeOracle::spawn_refresh`], or fed manually with
+/// [`PriorityFeeOracle::record`]. Clones share the same samples.
+#[derive(derive_more::Debug, Clone)]
+pub struct PriorityFeeOracle {
+	#[debug(skip)]
+	rpc: SolanaRpcClient,
+	config: PriorityFeeOracleConfig,
+	#[debug(skip)]
+	estimator: Option<Arc<dyn PriorityFeeEstimator>>,
+	samples: Arc<Mutex<PriorityFeeSamples>>,
+	/// The refresh tasks, which are aborted once the oracle and all of its
+	/// clones are dropped.
+	#[debug(skip)]
+	tasks: Arc<Mutex<TaskSet>>,
+}
+
+impl PartialEq for PriorityFeeOracle {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.samples, &other.samples)
+	}
+}
+
+impl Eq for PriorityFeeOracle {}
+
+impl PriorityFeeOracle {
+	pub fn new(rpc: &SolanaRpcClient, config: PriorityFeeOracleConfig) -> Self {
+		Self {
+			rpc: rpc.clone(),
+			config,
+			estimator: None,
+			samples: Arc::default(),
+			tasks: Arc::default(),
+		}
+	}
+
+	/// Ask the `estimator` first in [`PriorityFeeOracle::estimate`]. The
+	/// samples are only used when it fails.
+	#[must_use]
+	pub fn with_estimator(self, estimator: impl PriorityFeeEstimator + 'static) -> Self {
+		Self {
+			estimator: Some(Arc::new(estimator)),
+			..self
+		}
+	}
+
+	pub fn config(&self) -> PriorityFeeOracleConfig {
+		self.config
+	}
+
+	/// The accounts currently sampled, besides the network wide fees.
+	pub fn accounts(&self) -> Vec<Pubkey> {
+		self.lock().accounts.keys().copied().collect()
+	}
+
+	/// Add the `fees` returned by `getRecentPrioritizationFees` to the
+	/// samples of each of the `accounts`, or to the network wide samples when
+	/// `accounts` is empty.
+	pub fn record(&self, accounts: &[Pubkey], fees: &[RpcPrioritizationFee]) {
+		let mut samples = self.lock();
+
+		if accounts.is_empty() {
+			insert_fees(&mut samples.global, fees, self.config.window);
+			return;
+		}
+
+		samples.touch(accounts, self.config.max_accounts);
+
+		for account in accounts {
+			if let Some(account_samples) = samples.accounts.get_mut(account) {
+				insert_fees(&mut account_samples.fees, fees, self.config.window);
+			}
+		}
+	}
+
+	/// The compute unit price in micro-lamports for a transaction which
+	/// writes to the `accounts` and should land with the `urgency`, taken
+	/// from the current samples without making a request.
+	///
+	/// The `accounts` are added to the hot set so that the next refresh
+	/// samples them. Returns `0` when there are no samples yet.
+	pub fn suggest(&self, accounts: &[Pubkey], urgency: Urgency) -> u64 {
+		let percentile = self.config.percentile(urgency);
+		let mut samples = self.lock();
+
+		samples.touch(accounts, self.config.max_accounts);
+
+		let network = fee_percentile(samples.global.values().copied().collect(), percentile);
+
+		accounts
+			.iter()
+			.filter_map(|account| samples.accounts.get(account))
+			.map(|account| fee_percentile(account.fees.values().copied().collect(), percentile))
+			.fold(network, u64::max)
+	}
+
+	/// Like [`PriorityFeeOracle::suggest`] but the estimator is asked first
+	/// and the missing samples are fetched before suggesting a price.
+	pub async fn estimate(&self, accounts: &[Pubkey], urgency: Urgency) -> ClientResult<u64> {
+		if let Some(estimator) = &self.estimator {
+			if let Ok(fee) = estimator.estimate(accounts, urgency).await {
+				return Ok(fee);
+			}
+		}
+
+		let missing = {
+			let samples = self.lock();
+			let missing = accounts
+				.iter()
+				.filter(|account| {
+					samples
+						.accounts
+						.get(*account)
+						.is_none_or(|account| account.fees.is_empty())
+				})
+				.copied()
+				.collect::<Vec<_>>();
+
+			(!missing.is_empty() || samples.global.is_empty()).then_some(missing)
+		};
+
+		if let Some(missing) = missing {
+			self.refresh_accounts(&missing).await?;
+		}
+
+		Ok(self.suggest(accounts, urgency))
+	}
+
+	/// Fetch the recent prioritization fees of the network and of every
+	/// account in the hot set.
+	///
+	/// The accounts are fetched concurrently and every successful response
+	/// is recorded. Returns the first error.
+	pub async fn refresh(&self) -> ClientResult<()> {
+		self.refresh_accounts(&self.accounts()).await
+	}
+
+	/// Call [`PriorityFeeOracle::refresh`] now and then every
+	/// [`PriorityFeeOracleConfig::refresh_interval`] until the returned
+	/// [`AbortHandle`] is aborted or the oracle and all of its clones are
+	/// dropped. Failed requests are skipped.
+	pub fn spawn_refresh(&self) -> AbortHandle {
+		// The task gets its own task set so it doesn't keep the tasks of this
+		// oracle alive.
+		let oracle = Self {
+			tasks: Arc::default(),
+			..self.clone()
+		};
+
+		self.tasks
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.spawn_local(async move {
+				let mut ticks = interval(oracle.config.refresh_interval).boxed();
+
+				loop {
+					let _ = oracle.refresh().await;
+					ticks.next().await;
+				}
+			})
+	}
+
+	async fn refresh_accounts(&self, accounts: &[Pubkey]) -> ClientResult<()> {
+		let network = async {
+			let fees = self.rpc.get_recent_prioritization_fees().await?;
+			self.record(&[], &fees);
+
+			Ok(())
+		};
+		let accounts = accounts.iter().map(|account| {
+			async move {
+				let fees = self
+					.rpc
+					.get_recent_prioritization_fees_with_accounts(vec![*account])
+					.await?;
+				self.record(&[*account], &fees);
+
+				Ok(())
+			}
+		});
+		let (network, accounts) = futures::join!(network, join_all(accounts));
+
+		std::iter::once(network).chain(accounts).collect()
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, PriorityFeeSamples> {
+		self.samples.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+}
+
+#[derive(Debug, Default)]
+struct PriorityFeeSamples {
+	global: BTreeMap<Slot, u64>,
+	accounts: HashMap<Pubkey, AccountSamples>,
+	/// Incremented every time accounts are used, to find the least recently
+	/// used account.
+	uses: u64,
+}
+
+#[derive(Debug, Default)]
+struct AccountSamples {
+	fees: BTreeMap<Slot, u64>,
+	last_used: u64,
+}
+
+impl PriorityFeeSamples {
+	/// Mark the `accounts` as used, adding them to the hot set and dropping
+	/// the least recently used accounts beyond `max_accounts`.
+	fn touch(&mut self, accounts: &[Pubkey], max_accounts: usize) {
+		self.uses += 1;
+
+		for account in accounts {
+			self.accounts.entry(*account).or_default().last_used = self.uses;
+		}
+
+		while self.accounts.len() > max_accounts {
+			let Some(oldest) = self
+				.accounts
+				.iter()
+				.min_by_key(|(_, samples)| samples.last_used)
+				.map(|(account, _)| *account)
+			else {
+				break;
+			};
+
+			self.accounts.remove(&oldest);
+		}
+	}
+}
+
+fn insert_fees(samples: &mut BTreeMap<Slot, u64>, fees: &[RpcPrioritizationFee], window: usize) {
+	samples.extend(fees.iter().map(|fee| (fee.slot, fee.prioritization_fee)));
+
+	while samples.len() > window {
+		samples.pop_first();
+	}
+}
+
+/// The `percentile` of the `fees`, or `0` when there are none.
+pub(crate) fn fee_percentile(mut fees: Vec<u64>, percentile: u8) -> u64 {
+	fees.sort_unstable();
+
+	let Some(last_index) = fees.len().checked_sub(1) else {
+		return 0;
+	};
+
+	fees[last_index * usize::from(percentile.min(100)) / 100]
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+	use assert2::check;
+	use futures::executor::block_on;
+	use serde_json::json;
+
+	use super::*;
+	use crate::ClientError;
+	use crate::MockRpcProvider;
+
+	fn fees(fees: &[(Slot, u64)]) -> Vec<RpcPrioritizationFee> {
+		fees.iter()
+			.map(|&(slot, prioritization_fee)| {
+				RpcPrioritizationFee {
+					slot,
+					prioritization_fee,
+				}
+			})
+			.collect()
+	}
+
+	fn oracle(provider: &MockRpcProvider, config: PriorityFeeOracleConfig) -> PriorityFeeOracle {
+		PriorityFeeOracle::new(
+			&SolanaRpcClient::new_with_provider(provider.clone()),
+			config,
+		)
+	}
+
+	struct FixedEstimator(Option<u64>);
+
+	#[async_trait]
+	impl PriorityFeeEstimator for FixedEstimator {
+		async fn estimate(&self, _accounts: &[Pubkey], _urgency: Urgency) -> ClientResult<u64> {
+			self.0
+				.ok_or_else(|| ClientError::Other("estimator unavailable".into()))
+		}
+	}
+
+	#[test]
+	fn suggests_the_highest_percentile_of_the_hot_accounts() {
+		let oracle = oracle(&MockRpcProvider::new(), PriorityFeeOracleConfig::default());
+		let hot = Pubkey::new_unique();
+		let quiet = Pubkey::new_unique();
+
+		check!(oracle.suggest(&[hot], Urgency::High) == 0);
+
+		oracle.record(
+			&[],
+			&fees(&[(1, 0), (2, 100), (3, 200), (4, 300), (5, 400)]),
+		);
+		oracle.record(&[hot], &fees(&[(1, 1000), (2, 2000), (3, 3000)]));
+		oracle.record(&[quiet], &fees(&[(1, 0), (2, 0)]));
+
+		check!(oracle.suggest(&[], Urgency::Low) == 100);
+		check!(oracle.suggest(&[], Urgency::Medium) == 200);
+		check!(oracle.suggest(&[quiet], Urgency::High) == 300);
+		check!(oracle.suggest(&[quiet, hot], Urgency::Medium) == 2000);
+		check!(oracle.suggest(&[hot], Urgency::High) == 2000);
+	}
+
+	#[test]
+	fn keeps_a_rolling_window() {
+		let config = PriorityFeeOracleConfig::builder()
+			.window(2)
+			.max_accounts(2)
+			.build();
+		let oracle = oracle(&MockRpcProvider::new(), config);
+		let accounts = [
+			Pubkey::new_unique(),
+			Pubkey::new_unique(),
+			Pubkey::new_unique(),
+		];
+
+		oracle.record(&[], &fees(&[(1, 900), (2, 10), (3, 20)]));
+		check!(oracle.suggest(&[], Urgency::High) == 10);
+
+		oracle.suggest(&accounts[..1], Urgency::Low);
+		oracle.suggest(&accounts[1..2], Urgency::Low);
+		oracle.suggest(&accounts[2..], Urgency::Low);
+
+		let mut tracked = oracle.accounts();
+		tracked.sort();
+		let mut expected = accounts[1..].to_vec();
+		expected.sort();
+		check!(tracked == expected);
+	}
+
+	#[test]
+	fn refresh_samples_the_hot_set() -> anyhow::Result<()> {
+		let account = Pubkey::new_unique();
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getRecentPrioritizationFees",
+				json!([{ "slot": 1, "prioritizationFee": 100 }]),
+			)
+			.with_result(
+				"getRecentPrioritizationFees",
+				json!([{ "slot": 1, "prioritizationFee": 5000 }]),
+			);
+		let oracle = oracle(&provider, PriorityFeeOracleConfig::default());
+
+		oracle.suggest(&[account], Urgency::Medium);
+		block_on(oracle.refresh())?;
+
+		check!(oracle.suggest(&[], Urgency::Medium) == 100);
+		check!(oracle.suggest(&[account], Urgency::Medium) == 5000);
+
+		let params = provider
+			.requests()
+			.into_iter()
+			.map(|(_, params)| params)
+			.collect::<Vec<_>>();
+		check!(params == [json!([null]), json!([[account.to_string()]])]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn estimator_is_asked_first() -> anyhow::Result<()> {
+		let provider = MockRpcProvider::new().with_result(
+			"getRecentPrioritizationFees",
+			json!([{ "slot": 1, "prioritizationFee": 100 }]),
+		);
+		let account = Pubkey::new_unique();
+
+		let oracle = oracle(&provider, PriorityFeeOracleConfig::default())
+			.with_estimator(FixedEstimator(Some(42)));
+		check!(block_on(oracle.estimate(&[account], Urgency::High))? == 42);
+		check!(provider.requests().is_empty());
+
+		let oracle = oracle.with_estimator(FixedEstimator(None));
+		check!(block_on(oracle.estimate(&[account], Urgency::High))? == 100);
+		check!(provider.requests().len() == 2);
+
+		block_on(oracle.estimate(&[account], Urgency::High))?;
+		check!(provider.requests().len() == 2);
+
+		Ok(())
+	}
+
+	#[test]
+	fn helius_estimator() -> anyhow::Result<()> {
+		let provider = MockRpcProvider::new().with_result(
+			"getPriorityFeeEstimate",
+			json!({ "priorityFeeEstimate": 1234.5 }),
+		);
+		let estimator =
+			HeliusPriorityFeeEstimator::new(&SolanaRpcClient::new_with_provider(provider.clone()));
+		let account = Pubkey::new_unique();
+
+		check!(block_on(estimator.estimate(&[account], Urgency::Low))? == 1235);
+
+		let (method, params) = provider.requests().remove(0);
+		check!(method == "getPriorityFeeEstimate");
+		check!(
+			params
+				== json!([{
+					"accountKeys": [account.to_string()],
+					"options": { "priorityLevel": "Low" },
+				}])
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn refresh_stops_when_the_oracle_is_dropped() {
+		tokio::task::LocalSet::new()
+			.run_until(async {
+				let provider = MockRpcProvider::new()
+					.with_result("getRecentPrioritizationFees", fees(&[(1, 100)]));
+				let oracle = oracle(&provider, PriorityFeeOracleConfig::default());
+				let clone = oracle.clone();
+				let handle = oracle.spawn_refresh();
+				tokio::task::yield_now().await;
+
+				check!(oracle.suggest(&[], Urgency::Medium) == 100);
+
+				drop(oracle);
+				check!(!handle.is_aborted());
+
+				drop(clone);
+				check!(handle.is_aborted());
+			})
+			.await;
+	}
+}
@@ -1,32 +1,131 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use futures::StreamExt;
+use futures::stream;
+use futures::stream::BoxStream;
 use serde_json::Value;
+use typed_builder::TypedBuilder;
 #[cfg(feature = "ssr")]
 pub use ssr_http_provider::HttpProvider;
 #[cfg(not(feature = "ssr"))]
 pub use wasm_http_provider::HttpProvider;
 
+use crate::ClientError;
 use crate::ClientRequest;
 use crate::ClientResult;
 use crate::DEFAULT_ERROR_CODE;
 use crate::RpcError;
 use crate::RpcErrorDetails;
 
+/// The transport used by [`SolanaRpcClient`](crate::SolanaRpcClient) to make
+/// JSON-RPC requests.
+///
+/// The default [`HttpProvider`] uses the browser `fetch` api (or `reqwest` when
+/// the `ssr` feature is enabled). Environments which expose a different
+/// networking primitive, like Cloudflare Workers, Deno or Node, can implement
+/// this trait and pass it to
+/// [`SolanaRpcClient::new_with_provider`](crate::SolanaRpcClient::new_with_provider).
+///
+/// An implementation receives the JSON-RPC `method` name and the serialized
+/// `params` and should resolve with the full JSON-RPC response body (including
+/// the `jsonrpc`, `id` and `result` or `error` fields). Error responses should
+/// be returned as `Ok` so that the client can decode them into a
+/// [`RpcError`].
 #[async_trait]
 pub trait RpcProvider {
 	/// Send the request.
 	async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value>;
+
+	/// Send the request and resolve with the raw bytes of the JSON-RPC
+	/// response body.
+	///
+	/// The client deserializes every response from these bytes straight into
+	/// its type. The default implementation serializes the [`Value`] returned
+	/// by [`RpcProvider::send`], so providers which can read the body as bytes
+	/// should override it to skip the intermediate [`Value`].
+	async fn send_raw(&self, method: &'static str, request: Value) -> ClientResult<Vec<u8>> {
+		let value = self.send(method, request).await?;
+
+		serde_json::to_vec(&value).map_err(|error| ClientError::Other(error.to_string()))
+	}
+
+	/// Send the request and resolve with a stream of the chunks of the raw
+	/// JSON-RPC response body as they arrive.
+	///
+	/// This is used by the streaming methods, like
+	/// [`SolanaRpcClient::stream_program_accounts_with_config`](crate::SolanaRpcClient::stream_program_accounts_with_config),
+	/// to parse items before the whole body has been received. The default
+	/// implementation yields the body from [`RpcProvider::send_raw`] as a
+	/// single chunk.
+	async fn send_streaming(
+		&self,
+		method: &'static str,
+		request: Value,
+	) -> ClientResult<ResponseChunks> {
+		let body = self.send_raw(method, request).await?;
+
+		Ok(stream::once(async move { Ok(body) }).boxed())
+	}
+
 	/// Get the URL represented by this sender.
 	fn url(&self) -> String;
 }
 
+/// The chunks of a JSON-RPC response body returned by
+/// [`RpcProvider::send_streaming`].
+pub type ResponseChunks = BoxStream<'static, ClientResult<Vec<u8>>>;
+
+#[async_trait]
+impl<T: RpcProvider + Send + Sync + ?Sized> RpcProvider for Arc<T> {
+	async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
+		self.as_ref().send(method, request).await
+	}
+
+	async fn send_raw(&self, method: &'static str, request: Value) -> ClientResult<Vec<u8>> {
+		self.as_ref().send_raw(method, request).await
+	}
+
+	async fn send_streaming(
+		&self,
+		method: &'static str,
+		request: Value,
+	) -> ClientResult<ResponseChunks> {
+		self.as_ref().send_streaming(method, request).await
+	}
+
+	fn url(&self) -> String {
+		self.as_ref().url()
+	}
+}
+
+/// Configuration for the [`HttpProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypedBuilder)]
+pub struct HttpProviderConfig {
+	/// Send `Accept-Encoding: gzip` and transparently decompress gzipped
+	/// responses. Large `getProgramAccounts` and `getBlock` responses are
+	/// typically an order of magnitude smaller when compressed.
+	///
+	/// This only applies to the native `ssr` provider. In the browser the
+	/// `fetch` api negotiates compression automatically.
+	#[builder(default = true)]
+	pub gzip: bool,
+}
+
+impl Default for HttpProviderConfig {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
 #[cfg(feature = "ssr")]
 mod ssr_http_provider {
 	use reqwest::Client;
+	use reqwest::RequestBuilder;
 	use reqwest::header::CONTENT_TYPE;
 	use reqwest::header::HeaderMap;
 
 	use super::*;
-	use crate::ClientError;
 
 	#[derive(Debug, Clone)]
 	pub struct HttpProvider {
@@ -42,30 +141,12 @@ mod ssr_http_provider {
 		}
 
 		async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
-			let client_request = ClientRequest::builder()
-				.method(method)
-				.id(1)
-				.params(request)
-				.build();
 			#[cfg(not(target_arch = "wasm32"))]
-			let result: Value = self
-				.client
-				.post(&self.url)
-				.headers(self.headers.clone())
-				.json(&client_request)
-				.send()
-				.await?
-				.json()
-				.await?;
+			let result: Value = self.post(method, request).send().await?.json().await?;
 
 			#[cfg(target_arch = "wasm32")]
 			let result: Value = {
-				let request = self
-					.client
-					.post(&self.url)
-					.headers(self.headers.clone())
-					.json(&client_request)
-					.send();
+				let request = self.post(method, request).send();
 				let wrapped_request = send_wrapper::SendWrapper::new(request);
 				let response = wrapped_request.await?.json();
 				let wrapped_response = send_wrapper::SendWrapper::new(response);
@@ -75,20 +156,88 @@ mod ssr_http_provider {
 
 			Ok(result)
 		}
+
+		async fn send_raw(&self, method: &'static str, request: Value) -> ClientResult<Vec<u8>> {
+			#[cfg(not(target_arch = "wasm32"))]
+			let body = self.post(method, request).send().await?.bytes().await?;
+
+			#[cfg(target_arch = "wasm32")]
+			let body = {
+				let request = self.post(method, request).send();
+				let response = send_wrapper::SendWrapper::new(request).await?.bytes();
+				send_wrapper::SendWrapper::new(response).await?
+			};
+
+			Ok(body.into())
+		}
+
+		#[cfg(not(target_arch = "wasm32"))]
+		async fn send_streaming(
+			&self,
+			method: &'static str,
+			request: Value,
+		) -> ClientResult<ResponseChunks> {
+			let response = self.post(method, request).send().await?;
+			let chunks = stream::try_unfold(response, |mut response| {
+				async move {
+					let chunk = response.chunk().await?;
+					Ok(chunk.map(|chunk| (chunk.into(), response)))
+				}
+			});
+
+			Ok(chunks.boxed())
+		}
 	}
 
 	impl HttpProvider {
+		/// Create a provider with the default [`HttpProviderConfig`].
+		///
+		/// # Panics
+		///
+		/// Panics when the `reqwest` client can't be built, like
+		/// [`Client::new`]. Use [`HttpProvider::new_with_config`] to handle the
+		/// error.
 		pub fn new(url: impl Into<String>) -> Self {
-			let client = Client::new();
+			Self::new_with_config(url, HttpProviderConfig::default())
+				.unwrap_or_else(|error| panic!("{error}"))
+		}
+
+		/// Create a provider with the `config`. This fails when the `reqwest`
+		/// client can't be built, for example when the TLS backend can't be
+		/// initialized.
+		pub fn new_with_config(
+			url: impl Into<String>,
+			config: HttpProviderConfig,
+		) -> ClientResult<Self> {
+			#[cfg(not(target_arch = "wasm32"))]
+			let client = Client::builder().gzip(config.gzip).build()?;
+			#[cfg(target_arch = "wasm32")]
+			let client = {
+				let _ = config;
+				Client::new()
+			};
 			let url = url.into();
 			let mut headers = HeaderMap::new();
 			headers.append(CONTENT_TYPE, "application/json".parse().unwrap());
 
-			Self {
+			Ok(Self {
 				client,
 				headers,
 				url,
-			}
+			})
+		}
+
+		fn post(&self, method: &'static str, request: Value) -> RequestBuilder {
+			let client_request = ClientRequest::builder()
+				.method(method)
+				.id(1)
+				.params(request)
+				.build();
+
+			self.client
+				.post(&self.url)
+				.headers(self.headers.clone())
+				.json(&client_request)
 		}
 	}
 
@@ -96,7 +245,11 @@ mod ssr_http_provider {
 		fn from(error: reqwest::Error) -> Self {
 			let message = error.to_string();
 			let code = i32::from(error.status().map_or(DEFAULT_ERROR_CODE, |s| s.as_u16()));
-			let error = RpcErrorDetails { code, message };
+			let error = RpcErrorDetails {
+				code,
+				message,
+				data: None,
+			};
 
 			RpcError {
 				error,
@@ -120,14 +273,17 @@ mod wasm_http_provider {
 	use std::task::Poll;
 
 	use futures::Future;
+	use js_sys::Reflect;
+	use js_sys::Uint8Array;
 	use pin_project::pin_project;
 	use pin_project::pinned_drop;
 	use send_wrapper::SendWrapper;
 	use wasm_bindgen::prelude::*;
+	use wasm_bindgen_futures::JsFuture;
 	use web_sys::AbortController;
+	use web_sys::ReadableStreamDefaultReader;
 
 	use super::*;
-	use crate::ClientError;
 
 	#[pin_project(PinnedDrop)]
 	struct AbortableRequest<F: Future<Output = Result<gloo_net::http::Response, gloo_net::Error>>> {
@@ -188,19 +344,8 @@ mod wasm_http_provider {
 		}
 
 		async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
-			let client_request = ClientRequest::builder()
-				.method(method)
-				.id(0)
-				.params(request)
-				.build();
-
 			let future = async move {
-				let controller = AbortController::new().unwrap_throw();
-				let signal = controller.signal();
-				let request = gloo_net::http::Request::post(&self.0)
-					.abort_signal(Some(&signal))
-					.json(&client_request)?;
-				let response = AbortableRequest::new(request.send(), controller).await?;
+				let response = self.post(method, request).await?;
 				let value = response.json().await?;
 
 				Ok::<Value, ClientError>(value)
@@ -210,12 +355,98 @@ mod wasm_http_provider {
 
 			Ok(value)
 		}
+
+		async fn send_raw(&self, method: &'static str, request: Value) -> ClientResult<Vec<u8>> {
+			let future = async move {
+				let response = self.post(method, request).await?;
+				let body = response.binary().await?;
+
+				Ok::<Vec<u8>, ClientError>(body)
+			};
+
+			SendWrapper::new(future).await
+		}
+
+		async fn send_streaming(
+			&self,
+			method: &'static str,
+			request: Value,
+		) -> ClientResult<ResponseChunks> {
+			let future = async move {
+				let response = self.post(method, request).await?;
+				let Some(body) = response.body() else {
+					return Ok(stream::empty().boxed_local());
+				};
+				let reader = BodyReader(body.get_reader().unchecked_into());
+
+				// Each chunk is awaited from the `ReadableStream` so the main thread can
+				// handle other events while a large body is downloaded and parsed.
+				let chunks = stream::try_unfold(reader, |reader| {
+					async move {
+						let result = JsFuture::from(reader.0.read()).await?;
+
+						if Reflect::get(&result, &"done".into())?.is_truthy() {
+							return Ok(None);
+						}
+
+						let chunk = Uint8Array::new(&Reflect::get(&result, &"value".into())?);
+
+						Ok(Some((chunk.to_vec(), reader)))
+					}
+				});
+
+				Ok::<_, ClientError>(chunks.boxed_local())
+			};
+
+			let chunks = SendWrapper::new(future).await?;
+
+			Ok(SendWrapper::new(chunks).boxed())
+		}
+	}
+
+	/// Cancels the response body when the stream of chunks is dropped before
+	/// it has been read to the end.
+	struct BodyReader(ReadableStreamDefaultReader);
+
+	impl Drop for BodyReader {
+		fn drop(&mut self) {
+			let _ = self.0.cancel();
+		}
 	}
 
 	impl HttpProvider {
 		pub fn new(url: impl Into<String>) -> Self {
 			Self(url.into())
 		}
+
+		async fn post(
+			&self,
+			method: &'static str,
+			request: Value,
+		) -> Result<gloo_net::http::Response, ClientError> {
+			let client_request = ClientRequest::builder()
+				.method(method)
+				.id(0)
+				.params(request)
+				.build();
+			let controller = AbortController::new().unwrap_throw();
+			let signal = controller.signal();
+			let request = gloo_net::http::Request::post(&self.0)
+				.abort_signal(Some(&signal))
+				.json(&client_request)?;
+			let response = AbortableRequest::new(request.send(), controller).await?;
+
+			Ok(response)
+		}
+
+		/// The browser negotiates response compression itself so the config
+		/// has no effect here.
+		pub fn new_with_config(
+			url: impl Into<String>,
+			_config: HttpProviderConfig,
+		) -> ClientResult<Self> {
+			Ok(Self::new(url))
+		}
 	}
 
 	impl From<serde_wasm_bindgen::Error> for ClientError {
@@ -239,9 +470,3 @@ mod wasm_http_provider {
 		}
 	}
 }
-
-pub const DEVNET: &str = "https://api.devnet.solana.com";
-pub const TESTNET: &str = "https://api.testnet.solana.com";
-pub const MAINNET: &str = "https://api.mainnet-beta.solana.com";
-pub const LOCALNET: &str = "http://127.0.0.1:8899";
-pub const DEBUG: &str = "http://34.90.18.145:8899";
@@ -0,0 +1,385 @@
+use std::collections::VecDeque;
+use std::mem;
+
+use futures::StreamExt;
+use futures::stream;
+use futures::stream::BoxStream;
+use serde::de::DeserializeOwned;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::ResponseChunks;
+use crate::RpcError;
+
+/// Deserialize the items of the json array found at the object `path` of a
+/// streamed JSON-RPC response, yielding each item as soon as its closing byte
+/// arrives.
+///
+/// Only one item is held in memory at a time. An error response, or a
+/// response without the array, ends the stream with the error.
+pub(crate) fn stream_json_array<T: DeserializeOwned + Send + 'static>(
+	chunks: ResponseChunks,
+	path: &'static [&'static str],
+) -> BoxStream<'static, ClientResult<T>> {
+	let state = (
+		Some(chunks),
+		JsonArraySplitter::new(path),
+		VecDeque::<Vec<u8>>::new(),
+	);
+
+	stream::unfold(state, |(mut chunks, mut splitter, mut items)| {
+		async move {
+			loop {
+				if let Some(item) = items.pop_front() {
+					let result = serde_json::from_slice::<T>(&item)
+						.map_err(|error| ClientError::Other(error.to_string()));
+
+					return Some((result, (chunks, splitter, items)));
+				}
+
+				match chunks.as_mut()?.next().await {
+					Some(Ok(chunk)) => splitter.push(&chunk, &mut items),
+					Some(Err(error)) => return Some((Err(error), (None, splitter, items))),
+					None => {
+						if let Err(error) = splitter.finish() {
+							return Some((Err(error), (None, splitter, items)));
+						}
+
+						chunks = None;
+					}
+				}
+			}
+		}
+	})
+	.boxed()
+}
+
+/// An incremental scanner which splits the items of a json array out of a
+/// response body which arrives in chunks of any size.
+///
+/// The array is found by the keys of the objects which contain it, so
+/// `["result", "transactions"]` finds the transactions of a `getBlock`
+/// response. Everything outside of the array is kept so that an error
+/// response can still be decoded.
+#[derive(Debug)]
+pub(crate) struct JsonArraySplitter {
+	path: &'static [&'static str],
+	frames: Vec<Frame>,
+	in_string: bool,
+	escaped: bool,
+	/// The object key currently being read.
+	key: Option<Vec<u8>>,
+	array: ArrayState,
+	item: Vec<u8>,
+	rest: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArrayState {
+	Searching,
+	/// Inside the target array, which is the frame at this depth.
+	Inside(usize),
+	Closed,
+}
+
+#[derive(Debug)]
+struct Frame {
+	object: bool,
+	key: Vec<u8>,
+	expect_key: bool,
+}
+
+impl Frame {
+	fn new(object: bool) -> Self {
+		Self {
+			object,
+			key: Vec::new(),
+			expect_key: object,
+		}
+	}
+}
+
+impl JsonArraySplitter {
+	pub(crate) fn new(path: &'static [&'static str]) -> Self {
+		Self {
+			path,
+			frames: Vec::new(),
+			in_string: false,
+			escaped: false,
+			key: None,
+			array: ArrayState::Searching,
+			item: Vec::new(),
+			rest: Vec::new(),
+		}
+	}
+
+	/// Scan the next `chunk` of the body and append every completed item to
+	/// `items`.
+	pub(crate) fn push(&mut self, chunk: &[u8], items: &mut VecDeque<Vec<u8>>) {
+		let mut index = 0;
+
+		while index < chunk.len() {
+			if let ArrayState::Inside(depth) = self.array {
+				index = self.push_item(chunk, index, depth, items);
+			} else {
+				self.push_rest(chunk[index]);
+				index += 1;
+			}
+		}
+	}
+
+	/// Check that the array was found and closed once the body has ended.
+	pub(crate) fn finish(&self) -> ClientResult<()> {
+		if self.array == ArrayState::Closed {
+			return Ok(());
+		}
+
+		if let Ok(error) = serde_json::from_slice::<RpcError>(&self.rest) {
+			return Err(error.into());
+		}
+
+		let path = self.path.join(".");
+		let message = if let ArrayState::Inside(_) = self.array {
+			format!("The response ended before the end of `{path}`")
+		} else {
+			format!("The response doesn't contain an array at `{path}`")
+		};
+
+		Err(ClientError::Other(message))
+	}
+
+	/// Scan the bytes of the target array from `index` and return the index
+	/// of the next byte to scan.
+	fn push_item(
+		&mut self,
+		chunk: &[u8],
+		index: usize,
+		depth: usize,
+		items: &mut VecDeque<Vec<u8>>,
+	) -> usize {
+		if self.in_string {
+			let byte = chunk[index];
+
+			if self.escaped {
+				self.item.push(byte);
+				self.escaped = false;
+				return index + 1;
+			}
+
+			// Copy the contents of strings in one go since they are most of the bytes of
+			// the encoded account data.
+			let length = chunk[index..]
+				.iter()
+				.position(|byte| matches!(byte, b'"' | b'\\'))
+				.unwrap_or(chunk.len() - index);
+
+			if length > 0 {
+				self.item.extend_from_slice(&chunk[index..index + length]);
+				return index + length;
+			}
+
+			self.item.push(byte);
+
+			if byte == b'\\' {
+				self.escaped = true;
+			} else {
+				self.in_string = false;
+			}
+
+			return index + 1;
+		}
+
+		let byte = chunk[index];
+
+		if self.frames.len() == depth {
+			match byte {
+				b',' => self.flush(items),
+				b']' => {
+					self.flush(items);
+					self.frames.pop();
+					self.array = ArrayState::Closed;
+					self.rest.push(byte);
+				}
+				byte if byte.is_ascii_whitespace() && self.item.is_empty() => {}
+				byte => self.push_item_byte(byte),
+			}
+		} else {
+			self.push_item_byte(byte);
+		}
+
+		index + 1
+	}
+
+	fn push_item_byte(&mut self, byte: u8) {
+		self.item.push(byte);
+
+		match byte {
+			b'"' => self.in_string = true,
+			b'{' | b'[' => self.frames.push(Frame::new(false)),
+			b'}' | b']' => {
+				self.frames.pop();
+			}
+			_ => {}
+		}
+	}
+
+	fn flush(&mut self, items: &mut VecDeque<Vec<u8>>) {
+		if !self.item.is_empty() {
+			items.push_back(mem::take(&mut self.item));
+		}
+	}
+
+	fn push_rest(&mut self, byte: u8) {
+		self.rest.push(byte);
+
+		if self.in_string {
+			if self.escaped {
+				self.escaped = false;
+			} else if byte == b'\\' {
+				self.escaped = true;
+			} else if byte == b'"' {
+				self.in_string = false;
+
+				if let (Some(key), Some(frame)) = (self.key.take(), self.frames.last_mut()) {
+					frame.key = key;
+					frame.expect_key = false;
+				}
+
+				return;
+			}
+
+			if let Some(key) = self.key.as_mut() {
+				key.push(byte);
+			}
+
+			return;
+		}
+
+		match byte {
+			b'"' => {
+				self.in_string = true;
+
+				if self.frames.last().is_some_and(|frame| frame.expect_key) {
+					self.key = Some(Vec::new());
+				}
+			}
+			b'{' => self.frames.push(Frame::new(true)),
+			b'[' => {
+				let is_target = self.array == ArrayState::Searching && self.is_at_path();
+				self.frames.push(Frame::new(false));
+
+				if is_target {
+					self.array = ArrayState::Inside(self.frames.len());
+				}
+			}
+			b'}' | b']' => {
+				self.frames.pop();
+			}
+			b',' => {
+				if let Some(frame) = self.frames.last_mut() {
+					frame.expect_key = frame.object;
+				}
+			}
+			_ => {}
+		}
+	}
+
+	fn is_at_path(&self) -> bool {
+		self.frames.len() == self.path.len()
+			&& self
+				.frames
+				.iter()
+				.zip(self.path)
+				.all(|(frame, key)| frame.object && frame.key == key.as_bytes())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use assert2::let_assert;
+	use serde_json::Value;
+	use serde_json::json;
+
+	use super::*;
+	use crate::RpcErrorCode;
+
+	fn split(body: &[u8], path: &'static [&'static str], chunk_size: usize) -> Vec<Value> {
+		let mut splitter = JsonArraySplitter::new(path);
+		let mut items = VecDeque::new();
+
+		for chunk in body.chunks(chunk_size) {
+			splitter.push(chunk, &mut items);
+		}
+
+		check!(splitter.finish().is_ok());
+
+		items
+			.iter()
+			.map(|item| serde_json::from_slice(item).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn splits_items_across_any_chunk_size() -> anyhow::Result<()> {
+		let result = json!([
+			{ "pubkey": "a", "account": { "data": ["a\"]},[", "base64"] } },
+			{ "pubkey": "b\\", "account": { "data": [[], {}] } },
+			1,
+			"c",
+			null,
+		]);
+		let body = serde_json::to_vec_pretty(&json!({
+			"jsonrpc": "2.0",
+			"result": result,
+			"id": 1,
+		}))?;
+
+		for chunk_size in [1, 2, 3, 7, 64, body.len()] {
+			check!(Value::from(split(&body, &["result"], chunk_size)) == result);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn finds_nested_arrays() -> anyhow::Result<()> {
+		let transactions = json!([{ "meta": { "fee": 5000 } }, { "meta": null }]);
+		let body = serde_json::to_vec(&json!({
+			"jsonrpc": "2.0",
+			"result": {
+				"blockhash": "[\"transactions\"]",
+				"rewards": [{ "transactions": [1] }],
+				"transactions": transactions,
+			},
+			"id": 1,
+		}))?;
+
+		check!(Value::from(split(&body, &["result", "transactions"], 5)) == transactions);
+
+		Ok(())
+	}
+
+	#[test]
+	fn error_responses_are_decoded() {
+		let body = br#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid param"},"id":1}"#;
+		let mut splitter = JsonArraySplitter::new(&["result"]);
+		let mut items = VecDeque::new();
+		splitter.push(body, &mut items);
+
+		check!(items.is_empty());
+		let_assert!(Err(ClientError::Rpc(error)) = splitter.finish());
+		check!(error.code() == RpcErrorCode::InvalidParams);
+	}
+
+	#[test]
+	fn truncated_responses_fail() {
+		let body = br#"{"jsonrpc":"2.0","result":[{"a":1},{"b""#;
+		let mut splitter = JsonArraySplitter::new(&["result"]);
+		let mut items = VecDeque::new();
+		splitter.push(body, &mut items);
+
+		check!(items.len() == 1);
+		check!(let Err(ClientError::Other(_)) = splitter.finish());
+	}
+}
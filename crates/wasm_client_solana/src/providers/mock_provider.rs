@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::RpcProvider;
+use crate::ClientError;
+use crate::ClientResult;
+use crate::RpcError;
+use crate::RpcErrorDetails;
+
+/// The JSON-RPC error code returned when no fixture is registered for a
+/// method.
+const METHOD_NOT_FOUND_CODE: i32 = -32601;
+
+/// An in-memory [`RpcProvider`] which replays canned JSON responses.
+///
+/// Responses are registered per method and are returned in the order they
+/// were added. The last response registered for a method is replayed for
+/// every subsequent request, which keeps fixtures short for polling methods
+/// like `getSignatureStatuses`.
+///
+/// Every request is recorded so that tests can assert on the parameters that
+/// were sent.
+///
+/// ```
+/// use serde_json::json;
+/// use solana_sdk::pubkey::Pubkey;
+/// use wasm_client_solana::MockRpcProvider;
+/// use wasm_client_solana::SolanaRpcClient;
+/// # use wasm_client_solana::ClientResult;
+///
+/// # async fn run() -> ClientResult<()> {
+/// let provider = MockRpcProvider::new()
+/// 	.with_result("getBalance", json!({ "context": { "slot": 1 }, "value": 42 }));
+/// let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+/// let balance = rpc.get_balance(&Pubkey::new_unique()).await?;
+///
+/// assert_eq!(balance, 42);
+/// assert_eq!(provider.requests().len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockRpcProvider {
+	url: String,
+	responses: Arc<Mutex<HashMap<String, VecDeque<Value>>>>,
+	requests: Arc<Mutex<Vec<(String, Value)>>>,
+}
+
+impl Default for MockRpcProvider {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MockRpcProvider {
+	/// Create an empty provider which reports the [`LOCALNET`](crate::LOCALNET)
+	/// url.
+	pub fn new() -> Self {
+		Self::new_with_url(crate::LOCALNET)
+	}
+
+	/// Create an empty provider which reports the provided url.
+	pub fn new_with_url(url: impl Into<String>) -> Self {
+		Self {
+			url: url.into(),
+			responses: Arc::default(),
+			requests: Arc::default(),
+		}
+	}
+
+	/// Create a provider from a JSON object mapping each method name to either
+	/// a single raw JSON-RPC response or an array of responses to replay in
+	/// order.
+	///
+	/// ```json
+	/// {
+	///   "getBalance": { "jsonrpc": "2.0", "id": 0, "result": { "context": { "slot": 1 }, "value": 10 } },
+	///   "getSlot": [
+	///     { "jsonrpc": "2.0", "id": 0, "result": 1 },
+	///     { "jsonrpc": "2.0", "id": 0, "result": 2 }
+	///   ]
+	/// }
+	/// ```
+	pub fn from_fixtures(fixtures: &Value) -> ClientResult<Self> {
+		let Some(fixtures) = fixtures.as_object() else {
+			return Err(ClientError::Other(
+				"mock rpc fixtures must be a json object keyed by method".into(),
+			));
+		};
+
+		let provider = Self::new();
+
+		for (method, fixture) in fixtures {
+			match fixture {
+				Value::Array(responses) => {
+					for response in responses {
+						provider.push_response(method, response.clone());
+					}
+				}
+				response => provider.push_response(method, response.clone()),
+			}
+		}
+
+		Ok(provider)
+	}
+
+	/// Queue a raw JSON-RPC response for the `method`. This can be used to
+	/// replay error responses.
+	pub fn push_response(&self, method: impl Into<String>, response: Value) {
+		let mut responses = self.responses.lock().unwrap_or_else(PoisonError::into_inner);
+		responses
+			.entry(method.into())
+			.or_default()
+			.push_back(response);
+	}
+
+	/// Queue a successful response for the `method` by wrapping the `result`
+	/// in a JSON-RPC envelope.
+	pub fn push_result(&self, method: impl Into<String>, result: impl Serialize) {
+		let result = serde_json::to_value(result).unwrap_or(Value::Null);
+		let response = serde_json::json!({ "jsonrpc": "2.0", "id": 0, "result": result });
+
+		self.push_response(method, response);
+	}
+
+	/// Builder style version of [`MockRpcProvider::push_response`].
+	#[must_use]
+	pub fn with_response(self, method: impl Into<String>, response: Value) -> Self {
+		self.push_response(method, response);
+		self
+	}
+
+	/// Builder style version of [`MockRpcProvider::push_result`].
+	#[must_use]
+	pub fn with_result(self, method: impl Into<String>, result: impl Serialize) -> Self {
+		self.push_result(method, result);
+		self
+	}
+
+	/// All the requests received by this provider as `(method, params)`
+	/// pairs, in the order they were sent.
+	pub fn requests(&self) -> Vec<(String, Value)> {
+		self.requests
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.clone()
+	}
+
+	/// Remove all recorded requests.
+	pub fn clear_requests(&self) {
+		self.requests
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.clear();
+	}
+
+	fn next_response(&self, method: &str) -> Option<Value> {
+		let mut responses = self.responses.lock().unwrap_or_else(PoisonError::into_inner);
+		let queue = responses.get_mut(method)?;
+
+		if queue.len() > 1 {
+			queue.pop_front()
+		} else {
+			queue.front().cloned()
+		}
+	}
+}
+
+#[async_trait]
+impl RpcProvider for MockRpcProvider {
+	fn url(&self) -> String {
+		self.url.clone()
+	}
+
+	async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
+		self.requests
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.push((method.to_string(), request));
+
+		self.next_response(method).ok_or_else(|| {
+			RpcError {
+				error: RpcErrorDetails {
+					code: METHOD_NOT_FOUND_CODE,
+					message: format!("no mock response registered for `{method}`"),
+					data: None,
+				},
+				..Default::default()
+			}
+			.into()
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use futures::executor::block_on;
+	use serde_json::json;
+
+	use super::*;
+	use crate::ClientResponse;
+	use crate::GetBalanceRequest;
+	use crate::GetBalanceResponse;
+	use crate::HttpMethod;
+
+	#[test]
+	fn replays_fixtures_in_order() -> anyhow::Result<()> {
+		let provider = MockRpcProvider::from_fixtures(&json!({
+			"getSlot": [
+				{ "jsonrpc": "2.0", "id": 0, "result": 1 },
+				{ "jsonrpc": "2.0", "id": 0, "result": 2 },
+			],
+		}))?;
+
+		let first = block_on(provider.send("getSlot", json!([])))?;
+		let second = block_on(provider.send("getSlot", json!([])))?;
+		let third = block_on(provider.send("getSlot", json!([])))?;
+
+		check!(first["result"] == 1);
+		check!(second["result"] == 2);
+		check!(third["result"] == 2);
+		check!(provider.requests().len() == 3);
+
+		Ok(())
+	}
+
+	#[test]
+	fn records_requests() -> anyhow::Result<()> {
+		let pubkey = solana_sdk::pubkey::Pubkey::new_unique();
+		let provider = MockRpcProvider::new().with_result(
+			GetBalanceRequest::NAME,
+			GetBalanceResponse {
+				context: crate::Context {
+					slot: 1,
+					api_version: None,
+				},
+				value: 42,
+			},
+		);
+		let params = serde_json::to_value(GetBalanceRequest::new(pubkey))?;
+		let value = block_on(provider.send(GetBalanceRequest::NAME, params.clone()))?;
+		let response: ClientResponse<GetBalanceResponse> = serde_json::from_value(value)?;
+
+		check!(response.result.value == 42);
+		check!(provider.requests() == vec![(GetBalanceRequest::NAME.to_string(), params)]);
+
+		provider.clear_requests();
+		check!(provider.requests().is_empty());
+
+		Ok(())
+	}
+
+	#[test]
+	fn missing_fixture_is_an_error() {
+		let provider = MockRpcProvider::new();
+		let result = block_on(provider.send("getSlot", json!([])));
+
+		check!(let Err(ClientError::Rpc(_)) = result);
+	}
+
+	#[test]
+	fn invalid_fixtures() {
+		check!(MockRpcProvider::from_fixtures(&json!([])).is_err());
+	}
+}
@@ -1,5 +1,9 @@
 pub use http_provider::*;
+pub(crate) use json_stream::*;
+pub use mock_provider::*;
 pub use websocket_provider::*;
 
 mod http_provider;
+mod json_stream;
+mod mock_provider;
 mod websocket_provider;
@@ -8,6 +8,7 @@ use std::task::ready;
 
 use fork_stream::Forked;
 use fork_stream::StreamExt as _;
+use futures::Sink;
 use futures::SinkExt;
 use futures::Stream;
 use futures::StreamExt;
@@ -32,7 +33,7 @@ use crate::SubscriptionResult;
 use crate::UnsubscriptionResult;
 use crate::WebSocketMethod;
 use crate::WebSocketNotification;
-use crate::utils::get_ws_url;
+use crate::utils::derive_websocket_url;
 
 pub trait ToWebSocketValue {
 	fn to_websocket_value(&self) -> Result<Value, ClientWebSocketError>;
@@ -52,6 +53,44 @@ where
 	}
 }
 
+macro_rules! pubsub_transport {
+	($($bounds:tt)*) => {
+		/// The socket side of the rpc client.
+		///
+		/// Any duplex stream of JSON values can carry pubsub messages. Implement
+		/// this for a custom socket (e.g. the `WebSocket` exposed by Cloudflare
+		/// Workers or Deno) and pass it to
+		/// [`WebSocketProvider::new_with_transport`]. Messages sent into the
+		/// sink are complete JSON-RPC requests and every item yielded by the
+		/// stream should be a parsed JSON-RPC message.
+		///
+		/// The transport must also be `Send` when the `ssr` feature is enabled
+		/// on native targets.
+		pub trait PubsubTransport:
+			Stream<Item = Result<Value, ClientWebSocketError>>
+			+ Sink<Value, Error = ClientWebSocketError>
+			$($bounds)*
+		{
+		}
+
+		impl<T> PubsubTransport for T where
+			T: Stream<Item = Result<Value, ClientWebSocketError>>
+				+ Sink<Value, Error = ClientWebSocketError>
+				$($bounds)*
+		{
+		}
+	};
+}
+
+#[cfg(all(feature = "ssr", not(target_arch = "wasm32")))]
+pubsub_transport!(+ Send);
+#[cfg(not(all(feature = "ssr", not(target_arch = "wasm32"))))]
+pubsub_transport!();
+
+/// The boxed transport shared by the [`WebSocketProvider`] and its
+/// subscriptions.
+pub(crate) type PubsubStream = Pin<Box<dyn PubsubTransport>>;
+
 #[derive(Clone, derive_more::Debug)]
 pub struct WebSocketProvider {
 	/// The websocket url.
@@ -59,15 +98,28 @@ pub struct WebSocketProvider {
 	/// The client ID which identifies current client ID.
 	id: Arc<std::sync::Mutex<u32>>,
 	#[debug(skip)]
-	sender: Arc<Mutex<SplitSink<WebSocketStream, Value>>>,
+	sender: Arc<Mutex<SplitSink<PubsubStream, Value>>>,
 	#[debug(skip)]
-	receiver: Forked<SplitStream<WebSocketStream>>,
+	receiver: Forked<SplitStream<PubsubStream>>,
 }
 
 impl WebSocketProvider {
 	pub fn new(url: impl Into<String>) -> Self {
-		let url = get_ws_url(url);
+		let url = derive_websocket_url(&url.into());
 		let stream = WebSocketStream::new(&url);
+
+		Self::new_with_transport(url, stream)
+	}
+
+	/// Create a provider which sends and receives pubsub messages through a
+	/// custom [`PubsubTransport`]. The `url` is only used for reporting and is
+	/// stored as is.
+	pub fn new_with_transport(
+		url: impl Into<String>,
+		transport: impl PubsubTransport + 'static,
+	) -> Self {
+		let url = url.into();
+		let stream: PubsubStream = Box::pin(transport);
 		let (sink, stream) = stream.split();
 		let receiver = stream.fork();
 		let sender = Arc::new(Mutex::new(sink));
@@ -144,9 +196,9 @@ pub struct Unsubscription {
 	/// The name of the method used to unsubscribe.
 	pub(crate) method: &'static str,
 	/// The shared sink for pushing messages into the websocket stream.
-	pub(crate) sender: Arc<Mutex<SplitSink<WebSocketStream, Value>>>,
+	pub(crate) sender: Arc<Mutex<SplitSink<PubsubStream, Value>>>,
 	/// The shared receiver for websocket messages.
-	pub(crate) receiver: Forked<SplitStream<WebSocketStream>>,
+	pub(crate) receiver: Forked<SplitStream<PubsubStream>>,
 	/// The `id` that was originally used to create the parent subscription.
 	pub(crate) id: u32,
 	/// The `subscription_id` used to unsubscribe.
@@ -211,9 +263,9 @@ impl Unsubscription {
 pub struct Subscription<T: DeserializeOwned + WebSocketNotification> {
 	/// The shared receiver for receiving messages.
 	#[pin]
-	pub(crate) receiver: Forked<SplitStream<WebSocketStream>>,
+	pub(crate) receiver: Forked<SplitStream<PubsubStream>>,
 	/// The shared sink for pushing messages into the websocket stream.
-	pub(crate) sender: Arc<Mutex<SplitSink<WebSocketStream, Value>>>,
+	pub(crate) sender: Arc<Mutex<SplitSink<PubsubStream, Value>>>,
 	#[builder(default)]
 	pub(crate) latest: PhantomData<T>,
 	/// The `id` that was originally used to create the parent subscription.
@@ -308,23 +360,25 @@ impl<T: DeserializeOwned + WebSocketNotification> Stream for Subscription<T> {
 		let subscription_id = self.get_unsubscription().subscription_id;
 		let mut this = self.project();
 
-		let Some(result) = ready!(this.receiver.as_mut().poll_next(cx)) else {
-			return Poll::Ready(None);
-		};
+		// Skip the messages of other requests and subscriptions until the next
+		// notification of this subscription is received.
+		loop {
+			let Some(result) = ready!(this.receiver.as_mut().poll_next(cx)) else {
+				return Poll::Ready(None);
+			};
 
-		let Ok(value) = result else {
-			return Poll::Pending;
-		};
+			let Ok(value) = result else {
+				continue;
+			};
 
-		let Some(json) = serde_json::from_value::<SubscriptionResponse<T>>(value).ok() else {
-			return Poll::Pending;
-		};
+			let Some(json) = serde_json::from_value::<SubscriptionResponse<T>>(value).ok() else {
+				continue;
+			};
 
-		if json.method != T::NOTIFICATION || json.params.subscription != subscription_id {
-			return Poll::Pending;
+			if json.method == T::NOTIFICATION && json.params.subscription == subscription_id {
+				return Poll::Ready(Some(json));
+			}
 		}
-
-		Poll::Ready(Some(json))
 	}
 }
 
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
 use bincode::serialize;
@@ -9,6 +11,7 @@ use serde::de::DeserializeOwned;
 use serde_with::DisplayFromStr;
 use serde_with::serde_as;
 use serde_with::skip_serializing_none;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
 use solana_sdk::clock::Epoch;
 use solana_sdk::clock::Slot;
 use solana_sdk::commitment_config::CommitmentConfig;
@@ -19,10 +22,15 @@ use solana_sdk::signature::Signature;
 use typed_builder::TypedBuilder;
 
 use super::rpc_filter::RpcFilterType;
+use super::rpc_filter::TokenAccountsFilter;
 use crate::ClientError;
 use crate::ClientResult;
+use crate::DEFAULT_COMPUTE_UNIT_MARGIN_PERCENT;
+use crate::PriorityFeeOracle;
 use crate::RpcError;
+use crate::SLEEP_MS;
 use crate::SolanaRpcClient;
+use crate::Urgency;
 use crate::impl_websocket_method;
 use crate::nonce_utils;
 use crate::solana_account_decoder::UiAccount;
@@ -190,9 +198,10 @@ pub fn deserialize_and_decode<T: DeserializeOwned>(
 	Ok(decoded)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcSignatureStatusConfig {
+	#[builder(setter(strip_bool))]
 	pub search_transaction_history: bool,
 }
 
@@ -208,6 +217,11 @@ pub struct RpcSendTransactionConfig {
 	pub encoding: Option<UiTransactionEncoding>,
 	pub max_retries: Option<usize>,
 	pub min_context_slot: Option<Slot>,
+	/// Send the transaction without first checking that it fits in a packet.
+	/// This is never sent to the rpc.
+	#[serde(skip)]
+	#[builder(setter(!strip_option))]
+	pub skip_size_check: bool,
 }
 
 #[skip_serializing_none]
@@ -241,6 +255,99 @@ pub struct RpcSimulateTransactionConfig {
 	pub min_context_slot: Option<Slot>,
 }
 
+/// The options used by [`SolanaRpcClient::prepare_transaction`].
+#[derive(Debug, Clone, PartialEq, Eq, TypedBuilder)]
+pub struct PrepareTransactionConfig {
+	/// The percentage added to the compute units consumed by the simulation.
+	#[builder(default = DEFAULT_COMPUTE_UNIT_MARGIN_PERCENT)]
+	pub compute_unit_margin_percent: u64,
+	/// Set the compute unit price to this percentile of the prioritization
+	/// fees recently paid to lock the writable accounts of the transaction.
+	#[builder(default, setter(strip_option))]
+	pub priority_fee_percentile: Option<u8>,
+	/// The lookup tables used to compile the `v0` message.
+	#[builder(default)]
+	pub address_lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl Default for PrepareTransactionConfig {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+/// The options used by
+/// [`SolanaRpcClient::send_and_confirm_transaction_with_options`].
+#[derive(Debug, Clone, PartialEq, Eq, TypedBuilder)]
+pub struct SendOptions {
+	/// The text of an spl-memo instruction appended after all other
+	/// instructions.
+	#[builder(default, setter(into, strip_option))]
+	pub memo: Option<String>,
+	#[builder(default, setter(strip_option))]
+	pub compute_unit_limit: Option<u32>,
+	/// The compute unit price as a fixed number of micro-lamports or
+	/// estimated by a [`PriorityFeeOracle`].
+	#[builder(default, setter(into, strip_option))]
+	pub compute_unit_price: Option<ComputeUnitPrice>,
+	#[builder(default)]
+	pub skip_preflight: bool,
+}
+
+impl Default for SendOptions {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+/// The compute unit price set by [`SendOptions`].
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComputeUnitPrice {
+	/// A fixed price in micro-lamports.
+	Fixed(u64),
+	/// The price estimated by the oracle for the writable accounts of the
+	/// transaction.
+	Oracle(PriorityFeeOracle, Urgency),
+}
+
+impl From<u64> for ComputeUnitPrice {
+	fn from(micro_lamports: u64) -> Self {
+		Self::Fixed(micro_lamports)
+	}
+}
+
+impl From<(PriorityFeeOracle, Urgency)> for ComputeUnitPrice {
+	fn from((oracle, urgency): (PriorityFeeOracle, Urgency)) -> Self {
+		Self::Oracle(oracle, urgency)
+	}
+}
+
+/// The options used by [`SolanaRpcClient::transfer_sol`] and
+/// [`SolanaRpcClient::transfer_token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypedBuilder)]
+pub struct TransferConfig {
+	/// Create the associated token account of the recipient when it doesn't
+	/// exist. The sender pays the rent. This is only used by token transfers.
+	#[builder(default = true)]
+	pub create_associated_token_account: bool,
+	#[builder(default, setter(strip_option))]
+	pub compute_unit_limit: Option<u32>,
+	/// The compute unit price in micro-lamports.
+	#[builder(default, setter(strip_option))]
+	pub compute_unit_price: Option<u64>,
+	/// Use the durable nonce stored in this account instead of a recent
+	/// blockhash. The sender must be the nonce authority.
+	#[builder(default, setter(strip_option))]
+	pub nonce_account: Option<Pubkey>,
+}
+
+impl Default for TransferConfig {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
 #[serde_as]
 #[skip_serializing_none]
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
@@ -335,12 +442,15 @@ pub struct RpcLargestAccountsConfig {
 	pub filter: Option<RpcLargestAccountsFilter>,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+#[builder(field_defaults(default, setter(strip_option)))]
 #[serde(rename_all = "camelCase")]
 pub struct RpcSupplyConfig {
 	#[serde(flatten)]
 	pub commitment: Option<CommitmentConfig>,
 	#[serde(default)]
+	#[builder(!default, setter(!strip_option, strip_bool))]
 	pub exclude_non_circulating_accounts_list: bool,
 }
 
@@ -432,14 +542,29 @@ impl<'de> Deserialize<'de> for ProgramSubscribeRequest {
 	}
 }
 
+/// The filter passed as the second positional parameter of
+/// `getTokenAccountsByOwner` and `getTokenAccountsByDelegate`.
+///
+/// It serializes to either `{"mint": "<pubkey>"}` or
+/// `{"programId": "<pubkey>"}`. Any other shape is rejected by the node with
+/// `Invalid param: unrecognized filter`.
 #[serde_as]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcTokenAccountsFilter {
 	Mint(#[serde_as(as = "DisplayFromStr")] Pubkey),
 	ProgramId(#[serde_as(as = "DisplayFromStr")] Pubkey),
 }
 
+impl From<TokenAccountsFilter> for RpcTokenAccountsFilter {
+	fn from(filter: TokenAccountsFilter) -> Self {
+		match filter {
+			TokenAccountsFilter::Mint(mint) => Self::Mint(mint),
+			TokenAccountsFilter::ProgramId(program_id) => Self::ProgramId(program_id),
+		}
+	}
+}
+
 #[serde_as]
 #[skip_serializing_none]
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
@@ -580,7 +705,16 @@ pub struct RpcContextConfig {
 	pub min_context_slot: Option<Slot>,
 }
 
-#[derive(Debug, Default)]
+impl From<CommitmentConfig> for RpcContextConfig {
+	fn from(commitment: CommitmentConfig) -> Self {
+		Self {
+			commitment: Some(commitment),
+			min_context_slot: None,
+		}
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy)]
 pub struct GetConfirmedSignaturesForAddress2Config {
 	pub before: Option<Signature>,
 	pub until: Option<Signature>,
@@ -589,7 +723,8 @@ pub struct GetConfirmedSignaturesForAddress2Config {
 }
 
 #[skip_serializing_none]
-#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+#[builder(field_defaults(default, setter(strip_option)))]
 #[serde(rename_all = "camelCase")]
 pub struct RpcTransactionLogsConfig {
 	#[serde(flatten)]
@@ -643,6 +778,75 @@ pub struct RpcSignatureSubscribeConfig {
 	pub enable_received_notification: Option<bool>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, TypedBuilder)]
+pub struct SignatureSubscribeRequest {
+	pub signature: Signature,
+	#[builder(default, setter(into))]
+	pub config: RpcSignatureSubscribeConfig,
+}
+
+impl_websocket_method!(SignatureSubscribeRequest, "signature");
+
+impl Serialize for SignatureSubscribeRequest {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		#[serde_as]
+		#[derive(Serialize)]
+		#[serde(rename = "SignatureSubscribeRequest")]
+		struct Inner<'serde_tuple_inner>(
+			#[serde_as(as = "DisplayFromStr")] &'serde_tuple_inner Signature,
+			&'serde_tuple_inner RpcSignatureSubscribeConfig,
+		);
+
+		let inner = Inner(&self.signature, &self.config);
+		Serialize::serialize(&inner, serde_tuple::Serializer(serializer))
+	}
+}
+
+impl<'de> Deserialize<'de> for SignatureSubscribeRequest {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		#[serde_as]
+		#[derive(Deserialize)]
+		#[serde(rename = "SignatureSubscribeRequest")]
+		struct Inner(
+			#[serde_as(as = "DisplayFromStr")] Signature,
+			RpcSignatureSubscribeConfig,
+		);
+
+		let inner: Inner = Deserialize::deserialize(serde_tuple::Deserializer(deserializer))?;
+		Ok(SignatureSubscribeRequest {
+			signature: inner.0,
+			config: inner.1,
+		})
+	}
+}
+
+/// The options used by [`SolanaRpcClient::watch_signature`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypedBuilder)]
+pub struct WatchSignatureConfig {
+	/// The last block height at which the blockhash of the transaction is
+	/// valid. The watch ends with [`ClientError::TransactionExpired`] once
+	/// the block height passes it before the transaction is processed.
+	/// Without it the watch continues until the transaction is finalized.
+	#[builder(default, setter(strip_option))]
+	pub last_valid_block_height: Option<u64>,
+	/// How often the status is polled. The status is also polled when no
+	/// websocket is available.
+	#[builder(default = Duration::from_millis(SLEEP_MS))]
+	pub poll_interval: Duration,
+}
+
+impl Default for WatchSignatureConfig {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, TypedBuilder)]
 pub struct BlockSubscribeRequest {
 	pub filter: RpcBlockSubscribeFilter,
@@ -730,3 +934,149 @@ impl<'de> Deserialize<'de> for LogsSubscribeRequest {
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use serde_json::json;
+	use solana_sdk::pubkey;
+
+	use super::*;
+	use crate::methods::GetBalanceRequest;
+	use crate::methods::GetTokenAccountsByDelegateRequest;
+	use crate::methods::GetTokenAccountsByOwnerRequest;
+
+	const OWNER: Pubkey = pubkey!("4Qkev8aNZcqFNSRhQzwyLMFSsi94jHqE8WNVTJzTP99F");
+	const MINT: Pubkey = pubkey!("3wyAj7Rt1TWVPZVteFJPLa26JmLvdb1CAKEFZm3NY75E");
+	const TOKEN_PROGRAM: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+	#[test]
+	fn token_accounts_filter_serde() -> anyhow::Result<()> {
+		let mint = RpcTokenAccountsFilter::from(TokenAccountsFilter::Mint(MINT));
+		let program_id =
+			RpcTokenAccountsFilter::from(TokenAccountsFilter::ProgramId(TOKEN_PROGRAM));
+
+		check!(serde_json::to_value(mint)? == json!({ "mint": MINT.to_string() }));
+		check!(
+			serde_json::to_value(program_id)? == json!({ "programId": TOKEN_PROGRAM.to_string() })
+		);
+		check!(
+			serde_json::from_value::<RpcTokenAccountsFilter>(
+				json!({ "programId": TOKEN_PROGRAM.to_string() })
+			)? == program_id
+		);
+		check!(
+			serde_json::from_value::<RpcTokenAccountsFilter>(json!({ "owner": OWNER.to_string() }))
+				.is_err()
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn signature_subscribe_request_serde() -> anyhow::Result<()> {
+		let signature = Signature::from([7; 64]);
+		let request = SignatureSubscribeRequest::builder()
+			.signature(signature)
+			.config(
+				RpcSignatureSubscribeConfig::builder()
+					.commitment(CommitmentConfig::finalized())
+					.build(),
+			)
+			.build();
+		let value = json!([signature.to_string(), { "commitment": "finalized" }]);
+
+		check!(serde_json::to_value(&request)? == value);
+		check!(serde_json::from_value::<SignatureSubscribeRequest>(value)? == request);
+
+		Ok(())
+	}
+
+	#[test]
+	fn token_accounts_filter_is_positional() -> anyhow::Result<()> {
+		let owner_request =
+			GetTokenAccountsByOwnerRequest::new(OWNER, RpcTokenAccountsFilter::Mint(MINT));
+		let delegate_request = GetTokenAccountsByDelegateRequest::new_program_with_config(
+			OWNER,
+			TOKEN_PROGRAM,
+			RpcAccountInfoConfig {
+				commitment: Some(CommitmentConfig::confirmed()),
+				..Default::default()
+			},
+		);
+
+		check!(
+			serde_json::to_value(owner_request)?
+				== json!([OWNER.to_string(), { "mint": MINT.to_string() }, null])
+		);
+		check!(
+			serde_json::to_value(delegate_request)?
+				== json!([
+					OWNER.to_string(),
+					{ "programId": TOKEN_PROGRAM.to_string() },
+					{ "commitment": "confirmed" }
+				])
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn builders_skip_unset_fields() -> anyhow::Result<()> {
+		check!(serde_json::to_value(RpcContextConfig::default())? == json!({}));
+		check!(
+			serde_json::to_value(RpcSupplyConfig::default())?
+				== json!({ "excludeNonCirculatingAccountsList": false })
+		);
+		check!(RpcSignatureStatusConfig::builder().build() == RpcSignatureStatusConfig::default());
+		check!(
+			RpcAccountInfoConfig::builder().build()
+				== RpcAccountInfoConfig {
+					encoding: Some(UiAccountEncoding::Base64),
+					..Default::default()
+				}
+		);
+
+		let config = RpcContextConfig::builder()
+			.commitment(CommitmentConfig::finalized())
+			.min_context_slot(100)
+			.build();
+		check!(
+			serde_json::to_value(config)?
+				== json!({ "commitment": "finalized", "minContextSlot": 100 })
+		);
+
+		let config = RpcSupplyConfig::builder()
+			.exclude_non_circulating_accounts_list()
+			.build();
+		check!(
+			serde_json::to_value(config)? == json!({ "excludeNonCirculatingAccountsList": true })
+		);
+
+		let config = RpcSignatureStatusConfig::builder()
+			.search_transaction_history()
+			.build();
+		check!(serde_json::to_value(config)? == json!({ "searchTransactionHistory": true }));
+
+		Ok(())
+	}
+
+	#[test]
+	fn commitment_requests_accept_min_context_slot() -> anyhow::Result<()> {
+		let request = GetBalanceRequest::new_with_config(OWNER, CommitmentConfig::confirmed());
+		check!(
+			serde_json::to_value(request)?
+				== json!([OWNER.to_string(), { "commitment": "confirmed" }])
+		);
+
+		let request = GetBalanceRequest::new_with_config(
+			OWNER,
+			RpcContextConfig::builder().min_context_slot(42).build(),
+		);
+		check!(
+			serde_json::to_value(request)? == json!([OWNER.to_string(), { "minContextSlot": 42 }])
+		);
+
+		Ok(())
+	}
+}
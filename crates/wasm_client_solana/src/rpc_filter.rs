@@ -14,6 +14,12 @@ use thiserror::Error;
 const MAX_DATA_SIZE: usize = 128;
 const MAX_DATA_BASE58_SIZE: usize = 175;
 const MAX_DATA_BASE64_SIZE: usize = 172;
+/// The maximum number of filters accepted by `getProgramAccounts`.
+pub const MAX_FILTERS: usize = 4;
+/// The offset of the mint in a token account.
+pub const TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+/// The offset of the owner in a token account.
+pub const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -114,6 +120,111 @@ pub enum RpcFilterError {
 	Base58DecodeError(#[from] bs58::decode::Error),
 	#[error("base64 decode error")]
 	Base64DecodeError(#[from] base64::DecodeError),
+	#[error("{0} filters were provided but at most {MAX_FILTERS} are supported")]
+	TooManyFilters(usize),
+}
+
+/// Build the filters for `getProgramAccounts` and check them against the
+/// limits enforced by rpc nodes.
+///
+/// ```
+/// use solana_sdk::pubkey::Pubkey;
+/// use wasm_client_solana::rpc_filter::RpcFilterBuilder;
+/// # use wasm_client_solana::rpc_filter::RpcFilterError;
+///
+/// # fn run() -> Result<(), RpcFilterError> {
+/// let filters = RpcFilterBuilder::new()
+/// 	.data_size(165)
+/// 	.token_account_owner(&Pubkey::new_unique())
+/// 	.build()?;
+///
+/// assert_eq!(filters.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RpcFilterBuilder {
+	filters: Vec<RpcFilterType>,
+}
+
+impl RpcFilterBuilder {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add any filter.
+	#[must_use]
+	pub fn filter(mut self, filter: RpcFilterType) -> Self {
+		self.filters.push(filter);
+		self
+	}
+
+	/// Only match accounts with data of exactly `size` bytes.
+	#[must_use]
+	pub fn data_size(self, size: u64) -> Self {
+		self.filter(RpcFilterType::DataSize(size))
+	}
+
+	#[must_use]
+	pub fn memcmp(self, memcmp: Memcmp) -> Self {
+		self.filter(RpcFilterType::Memcmp(memcmp))
+	}
+
+	/// Only match accounts with `bytes` at the `offset` of their data.
+	#[must_use]
+	pub fn memcmp_bytes(self, offset: usize, bytes: &[u8]) -> Self {
+		self.memcmp(Memcmp::new_base58_encoded(offset, bytes))
+	}
+
+	/// Only match accounts with the `pubkey` at the `offset` of their data.
+	#[must_use]
+	pub fn memcmp_pubkey(self, offset: usize, pubkey: &Pubkey) -> Self {
+		self.memcmp_bytes(offset, pubkey.as_ref())
+	}
+
+	/// Only match valid token accounts, including token 2022 accounts.
+	#[must_use]
+	pub fn token_account_state(self) -> Self {
+		self.filter(RpcFilterType::TokenAccountState)
+	}
+
+	/// Only match token accounts owned by `owner`.
+	#[must_use]
+	pub fn token_account_owner(self, owner: &Pubkey) -> Self {
+		self.memcmp_pubkey(TOKEN_ACCOUNT_OWNER_OFFSET, owner)
+	}
+
+	/// Only match token accounts for the `mint`.
+	#[must_use]
+	pub fn token_account_mint(self, mint: &Pubkey) -> Self {
+		self.memcmp_pubkey(TOKEN_ACCOUNT_MINT_OFFSET, mint)
+	}
+
+	/// Only match anchor accounts of type `T`.
+	#[cfg(feature = "anchor")]
+	#[must_use]
+	pub fn anchor_discriminator<T: anchor_lang::Discriminator>(self) -> Self {
+		self.memcmp_bytes(0, T::DISCRIMINATOR)
+	}
+
+	/// Check the filters and return them.
+	///
+	/// # Errors
+	///
+	/// Returns [`RpcFilterError::TooManyFilters`] when more than
+	/// [`MAX_FILTERS`] were added and an error when the bytes of a memcmp
+	/// filter are invalid or larger than 128 bytes.
+	pub fn build(self) -> Result<Vec<RpcFilterType>, RpcFilterError> {
+		if self.filters.len() > MAX_FILTERS {
+			return Err(RpcFilterError::TooManyFilters(self.filters.len()));
+		}
+
+		for filter in &self.filters {
+			filter.verify()?;
+		}
+
+		Ok(self.filters)
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -185,6 +296,24 @@ impl Memcmp {
 		}
 	}
 
+	pub fn new_base64_encoded(offset: usize, bytes: &[u8]) -> Self {
+		Self {
+			offset,
+			bytes: MemcmpEncodedBytes::Base64(base64::encode(bytes)),
+			encoding: None,
+		}
+	}
+
+	/// Create a filter from `bytes` which are already base64 encoded. The
+	/// filter is sent with an explicit `base64` encoding.
+	pub fn new_base64(offset: usize, bytes: &str) -> Self {
+		Self {
+			offset,
+			bytes: MemcmpEncodedBytes::Base64(bytes.to_string()),
+			encoding: None,
+		}
+	}
+
 	pub fn bytes(&self) -> Option<Cow<Vec<u8>>> {
 		use MemcmpEncodedBytes::Base58;
 		use MemcmpEncodedBytes::Base64;
@@ -449,6 +578,125 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_memcmp_serde() {
+		let base64 = RpcFilterType::Memcmp(Memcmp::new_base64(4, "AQID"));
+		let value = serde_json::to_value(&base64).unwrap();
+		assert_eq!(
+			value,
+			serde_json::json!({ "memcmp": { "offset": 4, "bytes": "AQID", "encoding": "base64" } })
+		);
+		assert_eq!(
+			serde_json::from_value::<RpcFilterType>(value).unwrap(),
+			base64
+		);
+		assert_eq!(
+			RpcFilterType::Memcmp(Memcmp::new_base64_encoded(4, &[1, 2, 3])),
+			base64
+		);
+
+		let raw = RpcFilterType::Memcmp(Memcmp::new_raw_bytes(4, vec![1, 2, 3]));
+		let value = serde_json::to_value(&raw).unwrap();
+		assert_eq!(
+			value,
+			serde_json::json!({ "memcmp": { "offset": 4, "bytes": [1, 2, 3], "encoding": null } })
+		);
+		assert_eq!(serde_json::from_value::<RpcFilterType>(value).unwrap(), raw);
+
+		let base58 = serde_json::json!({ "memcmp": { "offset": 4, "bytes": "Ldp" } });
+		assert_eq!(
+			serde_json::from_value::<RpcFilterType>(base58).unwrap(),
+			RpcFilterType::Memcmp(Memcmp::new_base58_encoded(4, &[1, 2, 3]))
+		);
+	}
+
+	#[test]
+	fn test_bytes_match_encodings() {
+		let data = vec![0, 0, 0, 0, 1, 2, 3, 4];
+
+		assert!(Memcmp::new_base64(4, "AQID").bytes_match(&data));
+		assert!(Memcmp::new_raw_bytes(4, vec![1, 2, 3]).bytes_match(&data));
+		assert!(!Memcmp::new_raw_bytes(5, vec![1, 2, 3]).bytes_match(&data));
+		assert!(!Memcmp::new_base64(4, "not base64!").bytes_match(&data));
+		assert!(RpcFilterType::Memcmp(Memcmp::new_base64(4, "AQID")).allows(
+			&AccountSharedData::from(solana_sdk::account::Account {
+				data,
+				..Default::default()
+			})
+		));
+	}
+
+	#[test]
+	fn test_filter_builder() {
+		let owner = Pubkey::new_unique();
+		let mint = Pubkey::new_unique();
+		let filters = RpcFilterBuilder::new()
+			.data_size(165)
+			.token_account_owner(&owner)
+			.token_account_mint(&mint)
+			.token_account_state()
+			.build()
+			.unwrap();
+
+		assert_eq!(
+			filters,
+			vec![
+				RpcFilterType::DataSize(165),
+				RpcFilterType::Memcmp(Memcmp::new_base58_encoded(32, owner.as_ref())),
+				RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, mint.as_ref())),
+				RpcFilterType::TokenAccountState,
+			]
+		);
+
+		let mut data = vec![0; 165];
+		data[..32].copy_from_slice(mint.as_ref());
+		data[32..64].copy_from_slice(owner.as_ref());
+		assert!(filters[1..3].iter().all(|filter| {
+			filter.allows(&AccountSharedData::from(solana_sdk::account::Account {
+				data: data.clone(),
+				..Default::default()
+			}))
+		}));
+
+		assert_eq!(
+			RpcFilterBuilder::new()
+				.data_size(1)
+				.data_size(2)
+				.data_size(3)
+				.data_size(4)
+				.data_size(5)
+				.build(),
+			Err(RpcFilterError::TooManyFilters(5))
+		);
+		assert_eq!(
+			RpcFilterBuilder::new()
+				.memcmp_bytes(0, &[1; MAX_DATA_SIZE + 1])
+				.build(),
+			Err(RpcFilterError::DataTooLarge)
+		);
+	}
+
+	#[cfg(feature = "anchor")]
+	#[test]
+	fn test_filter_builder_anchor_discriminator() {
+		struct Vault;
+
+		impl anchor_lang::Discriminator for Vault {
+			const DISCRIMINATOR: &'static [u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+		}
+
+		assert_eq!(
+			RpcFilterBuilder::new()
+				.anchor_discriminator::<Vault>()
+				.build()
+				.unwrap(),
+			vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+				0,
+				&[1, 2, 3, 4, 5, 6, 7, 8]
+			))]
+		);
+	}
+
 	#[test]
 	fn test_verify_memcmp() {
 		let base58_bytes = "\
@@ -29,6 +29,7 @@ use crate::Context;
 use crate::impl_websocket_notification;
 use crate::solana_account_decoder::UiAccount;
 use crate::solana_account_decoder::parse_token::UiTokenAmount;
+use crate::solana_account_decoder_client_types::NumberOrString;
 use crate::solana_transaction_status::ConfirmedTransactionStatusWithSignature;
 use crate::solana_transaction_status::TransactionConfirmationStatus;
 use crate::solana_transaction_status::UiConfirmedBlock;
@@ -54,15 +55,36 @@ impl<T> OptionalContext<T> {
 	}
 }
 
+/// The context returned with the value of many rpc methods.
+#[serde_as]
 #[skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcResponseContext {
+	/// The slot at which the value was read.
+	#[serde_as(as = "NumberOrString")]
 	pub slot: Slot,
-	#[serde(skip_serializing_if = "Option::is_none")]
+	/// The version of the rpc api. This is only returned by newer nodes and is
+	/// `None` when it can't be parsed.
+	#[serde(
+		default,
+		skip_serializing_if = "Option::is_none",
+		deserialize_with = "deserialize_api_version"
+	)]
 	pub api_version: Option<RpcApiVersion>,
 }
 
+fn deserialize_api_version<'de, D>(
+	deserializer: D,
+) -> std::result::Result<Option<RpcApiVersion>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let version: Option<String> = Deserialize::deserialize(deserializer)?;
+
+	Ok(version.and_then(|version| version.parse().ok()))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RpcApiVersion(semver::Version);
 
@@ -95,9 +117,24 @@ impl<'de> Deserialize<'de> for RpcApiVersion {
 		D: Deserializer<'de>,
 	{
 		let s: String = Deserialize::deserialize(deserializer)?;
-		Ok(RpcApiVersion(
-			semver::Version::from_str(&s).map_err(serde::de::Error::custom)?,
-		))
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+impl FromStr for RpcApiVersion {
+	type Err = semver::Error;
+
+	/// Parse a version which may leave out the minor and patch numbers, like
+	/// `2.1`.
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		let s = s.trim().trim_start_matches('v');
+		let (core, rest) = s
+			.find(['-', '+'])
+			.map_or((s, ""), |index| s.split_at(index));
+		let missing = 2usize.saturating_sub(core.matches('.').count());
+		let padded = format!("{core}{}{rest}", ".0".repeat(missing));
+
+		semver::Version::from_str(&padded).map(Self)
 	}
 }
 
@@ -334,6 +371,14 @@ pub struct LogsNotificationResponse {
 
 impl_websocket_notification!(LogsNotificationResponse, "logs");
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SignatureNotificationResponse {
+	pub context: Context,
+	pub value: RpcSignatureResult,
+}
+
+impl_websocket_notification!(SignatureNotificationResponse, "signature");
+
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -547,6 +592,15 @@ pub struct RpcSimulateTransactionResult {
 	pub replacement_blockhash: Option<RpcBlockhash>,
 }
 
+impl RpcSimulateTransactionResult {
+	/// The return data of the simulation when it was set by `program_id`.
+	pub fn return_data_for(&self, program_id: &Pubkey) -> Option<&UiTransactionReturnData> {
+		self.return_data
+			.as_ref()
+			.filter(|return_data| return_data.program_id == *program_id)
+	}
+}
+
 #[serde_as]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
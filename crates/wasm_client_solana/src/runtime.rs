@@ -1,7 +1,20 @@
+//! Runtime primitives which work in the browser and natively.
+//!
+//! The timers are backed by `futures-timer`. Enable the `js` feature when
+//! targeting `wasm32-unknown-unknown` so they are driven by the browser.
+
 use std::fmt;
+use std::future::Future;
+use std::time::Duration;
 
+use futures::Stream;
+use futures::future::Either;
+use futures::future::select;
+use futures::stream;
+use futures_timer::Delay;
 use serde::Deserialize;
 use serde::Serialize;
+use typed_builder::TypedBuilder;
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
@@ -26,3 +39,195 @@ impl fmt::Display for RewardType {
 		)
 	}
 }
+
+/// Wait for the provided `duration`.
+pub async fn sleep(duration: Duration) {
+	Delay::new(duration).await;
+}
+
+/// A stream which yields every `period`, starting one `period` from now.
+pub fn interval(period: Duration) -> impl Stream<Item = ()> {
+	stream::unfold((), move |()| {
+		async move {
+			sleep(period).await;
+			Some(((), ()))
+		}
+	})
+}
+
+/// The error returned by [`timeout`] when the deadline is reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("deadline has elapsed")]
+pub struct Elapsed;
+
+/// Resolve with the output of `future` unless it takes longer than
+/// `duration`.
+///
+/// # Errors
+///
+/// Returns [`Elapsed`] when the `future` doesn't complete in time. The
+/// `future` is dropped.
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+	match select(Box::pin(future), Delay::new(duration)).await {
+		Either::Left((output, _)) => Ok(output),
+		Either::Right(((), _)) => Err(Elapsed),
+	}
+}
+
+/// How often and how quickly a failed operation is retried by
+/// [`retry_with_backoff`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TypedBuilder)]
+pub struct RetryPolicy {
+	/// The number of retries after the first attempt.
+	#[builder(default = 3)]
+	pub max_retries: usize,
+	/// The delay before the first retry.
+	#[builder(default = Duration::from_millis(500))]
+	pub initial_delay: Duration,
+	/// The upper limit of the delay between attempts.
+	#[builder(default = Duration::from_secs(10))]
+	pub max_delay: Duration,
+	/// The factor the delay grows by after every retry. Use `1` for a fixed
+	/// delay.
+	#[builder(default = 2)]
+	pub multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl RetryPolicy {
+	/// The delay before the provided `retry`, starting at `0` for the first
+	/// retry.
+	pub fn delay(&self, retry: usize) -> Duration {
+		u32::try_from(retry)
+			.ok()
+			.and_then(|retry| self.multiplier.checked_pow(retry))
+			.and_then(|factor| self.initial_delay.checked_mul(factor))
+			.map_or(self.max_delay, |delay| delay.min(self.max_delay))
+	}
+}
+
+/// Run `operation` until it succeeds, waiting longer between each attempt
+/// as described by the `policy`.
+///
+/// # Errors
+///
+/// Returns the last error once the retries are exhausted.
+pub async fn retry_with_backoff<T, E, F, Fut>(policy: RetryPolicy, operation: F) -> Result<T, E>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = Result<T, E>>,
+{
+	retry_with_backoff_if(policy, |_| true, operation).await
+}
+
+/// Like [`retry_with_backoff`] but only errors for which `should_retry`
+/// returns `true` are retried.
+///
+/// # Errors
+///
+/// Returns the first error which shouldn't be retried or the last error once
+/// the retries are exhausted.
+pub async fn retry_with_backoff_if<T, E, F, Fut>(
+	policy: RetryPolicy,
+	should_retry: impl Fn(&E) -> bool,
+	mut operation: F,
+) -> Result<T, E>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = Result<T, E>>,
+{
+	let mut retry = 0;
+
+	loop {
+		match operation().await {
+			Err(error) if retry < policy.max_retries && should_retry(&error) => {
+				sleep(policy.delay(retry)).await;
+				retry += 1;
+			}
+			result => return result,
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::cell::Cell;
+
+	use futures::StreamExt;
+	use futures::executor::block_on;
+
+	use super::*;
+
+	#[test]
+	fn retry_policy_delay() {
+		let policy = RetryPolicy::builder()
+			.initial_delay(Duration::from_millis(100))
+			.max_delay(Duration::from_millis(350))
+			.build();
+
+		assert_eq!(policy.delay(0), Duration::from_millis(100));
+		assert_eq!(policy.delay(1), Duration::from_millis(200));
+		assert_eq!(policy.delay(2), Duration::from_millis(350));
+		assert_eq!(policy.delay(usize::MAX), Duration::from_millis(350));
+	}
+
+	#[test]
+	fn retry_until_success() {
+		let policy = RetryPolicy::builder()
+			.initial_delay(Duration::from_millis(1))
+			.build();
+		let attempts = Cell::new(0);
+		let result = block_on(retry_with_backoff(policy, || {
+			attempts.set(attempts.get() + 1);
+			async {
+				if attempts.get() < 3 {
+					Err(attempts.get())
+				} else {
+					Ok("done")
+				}
+			}
+		}));
+
+		assert_eq!(result, Ok("done"));
+		assert_eq!(attempts.get(), 3);
+
+		attempts.set(0);
+		let result = block_on(retry_with_backoff_if(
+			policy,
+			|error: &u8| *error != 2,
+			|| {
+				attempts.set(attempts.get() + 1);
+				async { Err::<(), _>(attempts.get()) }
+			},
+		));
+
+		assert_eq!(result, Err(2));
+
+		attempts.set(0);
+		let result = block_on(retry_with_backoff(policy, || {
+			attempts.set(attempts.get() + 1);
+			async { Err::<(), _>(attempts.get()) }
+		}));
+
+		assert_eq!(result, Err(4));
+	}
+
+	#[test]
+	fn timeout_and_interval() {
+		let slow = timeout(Duration::from_millis(1), sleep(Duration::from_secs(10)));
+		assert_eq!(block_on(slow), Err(Elapsed));
+
+		let fast = timeout(Duration::from_secs(10), async { 42 });
+		assert_eq!(block_on(fast), Ok(42));
+
+		let ticks = interval(Duration::from_millis(1))
+			.take(3)
+			.collect::<Vec<_>>();
+		assert_eq!(block_on(ticks).len(), 3);
+	}
+}
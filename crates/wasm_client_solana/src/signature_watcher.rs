@@ -0,0 +1,439 @@
+use std::collections::VecDeque;
+
+use futures::StreamExt;
+use futures::future::Either;
+use futures::future::select;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::SolanaRpcClient;
+use crate::Subscription;
+use crate::rpc_config::RpcSignatureSubscribeConfig;
+use crate::rpc_config::SignatureSubscribeRequest;
+use crate::rpc_config::WatchSignatureConfig;
+use crate::rpc_response::ProcessedSignatureResult;
+use crate::rpc_response::RpcSignatureResult;
+use crate::rpc_response::SignatureNotificationResponse;
+use crate::runtime::sleep;
+use crate::runtime::timeout;
+use crate::solana_transaction_status::TransactionConfirmationStatus;
+
+const STATUSES: [TransactionConfirmationStatus; 3] = [
+	TransactionConfirmationStatus::Processed,
+	TransactionConfirmationStatus::Confirmed,
+	TransactionConfirmationStatus::Finalized,
+];
+
+/// The state behind [`SolanaRpcClient::watch_signature`].
+///
+/// Every step polls the status of the signature and then waits for the poll
+/// interval. While a websocket is available the wait ends early when the
+/// `signatureSubscribe` notification for the next status arrives. A socket
+/// which can't subscribe or which closes falls back to polling only.
+pub(crate) struct SignatureWatcher {
+	rpc: SolanaRpcClient,
+	signature: Signature,
+	config: WatchSignatureConfig,
+	reached: Option<TransactionConfirmationStatus>,
+	pending: VecDeque<TransactionConfirmationStatus>,
+	subscription: Option<(
+		TransactionConfirmationStatus,
+		Subscription<SignatureNotificationResponse>,
+	)>,
+	websocket: bool,
+	done: bool,
+}
+
+impl SignatureWatcher {
+	pub(crate) fn new(
+		rpc: SolanaRpcClient,
+		signature: Signature,
+		config: WatchSignatureConfig,
+	) -> Self {
+		Self {
+			rpc,
+			signature,
+			config,
+			reached: None,
+			pending: VecDeque::new(),
+			subscription: None,
+			websocket: true,
+			done: false,
+		}
+	}
+
+	/// The next status reached by the transaction. Returns `None` once the
+	/// transaction is finalized or after an error was returned.
+	pub(crate) async fn next(&mut self) -> Option<ClientResult<TransactionConfirmationStatus>> {
+		loop {
+			if let Some(status) = self.pending.pop_front() {
+				return Some(Ok(status));
+			}
+
+			if self.done || self.reached == Some(TransactionConfirmationStatus::Finalized) {
+				self.unsubscribe().await;
+				return None;
+			}
+
+			if let Err(error) = self.step().await {
+				self.done = true;
+				self.unsubscribe().await;
+				return Some(Err(error));
+			}
+		}
+	}
+
+	async fn step(&mut self) -> ClientResult<()> {
+		if self.poll().await? {
+			return Ok(());
+		}
+
+		self.subscribe().await;
+
+		let Some((status, subscription)) = &mut self.subscription else {
+			sleep(self.config.poll_interval).await;
+			return Ok(());
+		};
+
+		let notification = match select(
+			subscription.next(),
+			Box::pin(sleep(self.config.poll_interval)),
+		)
+		.await
+		{
+			Either::Left((Some(notification), _)) => notification,
+			Either::Left((None, _)) => {
+				// The socket closed, continue by polling.
+				self.subscription = None;
+				self.websocket = false;
+				return Ok(());
+			}
+			Either::Right(((), _)) => return Ok(()),
+		};
+		let status = status.clone();
+
+		// The node removes a signature subscription after its notification.
+		self.subscription = None;
+
+		match notification.params.result.value {
+			RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult {
+				err: Some(error),
+			}) => Err(ClientError::TransactionFailed(error)),
+			RpcSignatureResult::ProcessedSignature(_) => {
+				self.advance(status);
+				Ok(())
+			}
+			RpcSignatureResult::ReceivedSignature(_) => Ok(()),
+		}
+	}
+
+	/// Poll the status of the signature. Returns whether a new status was
+	/// reached.
+	async fn poll(&mut self) -> ClientResult<bool> {
+		let statuses = match self.rpc.get_signature_statuses(&[self.signature]).await {
+			Ok(statuses) => statuses,
+			Err(error) if error.is_retryable() => return Ok(false),
+			Err(error) => return Err(error),
+		};
+
+		if let Some(status) = statuses.into_iter().next().flatten() {
+			if let Some(error) = status.err {
+				return Err(ClientError::TransactionFailed(error));
+			}
+
+			return Ok(self.advance(status.confirmation_status()));
+		}
+
+		let Some(last_valid_block_height) = self
+			.config
+			.last_valid_block_height
+			.filter(|_| self.reached.is_none())
+		else {
+			return Ok(false);
+		};
+
+		if self.rpc.get_block_height().await? > last_valid_block_height {
+			return Err(ClientError::TransactionExpired {
+				last_valid_block_height,
+			});
+		}
+
+		Ok(false)
+	}
+
+	/// Queue every status up to `status` which wasn't reached yet. Returns
+	/// whether a new status was reached.
+	fn advance(&mut self, status: TransactionConfirmationStatus) -> bool {
+		if self.reached.as_ref() >= Some(&status) {
+			return false;
+		}
+
+		self.pending.extend(
+			STATUSES
+				.into_iter()
+				.filter(|next| self.reached.as_ref() < Some(next) && *next <= status),
+		);
+		self.reached = Some(status);
+
+		true
+	}
+
+	/// Subscribe to the next status when the websocket is available and the
+	/// current subscription is for a status which was already reached.
+	async fn subscribe(&mut self) {
+		if self
+			.subscription
+			.as_ref()
+			.is_some_and(|(status, _)| self.reached.as_ref() >= Some(status))
+		{
+			self.unsubscribe().await;
+		}
+
+		if !self.websocket || self.subscription.is_some() {
+			return;
+		}
+
+		let Some(status) = STATUSES
+			.into_iter()
+			.find(|status| self.reached.as_ref() < Some(status))
+		else {
+			return;
+		};
+		let commitment = match status {
+			TransactionConfirmationStatus::Processed => CommitmentConfig::processed(),
+			TransactionConfirmationStatus::Confirmed => CommitmentConfig::confirmed(),
+			TransactionConfirmationStatus::Finalized => CommitmentConfig::finalized(),
+		};
+		let request = SignatureSubscribeRequest::builder()
+			.signature(self.signature)
+			.config(
+				RpcSignatureSubscribeConfig::builder()
+					.commitment(commitment)
+					.build(),
+			)
+			.build();
+
+		// A socket which never connects shouldn't hold up polling.
+		match timeout(
+			self.config.poll_interval,
+			self.rpc.signature_subscribe(request),
+		)
+		.await
+		{
+			Ok(Ok(subscription)) => self.subscription = Some((status, subscription)),
+			_ => self.websocket = false,
+		}
+	}
+
+	async fn unsubscribe(&mut self) {
+		if let Some((_, subscription)) = self.subscription.take() {
+			let _ = timeout(self.config.poll_interval, subscription.unsubscribe()).await;
+		}
+	}
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+	use std::pin::Pin;
+	use std::task::Context;
+	use std::task::Poll;
+	use std::time::Duration;
+
+	use assert2::check;
+	use futures::Sink;
+	use futures::Stream;
+	use futures::channel::mpsc;
+	use futures::executor::block_on;
+	use serde_json::Value;
+	use serde_json::json;
+
+	use super::*;
+	use crate::ClientWebSocketError;
+	use crate::MockRpcProvider;
+	use crate::WebSocketProvider;
+
+	/// A pubsub transport which is driven by the test through channels.
+	struct ChannelTransport {
+		incoming: mpsc::UnboundedReceiver<Value>,
+		outgoing: mpsc::UnboundedSender<Value>,
+	}
+
+	impl Stream for ChannelTransport {
+		type Item = Result<Value, ClientWebSocketError>;
+
+		fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+			self.incoming.poll_next_unpin(cx).map(|value| value.map(Ok))
+		}
+	}
+
+	impl Sink<Value> for ChannelTransport {
+		type Error = ClientWebSocketError;
+
+		fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+			Poll::Ready(Ok(()))
+		}
+
+		fn start_send(self: Pin<&mut Self>, item: Value) -> Result<(), Self::Error> {
+			self.outgoing
+				.unbounded_send(item)
+				.map_err(|_| ClientWebSocketError::MessageSendError)
+		}
+
+		fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+			Poll::Ready(Ok(()))
+		}
+
+		fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+			Poll::Ready(Ok(()))
+		}
+	}
+
+	/// A client with the `provider` and a socket served through the returned
+	/// channels.
+	fn client(
+		provider: MockRpcProvider,
+	) -> (
+		SolanaRpcClient,
+		mpsc::UnboundedSender<Value>,
+		mpsc::UnboundedReceiver<Value>,
+	) {
+		let (server, incoming) = mpsc::unbounded();
+		let (outgoing, requests) = mpsc::unbounded();
+		let ws = WebSocketProvider::new_with_transport(
+			"ws://127.0.0.1:8900",
+			ChannelTransport { incoming, outgoing },
+		);
+		let rpc = SolanaRpcClient::new_with_providers(provider, ws, CommitmentConfig::confirmed());
+
+		(rpc, server, requests)
+	}
+
+	fn statuses(status: &Value) -> Value {
+		json!({ "context": { "slot": 10 }, "value": [status] })
+	}
+
+	fn status(confirmation_status: &str, err: &Value) -> Value {
+		json!({
+			"slot": 5,
+			"confirmations": null,
+			"err": err,
+			"status": { "Ok": null },
+			"confirmationStatus": confirmation_status,
+		})
+	}
+
+	fn watch(
+		rpc: &SolanaRpcClient,
+		config: WatchSignatureConfig,
+	) -> Vec<ClientResult<TransactionConfirmationStatus>> {
+		block_on(
+			rpc.watch_signature(&Signature::new_unique(), config)
+				.collect::<Vec<_>>(),
+		)
+	}
+
+	fn polling() -> WatchSignatureConfig {
+		WatchSignatureConfig::builder()
+			.poll_interval(Duration::from_millis(1))
+			.build()
+	}
+
+	#[test]
+	fn watch_signature_by_polling() {
+		let provider = MockRpcProvider::new()
+			.with_result("getSignatureStatuses", statuses(&Value::Null))
+			.with_result(
+				"getSignatureStatuses",
+				statuses(&status("processed", &Value::Null)),
+			)
+			.with_result(
+				"getSignatureStatuses",
+				statuses(&status("finalized", &Value::Null)),
+			);
+		// The socket is closed so the status is only polled.
+		let (rpc, ..) = client(provider.clone());
+		let updates = watch(&rpc, polling());
+
+		check!(updates.into_iter().collect::<Result<Vec<_>, _>>().ok() == Some(STATUSES.to_vec()));
+		check!(provider.requests().len() == 3);
+	}
+
+	#[test]
+	fn watch_signature_errors() {
+		let error = json!({ "InstructionError": [0, { "Custom": 1 }] });
+		let provider = MockRpcProvider::new().with_result(
+			"getSignatureStatuses",
+			statuses(&status("confirmed", &error)),
+		);
+		let (rpc, ..) = client(provider);
+		let updates = watch(&rpc, polling());
+
+		check!(updates.len() == 1);
+		check!(matches!(updates[0], Err(ClientError::TransactionFailed(_))));
+
+		let provider = MockRpcProvider::new()
+			.with_result("getSignatureStatuses", statuses(&Value::Null))
+			.with_result("getBlockHeight", 151);
+		let (rpc, ..) = client(provider);
+		let config = WatchSignatureConfig::builder()
+			.last_valid_block_height(150)
+			.poll_interval(Duration::from_millis(1))
+			.build();
+		let updates = watch(&rpc, config);
+
+		check!(updates.len() == 1);
+		check!(matches!(
+			updates[0],
+			Err(ClientError::TransactionExpired {
+				last_valid_block_height: 150
+			})
+		));
+	}
+
+	#[test]
+	fn watch_signature_from_notifications() {
+		let provider =
+			MockRpcProvider::new().with_result("getSignatureStatuses", statuses(&Value::Null));
+		let (rpc, server, mut requests) = client(provider);
+		let config = WatchSignatureConfig::builder()
+			.poll_interval(Duration::from_secs(5))
+			.build();
+		let serve = async {
+			let mut commitments = vec![];
+
+			for subscription in 1..=3 {
+				let Some(request) = requests.next().await else {
+					break;
+				};
+
+				commitments.push(request["params"][1]["commitment"].clone());
+				server
+					.unbounded_send(
+						json!({ "jsonrpc": "2.0", "id": request["id"], "result": subscription }),
+					)
+					.ok();
+				server
+					.unbounded_send(json!({
+						"jsonrpc": "2.0",
+						"method": "signatureNotification",
+						"params": {
+							"result": { "context": { "slot": 5 }, "value": { "err": null } },
+							"subscription": subscription,
+						},
+					}))
+					.ok();
+			}
+
+			commitments
+		};
+		let watch = rpc
+			.watch_signature(&Signature::new_unique(), config)
+			.collect::<Vec<_>>();
+		let (commitments, updates) = block_on(futures::future::join(serve, watch));
+
+		check!(commitments == vec![json!("processed"), json!("confirmed"), json!("finalized")]);
+		check!(updates.into_iter().collect::<Result<Vec<_>, _>>().ok() == Some(STATUSES.to_vec()));
+	}
+}
@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::signer::SignerError;
+pub use solana_sdk::signer::presigner::Presigner;
+use wallet_standard::SolanaSignatureOutput;
+use wallet_standard::WalletSolanaPubkey;
+use wallet_standard::WalletSolanaSignMessage;
+
+/// A signer which produces signatures asynchronously, like a browser wallet or
+/// a remote signing service.
+///
+/// Every [`Signer`] is also an `AsyncSigner`, including a [`Presigner`] which
+/// holds a signature created elsewhere. This makes it possible to sign a
+/// transaction with a mix of local, presigned and remote signers in one step
+/// with [`VersionedTransactionExtension::try_sign_with_async_signers`].
+///
+/// The methods are named so they don't clash with [`Signer`] for types which
+/// implement both traits.
+///
+/// The returned futures aren't required to be `Send` since wallets in the
+/// browser are bound to the main thread.
+///
+/// [`VersionedTransactionExtension::try_sign_with_async_signers`]: crate::VersionedTransactionExtension::try_sign_with_async_signers
+#[async_trait(?Send)]
+pub trait AsyncSigner {
+	/// The public key of the signer.
+	fn signer_pubkey(&self) -> Pubkey;
+	/// Sign the serialized `message`.
+	async fn try_sign_message_async(&self, message: &[u8]) -> Result<Signature, SignerError>;
+}
+
+#[async_trait(?Send)]
+impl<T: Signer + ?Sized> AsyncSigner for T {
+	fn signer_pubkey(&self) -> Pubkey {
+		self.pubkey()
+	}
+
+	async fn try_sign_message_async(&self, message: &[u8]) -> Result<Signature, SignerError> {
+		self.try_sign_message(message)
+	}
+}
+
+/// Use a connected wallet as an [`AsyncSigner`].
+///
+/// Wallet errors are surfaced as [`SignerError::Custom`].
+#[derive(Clone, Copy, Debug)]
+pub struct WalletSigner<'a, W>(pub &'a W);
+
+#[async_trait(?Send)]
+impl<W: WalletSolanaSignMessage + WalletSolanaPubkey> AsyncSigner for WalletSigner<'_, W> {
+	fn signer_pubkey(&self) -> Pubkey {
+		self.0.solana_pubkey()
+	}
+
+	async fn try_sign_message_async(&self, message: &[u8]) -> Result<Signature, SignerError> {
+		let output = self
+			.0
+			.sign_message_async(message.to_vec())
+			.await
+			.map_err(|error| SignerError::Custom(error.to_string()))?;
+
+		output
+			.try_signature()
+			.map_err(|error| SignerError::Custom(error.to_string()))
+	}
+}
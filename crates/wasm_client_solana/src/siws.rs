@@ -0,0 +1,501 @@
+//! Sign-In With Solana message construction and verification.
+//!
+//! A [`SiwsMessage`] serializes to the `signInWithSolana` message text which
+//! wallets sign, and [`verify`] checks a signed message on the server. Use
+//! [`SiwsMessage::to_sign_in_input`] to request the signature from a wallet
+//! with the same fields.
+
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use chrono::TimeDelta;
+use chrono::Utc;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use typed_builder::TypedBuilder;
+use wallet_standard::SolanaSignInInput;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+const URI: &str = "URI: ";
+const VERSION: &str = "Version: ";
+const CHAIN_ID: &str = "Chain ID: ";
+const NONCE: &str = "Nonce: ";
+const ISSUED_AT: &str = "Issued At: ";
+const EXPIRATION_TIME: &str = "Expiration Time: ";
+const NOT_BEFORE: &str = "Not Before: ";
+const REQUEST_ID: &str = "Request ID: ";
+const RESOURCES: &str = "Resources:";
+const RESOURCE_PREFIX: &str = "- ";
+const FIELD_PREFIXES: [&str; 9] = [
+	URI,
+	VERSION,
+	CHAIN_ID,
+	NONCE,
+	ISSUED_AT,
+	EXPIRATION_TIME,
+	NOT_BEFORE,
+	REQUEST_ID,
+	RESOURCES,
+];
+
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum SiwsError {
+	#[error("the message is not valid utf-8")]
+	InvalidUtf8,
+	#[error("invalid sign in message: {0}")]
+	InvalidMessage(String),
+	#[error("the signature is not valid for the message")]
+	InvalidSignature,
+	#[error("the message address ({address}) does not match the signer ({pubkey})")]
+	AddressMismatch { address: Pubkey, pubkey: Pubkey },
+	#[error("the message domain does not match the expected domain")]
+	DomainMismatch,
+	#[error("the message nonce does not match the expected nonce")]
+	NonceMismatch,
+	#[error("the message expired at {0}")]
+	Expired(DateTime<Utc>),
+	#[error("the message is not valid before {0}")]
+	NotYetValid(DateTime<Utc>),
+}
+
+/// The fields of a Sign-In With Solana message.
+///
+/// The [`Display`] implementation produces the canonical message text and
+/// [`FromStr`] parses it back. Timestamps are written with millisecond
+/// precision in UTC, which matches `Date.toISOString()` in the browser.
+#[derive(Clone, Debug, PartialEq, Eq, TypedBuilder)]
+#[builder(field_defaults(default, setter(into, strip_option)))]
+pub struct SiwsMessage {
+	/// The domain requesting the sign in.
+	#[builder(!default, setter(!strip_option))]
+	pub domain: String,
+	/// The account signing in.
+	#[builder(!default, setter(!strip_option))]
+	pub address: Pubkey,
+	/// A human readable statement shown to the user. It must not contain a
+	/// newline.
+	pub statement: Option<String>,
+	pub uri: Option<String>,
+	pub version: Option<String>,
+	/// The cluster, for example `mainnet`, `devnet` or `localnet`.
+	pub chain_id: Option<String>,
+	/// A random value used to prevent replay attacks.
+	pub nonce: Option<String>,
+	pub issued_at: Option<DateTime<Utc>>,
+	pub expiration_time: Option<DateTime<Utc>>,
+	pub not_before: Option<DateTime<Utc>>,
+	pub request_id: Option<String>,
+	#[builder(setter(!strip_option))]
+	pub resources: Vec<String>,
+}
+
+impl SiwsMessage {
+	/// The input for [`wallet_standard::WalletSolanaSignIn::sign_in`] with the
+	/// same fields as this message. The wallet builds the identical message
+	/// text from it.
+	pub fn to_sign_in_input(&self) -> SolanaSignInInput {
+		SolanaSignInInput {
+			domain: Some(self.domain.clone()),
+			address: Some(self.address.to_string()),
+			statement: self.statement.clone(),
+			uri: self.uri.clone(),
+			version: self.version.clone(),
+			chain_id: self.chain_id.clone(),
+			nonce: self.nonce.clone(),
+			issued_at: self.issued_at.map(format_time),
+			expiration_time: self.expiration_time.map(format_time),
+			not_before: self.not_before.map(format_time),
+			request_id: self.request_id.clone(),
+			resources: (!self.resources.is_empty()).then(|| self.resources.clone()),
+		}
+	}
+
+	/// Check that the message is valid at `now`, allowing for `clock_skew`
+	/// between the wallet and this machine.
+	///
+	/// # Errors
+	///
+	/// Returns [`SiwsError::Expired`] after the expiration time and
+	/// [`SiwsError::NotYetValid`] before the issued at or not before times.
+	pub fn check_time(&self, now: DateTime<Utc>, clock_skew: Duration) -> Result<(), SiwsError> {
+		let clock_skew = TimeDelta::from_std(clock_skew).unwrap_or(TimeDelta::MAX);
+
+		let expired = self.expiration_time.filter(|expiration_time| {
+			now >= expiration_time
+				.checked_add_signed(clock_skew)
+				.unwrap_or(*expiration_time)
+		});
+
+		if let Some(expiration_time) = expired {
+			return Err(SiwsError::Expired(expiration_time));
+		}
+
+		for time in [self.issued_at, self.not_before].into_iter().flatten() {
+			if now < time.checked_sub_signed(clock_skew).unwrap_or(time) {
+				return Err(SiwsError::NotYetValid(time));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl Display for SiwsMessage {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}{HEADER_SUFFIX}\n{}", self.domain, self.address)?;
+
+		if let Some(ref statement) = self.statement {
+			write!(f, "\n\n{statement}")?;
+		}
+
+		let mut fields = vec![];
+		let mut push_field = |prefix: &str, value: Option<String>| {
+			if let Some(value) = value {
+				fields.push(format!("{prefix}{value}"));
+			}
+		};
+
+		push_field(URI, self.uri.clone());
+		push_field(VERSION, self.version.clone());
+		push_field(CHAIN_ID, self.chain_id.clone());
+		push_field(NONCE, self.nonce.clone());
+		push_field(ISSUED_AT, self.issued_at.map(format_time));
+		push_field(EXPIRATION_TIME, self.expiration_time.map(format_time));
+		push_field(NOT_BEFORE, self.not_before.map(format_time));
+		push_field(REQUEST_ID, self.request_id.clone());
+
+		if !self.resources.is_empty() {
+			fields.push(RESOURCES.to_string());
+			fields.extend(
+				self.resources
+					.iter()
+					.map(|resource| format!("{RESOURCE_PREFIX}{resource}")),
+			);
+		}
+
+		if !fields.is_empty() {
+			write!(f, "\n\n{}", fields.join("\n"))?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromStr for SiwsMessage {
+	type Err = SiwsError;
+
+	fn from_str(message: &str) -> Result<Self, Self::Err> {
+		let mut lines = message.split('\n').peekable();
+		let domain = lines
+			.next()
+			.and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+			.filter(|domain| !domain.is_empty())
+			.ok_or_else(|| invalid("missing domain"))?;
+		let address = lines
+			.next()
+			.ok_or_else(|| invalid("missing address"))?
+			.parse::<Pubkey>()
+			.map_err(|_| invalid("invalid address"))?;
+		let mut siws = Self::builder()
+			.domain(domain.to_string())
+			.address(address)
+			.build();
+
+		if lines.peek().is_some() {
+			expect_blank_line(&mut lines)?;
+
+			if let Some(line) =
+				lines.next_if(|line| !FIELD_PREFIXES.iter().any(|prefix| line.starts_with(prefix)))
+			{
+				siws.statement = Some(line.to_string());
+
+				if lines.peek().is_some() {
+					expect_blank_line(&mut lines)?;
+				}
+			}
+		}
+
+		let mut field = |prefix: &str| {
+			lines
+				.next_if(|line| line.starts_with(prefix))
+				.map(|line| line[prefix.len()..].to_string())
+		};
+
+		siws.uri = field(URI);
+		siws.version = field(VERSION);
+		siws.chain_id = field(CHAIN_ID);
+		siws.nonce = field(NONCE);
+		siws.issued_at = field(ISSUED_AT).as_deref().map(parse_time).transpose()?;
+		siws.expiration_time = field(EXPIRATION_TIME)
+			.as_deref()
+			.map(parse_time)
+			.transpose()?;
+		siws.not_before = field(NOT_BEFORE).as_deref().map(parse_time).transpose()?;
+		siws.request_id = field(REQUEST_ID);
+
+		if lines.next_if_eq(&RESOURCES).is_some() {
+			while let Some(line) = lines.next_if(|line| line.starts_with(RESOURCE_PREFIX)) {
+				siws.resources
+					.push(line[RESOURCE_PREFIX.len()..].to_string());
+			}
+		}
+
+		if let Some(line) = lines.next() {
+			return Err(invalid(format!("unexpected line: {line}")));
+		}
+
+		Ok(siws)
+	}
+}
+
+/// Options for [`verify`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, TypedBuilder)]
+#[builder(field_defaults(default, setter(into, strip_option)))]
+pub struct SiwsVerifyConfig {
+	/// The domain which the message must be for.
+	pub domain: Option<String>,
+	/// The nonce which was issued for this sign in.
+	pub nonce: Option<String>,
+	/// The time to check the message against. Defaults to the current time.
+	pub now: Option<DateTime<Utc>>,
+	/// The tolerated difference between the clock of the wallet and this
+	/// machine.
+	#[builder(setter(!strip_option))]
+	pub clock_skew: Duration,
+}
+
+/// Verify a signed Sign-In With Solana message.
+///
+/// The signature is checked against the raw `message` bytes so the message
+/// doesn't need to have been created by [`SiwsMessage`]. The address of the
+/// message must match `pubkey` and the message must be valid at the
+/// configured time. The parsed message is returned on success.
+///
+/// # Errors
+///
+/// Returns an error when the message can't be parsed, the signature is
+/// invalid or any of the checks fail.
+pub fn verify(
+	message: &[u8],
+	signature: &Signature,
+	pubkey: &Pubkey,
+	config: SiwsVerifyConfig,
+) -> Result<SiwsMessage, SiwsError> {
+	let SiwsVerifyConfig {
+		domain,
+		nonce,
+		now,
+		clock_skew,
+	} = config;
+	let text = std::str::from_utf8(message).map_err(|_| SiwsError::InvalidUtf8)?;
+	let siws = text.parse::<SiwsMessage>()?;
+
+	if siws.address != *pubkey {
+		return Err(SiwsError::AddressMismatch {
+			address: siws.address,
+			pubkey: *pubkey,
+		});
+	}
+
+	if !signature.verify(pubkey.as_ref(), message) {
+		return Err(SiwsError::InvalidSignature);
+	}
+
+	if domain.is_some_and(|domain| domain != siws.domain) {
+		return Err(SiwsError::DomainMismatch);
+	}
+
+	if nonce.is_some() && nonce != siws.nonce {
+		return Err(SiwsError::NonceMismatch);
+	}
+
+	siws.check_time(now.unwrap_or_else(Utc::now), clock_skew)?;
+
+	Ok(siws)
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+	time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>, SiwsError> {
+	DateTime::parse_from_rfc3339(value)
+		.map(|time| time.with_timezone(&Utc))
+		.map_err(|_| invalid(format!("invalid timestamp: {value}")))
+}
+
+fn expect_blank_line<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Result<(), SiwsError> {
+	match lines.next() {
+		Some("") => Ok(()),
+		_ => Err(invalid("expected an empty line")),
+	}
+}
+
+fn invalid(message: impl Into<String>) -> SiwsError {
+	SiwsError::InvalidMessage(message.into())
+}
+
+#[cfg(test)]
+mod test {
+	use solana_sdk::signature::Keypair;
+	use solana_sdk::signer::Signer;
+	use wallet_standard::create_sign_in_message_text;
+
+	use super::*;
+
+	fn time(value: &str) -> DateTime<Utc> {
+		DateTime::parse_from_rfc3339(value).unwrap().into()
+	}
+
+	fn message(address: Pubkey) -> SiwsMessage {
+		SiwsMessage::builder()
+			.domain("example.com")
+			.address(address)
+			.statement("Sign in to example.com")
+			.uri("https://example.com/login")
+			.version("1")
+			.chain_id("mainnet")
+			.nonce("32891756")
+			.issued_at(time("2024-01-01T00:00:00Z"))
+			.expiration_time(time("2024-01-01T00:10:00Z"))
+			.resources(vec![
+				"ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/".into(),
+				"https://example.com/my-web2-claim.json".into(),
+			])
+			.build()
+	}
+
+	#[test]
+	fn serialize_and_parse_message() {
+		let address = Pubkey::new_unique();
+		let siws = message(address);
+		let text = siws.to_string();
+
+		assert_eq!(
+			text,
+			format!(
+				"example.com wants you to sign in with your Solana account:\n{address}\n\nSign \
+				 in to example.com\n\nURI: https://example.com/login\nVersion: 1\nChain ID: \
+				 mainnet\nNonce: 32891756\nIssued At: 2024-01-01T00:00:00.000Z\nExpiration Time: \
+				 2024-01-01T00:10:00.000Z\nResources:\n- \
+				 ipfs://bafybeiemxf5abjwjbikoz4mc3a3dla6ual3jsgpdr4cjr3oz3evfyavhwq/\n- \
+				 https://example.com/my-web2-claim.json"
+			)
+		);
+		assert_eq!(
+			create_sign_in_message_text(&siws.to_sign_in_input()).unwrap(),
+			text
+		);
+		assert_eq!(text.parse::<SiwsMessage>().unwrap(), siws);
+
+		let minimal = SiwsMessage::builder()
+			.domain("example.com")
+			.address(address)
+			.build();
+		assert_eq!(
+			minimal.to_string(),
+			format!("example.com wants you to sign in with your Solana account:\n{address}")
+		);
+		assert_eq!(minimal.to_string().parse::<SiwsMessage>().unwrap(), minimal);
+
+		let without_statement = SiwsMessage::builder()
+			.domain("example.com")
+			.address(address)
+			.nonce("1")
+			.build();
+		assert_eq!(
+			without_statement
+				.to_string()
+				.parse::<SiwsMessage>()
+				.unwrap(),
+			without_statement
+		);
+		assert!(matches!(
+			format!("{minimal}\nextra").parse::<SiwsMessage>(),
+			Err(SiwsError::InvalidMessage(_))
+		));
+	}
+
+	#[test]
+	fn verify_signed_message() {
+		let keypair = Keypair::new();
+		let siws = message(keypair.pubkey());
+		let text = siws.to_string();
+		let signature = keypair.sign_message(text.as_bytes());
+		let config = SiwsVerifyConfig::builder()
+			.domain("example.com")
+			.nonce("32891756")
+			.now(time("2024-01-01T00:05:00Z"))
+			.build();
+
+		assert_eq!(
+			verify(
+				text.as_bytes(),
+				&signature,
+				&keypair.pubkey(),
+				config.clone()
+			),
+			Ok(siws)
+		);
+		assert_eq!(
+			verify(
+				text.as_bytes(),
+				&Signature::default(),
+				&keypair.pubkey(),
+				config.clone()
+			),
+			Err(SiwsError::InvalidSignature)
+		);
+		assert!(matches!(
+			verify(
+				text.as_bytes(),
+				&signature,
+				&Pubkey::new_unique(),
+				config.clone()
+			),
+			Err(SiwsError::AddressMismatch { .. })
+		));
+
+		let other_nonce = SiwsVerifyConfig {
+			nonce: Some("1".into()),
+			..config.clone()
+		};
+		assert_eq!(
+			verify(text.as_bytes(), &signature, &keypair.pubkey(), other_nonce),
+			Err(SiwsError::NonceMismatch)
+		);
+
+		let expired = SiwsVerifyConfig {
+			now: Some(time("2024-01-01T00:10:30Z")),
+			..config.clone()
+		};
+		assert_eq!(
+			verify(
+				text.as_bytes(),
+				&signature,
+				&keypair.pubkey(),
+				expired.clone()
+			),
+			Err(SiwsError::Expired(time("2024-01-01T00:10:00Z")))
+		);
+
+		let with_skew = SiwsVerifyConfig {
+			clock_skew: Duration::from_secs(60),
+			..expired
+		};
+		assert!(verify(text.as_bytes(), &signature, &keypair.pubkey(), with_skew).is_ok());
+
+		let early = SiwsVerifyConfig {
+			now: Some(time("2023-12-31T23:59:00Z")),
+			..config
+		};
+		assert_eq!(
+			verify(text.as_bytes(), &signature, &keypair.pubkey(), early),
+			Err(SiwsError::NotYetValid(time("2024-01-01T00:00:00Z")))
+		);
+	}
+}
@@ -1,36 +1,81 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
 use std::time::Duration;
 
-use futures_timer::Delay;
+#[cfg(feature = "anchor")]
+use anchor_lang::AnchorDeserialize;
+#[cfg(feature = "anchor")]
+use anchor_lang::Discriminator;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use futures::future::AbortHandle;
+use futures::stream;
+use futures::stream::BoxStream;
 use serde::de::DeserializeOwned;
 use solana_sdk::account::Account;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
 use solana_sdk::clock::Epoch;
 use solana_sdk::clock::Slot;
 use solana_sdk::clock::UnixTimestamp;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
 use solana_sdk::epoch_info::EpochInfo;
 use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
 use solana_sdk::message::Message;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::message::v0;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::signer::SignerError;
+use solana_sdk::signers::Signers;
+use solana_sdk::transaction::TransactionError;
 use solana_sdk::transaction::VersionedTransaction;
-
+use solana_system_interface::instruction::advance_nonce_account;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Mint;
+
+use crate::BlockhashCache;
+use crate::BlockhashCacheConfig;
+use crate::COMPUTE_UNIT_MAX_LIMIT;
 use crate::ClientError;
 use crate::ClientResponse;
 use crate::ClientResult;
+use crate::Cluster;
+use crate::DEFAULT_MAX_CONCURRENT_REQUESTS;
 use crate::HttpProvider;
+use crate::MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS;
+use crate::MAX_MULTIPLE_ACCOUNTS;
 use crate::MAX_RETRIES;
+use crate::MAX_SEND_RETRIES;
+use crate::MAX_SIGNATURES_FOR_ADDRESS_LIMIT;
 use crate::RpcError;
 use crate::RpcProvider;
 use crate::SLEEP_MS;
+use crate::SimulationError;
 use crate::Subscription;
+use crate::TaskSet;
+use crate::VersionedMessageExtension;
+use crate::VersionedTransactionExtension;
 use crate::WebSocketProvider;
+use crate::derive_associated_token_address;
 use crate::methods::*;
+use crate::nonce_utils;
+use crate::nonce_utils::NonceError;
+use crate::priority_fees::fee_percentile;
 use crate::rpc_config::BlockSubscribeRequest;
+use crate::rpc_config::ComputeUnitPrice;
 use crate::rpc_config::GetConfirmedSignaturesForAddress2Config;
 use crate::rpc_config::LogsSubscribeRequest;
+use crate::rpc_config::PrepareTransactionConfig;
 use crate::rpc_config::ProgramSubscribeRequest;
 use crate::rpc_config::RpcAccountInfoConfig;
 use crate::rpc_config::RpcBlockConfig;
@@ -48,9 +93,14 @@ use crate::rpc_config::RpcSimulateTransactionConfig;
 use crate::rpc_config::RpcSupplyConfig;
 use crate::rpc_config::RpcTokenAccountsFilter;
 use crate::rpc_config::RpcTransactionConfig;
+use crate::rpc_config::SendOptions;
+use crate::rpc_config::SignatureSubscribeRequest;
+use crate::rpc_config::TransferConfig;
+use crate::rpc_config::WatchSignatureConfig;
 use crate::rpc_filter::TokenAccountsFilter;
 use crate::rpc_response::BlockNotificationResponse;
 use crate::rpc_response::LogsNotificationResponse;
+use crate::rpc_response::Response;
 use crate::rpc_response::RpcAccountBalance;
 use crate::rpc_response::RpcBlockProduction;
 use crate::rpc_response::RpcConfirmedTransactionStatusWithSignature;
@@ -60,9 +110,16 @@ use crate::rpc_response::RpcInflationReward;
 use crate::rpc_response::RpcLeaderSchedule;
 use crate::rpc_response::RpcPerfSample;
 use crate::rpc_response::RpcPrioritizationFee;
+use crate::rpc_response::RpcResponseContext;
 use crate::rpc_response::RpcSupply;
 use crate::rpc_response::RpcVersionInfo;
 use crate::rpc_response::RpcVoteAccountStatus;
+use crate::rpc_response::SignatureNotificationResponse;
+use crate::runtime::RetryPolicy;
+use crate::runtime::retry_with_backoff_if;
+use crate::runtime::sleep;
+use crate::signature_watcher::SignatureWatcher;
+use crate::solana_account_decoder::UiAccount;
 use crate::solana_account_decoder::UiAccountData;
 use crate::solana_account_decoder::UiAccountEncoding;
 use crate::solana_account_decoder::parse_address_lookup_table::LookupTableAccountType;
@@ -71,10 +128,13 @@ use crate::solana_account_decoder::parse_token::TokenAccountType;
 use crate::solana_account_decoder::parse_token::UiTokenAccount;
 use crate::solana_account_decoder::parse_token::UiTokenAmount;
 use crate::solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use crate::solana_transaction_status::EncodedTransactionWithStatusMeta;
 use crate::solana_transaction_status::TransactionConfirmationStatus;
 use crate::solana_transaction_status::TransactionStatus;
 use crate::solana_transaction_status::UiConfirmedBlock;
 use crate::solana_transaction_status::UiTransactionEncoding;
+use crate::stream_json_array;
+use crate::utils::try_join_limited;
 
 /// A client of a remote Solana node.
 ///
@@ -133,6 +193,47 @@ pub struct SolanaRpcClient {
 	#[debug(skip)]
 	provider: Arc<dyn RpcProvider + Send + Sync + 'static>,
 	ws: WebSocketProvider,
+	/// The decimals and token program of the mints used by
+	/// [`SolanaRpcClient::transfer_token`].
+	#[debug(skip)]
+	mints: Arc<Mutex<HashMap<Pubkey, MintInfo>>>,
+	/// The number of requests run at once when a method is split into
+	/// multiple requests.
+	max_concurrent_requests: usize,
+	/// The minimum slot sent with every request which accepts a
+	/// `minContextSlot`.
+	min_context_slot: Option<Slot>,
+	/// The blockhash cache used by the send and prepare helpers when enabled
+	/// with [`SolanaRpcClient::with_blockhash_cache`].
+	blockhash_cache: Option<BlockhashCache>,
+	/// The background tasks of the client, which are aborted once the client
+	/// and all of its clones are dropped.
+	#[debug(skip)]
+	tasks: Arc<Mutex<TaskSet>>,
+}
+
+/// The result of [`SolanaRpcClient::get_or_create_associated_token_account`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AssociatedTokenAccount {
+	/// The address of the associated token account.
+	pub address: Pubkey,
+	/// Whether the account was created by the call.
+	pub created: bool,
+}
+
+/// A transaction returned by [`SolanaRpcClient::get_transaction_versioned`].
+#[derive(Debug, PartialEq)]
+pub struct VersionedConfirmedTransaction {
+	/// The transaction and its status metadata as returned by the rpc.
+	pub encoded: EncodedConfirmedTransactionWithStatusMeta,
+	/// The decoded transaction.
+	pub transaction: VersionedTransaction,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct MintInfo {
+	decimals: u8,
+	token_program: Pubkey,
 }
 
 impl<S: Into<String>> From<S> for SolanaRpcClient {
@@ -162,6 +263,11 @@ impl SolanaRpcClient {
 			provider: Arc::new(HttpProvider::new(endpoint)),
 			commitment_config: CommitmentConfig::confirmed(),
 			ws: WebSocketProvider::new(endpoint),
+			mints: Arc::default(),
+			max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+			min_context_slot: None,
+			blockhash_cache: None,
+			tasks: Arc::default(),
 		}
 	}
 
@@ -181,6 +287,11 @@ impl SolanaRpcClient {
 			provider: Arc::new(HttpProvider::new(endpoint)),
 			commitment_config,
 			ws: WebSocketProvider::new(endpoint),
+			mints: Arc::default(),
+			max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+			min_context_slot: None,
+			blockhash_cache: None,
+			tasks: Arc::default(),
 		}
 	}
 
@@ -193,19 +304,67 @@ impl SolanaRpcClient {
 			provider: Arc::new(HttpProvider::new(http_endpoint)),
 			commitment_config,
 			ws: WebSocketProvider::new(ws_endpoint),
+			mints: Arc::default(),
+			max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+			min_context_slot: None,
+			blockhash_cache: None,
+			tasks: Arc::default(),
 		}
 	}
 
-	/// Create a new rpc from a custom provider.
-	pub fn new_with_provider(
-		provider: Arc<dyn RpcProvider + Send + Sync + 'static>,
+	/// Create a `SolanaRpcClient` for a [`Cluster`] with the default
+	/// [commitment level][cl] of [`Confirmed`](CommitmentLevel::Confirmed).
+	///
+	/// [cl]: https://solana.com/docs/rpc#configuring-state-commitment
+	pub fn new_for_cluster(cluster: &Cluster) -> Self {
+		Self::new_with_ws_and_commitment(
+			cluster.http_url(),
+			&cluster.ws_url(),
+			CommitmentConfig::confirmed(),
+		)
+	}
+
+	/// Create a new rpc from a custom [`RpcProvider`].
+	///
+	/// This is useful in environments where the default [`HttpProvider`] isn't
+	/// available, and for testing with the
+	/// [`MockRpcProvider`](crate::MockRpcProvider). The websocket url is
+	/// derived from the [`RpcProvider::url`].
+	pub fn new_with_provider(provider: impl RpcProvider + Send + Sync + 'static) -> Self {
+		Self::new_with_provider_and_commitment(provider, CommitmentConfig::confirmed())
+	}
+
+	/// Create a new rpc from a custom [`RpcProvider`] with the specified
+	/// [commitment level][cl].
+	///
+	/// [cl]: https://solana.com/docs/rpc#configuring-state-commitment
+	pub fn new_with_provider_and_commitment(
+		provider: impl RpcProvider + Send + Sync + 'static,
+		commitment_config: CommitmentConfig,
+	) -> Self {
+		let ws = WebSocketProvider::new(provider.url());
+
+		Self::new_with_providers(provider, ws, commitment_config)
+	}
+
+	/// Create a new rpc from a custom [`RpcProvider`] and a
+	/// [`WebSocketProvider`]. Use
+	/// [`WebSocketProvider::new_with_transport`] to plug in a custom
+	/// [`PubsubTransport`](crate::PubsubTransport).
+	pub fn new_with_providers(
+		provider: impl RpcProvider + Send + Sync + 'static,
+		ws: WebSocketProvider,
 		commitment_config: CommitmentConfig,
 	) -> Self {
-		let endpoint = provider.url();
 		Self {
-			provider,
+			provider: Arc::new(provider),
 			commitment_config,
-			ws: WebSocketProvider::new(endpoint),
+			ws,
+			mints: Arc::default(),
+			max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+			min_context_slot: None,
+			blockhash_cache: None,
+			tasks: Arc::default(),
 		}
 	}
 
@@ -214,6 +373,142 @@ impl SolanaRpcClient {
 		self.provider.url()
 	}
 
+	/// Get the websocket URL.
+	pub fn ws_url(&self) -> &str {
+		self.ws.url()
+	}
+
+	/// Set the number of requests run at once by methods which split their
+	/// input into multiple requests, like
+	/// [`SolanaRpcClient::get_multiple_accounts`]. Defaults to
+	/// [`DEFAULT_MAX_CONCURRENT_REQUESTS`].
+	#[must_use]
+	pub fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+		self.max_concurrent_requests = max_concurrent_requests;
+		self
+	}
+
+	pub fn max_concurrent_requests(&self) -> usize {
+		self.max_concurrent_requests
+	}
+
+	/// Create a client which sends `min_context_slot` with every request that
+	/// accepts it, so the node rejects reads from a slot before it.
+	///
+	/// Use the slot returned with a write or a previous read to make sure a
+	/// sequence of reads is at least as fresh. A larger `minContextSlot` passed
+	/// in a config takes precedence.
+	#[must_use]
+	pub fn with_min_context_slot(&self, min_context_slot: Slot) -> Self {
+		Self {
+			min_context_slot: Some(min_context_slot),
+			..self.clone()
+		}
+	}
+
+	pub fn min_context_slot(&self) -> Option<Slot> {
+		self.min_context_slot
+	}
+
+	/// Create a client which caches the latest blockhash for its commitment
+	/// level. The send and prepare helpers reuse the cached blockhash instead
+	/// of fetching one for every transaction.
+	///
+	/// The cache is shared with clones of the returned client. Call
+	/// [`SolanaRpcClient::spawn_blockhash_refresh`] to keep it fresh in the
+	/// background, otherwise it is refreshed when a helper finds it older
+	/// than [`BlockhashCacheConfig::max_age`].
+	#[must_use]
+	pub fn with_blockhash_cache(&self, config: BlockhashCacheConfig) -> Self {
+		Self {
+			blockhash_cache: Some(BlockhashCache::new(config)),
+			..self.clone()
+		}
+	}
+
+	pub fn blockhash_cache(&self) -> Option<&BlockhashCache> {
+		self.blockhash_cache.as_ref()
+	}
+
+	/// The cached blockhash and last valid block height for the commitment
+	/// level of the client, without making a request.
+	///
+	/// Returns `None` when the cache isn't enabled, when the blockhash is
+	/// stale or when it was fetched from another endpoint.
+	pub fn cached_blockhash(&self) -> Option<(Hash, u64)> {
+		self.blockhash_cache
+			.as_ref()?
+			.get(self.commitment(), &self.url())
+	}
+
+	/// The cached blockhash when it is available, otherwise the latest
+	/// blockhash. This is the blockhash used by the send and prepare
+	/// helpers.
+	pub async fn get_cached_or_latest_blockhash(&self) -> ClientResult<Hash> {
+		match self.cached_blockhash() {
+			Some((blockhash, _)) => Ok(blockhash),
+			None => self.get_latest_blockhash().await,
+		}
+	}
+
+	/// Fetch the latest blockhash into the cache now and then every
+	/// [`BlockhashCacheConfig::refresh_interval`] until the returned
+	/// [`AbortHandle`] is aborted or the client and all of its clones are
+	/// dropped.
+	///
+	/// Failed requests are skipped, the helpers fetch the blockhash
+	/// themselves once the cached one is stale. Returns `None` when the cache
+	/// isn't enabled.
+	pub fn spawn_blockhash_refresh(&self) -> Option<AbortHandle> {
+		let refresh_interval = self.blockhash_cache.as_ref()?.config().refresh_interval;
+		// The task gets its own task set so it doesn't keep the tasks of this
+		// client alive.
+		let rpc = Self {
+			tasks: Arc::default(),
+			..self.clone()
+		};
+
+		Some(self.spawn_task(async move {
+			loop {
+				let _ = rpc.get_latest_blockhash().await;
+				sleep(refresh_interval).await;
+			}
+		}))
+	}
+
+	fn spawn_task(&self, fut: impl Future<Output = ()> + 'static) -> AbortHandle {
+		self.tasks
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.spawn_local(fut)
+	}
+
+	/// The larger of `min_context_slot` and the slot set with
+	/// [`SolanaRpcClient::with_min_context_slot`].
+	fn context_slot(&self, min_context_slot: Option<Slot>) -> Option<Slot> {
+		min_context_slot.max(self.min_context_slot)
+	}
+
+	/// Reject a response whose `context` is older than the slot set with
+	/// [`SolanaRpcClient::with_min_context_slot`], for the methods which can't
+	/// send a `minContextSlot` with the request. The error is the one returned
+	/// by a node which hasn't reached the slot.
+	fn check_context_slot(&self, context: &RpcResponseContext) -> ClientResult<()> {
+		match self.context_slot(None) {
+			Some(min_context_slot) if context.slot < min_context_slot => {
+				Err(RpcError::min_context_slot_not_reached(context.slot).into())
+			}
+			_ => Ok(()),
+		}
+	}
+
+	fn context_config(&self, commitment_config: CommitmentConfig) -> RpcContextConfig {
+		RpcContextConfig {
+			commitment: Some(commitment_config),
+			min_context_slot: self.min_context_slot,
+		}
+	}
+
 	pub fn commitment(&self) -> CommitmentLevel {
 		self.commitment_config.commitment
 	}
@@ -223,19 +518,19 @@ impl SolanaRpcClient {
 	}
 
 	async fn send<T: HttpMethod, R: DeserializeOwned>(&self, request: T) -> ClientResult<R> {
-		let result = self
+		let body = self
 			.provider
-			.send(
+			.send_raw(
 				T::NAME,
 				serde_json::to_value(request)
 					.map_err(|error| ClientError::Other(error.to_string()))?,
 			)
 			.await?;
 
-		match serde_json::from_value::<R>(result.clone()) {
+		match serde_json::from_slice::<R>(&body) {
 			Ok(response) => Ok(response),
 			_ => {
-				match serde_json::from_value::<RpcError>(result) {
+				match serde_json::from_slice::<RpcError>(&body) {
 					Ok(error) => Err(error.into()),
 					Err(error) => Err(ClientError::Other(error.to_string())),
 				}
@@ -243,21 +538,53 @@ impl SolanaRpcClient {
 		}
 	}
 
-	pub async fn get_account_with_config(
+	/// Send the request and stream the items of the json array found at the
+	/// object `path` of the response.
+	async fn send_streaming<T: HttpMethod, R: DeserializeOwned + Send + 'static>(
+		&self,
+		request: T,
+		path: &'static [&'static str],
+	) -> ClientResult<BoxStream<'static, ClientResult<R>>> {
+		let chunks = self
+			.provider
+			.send_streaming(
+				T::NAME,
+				serde_json::to_value(request)
+					.map_err(|error| ClientError::Other(error.to_string()))?,
+			)
+			.await?;
+
+		Ok(stream_json_array(chunks, path))
+	}
+
+	/// Get the account of the `pubkey` along with the context of the response.
+	pub async fn get_account_with_context(
 		&self,
 		pubkey: &Pubkey,
 		config: RpcAccountInfoConfig,
-	) -> ClientResult<Option<Account>> {
+	) -> ClientResult<Response<Option<Account>>> {
+		let config = RpcAccountInfoConfig {
+			min_context_slot: self.context_slot(config.min_context_slot),
+			..config
+		};
 		let request = GetAccountInfoRequest::builder()
 			.pubkey(*pubkey)
 			.config(config)
 			.build();
 		let response: ClientResponse<GetAccountInfoResponse> = self.send(request).await?;
 
-		match response.result.value {
-			Some(ui_account) => Ok(ui_account.decode()),
-			None => Ok(None),
-		}
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value.and_then(|account| account.decode()),
+		})
+	}
+
+	pub async fn get_account_with_config(
+		&self,
+		pubkey: &Pubkey,
+		config: RpcAccountInfoConfig,
+	) -> ClientResult<Option<Account>> {
+		Ok(self.get_account_with_context(pubkey, config).await?.value)
 	}
 
 	pub async fn get_account_with_commitment(
@@ -289,15 +616,56 @@ impl SolanaRpcClient {
 		Ok(self.get_account(pubkey).await?.data)
 	}
 
-	pub async fn get_balance_with_commitment(
+	/// Fetch an anchor account and deserialize its data after checking the
+	/// discriminator.
+	#[cfg(feature = "anchor")]
+	pub async fn get_anchor_account_data<T: AnchorDeserialize + Discriminator>(
+		&self,
+		pubkey: &Pubkey,
+	) -> ClientResult<T> {
+		let request = GetAccountInfoRequest::builder()
+			.pubkey(*pubkey)
+			.config(RpcAccountInfoConfig {
+				commitment: Some(self.commitment_config()),
+				encoding: Some(UiAccountEncoding::Base64),
+				min_context_slot: self.context_slot(None),
+				..Default::default()
+			})
+			.build();
+		let response: ClientResponse<GetAccountInfoResponse> = self.send(request).await?;
+		let ui_account = response
+			.result
+			.value
+			.ok_or_else(|| RpcError::new(format!("Account {pubkey} not found.")))?;
+
+		Ok(ui_account.decode_anchor()?)
+	}
+
+	/// Get the balance of the `pubkey` along with the context of the response.
+	pub async fn get_balance_with_context(
 		&self,
 		pubkey: &Pubkey,
 		commitment_config: CommitmentConfig,
-	) -> ClientResult<u64> {
-		let request = GetBalanceRequest::new_with_config(*pubkey, commitment_config);
+	) -> ClientResult<Response<u64>> {
+		let request =
+			GetBalanceRequest::new_with_config(*pubkey, self.context_config(commitment_config));
 		let response: ClientResponse<GetBalanceResponse> = self.send(request).await?;
 
-		Ok(response.result.value)
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value,
+		})
+	}
+
+	pub async fn get_balance_with_commitment(
+		&self,
+		pubkey: &Pubkey,
+		commitment_config: CommitmentConfig,
+	) -> ClientResult<u64> {
+		Ok(self
+			.get_balance_with_context(pubkey, commitment_config)
+			.await?
+			.value)
 	}
 
 	pub async fn get_balance(&self, pubkey: &Pubkey) -> ClientResult<u64> {
@@ -313,14 +681,29 @@ impl SolanaRpcClient {
 		Ok(response.result.into())
 	}
 
+	/// Get the statuses of the `signatures`.
+	///
+	/// More than [`MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS`] signatures are
+	/// split into multiple requests.
 	pub async fn get_signature_statuses(
 		&self,
 		signatures: &[Signature],
 	) -> ClientResult<Vec<Option<TransactionStatus>>> {
-		let request = GetSignatureStatusesRequest::new(signatures.into());
-		let response: ClientResponse<GetSignatureStatusesResponse> = self.send(request).await?;
+		let requests = signatures
+			.chunks(MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS)
+			.map(|signatures| {
+				async move {
+					let request = GetSignatureStatusesRequest::new(signatures.into());
+					let response: ClientResponse<GetSignatureStatusesResponse> =
+						self.send(request).await?;
+
+					Ok(response.result.value)
+				}
+			});
+		let statuses =
+			try_join_limited::<_, _, ClientError>(requests, self.max_concurrent_requests).await?;
 
-		Ok(response.result.value)
+		Ok(statuses.into_iter().flatten().collect())
 	}
 
 	pub async fn get_transaction_with_config(
@@ -350,17 +733,122 @@ impl SolanaRpcClient {
 		}
 	}
 
-	pub async fn get_latest_blockhash_with_config(
+	/// Get a legacy or `v0` transaction encoded as `base64` along with the
+	/// decoded transaction.
+	///
+	/// When the node doesn't return the transaction the signature status is
+	/// checked to tell [`ClientError::TransactionNotConfirmed`] apart from
+	/// [`ClientError::TransactionNotFound`].
+	///
+	/// The configured commitment level is used, except that [`Processed`] is
+	/// replaced with [`Confirmed`] since `getTransaction` doesn't support it.
+	///
+	/// [`Processed`]: CommitmentLevel::Processed
+	/// [`Confirmed`]: CommitmentLevel::Confirmed
+	pub async fn get_transaction_versioned(
+		&self,
+		signature: &Signature,
+	) -> ClientResult<VersionedConfirmedTransaction> {
+		let commitment = match self.commitment() {
+			CommitmentLevel::Processed => CommitmentLevel::Confirmed,
+			commitment => commitment,
+		};
+		let config = RpcTransactionConfig {
+			encoding: Some(UiTransactionEncoding::Base64),
+			commitment: Some(CommitmentConfig { commitment }),
+			max_supported_transaction_version: Some(0),
+		};
+		let request = GetTransactionRequest::new_with_config(*signature, config);
+		let response: ClientResponse<GetTransactionResponse> = self.send(request).await?;
+		let Some(encoded): Option<EncodedConfirmedTransactionWithStatusMeta> =
+			response.result.into()
+		else {
+			return Err(self.missing_transaction_error(signature).await?);
+		};
+		let transaction = encoded
+			.transaction
+			.try_decode_transaction()
+			.map_err(|error| ClientError::Other(error.to_string()))?;
+
+		Ok(VersionedConfirmedTransaction {
+			encoded,
+			transaction,
+		})
+	}
+
+	/// Get the transactions of the `signatures` with
+	/// [`SolanaRpcClient::get_transaction_versioned`] in the order of the
+	/// `signatures`.
+	///
+	/// Up to [`SolanaRpcClient::max_concurrent_requests`] transactions are
+	/// fetched at once.
+	pub async fn get_transactions(
+		&self,
+		signatures: &[Signature],
+	) -> ClientResult<Vec<VersionedConfirmedTransaction>> {
+		let requests = signatures
+			.iter()
+			.map(|signature| self.get_transaction_versioned(signature));
+
+		try_join_limited(requests, self.max_concurrent_requests).await
+	}
+
+	async fn missing_transaction_error(&self, signature: &Signature) -> ClientResult<ClientError> {
+		let status = self
+			.get_signature_statuses(&[*signature])
+			.await?
+			.into_iter()
+			.next()
+			.flatten();
+
+		Ok(match status {
+			Some(status) => {
+				ClientError::TransactionNotConfirmed {
+					signature: *signature,
+					slot: status.slot,
+					confirmation_status: status.confirmation_status,
+				}
+			}
+			None => {
+				ClientError::TransactionNotFound {
+					signature: *signature,
+				}
+			}
+		})
+	}
+
+	/// Get the latest blockhash and the last block height at which it is valid
+	/// along with the context of the response.
+	pub async fn get_latest_blockhash_with_context(
 		&self,
 		commitment_config: CommitmentConfig,
-	) -> ClientResult<(Hash, u64)> {
-		let request = GetLatestBlockhashRequest::new_with_config(commitment_config);
+	) -> ClientResult<Response<(Hash, u64)>> {
+		let request =
+			GetLatestBlockhashRequest::new_with_config(self.context_config(commitment_config));
 		let response: ClientResponse<GetLatestBlockhashResponse> = self.send(request).await?;
-
-		Ok((
+		let value = (
 			response.result.value.blockhash,
 			response.result.value.last_valid_block_height,
-		))
+		);
+
+		if let Some(cache) = &self.blockhash_cache {
+			cache.insert(commitment_config.commitment, &self.url(), value);
+		}
+
+		Ok(Response {
+			context: response.result.context,
+			value,
+		})
+	}
+
+	pub async fn get_latest_blockhash_with_config(
+		&self,
+		commitment_config: CommitmentConfig,
+	) -> ClientResult<(Hash, u64)> {
+		Ok(self
+			.get_latest_blockhash_with_context(commitment_config)
+			.await?
+			.value)
 	}
 
 	pub async fn get_latest_blockhash_with_commitment(
@@ -379,21 +867,34 @@ impl SolanaRpcClient {
 		Ok(result.0)
 	}
 
-	pub async fn is_blockhash_valid(
+	/// Check whether the `blockhash` is still valid along with the context of
+	/// the response.
+	pub async fn is_blockhash_valid_with_context(
 		&self,
 		blockhash: &Hash,
 		commitment_config: CommitmentConfig,
-	) -> ClientResult<bool> {
+	) -> ClientResult<Response<bool>> {
 		let request = IsBlockhashValidRequest::new_with_config(
 			*blockhash,
-			RpcContextConfig {
-				commitment: Some(commitment_config),
-				min_context_slot: None,
-			},
+			self.context_config(commitment_config),
 		);
 		let response: ClientResponse<IsBlockhashValidResponse> = self.send(request).await?;
 
-		Ok(response.result.value)
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value,
+		})
+	}
+
+	pub async fn is_blockhash_valid(
+		&self,
+		blockhash: &Hash,
+		commitment_config: CommitmentConfig,
+	) -> ClientResult<bool> {
+		Ok(self
+			.is_blockhash_valid_with_context(blockhash, commitment_config)
+			.await?
+			.value)
 	}
 
 	pub async fn get_minimum_balance_for_rent_exemption(
@@ -407,11 +908,35 @@ impl SolanaRpcClient {
 		Ok(response.result.into())
 	}
 
-	pub async fn get_fee_for_message(&self, message: &Message) -> ClientResult<u64> {
-		let request = GetFeeForMessageRequest::new(message.to_owned());
+	/// Get the fee for the `message` along with the context of the response.
+	/// The fee is `None` when the blockhash of the message has expired.
+	pub async fn get_fee_for_message_with_context(
+		&self,
+		message: &Message,
+		config: RpcContextConfig,
+	) -> ClientResult<Response<Option<u64>>> {
+		let config = RpcContextConfig {
+			min_context_slot: self.context_slot(config.min_context_slot),
+			..config
+		};
+		let request = GetFeeForMessageRequest {
+			message: message.to_owned(),
+			config: (config != RpcContextConfig::default()).then_some(config),
+		};
 		let response: ClientResponse<GetFeeForMessageResponse> = self.send(request).await?;
 
-		Ok(response.result.into())
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value.fee(),
+		})
+	}
+
+	pub async fn get_fee_for_message(&self, message: &Message) -> ClientResult<u64> {
+		let response = self
+			.get_fee_for_message_with_context(message, RpcContextConfig::default())
+			.await?;
+
+		Ok(response.value.unwrap_or_default())
 	}
 
 	pub async fn send_transaction_with_config(
@@ -419,10 +944,27 @@ impl SolanaRpcClient {
 		transaction: &VersionedTransaction,
 		config: RpcSendTransactionConfig,
 	) -> ClientResult<Signature> {
+		if !config.skip_size_check {
+			transaction.fits_in_packet()?;
+		}
+
 		let transaction = transaction.to_owned();
 		let transaction_signature = transaction.signatures[0];
+		let config = RpcSendTransactionConfig {
+			min_context_slot: self.context_slot(config.min_context_slot),
+			..config
+		};
 		let request = SendTransactionRequest::new_with_config(transaction, config);
-		let response: ClientResponse<SendTransactionResponse> = self.send(request).await?;
+		let response: ClientResponse<SendTransactionResponse> =
+			self.send(request).await.inspect_err(|error| {
+				// The node doesn't know the cached blockhash, it may have been fetched
+				// from a node which is ahead.
+				if error.transaction_error() == Some(TransactionError::BlockhashNotFound) {
+					if let Some(cache) = &self.blockhash_cache {
+						cache.invalidate();
+					}
+				}
+			})?;
 		let signature: Signature = response.result.into();
 
 		// A mismatching RPC response signature indicates an issue with the RPC node,
@@ -455,6 +997,37 @@ impl SolanaRpcClient {
 		.await
 	}
 
+	/// Replace the blockhash of an expired `transaction` with the latest
+	/// blockhash, sign it again with `signers` and send it.
+	///
+	/// The existing signatures are invalid once the blockhash changes, so
+	/// `signers` must include every required signer of the transaction.
+	///
+	/// # Errors
+	///
+	/// Returns [`NonceError::DurableNonceTransaction`] when the transaction
+	/// uses a durable nonce, since its blockhash is the nonce value and
+	/// replacing it would invalidate the transaction.
+	pub async fn resign_and_resend<T: Signers + ?Sized>(
+		&self,
+		transaction: &VersionedTransaction,
+		signers: &T,
+	) -> ClientResult<Signature> {
+		if transaction.message.uses_durable_nonce() {
+			return Err(NonceError::DurableNonceTransaction.into());
+		}
+
+		let blockhash = self.get_latest_blockhash().await?;
+		let mut transaction = transaction.with_new_blockhash(blockhash);
+		transaction.try_sign(signers, None)?;
+
+		if !transaction.is_signed() {
+			return Err(SignerError::NotEnoughSigners.into());
+		}
+
+		self.send_transaction(&transaction).await
+	}
+
 	pub async fn confirm_transaction_with_commitment(
 		&self,
 		signature: &Signature,
@@ -489,7 +1062,7 @@ impl SolanaRpcClient {
 				}
 			}
 
-			Delay::new(Duration::from_millis(SLEEP_MS)).await;
+			sleep(Duration::from_millis(SLEEP_MS)).await;
 		}
 
 		Ok(is_success)
@@ -506,9 +1079,15 @@ impl SolanaRpcClient {
 		commitment_config: CommitmentConfig,
 		config: RpcSendTransactionConfig,
 	) -> ClientResult<Signature> {
-		let tx_hash = self
-			.send_transaction_with_config(transaction, config)
-			.await?;
+		let policy = RetryPolicy::builder()
+			.max_retries(MAX_SEND_RETRIES)
+			.initial_delay(Duration::from_millis(SLEEP_MS))
+			.multiplier(1)
+			.build();
+		let tx_hash = retry_with_backoff_if(policy, ClientError::is_retryable, || {
+			self.send_transaction_with_config(transaction, config)
+		})
+		.await?;
 
 		self.confirm_transaction_with_commitment(&tx_hash, commitment_config)
 			.await?;
@@ -541,28 +1120,141 @@ impl SolanaRpcClient {
 			.await
 	}
 
-	pub async fn get_program_accounts_with_config(
+	/// Build a `v0` transaction from the `instructions` with the `options`
+	/// applied, then sign, send and confirm it.
+	///
+	/// The memo is appended after the `instructions` and any existing compute
+	/// budget instructions are replaced. The transaction uses the latest
+	/// blockhash and must be fully signed by the `signers`.
+	pub async fn send_and_confirm_transaction_with_options<T: Signers + ?Sized>(
+		&self,
+		instructions: &[Instruction],
+		payer: &Pubkey,
+		signers: &T,
+		options: SendOptions,
+	) -> ClientResult<Signature> {
+		let SendOptions {
+			memo,
+			compute_unit_limit,
+			compute_unit_price,
+			skip_preflight,
+		} = options;
+		let mut instructions = instructions.to_vec();
+
+		if let Some(memo) = memo {
+			instructions.push(spl_memo::build_memo(memo.as_bytes(), &[]));
+		}
+
+		let blockhash = self.get_cached_or_latest_blockhash().await?;
+		let mut message = VersionedMessage::V0(v0::Message::try_compile(
+			payer,
+			&instructions,
+			&[],
+			blockhash,
+		)?);
+
+		if let Some(units) = compute_unit_limit {
+			message.with_compute_unit_limit(units)?;
+		}
+
+		match compute_unit_price {
+			Some(ComputeUnitPrice::Fixed(micro_lamports)) => {
+				message.with_compute_unit_price(micro_lamports)?;
+			}
+			Some(ComputeUnitPrice::Oracle(oracle, urgency)) => {
+				let micro_lamports = oracle
+					.estimate(&writable_accounts(&message), urgency)
+					.await?;
+				message.with_compute_unit_price(micro_lamports)?;
+			}
+			None => {}
+		}
+
+		let mut transaction = VersionedTransaction::new_unsigned(message);
+		transaction.try_sign(signers, None)?;
+
+		if !transaction.is_signed() {
+			return Err(SignerError::NotEnoughSigners.into());
+		}
+
+		let config = RpcSendTransactionConfig {
+			skip_preflight,
+			preflight_commitment: Some(self.commitment()),
+			encoding: Some(UiTransactionEncoding::Base64),
+			..Default::default()
+		};
+
+		self.send_and_confirm_transaction_with_config(
+			&transaction,
+			self.commitment_config(),
+			config,
+		)
+		.await
+	}
+
+	fn program_accounts_config(
 		&self,
-		pubkey: &Pubkey,
 		config: RpcProgramAccountsConfig,
-	) -> ClientResult<Vec<(Pubkey, Account)>> {
+	) -> RpcProgramAccountsConfig {
 		let commitment = config
 			.account_config
 			.commitment
 			.unwrap_or_else(|| self.commitment_config());
 		let account_config = RpcAccountInfoConfig {
 			commitment: Some(commitment),
+			min_context_slot: self.context_slot(config.account_config.min_context_slot),
 			..config.account_config
 		};
-		let config = RpcProgramAccountsConfig {
+
+		RpcProgramAccountsConfig {
 			account_config,
 			..config
-		};
-
-		let request = GetProgramAccountsRequest::new_with_config(*pubkey, config);
-		let response: ClientResponse<GetProgramAccountsResponse> = self.send(request).await?;
+		}
+	}
 
-		// Parse keyed accounts
+	/// Get the accounts owned by the program `pubkey` along with the context
+	/// of the response. The `with_context` option of the `config` is always
+	/// set.
+	pub async fn get_program_accounts_with_context(
+		&self,
+		pubkey: &Pubkey,
+		config: RpcProgramAccountsConfig,
+	) -> ClientResult<Response<Vec<(Pubkey, Account)>>> {
+		let config = RpcProgramAccountsConfig {
+			with_context: Some(true),
+			..self.program_accounts_config(config)
+		};
+		let request = GetProgramAccountsRequest::new_with_config(*pubkey, config);
+		let response: ClientResponse<GetProgramAccountsWithContextResponse> =
+			self.send(request).await?;
+		let accounts = response.result.value;
+
+		let mut pubkey_accounts: Vec<(Pubkey, Account)> = Vec::with_capacity(accounts.len());
+		for RpcKeyedAccount { pubkey, account } in accounts {
+			pubkey_accounts.push((
+				pubkey,
+				account
+					.decode()
+					.ok_or_else(|| RpcError::new(format!("Unable to decode {pubkey}")))?,
+			));
+		}
+
+		Ok(Response {
+			context: response.result.context,
+			value: pubkey_accounts,
+		})
+	}
+
+	pub async fn get_program_accounts_with_config(
+		&self,
+		pubkey: &Pubkey,
+		config: RpcProgramAccountsConfig,
+	) -> ClientResult<Vec<(Pubkey, Account)>> {
+		let config = self.program_accounts_config(config);
+		let request = GetProgramAccountsRequest::new_with_config(*pubkey, config);
+		let response: ClientResponse<GetProgramAccountsResponse> = self.send(request).await?;
+
+		// Parse keyed accounts
 		let accounts = response
 			.result
 			.keyed_accounts()
@@ -597,11 +1289,63 @@ impl SolanaRpcClient {
 		.await
 	}
 
+	/// Stream the accounts owned by the program `pubkey`, yielding each
+	/// account as soon as it has been parsed from the response.
+	///
+	/// The response of a large program can be hundreds of megabytes.
+	/// [`SolanaRpcClient::get_program_accounts_with_config`] holds all of it in
+	/// memory at once, while this only holds the account being parsed. In the
+	/// browser the body is read in chunks so the main thread can handle other
+	/// events between them.
+	pub async fn stream_program_accounts_with_config(
+		&self,
+		pubkey: &Pubkey,
+		config: RpcProgramAccountsConfig,
+	) -> ClientResult<BoxStream<'static, ClientResult<(Pubkey, Account)>>> {
+		let path: &'static [&'static str] = if config.with_context.unwrap_or_default() {
+			&["result", "value"]
+		} else {
+			&["result"]
+		};
+		let config = self.program_accounts_config(config);
+		let request = GetProgramAccountsRequest::new_with_config(*pubkey, config);
+		let accounts = self
+			.send_streaming::<_, RpcKeyedAccount>(request, path)
+			.await?;
+		let accounts = accounts.map(|result| {
+			let RpcKeyedAccount { pubkey, account } = result?;
+			let account = account
+				.decode()
+				.ok_or_else(|| RpcError::new(format!("Unable to decode {pubkey}")))?;
+
+			Ok((pubkey, account))
+		});
+
+		Ok(accounts.boxed())
+	}
+
+	pub async fn stream_program_accounts(
+		&self,
+		pubkey: &Pubkey,
+	) -> ClientResult<BoxStream<'static, ClientResult<(Pubkey, Account)>>> {
+		self.stream_program_accounts_with_config(
+			pubkey,
+			RpcProgramAccountsConfig {
+				account_config: RpcAccountInfoConfig {
+					encoding: Some(UiAccountEncoding::Base64),
+					..RpcAccountInfoConfig::default()
+				},
+				..RpcProgramAccountsConfig::default()
+			},
+		)
+		.await
+	}
+
 	pub async fn get_slot_with_commitment(
 		&self,
 		commitment_config: CommitmentConfig,
 	) -> ClientResult<Slot> {
-		let request = GetSlotRequest::new_with_config(commitment_config);
+		let request = GetSlotRequest::new_with_config(self.context_config(commitment_config));
 		let response: ClientResponse<GetSlotResponse> = self.send(request).await?;
 
 		Ok(response.result.into())
@@ -623,6 +1367,24 @@ impl SolanaRpcClient {
 		Ok(response.result.into())
 	}
 
+	/// Stream the transactions of the block at `slot`, yielding each
+	/// transaction as soon as it has been parsed from the response.
+	///
+	/// The other fields of the block are skipped. Use
+	/// [`SolanaRpcClient::get_block_with_config`] with
+	/// [`TransactionDetails::None`](crate::solana_transaction_status::TransactionDetails::None)
+	/// to fetch them without the transactions.
+	pub async fn stream_block_transactions_with_config(
+		&self,
+		slot: Slot,
+		config: RpcBlockConfig,
+	) -> ClientResult<BoxStream<'static, ClientResult<EncodedTransactionWithStatusMeta>>> {
+		let request = GetBlockRequest::new_with_config(slot, config);
+
+		self.send_streaming(request, &["result", "transactions"])
+			.await
+	}
+
 	pub async fn get_version(&self) -> ClientResult<RpcVersionInfo> {
 		let response: ClientResponse<GetVersionResponse> = self.send(GetVersionRequest).await?;
 
@@ -651,7 +1413,8 @@ impl SolanaRpcClient {
 		&self,
 		commitment_config: CommitmentConfig,
 	) -> ClientResult<u64> {
-		let request = GetBlockHeightRequest::new_with_config(commitment_config);
+		let request =
+			GetBlockHeightRequest::new_with_config(self.context_config(commitment_config));
 		let response: ClientResponse<GetBlockHeightResponse> = self.send(request).await?;
 
 		Ok(response.result.into())
@@ -678,7 +1441,7 @@ impl SolanaRpcClient {
 		&self,
 		commitment_config: CommitmentConfig,
 	) -> ClientResult<EpochInfo> {
-		let request = GetEpochInfoRequest::new_with_config(commitment_config);
+		let request = GetEpochInfoRequest::new_with_config(self.context_config(commitment_config));
 		let response: ClientResponse<GetEpochInfoResponse> = self.send(request).await?;
 
 		Ok(response.result.into())
@@ -727,6 +1490,20 @@ impl SolanaRpcClient {
 		Ok(response.result.into())
 	}
 
+	/// The compute unit price in micro-lamports estimated by the
+	/// `getPriorityFeeEstimate` method of the Helius rpc for a transaction
+	/// which writes to the `accounts`. Other rpc providers return an error.
+	pub async fn get_priority_fee_estimate(
+		&self,
+		accounts: Vec<Pubkey>,
+		priority_level: PriorityLevel,
+	) -> ClientResult<u64> {
+		let request = GetPriorityFeeEstimateRequest::new(accounts, priority_level);
+		let response: ClientResponse<GetPriorityFeeEstimateResponse> = self.send(request).await?;
+
+		Ok(response.result.into())
+	}
+
 	pub async fn get_blocks_with_limit_and_commitment(
 		&self,
 		start_slot: Slot,
@@ -749,38 +1526,74 @@ impl SolanaRpcClient {
 			.await
 	}
 
-	pub async fn get_largest_accounts_with_config(
+	/// Get the accounts with the largest balances along with the context of
+	/// the response.
+	pub async fn get_largest_accounts_with_context(
 		&self,
 		config: RpcLargestAccountsConfig,
-	) -> ClientResult<Vec<RpcAccountBalance>> {
-		let config = RpcLargestAccountsConfig {
-			commitment: config.commitment,
-			..config
-		};
-
+	) -> ClientResult<Response<Vec<RpcAccountBalance>>> {
 		let request = GetLargestAccountsRequest::new_with_config(config);
 		let response: ClientResponse<GetLargestAccountsResponse> = self.send(request).await?;
+		self.check_context_slot(&response.result.context)?;
 
-		Ok(response.result.value)
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value,
+		})
 	}
 
-	pub async fn get_supply_with_config(&self, config: RpcSupplyConfig) -> ClientResult<RpcSupply> {
+	pub async fn get_largest_accounts_with_config(
+		&self,
+		config: RpcLargestAccountsConfig,
+	) -> ClientResult<Vec<RpcAccountBalance>> {
+		Ok(self.get_largest_accounts_with_context(config).await?.value)
+	}
+
+	/// Get the supply of lamports along with the context of the response.
+	pub async fn get_supply_with_context(
+		&self,
+		config: RpcSupplyConfig,
+	) -> ClientResult<Response<RpcSupply>> {
 		let request = GetSupplyRequest::new_with_config(config);
 		let response: ClientResponse<GetSupplyResponse> = self.send(request).await?;
+		self.check_context_slot(&response.result.context)?;
+
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value,
+		})
+	}
 
-		Ok(response.result.value)
+	pub async fn get_supply_with_config(&self, config: RpcSupplyConfig) -> ClientResult<RpcSupply> {
+		Ok(self.get_supply_with_context(config).await?.value)
 	}
 
-	pub async fn get_stake_minimum_delegation_with_commitment(
+	/// Get the minimum delegation of a stake account along with the context
+	/// of the response.
+	pub async fn get_stake_minimum_delegation_with_context(
 		&self,
 		commitment: CommitmentLevel,
-	) -> ClientResult<u64> {
+	) -> ClientResult<Response<u64>> {
 		let request =
 			GetStakeMinimumDelegationRequest::new_with_config(CommitmentConfig { commitment });
 		let response: ClientResponse<GetStakeMinimumDelegationResponse> =
 			self.send(request).await?;
+		self.check_context_slot(&response.result.context)?;
 
-		Ok(response.result.value)
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value,
+		})
+	}
+
+	pub async fn get_stake_minimum_delegation_with_commitment(
+		&self,
+		commitment: CommitmentLevel,
+	) -> ClientResult<u64> {
+		Ok(self
+			.get_stake_minimum_delegation_with_context(commitment)
+			.await?
+			.value)
 	}
 
 	pub async fn get_stake_minimum_delegation(&self) -> ClientResult<u64> {
@@ -807,6 +1620,10 @@ impl SolanaRpcClient {
 		&self,
 		config: RpcContextConfig,
 	) -> ClientResult<u64> {
+		let config = RpcContextConfig {
+			min_context_slot: self.context_slot(config.min_context_slot),
+			..config
+		};
 		let request = GetTransactionCountRequest::new_with_config(config);
 		let response: ClientResponse<GetTransactionCountResponse> = self.send(request).await?;
 
@@ -829,26 +1646,79 @@ impl SolanaRpcClient {
 			.await
 	}
 
-	pub async fn get_multiple_accounts_with_config(
+	/// Get the accounts of the `pubkeys` along with the context of the
+	/// response.
+	///
+	/// More than [`MAX_MULTIPLE_ACCOUNTS`] pubkeys are split into multiple
+	/// requests. The returned context is the one with the lowest slot, so every
+	/// account is at least as fresh as that slot. A single request is still
+	/// sent without any `pubkeys` to read the context.
+	pub async fn get_multiple_accounts_with_context(
 		&self,
 		pubkeys: &[Pubkey],
 		config: RpcAccountInfoConfig,
-	) -> ClientResult<Vec<Option<Account>>> {
+	) -> ClientResult<Response<Vec<Option<Account>>>> {
 		let config = RpcAccountInfoConfig {
-			commitment: config.commitment,
+			min_context_slot: self.context_slot(config.min_context_slot),
 			..config
 		};
+		let chunks = if pubkeys.is_empty() {
+			vec![pubkeys]
+		} else {
+			pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS).collect()
+		};
+		let requests = chunks.into_iter().map(|pubkeys| {
+			let config = config.clone();
+
+			async move {
+				let request = GetMultipleAccountsRequest::new_with_config(pubkeys.to_vec(), config);
+				let response: ClientResponse<GetMultipleAccountsResponse> =
+					self.send(request).await?;
+				let accounts = response
+					.result
+					.value
+					.iter()
+					.map(|account| account.as_ref().and_then(UiAccount::decode))
+					.collect::<Vec<_>>();
+
+				Ok((response.result.context, accounts))
+			}
+		});
+		let chunks =
+			try_join_limited::<_, _, ClientError>(requests, self.max_concurrent_requests).await?;
+		let context = chunks
+			.iter()
+			.map(|(context, _)| context)
+			.min_by_key(|context| context.slot)
+			.cloned()
+			.ok_or_else(|| RpcError::new("no getMultipleAccounts response was received"))?;
+
+		Ok(Response {
+			context,
+			value: chunks
+				.into_iter()
+				.flat_map(|(_, accounts)| accounts)
+				.collect(),
+		})
+	}
 
-		let request = GetMultipleAccountsRequest::new_with_config(pubkeys.to_vec(), config);
-		let response: ClientResponse<GetMultipleAccountsResponse> = self.send(request).await?;
+	/// Get the accounts of the `pubkeys`.
+	///
+	/// More than [`MAX_MULTIPLE_ACCOUNTS`] pubkeys are split into multiple
+	/// requests.
+	pub async fn get_multiple_accounts_with_config(
+		&self,
+		pubkeys: &[Pubkey],
+		config: RpcAccountInfoConfig,
+	) -> ClientResult<Vec<Option<Account>>> {
+		if pubkeys.is_empty() {
+			return Ok(vec![]);
+		}
 
-		Ok(response
-			.result
-			.value
-			.iter()
-			.filter(|maybe_acc| maybe_acc.is_some())
-			.map(|acc| acc.clone().unwrap().decode())
-			.collect())
+		Ok(self
+			.get_multiple_accounts_with_context(pubkeys, config)
+			.await?
+			.value)
 	}
 
 	pub async fn get_multiple_accounts_with_commitment(
@@ -924,7 +1794,7 @@ impl SolanaRpcClient {
 			until: config.until,
 			limit: config.limit,
 			commitment: config.commitment,
-			min_context_slot: None,
+			min_context_slot: self.min_context_slot,
 		};
 
 		let request = GetSignaturesForAddressRequest::new_with_config(*address, config);
@@ -933,6 +1803,61 @@ impl SolanaRpcClient {
 		Ok(response.result.into())
 	}
 
+	/// Stream the signatures of the transactions which used the `address`,
+	/// newest first, requesting the next page once the previous one has been
+	/// read.
+	///
+	/// The `config` bounds the range like
+	/// [`SolanaRpcClient::get_signatures_for_address_with_config`] except that
+	/// the `limit` is the total number of signatures across every page. Each
+	/// page holds up to [`MAX_SIGNATURES_FOR_ADDRESS_LIMIT`] signatures.
+	pub fn stream_signatures_for_address(
+		&self,
+		address: &Pubkey,
+		config: GetConfirmedSignaturesForAddress2Config,
+	) -> impl Stream<Item = ClientResult<RpcConfirmedTransactionStatusWithSignature>> + use<> {
+		let GetConfirmedSignaturesForAddress2Config {
+			before,
+			until,
+			limit,
+			commitment,
+		} = config;
+		let rpc = self.clone();
+		let address = *address;
+
+		stream::try_unfold((before, limit, false), move |(before, remaining, done)| {
+			let rpc = rpc.clone();
+
+			async move {
+				if done || remaining == Some(0) {
+					return Ok::<_, ClientError>(None);
+				}
+
+				let page_limit = remaining.map_or(MAX_SIGNATURES_FOR_ADDRESS_LIMIT, |remaining| {
+					remaining.min(MAX_SIGNATURES_FOR_ADDRESS_LIMIT)
+				});
+				let config = GetConfirmedSignaturesForAddress2Config {
+					before,
+					until,
+					limit: Some(page_limit),
+					commitment,
+				};
+				let page = rpc
+					.get_signatures_for_address_with_config(&address, config)
+					.await?;
+				let done = page.len() < page_limit;
+				let before = page.last().map(|status| status.signature);
+				let remaining = remaining.map(|remaining| remaining.saturating_sub(page.len()));
+
+				Ok(Some((
+					stream::iter(page.into_iter().map(Ok::<_, ClientError>)),
+					(before, remaining, done),
+				)))
+			}
+		})
+		.try_flatten()
+	}
+
 	pub async fn minimum_ledger_slot(&self) -> ClientResult<Slot> {
 		let response: ClientResponse<MinimumLedgerSlotResponse> =
 			self.send(MinimumLedgerSlotRequest).await?;
@@ -990,14 +1915,27 @@ impl SolanaRpcClient {
 		.await
 	}
 
-	pub async fn get_block_production_with_config(
+	/// Get the recent block production along with the context of the
+	/// response.
+	pub async fn get_block_production_with_context(
 		&self,
 		config: RpcBlockProductionConfig,
-	) -> ClientResult<RpcBlockProduction> {
+	) -> ClientResult<Response<RpcBlockProduction>> {
 		let request = GetBlockProductionRequest::new_with_config(config);
 		let response: ClientResponse<GetBlockProductionResponse> = self.send(request).await?;
+		self.check_context_slot(&response.result.context)?;
+
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value,
+		})
+	}
 
-		Ok(response.result.value)
+	pub async fn get_block_production_with_config(
+		&self,
+		config: RpcBlockProductionConfig,
+	) -> ClientResult<RpcBlockProduction> {
+		Ok(self.get_block_production_with_context(config).await?.value)
 	}
 
 	pub async fn get_block_production_with_commitment(
@@ -1038,22 +1976,36 @@ impl SolanaRpcClient {
 		Ok(response.result.into())
 	}
 
+	/// Get the inflation rewards of the `addresses`.
+	///
+	/// More than [`MAX_MULTIPLE_ACCOUNTS`] addresses are split into multiple
+	/// requests.
 	pub async fn get_inflation_reward_with_config(
 		&self,
 		addresses: &[Pubkey],
 		epoch: Option<Epoch>,
 	) -> ClientResult<Vec<Option<RpcInflationReward>>> {
-		let request = GetInflationRewardRequest::new_with_config(
-			addresses.to_vec(),
-			RpcEpochConfig {
-				commitment: Some(self.commitment_config()),
-				epoch,
-				..Default::default()
-			},
-		);
-		let response: ClientResponse<GetInflationRewardResponse> = self.send(request).await?;
+		let requests = addresses.chunks(MAX_MULTIPLE_ACCOUNTS).map(|addresses| {
+			async move {
+				let request = GetInflationRewardRequest::new_with_config(
+					addresses.to_vec(),
+					RpcEpochConfig {
+						commitment: Some(self.commitment_config()),
+						epoch,
+						min_context_slot: self.min_context_slot,
+					},
+				);
+				let response: ClientResponse<GetInflationRewardResponse> =
+					self.send(request).await?;
+				let rewards: Vec<Option<RpcInflationReward>> = response.result.into();
+
+				Ok(rewards)
+			}
+		});
+		let rewards =
+			try_join_limited::<_, _, ClientError>(requests, self.max_concurrent_requests).await?;
 
-		Ok(response.result.into())
+		Ok(rewards.into_iter().flatten().collect())
 	}
 
 	pub async fn get_inflation_reward(
@@ -1063,16 +2015,19 @@ impl SolanaRpcClient {
 		self.get_inflation_reward_with_config(addresses, None).await
 	}
 
-	pub async fn get_token_account_with_commitment(
+	/// Get the token account at the `pubkey` along with the context of the
+	/// response. The value is `None` when there is no account or it isn't a
+	/// token account.
+	pub async fn get_token_account_with_context(
 		&self,
 		pubkey: &Pubkey,
 		commitment_config: CommitmentConfig,
-	) -> ClientResult<Option<UiTokenAccount>> {
+	) -> ClientResult<Response<Option<UiTokenAccount>>> {
 		let config = RpcAccountInfoConfig {
 			encoding: Some(UiAccountEncoding::JsonParsed),
 			commitment: Some(commitment_config),
 			data_slice: None,
-			min_context_slot: None,
+			min_context_slot: self.context_slot(None),
 		};
 
 		let request = GetAccountInfoRequest::builder()
@@ -1080,6 +2035,7 @@ impl SolanaRpcClient {
 			.config(config)
 			.build();
 		let response: ClientResponse<GetAccountInfoResponse> = self.send(request).await?;
+		let mut value = None;
 
 		if let Some(acc) = response.result.value {
 			if let UiAccountData::Json(account_data) = acc.data {
@@ -1090,12 +2046,30 @@ impl SolanaRpcClient {
 					};
 
 				if let TokenAccountType::Account(token_account) = token_account_type {
-					return Ok(Some(token_account));
+					value = Some(token_account);
 				}
 			}
 		}
 
-		Err(RpcError::new(format!("AccountNotFound: pubkey={pubkey}")).into())
+		Ok(Response {
+			context: response.result.context,
+			value,
+		})
+	}
+
+	pub async fn get_token_account_with_commitment(
+		&self,
+		pubkey: &Pubkey,
+		commitment_config: CommitmentConfig,
+	) -> ClientResult<Option<UiTokenAccount>> {
+		let response = self
+			.get_token_account_with_context(pubkey, commitment_config)
+			.await?;
+
+		match response.value {
+			Some(token_account) => Ok(Some(token_account)),
+			None => Err(RpcError::new(format!("AccountNotFound: pubkey={pubkey}")).into()),
+		}
 	}
 
 	pub async fn get_token_account(&self, pubkey: &Pubkey) -> ClientResult<Option<UiTokenAccount>> {
@@ -1103,31 +2077,48 @@ impl SolanaRpcClient {
 			.await
 	}
 
-	pub async fn get_token_accounts_by_owner_with_commitment(
+	/// Get the token accounts of the `owner` along with the context of the
+	/// response.
+	pub async fn get_token_accounts_by_owner_with_context(
 		&self,
 		owner: &Pubkey,
 		token_account_filter: TokenAccountsFilter,
 		commitment_config: CommitmentConfig,
-	) -> ClientResult<Vec<RpcKeyedAccount>> {
-		let token_account_filter = match token_account_filter {
-			TokenAccountsFilter::Mint(mint) => RpcTokenAccountsFilter::Mint(mint),
-			TokenAccountsFilter::ProgramId(program_id) => {
-				RpcTokenAccountsFilter::ProgramId(program_id)
-			}
-		};
-
+	) -> ClientResult<Response<Vec<RpcKeyedAccount>>> {
 		let config = RpcAccountInfoConfig {
 			encoding: Some(UiAccountEncoding::JsonParsed),
 			commitment: Some(commitment_config),
 			data_slice: None,
-			min_context_slot: None,
+			min_context_slot: self.min_context_slot,
 		};
 
-		let request =
-			GetTokenAccountsByOwnerRequest::new_with_config(*owner, token_account_filter, config);
+		let request = GetTokenAccountsByOwnerRequest::new_with_config(
+			*owner,
+			token_account_filter.into(),
+			config,
+		);
 		let response: ClientResponse<GetTokenAccountsByOwnerResponse> = self.send(request).await?;
 
-		Ok(response.result.value)
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value,
+		})
+	}
+
+	pub async fn get_token_accounts_by_owner_with_commitment(
+		&self,
+		owner: &Pubkey,
+		token_account_filter: TokenAccountsFilter,
+		commitment_config: CommitmentConfig,
+	) -> ClientResult<Vec<RpcKeyedAccount>> {
+		Ok(self
+			.get_token_accounts_by_owner_with_context(
+				owner,
+				token_account_filter,
+				commitment_config,
+			)
+			.await?
+			.value)
 	}
 
 	pub async fn get_token_accounts_by_owner(
@@ -1143,15 +2134,32 @@ impl SolanaRpcClient {
 		.await
 	}
 
-	pub async fn get_token_account_balance_with_commitment(
+	/// Get the token balance of the `pubkey` along with the context of the
+	/// response.
+	pub async fn get_token_account_balance_with_context(
 		&self,
 		pubkey: &Pubkey,
 		commitment_config: CommitmentConfig,
-	) -> ClientResult<UiTokenAmount> {
+	) -> ClientResult<Response<UiTokenAmount>> {
 		let request = GetTokenAccountBalanceRequest::new_with_config(*pubkey, commitment_config);
 		let response: ClientResponse<GetTokenAccountBalanceResponse> = self.send(request).await?;
+		self.check_context_slot(&response.result.context)?;
 
-		Ok(response.result.value)
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value,
+		})
+	}
+
+	pub async fn get_token_account_balance_with_commitment(
+		&self,
+		pubkey: &Pubkey,
+		commitment_config: CommitmentConfig,
+	) -> ClientResult<UiTokenAmount> {
+		Ok(self
+			.get_token_account_balance_with_context(pubkey, commitment_config)
+			.await?
+			.value)
 	}
 
 	pub async fn get_token_account_balance(&self, pubkey: &Pubkey) -> ClientResult<UiTokenAmount> {
@@ -1159,15 +2167,32 @@ impl SolanaRpcClient {
 			.await
 	}
 
-	pub async fn get_token_supply_with_commitment(
+	/// Get the supply of the token `mint` along with the context of the
+	/// response.
+	pub async fn get_token_supply_with_context(
 		&self,
 		mint: &Pubkey,
 		commitment_config: CommitmentConfig,
-	) -> ClientResult<UiTokenAmount> {
+	) -> ClientResult<Response<UiTokenAmount>> {
 		let request = GetTokenSupplyRequest::new_with_config(*mint, commitment_config);
 		let response: ClientResponse<GetTokenSupplyResponse> = self.send(request).await?;
+		self.check_context_slot(&response.result.context)?;
 
-		Ok(response.result.value)
+		Ok(Response {
+			context: response.result.context,
+			value: response.result.value,
+		})
+	}
+
+	pub async fn get_token_supply_with_commitment(
+		&self,
+		mint: &Pubkey,
+		commitment_config: CommitmentConfig,
+	) -> ClientResult<UiTokenAmount> {
+		Ok(self
+			.get_token_supply_with_context(mint, commitment_config)
+			.await?
+			.value)
 	}
 
 	pub async fn get_token_supply(&self, mint: &Pubkey) -> ClientResult<UiTokenAmount> {
@@ -1180,6 +2205,10 @@ impl SolanaRpcClient {
 		transaction: &VersionedTransaction,
 		config: RpcSimulateTransactionConfig,
 	) -> ClientResult<SimulateTransactionResponse> {
+		let config = RpcSimulateTransactionConfig {
+			min_context_slot: self.context_slot(config.min_context_slot),
+			..config
+		};
 		let request = SimulateTransactionRequest::new_with_config(transaction.to_owned(), config);
 		let response: ClientResponse<SimulateTransactionResponse> = self.send(request).await?;
 
@@ -1202,6 +2231,286 @@ impl SolanaRpcClient {
 		.await
 	}
 
+	/// Build a signed `v0` transaction with a compute unit limit taken from
+	/// simulating the `instructions`.
+	///
+	/// The compute units consumed by the simulation are increased by the
+	/// `compute_unit_margin_percent` of the `config`. When a
+	/// `priority_fee_percentile` is provided the compute unit price is set
+	/// from [`SolanaRpcClient::estimate_priority_fee`]. Any existing compute
+	/// budget instructions are replaced. The transaction is signed by the
+	/// `signers` with the latest blockhash and may be partially signed.
+	///
+	/// A failed simulation returns a [`ClientError::Simulation`] with the
+	/// logs of the transaction.
+	pub async fn prepare_transaction<T: Signers + ?Sized>(
+		&self,
+		instructions: &[Instruction],
+		payer: &Pubkey,
+		signers: &T,
+		config: PrepareTransactionConfig,
+	) -> ClientResult<VersionedTransaction> {
+		let PrepareTransactionConfig {
+			compute_unit_margin_percent,
+			priority_fee_percentile,
+			address_lookup_tables,
+		} = config;
+		let mut message = VersionedMessage::V0(v0::Message::try_compile(
+			payer,
+			instructions,
+			&address_lookup_tables,
+			Hash::default(),
+		)?);
+
+		if let Some(percentile) = priority_fee_percentile {
+			let price = self
+				.estimate_priority_fee(writable_accounts(&message), percentile)
+				.await?;
+
+			message.with_compute_unit_price(price)?;
+		}
+
+		message.with_compute_unit_limit(COMPUTE_UNIT_MAX_LIMIT as u32)?;
+
+		let simulation = self
+			.simulate_transaction(&VersionedTransaction::new_unsigned(message.clone()))
+			.await?
+			.value;
+		let units_consumed = match (simulation.err, simulation.units_consumed) {
+			(None, Some(units_consumed)) => units_consumed,
+			(err, _) => {
+				return Err(SimulationError {
+					err,
+					logs: simulation.logs.unwrap_or_default(),
+				}
+				.into());
+			}
+		};
+		let compute_unit_limit = (units_consumed.saturating_mul(100 + compute_unit_margin_percent)
+			/ 100)
+			.min(COMPUTE_UNIT_MAX_LIMIT as u64);
+
+		message.with_compute_unit_limit(u32::try_from(compute_unit_limit).unwrap_or(u32::MAX))?;
+
+		let mut transaction = VersionedTransaction::new_unsigned(message);
+		transaction.try_sign(signers, Some(self.get_cached_or_latest_blockhash().await?))?;
+
+		Ok(transaction)
+	}
+
+	/// The `percentile` of the prioritization fees paid in recent blocks by
+	/// transactions which lock any of the writable `accounts`. Returns `0`
+	/// when no fees were reported.
+	pub async fn estimate_priority_fee(
+		&self,
+		accounts: Vec<Pubkey>,
+		percentile: u8,
+	) -> ClientResult<u64> {
+		let fees = self
+			.get_recent_prioritization_fees_with_accounts(accounts)
+			.await?
+			.into_iter()
+			.map(|fee| fee.prioritization_fee)
+			.collect();
+
+		Ok(fee_percentile(fees, percentile))
+	}
+
+	/// Transfer `lamports` from the `from` signer to the `to` account. The
+	/// transaction is signed, sent and confirmed.
+	pub async fn transfer_sol<S: Signer + ?Sized>(
+		&self,
+		from: &S,
+		to: &Pubkey,
+		lamports: u64,
+		config: TransferConfig,
+	) -> ClientResult<Signature> {
+		let instruction =
+			solana_system_interface::instruction::transfer(&from.pubkey(), to, lamports);
+
+		self.send_transfer(from, vec![instruction], config).await
+	}
+
+	/// Transfer `amount` of the `mint` from the associated token account of
+	/// the `from` signer to the associated token account of `to_owner`. The
+	/// transaction is signed, sent and confirmed.
+	///
+	/// The transfer uses `transfer_checked` with the decimals of the mint. The
+	/// decimals and token program of each mint are fetched once and cached by
+	/// the client, so both token programs are supported. The associated token
+	/// account of the recipient is created idempotently unless disabled in
+	/// the `config`.
+	pub async fn transfer_token<S: Signer + ?Sized>(
+		&self,
+		from: &S,
+		mint: &Pubkey,
+		to_owner: &Pubkey,
+		amount: u64,
+		config: TransferConfig,
+	) -> ClientResult<Signature> {
+		let MintInfo {
+			decimals,
+			token_program,
+		} = self.get_mint_info(mint).await?;
+		let from_pubkey = from.pubkey();
+		let source =
+			get_associated_token_address_with_program_id(&from_pubkey, mint, &token_program);
+		let destination =
+			get_associated_token_address_with_program_id(to_owner, mint, &token_program);
+		let mut instructions = vec![];
+
+		if config.create_associated_token_account {
+			instructions.push(create_associated_token_account_idempotent(
+				&from_pubkey,
+				to_owner,
+				mint,
+				&token_program,
+			));
+		}
+
+		instructions.push(
+			spl_token_2022::instruction::transfer_checked(
+				&token_program,
+				&source,
+				mint,
+				&destination,
+				&from_pubkey,
+				&[],
+				amount,
+				decimals,
+			)
+			.map_err(|error| ClientError::Other(error.to_string()))?,
+		);
+
+		self.send_transfer(from, instructions, config).await
+	}
+
+	/// The associated token account of the `owner` for the `mint`, created by
+	/// the `payer` when it doesn't exist yet.
+	///
+	/// The account is looked up with a single `getAccountInfo` request and no
+	/// transaction is sent when it exists and is owned by the token program of
+	/// the mint. Otherwise it is created with the idempotent instruction, so a
+	/// concurrent creation doesn't fail and lamports already sent to the
+	/// address are kept, and the transaction is signed, sent and confirmed.
+	/// Both token programs are supported.
+	pub async fn get_or_create_associated_token_account<S: Signer + ?Sized>(
+		&self,
+		payer: &S,
+		owner: &Pubkey,
+		mint: &Pubkey,
+	) -> ClientResult<AssociatedTokenAccount> {
+		let MintInfo { token_program, .. } = self.get_mint_info(mint).await?;
+		let address = derive_associated_token_address(owner, mint, &token_program);
+		let account = self
+			.get_account_with_commitment(&address, self.commitment_config())
+			.await?;
+
+		if account.is_some_and(|account| account.owner == token_program) {
+			return Ok(AssociatedTokenAccount {
+				address,
+				created: false,
+			});
+		}
+
+		let payer_pubkey = payer.pubkey();
+		let instruction =
+			create_associated_token_account_idempotent(&payer_pubkey, owner, mint, &token_program);
+
+		self.send_and_confirm_transaction_with_options(
+			&[instruction],
+			&payer_pubkey,
+			&[payer],
+			SendOptions::default(),
+		)
+		.await?;
+
+		Ok(AssociatedTokenAccount {
+			address,
+			created: true,
+		})
+	}
+
+	async fn get_mint_info(&self, mint: &Pubkey) -> ClientResult<MintInfo> {
+		let cached = self
+			.mints
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.get(mint)
+			.copied();
+
+		if let Some(mint_info) = cached {
+			return Ok(mint_info);
+		}
+
+		let account = self.get_account(mint).await?;
+		let state = StateWithExtensions::<Mint>::unpack(&account.data)
+			.map_err(|error| RpcError::new(format!("Invalid mint {mint}: {error}")))?;
+		let mint_info = MintInfo {
+			decimals: state.base.decimals,
+			token_program: account.owner,
+		};
+
+		self.mints
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(*mint, mint_info);
+
+		Ok(mint_info)
+	}
+
+	/// Add the compute budget and durable nonce instructions to the transfer
+	/// `instructions` before signing, sending and confirming the transaction.
+	async fn send_transfer<S: Signer + ?Sized>(
+		&self,
+		from: &S,
+		instructions: Vec<Instruction>,
+		config: TransferConfig,
+	) -> ClientResult<Signature> {
+		let TransferConfig {
+			compute_unit_limit,
+			compute_unit_price,
+			nonce_account,
+			..
+		} = config;
+		let from_pubkey = from.pubkey();
+		let mut prefix = vec![];
+
+		let blockhash = if let Some(nonce_account) = nonce_account {
+			let account = nonce_utils::get_account_with_commitment(
+				self,
+				&nonce_account,
+				self.commitment_config(),
+			)
+			.await?;
+			let data = nonce_utils::data_from_account(&account)?;
+
+			if data.authority != from_pubkey {
+				return Err(NonceError::InvalidAuthority {
+					provided: from_pubkey,
+					expected: data.authority,
+				}
+				.into());
+			}
+
+			// The advance nonce instruction must be the first instruction.
+			prefix.push(advance_nonce_account(&nonce_account, &from_pubkey));
+			data.blockhash()
+		} else {
+			self.get_cached_or_latest_blockhash().await?
+		};
+
+		prefix.extend(compute_unit_limit.map(ComputeBudgetInstruction::set_compute_unit_limit));
+		prefix.extend(compute_unit_price.map(ComputeBudgetInstruction::set_compute_unit_price));
+		prefix.extend(instructions);
+
+		let mut transaction =
+			VersionedTransaction::new_unsigned_v0(&from_pubkey, &prefix, &[], blockhash)?;
+		transaction.try_sign(&[from], None)?;
+
+		self.send_and_confirm_transaction(&transaction).await
+	}
+
 	pub async fn get_health(&self) -> ClientResult<GetHealthResponse> {
 		let response: ClientResponse<GetHealthResponse> = self.send(GetHealthRequest).await?;
 
@@ -1307,12 +2616,16 @@ impl SolanaRpcClient {
 	pub async fn get_token_accounts_by_delegate_with_config(
 		&self,
 		pubkey: Pubkey,
-		filter: RpcTokenAccountsFilter,
+		filter: impl Into<RpcTokenAccountsFilter>,
 		config: RpcAccountInfoConfig,
 	) -> ClientResult<GetTokenAccountsByDelegateResponse> {
+		let config = RpcAccountInfoConfig {
+			min_context_slot: self.context_slot(config.min_context_slot),
+			..config
+		};
 		let request = GetTokenAccountsByDelegateRequest {
 			pubkey,
-			filter,
+			filter: filter.into(),
 			config: Some(config),
 		};
 		let response: ClientResponse<GetTokenAccountsByDelegateResponse> =
@@ -1324,11 +2637,11 @@ impl SolanaRpcClient {
 	pub async fn get_token_accounts_by_delegate(
 		&self,
 		pubkey: Pubkey,
-		filter: RpcTokenAccountsFilter,
+		filter: impl Into<RpcTokenAccountsFilter>,
 	) -> ClientResult<GetTokenAccountsByDelegateResponse> {
 		let request = GetTokenAccountsByDelegateRequest {
 			pubkey,
-			filter,
+			filter: filter.into(),
 			config: None,
 		};
 		let response: ClientResponse<GetTokenAccountsByDelegateResponse> =
@@ -1370,6 +2683,44 @@ impl SolanaRpcClient {
 		Ok(table_type)
 	}
 
+	/// Get the lookup tables at `addresses` in the form used to compile v0
+	/// messages with
+	/// [`VersionedMessageExtension::try_compile_with_lookup_tables`].
+	///
+	/// # Errors
+	///
+	/// Returns an error when any of the accounts doesn't exist or isn't an
+	/// initialized lookup table.
+	pub async fn get_address_lookup_table_accounts(
+		&self,
+		addresses: &[Pubkey],
+	) -> ClientResult<Vec<AddressLookupTableAccount>> {
+		let accounts = self.get_multiple_accounts(addresses).await?;
+
+		addresses
+			.iter()
+			.zip(accounts)
+			.map(|(key, account)| {
+				let account = account.ok_or_else(|| {
+					RpcError::new(format!("Address lookup table {key} not found"))
+				})?;
+				let table_type = parse_address_lookup_table(&account.data)
+					.map_err(|error| RpcError::new(error.to_string()))?;
+				let LookupTableAccountType::LookupTable(table) = table_type else {
+					return Err(RpcError::new(format!(
+						"Address lookup table {key} is uninitialized"
+					))
+					.into());
+				};
+
+				Ok(AddressLookupTableAccount {
+					key: *key,
+					addresses: table.addresses,
+				})
+			})
+			.collect()
+	}
+
 	/// Wait for the new block which is `n` blocks in the future.
 	pub async fn wait_for_new_block(&self, n: u8) -> ClientResult<()> {
 		let (_, last_valid_block_height) = self
@@ -1385,7 +2736,7 @@ impl SolanaRpcClient {
 				break;
 			}
 
-			Delay::new(Duration::from_millis(SLEEP_MS)).await;
+			sleep(Duration::from_millis(SLEEP_MS)).await;
 		}
 
 		Ok(())
@@ -1474,6 +2825,58 @@ impl SolanaRpcClient {
 		Ok(subscription)
 	}
 
+	/// Subscribe to the status of a transaction signature.
+	///
+	/// Receives a single message of type [`SignatureNotificationResponse`]
+	/// when the transaction reaches the commitment of the request, after
+	/// which the subscription is removed by the node.
+	///
+	/// # RPC Reference
+	///
+	/// This method corresponds directly to the [`signatureSubscribe`] RPC
+	/// method.
+	///
+	/// [`signatureSubscribe`]: https://docs.solana.com/api/websocket#signaturesubscribe
+	pub async fn signature_subscribe(
+		&self,
+		request: SignatureSubscribeRequest,
+	) -> ClientResult<Subscription<SignatureNotificationResponse>> {
+		let (id, subscription_id) = self.ws.create_subscription(request).await?;
+		let subscription = Subscription::new(&self.ws, id, subscription_id);
+
+		Ok(subscription)
+	}
+
+	/// Watch the confirmation status of a transaction as it moves from
+	/// [`Processed`](TransactionConfirmationStatus::Processed) to
+	/// [`Confirmed`](TransactionConfirmationStatus::Confirmed) and
+	/// [`Finalized`](TransactionConfirmationStatus::Finalized).
+	///
+	/// Every status is yielded once and in order, even when the transaction
+	/// skips a status between two checks. The stream ends after `Finalized`,
+	/// or with [`ClientError::TransactionFailed`] when the transaction fails
+	/// and [`ClientError::TransactionExpired`] when its blockhash expires
+	/// first.
+	///
+	/// Notifications from `signatureSubscribe` are used while the websocket
+	/// is available and the status is polled with `getSignatureStatuses`
+	/// every [`WatchSignatureConfig::poll_interval`], so the watch continues
+	/// when the socket drops.
+	pub fn watch_signature(
+		&self,
+		signature: &Signature,
+		config: WatchSignatureConfig,
+	) -> impl Stream<Item = ClientResult<TransactionConfirmationStatus>> + use<> {
+		let watcher = SignatureWatcher::new(self.clone(), *signature, config);
+
+		stream::unfold(watcher, |mut watcher| {
+			async move {
+				let item = watcher.next().await?;
+				Some((item, watcher))
+			}
+		})
+	}
+
 	/// Subscribe to program account events.
 	///
 	/// Receives messages of type [`GetProgramAccountsResponse`] when an account
@@ -1494,3 +2897,1022 @@ impl SolanaRpcClient {
 		Ok(subscription)
 	}
 }
+
+/// The static accounts of the `message` which may be locked as writable.
+fn writable_accounts(message: &VersionedMessage) -> Vec<Pubkey> {
+	message
+		.static_account_keys()
+		.iter()
+		.enumerate()
+		.filter(|(index, _)| message.is_maybe_writable(*index, None))
+		.map(|(_, pubkey)| *pubkey)
+		.collect()
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+	use assert2::check;
+	use assert2::let_assert;
+	use futures::executor::block_on;
+	use serde_json::Value;
+	use serde_json::json;
+	use solana_sdk::compute_budget::ComputeBudgetInstruction;
+	use solana_sdk::signature::Keypair;
+	use solana_sdk::signer::Signer;
+	use solana_system_interface::instruction::transfer;
+
+	use super::*;
+	use crate::MockRpcProvider;
+	use crate::PACKET_DATA_SIZE;
+	use crate::PriorityFeeOracle;
+	use crate::PriorityFeeOracleConfig;
+	use crate::RpcErrorCode;
+	use crate::Urgency;
+
+	fn simulation(value: &Value) -> Value {
+		json!({ "context": { "slot": 1 }, "value": value })
+	}
+
+	#[test]
+	fn prepare_transaction_from_simulation() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let blockhash = Hash::new_unique();
+		let transfer = transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getRecentPrioritizationFees",
+				json!([
+					{ "slot": 1, "prioritizationFee": 300 },
+					{ "slot": 2, "prioritizationFee": 100 },
+					{ "slot": 3, "prioritizationFee": 200 },
+				]),
+			)
+			.with_result(
+				"simulateTransaction",
+				simulation(&json!({
+					"err": null,
+					"logs": [],
+					"accounts": null,
+					"unitsConsumed": 1000,
+					"returnData": null,
+				})),
+			)
+			.with_result(
+				"getLatestBlockhash",
+				json!({
+					"context": { "slot": 1 },
+					"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+				}),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let config = PrepareTransactionConfig::builder()
+			.priority_fee_percentile(50)
+			.build();
+		let transaction = block_on(rpc.prepare_transaction(
+			std::slice::from_ref(&transfer),
+			&payer.pubkey(),
+			&[&payer],
+			config,
+		))?;
+		let expected = VersionedMessage::V0(v0::Message::try_compile(
+			&payer.pubkey(),
+			&[
+				ComputeBudgetInstruction::set_compute_unit_limit(1100),
+				ComputeBudgetInstruction::set_compute_unit_price(200),
+				transfer,
+			],
+			&[],
+			blockhash,
+		)?);
+
+		check!(transaction.message.instructions() == expected.instructions());
+		check!(*transaction.message.recent_blockhash() == blockhash);
+		check!(transaction.is_signed());
+
+		let methods = provider
+			.requests()
+			.into_iter()
+			.map(|(method, _)| method)
+			.collect::<Vec<_>>();
+		check!(
+			methods
+				== [
+					"getRecentPrioritizationFees",
+					"simulateTransaction",
+					"getLatestBlockhash"
+				]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn prepare_transaction_surfaces_simulation_logs() {
+		let payer = Keypair::new();
+		let logs = vec![
+			"Program 11111111111111111111111111111111 invoke [1]".to_string(),
+			"Transfer: insufficient lamports 0, need 1".to_string(),
+			"Program 11111111111111111111111111111111 failed: custom program error: 0x1"
+				.to_string(),
+		];
+		let provider = MockRpcProvider::new().with_result(
+			"simulateTransaction",
+			simulation(&json!({
+				"err": { "InstructionError": [1, { "Custom": 1 }] },
+				"logs": logs,
+				"accounts": null,
+				"unitsConsumed": 150,
+				"returnData": null,
+			})),
+		);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+		let error = block_on(rpc.prepare_transaction(
+			&[transfer(&payer.pubkey(), &Pubkey::new_unique(), 1)],
+			&payer.pubkey(),
+			&[&payer],
+			PrepareTransactionConfig::default(),
+		))
+		.unwrap_err();
+
+		let ClientError::Simulation(simulation_error) = &error else {
+			panic!("expected a simulation error: {error}");
+		};
+		check!(simulation_error.logs == logs);
+		check!(error.to_string().contains("insufficient lamports"));
+	}
+
+	#[test]
+	fn resign_and_resend_with_latest_blockhash() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let blockhash = Hash::new_unique();
+		let mut transaction = VersionedTransaction::new_unsigned_v0(
+			&payer.pubkey(),
+			&[transfer(&payer.pubkey(), &Pubkey::new_unique(), 1)],
+			&[],
+			Hash::new_unique(),
+		)?;
+		transaction.try_sign(&[&payer], None)?;
+		let mut expected = transaction.with_new_blockhash(blockhash);
+		expected.try_sign(&[&payer], None)?;
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getLatestBlockhash",
+				json!({
+					"context": { "slot": 1 },
+					"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+				}),
+			)
+			.with_result("sendTransaction", expected.signatures[0].to_string());
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+
+		let signature = block_on(rpc.resign_and_resend(&transaction, &[&payer]))?;
+		check!(signature == expected.signatures[0]);
+		check!(provider.requests().len() == 2);
+
+		Ok(())
+	}
+
+	#[test]
+	fn resign_and_resend_refuses_durable_nonce() {
+		let payer = Keypair::new();
+		let message = Message::new_with_nonce(
+			vec![transfer(&payer.pubkey(), &Pubkey::new_unique(), 1)],
+			Some(&payer.pubkey()),
+			&Pubkey::new_unique(),
+			&payer.pubkey(),
+		);
+		let transaction = VersionedTransaction::new_unsigned(VersionedMessage::Legacy(message));
+		let provider = MockRpcProvider::new();
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+
+		let error = block_on(rpc.resign_and_resend(&transaction, &[&payer])).unwrap_err();
+		check!(matches!(
+			error,
+			ClientError::Nonce(NonceError::DurableNonceTransaction)
+		));
+		check!(provider.requests().is_empty());
+	}
+
+	#[test]
+	fn send_transaction_checks_size() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let mut instruction = transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+		instruction.data = vec![0; PACKET_DATA_SIZE];
+		let mut transaction = VersionedTransaction::new_unsigned_v0(
+			&payer.pubkey(),
+			&[instruction],
+			&[],
+			Hash::new_unique(),
+		)?;
+		transaction.try_sign(&[&payer], None)?;
+		let provider = MockRpcProvider::new()
+			.with_result("sendTransaction", transaction.signatures[0].to_string());
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+
+		let error = block_on(rpc.send_transaction(&transaction)).unwrap_err();
+		let ClientError::TransactionTooLarge(too_large) = error else {
+			panic!("expected the transaction to be too large: {error}");
+		};
+		check!(too_large.size == transaction.serialized_size());
+		check!(too_large.signatures_size + too_large.message_size == too_large.size);
+		check!(provider.requests().is_empty());
+
+		let config = RpcSendTransactionConfig::builder()
+			.skip_preflight_bool(false)
+			.skip_size_check(true)
+			.build();
+		let signature = block_on(rpc.send_transaction_with_config(&transaction, config))?;
+		check!(signature == transaction.signatures[0]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn get_address_lookup_table_accounts() -> anyhow::Result<()> {
+		use std::borrow::Cow;
+
+		use base64::Engine;
+		use base64::prelude::BASE64_STANDARD;
+		use solana_sdk::address_lookup_table::state::AddressLookupTable;
+		use solana_sdk::address_lookup_table::state::LookupTableMeta;
+
+		let key = Pubkey::new_unique();
+		let missing = Pubkey::new_unique();
+		let addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+		let data = AddressLookupTable {
+			meta: LookupTableMeta::default(),
+			addresses: Cow::Borrowed(&addresses),
+		}
+		.serialize_for_tests()?;
+		let account = json!({
+			"lamports": 1_000_000,
+			"data": [BASE64_STANDARD.encode(data), "base64"],
+			"owner": solana_sdk::address_lookup_table::program::id().to_string(),
+			"executable": false,
+			"rentEpoch": 0,
+			"space": 0,
+		});
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getMultipleAccounts",
+				json!({ "context": { "slot": 1 }, "value": [account] }),
+			)
+			.with_result(
+				"getMultipleAccounts",
+				json!({ "context": { "slot": 1 }, "value": [account, null] }),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		let tables = block_on(rpc.get_address_lookup_table_accounts(&[key]))?;
+		check!(tables == [AddressLookupTableAccount { key, addresses }]);
+
+		let error = block_on(rpc.get_address_lookup_table_accounts(&[key, missing])).unwrap_err();
+		check!(error.to_string().contains(&missing.to_string()));
+
+		Ok(())
+	}
+
+	fn confirmed_status() -> Value {
+		json!({
+			"context": { "slot": 1 },
+			"value": [{
+				"slot": 1,
+				"confirmations": null,
+				"err": null,
+				"status": { "Ok": null },
+				"confirmationStatus": "finalized",
+			}],
+		})
+	}
+
+	#[test]
+	fn split_signature_statuses_into_chunks() -> anyhow::Result<()> {
+		let statuses = |count: usize| {
+			json!({
+				"context": { "slot": 1 },
+				"value": (0..count).map(|slot| json!({
+					"slot": slot,
+					"confirmations": null,
+					"err": null,
+					"status": { "Ok": null },
+					"confirmationStatus": "finalized",
+				})).collect::<Vec<_>>(),
+			})
+		};
+		let provider = MockRpcProvider::new()
+			.with_result("getSignatureStatuses", statuses(256))
+			.with_result("getSignatureStatuses", statuses(44));
+		let rpc =
+			SolanaRpcClient::new_with_provider(provider.clone()).with_max_concurrent_requests(1);
+		let signatures = (0..300)
+			.map(|_| Signature::new_unique())
+			.collect::<Vec<_>>();
+
+		let result = block_on(rpc.get_signature_statuses(&signatures))?;
+		check!(result.len() == 300);
+		check!(result[255].as_ref().map(|status| status.slot) == Some(255));
+		check!(result[256].as_ref().map(|status| status.slot) == Some(0));
+
+		let requests = provider.requests();
+		check!(requests.len() == 2);
+		check!(requests[0].1[0].as_array().map(Vec::len) == Some(256));
+		check!(requests[1].1[0].as_array().map(Vec::len) == Some(44));
+		check!(requests[1].1[0][0] == signatures[256].to_string());
+
+		provider.clear_requests();
+		check!(block_on(rpc.get_multiple_accounts(&[]))?.is_empty());
+		check!(provider.requests().is_empty());
+
+		Ok(())
+	}
+
+	#[test]
+	fn read_with_context_and_min_context_slot() -> anyhow::Result<()> {
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getBalance",
+				json!({ "context": { "slot": 120, "apiVersion": "2.1" }, "value": 5 }),
+			)
+			.with_result(
+				"getBalance",
+				json!({ "context": { "slot": 121, "apiVersion": "unknown" }, "value": 6 }),
+			)
+			.with_result(
+				"getMultipleAccounts",
+				json!({ "context": { "slot": 130 }, "value": [null] }),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone()).with_min_context_slot(100);
+		let pubkey = Pubkey::new_unique();
+
+		let balance =
+			block_on(rpc.get_balance_with_context(&pubkey, CommitmentConfig::confirmed()))?;
+		check!(balance.value == 5);
+		check!(balance.context.slot == 120);
+		check!(balance.context.api_version.as_deref() == Some(&semver::Version::new(2, 1, 0)));
+
+		let balance =
+			block_on(rpc.get_balance_with_context(&pubkey, CommitmentConfig::confirmed()))?;
+		check!(balance.context.api_version == None);
+
+		let accounts = block_on(
+			rpc.get_multiple_accounts_with_context(
+				&[pubkey],
+				RpcAccountInfoConfig::builder()
+					.min_context_slot(110u64)
+					.build(),
+			),
+		)?;
+		check!(accounts.value == vec![None]);
+		check!(accounts.context.slot == 130);
+
+		let requests = provider.requests();
+		check!(requests[0].1[1] == json!({ "commitment": "confirmed", "minContextSlot": 100 }));
+		check!(requests[2].1[1]["minContextSlot"] == 110);
+		check!(rpc.min_context_slot() == Some(100));
+
+		Ok(())
+	}
+
+	#[test]
+	fn reject_context_older_than_the_min_context_slot() -> anyhow::Result<()> {
+		let amount =
+			json!({ "amount": "5", "decimals": 0, "uiAmount": 5.0, "uiAmountString": "5" });
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getTokenSupply",
+				json!({ "context": { "slot": 90 }, "value": amount }),
+			)
+			.with_result(
+				"getTokenAccountBalance",
+				json!({ "context": { "slot": 100 }, "value": amount }),
+			)
+			.with_result(
+				"getMultipleAccounts",
+				json!({ "context": { "slot": 140 }, "value": [] }),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone()).with_min_context_slot(100);
+		let pubkey = Pubkey::new_unique();
+
+		let_assert!(
+			Err(error) =
+				block_on(rpc.get_token_supply_with_context(&pubkey, CommitmentConfig::confirmed()))
+		);
+		check!(error.rpc_error_code() == Some(RpcErrorCode::MinContextSlotNotReached));
+
+		let balance = block_on(
+			rpc.get_token_account_balance_with_context(&pubkey, CommitmentConfig::confirmed()),
+		)?;
+		check!(balance.context.slot == 100);
+
+		let accounts =
+			block_on(rpc.get_multiple_accounts_with_context(&[], RpcAccountInfoConfig::default()))?;
+		check!(accounts.value.is_empty());
+		check!(accounts.context.slot == 140);
+		check!(provider.requests()[2].1[0] == json!([]));
+
+		Ok(())
+	}
+
+	#[test]
+	fn context_variants_return_the_response_context() -> anyhow::Result<()> {
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getFeeForMessage",
+				json!({ "context": { "slot": 120 }, "value": null }),
+			)
+			.with_result(
+				"getProgramAccounts",
+				json!({ "context": { "slot": 130 }, "value": [] }),
+			)
+			.with_result(
+				"isBlockhashValid",
+				json!({ "context": { "slot": 110 }, "value": true }),
+			)
+			.with_result(
+				"getLargestAccounts",
+				json!({ "context": { "slot": 90 }, "value": [] }),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone()).with_min_context_slot(100);
+
+		let fee = block_on(
+			rpc.get_fee_for_message_with_context(&Message::default(), RpcContextConfig::default()),
+		)?;
+		check!(fee.value.is_none());
+		check!(fee.context.slot == 120);
+		check!(provider.requests()[0].1[1] == json!({ "minContextSlot": 100 }));
+
+		let accounts = block_on(rpc.get_program_accounts_with_context(
+			&Pubkey::new_unique(),
+			RpcProgramAccountsConfig::default(),
+		))?;
+		check!(accounts.value.is_empty());
+		check!(accounts.context.slot == 130);
+		check!(provider.requests()[1].1[1]["withContext"] == json!(true));
+
+		let valid = block_on(
+			rpc.is_blockhash_valid_with_context(&Hash::default(), CommitmentConfig::confirmed()),
+		)?;
+		check!(valid.value);
+		check!(valid.context.slot == 110);
+
+		let_assert!(
+			Err(error) = block_on(
+				rpc.get_largest_accounts_with_context(RpcLargestAccountsConfig::default())
+			)
+		);
+		check!(error.rpc_error_code() == Some(RpcErrorCode::MinContextSlotNotReached));
+
+		Ok(())
+	}
+
+	#[test]
+	fn client_for_cluster() {
+		let rpc = SolanaRpcClient::new_for_cluster(&Cluster::Localnet);
+		check!(rpc.url() == "http://127.0.0.1:8899");
+		check!(rpc.ws_url() == "ws://127.0.0.1:8900");
+
+		let rpc = SolanaRpcClient::new_for_cluster(&Cluster::custom(
+			"https://mainnet.helius-rpc.com/?api-key=abc",
+		));
+		check!(rpc.ws_url() == "wss://mainnet.helius-rpc.com/?api-key=abc");
+	}
+
+	#[test]
+	fn cached_blockhash_per_commitment() -> anyhow::Result<()> {
+		let finalized = Hash::new_unique();
+		let confirmed = Hash::new_unique();
+		let latest_blockhash = |blockhash: Hash| {
+			json!({
+				"context": { "slot": 1 },
+				"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 150 },
+			})
+		};
+		let provider = MockRpcProvider::new()
+			.with_result("getLatestBlockhash", latest_blockhash(finalized))
+			.with_result("getLatestBlockhash", latest_blockhash(confirmed));
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone())
+			.with_blockhash_cache(BlockhashCacheConfig::default());
+
+		check!(rpc.cached_blockhash() == None);
+
+		block_on(rpc.get_latest_blockhash_with_commitment(CommitmentConfig::finalized()))?;
+		check!(rpc.cached_blockhash() == None);
+
+		check!(block_on(rpc.get_cached_or_latest_blockhash())? == confirmed);
+		check!(block_on(rpc.get_cached_or_latest_blockhash())? == confirmed);
+		check!(rpc.cached_blockhash() == Some((confirmed, 150)));
+		check!(provider.requests().len() == 2);
+
+		if let Some(cache) = rpc.blockhash_cache() {
+			cache.invalidate();
+		}
+
+		check!(rpc.cached_blockhash() == None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn transfer_token_with_cached_mint() -> anyhow::Result<()> {
+		use base64::Engine;
+		use base64::prelude::BASE64_STANDARD;
+		use solana_sdk::program_pack::Pack;
+		use spl_token::state::Mint as TokenMint;
+
+		let payer = Keypair::new();
+		let recipient = Pubkey::new_unique();
+		let mint = Pubkey::new_unique();
+		let blockhash = Hash::new_unique();
+		let mut data = vec![0; TokenMint::LEN];
+		TokenMint {
+			decimals: 6,
+			is_initialized: true,
+			..TokenMint::default()
+		}
+		.pack_into_slice(&mut data);
+		let instructions = [
+			ComputeBudgetInstruction::set_compute_unit_price(5),
+			create_associated_token_account_idempotent(
+				&payer.pubkey(),
+				&recipient,
+				&mint,
+				&spl_token::id(),
+			),
+			spl_token::instruction::transfer_checked(
+				&spl_token::id(),
+				&get_associated_token_address_with_program_id(
+					&payer.pubkey(),
+					&mint,
+					&spl_token::id(),
+				),
+				&mint,
+				&get_associated_token_address_with_program_id(&recipient, &mint, &spl_token::id()),
+				&payer.pubkey(),
+				&[],
+				10,
+				6,
+			)?,
+		];
+		let mut expected =
+			VersionedTransaction::new_unsigned_v0(&payer.pubkey(), &instructions, &[], blockhash)?;
+		expected.try_sign(&[&payer], None)?;
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getAccountInfo",
+				json!({
+					"context": { "slot": 1 },
+					"value": {
+						"lamports": 1_461_600,
+						"data": [BASE64_STANDARD.encode(data), "base64"],
+						"owner": spl_token::id().to_string(),
+						"executable": false,
+						"rentEpoch": 0,
+						"space": TokenMint::LEN,
+					},
+				}),
+			)
+			.with_result(
+				"getLatestBlockhash",
+				json!({
+					"context": { "slot": 1 },
+					"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+				}),
+			)
+			.with_result("sendTransaction", expected.signatures[0].to_string())
+			.with_result("getSignatureStatuses", confirmed_status());
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let config = TransferConfig::builder().compute_unit_price(5).build();
+
+		for _ in 0..2 {
+			let signature = block_on(rpc.transfer_token(&payer, &mint, &recipient, 10, config))?;
+			check!(signature == expected.signatures[0]);
+		}
+
+		let mint_requests = provider
+			.requests()
+			.into_iter()
+			.filter(|(method, _)| method == "getAccountInfo")
+			.count();
+		check!(mint_requests == 1);
+
+		Ok(())
+	}
+
+	#[test]
+	fn get_or_create_associated_token_account_once() -> anyhow::Result<()> {
+		use base64::Engine;
+		use base64::prelude::BASE64_STANDARD;
+		use solana_sdk::program_pack::Pack;
+		use spl_token_2022::state::Mint as TokenMint;
+
+		let payer = Keypair::new();
+		let owner = Pubkey::new_unique();
+		let mint = Pubkey::new_unique();
+		let blockhash = Hash::new_unique();
+		let mut data = vec![0; TokenMint::LEN];
+		TokenMint {
+			decimals: 6,
+			is_initialized: true,
+			..TokenMint::default()
+		}
+		.pack_into_slice(&mut data);
+		let address = derive_associated_token_address(&owner, &mint, &spl_token_2022::id());
+		let instruction = create_associated_token_account_idempotent(
+			&payer.pubkey(),
+			&owner,
+			&mint,
+			&spl_token_2022::id(),
+		);
+		let mut expected =
+			VersionedTransaction::new_unsigned_v0(&payer.pubkey(), &[instruction], &[], blockhash)?;
+		expected.try_sign(&[&payer], None)?;
+		let account = |owner: Pubkey, data: &[u8]| {
+			json!({
+				"context": { "slot": 1 },
+				"value": {
+					"lamports": 2_039_280,
+					"data": [BASE64_STANDARD.encode(data), "base64"],
+					"owner": owner.to_string(),
+					"executable": false,
+					"rentEpoch": 0,
+					"space": data.len(),
+				},
+			})
+		};
+		let provider = MockRpcProvider::new()
+			.with_result("getAccountInfo", account(spl_token_2022::id(), &data))
+			.with_result(
+				"getAccountInfo",
+				json!({ "context": { "slot": 1 }, "value": null }),
+			)
+			.with_result(
+				"getAccountInfo",
+				account(solana_sdk::system_program::id(), &[]),
+			)
+			.with_result("getAccountInfo", account(spl_token_2022::id(), &[0; 165]))
+			.with_result(
+				"getLatestBlockhash",
+				json!({
+					"context": { "slot": 1 },
+					"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+				}),
+			)
+			.with_result("sendTransaction", expected.signatures[0].to_string())
+			.with_result("getSignatureStatuses", confirmed_status());
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+
+		let first = block_on(rpc.get_or_create_associated_token_account(&payer, &owner, &mint))?;
+		check!(
+			first
+				== AssociatedTokenAccount {
+					address,
+					created: true
+				}
+		);
+
+		// Lamports sent to the address before it is created don't make it a
+		// token account.
+		let funded = block_on(rpc.get_or_create_associated_token_account(&payer, &owner, &mint))?;
+		check!(
+			funded
+				== AssociatedTokenAccount {
+					address,
+					created: true
+				}
+		);
+
+		let requests = provider.requests().len();
+		let existing = block_on(rpc.get_or_create_associated_token_account(&payer, &owner, &mint))?;
+		check!(
+			existing
+				== AssociatedTokenAccount {
+					address,
+					created: false
+				}
+		);
+
+		let methods: Vec<String> = provider.requests()[requests..]
+			.iter()
+			.map(|(method, _)| method.clone())
+			.collect();
+		check!(methods == ["getAccountInfo"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn transfer_sol_refuses_foreign_nonce_authority() {
+		use solana_sdk::nonce::state::Data;
+		use solana_sdk::nonce::state::DurableNonce;
+		use solana_sdk::nonce::state::State;
+		use solana_sdk::nonce::state::Versions;
+
+		let payer = Keypair::new();
+		let authority = Pubkey::new_unique();
+		let nonce_account = Pubkey::new_unique();
+		let data = Data::new(
+			authority,
+			DurableNonce::from_blockhash(&Hash::new_unique()),
+			5000,
+		);
+		let state = Versions::new(State::Initialized(data));
+		let provider = MockRpcProvider::new().with_result(
+			"getAccountInfo",
+			json!({
+				"context": { "slot": 1 },
+				"value": {
+					"lamports": 1_447_680,
+					"data": [bs58::encode(bincode::serialize(&state).unwrap()).into_string(), "base58"],
+					"owner": solana_system_interface::program::id().to_string(),
+					"executable": false,
+					"rentEpoch": 0,
+					"space": 80,
+				},
+			}),
+		);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let config = TransferConfig::builder()
+			.nonce_account(nonce_account)
+			.build();
+
+		let error =
+			block_on(rpc.transfer_sol(&payer, &Pubkey::new_unique(), 1, config)).unwrap_err();
+		check!(matches!(
+			error,
+			ClientError::Nonce(NonceError::InvalidAuthority { expected, .. }) if expected == authority
+		));
+		check!(provider.requests().len() == 1);
+	}
+
+	#[test]
+	fn send_with_memo_and_priority_fee() -> anyhow::Result<()> {
+		let payer = Keypair::new();
+		let blockhash = Hash::new_unique();
+		let transfer = transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+		let mut expected = VersionedTransaction::new_unsigned_v0(
+			&payer.pubkey(),
+			&[transfer.clone(), spl_memo::build_memo(b"invoice-42", &[])],
+			&[],
+			blockhash,
+		)?;
+		expected
+			.with_compute_unit_limit(500)?
+			.with_compute_unit_price(10)?
+			.try_sign(&[&payer], None)?;
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getLatestBlockhash",
+				json!({
+					"context": { "slot": 1 },
+					"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+				}),
+			)
+			.with_result("sendTransaction", expected.signatures[0].to_string())
+			.with_result("getSignatureStatuses", confirmed_status());
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let options = SendOptions::builder()
+			.memo("invoice-42")
+			.compute_unit_limit(500)
+			.compute_unit_price(10)
+			.skip_preflight(true)
+			.build();
+
+		let signature = block_on(rpc.send_and_confirm_transaction_with_options(
+			&[transfer],
+			&payer.pubkey(),
+			&[&payer],
+			options,
+		))?;
+		check!(signature == expected.signatures[0]);
+
+		let (_, params) = provider
+			.requests()
+			.into_iter()
+			.find(|(method, _)| method == "sendTransaction")
+			.unwrap();
+		check!(params[1]["skipPreflight"] == true);
+
+		Ok(())
+	}
+
+	#[test]
+	fn send_with_priority_fee_oracle() -> anyhow::Result<()> {
+		use base64::Engine;
+		use base64::prelude::BASE64_STANDARD;
+
+		let payer = Keypair::new();
+		let blockhash = Hash::new_unique();
+		let recipient = Pubkey::new_unique();
+		let transfer = transfer(&payer.pubkey(), &recipient, 1);
+		let mut expected = VersionedTransaction::new_unsigned_v0(
+			&payer.pubkey(),
+			std::slice::from_ref(&transfer),
+			&[],
+			blockhash,
+		)?;
+		expected
+			.with_compute_unit_price(2000)?
+			.try_sign(&[&payer], None)?;
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getLatestBlockhash",
+				json!({
+					"context": { "slot": 1 },
+					"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+				}),
+			)
+			.with_result("sendTransaction", expected.signatures[0].to_string())
+			.with_result("getSignatureStatuses", confirmed_status());
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let oracle = PriorityFeeOracle::new(&rpc, PriorityFeeOracleConfig::default());
+		let fees = [(1, 1000), (2, 2000), (3, 3000)].map(|(slot, prioritization_fee)| {
+			RpcPrioritizationFee {
+				slot,
+				prioritization_fee,
+			}
+		});
+		oracle.record(&[], &fees[..1]);
+		oracle.record(&[recipient], &fees[..1]);
+		oracle.record(&[payer.pubkey()], &fees);
+		let options = SendOptions::builder()
+			.compute_unit_price((oracle, Urgency::High))
+			.build();
+
+		let signature = block_on(rpc.send_and_confirm_transaction_with_options(
+			&[transfer],
+			&payer.pubkey(),
+			&[&payer],
+			options,
+		))?;
+		check!(signature == expected.signatures[0]);
+
+		let requests = provider.requests();
+		let (_, params) = requests
+			.iter()
+			.find(|(method, _)| method == "sendTransaction")
+			.unwrap();
+		let sent: VersionedTransaction =
+			bincode::deserialize(&BASE64_STANDARD.decode(params[0].as_str().unwrap())?)?;
+		check!(sent == expected);
+		check!(
+			requests
+				.iter()
+				.all(|(method, _)| method != "getRecentPrioritizationFees")
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn get_transactions_in_order() -> anyhow::Result<()> {
+		use base64::Engine;
+		use base64::prelude::BASE64_STANDARD;
+
+		let payer = Keypair::new();
+		let transactions = [1, 2].map(|lamports| {
+			let transfer = transfer(&payer.pubkey(), &Pubkey::new_unique(), lamports);
+			let message =
+				v0::Message::try_compile(&payer.pubkey(), &[transfer], &[], Hash::new_unique())
+					.unwrap();
+
+			VersionedTransaction::try_new(VersionedMessage::V0(message), &[&payer]).unwrap()
+		});
+		let confirmed = |slot: u64, transaction: &VersionedTransaction| {
+			json!({
+				"slot": slot,
+				"blockTime": null,
+				"version": 0,
+				"transaction": [
+					BASE64_STANDARD.encode(bincode::serialize(transaction).unwrap()),
+					"base64",
+				],
+				"meta": null,
+			})
+		};
+		let provider = MockRpcProvider::new()
+			.with_result("getTransaction", confirmed(10, &transactions[0]))
+			.with_result("getTransaction", confirmed(11, &transactions[1]));
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let signatures = transactions
+			.each_ref()
+			.map(|transaction| transaction.signatures[0]);
+
+		let fetched = block_on(rpc.get_transactions(&signatures))?;
+		check!(fetched.len() == 2);
+		check!(fetched[0].encoded.slot == 10);
+		check!(fetched[0].transaction == transactions[0]);
+		check!(fetched[1].encoded.slot == 11);
+		check!(fetched[1].transaction == transactions[1]);
+
+		let requests = provider.requests();
+		check!(requests.len() == 2);
+		check!(requests[0].1[0] == signatures[0].to_string());
+		check!(
+			requests[0].1[1]
+				== json!({
+					"encoding": "base64",
+					"commitment": "confirmed",
+					"maxSupportedTransactionVersion": 0,
+				})
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn versioned_transaction_uses_the_client_commitment() {
+		let signature = Signature::new_unique();
+		let commitments = [
+			(CommitmentConfig::confirmed(), "confirmed"),
+			(CommitmentConfig::finalized(), "finalized"),
+			(CommitmentConfig::processed(), "confirmed"),
+		];
+
+		for (commitment_config, expected) in commitments {
+			let provider = MockRpcProvider::new()
+				.with_result("getTransaction", Value::Null)
+				.with_result(
+					"getSignatureStatuses",
+					json!({ "context": { "slot": 1 }, "value": [null] }),
+				);
+			let rpc = SolanaRpcClient::new_with_provider_and_commitment(
+				provider.clone(),
+				commitment_config,
+			);
+
+			let _ = block_on(rpc.get_transaction_versioned(&signature));
+			check!(provider.requests()[0].1[1]["commitment"] == expected);
+		}
+	}
+
+	#[test]
+	fn missing_transaction_checks_signature_status() {
+		let signature = Signature::new_unique();
+		let provider = MockRpcProvider::new()
+			.with_result("getTransaction", Value::Null)
+			.with_result(
+				"getSignatureStatuses",
+				json!({
+					"context": { "slot": 1 },
+					"value": [{
+						"slot": 7,
+						"confirmations": 0,
+						"err": null,
+						"status": { "Ok": null },
+						"confirmationStatus": "processed",
+					}],
+				}),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		let result = block_on(rpc.get_transaction_versioned(&signature));
+		check!(
+			let Err(ClientError::TransactionNotConfirmed {
+				slot: 7,
+				confirmation_status: Some(TransactionConfirmationStatus::Processed),
+				..
+			}) = result
+		);
+
+		let provider = MockRpcProvider::new()
+			.with_result("getTransaction", Value::Null)
+			.with_result(
+				"getSignatureStatuses",
+				json!({ "context": { "slot": 1 }, "value": [null] }),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		let result = block_on(rpc.get_transaction_versioned(&signature));
+		let_assert!(Err(ClientError::TransactionNotFound { signature: missing }) = result);
+		check!(missing == signature);
+	}
+
+	#[tokio::test]
+	async fn blockhash_refresh_stops_when_the_client_is_dropped() {
+		tokio::task::LocalSet::new()
+			.run_until(async {
+				let provider = MockRpcProvider::new().with_result(
+					"getLatestBlockhash",
+					json!({
+						"context": { "slot": 1 },
+						"value": { "blockhash": Hash::new_unique().to_string(), "lastValidBlockHeight": 100 },
+					}),
+				);
+				let rpc = SolanaRpcClient::new_with_provider(provider.clone())
+					.with_blockhash_cache(BlockhashCacheConfig::default());
+				let clone = rpc.clone();
+				let handle = rpc.spawn_blockhash_refresh().unwrap();
+				tokio::task::yield_now().await;
+
+				check!(provider.requests().len() == 1);
+				check!(rpc.cached_blockhash().is_some());
+
+				drop(rpc);
+				check!(!handle.is_aborted());
+
+				drop(clone);
+				check!(handle.is_aborted());
+			})
+			.await;
+	}
+}
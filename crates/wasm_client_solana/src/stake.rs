@@ -0,0 +1,728 @@
+//! Stake account helpers.
+//!
+//! Each helper checks the stake account against the current cluster state
+//! and returns an unsigned `v0` transaction paid for by the authority. Sign
+//! it with [`crate::VersionedTransactionExtension::try_sign`] and send it
+//! with [`SolanaRpcClient::send_and_confirm_transaction`].
+
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::account::Account;
+use solana_sdk::clock::Clock;
+use solana_sdk::clock::Epoch;
+use solana_sdk::clock::UnixTimestamp;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::stake_history::StakeHistory;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_stake_interface::instruction;
+use solana_stake_interface::state::Authorized;
+use solana_stake_interface::state::Lockup;
+use solana_stake_interface::state::StakeStateV2;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::SolanaRpcClient;
+use crate::VersionedTransactionExtension;
+
+/// The epoch from which the stake program warms up and cools down stake at
+/// the reduced rate. The rate is active from genesis on every cluster, so
+/// this is passed to [`Delegation::stake`] to find the effective stake.
+///
+/// [`Delegation::stake`]: solana_stake_interface::state::Delegation::stake
+pub const NEW_WARMUP_COOLDOWN_RATE_EPOCH: Option<Epoch> = Some(0);
+
+#[derive(Clone, Debug, Serialize, Deserialize, thiserror::Error, PartialEq, Eq)]
+pub enum StakeError {
+	#[error("invalid account owner")]
+	InvalidAccountOwner,
+	#[error("invalid account data")]
+	InvalidAccountData,
+	#[error("the stake account is not initialized")]
+	Uninitialized,
+	#[error("the stake account is not delegated")]
+	NotDelegated,
+	#[error("the stake account was deactivated in epoch {epoch}")]
+	AlreadyDeactivated { epoch: Epoch },
+	#[error("provided authority ({provided}) does not match stake authority ({expected})")]
+	InvalidAuthority { provided: Pubkey, expected: Pubkey },
+	#[error("{lamports} lamports is below the minimum of {minimum} lamports")]
+	BelowMinimum { lamports: u64, minimum: u64 },
+	#[error("{lamports} lamports is more than the {available} lamports available")]
+	InsufficientFunds { lamports: u64, available: u64 },
+	#[error(
+		"the lockup is in force until epoch {epoch} and unix timestamp {unix_timestamp} unless \
+		 signed by the custodian ({custodian})"
+	)]
+	LockupInForce {
+		unix_timestamp: UnixTimestamp,
+		epoch: Epoch,
+		custodian: Pubkey,
+	},
+}
+
+/// Deserialize the state of a stake account.
+///
+/// # Errors
+///
+/// Returns [`StakeError::InvalidAccountOwner`] if the account is not owned by
+/// the stake program and [`StakeError::InvalidAccountData`] if the data
+/// fails to deserialize.
+pub fn state_from_account(account: &Account) -> Result<StakeStateV2, StakeError> {
+	if account.owner != solana_stake_interface::program::id() {
+		return Err(StakeError::InvalidAccountOwner);
+	}
+
+	bincode::deserialize(&account.data).map_err(|_| StakeError::InvalidAccountData)
+}
+
+/// Check that `lamports` can be withdrawn from a stake account holding
+/// `balance` lamports of which `locked` are delegated.
+///
+/// The whole balance of an undelegated account can be withdrawn, closing
+/// it. Otherwise the rent exempt `reserve` must be left behind.
+fn check_withdrawal(
+	lamports: u64,
+	balance: u64,
+	locked: u64,
+	reserve: u64,
+) -> Result<(), StakeError> {
+	if locked == 0 && lamports == balance {
+		return Ok(());
+	}
+
+	let available = balance.saturating_sub(locked.saturating_add(reserve));
+
+	if lamports > available {
+		return Err(StakeError::InsufficientFunds {
+			lamports,
+			available,
+		});
+	}
+
+	Ok(())
+}
+
+/// Check that both the split and the remaining balance of a stake account
+/// hold at least `minimum` lamports. The whole balance may be split off.
+fn check_split(lamports: u64, balance: u64, minimum: u64) -> Result<(), StakeError> {
+	if lamports > balance {
+		return Err(StakeError::InsufficientFunds {
+			lamports,
+			available: balance,
+		});
+	}
+
+	if lamports < minimum {
+		return Err(StakeError::BelowMinimum { lamports, minimum });
+	}
+
+	let remaining = balance - lamports;
+
+	if remaining > 0 && remaining < minimum {
+		return Err(StakeError::BelowMinimum {
+			lamports: remaining,
+			minimum,
+		});
+	}
+
+	Ok(())
+}
+
+fn check_authority(provided: &Pubkey, expected: &Pubkey) -> Result<(), StakeError> {
+	if provided == expected {
+		Ok(())
+	} else {
+		Err(StakeError::InvalidAuthority {
+			provided: *provided,
+			expected: *expected,
+		})
+	}
+}
+
+impl SolanaRpcClient {
+	/// Deserialize the state of a stake account.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the account is not owned by the stake program or
+	/// its data fails to deserialize.
+	pub async fn get_stake_account_state(
+		&self,
+		stake_account: &Pubkey,
+	) -> ClientResult<StakeStateV2> {
+		let account = self.get_account(stake_account).await?;
+		let state = state_from_account(&account)?;
+
+		Ok(state)
+	}
+
+	/// Create the `stake_account` funded by the `authority`, which becomes its
+	/// staker and withdrawer, and delegate `lamports` to the `vote_account`.
+	///
+	/// The account is funded with its rent exempt reserve on top of the
+	/// delegated `lamports`. Both the `authority` and the `stake_account`
+	/// must sign the transaction.
+	///
+	/// # Errors
+	///
+	/// Returns [`StakeError::BelowMinimum`] when `lamports` is below
+	/// [`SolanaRpcClient::get_stake_minimum_delegation`].
+	pub async fn create_and_delegate_stake(
+		&self,
+		authority: &Pubkey,
+		stake_account: &Pubkey,
+		vote_account: &Pubkey,
+		lamports: u64,
+	) -> ClientResult<VersionedTransaction> {
+		let minimum = self.get_stake_minimum_delegation().await?;
+
+		if lamports < minimum {
+			return Err(StakeError::BelowMinimum { lamports, minimum }.into());
+		}
+
+		let reserve = self
+			.get_minimum_balance_for_rent_exemption(StakeStateV2::size_of())
+			.await?;
+		let instructions = instruction::create_account_and_delegate_stake(
+			authority,
+			stake_account,
+			vote_account,
+			&Authorized::auto(authority),
+			&Lockup::default(),
+			reserve.saturating_add(lamports),
+		);
+
+		self.stake_transaction(authority, &instructions).await
+	}
+
+	/// Deactivate the delegated `stake_account`. The stake cools down over
+	/// the following epochs after which it can be withdrawn.
+	///
+	/// # Errors
+	///
+	/// Returns a [`StakeError`] when the account isn't delegated, is already
+	/// deactivated or the `authority` isn't its staker.
+	pub async fn deactivate_stake(
+		&self,
+		stake_account: &Pubkey,
+		authority: &Pubkey,
+	) -> ClientResult<VersionedTransaction> {
+		let StakeStateV2::Stake(meta, stake, _) =
+			self.get_stake_account_state(stake_account).await?
+		else {
+			return Err(StakeError::NotDelegated.into());
+		};
+
+		check_authority(authority, &meta.authorized.staker)?;
+
+		if stake.delegation.deactivation_epoch != Epoch::MAX {
+			return Err(StakeError::AlreadyDeactivated {
+				epoch: stake.delegation.deactivation_epoch,
+			}
+			.into());
+		}
+
+		let instructions = [instruction::deactivate_stake(stake_account, authority)];
+
+		self.stake_transaction(authority, &instructions).await
+	}
+
+	/// Withdraw `lamports` from the `stake_account` to the `recipient`.
+	///
+	/// The lockup of the account is checked against the current clock and
+	/// must have expired unless the `custodian` of the lockup is provided, in
+	/// which case it must also sign the transaction. Stake which hasn't been
+	/// deactivated can't be withdrawn. Once it is deactivated the stake which
+	/// is still effective is read from the stake history, the same way the
+	/// stake program does, so the part which has cooled down can be withdrawn.
+	///
+	/// # Errors
+	///
+	/// Returns a [`StakeError`] when the `withdrawer` isn't the withdraw
+	/// authority, the lockup is in force or the account doesn't hold enough
+	/// withdrawable lamports.
+	pub async fn withdraw_stake(
+		&self,
+		stake_account: &Pubkey,
+		withdrawer: &Pubkey,
+		recipient: &Pubkey,
+		lamports: u64,
+		custodian: Option<&Pubkey>,
+	) -> ClientResult<VersionedTransaction> {
+		let account = self.get_account(stake_account).await?;
+		let state = state_from_account(&account)?;
+		let meta = state.meta().ok_or(StakeError::Uninitialized)?;
+
+		check_authority(withdrawer, &meta.authorized.withdrawer)?;
+
+		let clock = self.get_clock().await?;
+
+		if meta.lockup.is_in_force(&clock, custodian) {
+			return Err(StakeError::LockupInForce {
+				unix_timestamp: meta.lockup.unix_timestamp,
+				epoch: meta.lockup.epoch,
+				custodian: meta.lockup.custodian,
+			}
+			.into());
+		}
+
+		let locked = match state.delegation() {
+			Some(delegation) if clock.epoch >= delegation.deactivation_epoch => {
+				let history = self.get_stake_history().await?;
+
+				delegation.stake(clock.epoch, &history, NEW_WARMUP_COOLDOWN_RATE_EPOCH)
+			}
+			Some(delegation) => delegation.stake,
+			None => 0,
+		};
+
+		check_withdrawal(lamports, account.lamports, locked, meta.rent_exempt_reserve)?;
+
+		let instructions = [instruction::withdraw(
+			stake_account,
+			withdrawer,
+			recipient,
+			lamports,
+			custodian,
+		)];
+
+		self.stake_transaction(withdrawer, &instructions).await
+	}
+
+	/// Split `lamports` from the `stake_account` into the new
+	/// `split_stake_account`, which must sign the transaction along with the
+	/// `authority`.
+	///
+	/// The split and the remaining stake must each cover the rent exempt
+	/// reserve of a stake account and, when the stake is delegated, the
+	/// [`SolanaRpcClient::get_stake_minimum_delegation`].
+	///
+	/// # Errors
+	///
+	/// Returns a [`StakeError`] when the `authority` isn't the staker or
+	/// either side of the split is below the minimum.
+	pub async fn split_stake(
+		&self,
+		stake_account: &Pubkey,
+		authority: &Pubkey,
+		split_stake_account: &Pubkey,
+		lamports: u64,
+	) -> ClientResult<VersionedTransaction> {
+		let account = self.get_account(stake_account).await?;
+		let state = state_from_account(&account)?;
+		let meta = state.meta().ok_or(StakeError::Uninitialized)?;
+
+		check_authority(authority, &meta.authorized.staker)?;
+
+		let reserve = self
+			.get_minimum_balance_for_rent_exemption(StakeStateV2::size_of())
+			.await?;
+		let minimum = if state.delegation().is_some() {
+			reserve.saturating_add(self.get_stake_minimum_delegation().await?)
+		} else {
+			reserve
+		};
+
+		check_split(lamports, account.lamports, minimum)?;
+
+		let instructions =
+			instruction::split(stake_account, authority, lamports, split_stake_account);
+
+		self.stake_transaction(authority, &instructions).await
+	}
+
+	async fn get_clock(&self) -> ClientResult<Clock> {
+		let account = self.get_account(&sysvar::clock::id()).await?;
+
+		bincode::deserialize(&account.data).map_err(|error| ClientError::Other(error.to_string()))
+	}
+
+	async fn get_stake_history(&self) -> ClientResult<StakeHistory> {
+		let account = self.get_account(&sysvar::stake_history::id()).await?;
+
+		bincode::deserialize(&account.data).map_err(|error| ClientError::Other(error.to_string()))
+	}
+
+	async fn stake_transaction(
+		&self,
+		payer: &Pubkey,
+		instructions: &[Instruction],
+	) -> ClientResult<VersionedTransaction> {
+		let blockhash = self.get_cached_or_latest_blockhash().await?;
+		let transaction =
+			VersionedTransaction::new_unsigned_v0(payer, instructions, &[], blockhash)?;
+
+		Ok(transaction)
+	}
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+	use assert2::check;
+	use assert2::let_assert;
+	use base64::Engine;
+	use base64::prelude::BASE64_STANDARD;
+	use futures::executor::block_on;
+	use serde_json::Value;
+	use serde_json::json;
+	use solana_sdk::hash::Hash;
+	use solana_sdk::stake_history::StakeHistoryEntry;
+	use solana_stake_interface::stake_flags::StakeFlags;
+	use solana_stake_interface::state::Delegation;
+	use solana_stake_interface::state::Meta;
+	use solana_stake_interface::state::Stake;
+
+	use super::*;
+	use crate::MockRpcProvider;
+
+	const RESERVE: u64 = 2_282_880;
+
+	fn account_info(owner: &Pubkey, lamports: u64, data: &[u8]) -> Value {
+		json!({
+			"context": { "slot": 1 },
+			"value": {
+				"lamports": lamports,
+				"data": [BASE64_STANDARD.encode(data), "base64"],
+				"owner": owner.to_string(),
+				"executable": false,
+				"rentEpoch": 0,
+				"space": data.len(),
+			},
+		})
+	}
+
+	fn stake_account(state: &StakeStateV2, lamports: u64) -> Value {
+		let mut data = bincode::serialize(state).unwrap();
+		data.resize(StakeStateV2::size_of(), 0);
+
+		account_info(&solana_stake_interface::program::id(), lamports, &data)
+	}
+
+	fn clock_account(epoch: Epoch) -> Value {
+		let clock = Clock {
+			slot: 100,
+			epoch,
+			unix_timestamp: 1_700_000_000,
+			..Clock::default()
+		};
+
+		account_info(&sysvar::id(), 1, &bincode::serialize(&clock).unwrap())
+	}
+
+	fn stake_history_account(entries: &[(Epoch, StakeHistoryEntry)]) -> Value {
+		let mut history = StakeHistory::default();
+
+		for (epoch, entry) in entries {
+			history.add(*epoch, entry.clone());
+		}
+
+		account_info(&sysvar::id(), 1, &bincode::serialize(&history).unwrap())
+	}
+
+	fn meta(authority: &Pubkey, lockup: Lockup) -> Meta {
+		Meta {
+			rent_exempt_reserve: RESERVE,
+			authorized: Authorized::auto(authority),
+			lockup,
+		}
+	}
+
+	fn delegated(authority: &Pubkey, stake: u64, deactivation_epoch: Epoch) -> StakeStateV2 {
+		let delegation = Delegation {
+			voter_pubkey: Pubkey::new_unique(),
+			stake,
+			activation_epoch: 0,
+			deactivation_epoch,
+			..Delegation::default()
+		};
+		let stake = Stake {
+			delegation,
+			credits_observed: 0,
+		};
+
+		StakeStateV2::Stake(
+			meta(authority, Lockup::default()),
+			stake,
+			StakeFlags::empty(),
+		)
+	}
+
+	fn latest_blockhash(blockhash: Hash) -> Value {
+		json!({
+			"context": { "slot": 1 },
+			"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+		})
+	}
+
+	#[test]
+	fn create_and_delegate_funds_the_reserve() -> anyhow::Result<()> {
+		let authority = Pubkey::new_unique();
+		let stake_account = Pubkey::new_unique();
+		let vote_account = Pubkey::new_unique();
+		let blockhash = Hash::new_unique();
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getStakeMinimumDelegation",
+				json!({ "context": { "slot": 1 }, "value": 1_000_000_000 }),
+			)
+			.with_result("getMinimumBalanceForRentExemption", RESERVE)
+			.with_result("getLatestBlockhash", latest_blockhash(blockhash));
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		let transaction = block_on(rpc.create_and_delegate_stake(
+			&authority,
+			&stake_account,
+			&vote_account,
+			1_000_000_000,
+		))?;
+		let instructions = instruction::create_account_and_delegate_stake(
+			&authority,
+			&stake_account,
+			&vote_account,
+			&Authorized::auto(&authority),
+			&Lockup::default(),
+			RESERVE + 1_000_000_000,
+		);
+		let expected =
+			VersionedTransaction::new_unsigned_v0(&authority, &instructions, &[], blockhash)?;
+
+		check!(transaction == expected);
+		check!(transaction.message.header().num_required_signatures == 2);
+
+		let_assert!(
+			Err(ClientError::Stake(StakeError::BelowMinimum { minimum, .. })) = block_on(
+				rpc.create_and_delegate_stake(&authority, &stake_account, &vote_account, 1)
+			)
+		);
+		check!(minimum == 1_000_000_000);
+
+		Ok(())
+	}
+
+	#[test]
+	fn deactivate_checks_the_staker() {
+		let authority = Pubkey::new_unique();
+		let other = Pubkey::new_unique();
+		let provider = MockRpcProvider::new().with_result(
+			"getAccountInfo",
+			stake_account(&delegated(&authority, 5_000_000_000, Epoch::MAX), 0),
+		);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+		let stake = Pubkey::new_unique();
+
+		let_assert!(
+			Err(ClientError::Stake(StakeError::InvalidAuthority {
+				expected,
+				..
+			})) = block_on(rpc.deactivate_stake(&stake, &other))
+		);
+		check!(expected == authority);
+
+		let provider = MockRpcProvider::new().with_result(
+			"getAccountInfo",
+			stake_account(&delegated(&authority, 5_000_000_000, 4), 0),
+		);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		check!(
+			let Err(ClientError::Stake(StakeError::AlreadyDeactivated { epoch: 4 })) =
+				block_on(rpc.deactivate_stake(&stake, &authority))
+		);
+	}
+
+	#[test]
+	fn withdraw_respects_the_lockup() -> anyhow::Result<()> {
+		let authority = Pubkey::new_unique();
+		let custodian = Pubkey::new_unique();
+		let recipient = Pubkey::new_unique();
+		let stake_pubkey = Pubkey::new_unique();
+		let blockhash = Hash::new_unique();
+		let lockup = Lockup {
+			unix_timestamp: 0,
+			epoch: 10,
+			custodian,
+		};
+		let state = StakeStateV2::Initialized(meta(&authority, lockup));
+		let provider = MockRpcProvider::new()
+			.with_result("getAccountInfo", stake_account(&state, 3_000_000_000))
+			.with_result("getAccountInfo", clock_account(5));
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		let_assert!(
+			Err(ClientError::Stake(StakeError::LockupInForce {
+				epoch: 10,
+				..
+			})) = block_on(rpc.withdraw_stake(&stake_pubkey, &authority, &recipient, 1_000, None))
+		);
+
+		let provider = MockRpcProvider::new()
+			.with_result("getAccountInfo", stake_account(&state, 3_000_000_000))
+			.with_result("getAccountInfo", clock_account(5))
+			.with_result("getLatestBlockhash", latest_blockhash(blockhash));
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+		let transaction = block_on(rpc.withdraw_stake(
+			&stake_pubkey,
+			&authority,
+			&recipient,
+			3_000_000_000,
+			Some(&custodian),
+		))?;
+		let instructions = [instruction::withdraw(
+			&stake_pubkey,
+			&authority,
+			&recipient,
+			3_000_000_000,
+			Some(&custodian),
+		)];
+
+		check!(
+			transaction
+				== VersionedTransaction::new_unsigned_v0(
+					&authority,
+					&instructions,
+					&[],
+					blockhash
+				)?
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn withdraw_leaves_active_stake() {
+		let authority = Pubkey::new_unique();
+		let state = delegated(&authority, 5_000_000_000, 4);
+		let provider = MockRpcProvider::new()
+			.with_result("getAccountInfo", stake_account(&state, 6_000_000_000))
+			.with_result("getAccountInfo", clock_account(4))
+			.with_result("getAccountInfo", stake_history_account(&[]));
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		let_assert!(
+			Err(ClientError::Stake(StakeError::InsufficientFunds {
+				available,
+				..
+			})) = block_on(rpc.withdraw_stake(
+				&Pubkey::new_unique(),
+				&authority,
+				&Pubkey::new_unique(),
+				2_000_000_000,
+				None,
+			))
+		);
+		check!(available == 1_000_000_000 - RESERVE);
+	}
+
+	#[test]
+	fn withdraw_leaves_stake_which_is_cooling_down() {
+		let authority = Pubkey::new_unique();
+		let state = delegated(&authority, 5_000_000_000, 4);
+		// The whole cluster stake is deactivated in epoch 4, so only 9% of it
+		// cools down by epoch 5 and 4.55 SOL of this account is still
+		// effective.
+		let history = [(
+			4,
+			StakeHistoryEntry {
+				effective: 10_000_000_000,
+				activating: 0,
+				deactivating: 10_000_000_000,
+			},
+		)];
+		let provider = MockRpcProvider::new()
+			.with_result("getAccountInfo", stake_account(&state, 6_000_000_000))
+			.with_result("getAccountInfo", clock_account(5))
+			.with_result("getAccountInfo", stake_history_account(&history));
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		let_assert!(
+			Err(ClientError::Stake(StakeError::InsufficientFunds {
+				available,
+				..
+			})) = block_on(rpc.withdraw_stake(
+				&Pubkey::new_unique(),
+				&authority,
+				&Pubkey::new_unique(),
+				2_000_000_000,
+				None,
+			))
+		);
+		check!(available == 1_450_000_000 - RESERVE);
+	}
+
+	#[test]
+	fn withdrawals() {
+		check!(check_withdrawal(10, 10, 0, 3).is_ok());
+		check!(check_withdrawal(7, 10, 0, 3).is_ok());
+		check!(
+			check_withdrawal(8, 10, 0, 3)
+				== Err(StakeError::InsufficientFunds {
+					lamports: 8,
+					available: 7,
+				})
+		);
+		check!(
+			check_withdrawal(10, 10, 5, 3)
+				== Err(StakeError::InsufficientFunds {
+					lamports: 10,
+					available: 2,
+				})
+		);
+	}
+
+	#[test]
+	fn splits() {
+		check!(check_split(10, 20, 10).is_ok());
+		check!(check_split(20, 20, 10).is_ok());
+		check!(
+			check_split(9, 20, 10)
+				== Err(StakeError::BelowMinimum {
+					lamports: 9,
+					minimum: 10,
+				})
+		);
+		check!(
+			check_split(11, 20, 10)
+				== Err(StakeError::BelowMinimum {
+					lamports: 9,
+					minimum: 10,
+				})
+		);
+		check!(
+			check_split(21, 20, 10)
+				== Err(StakeError::InsufficientFunds {
+					lamports: 21,
+					available: 20,
+				})
+		);
+	}
+
+	#[test]
+	fn split_adds_the_minimum_delegation() {
+		let authority = Pubkey::new_unique();
+		let state = delegated(&authority, 5_000_000_000, Epoch::MAX);
+		let provider = MockRpcProvider::new()
+			.with_result(
+				"getAccountInfo",
+				stake_account(&state, 5_000_000_000 + RESERVE),
+			)
+			.with_result("getMinimumBalanceForRentExemption", RESERVE)
+			.with_result(
+				"getStakeMinimumDelegation",
+				json!({ "context": { "slot": 1 }, "value": 1_000_000_000 }),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider);
+
+		let_assert!(
+			Err(ClientError::Stake(StakeError::BelowMinimum { minimum, .. })) =
+				block_on(rpc.split_stake(
+					&Pubkey::new_unique(),
+					&authority,
+					&Pubkey::new_unique(),
+					1_000_000_000,
+				))
+		);
+		check!(minimum == 1_000_000_000 + RESERVE);
+	}
+}
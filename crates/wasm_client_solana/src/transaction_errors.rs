@@ -0,0 +1,412 @@
+//! Readable descriptions and retry classification for a
+//! [`TransactionError`].
+
+use std::collections::HashMap;
+use std::fmt;
+
+use num_traits::FromPrimitive;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
+use solana_system_interface::error::SystemError;
+use spl_associated_token_account::error::AssociatedTokenAccountError;
+
+/// Decode the `Custom(code)` instruction error of a program into a message.
+pub type CustomErrorDecoder = fn(u32) -> Option<String>;
+
+/// A lookup of [`CustomErrorDecoder`]s keyed by program id.
+///
+/// The default registry decodes the errors of the system, spl-token,
+/// token-2022 and associated token account programs. Register a decoder for
+/// your own programs to get readable errors for them as well.
+///
+/// ```
+/// use solana_sdk::instruction::InstructionError;
+/// use solana_sdk::pubkey::Pubkey;
+/// use solana_sdk::transaction::TransactionError;
+/// use wasm_client_solana::transaction_errors::ProgramErrorRegistry;
+///
+/// // The `#[error_code]` enum of an anchor program starts at 6000.
+/// fn decode_counter_error(code: u32) -> Option<String> {
+/// 	match code {
+/// 		6000 => Some("The counter overflowed".into()),
+/// 		_ => None,
+/// 	}
+/// }
+///
+/// let program_id = Pubkey::new_unique();
+/// let registry = ProgramErrorRegistry::default().with_decoder(program_id, decode_counter_error);
+///
+/// assert_eq!(
+/// 	registry.decode(&program_id, 6000).as_deref(),
+/// 	Some("The counter overflowed")
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProgramErrorRegistry {
+	decoders: HashMap<Pubkey, CustomErrorDecoder>,
+}
+
+impl Default for ProgramErrorRegistry {
+	fn default() -> Self {
+		let decoders: [(Pubkey, CustomErrorDecoder); 4] = [
+			(solana_system_interface::program::id(), decode_system_error),
+			(spl_token::id(), decode_token_error),
+			(spl_token_2022::id(), decode_token_2022_error),
+			(
+				spl_associated_token_account::id(),
+				decode_associated_token_account_error,
+			),
+		];
+
+		Self {
+			decoders: decoders.into_iter().collect(),
+		}
+	}
+}
+
+impl ProgramErrorRegistry {
+	/// A registry without any decoders, not even the builtin ones.
+	pub fn empty() -> Self {
+		Self {
+			decoders: HashMap::new(),
+		}
+	}
+
+	/// Register the `decoder` for the custom errors of `program_id`, replacing
+	/// any existing decoder for the program.
+	pub fn insert(&mut self, program_id: Pubkey, decoder: CustomErrorDecoder) -> &mut Self {
+		self.decoders.insert(program_id, decoder);
+		self
+	}
+
+	/// Builder style version of [`ProgramErrorRegistry::insert`].
+	#[must_use]
+	pub fn with_decoder(mut self, program_id: Pubkey, decoder: CustomErrorDecoder) -> Self {
+		self.insert(program_id, decoder);
+		self
+	}
+
+	/// Remove the decoder for `program_id`. Returns `true` when a decoder was
+	/// registered.
+	pub fn remove(&mut self, program_id: &Pubkey) -> bool {
+		self.decoders.remove(program_id).is_some()
+	}
+
+	/// Check whether the custom errors of `program_id` can be decoded.
+	pub fn contains(&self, program_id: &Pubkey) -> bool {
+		self.decoders.contains_key(program_id)
+	}
+
+	/// Decode the custom error `code` returned by `program_id`.
+	pub fn decode(&self, program_id: &Pubkey, code: u32) -> Option<String> {
+		self.decoders
+			.get(program_id)
+			.and_then(|decoder| decoder(code))
+	}
+
+	/// Describe the `error`. When the transaction `message` is provided the
+	/// program of a failed instruction is resolved so that its custom errors
+	/// can be decoded.
+	pub fn describe(
+		&self,
+		error: &TransactionError,
+		message: Option<&VersionedMessage>,
+	) -> TransactionErrorDescription {
+		let mut description = TransactionErrorDescription {
+			error: error.clone(),
+			instruction_index: None,
+			program_id: None,
+			custom_error: None,
+		};
+
+		let TransactionError::InstructionError(index, instruction_error) = error else {
+			return description;
+		};
+
+		description.instruction_index = Some(*index);
+		description.program_id = message.and_then(|message| {
+			let instruction = message.instructions().get(usize::from(*index))?;
+			message
+				.static_account_keys()
+				.get(usize::from(instruction.program_id_index))
+				.copied()
+		});
+
+		if let (Some(program_id), InstructionError::Custom(code)) =
+			(description.program_id, instruction_error)
+		{
+			description.custom_error = self.decode(&program_id, *code);
+		}
+
+		description
+	}
+}
+
+/// A [`TransactionError`] with the context needed to display it to users.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionErrorDescription {
+	pub error: TransactionError,
+	/// The index of the failed instruction.
+	pub instruction_index: Option<u8>,
+	/// The program of the failed instruction.
+	pub program_id: Option<Pubkey>,
+	/// The decoded message of a `Custom` instruction error.
+	pub custom_error: Option<String>,
+}
+
+impl TransactionErrorDescription {
+	pub fn kind(&self) -> TransactionErrorKind {
+		TransactionErrorKind::from(&self.error)
+	}
+}
+
+impl fmt::Display for TransactionErrorDescription {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let TransactionError::InstructionError(index, error) = &self.error else {
+			return write!(f, "{}", self.error);
+		};
+
+		write!(f, "Instruction {index}")?;
+
+		if let Some(program_id) = self.program_id {
+			write!(f, " of program {program_id}")?;
+		}
+
+		match (&self.custom_error, error) {
+			(Some(message), InstructionError::Custom(code)) => {
+				write!(f, " failed: {message} (custom program error: {code:#x})")
+			}
+			_ => write!(f, " failed: {error}"),
+		}
+	}
+}
+
+/// Whether resubmitting a failed transaction can succeed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionErrorKind {
+	/// The failure depends on the state of the cluster, for example a
+	/// blockhash which the node hasn't seen yet or a full block. Sending the
+	/// transaction again may succeed.
+	Retryable,
+	/// The transaction will fail again unless it is changed.
+	Permanent,
+}
+
+impl TransactionErrorKind {
+	pub fn is_retryable(self) -> bool {
+		self == Self::Retryable
+	}
+}
+
+impl From<&TransactionError> for TransactionErrorKind {
+	fn from(error: &TransactionError) -> Self {
+		match error {
+			TransactionError::BlockhashNotFound
+			| TransactionError::AccountInUse
+			| TransactionError::WouldExceedMaxBlockCostLimit
+			| TransactionError::WouldExceedMaxAccountCostLimit
+			| TransactionError::WouldExceedMaxVoteCostLimit
+			| TransactionError::WouldExceedAccountDataBlockLimit
+			| TransactionError::ClusterMaintenance
+			| TransactionError::ProgramCacheHitMaxLimit => Self::Retryable,
+			_ => Self::Permanent,
+		}
+	}
+}
+
+fn decode_system_error(code: u32) -> Option<String> {
+	SystemError::from_u32(code).map(|error| error.to_string())
+}
+
+fn decode_token_error(code: u32) -> Option<String> {
+	spl_token::error::TokenError::from_u32(code).map(|error| error.to_string())
+}
+
+fn decode_token_2022_error(code: u32) -> Option<String> {
+	spl_token_2022::error::TokenError::from_u32(code).map(|error| error.to_string())
+}
+
+fn decode_associated_token_account_error(code: u32) -> Option<String> {
+	AssociatedTokenAccountError::from_u32(code).map(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use solana_sdk::hash::Hash;
+	use solana_sdk::message::Message;
+
+	use super::*;
+
+	fn token_transfer_message(payer: &Pubkey) -> VersionedMessage {
+		let source = Pubkey::new_unique();
+		let destination = Pubkey::new_unique();
+		let instructions = [
+			solana_system_interface::instruction::transfer(payer, &destination, 1),
+			spl_token::instruction::transfer(
+				&spl_token::id(),
+				&source,
+				&destination,
+				payer,
+				&[],
+				10,
+			)
+			.unwrap(),
+		];
+
+		VersionedMessage::Legacy(Message::new_with_blockhash(
+			&instructions,
+			Some(payer),
+			&Hash::default(),
+		))
+	}
+
+	#[test]
+	fn describes_custom_program_errors() {
+		let message = token_transfer_message(&Pubkey::new_unique());
+		let registry = ProgramErrorRegistry::default();
+		let error = TransactionError::InstructionError(1, InstructionError::Custom(1));
+		let description = registry.describe(&error, Some(&message));
+
+		check!(description.instruction_index == Some(1));
+		check!(description.program_id == Some(spl_token::id()));
+		check!(description.custom_error.as_deref() == Some("Insufficient funds"));
+		check!(
+			description.to_string()
+				== format!(
+					"Instruction 1 of program {} failed: Insufficient funds (custom program \
+					 error: 0x1)",
+					spl_token::id()
+				)
+		);
+		check!(description.kind() == TransactionErrorKind::Permanent);
+
+		let system_error = TransactionError::InstructionError(0, InstructionError::Custom(1));
+		check!(
+			registry
+				.describe(&system_error, Some(&message))
+				.custom_error
+				.is_some()
+		);
+	}
+
+	#[test]
+	fn describes_errors_without_context() {
+		let registry = ProgramErrorRegistry::empty();
+		let error = TransactionError::InstructionError(2, InstructionError::Custom(6001));
+		let description = registry.describe(&error, None);
+
+		check!(description.program_id == None);
+		check!(description.custom_error == None);
+		check!(description.to_string() == "Instruction 2 failed: custom program error: 0x1771");
+
+		let description = registry.describe(&TransactionError::BlockhashNotFound, None);
+		check!(description.to_string() == TransactionError::BlockhashNotFound.to_string());
+		check!(description.kind() == TransactionErrorKind::Retryable);
+	}
+
+	#[test]
+	fn registers_program_decoders() {
+		let program_id = Pubkey::new_unique();
+		let mut registry = ProgramErrorRegistry::default();
+
+		check!(!registry.contains(&program_id));
+		check!(registry.decode(&program_id, 6000) == None);
+
+		registry.insert(program_id, |code| (code == 6000).then(|| "overflow".into()));
+		check!(registry.decode(&program_id, 6000).as_deref() == Some("overflow"));
+		check!(registry.decode(&program_id, 6001) == None);
+		check!(registry.remove(&program_id));
+		check!(!registry.contains(&program_id));
+	}
+
+	#[test]
+	fn classifies_errors() {
+		check!(TransactionErrorKind::from(&TransactionError::BlockhashNotFound).is_retryable());
+		check!(
+			TransactionErrorKind::from(&TransactionError::WouldExceedMaxBlockCostLimit)
+				.is_retryable()
+		);
+		check!(
+			!TransactionErrorKind::from(&TransactionError::InsufficientFundsForFee).is_retryable()
+		);
+		check!(
+			!TransactionErrorKind::from(&TransactionError::InstructionError(
+				0,
+				InstructionError::Custom(0)
+			))
+			.is_retryable()
+		);
+	}
+
+	#[cfg(feature = "ssr")]
+	fn preflight_failure(err: &str) -> serde_json::Value {
+		serde_json::json!({
+			"jsonrpc": "2.0",
+			"id": 0,
+			"error": {
+				"code": -32002,
+				"message": "Transaction simulation failed",
+				"data": { "err": err, "logs": [], "accounts": null },
+			},
+		})
+	}
+
+	#[cfg(feature = "ssr")]
+	#[test]
+	fn send_and_confirm_retries_retryable_errors() -> anyhow::Result<()> {
+		use futures::executor::block_on;
+		use serde_json::json;
+		use solana_sdk::signature::Keypair;
+		use solana_sdk::signer::Signer;
+		use solana_sdk::transaction::VersionedTransaction;
+
+		use crate::MockRpcProvider;
+		use crate::SolanaRpcClient;
+
+		let payer = Keypair::new();
+		let transaction =
+			VersionedTransaction::try_new(token_transfer_message(&payer.pubkey()), &[&payer])?;
+		let signature = transaction.signatures[0];
+		let provider = MockRpcProvider::new()
+			.with_response("sendTransaction", preflight_failure("BlockhashNotFound"))
+			.with_result("sendTransaction", signature.to_string())
+			.with_result(
+				"getSignatureStatuses",
+				json!({
+					"context": { "slot": 1 },
+					"value": [{
+						"slot": 1,
+						"confirmations": null,
+						"err": null,
+						"status": { "Ok": null },
+						"confirmationStatus": "finalized",
+					}],
+				}),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+
+		check!(block_on(rpc.send_and_confirm_transaction(&transaction))? == signature);
+		let sent = provider
+			.requests()
+			.into_iter()
+			.filter(|(method, _)| method == "sendTransaction")
+			.count();
+		check!(sent == 2);
+
+		let provider = MockRpcProvider::new().with_response(
+			"sendTransaction",
+			preflight_failure("InsufficientFundsForFee"),
+		);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+		let error = block_on(rpc.send_and_confirm_transaction(&transaction)).unwrap_err();
+
+		check!(error.transaction_error() == Some(TransactionError::InsufficientFundsForFee));
+		check!(!error.is_retryable());
+		check!(provider.requests().len() == 1);
+
+		Ok(())
+	}
+}
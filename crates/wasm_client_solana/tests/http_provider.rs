@@ -0,0 +1,166 @@
+#![cfg(feature = "ssr")]
+
+use std::io::Write;
+
+use assert2::check;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use futures::TryStreamExt;
+use serde_json::Value;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use wasm_client_solana::HttpProvider;
+use wasm_client_solana::HttpProviderConfig;
+use wasm_client_solana::RpcErrorCode;
+use wasm_client_solana::RpcProvider;
+use wasm_client_solana::SolanaRpcClient;
+
+#[tokio::test]
+async fn decompresses_gzip_responses() -> anyhow::Result<()> {
+	let (url, server) = serve_once(fixture(), true).await?;
+	let provider = HttpProvider::new(url);
+	let response = provider.send("getSlot", json!([])).await?;
+	let request = server.await??;
+
+	check!(request.contains("accept-encoding: gzip"));
+	check!(response == fixture());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn gzip_can_be_disabled() -> anyhow::Result<()> {
+	let (url, server) = serve_once(fixture(), false).await?;
+	let config = HttpProviderConfig::builder().gzip(false).build();
+	let provider = HttpProvider::new_with_config(url, config)?;
+	let response = provider.send("getSlot", json!([])).await?;
+	let request = server.await??;
+
+	check!(!request.contains("gzip"));
+	check!(response == fixture());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn streams_program_accounts() -> anyhow::Result<()> {
+	let owner = Pubkey::new_unique();
+	let pubkeys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+	let body = program_accounts_fixture(&owner, &pubkeys);
+
+	let (url, server) = serve_once(body.clone(), true).await?;
+	let rpc = SolanaRpcClient::new_with_provider(HttpProvider::new(url));
+	let streamed: Vec<_> = rpc
+		.stream_program_accounts(&owner)
+		.await?
+		.try_collect()
+		.await?;
+	server.await??;
+
+	let (url, server) = serve_once(body, false).await?;
+	let rpc = SolanaRpcClient::new_with_provider(HttpProvider::new(url));
+	let accounts = rpc.get_program_accounts(&owner).await?;
+	server.await??;
+
+	check!(
+		streamed
+			.iter()
+			.map(|(pubkey, _)| *pubkey)
+			.collect::<Vec<_>>()
+			== pubkeys
+	);
+	check!(streamed[1].1.data == vec![1, 2, 3]);
+	check!(streamed[1].1.owner == owner);
+	check!(streamed == accounts);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn streaming_error_responses() -> anyhow::Result<()> {
+	let body = json!({
+		"jsonrpc": "2.0",
+		"id": 1,
+		"error": { "code": -32010, "message": "excluded from account secondary indexes" },
+	});
+	let (url, server) = serve_once(body, false).await?;
+	let rpc = SolanaRpcClient::new_with_provider(HttpProvider::new(url));
+	let result: Result<Vec<_>, _> = rpc
+		.stream_program_accounts(&Pubkey::new_unique())
+		.await?
+		.try_collect()
+		.await;
+	server.await??;
+
+	let error = result.unwrap_err();
+	check!(error.rpc_error_code() == Some(RpcErrorCode::KeyExcludedFromSecondaryIndex));
+
+	Ok(())
+}
+
+fn fixture() -> Value {
+	json!({ "jsonrpc": "2.0", "id": 1, "result": 1234 })
+}
+
+fn program_accounts_fixture(owner: &Pubkey, pubkeys: &[Pubkey]) -> Value {
+	let accounts: Vec<Value> = pubkeys
+		.iter()
+		.map(|pubkey| {
+			json!({
+				"pubkey": pubkey.to_string(),
+				"account": {
+					"lamports": 1_000_000,
+					"data": ["AQID", "base64"],
+					"owner": owner.to_string(),
+					"executable": false,
+					"rentEpoch": 0,
+					"space": 3,
+				},
+			})
+		})
+		.collect();
+
+	json!({ "jsonrpc": "2.0", "id": 1, "result": accounts })
+}
+
+/// Serve a single request on a random local port. The response body is the
+/// gzipped json `body` when `gzip` is true, with the `content-length` of the
+/// compressed bytes. Resolves with the lowercased request head.
+async fn serve_once(
+	body: Value,
+	gzip: bool,
+) -> anyhow::Result<(String, JoinHandle<anyhow::Result<String>>)> {
+	let listener = TcpListener::bind("127.0.0.1:0").await?;
+	let url = format!("http://{}", listener.local_addr()?);
+	let handle = tokio::spawn(async move {
+		let (mut socket, _) = listener.accept().await?;
+		let mut buffer = vec![0; 8192];
+		let size = socket.read(&mut buffer).await?;
+		let request = String::from_utf8_lossy(&buffer[..size]).to_lowercase();
+		let json = serde_json::to_vec(&body)?;
+		let (body, encoding) = if gzip {
+			let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+			encoder.write_all(&json)?;
+			(encoder.finish()?, "content-encoding: gzip\r\n")
+		} else {
+			(json, "")
+		};
+		let head = format!(
+			"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n{encoding}content-length: \
+			 {}\r\nconnection: close\r\n\r\n",
+			body.len()
+		);
+
+		socket.write_all(head.as_bytes()).await?;
+		socket.write_all(&body).await?;
+		socket.shutdown().await?;
+
+		Ok(request)
+	});
+
+	Ok((url, handle))
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "js")]
+
+use assert2::check;
+use js_sys::Reflect;
+use serde_json::json;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+use wasm_client_solana::solana_transaction_status_client_types::EncodedConfirmedTransactionWithStatusMeta;
+use wasm_client_solana::solana_transaction_status_client_types::js::JsConvert;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const OWNER: &str = "92vBXvpoy6Vh5z5dtQR71ee8wseDY3idM8TByDztC6v1";
+
+fn v0_transaction() -> EncodedConfirmedTransactionWithStatusMeta {
+	let token_balance = |amount: &str, ui_amount: f64| {
+		json!({
+			"accountIndex": 1,
+			"mint": MINT,
+			"owner": OWNER,
+			"programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+			"uiTokenAmount": {
+				"amount": amount,
+				"decimals": 6,
+				"uiAmount": ui_amount,
+				"uiAmountString": ui_amount.to_string(),
+			},
+		})
+	};
+	let transaction = json!({
+		"slot": 9_007_199_254_740_993_u64,
+		"blockTime": 1_700_000_000,
+		"version": 0,
+		"transaction": {
+			"signatures": ["5sstmVwo4cpTq3MzkpdfteEVkj9JiUSzWMhrMrNc4hdLR4nhWWoMYAXtzX5iRKiFaEqHXpnSs2Db5kHngoKWX9EL"],
+			"message": {
+				"header": {
+					"numRequiredSignatures": 1,
+					"numReadonlySignedAccounts": 0,
+					"numReadonlyUnsignedAccounts": 1,
+				},
+				"accountKeys": [OWNER, "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"],
+				"recentBlockhash": MINT,
+				"instructions": [{
+					"programIdIndex": 1,
+					"accounts": [0, 2],
+					"data": "3Bxs4h24hBtQy9rw",
+					"stackHeight": null,
+				}],
+				"addressTableLookups": [{
+					"accountKey": MINT,
+					"writableIndexes": [0],
+					"readonlyIndexes": [],
+				}],
+			},
+		},
+		"meta": {
+			"err": null,
+			"status": { "Ok": null },
+			"fee": 5000,
+			"preBalances": [1_000_000_000, 1],
+			"postBalances": [999_995_000, 1],
+			"preTokenBalances": [token_balance("2000000", 2.0)],
+			"postTokenBalances": [token_balance("1500000", 1.5)],
+			"loadedAddresses": { "writable": [OWNER], "readonly": [] },
+			"computeUnitsConsumed": 4_500,
+		},
+	});
+
+	serde_json::from_value(transaction).expect("the fixture is a valid transaction")
+}
+
+fn get(value: &JsValue, path: &[&str]) -> JsValue {
+	path.iter().fold(value.clone(), |value, key| {
+		Reflect::get(&value, &(*key).into()).unwrap_or(JsValue::UNDEFINED)
+	})
+}
+
+#[wasm_bindgen_test]
+pub fn v0_transaction_round_trip() -> Result<(), JsValue> {
+	let transaction = v0_transaction();
+	let value: JsValue = transaction.to_js()?.into();
+	let balance = get(&value, &["meta", "postTokenBalances"]);
+	let balance = Reflect::get_u32(&balance, 0).unwrap_or(JsValue::UNDEFINED);
+
+	check!(get(&value, &["slot"]).is_bigint());
+	check!(get(&value, &["blockTime"]).is_bigint());
+	check!(get(&value, &["version"]).as_f64() == Some(0.0));
+	check!(get(&value, &["meta", "fee"]).is_bigint());
+	check!(get(&balance, &["mint"]).as_string().as_deref() == Some(MINT));
+	check!(get(&balance, &["owner"]).as_string().as_deref() == Some(OWNER));
+	check!(get(&balance, &["uiTokenAmount", "decimals"]).as_f64() == Some(6.0));
+
+	let decoded = EncodedConfirmedTransactionWithStatusMeta::from_js(value.unchecked_into())?;
+
+	check!(decoded == transaction);
+
+	Ok(())
+}
@@ -0,0 +1,119 @@
+//! Responses from different rpc providers deserialize into the same types.
+//!
+//! The fixtures are trimmed responses which reproduce the quirks of each
+//! provider: extra fields which agave doesn't return, integers encoded as
+//! strings and optional fields which are left out.
+
+#![cfg(feature = "ssr")]
+
+use assert2::check;
+use futures::executor::block_on;
+use serde_json::Value;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use wasm_client_solana::MockRpcProvider;
+use wasm_client_solana::SolanaRpcClient;
+use wasm_client_solana::rpc_config::RpcAccountInfoConfig;
+use wasm_client_solana::rpc_config::RpcBlockConfig;
+use wasm_client_solana::rpc_config::RpcTransactionConfig;
+
+const PROVIDERS: [(&str, &str); 3] = [
+	("helius", include_str!("fixtures/providers/helius.json")),
+	("triton", include_str!("fixtures/providers/triton.json")),
+	("alchemy", include_str!("fixtures/providers/alchemy.json")),
+];
+
+const PAYER: Pubkey = pubkey!("92vBXvpoy6Vh5z5dtQR71ee8wseDY3idM8TByDztC6v1");
+const SIGNATURE: &str =
+	"5sstmVwo4cpTq3MzkpdfteEVkj9JiUSzWMhrMrNc4hdLR4nhWWoMYAXtzX5iRKiFaEqHXpnSs2Db5kHngoKWX9EL";
+
+fn rpc(fixtures: &str) -> anyhow::Result<SolanaRpcClient> {
+	let fixtures: Value = serde_json::from_str(fixtures)?;
+	let provider = MockRpcProvider::from_fixtures(&fixtures)?;
+
+	Ok(SolanaRpcClient::new_with_provider(provider))
+}
+
+#[test]
+fn get_transaction() -> anyhow::Result<()> {
+	let signature: Signature = SIGNATURE.parse()?;
+
+	for (provider, fixtures) in PROVIDERS {
+		let rpc = rpc(fixtures)?;
+		let transaction =
+			block_on(rpc.get_transaction_with_config(&signature, RpcTransactionConfig::default()))
+				.map_err(|error| anyhow::anyhow!("{provider}: {error}"))?;
+		let meta = transaction.transaction.meta.as_ref();
+
+		check!(transaction.slot == 301_234_567, "{provider}");
+		check!(transaction.block_time == Some(1_730_000_000), "{provider}");
+		check!(meta.map(|meta| meta.fee) == Some(5_000), "{provider}");
+		check!(
+			meta.map(|meta| meta.post_balances.clone()) == Some(vec![998_995_000, 1_000_000, 1]),
+			"{provider}"
+		);
+		check!(
+			transaction
+				.transaction
+				.decode_transaction()
+				.map(|transaction| transaction.signatures)
+				== Some(vec![signature]),
+			"{provider}"
+		);
+
+		let compute_units = (provider != "alchemy").then_some(150);
+		check!(
+			meta.and_then(|meta| meta.compute_units_consumed) == compute_units,
+			"{provider}"
+		);
+	}
+
+	Ok(())
+}
+
+#[test]
+fn get_block() -> anyhow::Result<()> {
+	for (provider, fixtures) in PROVIDERS {
+		let rpc = rpc(fixtures)?;
+		let block = block_on(rpc.get_block_with_config(301_234_567, RpcBlockConfig::default()))
+			.map_err(|error| anyhow::anyhow!("{provider}: {error}"))?;
+		let rewards = block.rewards.unwrap_or_default();
+
+		check!(block.parent_slot == 301_234_566, "{provider}");
+		check!(block.block_time == Some(1_730_000_000), "{provider}");
+		check!(
+			block.signatures.map(|signatures| signatures.len()) == Some(1),
+			"{provider}"
+		);
+		check!(rewards.len() == 1, "{provider}");
+		check!(rewards[0].lamports == 2_500, "{provider}");
+		check!(rewards[0].post_balance == 40_000_002_500, "{provider}");
+
+		let block_height = (provider != "alchemy").then_some(279_871_234);
+		check!(block.block_height == block_height, "{provider}");
+	}
+
+	Ok(())
+}
+
+#[test]
+fn get_account_info() -> anyhow::Result<()> {
+	for (provider, fixtures) in PROVIDERS {
+		let rpc = rpc(fixtures)?;
+		let response =
+			block_on(rpc.get_account_with_context(&PAYER, RpcAccountInfoConfig::default()))
+				.map_err(|error| anyhow::anyhow!("{provider}: {error}"))?;
+		let account = response
+			.value
+			.ok_or_else(|| anyhow::anyhow!("{provider}: missing account"))?;
+
+		check!(response.context.slot == 301_234_570, "{provider}");
+		check!(account.lamports == 2_039_280, "{provider}");
+		check!(account.data == vec![1, 2, 3], "{provider}");
+		check!(account.owner == spl_token::ID, "{provider}");
+		check!(account.rent_epoch == u64::MAX, "{provider}");
+	}
+
+	Ok(())
+}
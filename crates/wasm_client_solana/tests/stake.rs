@@ -0,0 +1,194 @@
+#![cfg(feature = "ssr")]
+
+use assert2::check;
+use assert2::let_assert;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use futures::executor::block_on;
+use serde_json::Value;
+use serde_json::json;
+use solana_sdk::clock::Clock;
+use solana_sdk::clock::Epoch;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::stake_history::StakeHistory;
+use solana_sdk::stake_history::StakeHistoryEntry;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_stake_interface::instruction;
+use solana_stake_interface::stake_flags::StakeFlags;
+use solana_stake_interface::state::Authorized;
+use solana_stake_interface::state::Delegation;
+use solana_stake_interface::state::Lockup;
+use solana_stake_interface::state::Meta;
+use solana_stake_interface::state::Stake;
+use solana_stake_interface::state::StakeStateV2;
+use wasm_client_solana::ClientError;
+use wasm_client_solana::MockRpcProvider;
+use wasm_client_solana::SolanaRpcClient;
+use wasm_client_solana::VersionedTransactionExtension;
+use wasm_client_solana::stake::StakeError;
+
+const LAMPORTS: u64 = 1_000_000_000;
+const RESERVE: u64 = 2_282_880;
+
+#[test]
+fn split_rejects_amounts_below_the_minimum_delegation() {
+	let authority = Pubkey::new_unique();
+	let state = delegated(&authority, 3 * LAMPORTS, Epoch::MAX);
+	let provider = MockRpcProvider::new()
+		.with_result(
+			"getAccountInfo",
+			stake_account(&state, 3 * LAMPORTS + RESERVE),
+		)
+		.with_result("getMinimumBalanceForRentExemption", RESERVE)
+		.with_result(
+			"getStakeMinimumDelegation",
+			json!({ "context": { "slot": 1 }, "value": LAMPORTS }),
+		);
+	let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+
+	let_assert!(
+		Err(ClientError::Stake(StakeError::BelowMinimum { minimum, .. })) =
+			block_on(rpc.split_stake(
+				&Pubkey::new_unique(),
+				&authority,
+				&Pubkey::new_unique(),
+				RESERVE
+			))
+	);
+	check!(minimum == LAMPORTS + RESERVE);
+	check!(
+		!provider
+			.requests()
+			.iter()
+			.any(|(method, _)| method == "getLatestBlockhash")
+	);
+}
+
+#[test]
+fn withdraw_waits_for_the_stake_to_cool_down() -> anyhow::Result<()> {
+	let authority = Pubkey::new_unique();
+	let stake_pubkey = Pubkey::new_unique();
+	let recipient = Pubkey::new_unique();
+	let blockhash = Hash::new_unique();
+	let balance = LAMPORTS + RESERVE;
+	let state = delegated(&authority, LAMPORTS, 4);
+	// Half of the cluster stake is deactivated in epoch 4, so only 18% of it
+	// cools down by epoch 5 and 0.82 SOL of this account is still effective.
+	let history = [(
+		4,
+		StakeHistoryEntry {
+			effective: 10 * LAMPORTS,
+			activating: 0,
+			deactivating: 5 * LAMPORTS,
+		},
+	)];
+	let provider = MockRpcProvider::new()
+		.with_result("getAccountInfo", stake_account(&state, balance))
+		.with_result("getAccountInfo", clock_account(5))
+		.with_result("getAccountInfo", stake_history_account(&history));
+	let rpc = SolanaRpcClient::new_with_provider(provider);
+
+	let_assert!(
+		Err(ClientError::Stake(StakeError::InsufficientFunds {
+			available,
+			..
+		})) = block_on(rpc.withdraw_stake(&stake_pubkey, &authority, &recipient, balance, None))
+	);
+	check!(available == 180_000_000);
+
+	// Without a history entry for the deactivation epoch the stake program
+	// treats the stake as fully deactivated.
+	let provider = MockRpcProvider::new()
+		.with_result("getAccountInfo", stake_account(&state, balance))
+		.with_result("getAccountInfo", clock_account(6))
+		.with_result("getAccountInfo", stake_history_account(&[]))
+		.with_result(
+			"getLatestBlockhash",
+			json!({
+				"context": { "slot": 1 },
+				"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+			}),
+		);
+	let rpc = SolanaRpcClient::new_with_provider(provider);
+	let transaction =
+		block_on(rpc.withdraw_stake(&stake_pubkey, &authority, &recipient, balance, None))?;
+	let instructions = [instruction::withdraw(
+		&stake_pubkey,
+		&authority,
+		&recipient,
+		balance,
+		None,
+	)];
+
+	check!(
+		transaction
+			== VersionedTransaction::new_unsigned_v0(&authority, &instructions, &[], blockhash)?
+	);
+
+	Ok(())
+}
+
+fn account_info(owner: &Pubkey, lamports: u64, data: &[u8]) -> Value {
+	json!({
+		"context": { "slot": 1 },
+		"value": {
+			"lamports": lamports,
+			"data": [BASE64_STANDARD.encode(data), "base64"],
+			"owner": owner.to_string(),
+			"executable": false,
+			"rentEpoch": 0,
+			"space": data.len(),
+		},
+	})
+}
+
+fn stake_account(state: &StakeStateV2, lamports: u64) -> Value {
+	let mut data = bincode::serialize(state).unwrap();
+	data.resize(StakeStateV2::size_of(), 0);
+
+	account_info(&solana_stake_interface::program::id(), lamports, &data)
+}
+
+fn clock_account(epoch: Epoch) -> Value {
+	let clock = Clock {
+		slot: 100,
+		epoch,
+		unix_timestamp: 1_700_000_000,
+		..Clock::default()
+	};
+
+	account_info(&sysvar::id(), 1, &bincode::serialize(&clock).unwrap())
+}
+
+fn stake_history_account(entries: &[(Epoch, StakeHistoryEntry)]) -> Value {
+	let mut history = StakeHistory::default();
+
+	for (epoch, entry) in entries {
+		history.add(*epoch, entry.clone());
+	}
+
+	account_info(&sysvar::id(), 1, &bincode::serialize(&history).unwrap())
+}
+
+fn delegated(authority: &Pubkey, stake: u64, deactivation_epoch: Epoch) -> StakeStateV2 {
+	let meta = Meta {
+		rent_exempt_reserve: RESERVE,
+		authorized: Authorized::auto(authority),
+		lockup: Lockup::default(),
+	};
+	let delegation = Delegation {
+		voter_pubkey: Pubkey::new_unique(),
+		stake,
+		activation_epoch: 0,
+		deactivation_epoch,
+		..Delegation::default()
+	};
+	let stake = Stake {
+		delegation,
+		credits_observed: 0,
+	};
+
+	StakeStateV2::Stake(meta, stake, StakeFlags::empty())
+}
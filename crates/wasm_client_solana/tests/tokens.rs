@@ -0,0 +1,115 @@
+#![cfg(feature = "ssr")]
+
+use assert2::check;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use futures::executor::block_on;
+use serde_json::Value;
+use serde_json::json;
+use solana_sdk::hash::Hash;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::state::Mint;
+use wasm_client_solana::MockRpcProvider;
+use wasm_client_solana::SolanaRpcClient;
+use wasm_client_solana::VersionedTransactionExtension;
+use wasm_client_solana::derive_associated_token_address;
+
+#[test]
+fn get_or_create_associated_token_account_sends_once() -> anyhow::Result<()> {
+	let payer = Keypair::new();
+	let owner = Pubkey::new_unique();
+
+	for token_program in [spl_token::id(), spl_token_2022::id()] {
+		let mint = Pubkey::new_unique();
+		let blockhash = Hash::new_unique();
+		let address = derive_associated_token_address(&owner, &mint, &token_program);
+		let instruction = create_associated_token_account_idempotent(
+			&payer.pubkey(),
+			&owner,
+			&mint,
+			&token_program,
+		);
+		let mut transaction =
+			VersionedTransaction::new_unsigned_v0(&payer.pubkey(), &[instruction], &[], blockhash)?;
+		transaction.try_sign(&[&payer], None)?;
+		let provider = MockRpcProvider::new()
+			.with_result("getAccountInfo", account_info(&token_program, &mint_data()))
+			.with_result(
+				"getAccountInfo",
+				json!({ "context": { "slot": 1 }, "value": null }),
+			)
+			.with_result("getAccountInfo", account_info(&token_program, &[0; 165]))
+			.with_result(
+				"getLatestBlockhash",
+				json!({
+					"context": { "slot": 1 },
+					"value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 },
+				}),
+			)
+			.with_result("sendTransaction", transaction.signatures[0].to_string())
+			.with_result(
+				"getSignatureStatuses",
+				json!({
+					"context": { "slot": 1 },
+					"value": [{
+						"slot": 1,
+						"confirmations": null,
+						"err": null,
+						"status": { "Ok": null },
+						"confirmationStatus": "finalized",
+					}],
+				}),
+			);
+		let rpc = SolanaRpcClient::new_with_provider(provider.clone());
+
+		let created = block_on(rpc.get_or_create_associated_token_account(&payer, &owner, &mint))?;
+
+		check!(created.created);
+		check!(created.address == address);
+
+		let existing = block_on(rpc.get_or_create_associated_token_account(&payer, &owner, &mint))?;
+
+		check!(!existing.created);
+		check!(existing.address == address);
+
+		let sent = provider
+			.requests()
+			.iter()
+			.filter(|(method, _)| method == "sendTransaction")
+			.count();
+		check!(sent == 1);
+	}
+
+	Ok(())
+}
+
+fn mint_data() -> Vec<u8> {
+	let mut data = vec![0; Mint::LEN];
+	Mint {
+		decimals: 6,
+		is_initialized: true,
+		..Mint::default()
+	}
+	.pack_into_slice(&mut data);
+
+	data
+}
+
+fn account_info(owner: &Pubkey, data: &[u8]) -> Value {
+	json!({
+		"context": { "slot": 1 },
+		"value": {
+			"lamports": 2_039_280,
+			"data": [BASE64_STANDARD.encode(data), "base64"],
+			"owner": owner.to_string(),
+			"executable": false,
+			"rentEpoch": 0,
+			"space": data.len(),
+		},
+	})
+}
@@ -205,6 +205,7 @@ in
         echo "running tests in chrome..."
         export RUSTFLAGS='--cfg getrandom_backend="wasm_js"' 
         CHROMEDRIVER=$DEVENV_PROFILE/bin/chromedriver cargo test_wasm
+        CHROMEDRIVER=$DEVENV_PROFILE/bin/chromedriver cargo test_example_js_client
 
         # echo "running tests in firefox..."
         # GECKODRIVER=$DEVENV_PROFILE/bin/geckodriver cargo test_wasm
@@ -0,0 +1,74 @@
+//! Compare serializing and deserializing 1000 accounts as json with the
+//! compact accounts as `bincode`.
+//!
+//! Run with `cargo bench -p solana-account-decoder-client-types-wasm`.
+
+use std::hint::black_box;
+use std::time::Duration;
+use std::time::Instant;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use solana_account_decoder_client_types_wasm::UiAccount;
+use solana_account_decoder_client_types_wasm::UiAccountData;
+use solana_account_decoder_client_types_wasm::UiAccountEncoding;
+use solana_account_decoder_client_types_wasm::compact::CompactUiAccount;
+use solana_pubkey::Pubkey;
+
+const ACCOUNTS: usize = 1000;
+const DATA_LENGTH: usize = 165;
+const ITERATIONS: u32 = 50;
+
+fn main() {
+	let accounts: Vec<UiAccount> = (0..ACCOUNTS)
+		.map(|index| {
+			let data: Vec<u8> = (0..DATA_LENGTH).map(|byte| (byte + index) as u8).collect();
+
+			UiAccount::builder()
+				.lamports(2_039_280)
+				.data(UiAccountData::Binary(
+					BASE64_STANDARD.encode(data),
+					UiAccountEncoding::Base64,
+				))
+				.owner(Pubkey::new_unique())
+				.rent_epoch(u64::MAX)
+				.space(DATA_LENGTH as u64)
+				.build()
+		})
+		.collect();
+	let compact = accounts
+		.iter()
+		.cloned()
+		.map(CompactUiAccount::try_from)
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+
+	let json = serde_json::to_vec(&accounts).unwrap();
+	let json_time = measure(|| {
+		let bytes = serde_json::to_vec(black_box(&accounts)).unwrap();
+		black_box(serde_json::from_slice::<Vec<UiAccount>>(&bytes).unwrap());
+	});
+
+	let binary = bincode::serialize(&compact).unwrap();
+	let binary_time = measure(|| {
+		let bytes = bincode::serialize(black_box(&compact)).unwrap();
+		black_box(bincode::deserialize::<Vec<CompactUiAccount>>(&bytes).unwrap());
+	});
+
+	println!("{ACCOUNTS} accounts with {DATA_LENGTH} bytes of data");
+	println!("json:    {:>8} bytes {json_time:>12.2?}", json.len());
+	println!("bincode: {:>8} bytes {binary_time:>12.2?}", binary.len());
+}
+
+/// The mean time of a serialize and deserialize round trip.
+fn measure(mut round_trip: impl FnMut()) -> Duration {
+	round_trip();
+
+	let start = Instant::now();
+
+	for _ in 0..ITERATIONS {
+		round_trip();
+	}
+
+	start.elapsed() / ITERATIONS
+}
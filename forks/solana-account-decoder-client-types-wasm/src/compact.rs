@@ -0,0 +1,197 @@
+//! Compact versions of the ui types for binary formats such as `bincode` and
+//! `postcard`.
+//!
+//! The json rpc types write pubkeys as base58 strings and account data as
+//! base58 or base64 strings which are slow to parse and larger than the raw
+//! bytes. The compact types keep the raw bytes instead. They are created from
+//! the json types with `TryFrom`, which fails when the account data can't be
+//! decoded, and convert back with `From`.
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+use serde_json::Value;
+use solana_pubkey::Pubkey;
+
+use crate::ParsedAccount;
+use crate::UiAccount;
+use crate::UiAccountData;
+use crate::UiAccountDecodeError;
+use crate::UiAccountEncoding;
+
+/// A [`UiAccount`] with the `owner` stored as 32 bytes and the data stored
+/// as raw bytes.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CompactUiAccount {
+	pub lamports: u64,
+	pub data: CompactUiAccountData,
+	pub owner: Pubkey,
+	pub executable: bool,
+	pub rent_epoch: u64,
+	pub space: Option<u64>,
+}
+
+impl TryFrom<UiAccount> for CompactUiAccount {
+	type Error = UiAccountDecodeError;
+
+	fn try_from(account: UiAccount) -> Result<Self, Self::Error> {
+		Ok(Self {
+			lamports: account.lamports,
+			data: account.data.try_into()?,
+			owner: account.owner,
+			executable: account.executable,
+			rent_epoch: account.rent_epoch,
+			space: account.space,
+		})
+	}
+}
+
+impl From<CompactUiAccount> for UiAccount {
+	fn from(account: CompactUiAccount) -> Self {
+		Self {
+			lamports: account.lamports,
+			data: account.data.into(),
+			owner: account.owner,
+			executable: account.executable,
+			rent_epoch: account.rent_epoch,
+			space: account.space,
+		}
+	}
+}
+
+/// The data of a [`CompactUiAccount`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum CompactUiAccountData {
+	/// The decoded account data. This becomes `base64` data when converted
+	/// back into [`UiAccountData`].
+	Binary(Vec<u8>),
+	/// The `jsonParsed` data with the `parsed` value stored as a json string,
+	/// since binary formats can't read an untyped [`Value`].
+	Json {
+		program: String,
+		parsed: String,
+		space: u64,
+	},
+}
+
+impl TryFrom<UiAccountData> for CompactUiAccountData {
+	type Error = UiAccountDecodeError;
+
+	fn try_from(data: UiAccountData) -> Result<Self, Self::Error> {
+		match data {
+			UiAccountData::Json(account) => {
+				Ok(Self::Json {
+					program: account.program,
+					parsed: account.parsed.to_string(),
+					space: account.space,
+				})
+			}
+			data => data.try_decode().map(Self::Binary),
+		}
+	}
+}
+
+impl From<CompactUiAccountData> for UiAccountData {
+	fn from(data: CompactUiAccountData) -> Self {
+		match data {
+			CompactUiAccountData::Binary(bytes) => {
+				Self::Binary(BASE64_STANDARD.encode(bytes), UiAccountEncoding::Base64)
+			}
+			CompactUiAccountData::Json {
+				program,
+				parsed,
+				space,
+			} => {
+				let parsed = serde_json::from_str(&parsed).unwrap_or(Value::String(parsed));
+
+				Self::Json(ParsedAccount {
+					program,
+					parsed,
+					space,
+				})
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use serde_json::json;
+
+	use super::*;
+
+	fn account(data: UiAccountData) -> UiAccount {
+		UiAccount::builder()
+			.lamports(1_000_000)
+			.data(data)
+			.owner(Pubkey::new_unique())
+			.rent_epoch(u64::MAX)
+			.space(4_u64)
+			.build()
+	}
+
+	#[test]
+	fn binary_round_trip() {
+		let ui_account = account(UiAccountData::Binary(
+			BASE64_STANDARD.encode([1, 2, 3, 4]),
+			UiAccountEncoding::Base64,
+		));
+		let compact = CompactUiAccount::try_from(ui_account.clone()).unwrap();
+		let bytes = bincode::serialize(&compact).unwrap();
+
+		assert_eq!(compact.data, CompactUiAccountData::Binary(vec![1, 2, 3, 4]));
+		assert!(bytes.len() < serde_json::to_vec(&ui_account).unwrap().len());
+		assert_eq!(
+			bincode::deserialize::<CompactUiAccount>(&bytes).unwrap(),
+			compact
+		);
+		assert_eq!(UiAccount::from(compact), ui_account);
+	}
+
+	#[test]
+	fn base58_data_becomes_base64() {
+		let compact = CompactUiAccount::try_from(account(UiAccountData::Binary(
+			bs58::encode([1, 2, 3, 4]).into_string(),
+			UiAccountEncoding::Base58,
+		)))
+		.unwrap();
+
+		assert_eq!(
+			UiAccount::from(compact).data,
+			UiAccountData::Binary(
+				BASE64_STANDARD.encode([1, 2, 3, 4]),
+				UiAccountEncoding::Base64
+			)
+		);
+	}
+
+	#[test]
+	fn json_round_trip() {
+		let ui_account = account(UiAccountData::Json(ParsedAccount {
+			program: "spl-token".into(),
+			parsed: json!({ "type": "mint", "info": { "decimals": 6 } }),
+			space: 82,
+		}));
+		let compact = CompactUiAccount::try_from(ui_account.clone()).unwrap();
+		let bytes = bincode::serialize(&compact).unwrap();
+		let decoded = bincode::deserialize::<CompactUiAccount>(&bytes).unwrap();
+
+		assert_eq!(UiAccount::from(decoded), ui_account);
+	}
+
+	#[test]
+	fn undecodable_data_is_rejected() {
+		assert_eq!(
+			CompactUiAccount::try_from(account(UiAccountData::Binary(
+				"not base64!".into(),
+				UiAccountEncoding::Base64,
+			))),
+			Err(UiAccountDecodeError::InvalidBase64)
+		);
+		assert_eq!(
+			CompactUiAccount::try_from(account(UiAccountData::LegacyBinary("0OIl".into()))),
+			Err(UiAccountDecodeError::InvalidBase58)
+		);
+	}
+}
@@ -0,0 +1,108 @@
+//! Conversions between the ui types and javascript values.
+//!
+//! The values have the same shape as the json rpc responses except that
+//! 64 bit integers become a `bigint`, so lamports and slots never lose
+//! precision. Pubkeys, hashes and signatures stay base58 strings.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+pub use serde_wasm_bindgen::Error;
+use serde_wasm_bindgen::Serializer;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+
+use crate::UiAccount;
+use crate::UiDataSliceConfig;
+use crate::token::UiTokenAmount;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPESCRIPT_TYPES: &str = r#"
+export type UiAccountEncoding = "binary" | "base58" | "base64" | "jsonParsed" | "base64+zstd";
+
+export interface ParsedAccount {
+	program: string;
+	parsed: unknown;
+	space: bigint;
+}
+
+export type UiAccountData = string | ParsedAccount | [string, UiAccountEncoding];
+
+export interface UiAccount {
+	lamports: bigint;
+	data: UiAccountData;
+	owner: string;
+	executable: boolean;
+	rentEpoch: bigint;
+	space?: bigint;
+}
+
+export interface UiDataSliceConfig {
+	offset: bigint;
+	length: bigint;
+}
+
+export interface UiTokenAmount {
+	uiAmount: number | null;
+	decimals: number;
+	amount: string;
+	uiAmountString: string;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+	#[wasm_bindgen(typescript_type = "UiAccount")]
+	pub type JsUiAccount;
+	#[wasm_bindgen(typescript_type = "UiDataSliceConfig")]
+	pub type JsUiDataSliceConfig;
+	#[wasm_bindgen(typescript_type = "UiTokenAmount")]
+	pub type JsUiTokenAmount;
+}
+
+/// A ui type with a typescript definition.
+pub trait JsConvert: Serialize + DeserializeOwned {
+	/// The javascript value typed with the typescript definition.
+	type Js: JsCast;
+
+	/// Convert into a plain javascript object.
+	fn to_js(&self) -> Result<Self::Js, Error> {
+		to_js_value(self).map(JsCast::unchecked_into)
+	}
+
+	/// Read the value back from a javascript object. Integers are also
+	/// accepted as numbers or strings, the same as in json.
+	fn from_js(value: Self::Js) -> Result<Self, Error> {
+		from_js_value(value.into())
+	}
+}
+
+impl JsConvert for UiAccount {
+	type Js = JsUiAccount;
+}
+
+impl JsConvert for UiDataSliceConfig {
+	type Js = JsUiDataSliceConfig;
+}
+
+impl JsConvert for UiTokenAmount {
+	type Js = JsUiTokenAmount;
+}
+
+/// The serializer used for all the ui types. Missing values are `null`,
+/// maps are plain objects and `u64` and `i64` are `bigint`.
+pub fn serializer() -> Serializer {
+	Serializer::new()
+		.serialize_missing_as_null(true)
+		.serialize_maps_as_objects(true)
+		.serialize_large_number_types_as_bigints(true)
+}
+
+/// Convert any serializable `value` with the [`serializer`].
+pub fn to_js_value<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, Error> {
+	value.serialize(&serializer())
+}
+
+/// Deserialize a `value` created by [`to_js_value`] or parsed from json.
+pub fn from_js_value<T: DeserializeOwned>(value: JsValue) -> Result<T, Error> {
+	serde_wasm_bindgen::from_value(value)
+}
@@ -1,39 +1,175 @@
 //! Core RPC client types for solana-account-decoder
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
+use core::fmt;
+use core::str::FromStr;
 #[cfg(feature = "zstd")]
 use std::io::Read;
 
+#[cfg(feature = "anchor")]
+use anchor_lang::AnchorDeserialize;
+#[cfg(feature = "anchor")]
+use anchor_lang::Discriminator;
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
+use borsh::BorshDeserialize;
+use serde::Deserializer;
+use serde::Serializer;
+use serde::de;
+use serde::de::Unexpected;
+use serde::de::Visitor;
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
 use serde_json::Value;
+use serde_with::DeserializeAs;
 use serde_with::DisplayFromStr;
+use serde_with::PickFirst;
+use serde_with::Same;
+use serde_with::SerializeAs;
 use serde_with::serde_as;
 use serde_with::skip_serializing_none;
+use solana_account::ReadableAccount;
 use solana_account::WritableAccount;
 use solana_pubkey::Pubkey;
+use thiserror::Error;
 use typed_builder::TypedBuilder;
 
+pub mod compact;
+#[cfg(feature = "js")]
+pub mod js;
 pub mod token;
 
+/// The maximum number of account data bytes which the RPC will encode as
+/// `base58`.
+pub const MAX_BASE58_BYTES: usize = 128;
+
+/// The longest `base58` string the RPC returns for account data of
+/// [`MAX_BASE58_BYTES`]. Longer strings are rejected before decoding since
+/// decoding `base58` takes time quadratic in its length.
+pub const MAX_BASE58_LENGTH: usize = max_base58_length(MAX_BASE58_BYTES);
+
+/// An upper bound on the length of the `base58` encoding of `bytes` bytes.
+///
+/// Each character holds `log2(58)` bits, about 0.73 bytes, so a byte takes a
+/// little under 1.37 characters which is rounded up to 1.38. Each leading
+/// zero byte becomes a single `1`, and the final `+ 1` covers the character
+/// which is only partly used.
+pub const fn max_base58_length(bytes: usize) -> usize {
+	bytes.saturating_mul(138) / 100 + 1
+}
+
+/// A `serde_as` adapter which reads an integer from either a json number or a
+/// string of digits, since some rpc providers return large integers as
+/// strings. The value is always written as a number.
+pub type NumberOrString = PickFirst<(Same, DisplayFromStr)>;
+
+/// A `serde_as` adapter for `u64` fields which json stacks that read numbers
+/// as doubles may have mangled. Integers, strings of digits and floats are
+/// accepted. `u64::MAX` becomes the float `2^64` in a double, so that float is
+/// read back as `u64::MAX`. The value is always written as a number.
+pub struct LenientU64;
+
+impl SerializeAs<u64> for LenientU64 {
+	fn serialize_as<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_u64(*value)
+	}
+}
+
+impl<'de> DeserializeAs<'de, u64> for LenientU64 {
+	fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+		deserializer.deserialize_any(LenientU64Visitor)
+	}
+}
+
+struct LenientU64Visitor;
+
+impl Visitor<'_> for LenientU64Visitor {
+	type Value = u64;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("an unsigned integer as a number or a string")
+	}
+
+	fn visit_u64<E: de::Error>(self, value: u64) -> Result<u64, E> {
+		Ok(value)
+	}
+
+	fn visit_i64<E: de::Error>(self, value: i64) -> Result<u64, E> {
+		u64::try_from(value).map_err(|_| E::invalid_value(Unexpected::Signed(value), &self))
+	}
+
+	fn visit_f64<E: de::Error>(self, value: f64) -> Result<u64, E> {
+		float_to_u64(value).ok_or_else(|| E::invalid_value(Unexpected::Float(value), &self))
+	}
+
+	fn visit_str<E: de::Error>(self, value: &str) -> Result<u64, E> {
+		value
+			.parse::<u64>()
+			.ok()
+			.or_else(|| value.parse::<f64>().ok().and_then(float_to_u64))
+			.ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+	}
+}
+
+/// Convert a float holding an unsigned integer, reading the float closest to
+/// `u64::MAX` as `u64::MAX`.
+fn float_to_u64(value: f64) -> Option<u64> {
+	// The smallest double above every `u64`, which is what `u64::MAX` rounds to.
+	const TWO_POW_64: f64 = 18_446_744_073_709_551_616.0;
+
+	if value.to_bits() == TWO_POW_64.to_bits() {
+		Some(u64::MAX)
+	} else if (0.0..TWO_POW_64).contains(&value) && value.fract() == 0.0 {
+		Some(value as u64)
+	} else {
+		None
+	}
+}
+
 /// A duplicate representation of an Account for pretty JSON serialization
 #[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, TypedBuilder)]
 #[serde(rename_all = "camelCase")]
 pub struct UiAccount {
+	#[serde_as(as = "LenientU64")]
 	pub lamports: u64,
 	pub data: UiAccountData,
 	#[serde_as(as = "DisplayFromStr")]
 	pub owner: Pubkey,
 	#[builder(setter(into, strip_bool(fallback = executable_bool)))]
 	pub executable: bool,
+	/// Accounts are rent exempt when this is left out by the rpc.
+	#[serde(default = "rent_exempt_epoch")]
+	#[serde_as(as = "LenientU64")]
 	pub rent_epoch: u64,
+	#[serde_as(as = "Option<NumberOrString>")]
 	#[builder(default, setter(into, strip_option(fallback = space_opt)))]
 	pub space: Option<u64>,
 }
 
+fn rent_exempt_epoch() -> u64 {
+	u64::MAX
+}
+
+/// A [`UiAccount`] which serializes its `u64` fields as strings, returned by
+/// [`UiAccount::with_string_numbers`].
+#[serde_as]
+#[skip_serializing_none]
+#[derive(Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAccountWithStringNumbers<'a> {
+	#[serde_as(as = "DisplayFromStr")]
+	lamports: u64,
+	data: &'a UiAccountData,
+	#[serde_as(as = "DisplayFromStr")]
+	owner: Pubkey,
+	executable: bool,
+	#[serde_as(as = "DisplayFromStr")]
+	rent_epoch: u64,
+	#[serde_as(as = "Option<DisplayFromStr>")]
+	space: Option<u64>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum UiAccountData {
@@ -45,32 +181,106 @@ pub enum UiAccountData {
 impl UiAccountData {
 	/// Returns decoded account data in binary format if possible
 	pub fn decode(&self) -> Option<Vec<u8>> {
+		self.try_decode().ok()
+	}
+
+	/// Returns the decoded account data or the reason it couldn't be decoded.
+	pub fn try_decode(&self) -> Result<Vec<u8>, UiAccountDecodeError> {
 		match self {
-			UiAccountData::Json(_) => None,
-			UiAccountData::LegacyBinary(blob) => bs58::decode(blob).into_vec().ok(),
+			UiAccountData::Json(_) => {
+				Err(UiAccountDecodeError::UnsupportedEncoding(
+					UiAccountEncoding::JsonParsed,
+				))
+			}
+			UiAccountData::LegacyBinary(blob) => decode_base58(blob),
 			UiAccountData::Binary(blob, encoding) => {
 				match encoding {
-					UiAccountEncoding::Base58 => bs58::decode(blob).into_vec().ok(),
-					UiAccountEncoding::Base64 => BASE64_STANDARD.decode(blob).ok(),
+					UiAccountEncoding::Base58 => decode_base58(blob),
+					UiAccountEncoding::Base64 => decode_base64(blob),
 					#[cfg(feature = "zstd")]
 					UiAccountEncoding::Base64Zstd => {
-						BASE64_STANDARD.decode(blob).ok().and_then(|zstd_data| {
-							let mut data = vec![];
-							zstd::stream::read::Decoder::new(zstd_data.as_slice())
-								.and_then(|mut reader| reader.read_to_end(&mut data))
-								.map(|_| data)
-								.ok()
-						})
+						let zstd_data = decode_base64(blob)?;
+						let mut data = vec![];
+						zstd::stream::read::Decoder::new(zstd_data.as_slice())
+							.and_then(|mut reader| reader.read_to_end(&mut data))
+							.map_err(|error| {
+								UiAccountDecodeError::ZstdDecompression(error.to_string())
+							})?;
+						Ok(data)
 					}
 					#[cfg(not(feature = "zstd"))]
-					UiAccountEncoding::Base64Zstd => None,
-					UiAccountEncoding::Binary | UiAccountEncoding::JsonParsed => None,
+					UiAccountEncoding::Base64Zstd => Err(UiAccountDecodeError::ZstdFeatureDisabled),
+					UiAccountEncoding::Binary | UiAccountEncoding::JsonParsed => {
+						Err(UiAccountDecodeError::UnsupportedEncoding(*encoding))
+					}
 				}
 			}
 		}
 	}
 }
 
+fn decode_base58(blob: &str) -> Result<Vec<u8>, UiAccountDecodeError> {
+	if blob.len() > MAX_BASE58_LENGTH {
+		return Err(UiAccountDecodeError::Base58TooLong(blob.len()));
+	}
+
+	bs58::decode(blob)
+		.into_vec()
+		.map_err(|_| UiAccountDecodeError::InvalidBase58)
+}
+
+fn decode_base64(blob: &str) -> Result<Vec<u8>, UiAccountDecodeError> {
+	BASE64_STANDARD
+		.decode(blob)
+		.map_err(|_| UiAccountDecodeError::InvalidBase64)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Error)]
+pub enum UiAccountDecodeError {
+	#[error("account data with the {0:?} encoding can't be decoded to binary")]
+	UnsupportedEncoding(UiAccountEncoding),
+	#[error("account data is not valid base58")]
+	InvalidBase58,
+	#[error(
+		"base58 account data of length {0} is longer than the rpc returns for {MAX_BASE58_BYTES} \
+		 bytes"
+	)]
+	Base58TooLong(usize),
+	#[error("account data is not valid base64")]
+	InvalidBase64,
+	#[error("account data could not be decompressed: {0}")]
+	ZstdDecompression(String),
+	#[error("`base64+zstd` account data requires the `zstd` feature")]
+	ZstdFeatureDisabled,
+}
+
+/// The error returned when typed data can't be read from a [`UiAccount`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Error)]
+pub enum UiAccountDeserializeError {
+	#[error(transparent)]
+	Decode(#[from] UiAccountDecodeError),
+	#[error("account discriminator mismatch: expected {expected:?}, found {actual:?}")]
+	DiscriminatorMismatch { expected: Vec<u8>, actual: Vec<u8> },
+	#[error("account data is too short for the requested type")]
+	TruncatedData,
+	#[error("account data could not be deserialized: {0}")]
+	Deserialize(String),
+}
+
+impl From<std::io::Error> for UiAccountDeserializeError {
+	fn from(error: std::io::Error) -> Self {
+		// borsh reports running out of input with this message rather than an
+		// `UnexpectedEof` error kind.
+		if error.kind() == std::io::ErrorKind::UnexpectedEof
+			|| error.to_string() == "Unexpected length of input"
+		{
+			Self::TruncatedData
+		} else {
+			Self::Deserialize(error.to_string())
+		}
+	}
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub enum UiAccountEncoding {
@@ -82,10 +292,158 @@ pub enum UiAccountEncoding {
 	Base64Zstd,
 }
 
+impl UiAccountEncoding {
+	/// The name of the encoding used by the RPC.
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::Binary => "binary",
+			Self::Base58 => "base58",
+			Self::Base64 => "base64",
+			Self::JsonParsed => "jsonParsed",
+			Self::Base64Zstd => "base64+zstd",
+		}
+	}
+}
+
+impl fmt::Display for UiAccountEncoding {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for UiAccountEncoding {
+	type Err = ParseEnumError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"binary" => Ok(Self::Binary),
+			"base58" => Ok(Self::Base58),
+			"base64" => Ok(Self::Base64),
+			"jsonParsed" => Ok(Self::JsonParsed),
+			"base64+zstd" => Ok(Self::Base64Zstd),
+			_ => Err(ParseEnumError::new("account encoding", s)),
+		}
+	}
+}
+
+/// The error returned when a string doesn't match the RPC name of any
+/// variant of an enum.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unknown {kind} `{value}`")]
+pub struct ParseEnumError {
+	pub kind: &'static str,
+	pub value: String,
+}
+
+impl ParseEnumError {
+	pub fn new(kind: &'static str, value: impl Into<String>) -> Self {
+		Self {
+			kind,
+			value: value.into(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum UiAccountEncodeError {
+	#[error(
+		"encoded binary (base 58) data should be less than {MAX_BASE58_BYTES} bytes, received {0} \
+		 bytes, please use base64 encoding"
+	)]
+	Base58DataTooLarge(usize),
+	#[error("`jsonParsed` encoding requires an account parser, use base64 encoding instead")]
+	JsonParsedUnsupported,
+	#[error("`base64+zstd` encoding requires the `zstd` feature")]
+	ZstdUnsupported,
+}
+
 impl UiAccount {
+	/// Serialize the account with `lamports`, `rentEpoch` and `space` as
+	/// strings.
+	///
+	/// Javascript reads json numbers into a double, which rounds a
+	/// `rentEpoch` of `u64::MAX` up to `2^64`. The strings are read back by
+	/// the [`UiAccount`] deserializer.
+	pub fn with_string_numbers(&self) -> UiAccountWithStringNumbers<'_> {
+		UiAccountWithStringNumbers {
+			lamports: self.lamports,
+			data: &self.data,
+			owner: self.owner,
+			executable: self.executable,
+			rent_epoch: self.rent_epoch,
+			space: self.space,
+		}
+	}
+
+	/// Encode an account in the same way the RPC encodes the accounts it
+	/// returns. This is the inverse of [`UiAccount::decode`].
+	///
+	/// The `data_slice` is applied before encoding and an `offset` beyond the
+	/// end of the data results in empty data. The `space` is always the length
+	/// of the full account data.
+	///
+	/// The `pubkey` is unused by the binary encodings and `jsonParsed` isn't
+	/// supported since it needs the account parsers from
+	/// `solana-account-decoder-wasm`.
+	pub fn encode<T: ReadableAccount>(
+		_pubkey: &Pubkey,
+		account: &T,
+		encoding: UiAccountEncoding,
+		data_slice: Option<UiDataSliceConfig>,
+	) -> Result<Self, UiAccountEncodeError> {
+		let data = data_slice.map_or(account.data(), |data_slice| {
+			data_slice.slice(account.data())
+		});
+
+		if matches!(
+			encoding,
+			UiAccountEncoding::Binary | UiAccountEncoding::Base58
+		) && data.len() > MAX_BASE58_BYTES
+		{
+			return Err(UiAccountEncodeError::Base58DataTooLarge(data.len()));
+		}
+
+		let data = encode_data(data, encoding)?;
+
+		Ok(Self {
+			lamports: account.lamports(),
+			data,
+			owner: *account.owner(),
+			executable: account.executable(),
+			rent_epoch: account.rent_epoch(),
+			space: Some(account.data().len() as u64),
+		})
+	}
+
 	pub fn decode<T: WritableAccount>(&self) -> Option<T> {
-		let data = self.data.decode()?;
-		Some(T::create(
+		self.try_decode().ok()
+	}
+
+	/// Trim the data of an already fetched account as if it had been requested
+	/// with `data_slice`. The data keeps its encoding and `space` keeps the
+	/// length of the full account data.
+	pub fn sliced(self, data_slice: UiDataSliceConfig) -> Result<Self, UiAccountDecodeError> {
+		let encoding = match &self.data {
+			UiAccountData::LegacyBinary(_) => UiAccountEncoding::Binary,
+			UiAccountData::Json(_) => UiAccountEncoding::JsonParsed,
+			UiAccountData::Binary(_, encoding) => *encoding,
+		};
+		let data = self.data.try_decode()?;
+		let space = self.space.unwrap_or(data.len() as u64);
+		let data = encode_data(data_slice.slice(&data), encoding)
+			.map_err(|_| UiAccountDecodeError::UnsupportedEncoding(encoding))?;
+
+		Ok(Self {
+			data,
+			space: Some(space),
+			..self
+		})
+	}
+
+	/// Decode the account or return the reason the data couldn't be decoded.
+	pub fn try_decode<T: WritableAccount>(&self) -> Result<T, UiAccountDecodeError> {
+		let data = self.data.try_decode()?;
+		Ok(T::create(
 			self.lamports,
 			data,
 			self.owner,
@@ -93,6 +451,34 @@ impl UiAccount {
 			self.rent_epoch,
 		))
 	}
+
+	/// Deserialize the account data as borsh. Trailing bytes are ignored since
+	/// accounts are often allocated with more space than the value needs.
+	pub fn decode_borsh<T: BorshDeserialize>(&self) -> Result<T, UiAccountDeserializeError> {
+		let data = self.data.try_decode()?;
+		Ok(T::deserialize(&mut data.as_slice())?)
+	}
+
+	/// Deserialize an anchor account after checking that the data starts with
+	/// the discriminator of `T`.
+	#[cfg(feature = "anchor")]
+	pub fn decode_anchor<T: AnchorDeserialize + Discriminator>(
+		&self,
+	) -> Result<T, UiAccountDeserializeError> {
+		let data = self.data.try_decode()?;
+		let Some((actual, mut rest)) = data.split_at_checked(T::DISCRIMINATOR.len()) else {
+			return Err(UiAccountDeserializeError::TruncatedData);
+		};
+
+		if actual != T::DISCRIMINATOR {
+			return Err(UiAccountDeserializeError::DiscriminatorMismatch {
+				expected: T::DISCRIMINATOR.to_vec(),
+				actual: actual.to_vec(),
+			});
+		}
+
+		Ok(T::deserialize(&mut rest)?)
+	}
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -109,3 +495,473 @@ pub struct UiDataSliceConfig {
 	pub offset: usize,
 	pub length: usize,
 }
+
+impl UiDataSliceConfig {
+	/// Apply the slice in the same way as the RPC. An `offset` past the end of
+	/// the data yields no data and the `length` is clamped to the bytes that
+	/// remain after the `offset`.
+	pub fn slice<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+		let data = data.get(self.offset..).unwrap_or_default();
+		&data[..self.length.min(data.len())]
+	}
+}
+
+/// Encode raw data without applying the `base58` size limit.
+fn encode_data(
+	data: &[u8],
+	encoding: UiAccountEncoding,
+) -> Result<UiAccountData, UiAccountEncodeError> {
+	let data = match encoding {
+		UiAccountEncoding::Binary => UiAccountData::LegacyBinary(bs58::encode(data).into_string()),
+		UiAccountEncoding::Base58 => {
+			UiAccountData::Binary(bs58::encode(data).into_string(), encoding)
+		}
+		UiAccountEncoding::Base64 => UiAccountData::Binary(BASE64_STANDARD.encode(data), encoding),
+		#[cfg(feature = "zstd")]
+		UiAccountEncoding::Base64Zstd => {
+			// fall back to base64 like the rpc when compression fails
+			match zstd::stream::encode_all(data, 0) {
+				Ok(zstd_data) => UiAccountData::Binary(BASE64_STANDARD.encode(zstd_data), encoding),
+				Err(_) => {
+					UiAccountData::Binary(BASE64_STANDARD.encode(data), UiAccountEncoding::Base64)
+				}
+			}
+		}
+		#[cfg(not(feature = "zstd"))]
+		UiAccountEncoding::Base64Zstd => return Err(UiAccountEncodeError::ZstdUnsupported),
+		UiAccountEncoding::JsonParsed => return Err(UiAccountEncodeError::JsonParsedUnsupported),
+	};
+
+	Ok(data)
+}
+
+#[cfg(test)]
+mod test {
+	use rand::Rng;
+	use rand::SeedableRng;
+	use rand::rngs::StdRng;
+	use solana_account::Account;
+
+	use super::*;
+
+	#[test]
+	fn test_account_encoding_from_str() {
+		for encoding in [
+			UiAccountEncoding::Binary,
+			UiAccountEncoding::Base58,
+			UiAccountEncoding::Base64,
+			UiAccountEncoding::JsonParsed,
+			UiAccountEncoding::Base64Zstd,
+		] {
+			let name = encoding.to_string();
+			assert_eq!(serde_json::to_value(encoding).unwrap(), name.as_str());
+			assert_eq!(name.parse::<UiAccountEncoding>(), Ok(encoding));
+		}
+
+		assert_eq!(
+			"Base64".parse::<UiAccountEncoding>(),
+			Err(ParseEnumError::new("account encoding", "Base64"))
+		);
+	}
+
+	const OWNER: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+	#[test]
+	fn test_rent_epoch_float_forms() {
+		// These payloads weren't captured from a provider. Each one is rebuilt
+		// from what the named serializer writes for `u64::MAX` once it has been
+		// read as a double.
+		let payloads = [
+			// `JSON.stringify` in javascript writes the double in full.
+			r#"{"lamports":2039280,"data":["","base64"],"owner":"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA","executable":false,"rentEpoch":18446744073709552000,"space":0}"#,
+			// `serde_json` writes every `f64` in the shortest form, which uses
+			// an exponent for large values.
+			r#"{"lamports":2039280.0,"data":["","base64"],"owner":"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA","executable":false,"rentEpoch":1.8446744073709552e19,"space":0}"#,
+			// `json.dumps` in python writes the exponent with a sign.
+			r#"{"lamports":2039280.0,"data":["","base64"],"owner":"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA","executable":false,"rentEpoch":1.8446744073709552e+19,"space":0}"#,
+			// Proxies which quote large integers keep the exact value.
+			r#"{"lamports":"2039280","data":["","base64"],"owner":"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA","executable":false,"rentEpoch":"18446744073709551615","space":0}"#,
+			// Proxies which quote a value after reading it as a double.
+			r#"{"lamports":2039280,"data":["","base64"],"owner":"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA","executable":false,"rentEpoch":"1.8446744073709552e19","space":0}"#,
+			// The node itself writes the exact integer.
+			r#"{"lamports":2039280,"data":["","base64"],"owner":"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA","executable":false,"rentEpoch":18446744073709551615,"space":0}"#,
+		];
+
+		assert_eq!(
+			serde_json::to_string(&(u64::MAX as f64)).unwrap(),
+			"1.8446744073709552e19"
+		);
+
+		for payload in payloads {
+			let account: UiAccount = serde_json::from_str(payload).unwrap();
+
+			assert_eq!(account.lamports, 2_039_280, "{payload}");
+			assert_eq!(account.rent_epoch, u64::MAX, "{payload}");
+			assert_eq!(
+				serde_json::to_value(&account).unwrap()["rentEpoch"],
+				serde_json::json!(u64::MAX)
+			);
+		}
+	}
+
+	#[test]
+	fn test_rent_epoch_invalid_forms() {
+		for rent_epoch in ["-1", "1.5", "3.7e19", "\"max\"", "\"-1\""] {
+			let payload = format!(
+				r#"{{"lamports":1,"data":["","base64"],"owner":"{OWNER}","executable":false,"rentEpoch":{rent_epoch}}}"#
+			);
+
+			assert!(
+				serde_json::from_str::<UiAccount>(&payload).is_err(),
+				"{rent_epoch}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_with_string_numbers() {
+		let account = UiAccount::builder()
+			.lamports(u64::MAX - 1)
+			.data(UiAccountData::Binary(
+				String::new(),
+				UiAccountEncoding::Base64,
+			))
+			.owner(OWNER.parse::<Pubkey>().unwrap())
+			.rent_epoch(u64::MAX)
+			.space(0_u64)
+			.build();
+		let value = serde_json::to_value(account.with_string_numbers()).unwrap();
+
+		assert_eq!(value["lamports"], "18446744073709551614");
+		assert_eq!(value["rentEpoch"], "18446744073709551615");
+		assert_eq!(value["space"], "0");
+		assert_eq!(value["owner"], OWNER);
+		assert_eq!(serde_json::from_value::<UiAccount>(value).unwrap(), account);
+	}
+
+	fn account(size: usize) -> Account {
+		Account {
+			lamports: 42,
+			data: (0..size).map(|byte| (byte % 251) as u8).collect(),
+			owner: Pubkey::new_unique(),
+			executable: false,
+			rent_epoch: 7,
+		}
+	}
+
+	#[test]
+	fn encode_decode_round_trip() {
+		let pubkey = Pubkey::new_unique();
+		let account = account(100);
+		let mut encodings = vec![
+			UiAccountEncoding::Binary,
+			UiAccountEncoding::Base58,
+			UiAccountEncoding::Base64,
+		];
+
+		if cfg!(feature = "zstd") {
+			encodings.push(UiAccountEncoding::Base64Zstd);
+		}
+
+		for encoding in encodings {
+			let ui_account = UiAccount::encode(&pubkey, &account, encoding, None).unwrap();
+			assert_eq!(ui_account.space, Some(100));
+			assert_eq!(ui_account.decode::<Account>(), Some(account.clone()));
+
+			for (offset, length, expected) in [
+				(10, 20, &account.data[10..30]),
+				(90, 20, &account.data[90..]),
+				(100, 5, &[][..]),
+				(500, 5, &[][..]),
+			] {
+				let data_slice = UiDataSliceConfig { offset, length };
+				let ui_account =
+					UiAccount::encode(&pubkey, &account, encoding, Some(data_slice)).unwrap();
+				let decoded = ui_account.decode::<Account>().unwrap();
+				assert_eq!(decoded.data, expected);
+				assert_eq!(ui_account.space, Some(100));
+			}
+		}
+	}
+
+	#[test]
+	fn encode_base58_limit() {
+		let pubkey = Pubkey::new_unique();
+		let account = account(MAX_BASE58_BYTES + 1);
+
+		for encoding in [UiAccountEncoding::Binary, UiAccountEncoding::Base58] {
+			assert_eq!(
+				UiAccount::encode(&pubkey, &account, encoding, None),
+				Err(UiAccountEncodeError::Base58DataTooLarge(
+					MAX_BASE58_BYTES + 1
+				))
+			);
+
+			let data_slice = UiDataSliceConfig {
+				offset: 0,
+				length: MAX_BASE58_BYTES,
+			};
+			assert!(UiAccount::encode(&pubkey, &account, encoding, Some(data_slice)).is_ok());
+		}
+
+		assert_eq!(
+			UiAccount::encode(&pubkey, &account, UiAccountEncoding::JsonParsed, None),
+			Err(UiAccountEncodeError::JsonParsedUnsupported)
+		);
+	}
+
+	#[test]
+	fn try_decode_errors() {
+		let json = UiAccountData::Json(ParsedAccount {
+			program: "nonce".into(),
+			parsed: Value::Null,
+			space: 0,
+		});
+		assert_eq!(
+			json.try_decode(),
+			Err(UiAccountDecodeError::UnsupportedEncoding(
+				UiAccountEncoding::JsonParsed
+			))
+		);
+		assert_eq!(
+			UiAccountData::Binary("AQID".into(), UiAccountEncoding::Binary).try_decode(),
+			Err(UiAccountDecodeError::UnsupportedEncoding(
+				UiAccountEncoding::Binary
+			))
+		);
+		assert_eq!(
+			UiAccountData::LegacyBinary("0OIl".into()).try_decode(),
+			Err(UiAccountDecodeError::InvalidBase58)
+		);
+		assert_eq!(
+			UiAccountData::Binary("not base64!".into(), UiAccountEncoding::Base64).try_decode(),
+			Err(UiAccountDecodeError::InvalidBase64)
+		);
+		assert_eq!(
+			UiAccountData::Binary("AQID".into(), UiAccountEncoding::Base64).try_decode(),
+			Ok(vec![1, 2, 3])
+		);
+
+		let zstd = UiAccountData::Binary("AQID".into(), UiAccountEncoding::Base64Zstd);
+		if cfg!(feature = "zstd") {
+			assert!(matches!(
+				zstd.try_decode(),
+				Err(UiAccountDecodeError::ZstdDecompression(_))
+			));
+		} else {
+			assert_eq!(
+				zstd.try_decode(),
+				Err(UiAccountDecodeError::ZstdFeatureDisabled)
+			);
+		}
+
+		let ui_account = UiAccount::builder()
+			.lamports(1)
+			.data(json)
+			.owner(Pubkey::new_unique())
+			.executable_bool(false)
+			.rent_epoch(0)
+			.build();
+		assert!(ui_account.try_decode::<Account>().is_err());
+	}
+
+	fn borsh_account(data: &[u8]) -> UiAccount {
+		UiAccount::builder()
+			.lamports(1)
+			.data(UiAccountData::Binary(
+				BASE64_STANDARD.encode(data),
+				UiAccountEncoding::Base64,
+			))
+			.owner(Pubkey::new_unique())
+			.executable_bool(false)
+			.rent_epoch(0)
+			.build()
+	}
+
+	#[test]
+	fn decode_borsh() {
+		let mut data = borsh::to_vec(&(7u64, [3u8; 32])).unwrap();
+		data.extend([0; 16]);
+		let ui_account = borsh_account(&data);
+
+		assert_eq!(
+			ui_account.decode_borsh::<(u64, [u8; 32])>(),
+			Ok((7, [3; 32]))
+		);
+		assert_eq!(
+			borsh_account(&data[..20]).decode_borsh::<(u64, [u8; 32])>(),
+			Err(UiAccountDeserializeError::TruncatedData)
+		);
+		assert_eq!(
+			borsh_account(&[2]).decode_borsh::<bool>(),
+			Err(UiAccountDeserializeError::Deserialize(
+				"Invalid bool representation: 2".into()
+			))
+		);
+	}
+
+	#[cfg(feature = "anchor")]
+	#[test]
+	fn decode_anchor() {
+		#[derive(Debug, PartialEq)]
+		struct Counter(u64);
+
+		impl AnchorDeserialize for Counter {
+			fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+				<u64 as AnchorDeserialize>::deserialize_reader(reader).map(Self)
+			}
+		}
+
+		impl Discriminator for Counter {
+			const DISCRIMINATOR: &'static [u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+		}
+
+		let mut data = Counter::DISCRIMINATOR.to_vec();
+		data.extend(5u64.to_le_bytes());
+
+		assert_eq!(
+			borsh_account(&data).decode_anchor::<Counter>(),
+			Ok(Counter(5))
+		);
+		assert_eq!(
+			borsh_account(&data[..4]).decode_anchor::<Counter>(),
+			Err(UiAccountDeserializeError::TruncatedData)
+		);
+		assert_eq!(
+			borsh_account(&data[..12]).decode_anchor::<Counter>(),
+			Err(UiAccountDeserializeError::TruncatedData)
+		);
+
+		data[0] = 0;
+		assert_eq!(
+			borsh_account(&data).decode_anchor::<Counter>(),
+			Err(UiAccountDeserializeError::DiscriminatorMismatch {
+				expected: Counter::DISCRIMINATOR.to_vec(),
+				actual: vec![0, 2, 3, 4, 5, 6, 7, 8],
+			})
+		);
+	}
+
+	/// The slicing done by the rpc node.
+	fn reference_slice(data: &[u8], offset: usize, length: usize) -> &[u8] {
+		if offset >= data.len() {
+			&[]
+		} else if length > data.len() - offset {
+			&data[offset..]
+		} else {
+			&data[offset..offset + length]
+		}
+	}
+
+	#[test]
+	fn data_slice_matches_rpc() {
+		let mut rng = StdRng::seed_from_u64(0);
+
+		for _ in 0..10_000 {
+			let data: Vec<u8> = (0..rng.random_range(0..64)).map(|_| rng.random()).collect();
+			let offset = rng.random_range(0..96);
+			let length = if rng.random_bool(0.05) {
+				usize::MAX
+			} else {
+				rng.random_range(0..96)
+			};
+			let data_slice = UiDataSliceConfig { offset, length };
+
+			assert_eq!(
+				data_slice.slice(&data),
+				reference_slice(&data, offset, length),
+				"offset: {offset}, length: {length}, data length: {}",
+				data.len()
+			);
+		}
+	}
+
+	#[test]
+	fn base58_length_is_checked_before_decoding() {
+		let largest = vec![255; MAX_BASE58_BYTES];
+		let blob = bs58::encode(&largest).into_string();
+
+		assert!(blob.len() <= MAX_BASE58_LENGTH);
+		assert_eq!(UiAccountData::LegacyBinary(blob).try_decode(), Ok(largest));
+
+		let zeros = "1".repeat(MAX_BASE58_BYTES);
+		assert_eq!(
+			UiAccountData::LegacyBinary(zeros).try_decode(),
+			Ok(vec![0; MAX_BASE58_BYTES])
+		);
+
+		let pasted = bs58::encode(vec![7; 1024]).into_string();
+		assert_eq!(
+			UiAccountData::Binary(pasted.clone(), UiAccountEncoding::Base58).try_decode(),
+			Err(UiAccountDecodeError::Base58TooLong(pasted.len()))
+		);
+	}
+
+	/// Random strings, mostly of `base58` and `base64` characters, never panic
+	/// and long `base58` strings are always rejected by length.
+	#[test]
+	fn fuzz_try_decode() {
+		const CHARACTERS: &[u8] =
+			b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz+/=0OIl \0\xff";
+		let mut rng = StdRng::seed_from_u64(409);
+
+		for _ in 0..2_000 {
+			let length = rng.random_range(0..4_096);
+			let blob: String = (0..length)
+				.map(|_| char::from(CHARACTERS[rng.random_range(0..CHARACTERS.len())]))
+				.collect();
+
+			for encoding in [
+				UiAccountEncoding::Binary,
+				UiAccountEncoding::Base58,
+				UiAccountEncoding::Base64,
+				UiAccountEncoding::JsonParsed,
+				UiAccountEncoding::Base64Zstd,
+			] {
+				let _ = UiAccountData::Binary(blob.clone(), encoding).try_decode();
+			}
+
+			let result = UiAccountData::LegacyBinary(blob.clone()).try_decode();
+
+			if blob.len() > MAX_BASE58_LENGTH {
+				assert_eq!(result, Err(UiAccountDecodeError::Base58TooLong(blob.len())));
+			}
+		}
+	}
+
+	#[test]
+	fn sliced_account() {
+		let pubkey = Pubkey::new_unique();
+		let account = account(100);
+		let data_slice = UiDataSliceConfig {
+			offset: 10,
+			length: 20,
+		};
+
+		for encoding in [UiAccountEncoding::Base58, UiAccountEncoding::Base64] {
+			let full = UiAccount::encode(&pubkey, &account, encoding, None).unwrap();
+			let sliced = full.sliced(data_slice).unwrap();
+
+			assert_eq!(
+				sliced,
+				UiAccount::encode(&pubkey, &account, encoding, Some(data_slice)).unwrap()
+			);
+			assert_eq!(sliced.space, Some(100));
+		}
+
+		let json = UiAccount {
+			data: UiAccountData::Json(ParsedAccount {
+				program: "nonce".into(),
+				parsed: Value::Null,
+				space: 0,
+			}),
+			..UiAccount::encode(&pubkey, &account, UiAccountEncoding::Base64, None).unwrap()
+		};
+		assert_eq!(
+			json.sliced(data_slice),
+			Err(UiAccountDecodeError::UnsupportedEncoding(
+				UiAccountEncoding::JsonParsed
+			))
+		);
+	}
+}
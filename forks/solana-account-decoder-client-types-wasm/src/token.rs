@@ -2,6 +2,9 @@ use core::str::FromStr;
 
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
+use serde_with::DisplayFromStr;
+use serde_with::serde_as;
+use solana_pubkey::Pubkey;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -15,6 +18,50 @@ pub struct UiTokenAmount {
 impl Eq for UiTokenAmount {}
 
 impl UiTokenAmount {
+	/// Create the amount the RPC returns for a raw token `amount` of a mint
+	/// without interest or scaling extensions.
+	pub fn from_raw(amount: u64, decimals: u8) -> Self {
+		// The node computes this with a 64 bit `usize` so `u64` keeps the
+		// result identical on 32 bit targets.
+		let ui_amount = 10_u64
+			.checked_pow(u32::from(decimals))
+			.map(|dividend| amount as f64 / dividend as f64);
+
+		Self {
+			ui_amount,
+			decimals,
+			amount: amount.to_string(),
+			ui_amount_string: real_number_string_trimmed(amount, decimals),
+		}
+	}
+
+	/// The raw token amount parsed from the `amount` string.
+	pub fn raw(&self) -> Option<u128> {
+		self.amount.parse().ok()
+	}
+
+	/// Add two amounts of the same mint. Returns `None` when the `decimals`
+	/// differ or the sum overflows a `u64`.
+	pub fn checked_add(&self, other: &Self) -> Option<Self> {
+		let (left, right) = self.raw_pair(other)?;
+		Some(Self::from_raw(left.checked_add(right)?, self.decimals))
+	}
+
+	/// Subtract `other` from this amount. Returns `None` when the `decimals`
+	/// differ or the result would be negative.
+	pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+		let (left, right) = self.raw_pair(other)?;
+		Some(Self::from_raw(left.checked_sub(right)?, self.decimals))
+	}
+
+	fn raw_pair(&self, other: &Self) -> Option<(u64, u64)> {
+		if self.decimals != other.decimals {
+			return None;
+		}
+
+		Some((self.amount.parse().ok()?, other.amount.parse().ok()?))
+	}
+
 	pub fn real_number_string(&self) -> String {
 		real_number_string(
 			u64::from_str(&self.amount).unwrap_or_default(),
@@ -32,6 +79,28 @@ impl UiTokenAmount {
 			)
 		}
 	}
+
+	/// Recalculate the ui amount of a token from an interest bearing mint at
+	/// the provided `unix_timestamp`.
+	///
+	/// The raw `amount` is left untouched since interest only changes the
+	/// displayed value. Returns `None` when the amount can't be parsed or the
+	/// calculation overflows.
+	pub fn apply_interest(
+		&self,
+		config: &UiInterestBearingConfig,
+		unix_timestamp: i64,
+	) -> Option<Self> {
+		let amount = u64::from_str(&self.amount).ok()?;
+		let ui_amount_string = config.amount_to_ui_amount(amount, self.decimals, unix_timestamp)?;
+
+		Some(Self {
+			ui_amount: f64::from_str(&ui_amount_string).ok(),
+			decimals: self.decimals,
+			amount: self.amount.clone(),
+			ui_amount_string,
+		})
+	}
 }
 
 #[allow(clippy::arithmetic_side_effects)]
@@ -49,12 +118,15 @@ pub fn real_number_string(amount: u64, decimals: u8) -> String {
 }
 
 pub fn real_number_string_trimmed(amount: u64, decimals: u8) -> String {
-	let mut s = real_number_string(amount, decimals);
+	trim_ui_amount_string(real_number_string(amount, decimals), decimals)
+}
+
+fn trim_ui_amount_string(mut ui_amount: String, decimals: u8) -> String {
 	if decimals > 0 {
-		let zeros_trimmed = s.trim_end_matches('0');
-		s = zeros_trimmed.trim_end_matches('.').to_string();
+		let zeros_trimmed = ui_amount.trim_end_matches('0');
+		ui_amount = zeros_trimmed.trim_end_matches('.').to_string();
 	}
-	s
+	ui_amount
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -66,22 +138,29 @@ pub enum TokenAccountType {
 	Multisig(UiMultisig),
 }
 
+/// A token account in the shape returned by the `jsonParsed` encoding.
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiTokenAccount {
-	pub mint: String,
-	pub owner: String,
+	#[serde_as(as = "DisplayFromStr")]
+	pub mint: Pubkey,
+	#[serde_as(as = "DisplayFromStr")]
+	pub owner: Pubkey,
 	pub token_amount: UiTokenAmount,
+	#[serde_as(as = "Option<DisplayFromStr>")]
 	#[serde(skip_serializing_if = "Option::is_none")]
-	pub delegate: Option<String>,
+	pub delegate: Option<Pubkey>,
 	pub state: UiAccountState,
 	pub is_native: bool,
+	/// The rent exempt reserve of a native (wrapped SOL) account.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub rent_exempt_reserve: Option<UiTokenAmount>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub delegated_amount: Option<UiTokenAmount>,
+	#[serde_as(as = "Option<DisplayFromStr>")]
 	#[serde(skip_serializing_if = "Option::is_none")]
-	pub close_authority: Option<String>,
+	pub close_authority: Option<Pubkey>,
 	#[serde(skip_serializing_if = "Vec::is_empty", default)]
 	pub extensions: Vec<UiExtension>,
 }
@@ -126,6 +205,17 @@ pub enum UiExtension {
 	ScaledUiAmountConfig(UiScaledUiAmountConfig),
 	PausableConfig(UiPausableConfig),
 	PausableAccount,
+	/// An extension type which isn't known to this version of the decoder.
+	Unknown(UiUnknownExtension),
+}
+
+/// The raw TLV entry of an unrecognized extension.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiUnknownExtension {
+	pub extension_type: u16,
+	/// The `base64` encoded extension data.
+	pub data: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -210,6 +300,38 @@ pub struct UiInterestBearingConfig {
 	pub current_rate: i16,
 }
 
+/// Seconds in a year as defined by the token-2022 interest bearing extension.
+const SECONDS_PER_YEAR: f64 = 60. * 60. * 24. * 365.24;
+const ONE_IN_BASIS_POINTS: f64 = 10_000.;
+
+impl UiInterestBearingConfig {
+	/// Convert a raw `amount` into its ui representation including the interest
+	/// accrued by `unix_timestamp`. Interest is compounded continuously in the
+	/// same way as the token-2022 program.
+	pub fn amount_to_ui_amount(
+		&self,
+		amount: u64,
+		decimals: u8,
+		unix_timestamp: i64,
+	) -> Option<String> {
+		let pre_update_timespan = self
+			.last_update_timestamp
+			.checked_sub(self.initialization_timestamp)?;
+		let post_update_timespan = unix_timestamp.checked_sub(self.last_update_timestamp)?;
+		let total_scale = interest_exp(self.pre_update_average_rate, pre_update_timespan)
+			* interest_exp(self.current_rate, post_update_timespan)
+			/ 10_f64.powi(i32::from(decimals));
+		let ui_amount = format!("{:.*}", usize::from(decimals), amount as f64 * total_scale);
+
+		Some(trim_ui_amount_string(ui_amount, decimals))
+	}
+}
+
+fn interest_exp(rate: i16, timespan: i64) -> f64 {
+	let numerator = i128::from(rate) * i128::from(timespan);
+	(numerator as f64 / SECONDS_PER_YEAR / ONE_IN_BASIS_POINTS).exp()
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiMemoTransfer {
@@ -303,25 +425,45 @@ pub struct UiConfidentialMintBurn {
 	pub pending_burn: String,
 }
 
+/// A token mint in the shape returned by the `jsonParsed` encoding.
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiMint {
-	pub mint_authority: Option<String>,
+	#[serde_as(as = "Option<DisplayFromStr>")]
+	pub mint_authority: Option<Pubkey>,
 	pub supply: String,
 	pub decimals: u8,
 	pub is_initialized: bool,
-	pub freeze_authority: Option<String>,
+	#[serde_as(as = "Option<DisplayFromStr>")]
+	pub freeze_authority: Option<Pubkey>,
 	#[serde(skip_serializing_if = "Vec::is_empty", default)]
 	pub extensions: Vec<UiExtension>,
 }
 
+impl UiMint {
+	/// The interest bearing config of a token-2022 mint which can be passed to
+	/// [`UiTokenAmount::apply_interest`].
+	pub fn interest_bearing_config(&self) -> Option<&UiInterestBearingConfig> {
+		self.extensions.iter().find_map(|extension| {
+			match extension {
+				UiExtension::InterestBearingConfig(config) => Some(config),
+				_ => None,
+			}
+		})
+	}
+}
+
+/// A token multisig in the shape returned by the `jsonParsed` encoding.
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiMultisig {
 	pub num_required_signers: u8,
 	pub num_valid_signers: u8,
 	pub is_initialized: bool,
-	pub signers: Vec<String>,
+	#[serde_as(as = "Vec<DisplayFromStr>")]
+	pub signers: Vec<Pubkey>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
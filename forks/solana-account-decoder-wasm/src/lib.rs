@@ -133,17 +133,7 @@ impl Default for UiFeeCalculator {
 }
 
 fn slice_data(data: &[u8], data_slice_config: Option<UiDataSliceConfig>) -> &[u8] {
-	if let Some(UiDataSliceConfig { offset, length }) = data_slice_config {
-		if offset >= data.len() {
-			&[]
-		} else if length > data.len() - offset {
-			&data[offset..]
-		} else {
-			&data[offset..offset + length]
-		}
-	} else {
-		data
-	}
+	data_slice_config.map_or(data, |data_slice_config| data_slice_config.slice(data))
 }
 
 #[cfg(test)]
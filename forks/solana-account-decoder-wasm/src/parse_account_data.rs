@@ -68,6 +68,9 @@ pub enum ParseAccountError {
 	#[error("Additional data required to parse: {0}")]
 	AdditionalDataMissing(String),
 
+	#[error("Address lookup table addresses length {0} is not a multiple of 32")]
+	InvalidLookupTableAddressesLength(usize),
+
 	#[error("Instruction error")]
 	InstructionError(#[from] InstructionError),
 
@@ -75,7 +78,7 @@ pub enum ParseAccountError {
 	SerdeJsonError(#[from] serde_json::error::Error),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ParsableAccount {
 	AddressLookupTable,
@@ -278,6 +281,15 @@ pub fn parse_account_data_v3(
 	let program_name = PARSABLE_PROGRAM_IDS
 		.get(program_id)
 		.ok_or(ParseAccountError::ProgramNotParsable)?;
+	parse_builtin_account_data(*program_name, pubkey, data, additional_data)
+}
+
+fn parse_builtin_account_data(
+	program_name: ParsableAccount,
+	pubkey: &Pubkey,
+	data: &[u8],
+	additional_data: Option<AccountAdditionalDataV3>,
+) -> Result<ParsedAccount, ParseAccountError> {
 	let additional_data = additional_data.unwrap_or_default();
 	let parsed_json = match program_name {
 		ParsableAccount::AddressLookupTable => {
@@ -305,16 +317,153 @@ pub fn parse_account_data_v3(
 	})
 }
 
+/// A custom parser for the accounts owned by a program. It receives the
+/// account pubkey and data and should return the same shape as the
+/// `jsonParsed` encoding.
+pub type AccountParser = fn(&Pubkey, &[u8]) -> Result<ParsedAccount, ParseAccountError>;
+
+/// A lookup of account parsers keyed by the owning program id.
+///
+/// The default registry contains the parsers for every program supported by
+/// [`parse_account_data_v3`]. Custom parsers can be added for other programs
+/// or to replace a builtin parser.
+///
+/// ```
+/// use solana_account_decoder_wasm::parse_account_data::AccountParserRegistry;
+/// use solana_account_decoder_wasm::parse_account_data::ParsedAccount;
+/// use solana_pubkey::Pubkey;
+///
+/// let program_id = Pubkey::new_unique();
+/// let registry = AccountParserRegistry::default().with_parser(program_id, |_, data| {
+/// 	Ok(ParsedAccount {
+/// 		program: "counter".into(),
+/// 		parsed: data.first().copied().unwrap_or_default().into(),
+/// 		space: data.len() as u64,
+/// 	})
+/// });
+///
+/// let parsed = registry
+/// 	.parse(&program_id, &Pubkey::new_unique(), &[7])
+/// 	.unwrap();
+/// assert_eq!(parsed.parsed, 7);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AccountParserRegistry {
+	builtin: HashMap<Pubkey, ParsableAccount>,
+	custom: HashMap<Pubkey, AccountParser>,
+}
+
+impl Default for AccountParserRegistry {
+	fn default() -> Self {
+		Self {
+			builtin: PARSABLE_PROGRAM_IDS.clone(),
+			custom: HashMap::new(),
+		}
+	}
+}
+
+impl AccountParserRegistry {
+	/// A registry without any parsers, not even the builtin ones.
+	pub fn empty() -> Self {
+		Self {
+			builtin: HashMap::new(),
+			custom: HashMap::new(),
+		}
+	}
+
+	/// Register a `parser` for the accounts owned by `program_id`, replacing
+	/// any existing parser for the program.
+	pub fn insert(&mut self, program_id: Pubkey, parser: AccountParser) -> &mut Self {
+		self.builtin.remove(&program_id);
+		self.custom.insert(program_id, parser);
+		self
+	}
+
+	/// Builder style version of [`AccountParserRegistry::insert`].
+	#[must_use]
+	pub fn with_parser(mut self, program_id: Pubkey, parser: AccountParser) -> Self {
+		self.insert(program_id, parser);
+		self
+	}
+
+	/// Remove the parser for `program_id`. Returns `true` when a parser was
+	/// registered.
+	pub fn remove(&mut self, program_id: &Pubkey) -> bool {
+		let builtin = self.builtin.remove(program_id);
+		let custom = self.custom.remove(program_id);
+		builtin.is_some() || custom.is_some()
+	}
+
+	/// Check whether accounts owned by `program_id` can be parsed.
+	pub fn contains(&self, program_id: &Pubkey) -> bool {
+		self.builtin.contains_key(program_id) || self.custom.contains_key(program_id)
+	}
+
+	/// Parse the `data` of the account at `pubkey` which is owned by `owner`.
+	pub fn parse(
+		&self,
+		owner: &Pubkey,
+		pubkey: &Pubkey,
+		data: &[u8],
+	) -> Result<ParsedAccount, ParseAccountError> {
+		self.parse_with_additional_data(owner, pubkey, data, None)
+	}
+
+	/// Parse the account data with `additional_data` which the builtin
+	/// parsers need for token accounts. Custom parsers ignore it.
+	pub fn parse_with_additional_data(
+		&self,
+		owner: &Pubkey,
+		pubkey: &Pubkey,
+		data: &[u8],
+		additional_data: Option<AccountAdditionalDataV3>,
+	) -> Result<ParsedAccount, ParseAccountError> {
+		if let Some(parser) = self.custom.get(owner) {
+			return parser(pubkey, data);
+		}
+
+		let program_name = self
+			.builtin
+			.get(owner)
+			.ok_or(ParseAccountError::ProgramNotParsable)?;
+		parse_builtin_account_data(*program_name, pubkey, data, additional_data)
+	}
+}
+
+/// Parse the binary data of an account fetched with a `base58`, `base64` or
+/// `base64+zstd` encoding into the same shape as the `jsonParsed` encoding.
+///
+/// Accounts which already contain `jsonParsed` data are returned unchanged.
+pub fn parse_ui_account(
+	pubkey: &Pubkey,
+	account: &UiAccount,
+	additional_data: Option<AccountAdditionalDataV3>,
+) -> Result<ParsedAccount, ParseAccountError> {
+	if let UiAccountData::Json(parsed_account) = &account.data {
+		return Ok(parsed_account.clone());
+	}
+
+	let data = account.data.decode().ok_or(ParseAccountError::from(
+		InstructionError::InvalidAccountData,
+	))?;
+
+	parse_account_data_v3(pubkey, &account.owner, &data, additional_data)
+}
+
 #[cfg(test)]
 mod test {
+	use solana_account::Account;
+	use solana_clock::Clock;
 	use solana_nonce::state::Data;
 	use solana_nonce::state::State;
 	use solana_nonce::versions::Versions;
+	use solana_sysvar::clock;
 	use solana_vote_interface::program::id as vote_program_id;
 	use solana_vote_interface::state::VoteState;
 	use solana_vote_interface::state::VoteStateVersions;
 
 	use super::*;
+	use crate::encode_ui_account;
 
 	#[test]
 	fn test_parse_account_data() {
@@ -349,4 +498,110 @@ mod test {
 		assert_eq!(parsed.program, "nonce".to_string());
 		assert_eq!(parsed.space, State::size() as u64);
 	}
+
+	#[test]
+	fn test_parse_ui_account() {
+		let nonce_pubkey = solana_pubkey::new_rand();
+		let nonce_account = Account {
+			lamports: 1,
+			data: bincode::serialize(&Versions::new(State::Initialized(Data::default()))).unwrap(),
+			owner: system_program::id(),
+			..Account::default()
+		};
+		let clock = Clock {
+			slot: 10,
+			epoch: 2,
+			..Clock::default()
+		};
+		let clock_account = Account {
+			lamports: 1,
+			data: bincode::serialize(&clock).unwrap(),
+			owner: sysvar::id(),
+			..Account::default()
+		};
+
+		for (pubkey, account) in [(nonce_pubkey, nonce_account), (clock::id(), clock_account)] {
+			let expected =
+				parse_account_data_v3(&pubkey, &account.owner, &account.data, None).unwrap();
+			let base64_account =
+				encode_ui_account(&pubkey, &account, UiAccountEncoding::Base64, None, None);
+			let json_account =
+				encode_ui_account(&pubkey, &account, UiAccountEncoding::JsonParsed, None, None);
+
+			assert_eq!(
+				parse_ui_account(&pubkey, &base64_account, None).unwrap(),
+				expected
+			);
+			assert_eq!(
+				parse_ui_account(&pubkey, &json_account, None).unwrap(),
+				expected
+			);
+		}
+
+		let unknown_account = Account {
+			data: vec![1, 2, 3],
+			owner: solana_pubkey::new_rand(),
+			..Account::default()
+		};
+		let base64_account = encode_ui_account(
+			&nonce_pubkey,
+			&unknown_account,
+			UiAccountEncoding::Base64,
+			None,
+			None,
+		);
+		assert!(parse_ui_account(&nonce_pubkey, &base64_account, None).is_err());
+	}
+
+	#[test]
+	fn test_account_parser_registry() {
+		let account_pubkey = solana_pubkey::new_rand();
+		let nonce_account_data =
+			bincode::serialize(&Versions::new(State::Initialized(Data::default()))).unwrap();
+		let mut registry = AccountParserRegistry::default();
+
+		assert_eq!(
+			registry
+				.parse(&system_program::id(), &account_pubkey, &nonce_account_data)
+				.unwrap(),
+			parse_account_data_v3(
+				&account_pubkey,
+				&system_program::id(),
+				&nonce_account_data,
+				None
+			)
+			.unwrap()
+		);
+
+		let custom_program = solana_pubkey::new_rand();
+		assert!(
+			registry
+				.parse(&custom_program, &account_pubkey, &[1, 2])
+				.is_err()
+		);
+
+		registry.insert(custom_program, |pubkey, data| {
+			Ok(ParsedAccount {
+				program: "custom".into(),
+				parsed: serde_json::json!({ "pubkey": pubkey.to_string(), "sum": data.iter().sum::<u8>() }),
+				space: data.len() as u64,
+			})
+		});
+		let parsed = registry
+			.parse(&custom_program, &account_pubkey, &[1, 2])
+			.unwrap();
+		assert_eq!(parsed.program, "custom");
+		assert_eq!(
+			parsed.parsed,
+			serde_json::json!({ "pubkey": account_pubkey.to_string(), "sum": 3 })
+		);
+
+		assert!(registry.remove(&system_program::id()));
+		assert!(!registry.contains(&system_program::id()));
+		assert!(
+			AccountParserRegistry::empty()
+				.parse(&vote_program_id(), &account_pubkey, &[])
+				.is_err()
+		);
+	}
 }
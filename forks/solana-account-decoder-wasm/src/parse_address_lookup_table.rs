@@ -1,7 +1,12 @@
 use serde::Deserialize;
 use serde::Serialize;
+use serde_with::DisplayFromStr;
+use serde_with::serde_as;
 use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_address_lookup_table_interface::state::LOOKUP_TABLE_META_SIZE;
 use solana_instruction::error::InstructionError;
+use solana_pubkey::PUBKEY_BYTES;
+use solana_pubkey::Pubkey;
 
 use crate::parse_account_data::ParsableAccount;
 use crate::parse_account_data::ParseAccountError;
@@ -9,6 +14,15 @@ use crate::parse_account_data::ParseAccountError;
 pub fn parse_address_lookup_table(
 	data: &[u8],
 ) -> Result<LookupTableAccountType, ParseAccountError> {
+	if let Some(addresses) = data
+		.get(LOOKUP_TABLE_META_SIZE..)
+		.filter(|addresses| addresses.len() % PUBKEY_BYTES != 0)
+	{
+		return Err(ParseAccountError::InvalidLookupTableAddressesLength(
+			addresses.len(),
+		));
+	}
+
 	AddressLookupTable::deserialize(data)
 		.map(|address_lookup_table| {
 			LookupTableAccountType::LookupTable(address_lookup_table.into())
@@ -32,15 +46,18 @@ pub enum LookupTableAccountType {
 	LookupTable(UiLookupTable),
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiLookupTable {
 	pub deactivation_slot: String,
 	pub last_extended_slot: String,
 	pub last_extended_slot_start_index: u8,
-	#[serde(skip_serializing_if = "Option::is_none")]
-	pub authority: Option<String>,
-	pub addresses: Vec<String>,
+	#[serde_as(as = "Option<DisplayFromStr>")]
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub authority: Option<Pubkey>,
+	#[serde_as(as = "Vec<DisplayFromStr>")]
+	pub addresses: Vec<Pubkey>,
 }
 
 impl From<AddressLookupTable<'_>> for UiLookupTable {
@@ -51,15 +68,8 @@ impl From<AddressLookupTable<'_>> for UiLookupTable {
 			last_extended_slot_start_index: address_lookup_table
 				.meta
 				.last_extended_slot_start_index,
-			authority: address_lookup_table
-				.meta
-				.authority
-				.map(|authority| authority.to_string()),
-			addresses: address_lookup_table
-				.addresses
-				.iter()
-				.map(ToString::to_string)
-				.collect(),
+			authority: address_lookup_table.meta.authority,
+			addresses: address_lookup_table.addresses.into_owned(),
 		}
 	}
 }
@@ -68,9 +78,8 @@ impl From<AddressLookupTable<'_>> for UiLookupTable {
 mod test {
 	use std::borrow::Cow;
 
-	use solana_address_lookup_table_interface::state::LOOKUP_TABLE_META_SIZE;
+	use assert_matches::assert_matches;
 	use solana_address_lookup_table_interface::state::LookupTableMeta;
-	use solana_pubkey::Pubkey;
 
 	use super::*;
 
@@ -92,7 +101,7 @@ mod test {
 		addresses.resize_with(num_addresses, Pubkey::new_unique);
 		let lookup_table = AddressLookupTable {
 			meta: lookup_table_meta,
-			addresses: Cow::Owned(addresses),
+			addresses: Cow::Owned(addresses.clone()),
 		};
 		let lookup_table_data = AddressLookupTable::serialize_for_tests(lookup_table).unwrap();
 
@@ -110,10 +119,20 @@ mod test {
 				ui_lookup_table.last_extended_slot_start_index,
 				last_extended_slot_start_index
 			);
-			assert_eq!(ui_lookup_table.authority, Some(authority.to_string()));
-			assert_eq!(ui_lookup_table.addresses.len(), num_addresses);
+			assert_eq!(ui_lookup_table.authority, Some(authority));
+			assert_eq!(ui_lookup_table.addresses, addresses);
+		} else {
+			panic!("expected a lookup table");
 		}
 
+		let mut truncated_data = lookup_table_data.clone();
+		truncated_data.truncate(lookup_table_data.len() - 5);
+		assert_matches!(
+			parse_address_lookup_table(&truncated_data),
+			Err(ParseAccountError::InvalidLookupTableAddressesLength(length))
+				if length == num_addresses * PUBKEY_BYTES - 5
+		);
+
 		assert_eq!(
 			parse_address_lookup_table(&[0u8; LOOKUP_TABLE_META_SIZE]).unwrap(),
 			LookupTableAccountType::Uninitialized
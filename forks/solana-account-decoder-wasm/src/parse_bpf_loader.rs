@@ -1,9 +1,10 @@
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
 use bincode::deserialize;
-use bincode::serialized_size;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_with::DisplayFromStr;
+use serde_with::serde_as;
 use solana_loader_v3_interface::state::UpgradeableLoaderState;
 use solana_pubkey::Pubkey;
 
@@ -15,24 +16,14 @@ use crate::parse_account_data::ParseAccountError;
 pub fn parse_bpf_upgradeable_loader(
 	data: &[u8],
 ) -> Result<BpfUpgradeableLoaderAccountType, ParseAccountError> {
-	let account_state: UpgradeableLoaderState = deserialize(data).map_err(|_| {
-		ParseAccountError::AccountNotParsable(ParsableAccount::BpfUpgradeableLoader)
-	})?;
+	let account_state = deserialize_loader_state(data)?;
 	let parsed_account = match account_state {
 		UpgradeableLoaderState::Uninitialized => BpfUpgradeableLoaderAccountType::Uninitialized,
 		UpgradeableLoaderState::Buffer { authority_address } => {
-			let offset = if authority_address.is_some() {
-				UpgradeableLoaderState::size_of_buffer_metadata()
-			} else {
-				// This case included for code completeness; in practice, a Buffer account will
-				// always have authority_address.is_some()
-				UpgradeableLoaderState::size_of_buffer_metadata()
-					- serialized_size(&Pubkey::default()).unwrap() as usize
-			};
 			BpfUpgradeableLoaderAccountType::Buffer(UiBuffer {
-				authority: authority_address.map(|pubkey| pubkey.to_string()),
+				authority: authority_address,
 				data: UiAccountData::Binary(
-					BASE64_STANDARD.encode(&data[offset..]),
+					BASE64_STANDARD.encode(loader_data(data, &account_state)?),
 					UiAccountEncoding::Base64,
 				),
 			})
@@ -41,24 +32,18 @@ pub fn parse_bpf_upgradeable_loader(
 			programdata_address,
 		} => {
 			BpfUpgradeableLoaderAccountType::Program(UiProgram {
-				program_data: programdata_address.to_string(),
+				program_data: programdata_address,
 			})
 		}
 		UpgradeableLoaderState::ProgramData {
 			slot,
 			upgrade_authority_address,
 		} => {
-			let offset = if upgrade_authority_address.is_some() {
-				UpgradeableLoaderState::size_of_programdata_metadata()
-			} else {
-				UpgradeableLoaderState::size_of_programdata_metadata()
-					- serialized_size(&Pubkey::default()).unwrap() as usize
-			};
 			BpfUpgradeableLoaderAccountType::ProgramData(UiProgramData {
 				slot,
-				authority: upgrade_authority_address.map(|pubkey| pubkey.to_string()),
+				authority: upgrade_authority_address,
 				data: UiAccountData::Binary(
-					BASE64_STANDARD.encode(&data[offset..]),
+					BASE64_STANDARD.encode(loader_data(data, &account_state)?),
 					UiAccountEncoding::Base64,
 				),
 			})
@@ -67,6 +52,62 @@ pub fn parse_bpf_upgradeable_loader(
 	Ok(parsed_account)
 }
 
+/// Borrow the bytes stored after the metadata of an upgradeable loader
+/// `Buffer` or `ProgramData` account.
+///
+/// For a `ProgramData` account these are the bytes of the deployed ELF. Unlike
+/// [`parse_bpf_upgradeable_loader`] the data isn't copied or encoded.
+pub fn parse_bpf_upgradeable_loader_data(data: &[u8]) -> Result<&[u8], ParseAccountError> {
+	let account_state = deserialize_loader_state(data)?;
+	loader_data(data, &account_state)
+}
+
+fn deserialize_loader_state(data: &[u8]) -> Result<UpgradeableLoaderState, ParseAccountError> {
+	deserialize(data)
+		.map_err(|_| ParseAccountError::AccountNotParsable(ParsableAccount::BpfUpgradeableLoader))
+}
+
+fn loader_data<'a>(
+	data: &'a [u8],
+	account_state: &UpgradeableLoaderState,
+) -> Result<&'a [u8], ParseAccountError> {
+	let (metadata_size, has_authority) = match account_state {
+		UpgradeableLoaderState::Buffer { authority_address } => {
+			(
+				UpgradeableLoaderState::size_of_buffer_metadata(),
+				authority_address.is_some(),
+			)
+		}
+		UpgradeableLoaderState::ProgramData {
+			upgrade_authority_address,
+			..
+		} => {
+			(
+				UpgradeableLoaderState::size_of_programdata_metadata(),
+				upgrade_authority_address.is_some(),
+			)
+		}
+		UpgradeableLoaderState::Uninitialized | UpgradeableLoaderState::Program { .. } => {
+			return Err(ParseAccountError::AccountNotParsable(
+				ParsableAccount::BpfUpgradeableLoader,
+			));
+		}
+	};
+	// The authority is serialized as an `Option<Pubkey>` so the metadata is
+	// shorter by the size of a pubkey when there is no authority. In practice a
+	// `Buffer` account will always have an authority.
+	let offset = if has_authority {
+		metadata_size
+	} else {
+		metadata_size - size_of::<Pubkey>()
+	};
+
+	data.get(offset..)
+		.ok_or(ParseAccountError::AccountNotParsable(
+			ParsableAccount::BpfUpgradeableLoader,
+		))
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase", tag = "type", content = "info")]
 pub enum BpfUpgradeableLoaderAccountType {
@@ -76,24 +117,30 @@ pub enum BpfUpgradeableLoaderAccountType {
 	ProgramData(UiProgramData),
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiBuffer {
-	pub authority: Option<String>,
+	#[serde_as(as = "Option<DisplayFromStr>")]
+	pub authority: Option<Pubkey>,
 	pub data: UiAccountData,
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiProgram {
-	pub program_data: String,
+	#[serde_as(as = "DisplayFromStr")]
+	pub program_data: Pubkey,
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiProgramData {
 	pub slot: u64,
-	pub authority: Option<String>,
+	#[serde_as(as = "Option<DisplayFromStr>")]
+	pub authority: Option<Pubkey>,
 	pub data: UiAccountData,
 }
 
@@ -124,7 +171,7 @@ mod test {
 		assert_eq!(
 			parse_bpf_upgradeable_loader(&account_data).unwrap(),
 			BpfUpgradeableLoaderAccountType::Buffer(UiBuffer {
-				authority: Some(authority.to_string()),
+				authority: Some(authority),
 				data: UiAccountData::Binary(
 					BASE64_STANDARD.encode(&program),
 					UiAccountEncoding::Base64
@@ -158,7 +205,7 @@ mod test {
 		assert_eq!(
 			parse_bpf_upgradeable_loader(&account_data).unwrap(),
 			BpfUpgradeableLoaderAccountType::Program(UiProgram {
-				program_data: programdata_address.to_string(),
+				program_data: programdata_address,
 			})
 		);
 
@@ -174,7 +221,7 @@ mod test {
 			parse_bpf_upgradeable_loader(&account_data).unwrap(),
 			BpfUpgradeableLoaderAccountType::ProgramData(UiProgramData {
 				slot,
-				authority: Some(authority.to_string()),
+				authority: Some(authority),
 				data: UiAccountData::Binary(
 					BASE64_STANDARD.encode(&program),
 					UiAccountEncoding::Base64
@@ -200,4 +247,27 @@ mod test {
 			})
 		);
 	}
+
+	#[test]
+	fn test_parse_bpf_upgradeable_loader_data() {
+		let program = vec![7u8; 64];
+		let bpf_loader_state = UpgradeableLoaderState::ProgramData {
+			slot: 42,
+			upgrade_authority_address: Some(Pubkey::new_unique()),
+		};
+		let mut account_data = serialize(&bpf_loader_state).unwrap();
+		account_data.extend_from_slice(&program);
+		let elf = parse_bpf_upgradeable_loader_data(&account_data).unwrap();
+		assert_eq!(elf, program.as_slice());
+		assert!(std::ptr::eq(
+			elf.as_ptr(),
+			account_data[account_data.len() - program.len()..].as_ptr()
+		));
+
+		let bpf_loader_state = UpgradeableLoaderState::Program {
+			programdata_address: Pubkey::new_unique(),
+		};
+		let account_data = serialize(&bpf_loader_state).unwrap();
+		assert!(parse_bpf_upgradeable_loader_data(&account_data).is_err());
+	}
 }
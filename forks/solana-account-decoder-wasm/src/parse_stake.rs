@@ -1,8 +1,12 @@
 use bincode::deserialize;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_with::DisplayFromStr;
+use serde_with::serde_as;
+use solana_account_decoder_client_types_wasm::UiAccountData;
 use solana_clock::Epoch;
 use solana_clock::UnixTimestamp;
+use solana_pubkey::Pubkey;
 use solana_stake_interface::state::Authorized;
 use solana_stake_interface::state::Delegation;
 use solana_stake_interface::state::Lockup;
@@ -36,6 +40,31 @@ pub fn parse_stake(data: &[u8]) -> Result<StakeAccountType, ParseAccountError> {
 	Ok(parsed_account)
 }
 
+impl TryFrom<&UiAccountData> for StakeAccountType {
+	type Error = ParseAccountError;
+
+	/// Read a stake account from the `jsonParsed` output of the stake program
+	/// or from any of the binary encodings.
+	fn try_from(data: &UiAccountData) -> Result<Self, Self::Error> {
+		match data {
+			UiAccountData::Json(parsed_account) if parsed_account.program == "stake" => {
+				Ok(serde_json::from_value(parsed_account.parsed.clone())?)
+			}
+			UiAccountData::Json(_) => {
+				Err(ParseAccountError::AccountNotParsable(
+					ParsableAccount::Stake,
+				))
+			}
+			UiAccountData::LegacyBinary(_) | UiAccountData::Binary(..) => {
+				let bytes = data.decode().ok_or(ParseAccountError::AccountNotParsable(
+					ParsableAccount::Stake,
+				))?;
+				parse_stake(&bytes)
+			}
+		}
+	}
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase", tag = "type", content = "info")]
 pub enum StakeAccountType {
@@ -70,12 +99,14 @@ impl From<Meta> for UiMeta {
 	}
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiLockup {
 	pub unix_timestamp: UnixTimestamp,
 	pub epoch: Epoch,
-	pub custodian: String,
+	#[serde_as(as = "DisplayFromStr")]
+	pub custodian: Pubkey,
 }
 
 impl From<Lockup> for UiLockup {
@@ -83,23 +114,26 @@ impl From<Lockup> for UiLockup {
 		Self {
 			unix_timestamp: lockup.unix_timestamp,
 			epoch: lockup.epoch,
-			custodian: lockup.custodian.to_string(),
+			custodian: lockup.custodian,
 		}
 	}
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiAuthorized {
-	pub staker: String,
-	pub withdrawer: String,
+	#[serde_as(as = "DisplayFromStr")]
+	pub staker: Pubkey,
+	#[serde_as(as = "DisplayFromStr")]
+	pub withdrawer: Pubkey,
 }
 
 impl From<Authorized> for UiAuthorized {
 	fn from(authorized: Authorized) -> Self {
 		Self {
-			staker: authorized.staker.to_string(),
-			withdrawer: authorized.withdrawer.to_string(),
+			staker: authorized.staker,
+			withdrawer: authorized.withdrawer,
 		}
 	}
 }
@@ -120,10 +154,12 @@ impl From<Stake> for UiStake {
 	}
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiDelegation {
-	pub voter: String,
+	#[serde_as(as = "DisplayFromStr")]
+	pub voter: Pubkey,
 	pub stake: StringAmount,
 	pub activation_epoch: StringAmount,
 	pub deactivation_epoch: StringAmount,
@@ -138,7 +174,7 @@ impl From<Delegation> for UiDelegation {
 	fn from(delegation: Delegation) -> Self {
 		#[allow(deprecated)]
 		Self {
-			voter: delegation.voter_pubkey.to_string(),
+			voter: delegation.voter_pubkey,
 			stake: delegation.stake.to_string(),
 			activation_epoch: delegation.activation_epoch.to_string(),
 			deactivation_epoch: delegation.deactivation_epoch.to_string(),
@@ -150,9 +186,13 @@ impl From<Delegation> for UiDelegation {
 #[cfg(test)]
 mod test {
 	use bincode::serialize;
+	use solana_account::Account;
+	use solana_account_decoder_client_types_wasm::UiAccountEncoding;
+	use solana_sdk_ids::stake;
 	use solana_stake_interface::stake_flags::StakeFlags;
 
 	use super::*;
+	use crate::encode_ui_account;
 
 	#[test]
 	#[allow(deprecated)]
@@ -186,13 +226,13 @@ mod test {
 				meta: UiMeta {
 					rent_exempt_reserve: 42.to_string(),
 					authorized: UiAuthorized {
-						staker: pubkey.to_string(),
-						withdrawer: pubkey.to_string(),
+						staker: pubkey,
+						withdrawer: pubkey,
 					},
 					lockup: UiLockup {
 						unix_timestamp: 0,
 						epoch: 1,
-						custodian: custodian.to_string(),
+						custodian,
 					}
 				},
 				stake: None,
@@ -219,18 +259,18 @@ mod test {
 				meta: UiMeta {
 					rent_exempt_reserve: 42.to_string(),
 					authorized: UiAuthorized {
-						staker: pubkey.to_string(),
-						withdrawer: pubkey.to_string(),
+						staker: pubkey,
+						withdrawer: pubkey,
 					},
 					lockup: UiLockup {
 						unix_timestamp: 0,
 						epoch: 1,
-						custodian: custodian.to_string(),
+						custodian,
 					}
 				},
 				stake: Some(UiStake {
 					delegation: UiDelegation {
-						voter: voter_pubkey.to_string(),
+						voter: voter_pubkey,
 						stake: 20.to_string(),
 						activation_epoch: 2.to_string(),
 						deactivation_epoch: u64::MAX.to_string(),
@@ -251,4 +291,46 @@ mod test {
 		let bad_data = vec![1, 2, 3, 4];
 		assert!(parse_stake(&bad_data).is_err());
 	}
+
+	#[test]
+	fn test_stake_account_from_ui_account_data() {
+		let pubkey = solana_pubkey::new_rand();
+		let meta = Meta {
+			rent_exempt_reserve: 42,
+			authorized: Authorized::auto(&pubkey),
+			lockup: Lockup::default(),
+		};
+		let stake = Stake {
+			delegation: Delegation::new(&solana_pubkey::new_rand(), 20, 2),
+			credits_observed: 10,
+		};
+		let stake_data = serialize(&StakeStateV2::Stake(meta, stake, StakeFlags::empty())).unwrap();
+		let account = Account {
+			lamports: 62,
+			data: stake_data.clone(),
+			owner: stake::id(),
+			..Account::default()
+		};
+		let expected = parse_stake(&stake_data).unwrap();
+
+		for encoding in [UiAccountEncoding::JsonParsed, UiAccountEncoding::Base64] {
+			let ui_account = encode_ui_account(&pubkey, &account, encoding, None, None);
+			assert_eq!(
+				StakeAccountType::try_from(&ui_account.data).unwrap(),
+				expected
+			);
+		}
+
+		let ui_account = encode_ui_account(
+			&pubkey,
+			&Account {
+				data: vec![1, 2, 3, 4],
+				..Account::default()
+			},
+			UiAccountEncoding::Base64,
+			None,
+			None,
+		);
+		assert!(StakeAccountType::try_from(&ui_account.data).is_err());
+	}
 }
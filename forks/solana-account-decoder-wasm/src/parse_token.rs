@@ -13,7 +13,6 @@ use solana_program_pack::Pack;
 use solana_pubkey::Pubkey;
 pub use spl_generic_token::is_known_spl_token_id;
 pub use spl_generic_token::spl_token_ids;
-use spl_token_2022::extension::BaseStateWithExtensions;
 use spl_token_2022::extension::StateWithExtensions;
 use spl_token_2022::generic_token_account::GenericTokenAccount;
 use spl_token_2022::state::Account;
@@ -25,9 +24,14 @@ use crate::parse_account_data::ParsableAccount;
 use crate::parse_account_data::ParseAccountError;
 use crate::parse_account_data::SplTokenAdditionalData;
 use crate::parse_account_data::SplTokenAdditionalDataV2;
-use crate::parse_token_extension::parse_extension;
-
-#[deprecated(since = "2.0.0", note = "Use `parse_token_v3` instead")]
+use crate::parse_token_extension::parse_extensions;
+
+/// Parse the raw bytes of an spl-token or token-2022 account, mint or
+/// multisig.
+///
+/// The `decimals` of the mint are required to parse a token account. This
+/// decodes the spl-token layout directly so it can be used with `base64`
+/// encoded accounts when the node doesn't return `jsonParsed` data.
 #[allow(deprecated)]
 pub fn parse_token(
 	data: &[u8],
@@ -56,19 +60,12 @@ pub fn parse_token_v3(
 				"no mint_decimals provided to parse spl-token account".to_string(),
 			)
 		})?;
-		let extension_types = account.get_extension_types().unwrap_or_default();
-		let ui_extensions = extension_types
-			.iter()
-			.map(|extension_type| parse_extension::<Account>(extension_type, &account))
-			.collect();
+		let ui_extensions = parse_extensions(&account);
 		return Ok(TokenAccountType::Account(UiTokenAccount {
-			mint: account.base.mint.to_string(),
-			owner: account.base.owner.to_string(),
+			mint: account.base.mint,
+			owner: account.base.owner,
 			token_amount: token_amount_to_ui_amount_v3(account.base.amount, additional_data),
-			delegate: match account.base.delegate {
-				COption::Some(pubkey) => Some(pubkey.to_string()),
-				COption::None => None,
-			},
+			delegate: account.base.delegate.into(),
 			state: convert_account_state(account.base.state),
 			is_native: account.base.is_native(),
 			rent_exempt_reserve: match account.base.is_native {
@@ -85,31 +82,18 @@ pub fn parse_token_v3(
 					additional_data,
 				))
 			},
-			close_authority: match account.base.close_authority {
-				COption::Some(pubkey) => Some(pubkey.to_string()),
-				COption::None => None,
-			},
+			close_authority: account.base.close_authority.into(),
 			extensions: ui_extensions,
 		}));
 	}
 	if let Ok(mint) = StateWithExtensions::<Mint>::unpack(data) {
-		let extension_types = mint.get_extension_types().unwrap_or_default();
-		let ui_extensions = extension_types
-			.iter()
-			.map(|extension_type| parse_extension::<Mint>(extension_type, &mint))
-			.collect();
+		let ui_extensions = parse_extensions(&mint);
 		return Ok(TokenAccountType::Mint(UiMint {
-			mint_authority: match mint.base.mint_authority {
-				COption::Some(pubkey) => Some(pubkey.to_string()),
-				COption::None => None,
-			},
+			mint_authority: mint.base.mint_authority.into(),
 			supply: mint.base.supply.to_string(),
 			decimals: mint.base.decimals,
 			is_initialized: mint.base.is_initialized,
-			freeze_authority: match mint.base.freeze_authority {
-				COption::Some(pubkey) => Some(pubkey.to_string()),
-				COption::None => None,
-			},
+			freeze_authority: mint.base.freeze_authority.into(),
 			extensions: ui_extensions,
 		}));
 	}
@@ -123,13 +107,8 @@ pub fn parse_token_v3(
 			signers: multisig
 				.signers
 				.iter()
-				.filter_map(|pubkey| {
-					if pubkey == &Pubkey::default() {
-						None
-					} else {
-						Some(pubkey.to_string())
-					}
-				})
+				.filter(|pubkey| pubkey != &&Pubkey::default())
+				.copied()
 				.collect(),
 		}))
 	} else {
@@ -189,10 +168,7 @@ pub fn token_amount_to_ui_amount_v3(
 			ui_amount_string.unwrap_or(String::new()),
 		)
 	} else {
-		let ui_amount = 10_usize
-			.checked_pow(u32::from(decimals))
-			.map(|dividend| amount as f64 / dividend as f64);
-		(ui_amount, real_number_string_trimmed(amount, decimals))
+		return UiTokenAmount::from_raw(amount, decimals);
 	};
 	UiTokenAmount {
 		ui_amount,
@@ -221,8 +197,10 @@ mod test {
 	use spl_token_2022::extension::scaled_ui_amount::ScaledUiAmountConfig;
 
 	use super::*;
+	use crate::parse_token_extension::UiInterestBearingConfig;
 	use crate::parse_token_extension::UiMemoTransfer;
 	use crate::parse_token_extension::UiMintCloseAuthority;
+	use crate::parse_token_extension::UiUnknownExtension;
 
 	const INT_SECONDS_PER_YEAR: i64 = 6 * 6 * 24 * 36524;
 
@@ -248,8 +226,8 @@ mod test {
 			)
 			.unwrap(),
 			TokenAccountType::Account(UiTokenAccount {
-				mint: mint_pubkey.to_string(),
-				owner: owner_pubkey.to_string(),
+				mint: mint_pubkey,
+				owner: owner_pubkey,
 				token_amount: UiTokenAmount {
 					ui_amount: Some(0.42),
 					decimals: 2,
@@ -261,7 +239,7 @@ mod test {
 				is_native: false,
 				rent_exempt_reserve: None,
 				delegated_amount: None,
-				close_authority: Some(owner_pubkey.to_string()),
+				close_authority: Some(owner_pubkey),
 				extensions: vec![],
 			}),
 		);
@@ -278,11 +256,11 @@ mod test {
 		assert_eq!(
 			parse_token_v3(&mint_data, None).unwrap(),
 			TokenAccountType::Mint(UiMint {
-				mint_authority: Some(owner_pubkey.to_string()),
+				mint_authority: Some(owner_pubkey),
 				supply: 42.to_string(),
 				decimals: 3,
 				is_initialized: true,
-				freeze_authority: Some(owner_pubkey.to_string()),
+				freeze_authority: Some(owner_pubkey),
 				extensions: vec![],
 			}),
 		);
@@ -308,11 +286,7 @@ mod test {
 				num_required_signers: 2,
 				num_valid_signers: 3,
 				is_initialized: true,
-				signers: vec![
-					first_signer.to_string(),
-					second_signer.to_string(),
-					third_signer.to_string()
-				],
+				signers: vec![first_signer, second_signer, third_signer],
 			}),
 		);
 
@@ -320,6 +294,97 @@ mod test {
 		assert!(parse_token_v3(&bad_data, None).is_err());
 	}
 
+	#[test]
+	fn test_json_parsed_round_trip() {
+		// `jsonParsed` responses captured from mainnet for the USDC mint and a
+		// delegated USDC token account.
+		let mint_json = serde_json::json!({
+			"type": "mint",
+			"info": {
+				"decimals": 6,
+				"freezeAuthority": "7dGbd2QZcCKcTndnHcTL8q7SMVXAkp688NTQYwrRCrar",
+				"isInitialized": true,
+				"mintAuthority": "BJE5MMbqXjVwjAF7oxwPYXnTXDyspzZyt4vwenNw5ruG",
+				"supply": "9123145838452913"
+			}
+		});
+		let account_json = serde_json::json!({
+			"type": "account",
+			"info": {
+				"delegate": "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T",
+				"delegatedAmount": {
+					"amount": "1000000",
+					"decimals": 6,
+					"uiAmount": 1.0,
+					"uiAmountString": "1"
+				},
+				"isNative": false,
+				"mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+				"owner": "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1",
+				"state": "initialized",
+				"tokenAmount": {
+					"amount": "5021340",
+					"decimals": 6,
+					"uiAmount": 5.02134,
+					"uiAmountString": "5.02134"
+				}
+			}
+		});
+
+		let mint: TokenAccountType = serde_json::from_value(mint_json.clone()).unwrap();
+		let TokenAccountType::Mint(ref ui_mint) = mint else {
+			panic!("expected a mint");
+		};
+		assert_eq!(
+			ui_mint.mint_authority,
+			Some(Pubkey::from_str("BJE5MMbqXjVwjAF7oxwPYXnTXDyspzZyt4vwenNw5ruG").unwrap())
+		);
+		assert_eq!(serde_json::to_value(&mint).unwrap(), mint_json);
+
+		let account: TokenAccountType = serde_json::from_value(account_json.clone()).unwrap();
+		let TokenAccountType::Account(ref ui_account) = account else {
+			panic!("expected a token account");
+		};
+		assert_eq!(
+			ui_account.mint,
+			Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap()
+		);
+		assert_eq!(ui_account.state, UiAccountState::Initialized);
+		assert_eq!(serde_json::to_value(&account).unwrap(), account_json);
+
+		// the same output is produced locally from the raw bytes.
+		let mut mint_data = vec![0; Mint::get_packed_len()];
+		Mint::pack(
+			Mint {
+				mint_authority: COption::Some(ui_mint.mint_authority.unwrap()),
+				supply: 9_123_145_838_452_913,
+				decimals: 6,
+				is_initialized: true,
+				freeze_authority: COption::Some(ui_mint.freeze_authority.unwrap()),
+			},
+			&mut mint_data,
+		)
+		.unwrap();
+		assert_eq!(parse_token(&mint_data, None).unwrap(), mint);
+
+		let mut account_data = vec![0; Account::get_packed_len()];
+		Account::pack(
+			Account {
+				mint: ui_account.mint,
+				owner: ui_account.owner,
+				amount: 5_021_340,
+				delegate: COption::Some(ui_account.delegate.unwrap()),
+				state: AccountState::Initialized,
+				is_native: COption::None,
+				delegated_amount: 1_000_000,
+				close_authority: COption::None,
+			},
+			&mut account_data,
+		)
+		.unwrap();
+		assert_eq!(parse_token(&account_data, Some(6)).unwrap(), account);
+	}
+
 	#[test]
 	fn test_get_token_account_mint() {
 		let mint_pubkey = Pubkey::new_from_array([2; 32]);
@@ -406,6 +471,39 @@ mod test {
 		assert_eq!(token_amount.ui_amount, None);
 	}
 
+	#[test]
+	fn test_ui_token_amount_arithmetic() {
+		let amount = UiTokenAmount::from_raw(1_500_000_000, 9);
+		assert_eq!(
+			amount,
+			token_amount_to_ui_amount_v3(
+				1_500_000_000,
+				&SplTokenAdditionalDataV2::with_decimals(9)
+			)
+		);
+		assert_eq!(amount.ui_amount_string, "1.5");
+		assert_eq!(amount.raw(), Some(1_500_000_000));
+
+		let large = UiTokenAmount::from_raw(u64::MAX, 6);
+		assert_eq!(large.ui_amount_string, "18446744073709.551615");
+		assert_eq!(large.raw(), Some(u128::from(u64::MAX)));
+		assert_eq!(UiTokenAmount::from_raw(0, 6).ui_amount_string, "0");
+		assert_eq!(UiTokenAmount::from_raw(1, 20).ui_amount, None);
+
+		let sum = amount
+			.checked_add(&UiTokenAmount::from_raw(500_000_000, 9))
+			.unwrap();
+		assert_eq!(sum, UiTokenAmount::from_raw(2_000_000_000, 9));
+		assert_eq!(sum.ui_amount_string, "2");
+		assert_eq!(
+			sum.checked_sub(&amount),
+			Some(UiTokenAmount::from_raw(500_000_000, 9))
+		);
+		assert_eq!(amount.checked_sub(&sum), None);
+		assert_eq!(large.checked_add(&UiTokenAmount::from_raw(1, 6)), None);
+		assert_eq!(amount.checked_add(&UiTokenAmount::from_raw(1, 6)), None);
+	}
+
 	#[test]
 	fn test_ui_token_amount_with_interest() {
 		// constant 5%
@@ -460,6 +558,50 @@ mod test {
 		assert_eq!(token_amount.ui_amount_string, "inf");
 	}
 
+	#[test]
+	fn test_ui_token_amount_apply_interest() {
+		let config = InterestBearingConfig {
+			initialization_timestamp: 0.into(),
+			pre_update_average_rate: 500.into(),
+			last_update_timestamp: INT_SECONDS_PER_YEAR.into(),
+			current_rate: 250.into(),
+			..Default::default()
+		};
+		let ui_config = UiInterestBearingConfig {
+			rate_authority: None,
+			initialization_timestamp: 0,
+			pre_update_average_rate: 500,
+			last_update_timestamp: INT_SECONDS_PER_YEAR,
+			current_rate: 250,
+		};
+		let unix_timestamp = INT_SECONDS_PER_YEAR * 2;
+		let additional_data = SplTokenAdditionalDataV2 {
+			decimals: 6,
+			interest_bearing_config: Some((config, unix_timestamp)),
+			..Default::default()
+		};
+		let token_amount = token_amount_to_ui_amount_v3(1_234_567_890, &additional_data);
+		let applied = token_amount_to_ui_amount_v3(
+			1_234_567_890,
+			&SplTokenAdditionalDataV2::with_decimals(6),
+		)
+		.apply_interest(&ui_config, unix_timestamp)
+		.unwrap();
+
+		assert_eq!(applied, token_amount);
+		assert_eq!(applied.amount, "1234567890");
+
+		let mint = UiMint {
+			mint_authority: None,
+			supply: "0".into(),
+			decimals: 6,
+			is_initialized: true,
+			freeze_authority: None,
+			extensions: vec![UiExtension::InterestBearingConfig(ui_config)],
+		};
+		assert!(mint.interest_bearing_config().is_some());
+	}
+
 	#[test]
 	fn test_ui_token_amount_with_multiplier() {
 		// 2x multiplier
@@ -564,8 +706,8 @@ mod test {
 			)
 			.unwrap(),
 			TokenAccountType::Account(UiTokenAccount {
-				mint: mint_pubkey.to_string(),
-				owner: owner_pubkey.to_string(),
+				mint: mint_pubkey,
+				owner: owner_pubkey,
 				token_amount: UiTokenAmount {
 					ui_amount: Some(0.42),
 					decimals: 2,
@@ -577,7 +719,7 @@ mod test {
 				is_native: false,
 				rent_exempt_reserve: None,
 				delegated_amount: None,
-				close_authority: Some(owner_pubkey.to_string()),
+				close_authority: Some(owner_pubkey),
 				extensions: vec![],
 			}),
 		);
@@ -604,8 +746,8 @@ mod test {
 			)
 			.unwrap(),
 			TokenAccountType::Account(UiTokenAccount {
-				mint: mint_pubkey.to_string(),
-				owner: owner_pubkey.to_string(),
+				mint: mint_pubkey,
+				owner: owner_pubkey,
 				token_amount: UiTokenAmount {
 					ui_amount: Some(0.42),
 					decimals: 2,
@@ -617,7 +759,7 @@ mod test {
 				is_native: false,
 				rent_exempt_reserve: None,
 				delegated_amount: None,
-				close_authority: Some(owner_pubkey.to_string()),
+				close_authority: Some(owner_pubkey),
 				extensions: vec![
 					UiExtension::ImmutableOwner,
 					UiExtension::MemoTransfer(UiMemoTransfer {
@@ -652,11 +794,11 @@ mod test {
 		assert_eq!(
 			parse_token_v3(&mint_data, None).unwrap(),
 			TokenAccountType::Mint(UiMint {
-				mint_authority: Some(owner_pubkey.to_string()),
+				mint_authority: Some(owner_pubkey),
 				supply: 42.to_string(),
 				decimals: 3,
 				is_initialized: true,
-				freeze_authority: Some(owner_pubkey.to_string()),
+				freeze_authority: Some(owner_pubkey),
 				extensions: vec![],
 			}),
 		);
@@ -676,15 +818,56 @@ mod test {
 		assert_eq!(
 			parse_token_v3(&mint_data, None).unwrap(),
 			TokenAccountType::Mint(UiMint {
-				mint_authority: Some(owner_pubkey.to_string()),
+				mint_authority: Some(owner_pubkey),
 				supply: 42.to_string(),
 				decimals: 3,
 				is_initialized: true,
-				freeze_authority: Some(owner_pubkey.to_string()),
+				freeze_authority: Some(owner_pubkey),
 				extensions: vec![UiExtension::MintCloseAuthority(UiMintCloseAuthority {
 					close_authority: Some(owner_pubkey.to_string()),
 				})],
 			}),
 		);
 	}
+
+	#[test]
+	fn test_parse_token_mint_with_unknown_extension() {
+		let owner_pubkey = Pubkey::new_from_array([3; 32]);
+		let mint_size =
+			ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MintCloseAuthority])
+				.unwrap();
+		let mut mint_data = vec![0; mint_size];
+		let mut mint_state =
+			StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint_data).unwrap();
+
+		let mint_close_authority = mint_state
+			.init_extension::<MintCloseAuthority>(true)
+			.unwrap();
+		mint_close_authority.close_authority = Some(owner_pubkey).try_into().unwrap();
+		mint_state.base.is_initialized = true;
+		mint_state.pack_base();
+		mint_state.init_account_type().unwrap();
+
+		// place an unrecognized extension before the known one
+		let tlv_start = Account::get_packed_len() + 1;
+		let unknown_entry = [0xff, 0xff, 3, 0, 1, 2, 3];
+		mint_data.splice(tlv_start..tlv_start, unknown_entry);
+
+		let TokenAccountType::Mint(mint) = parse_token_v3(&mint_data, None).unwrap() else {
+			panic!("expected a mint");
+		};
+
+		assert_eq!(
+			mint.extensions,
+			vec![
+				UiExtension::Unknown(UiUnknownExtension {
+					extension_type: u16::MAX,
+					data: "AQID".into(),
+				}),
+				UiExtension::MintCloseAuthority(UiMintCloseAuthority {
+					close_authority: Some(owner_pubkey.to_string()),
+				}),
+			]
+		);
+	}
 }
@@ -1,3 +1,7 @@
+use std::iter;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
 pub use solana_account_decoder_client_types_wasm::token::UiConfidentialMintBurn;
 pub use solana_account_decoder_client_types_wasm::token::UiConfidentialTransferAccount;
 pub use solana_account_decoder_client_types_wasm::token::UiConfidentialTransferFeeAmount;
@@ -23,9 +27,11 @@ pub use solana_account_decoder_client_types_wasm::token::UiTransferFeeAmount;
 pub use solana_account_decoder_client_types_wasm::token::UiTransferFeeConfig;
 pub use solana_account_decoder_client_types_wasm::token::UiTransferHook;
 pub use solana_account_decoder_client_types_wasm::token::UiTransferHookAccount;
+pub use solana_account_decoder_client_types_wasm::token::UiUnknownExtension;
 use solana_clock::UnixTimestamp;
 use solana_program_pack::Pack;
 use solana_pubkey::Pubkey;
+use spl_pod::bytemuck::pod_from_bytes;
 use spl_token_2022::extension::BaseState;
 use spl_token_2022::extension::BaseStateWithExtensions;
 use spl_token_2022::extension::ExtensionType;
@@ -34,43 +40,93 @@ use spl_token_2022::extension::{self};
 use spl_token_2022::solana_zk_sdk::encryption::pod::elgamal::PodElGamalPubkey;
 use spl_token_group_interface::state::TokenGroup;
 use spl_token_group_interface::state::TokenGroupMember;
+use spl_token_metadata_interface::solana_borsh::v1::try_from_slice_unchecked;
 use spl_token_metadata_interface::state::TokenMetadata;
 
 use crate::parse_token::convert_account_state;
 
+/// Parse every extension stored in the TLV region which follows the base
+/// account or mint layout.
+///
+/// Extension types which are unknown to this version of `spl-token-2022` are
+/// preserved as [`UiExtension::Unknown`] with their raw bytes rather than
+/// hiding all the extensions of the account.
+pub fn parse_extensions<S: BaseState + Pack>(account: &StateWithExtensions<S>) -> Vec<UiExtension> {
+	tlv_entries(account.get_tlv_data())
+		.map(|(extension_type, value)| {
+			match ExtensionType::try_from(extension_type) {
+				Ok(extension_type) => parse_extension_data(extension_type, value),
+				Err(_) => {
+					UiExtension::Unknown(UiUnknownExtension {
+						extension_type,
+						data: BASE64_STANDARD.encode(value),
+					})
+				}
+			}
+		})
+		.collect()
+}
+
 pub fn parse_extension<S: BaseState + Pack>(
 	extension_type: &ExtensionType,
 	account: &StateWithExtensions<S>,
 ) -> UiExtension {
+	let raw_extension_type = u16::from(*extension_type);
+	let value = tlv_entries(account.get_tlv_data())
+		.find(|(extension_type, _)| *extension_type == raw_extension_type)
+		.map_or(&[][..], |(_, value)| value);
+
+	parse_extension_data(*extension_type, value)
+}
+
+/// Iterate over the `(extension_type, value)` entries of the TLV data. This
+/// stops at the first uninitialized entry or when the data is malformed.
+fn tlv_entries(tlv_data: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+	let mut remaining = tlv_data;
+
+	iter::from_fn(move || {
+		let ([type_lo, type_hi, length_lo, length_hi], rest) =
+			remaining.split_first_chunk::<4>()?;
+		let extension_type = u16::from_le_bytes([*type_lo, *type_hi]);
+
+		if extension_type == u16::from(ExtensionType::Uninitialized) {
+			return None;
+		}
+
+		let length = usize::from(u16::from_le_bytes([*length_lo, *length_hi]));
+		let value = rest.get(..length)?;
+		remaining = &rest[length..];
+
+		Some((extension_type, value))
+	})
+}
+
+fn parse_extension_data(extension_type: ExtensionType, value: &[u8]) -> UiExtension {
 	match extension_type {
 		ExtensionType::Uninitialized => UiExtension::Uninitialized,
 		ExtensionType::TransferFeeConfig => {
-			account
-				.get_extension::<extension::transfer_fee::TransferFeeConfig>()
+			pod_from_bytes::<extension::transfer_fee::TransferFeeConfig>(value)
 				.map(|&extension| {
 					UiExtension::TransferFeeConfig(convert_transfer_fee_config(extension))
 				})
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::TransferFeeAmount => {
-			account
-				.get_extension::<extension::transfer_fee::TransferFeeAmount>()
+			pod_from_bytes::<extension::transfer_fee::TransferFeeAmount>(value)
 				.map(|&extension| {
 					UiExtension::TransferFeeAmount(convert_transfer_fee_amount(extension))
 				})
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::MintCloseAuthority => {
-			account
-				.get_extension::<extension::mint_close_authority::MintCloseAuthority>()
+			pod_from_bytes::<extension::mint_close_authority::MintCloseAuthority>(value)
 				.map(|&extension| {
 					UiExtension::MintCloseAuthority(convert_mint_close_authority(extension))
 				})
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::ConfidentialTransferMint => {
-			account
-				.get_extension::<extension::confidential_transfer::ConfidentialTransferMint>()
+			pod_from_bytes::<extension::confidential_transfer::ConfidentialTransferMint>(value)
 				.map(|&extension| {
 					UiExtension::ConfidentialTransferMint(convert_confidential_transfer_mint(
 						extension,
@@ -79,19 +135,18 @@ pub fn parse_extension<S: BaseState + Pack>(
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::ConfidentialTransferFeeConfig => {
-			account
-				.get_extension::<extension::confidential_transfer_fee::ConfidentialTransferFeeConfig>(
+			pod_from_bytes::<extension::confidential_transfer_fee::ConfidentialTransferFeeConfig>(
+				value,
+			)
+			.map(|&extension| {
+				UiExtension::ConfidentialTransferFeeConfig(
+					convert_confidential_transfer_fee_config(extension),
 				)
-				.map(|&extension| {
-					UiExtension::ConfidentialTransferFeeConfig(
-						convert_confidential_transfer_fee_config(extension),
-					)
-				})
-				.unwrap_or(UiExtension::UnparseableExtension)
+			})
+			.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::ConfidentialTransferAccount => {
-			account
-				.get_extension::<extension::confidential_transfer::ConfidentialTransferAccount>()
+			pod_from_bytes::<extension::confidential_transfer::ConfidentialTransferAccount>(value)
 				.map(|&extension| {
 					UiExtension::ConfidentialTransferAccount(convert_confidential_transfer_account(
 						extension,
@@ -100,19 +155,18 @@ pub fn parse_extension<S: BaseState + Pack>(
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::ConfidentialTransferFeeAmount => {
-			account
-				.get_extension::<extension::confidential_transfer_fee::ConfidentialTransferFeeAmount>(
+			pod_from_bytes::<extension::confidential_transfer_fee::ConfidentialTransferFeeAmount>(
+				value,
+			)
+			.map(|&extension| {
+				UiExtension::ConfidentialTransferFeeAmount(
+					convert_confidential_transfer_fee_amount(extension),
 				)
-				.map(|&extension| {
-					UiExtension::ConfidentialTransferFeeAmount(
-						convert_confidential_transfer_fee_amount(extension),
-					)
-				})
-				.unwrap_or(UiExtension::UnparseableExtension)
+			})
+			.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::DefaultAccountState => {
-			account
-				.get_extension::<extension::default_account_state::DefaultAccountState>()
+			pod_from_bytes::<extension::default_account_state::DefaultAccountState>(value)
 				.map(|&extension| {
 					UiExtension::DefaultAccountState(convert_default_account_state(extension))
 				})
@@ -120,29 +174,25 @@ pub fn parse_extension<S: BaseState + Pack>(
 		}
 		ExtensionType::ImmutableOwner => UiExtension::ImmutableOwner,
 		ExtensionType::MemoTransfer => {
-			account
-				.get_extension::<extension::memo_transfer::MemoTransfer>()
+			pod_from_bytes::<extension::memo_transfer::MemoTransfer>(value)
 				.map(|&extension| UiExtension::MemoTransfer(convert_memo_transfer(extension)))
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::NonTransferable => UiExtension::NonTransferable,
 		ExtensionType::InterestBearingConfig => {
-			account
-				.get_extension::<extension::interest_bearing_mint::InterestBearingConfig>()
+			pod_from_bytes::<extension::interest_bearing_mint::InterestBearingConfig>(value)
 				.map(|&extension| {
 					UiExtension::InterestBearingConfig(convert_interest_bearing_config(extension))
 				})
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::CpiGuard => {
-			account
-				.get_extension::<extension::cpi_guard::CpiGuard>()
+			pod_from_bytes::<extension::cpi_guard::CpiGuard>(value)
 				.map(|&extension| UiExtension::CpiGuard(convert_cpi_guard(extension)))
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::PermanentDelegate => {
-			account
-				.get_extension::<extension::permanent_delegate::PermanentDelegate>()
+			pod_from_bytes::<extension::permanent_delegate::PermanentDelegate>(value)
 				.map(|&extension| {
 					UiExtension::PermanentDelegate(convert_permanent_delegate(extension))
 				})
@@ -150,78 +200,67 @@ pub fn parse_extension<S: BaseState + Pack>(
 		}
 		ExtensionType::NonTransferableAccount => UiExtension::NonTransferableAccount,
 		ExtensionType::MetadataPointer => {
-			account
-				.get_extension::<extension::metadata_pointer::MetadataPointer>()
+			pod_from_bytes::<extension::metadata_pointer::MetadataPointer>(value)
 				.map(|&extension| UiExtension::MetadataPointer(convert_metadata_pointer(extension)))
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::TokenMetadata => {
-			account
-				.get_variable_len_extension::<TokenMetadata>()
+			try_from_slice_unchecked::<TokenMetadata>(value)
 				.map(|extension| UiExtension::TokenMetadata(convert_token_metadata(extension)))
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::TransferHook => {
-			account
-				.get_extension::<extension::transfer_hook::TransferHook>()
+			pod_from_bytes::<extension::transfer_hook::TransferHook>(value)
 				.map(|&extension| UiExtension::TransferHook(convert_transfer_hook(extension)))
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::TransferHookAccount => {
-			account
-				.get_extension::<extension::transfer_hook::TransferHookAccount>()
+			pod_from_bytes::<extension::transfer_hook::TransferHookAccount>(value)
 				.map(|&extension| {
 					UiExtension::TransferHookAccount(convert_transfer_hook_account(extension))
 				})
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::GroupPointer => {
-			account
-				.get_extension::<extension::group_pointer::GroupPointer>()
+			pod_from_bytes::<extension::group_pointer::GroupPointer>(value)
 				.map(|&extension| UiExtension::GroupPointer(convert_group_pointer(extension)))
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::GroupMemberPointer => {
-			account
-				.get_extension::<extension::group_member_pointer::GroupMemberPointer>()
+			pod_from_bytes::<extension::group_member_pointer::GroupMemberPointer>(value)
 				.map(|&extension| {
 					UiExtension::GroupMemberPointer(convert_group_member_pointer(extension))
 				})
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::TokenGroup => {
-			account
-				.get_extension::<TokenGroup>()
+			pod_from_bytes::<TokenGroup>(value)
 				.map(|&extension| UiExtension::TokenGroup(convert_token_group(extension)))
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::TokenGroupMember => {
-			account
-				.get_extension::<TokenGroupMember>()
+			pod_from_bytes::<TokenGroupMember>(value)
 				.map(|&extension| {
 					UiExtension::TokenGroupMember(convert_token_group_member(extension))
 				})
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::ConfidentialMintBurn => {
-			account
-				.get_extension::<extension::confidential_mint_burn::ConfidentialMintBurn>()
+			pod_from_bytes::<extension::confidential_mint_burn::ConfidentialMintBurn>(value)
 				.map(|&extension| {
 					UiExtension::ConfidentialMintBurn(convert_confidential_mint_burn(extension))
 				})
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::ScaledUiAmount => {
-			account
-				.get_extension::<extension::scaled_ui_amount::ScaledUiAmountConfig>()
+			pod_from_bytes::<extension::scaled_ui_amount::ScaledUiAmountConfig>(value)
 				.map(|&extension| {
 					UiExtension::ScaledUiAmountConfig(convert_scaled_ui_amount(extension))
 				})
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
 		ExtensionType::Pausable => {
-			account
-				.get_extension::<extension::pausable::PausableConfig>()
+			pod_from_bytes::<extension::pausable::PausableConfig>(value)
 				.map(|&extension| UiExtension::PausableConfig(convert_pausable_config(extension)))
 				.unwrap_or(UiExtension::UnparseableExtension)
 		}
@@ -1,5 +1,7 @@
 use serde::Deserialize;
 use serde::Serialize;
+use serde_with::DisplayFromStr;
+use serde_with::serde_as;
 use solana_clock::Epoch;
 use solana_clock::Slot;
 use solana_pubkey::Pubkey;
@@ -10,6 +12,10 @@ use solana_vote_interface::state::VoteState;
 use crate::StringAmount;
 use crate::parse_account_data::ParseAccountError;
 
+/// Parse a vote account.
+///
+/// The legacy `0_23_5` and `1_14_11` layouts are selected by their version
+/// discriminant and converted into the current layout before parsing.
 pub fn parse_vote(data: &[u8]) -> Result<VoteAccountType, ParseAccountError> {
 	let mut vote_state = VoteState::deserialize(data).map_err(ParseAccountError::from)?;
 	let epoch_credits = vote_state
@@ -26,12 +32,7 @@ pub fn parse_vote(data: &[u8]) -> Result<VoteAccountType, ParseAccountError> {
 	let votes = vote_state
 		.votes
 		.iter()
-		.map(|lockout| {
-			UiLockout {
-				slot: lockout.slot(),
-				confirmation_count: lockout.confirmation_count(),
-			}
-		})
+		.map(|landed_vote| UiLockout::from(&landed_vote.lockout))
 		.collect();
 	let authorized_voters = vote_state
 		.authorized_voters()
@@ -39,7 +40,7 @@ pub fn parse_vote(data: &[u8]) -> Result<VoteAccountType, ParseAccountError> {
 		.map(|(epoch, authorized_voter)| {
 			UiAuthorizedVoters {
 				epoch: *epoch,
-				authorized_voter: authorized_voter.to_string(),
+				authorized_voter: *authorized_voter,
 			}
 		})
 		.collect();
@@ -51,7 +52,7 @@ pub fn parse_vote(data: &[u8]) -> Result<VoteAccountType, ParseAccountError> {
 		.map(
 			|(authorized_pubkey, epoch_of_last_authorized_switch, target_epoch)| {
 				UiPriorVoters {
-					authorized_pubkey: authorized_pubkey.to_string(),
+					authorized_pubkey: *authorized_pubkey,
 					epoch_of_last_authorized_switch: *epoch_of_last_authorized_switch,
 					target_epoch: *target_epoch,
 				}
@@ -59,8 +60,8 @@ pub fn parse_vote(data: &[u8]) -> Result<VoteAccountType, ParseAccountError> {
 		)
 		.collect();
 	Ok(VoteAccountType::Vote(UiVoteState {
-		node_pubkey: vote_state.node_pubkey.to_string(),
-		authorized_withdrawer: vote_state.authorized_withdrawer.to_string(),
+		node_pubkey: vote_state.node_pubkey,
+		authorized_withdrawer: vote_state.authorized_withdrawer,
 		commission: vote_state.commission,
 		votes,
 		root_slot: vote_state.root_slot,
@@ -79,25 +80,30 @@ pub enum VoteAccountType {
 }
 
 /// A duplicate representation of `VoteState` for pretty JSON serialization
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiVoteState {
-	node_pubkey: String,
-	authorized_withdrawer: String,
-	commission: u8,
-	votes: Vec<UiLockout>,
-	root_slot: Option<Slot>,
-	authorized_voters: Vec<UiAuthorizedVoters>,
-	prior_voters: Vec<UiPriorVoters>,
-	epoch_credits: Vec<UiEpochCredits>,
-	last_timestamp: BlockTimestamp,
+	#[serde_as(as = "DisplayFromStr")]
+	pub node_pubkey: Pubkey,
+	#[serde_as(as = "DisplayFromStr")]
+	pub authorized_withdrawer: Pubkey,
+	pub commission: u8,
+	/// The recent votes of the validator, oldest first.
+	pub votes: Vec<UiLockout>,
+	pub root_slot: Option<Slot>,
+	pub authorized_voters: Vec<UiAuthorizedVoters>,
+	pub prior_voters: Vec<UiPriorVoters>,
+	/// The `(epoch, credits, previous_credits)` history of the validator.
+	pub epoch_credits: Vec<UiEpochCredits>,
+	pub last_timestamp: BlockTimestamp,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-struct UiLockout {
-	slot: Slot,
-	confirmation_count: u32,
+pub struct UiLockout {
+	pub slot: Slot,
+	pub confirmation_count: u32,
 }
 
 impl From<&Lockout> for UiLockout {
@@ -109,31 +115,38 @@ impl From<&Lockout> for UiLockout {
 	}
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-struct UiAuthorizedVoters {
-	epoch: Epoch,
-	authorized_voter: String,
+pub struct UiAuthorizedVoters {
+	pub epoch: Epoch,
+	#[serde_as(as = "DisplayFromStr")]
+	pub authorized_voter: Pubkey,
 }
 
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-struct UiPriorVoters {
-	authorized_pubkey: String,
-	epoch_of_last_authorized_switch: Epoch,
-	target_epoch: Epoch,
+pub struct UiPriorVoters {
+	#[serde_as(as = "DisplayFromStr")]
+	pub authorized_pubkey: Pubkey,
+	pub epoch_of_last_authorized_switch: Epoch,
+	pub target_epoch: Epoch,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-struct UiEpochCredits {
-	epoch: Epoch,
-	credits: StringAmount,
-	previous_credits: StringAmount,
+pub struct UiEpochCredits {
+	pub epoch: Epoch,
+	pub credits: StringAmount,
+	pub previous_credits: StringAmount,
 }
 
 #[cfg(test)]
 mod test {
+	use solana_clock::Clock;
+	use solana_vote_interface::state::VoteInit;
+	use solana_vote_interface::state::VoteState1_14_11;
 	use solana_vote_interface::state::VoteStateVersions;
 
 	use super::*;
@@ -145,8 +158,8 @@ mod test {
 		let versioned = VoteStateVersions::new_current(vote_state);
 		VoteState::serialize(&versioned, &mut vote_account_data).unwrap();
 		let expected_vote_state = UiVoteState {
-			node_pubkey: Pubkey::default().to_string(),
-			authorized_withdrawer: Pubkey::default().to_string(),
+			node_pubkey: Pubkey::default(),
+			authorized_withdrawer: Pubkey::default(),
 			..UiVoteState::default()
 		};
 		assert_eq!(
@@ -157,4 +170,74 @@ mod test {
 		let bad_data = vec![0; 4];
 		assert!(parse_vote(&bad_data).is_err());
 	}
+
+	#[test]
+	fn test_parse_vote_versions() {
+		let node_pubkey = Pubkey::new_unique();
+		let authorized_voter = Pubkey::new_unique();
+		let authorized_withdrawer = Pubkey::new_unique();
+		let vote_init = VoteInit {
+			node_pubkey,
+			authorized_voter,
+			authorized_withdrawer,
+			commission: 5,
+		};
+		let clock = Clock {
+			epoch: 1,
+			..Clock::default()
+		};
+		let mut vote_state = VoteState::new(&vote_init, &clock);
+		vote_state
+			.votes
+			.push_back(Lockout::new_with_confirmation_count(10, 2).into());
+		vote_state.root_slot = Some(3);
+		vote_state.increment_credits(1, 5);
+		vote_state.increment_credits(2, 7);
+		vote_state.last_timestamp = BlockTimestamp {
+			slot: 10,
+			timestamp: 100,
+		};
+
+		let expected = VoteAccountType::Vote(UiVoteState {
+			node_pubkey,
+			authorized_withdrawer,
+			commission: 5,
+			votes: vec![UiLockout {
+				slot: 10,
+				confirmation_count: 2,
+			}],
+			root_slot: Some(3),
+			authorized_voters: vec![UiAuthorizedVoters {
+				epoch: 1,
+				authorized_voter,
+			}],
+			prior_voters: vec![],
+			epoch_credits: vec![
+				UiEpochCredits {
+					epoch: 1,
+					credits: 5.to_string(),
+					previous_credits: 0.to_string(),
+				},
+				UiEpochCredits {
+					epoch: 2,
+					credits: 12.to_string(),
+					previous_credits: 5.to_string(),
+				},
+			],
+			last_timestamp: BlockTimestamp {
+				slot: 10,
+				timestamp: 100,
+			},
+		});
+
+		let versions = [
+			VoteStateVersions::new_current(vote_state.clone()),
+			VoteStateVersions::V1_14_11(Box::new(VoteState1_14_11::from(vote_state))),
+		];
+
+		for versioned in versions {
+			let data = bincode::serialize(&versioned).unwrap();
+			assert_eq!(parse_vote(&data).unwrap(), expected);
+		}
+	}
 }
@@ -0,0 +1,227 @@
+//! Convert the messages and metadata of a 3000 transaction block into the
+//! ui types, comparing the previous conversions with the owning conversions.
+//! The allocations made by each conversion are printed before they are
+//! timed.
+//!
+//! Run with `cargo bench -p solana-transaction-status-client-types-wasm`.
+
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::hint::black_box;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use criterion::BatchSize;
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use solana_hash::Hash;
+use solana_message::MessageHeader;
+use solana_message::VersionedMessage;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::v0;
+use solana_message::v0::LoadedAddresses;
+use solana_message::v0::MessageAddressTableLookup;
+use solana_pubkey::Pubkey;
+use solana_transaction_status_client_types_wasm::InnerInstruction;
+use solana_transaction_status_client_types_wasm::InnerInstructions;
+use solana_transaction_status_client_types_wasm::TransactionStatusMeta;
+use solana_transaction_status_client_types_wasm::UiCompiledInstruction;
+use solana_transaction_status_client_types_wasm::UiInnerInstructions;
+use solana_transaction_status_client_types_wasm::UiInstruction;
+use solana_transaction_status_client_types_wasm::UiLoadedAddresses;
+use solana_transaction_status_client_types_wasm::UiRawMessage;
+use solana_transaction_status_client_types_wasm::UiTransactionStatusMeta;
+
+const TRANSACTIONS: usize = 3000;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(ptr, layout) }
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+type Block = Vec<(VersionedMessage, TransactionStatusMeta)>;
+type UiBlock = Vec<(UiRawMessage, UiTransactionStatusMeta)>;
+
+fn ui_conversions(criterion: &mut Criterion) {
+	let block = block();
+	let (before_allocations, before_bytes) = measure(&block, convert_block_before);
+	let (owned_allocations, owned_bytes) = measure(&block, convert_block_owned);
+
+	println!("{TRANSACTIONS} transactions");
+	println!("before: {before_allocations:>8} allocations {before_bytes:>10} bytes");
+	println!("owned:  {owned_allocations:>8} allocations {owned_bytes:>10} bytes");
+	println!(
+		"{:.1}% fewer allocations",
+		100.0 - owned_allocations as f64 * 100.0 / before_allocations as f64
+	);
+
+	let mut group = criterion.benchmark_group("ui_conversions");
+	group.bench_function("before", |bencher| {
+		bencher.iter_batched(
+			|| block.clone(),
+			convert_block_before,
+			BatchSize::LargeInput,
+		);
+	});
+	group.bench_function("owned", |bencher| {
+		bencher.iter_batched(|| block.clone(), convert_block_owned, BatchSize::LargeInput);
+	});
+	group.finish();
+}
+
+criterion_group!(benches, ui_conversions);
+criterion_main!(benches);
+
+fn convert_block_before(block: Block) -> UiBlock {
+	block.into_iter().map(convert_before).collect()
+}
+
+fn convert_block_owned(block: Block) -> UiBlock {
+	block
+		.into_iter()
+		.map(|(message, meta)| {
+			(
+				UiRawMessage::from(message),
+				UiTransactionStatusMeta::from_owned(meta),
+			)
+		})
+		.collect()
+}
+
+/// The conversion before the owning conversions were added. The message
+/// could only be converted by reference and the metadata copied the loaded
+/// addresses and the accounts of every inner instruction.
+fn convert_before(
+	(message, meta): (VersionedMessage, TransactionStatusMeta),
+) -> (UiRawMessage, UiTransactionStatusMeta) {
+	let meta = UiTransactionStatusMeta {
+		err: meta.status.clone().err(),
+		status: meta.status,
+		fee: meta.fee,
+		pre_balances: meta.pre_balances,
+		post_balances: meta.post_balances,
+		inner_instructions: meta.inner_instructions.map(|ixs| {
+			ixs.into_iter()
+				.map(|ix| {
+					UiInnerInstructions {
+						index: ix.index,
+						instructions: ix
+							.instructions
+							.iter()
+							.map(|ix| {
+								UiInstruction::Compiled(UiCompiledInstruction::from(
+									&ix.instruction,
+									ix.stack_height,
+								))
+							})
+							.collect(),
+					}
+				})
+				.collect()
+		}),
+		log_messages: meta.log_messages,
+		pre_token_balances: meta
+			.pre_token_balances
+			.map(|balance| balance.into_iter().collect()),
+		post_token_balances: meta
+			.post_token_balances
+			.map(|balance| balance.into_iter().collect()),
+		rewards: meta.rewards,
+		loaded_addresses: Some(UiLoadedAddresses::from(&meta.loaded_addresses)),
+		return_data: meta.return_data.map(Into::into),
+		compute_units_consumed: meta.compute_units_consumed,
+		cost_units: meta.cost_units,
+	};
+
+	(UiRawMessage::from(&message), meta)
+}
+
+/// Convert a copy of the `block`, counting only the allocations made by the
+/// conversion.
+fn measure(block: &Block, convert: fn(Block) -> UiBlock) -> (usize, usize) {
+	let block = block.clone();
+	let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+	let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+	let converted = black_box(convert(block));
+	let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+	let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes;
+
+	drop(converted);
+
+	(allocations, bytes)
+}
+
+fn block() -> Block {
+	let lookup_table = Pubkey::new_unique();
+
+	(0..TRANSACTIONS)
+		.map(|index| {
+			let account_keys = (0..4).map(|_| Pubkey::new_unique()).collect();
+			let instructions = (0..2)
+				.map(|program| {
+					CompiledInstruction::new_from_raw_parts(3, vec![program; 8], vec![0, 1, 2, 4])
+				})
+				.collect();
+			let message = VersionedMessage::V0(v0::Message {
+				header: MessageHeader {
+					num_required_signatures: 1,
+					num_readonly_signed_accounts: 0,
+					num_readonly_unsigned_accounts: 1,
+				},
+				account_keys,
+				recent_blockhash: Hash::new_unique(),
+				instructions,
+				address_table_lookups: vec![MessageAddressTableLookup {
+					account_key: lookup_table,
+					writable_indexes: vec![0, 1],
+					readonly_indexes: vec![2],
+				}],
+			});
+			let inner_instructions = (0..4)
+				.map(|stack| {
+					InnerInstruction {
+						instruction: CompiledInstruction::new_from_raw_parts(
+							3,
+							vec![stack; 8],
+							vec![0, 1, 4, 5, 6],
+						),
+						stack_height: Some(2),
+					}
+				})
+				.collect();
+			let meta = TransactionStatusMeta {
+				fee: 5000,
+				pre_balances: vec![index as u64; 7],
+				post_balances: vec![index as u64; 7],
+				inner_instructions: Some(vec![InnerInstructions {
+					index: 0,
+					instructions: inner_instructions,
+				}]),
+				loaded_addresses: LoadedAddresses {
+					writable: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+					readonly: vec![Pubkey::new_unique()],
+				},
+				..TransactionStatusMeta::default()
+			};
+
+			(message, meta)
+		})
+		.collect()
+}
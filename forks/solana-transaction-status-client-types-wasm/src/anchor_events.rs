@@ -0,0 +1,201 @@
+//! Extract anchor events from the logs and inner instructions of a
+//! transaction.
+
+use anchor_lang::AnchorDeserialize;
+use anchor_lang::Discriminator;
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use solana_pubkey::Pubkey;
+
+use crate::UiInstruction;
+use crate::UiParsedInstruction;
+use crate::UiTransactionStatusMeta;
+use crate::program_logs::ProgramInvocation;
+
+/// Extract every event of type `T` emitted by `program_id`.
+///
+/// Events are read from the `Program data: ` logs emitted with `emit!` and
+/// from the self invocations made with `emit_cpi!`. The program is matched
+/// against the invocation tree from the logs, so events are found at any
+/// depth, including when the program is invoked by another top level program.
+/// Events emitted with `emit_cpi!` can only be attributed when the
+/// `log_messages` are available.
+///
+/// Data which doesn't start with the discriminator of `T` or which fails to
+/// deserialize is skipped.
+pub fn extract_anchor_events<T: AnchorDeserialize + Discriminator>(
+	meta: &UiTransactionStatusMeta,
+	program_id: &Pubkey,
+) -> Vec<T> {
+	let mut events = vec![];
+
+	for (index, invocation) in meta.parsed_logs().iter().enumerate() {
+		let inner_instructions = u8::try_from(index)
+			.map(|index| meta.inner_instructions_for(index))
+			.unwrap_or_default();
+
+		collect_events(
+			invocation,
+			None,
+			&mut inner_instructions.iter(),
+			program_id,
+			&mut events,
+		);
+	}
+
+	events
+}
+
+/// Walk the invocation tree in order. Every nested invocation is paired with
+/// the next inner instruction of the top level instruction.
+fn collect_events<'a, T: AnchorDeserialize + Discriminator>(
+	invocation: &ProgramInvocation,
+	instruction: Option<&UiInstruction>,
+	inner_instructions: &mut impl Iterator<Item = &'a UiInstruction>,
+	program_id: &Pubkey,
+	events: &mut Vec<T>,
+) {
+	if invocation.program_id == *program_id {
+		let event = instruction.and_then(instruction_data).and_then(|data| {
+			data.strip_prefix(EVENT_IX_TAG_LE)
+				.and_then(decode_event::<T>)
+		});
+
+		events.extend(event);
+
+		events.extend(
+			invocation
+				.data
+				.iter()
+				.flatten()
+				.filter_map(|data| decode_event::<T>(data)),
+		);
+	}
+
+	for child in &invocation.invocations {
+		let instruction = inner_instructions.next();
+		collect_events(child, instruction, inner_instructions, program_id, events);
+	}
+}
+
+fn instruction_data(instruction: &UiInstruction) -> Option<Vec<u8>> {
+	let data = match instruction {
+		UiInstruction::Compiled(compiled) => &compiled.data,
+		UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(decoded)) => &decoded.data,
+		UiInstruction::Parsed(UiParsedInstruction::Parsed(_)) => return None,
+	};
+
+	bs58::decode(data).into_vec().ok()
+}
+
+fn decode_event<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+	let (discriminator, mut rest) = data.split_at_checked(T::DISCRIMINATOR.len())?;
+
+	if discriminator != T::DISCRIMINATOR {
+		return None;
+	}
+
+	T::deserialize(&mut rest).ok()
+}
+
+#[cfg(test)]
+mod test {
+	use base64::Engine;
+	use base64::prelude::BASE64_STANDARD;
+
+	use super::*;
+	use crate::UiCompiledInstruction;
+	use crate::UiInnerInstructions;
+
+	#[derive(Debug, PartialEq)]
+	struct Deposited(u64);
+
+	impl AnchorDeserialize for Deposited {
+		fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+			<u64 as AnchorDeserialize>::deserialize_reader(reader).map(Self)
+		}
+	}
+
+	impl Discriminator for Deposited {
+		const DISCRIMINATOR: &'static [u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+	}
+
+	fn event_data(amount: u64) -> Vec<u8> {
+		let mut data = Deposited::DISCRIMINATOR.to_vec();
+		data.extend(amount.to_le_bytes());
+		data
+	}
+
+	fn compiled(data: &[u8]) -> UiInstruction {
+		UiInstruction::Compiled(UiCompiledInstruction {
+			program_id_index: 0,
+			accounts: vec![],
+			data: bs58::encode(data).into_string(),
+			stack_height: None,
+		})
+	}
+
+	fn meta(
+		logs: Vec<String>,
+		inner_instructions: Vec<UiInnerInstructions>,
+	) -> UiTransactionStatusMeta {
+		UiTransactionStatusMeta {
+			err: None,
+			status: Ok(()),
+			fee: 5000,
+			pre_balances: vec![],
+			post_balances: vec![],
+			inner_instructions: Some(inner_instructions),
+			log_messages: Some(logs),
+			pre_token_balances: None,
+			post_token_balances: None,
+			rewards: None,
+			loaded_addresses: None,
+			return_data: None,
+			compute_units_consumed: None,
+			cost_units: None,
+		}
+	}
+
+	#[test]
+	fn test_extract_anchor_events() {
+		let program_id = Pubkey::new_unique();
+		let router = Pubkey::new_unique();
+		let other = Pubkey::new_unique();
+		let mut cpi_event = EVENT_IX_TAG_LE.to_vec();
+		cpi_event.extend(event_data(3));
+
+		let logs = [
+			format!("Program {program_id} invoke [1]"),
+			format!("Program data: {}", BASE64_STANDARD.encode(event_data(1))),
+			format!("Program {program_id} success"),
+			format!("Program {router} invoke [1]"),
+			format!("Program {other} invoke [2]"),
+			format!("Program data: {}", BASE64_STANDARD.encode(event_data(99))),
+			format!("Program {other} success"),
+			format!("Program {program_id} invoke [2]"),
+			format!("Program data: {}", BASE64_STANDARD.encode(event_data(2))),
+			format!("Program data: {}", BASE64_STANDARD.encode([9; 16])),
+			format!("Program {program_id} invoke [3]"),
+			format!("Program {program_id} success"),
+			format!("Program {program_id} success"),
+			format!("Program {router} success"),
+		]
+		.to_vec();
+		let inner_instructions = vec![UiInnerInstructions {
+			index: 1,
+			instructions: vec![compiled(&cpi_event), compiled(&[]), compiled(&cpi_event)],
+		}];
+
+		let events =
+			extract_anchor_events::<Deposited>(&meta(logs, inner_instructions), &program_id);
+		assert_eq!(events, vec![Deposited(1), Deposited(2), Deposited(3)]);
+	}
+
+	#[test]
+	fn test_extract_anchor_events_without_logs() {
+		let mut meta = meta(vec![], vec![]);
+		meta.log_messages = None;
+
+		assert!(extract_anchor_events::<Deposited>(&meta, &Pubkey::new_unique()).is_empty());
+	}
+}
@@ -0,0 +1,1076 @@
+//! Compact versions of the transaction ui types for binary formats such as
+//! `bincode` and `postcard`, where signatures, hashes and pubkeys are stored
+//! as fixed byte arrays instead of base58 strings.
+//!
+//! The untagged json enums, such as [`EncodedTransaction`], become tagged
+//! enums since a non self describing format can't guess the variant. Use
+//! [`encode_compact`] and [`decode_compact`] to persist the compact types
+//! with a version byte.
+//!
+//! Types which contain a [`UiRawMessage`] are created with `TryFrom`, which
+//! fails when its account keys or blockhash aren't valid base58, and convert
+//! back with `From`.
+
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+use serde_json::Value;
+pub use solana_account_decoder_client_types_wasm::compact::*;
+use solana_account_decoder_client_types_wasm::token::UiTokenAmount;
+use solana_hash::Hash;
+use solana_message::MessageHeader;
+use solana_pubkey::Pubkey;
+use solana_reward_info::RewardType;
+use solana_signature::Signature;
+use solana_transaction::versioned::TransactionVersion;
+use solana_transaction_error::TransactionError;
+use solana_transaction_error::TransactionResult;
+use thiserror::Error;
+
+use crate::ConfirmedTransactionStatusWithSignature;
+use crate::EncodedTransaction;
+use crate::EncodedTransactionWithStatusMeta;
+use crate::ParsedAccount;
+use crate::ParsedAccountSource;
+use crate::ParsedInstruction;
+use crate::Reward;
+use crate::TransactionBinaryEncoding;
+use crate::TransactionTokenBalance;
+use crate::UiAccountsList;
+use crate::UiAddressTableLookup;
+use crate::UiCompiledInstruction;
+use crate::UiConfirmedBlock;
+use crate::UiInnerInstructions;
+use crate::UiInstruction;
+use crate::UiLoadedAddresses;
+use crate::UiMessage;
+use crate::UiParsedInstruction;
+use crate::UiParsedMessage;
+use crate::UiPartiallyDecodedInstruction;
+use crate::UiRawMessage;
+use crate::UiReturnDataEncoding;
+use crate::UiTransaction;
+use crate::UiTransactionReturnData;
+use crate::UiTransactionStatusMeta;
+
+/// A [`ConfirmedTransactionStatusWithSignature`] with the `signature` stored
+/// as 64 bytes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactConfirmedTransactionStatusWithSignature {
+	pub signature: Signature,
+	pub slot: u64,
+	pub err: Option<TransactionError>,
+	pub memo: Option<String>,
+	pub block_time: Option<i64>,
+}
+
+impl From<ConfirmedTransactionStatusWithSignature>
+	for CompactConfirmedTransactionStatusWithSignature
+{
+	fn from(status: ConfirmedTransactionStatusWithSignature) -> Self {
+		Self {
+			signature: status.signature,
+			slot: status.slot,
+			err: status.err,
+			memo: status.memo,
+			block_time: status.block_time,
+		}
+	}
+}
+
+impl From<CompactConfirmedTransactionStatusWithSignature>
+	for ConfirmedTransactionStatusWithSignature
+{
+	fn from(status: CompactConfirmedTransactionStatusWithSignature) -> Self {
+		Self {
+			signature: status.signature,
+			slot: status.slot,
+			err: status.err,
+			memo: status.memo,
+			block_time: status.block_time,
+		}
+	}
+}
+
+/// A [`TransactionTokenBalance`] with the `mint`, `owner` and `program_id`
+/// stored as 32 bytes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactTransactionTokenBalance {
+	pub account_index: u8,
+	pub mint: Pubkey,
+	pub ui_token_amount: UiTokenAmount,
+	pub owner: Option<Pubkey>,
+	pub program_id: Option<Pubkey>,
+}
+
+impl From<TransactionTokenBalance> for CompactTransactionTokenBalance {
+	fn from(balance: TransactionTokenBalance) -> Self {
+		Self {
+			account_index: balance.account_index,
+			mint: balance.mint,
+			ui_token_amount: balance.ui_token_amount,
+			owner: balance.owner,
+			program_id: balance.program_id,
+		}
+	}
+}
+
+impl From<CompactTransactionTokenBalance> for TransactionTokenBalance {
+	fn from(balance: CompactTransactionTokenBalance) -> Self {
+		Self {
+			account_index: balance.account_index,
+			mint: balance.mint,
+			ui_token_amount: balance.ui_token_amount,
+			owner: balance.owner,
+			program_id: balance.program_id,
+		}
+	}
+}
+
+/// The version byte written before the `bincode` data by [`encode_compact`].
+/// It changes whenever the layout of a compact type changes so bytes from an
+/// older release are rejected instead of being misread.
+pub const COMPACT_FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Error)]
+pub enum CompactDecodeError {
+	#[error("the compact data is empty")]
+	MissingVersion,
+	#[error("unsupported compact format version {0}, expected {COMPACT_FORMAT_VERSION}")]
+	UnsupportedVersion(u8),
+	#[error(transparent)]
+	Bincode(#[from] bincode::Error),
+}
+
+/// The error returned when a ui type can't be converted to its compact form.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum CompactConversionError {
+	#[error("invalid account key {0}")]
+	InvalidPubkey(String),
+	#[error("invalid blockhash {0}")]
+	InvalidHash(String),
+}
+
+/// Serialize a compact type with `bincode`, prefixed by the
+/// [`COMPACT_FORMAT_VERSION`].
+pub fn encode_compact<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, bincode::Error> {
+	let mut bytes = vec![COMPACT_FORMAT_VERSION];
+	bincode::serialize_into(&mut bytes, value)?;
+
+	Ok(bytes)
+}
+
+/// Deserialize the bytes created by [`encode_compact`].
+pub fn decode_compact<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, CompactDecodeError> {
+	match bytes.split_first() {
+		None => Err(CompactDecodeError::MissingVersion),
+		Some((&COMPACT_FORMAT_VERSION, data)) => Ok(bincode::deserialize(data)?),
+		Some((&version, _)) => Err(CompactDecodeError::UnsupportedVersion(version)),
+	}
+}
+
+/// The compact [`UiConfirmedBlock`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompactUiConfirmedBlock {
+	pub previous_blockhash: Hash,
+	pub blockhash: Hash,
+	pub parent_slot: u64,
+	pub transactions: Option<Vec<CompactEncodedTransactionWithStatusMeta>>,
+	pub signatures: Option<Vec<Signature>>,
+	pub rewards: Option<Vec<CompactReward>>,
+	pub num_reward_partitions: Option<u64>,
+	pub block_time: Option<i64>,
+	pub block_height: Option<u64>,
+}
+
+impl TryFrom<UiConfirmedBlock> for CompactUiConfirmedBlock {
+	type Error = CompactConversionError;
+
+	fn try_from(block: UiConfirmedBlock) -> Result<Self, Self::Error> {
+		Ok(Self {
+			previous_blockhash: block.previous_blockhash,
+			blockhash: block.blockhash,
+			parent_slot: block.parent_slot,
+			transactions: block.transactions.map(try_convert_all).transpose()?,
+			signatures: block.signatures,
+			rewards: block.rewards.map(convert_all),
+			num_reward_partitions: block.num_reward_partitions,
+			block_time: block.block_time,
+			block_height: block.block_height,
+		})
+	}
+}
+
+impl From<CompactUiConfirmedBlock> for UiConfirmedBlock {
+	fn from(block: CompactUiConfirmedBlock) -> Self {
+		Self {
+			previous_blockhash: block.previous_blockhash,
+			blockhash: block.blockhash,
+			parent_slot: block.parent_slot,
+			transactions: block.transactions.map(convert_all),
+			signatures: block.signatures,
+			rewards: block.rewards.map(convert_all),
+			num_reward_partitions: block.num_reward_partitions,
+			block_time: block.block_time,
+			block_height: block.block_height,
+		}
+	}
+}
+
+/// The compact [`EncodedTransactionWithStatusMeta`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactEncodedTransactionWithStatusMeta {
+	pub transaction: CompactEncodedTransaction,
+	pub meta: Option<CompactUiTransactionStatusMeta>,
+	pub version: Option<CompactTransactionVersion>,
+}
+
+impl TryFrom<EncodedTransactionWithStatusMeta> for CompactEncodedTransactionWithStatusMeta {
+	type Error = CompactConversionError;
+
+	fn try_from(transaction: EncodedTransactionWithStatusMeta) -> Result<Self, Self::Error> {
+		Ok(Self {
+			transaction: transaction.transaction.try_into()?,
+			meta: transaction.meta.map(Into::into),
+			version: transaction.version.map(Into::into),
+		})
+	}
+}
+
+impl From<CompactEncodedTransactionWithStatusMeta> for EncodedTransactionWithStatusMeta {
+	fn from(transaction: CompactEncodedTransactionWithStatusMeta) -> Self {
+		Self {
+			transaction: transaction.transaction.into(),
+			meta: transaction.meta.map(Into::into),
+			version: transaction.version.map(Into::into),
+		}
+	}
+}
+
+/// The [`TransactionVersion`] as a tagged enum, since the untagged json
+/// representation can't be read from `bincode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompactTransactionVersion {
+	Legacy,
+	Number(u8),
+}
+
+impl From<TransactionVersion> for CompactTransactionVersion {
+	fn from(version: TransactionVersion) -> Self {
+		match version {
+			TransactionVersion::Legacy(_) => Self::Legacy,
+			TransactionVersion::Number(number) => Self::Number(number),
+		}
+	}
+}
+
+impl From<CompactTransactionVersion> for TransactionVersion {
+	fn from(version: CompactTransactionVersion) -> Self {
+		match version {
+			CompactTransactionVersion::Legacy => Self::LEGACY,
+			CompactTransactionVersion::Number(number) => Self::Number(number),
+		}
+	}
+}
+
+/// The compact [`EncodedTransaction`]. Unlike the untagged json enum each
+/// variant is tagged so the binary and json encodings survive a round trip.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompactEncodedTransaction {
+	LegacyBinary(String),
+	Binary(String, TransactionBinaryEncoding),
+	Json(CompactUiTransaction),
+	Accounts(CompactUiAccountsList),
+}
+
+impl TryFrom<EncodedTransaction> for CompactEncodedTransaction {
+	type Error = CompactConversionError;
+
+	fn try_from(transaction: EncodedTransaction) -> Result<Self, Self::Error> {
+		let transaction = match transaction {
+			EncodedTransaction::LegacyBinary(blob) => Self::LegacyBinary(blob),
+			EncodedTransaction::Binary(blob, encoding) => Self::Binary(blob, encoding),
+			EncodedTransaction::Json(transaction) => Self::Json(transaction.try_into()?),
+			EncodedTransaction::Accounts(accounts) => Self::Accounts(accounts.into()),
+		};
+
+		Ok(transaction)
+	}
+}
+
+impl From<CompactEncodedTransaction> for EncodedTransaction {
+	fn from(transaction: CompactEncodedTransaction) -> Self {
+		match transaction {
+			CompactEncodedTransaction::LegacyBinary(blob) => Self::LegacyBinary(blob),
+			CompactEncodedTransaction::Binary(blob, encoding) => Self::Binary(blob, encoding),
+			CompactEncodedTransaction::Json(transaction) => Self::Json(transaction.into()),
+			CompactEncodedTransaction::Accounts(accounts) => Self::Accounts(accounts.into()),
+		}
+	}
+}
+
+/// The compact [`UiTransaction`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiTransaction {
+	pub signatures: Vec<Signature>,
+	pub message: CompactUiMessage,
+}
+
+impl TryFrom<UiTransaction> for CompactUiTransaction {
+	type Error = CompactConversionError;
+
+	fn try_from(transaction: UiTransaction) -> Result<Self, Self::Error> {
+		Ok(Self {
+			signatures: transaction.signatures,
+			message: transaction.message.try_into()?,
+		})
+	}
+}
+
+impl From<CompactUiTransaction> for UiTransaction {
+	fn from(transaction: CompactUiTransaction) -> Self {
+		Self {
+			signatures: transaction.signatures,
+			message: transaction.message.into(),
+		}
+	}
+}
+
+/// The compact [`UiMessage`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompactUiMessage {
+	Parsed(CompactUiParsedMessage),
+	Raw(CompactUiRawMessage),
+}
+
+impl TryFrom<UiMessage> for CompactUiMessage {
+	type Error = CompactConversionError;
+
+	fn try_from(message: UiMessage) -> Result<Self, Self::Error> {
+		let message = match message {
+			UiMessage::Parsed(message) => Self::Parsed(message.into()),
+			UiMessage::Raw(message) => Self::Raw(message.try_into()?),
+		};
+
+		Ok(message)
+	}
+}
+
+impl From<CompactUiMessage> for UiMessage {
+	fn from(message: CompactUiMessage) -> Self {
+		match message {
+			CompactUiMessage::Parsed(message) => Self::Parsed(message.into()),
+			CompactUiMessage::Raw(message) => Self::Raw(message.into()),
+		}
+	}
+}
+
+/// The compact [`UiParsedMessage`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiParsedMessage {
+	pub account_keys: Vec<CompactParsedAccount>,
+	pub recent_blockhash: Hash,
+	pub instructions: Vec<CompactUiInstruction>,
+	pub address_table_lookups: Option<Vec<CompactUiAddressTableLookup>>,
+}
+
+impl From<UiParsedMessage> for CompactUiParsedMessage {
+	fn from(message: UiParsedMessage) -> Self {
+		Self {
+			account_keys: convert_all(message.account_keys),
+			recent_blockhash: message.recent_blockhash,
+			instructions: convert_all(message.instructions),
+			address_table_lookups: message.address_table_lookups.map(convert_all),
+		}
+	}
+}
+
+impl From<CompactUiParsedMessage> for UiParsedMessage {
+	fn from(message: CompactUiParsedMessage) -> Self {
+		Self {
+			account_keys: convert_all(message.account_keys),
+			recent_blockhash: message.recent_blockhash,
+			instructions: convert_all(message.instructions),
+			address_table_lookups: message.address_table_lookups.map(convert_all),
+		}
+	}
+}
+
+/// The compact [`UiRawMessage`], with the account keys and blockhash parsed
+/// from base58.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiRawMessage {
+	pub header: MessageHeader,
+	pub account_keys: Vec<Pubkey>,
+	pub recent_blockhash: Hash,
+	pub instructions: Vec<UiCompiledInstruction>,
+	pub address_table_lookups: Option<Vec<CompactUiAddressTableLookup>>,
+}
+
+impl TryFrom<UiRawMessage> for CompactUiRawMessage {
+	type Error = CompactConversionError;
+
+	fn try_from(message: UiRawMessage) -> Result<Self, Self::Error> {
+		let account_keys = message
+			.account_keys
+			.into_iter()
+			.map(|key| {
+				Pubkey::from_str(&key).map_err(|_| CompactConversionError::InvalidPubkey(key))
+			})
+			.collect::<Result<_, _>>()?;
+		let recent_blockhash = Hash::from_str(&message.recent_blockhash)
+			.map_err(|_| CompactConversionError::InvalidHash(message.recent_blockhash))?;
+
+		Ok(Self {
+			header: message.header,
+			account_keys,
+			recent_blockhash,
+			instructions: message.instructions,
+			address_table_lookups: message.address_table_lookups.map(convert_all),
+		})
+	}
+}
+
+impl From<CompactUiRawMessage> for UiRawMessage {
+	fn from(message: CompactUiRawMessage) -> Self {
+		Self {
+			header: message.header,
+			account_keys: message
+				.account_keys
+				.iter()
+				.map(ToString::to_string)
+				.collect(),
+			recent_blockhash: message.recent_blockhash.to_string(),
+			instructions: message.instructions,
+			address_table_lookups: message.address_table_lookups.map(convert_all),
+		}
+	}
+}
+
+/// The compact [`UiAccountsList`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiAccountsList {
+	pub signatures: Vec<Signature>,
+	pub account_keys: Vec<CompactParsedAccount>,
+}
+
+impl From<UiAccountsList> for CompactUiAccountsList {
+	fn from(accounts: UiAccountsList) -> Self {
+		Self {
+			signatures: accounts.signatures,
+			account_keys: convert_all(accounts.account_keys),
+		}
+	}
+}
+
+impl From<CompactUiAccountsList> for UiAccountsList {
+	fn from(accounts: CompactUiAccountsList) -> Self {
+		Self {
+			signatures: accounts.signatures,
+			account_keys: convert_all(accounts.account_keys),
+		}
+	}
+}
+
+/// The compact [`ParsedAccount`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactParsedAccount {
+	pub pubkey: Pubkey,
+	pub writable: bool,
+	pub signer: bool,
+	pub source: Option<ParsedAccountSource>,
+}
+
+impl From<ParsedAccount> for CompactParsedAccount {
+	fn from(account: ParsedAccount) -> Self {
+		Self {
+			pubkey: account.pubkey,
+			writable: account.writable,
+			signer: account.signer,
+			source: account.source,
+		}
+	}
+}
+
+impl From<CompactParsedAccount> for ParsedAccount {
+	fn from(account: CompactParsedAccount) -> Self {
+		Self {
+			pubkey: account.pubkey,
+			writable: account.writable,
+			signer: account.signer,
+			source: account.source,
+		}
+	}
+}
+
+/// The compact [`UiAddressTableLookup`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiAddressTableLookup {
+	pub account_key: Pubkey,
+	pub writable_indexes: Vec<u8>,
+	pub readonly_indexes: Vec<u8>,
+}
+
+impl From<UiAddressTableLookup> for CompactUiAddressTableLookup {
+	fn from(lookup: UiAddressTableLookup) -> Self {
+		Self {
+			account_key: lookup.account_key,
+			writable_indexes: lookup.writable_indexes,
+			readonly_indexes: lookup.readonly_indexes,
+		}
+	}
+}
+
+impl From<CompactUiAddressTableLookup> for UiAddressTableLookup {
+	fn from(lookup: CompactUiAddressTableLookup) -> Self {
+		Self {
+			account_key: lookup.account_key,
+			writable_indexes: lookup.writable_indexes,
+			readonly_indexes: lookup.readonly_indexes,
+		}
+	}
+}
+
+/// The compact [`UiInstruction`], with a tagged variant for each kind of
+/// instruction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompactUiInstruction {
+	Compiled(UiCompiledInstruction),
+	Parsed(CompactParsedInstruction),
+	PartiallyDecoded(CompactUiPartiallyDecodedInstruction),
+}
+
+impl From<UiInstruction> for CompactUiInstruction {
+	fn from(instruction: UiInstruction) -> Self {
+		match instruction {
+			UiInstruction::Compiled(instruction) => Self::Compiled(instruction),
+			UiInstruction::Parsed(UiParsedInstruction::Parsed(instruction)) => {
+				Self::Parsed(instruction.into())
+			}
+			UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(instruction)) => {
+				Self::PartiallyDecoded(instruction.into())
+			}
+		}
+	}
+}
+
+impl From<CompactUiInstruction> for UiInstruction {
+	fn from(instruction: CompactUiInstruction) -> Self {
+		match instruction {
+			CompactUiInstruction::Compiled(instruction) => Self::Compiled(instruction),
+			CompactUiInstruction::Parsed(instruction) => {
+				Self::Parsed(UiParsedInstruction::Parsed(instruction.into()))
+			}
+			CompactUiInstruction::PartiallyDecoded(instruction) => {
+				Self::Parsed(UiParsedInstruction::PartiallyDecoded(instruction.into()))
+			}
+		}
+	}
+}
+
+/// The compact [`ParsedInstruction`] with the `parsed` value stored as a json
+/// string, since `bincode` can't read an untyped [`Value`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactParsedInstruction {
+	pub program: String,
+	pub program_id: Pubkey,
+	pub parsed: String,
+	pub stack_height: Option<u32>,
+}
+
+impl From<ParsedInstruction> for CompactParsedInstruction {
+	fn from(instruction: ParsedInstruction) -> Self {
+		Self {
+			program: instruction.program,
+			program_id: instruction.program_id,
+			parsed: instruction.parsed.to_string(),
+			stack_height: instruction.stack_height,
+		}
+	}
+}
+
+impl From<CompactParsedInstruction> for ParsedInstruction {
+	fn from(instruction: CompactParsedInstruction) -> Self {
+		let parsed =
+			serde_json::from_str(&instruction.parsed).unwrap_or(Value::String(instruction.parsed));
+
+		Self {
+			program: instruction.program,
+			program_id: instruction.program_id,
+			parsed,
+			stack_height: instruction.stack_height,
+		}
+	}
+}
+
+/// The compact [`UiPartiallyDecodedInstruction`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiPartiallyDecodedInstruction {
+	pub program_id: Pubkey,
+	pub accounts: Vec<Pubkey>,
+	pub data: String,
+	pub stack_height: Option<u32>,
+}
+
+impl From<UiPartiallyDecodedInstruction> for CompactUiPartiallyDecodedInstruction {
+	fn from(instruction: UiPartiallyDecodedInstruction) -> Self {
+		Self {
+			program_id: instruction.program_id,
+			accounts: instruction.accounts,
+			data: instruction.data,
+			stack_height: instruction.stack_height,
+		}
+	}
+}
+
+impl From<CompactUiPartiallyDecodedInstruction> for UiPartiallyDecodedInstruction {
+	fn from(instruction: CompactUiPartiallyDecodedInstruction) -> Self {
+		Self {
+			program_id: instruction.program_id,
+			accounts: instruction.accounts,
+			data: instruction.data,
+			stack_height: instruction.stack_height,
+		}
+	}
+}
+
+/// The compact [`UiInnerInstructions`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiInnerInstructions {
+	pub index: u8,
+	pub instructions: Vec<CompactUiInstruction>,
+}
+
+impl From<UiInnerInstructions> for CompactUiInnerInstructions {
+	fn from(inner_instructions: UiInnerInstructions) -> Self {
+		Self {
+			index: inner_instructions.index,
+			instructions: convert_all(inner_instructions.instructions),
+		}
+	}
+}
+
+impl From<CompactUiInnerInstructions> for UiInnerInstructions {
+	fn from(inner_instructions: CompactUiInnerInstructions) -> Self {
+		Self {
+			index: inner_instructions.index,
+			instructions: convert_all(inner_instructions.instructions),
+		}
+	}
+}
+
+/// The compact [`UiTransactionStatusMeta`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiTransactionStatusMeta {
+	pub err: Option<TransactionError>,
+	pub status: TransactionResult<()>,
+	pub fee: u64,
+	pub pre_balances: Vec<u64>,
+	pub post_balances: Vec<u64>,
+	pub inner_instructions: Option<Vec<CompactUiInnerInstructions>>,
+	pub log_messages: Option<Vec<String>>,
+	pub pre_token_balances: Option<Vec<CompactTransactionTokenBalance>>,
+	pub post_token_balances: Option<Vec<CompactTransactionTokenBalance>>,
+	pub rewards: Option<Vec<CompactReward>>,
+	pub loaded_addresses: Option<CompactUiLoadedAddresses>,
+	pub return_data: Option<CompactUiTransactionReturnData>,
+	pub compute_units_consumed: Option<u64>,
+	pub cost_units: Option<u64>,
+}
+
+impl From<UiTransactionStatusMeta> for CompactUiTransactionStatusMeta {
+	fn from(meta: UiTransactionStatusMeta) -> Self {
+		Self {
+			err: meta.err,
+			status: meta.status,
+			fee: meta.fee,
+			pre_balances: meta.pre_balances,
+			post_balances: meta.post_balances,
+			inner_instructions: meta.inner_instructions.map(convert_all),
+			log_messages: meta.log_messages,
+			pre_token_balances: meta.pre_token_balances.map(convert_all),
+			post_token_balances: meta.post_token_balances.map(convert_all),
+			rewards: meta.rewards.map(convert_all),
+			loaded_addresses: meta.loaded_addresses.map(Into::into),
+			return_data: meta.return_data.map(Into::into),
+			compute_units_consumed: meta.compute_units_consumed,
+			cost_units: meta.cost_units,
+		}
+	}
+}
+
+impl From<CompactUiTransactionStatusMeta> for UiTransactionStatusMeta {
+	fn from(meta: CompactUiTransactionStatusMeta) -> Self {
+		Self {
+			err: meta.err,
+			status: meta.status,
+			fee: meta.fee,
+			pre_balances: meta.pre_balances,
+			post_balances: meta.post_balances,
+			inner_instructions: meta.inner_instructions.map(convert_all),
+			log_messages: meta.log_messages,
+			pre_token_balances: meta.pre_token_balances.map(convert_all),
+			post_token_balances: meta.post_token_balances.map(convert_all),
+			rewards: meta.rewards.map(convert_all),
+			loaded_addresses: meta.loaded_addresses.map(Into::into),
+			return_data: meta.return_data.map(Into::into),
+			compute_units_consumed: meta.compute_units_consumed,
+			cost_units: meta.cost_units,
+		}
+	}
+}
+
+/// The compact [`Reward`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactReward {
+	pub pubkey: Pubkey,
+	pub lamports: i64,
+	pub post_balance: u64,
+	pub reward_type: Option<RewardType>,
+	pub commission: Option<u8>,
+}
+
+impl From<Reward> for CompactReward {
+	fn from(reward: Reward) -> Self {
+		Self {
+			pubkey: reward.pubkey,
+			lamports: reward.lamports,
+			post_balance: reward.post_balance,
+			reward_type: reward.reward_type,
+			commission: reward.commission,
+		}
+	}
+}
+
+impl From<CompactReward> for Reward {
+	fn from(reward: CompactReward) -> Self {
+		Self {
+			pubkey: reward.pubkey,
+			lamports: reward.lamports,
+			post_balance: reward.post_balance,
+			reward_type: reward.reward_type,
+			commission: reward.commission,
+		}
+	}
+}
+
+/// The compact [`UiLoadedAddresses`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiLoadedAddresses {
+	pub writable: Vec<Pubkey>,
+	pub readonly: Vec<Pubkey>,
+}
+
+impl From<UiLoadedAddresses> for CompactUiLoadedAddresses {
+	fn from(addresses: UiLoadedAddresses) -> Self {
+		Self {
+			writable: addresses.writable,
+			readonly: addresses.readonly,
+		}
+	}
+}
+
+impl From<CompactUiLoadedAddresses> for UiLoadedAddresses {
+	fn from(addresses: CompactUiLoadedAddresses) -> Self {
+		Self {
+			writable: addresses.writable,
+			readonly: addresses.readonly,
+		}
+	}
+}
+
+/// The compact [`UiTransactionReturnData`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactUiTransactionReturnData {
+	pub program_id: Pubkey,
+	pub data: (String, UiReturnDataEncoding),
+}
+
+impl From<UiTransactionReturnData> for CompactUiTransactionReturnData {
+	fn from(return_data: UiTransactionReturnData) -> Self {
+		Self {
+			program_id: return_data.program_id,
+			data: return_data.data,
+		}
+	}
+}
+
+impl From<CompactUiTransactionReturnData> for UiTransactionReturnData {
+	fn from(return_data: CompactUiTransactionReturnData) -> Self {
+		Self {
+			program_id: return_data.program_id,
+			data: return_data.data,
+		}
+	}
+}
+
+fn convert_all<T, U: From<T>>(items: Vec<T>) -> Vec<U> {
+	items.into_iter().map(Into::into).collect()
+}
+
+fn try_convert_all<T, U: TryFrom<T>>(items: Vec<T>) -> Result<Vec<U>, U::Error> {
+	items.into_iter().map(TryInto::try_into).collect()
+}
+
+#[cfg(test)]
+mod test {
+	use serde_json::json;
+
+	use super::*;
+
+	#[test]
+	fn transaction_status_round_trip() {
+		let status = ConfirmedTransactionStatusWithSignature {
+			signature: Signature::from([7; 64]),
+			slot: 42,
+			err: Some(TransactionError::AccountInUse),
+			memo: Some("[5] hello".into()),
+			block_time: None,
+		};
+		let compact = CompactConfirmedTransactionStatusWithSignature::from(status.clone());
+		let bytes = bincode::serialize(&compact).unwrap();
+
+		assert!(bytes.len() < serde_json::to_vec(&status).unwrap().len());
+		assert_eq!(bytes[..64], [7; 64]);
+		assert_eq!(
+			ConfirmedTransactionStatusWithSignature::from(
+				bincode::deserialize::<CompactConfirmedTransactionStatusWithSignature>(&bytes)
+					.unwrap()
+			),
+			status
+		);
+	}
+
+	#[test]
+	fn token_balance_round_trip() {
+		let balance = TransactionTokenBalance {
+			account_index: 1,
+			mint: Pubkey::new_unique(),
+			ui_token_amount: UiTokenAmount::from_raw(1_500_000, 6),
+			owner: Some(Pubkey::new_unique()),
+			program_id: None,
+		};
+		let compact = CompactTransactionTokenBalance::from(balance.clone());
+		let bytes = bincode::serialize(&compact).unwrap();
+
+		assert_eq!(
+			TransactionTokenBalance::from(
+				bincode::deserialize::<CompactTransactionTokenBalance>(&bytes).unwrap()
+			),
+			balance
+		);
+	}
+
+	const SIGNATURE: &str =
+		"5sstmVwo4cpTq3MzkpdfteEVkj9JiUSzWMhrMrNc4hdLR4nhWWoMYAXtzX5iRKiFaEqHXpnSs2Db5kHngoKWX9EL";
+	const KEY: &str = "92vBXvpoy6Vh5z5dtQR71ee8wseDY3idM8TByDztC6v1";
+	const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+	fn meta() -> UiTransactionStatusMeta {
+		serde_json::from_value(json!({
+			"err": { "InstructionError": [1, { "Custom": 6001 }] },
+			"status": { "Err": { "InstructionError": [1, { "Custom": 6001 }] } },
+			"fee": 5000,
+			"preBalances": [1_000_000, 1],
+			"postBalances": [995_000, 1],
+			"innerInstructions": [{
+				"index": 0,
+				"instructions": [
+					{ "programIdIndex": 1, "accounts": [0], "data": "3Bxs", "stackHeight": 2 },
+					{
+						"program": "spl-token",
+						"programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+						"parsed": { "type": "transfer", "info": { "amount": "10" } },
+						"stackHeight": 2,
+					},
+					{ "programId": KEY, "accounts": [MINT], "data": "3Bxs" },
+				],
+			}],
+			"logMessages": ["Program log: hello"],
+			"preTokenBalances": [{
+				"accountIndex": 1,
+				"mint": MINT,
+				"uiTokenAmount": { "amount": "10", "decimals": 0, "uiAmount": 10.0, "uiAmountString": "10" },
+			}],
+			"postTokenBalances": [],
+			"rewards": [{ "pubkey": KEY, "lamports": -5, "postBalance": 10, "rewardType": "Fee" }],
+			"loadedAddresses": { "writable": [MINT], "readonly": [] },
+			"returnData": { "programId": KEY, "data": ["AQID", "base64"] },
+			"computeUnitsConsumed": 4_500,
+		}))
+		.unwrap()
+	}
+
+	fn transactions() -> Vec<EncodedTransaction> {
+		let header = json!({
+			"numRequiredSignatures": 1,
+			"numReadonlySignedAccounts": 0,
+			"numReadonlyUnsignedAccounts": 1,
+		});
+		let lookups =
+			json!([{ "accountKey": MINT, "writableIndexes": [0], "readonlyIndexes": [1] }]);
+		let account_keys = json!([
+			{ "pubkey": KEY, "writable": true, "signer": true, "source": "transaction" },
+			{ "pubkey": MINT, "writable": true, "signer": false, "source": "lookupTable" },
+		]);
+
+		[
+			json!("3Bxs4h24hBtQy9rw"),
+			json!(["AQID", "base64"]),
+			json!(["3Bxs", "base58"]),
+			json!({
+				"signatures": [SIGNATURE],
+				"message": {
+					"header": header,
+					"accountKeys": [KEY, MINT],
+					"recentBlockhash": MINT,
+					"instructions": [{ "programIdIndex": 1, "accounts": [0], "data": "3Bxs", "stackHeight": null }],
+					"addressTableLookups": lookups,
+				},
+			}),
+			json!({
+				"signatures": [SIGNATURE],
+				"message": {
+					"accountKeys": account_keys,
+					"recentBlockhash": MINT,
+					"instructions": meta().inner_instructions.unwrap()[0].instructions,
+					"addressTableLookups": lookups,
+				},
+			}),
+			json!({ "signatures": [SIGNATURE], "accountKeys": account_keys }),
+		]
+		.into_iter()
+		.map(|transaction| serde_json::from_value(transaction).unwrap())
+		.collect()
+	}
+
+	#[test]
+	fn encoded_transactions_round_trip() {
+		let transactions = transactions();
+
+		assert!(matches!(
+			transactions[0],
+			EncodedTransaction::LegacyBinary(_)
+		));
+		assert!(matches!(transactions[1], EncodedTransaction::Binary(..)));
+		assert!(matches!(
+			transactions[3],
+			EncodedTransaction::Json(UiTransaction {
+				message: UiMessage::Raw(_),
+				..
+			})
+		));
+		assert!(matches!(
+			transactions[4],
+			EncodedTransaction::Json(UiTransaction {
+				message: UiMessage::Parsed(_),
+				..
+			})
+		));
+		assert!(matches!(transactions[5], EncodedTransaction::Accounts(_)));
+
+		for (index, transaction) in transactions.into_iter().enumerate() {
+			let transaction = EncodedTransactionWithStatusMeta {
+				transaction,
+				meta: (index % 2 == 0).then(meta),
+				version: Some(if index % 3 == 0 {
+					TransactionVersion::LEGACY
+				} else {
+					TransactionVersion::Number(0)
+				}),
+			};
+			let compact =
+				CompactEncodedTransactionWithStatusMeta::try_from(transaction.clone()).unwrap();
+			let bytes = encode_compact(&compact).unwrap();
+			let decoded: CompactEncodedTransactionWithStatusMeta = decode_compact(&bytes).unwrap();
+
+			assert_eq!(bytes[0], COMPACT_FORMAT_VERSION);
+			assert_eq!(EncodedTransactionWithStatusMeta::from(decoded), transaction);
+		}
+	}
+
+	#[test]
+	fn block_round_trip() {
+		let block: UiConfirmedBlock = serde_json::from_value(json!({
+			"previousBlockhash": MINT,
+			"blockhash": KEY,
+			"parentSlot": 41,
+			"signatures": [SIGNATURE],
+			"rewards": [{ "pubkey": KEY, "lamports": 5000, "postBalance": 10_000, "rewardType": "Voting", "commission": 10 }],
+			"blockTime": 1_700_000_000,
+			"blockHeight": 40,
+		}))
+		.unwrap();
+		let block = UiConfirmedBlock {
+			transactions: Some(
+				transactions()
+					.into_iter()
+					.map(|transaction| {
+						EncodedTransactionWithStatusMeta {
+							transaction,
+							meta: Some(meta()),
+							version: None,
+						}
+					})
+					.collect(),
+			),
+			..block
+		};
+		let bytes =
+			encode_compact(&CompactUiConfirmedBlock::try_from(block.clone()).unwrap()).unwrap();
+		let decoded: CompactUiConfirmedBlock = decode_compact(&bytes).unwrap();
+
+		assert_eq!(bytes[1..33], *Hash::from_str(MINT).unwrap().as_ref());
+		assert_eq!(UiConfirmedBlock::from(decoded), block);
+	}
+
+	#[test]
+	fn raw_message_keys_are_stored_as_bytes() {
+		let EncodedTransaction::Json(transaction) = transactions().remove(3) else {
+			unreachable!();
+		};
+		let UiMessage::Raw(message) = transaction.message else {
+			unreachable!();
+		};
+		let compact = CompactUiRawMessage::try_from(message.clone()).unwrap();
+
+		assert_eq!(compact.account_keys[0], Pubkey::from_str(KEY).unwrap());
+		assert_eq!(compact.recent_blockhash, Hash::from_str(MINT).unwrap());
+		assert_eq!(UiRawMessage::from(compact), message);
+
+		let invalid = UiRawMessage {
+			account_keys: vec!["0OIl".into()],
+			..message.clone()
+		};
+		assert_eq!(
+			CompactUiRawMessage::try_from(invalid),
+			Err(CompactConversionError::InvalidPubkey("0OIl".into()))
+		);
+
+		let invalid = UiRawMessage {
+			recent_blockhash: "0OIl".into(),
+			..message
+		};
+		assert_eq!(
+			CompactUiRawMessage::try_from(invalid),
+			Err(CompactConversionError::InvalidHash("0OIl".into()))
+		);
+	}
+
+	#[test]
+	fn unsupported_version() {
+		let mut bytes = encode_compact(&CompactUiTransactionStatusMeta::from(meta())).unwrap();
+		bytes[0] = COMPACT_FORMAT_VERSION + 1;
+
+		assert!(matches!(
+			decode_compact::<CompactUiTransactionStatusMeta>(&bytes),
+			Err(CompactDecodeError::UnsupportedVersion(version)) if version == COMPACT_FORMAT_VERSION + 1
+		));
+		assert!(matches!(
+			decode_compact::<CompactUiTransactionStatusMeta>(&[]),
+			Err(CompactDecodeError::MissingVersion)
+		));
+	}
+}
@@ -0,0 +1,219 @@
+//! Conversions between the transaction ui types and javascript values.
+//!
+//! The values use the serializer of the account decoder types so `u64` and
+//! `i64` fields become a `bigint` while pubkeys and signatures stay base58
+//! strings.
+
+pub use solana_account_decoder_client_types_wasm::js::*;
+use wasm_bindgen::prelude::*;
+
+use crate::EncodedConfirmedTransactionWithStatusMeta;
+use crate::EncodedTransactionWithStatusMeta;
+use crate::Reward;
+use crate::TransactionTokenBalance;
+use crate::UiConfirmedBlock;
+use crate::UiTransactionStatusMeta;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPESCRIPT_TYPES: &str = r#"
+export type TransactionError = string | Record<string, unknown>;
+
+export type TransactionResult = { Ok: null } | { Err: TransactionError };
+
+export type TransactionVersion = "legacy" | number;
+
+export type TransactionBinaryEncoding = "base58" | "base64";
+
+export type RewardType = "Fee" | "Rent" | "Staking" | "Voting";
+
+export interface Reward {
+	pubkey: string;
+	lamports: bigint;
+	postBalance: bigint;
+	rewardType?: RewardType;
+	commission?: number;
+}
+
+export interface TransactionTokenBalance {
+	accountIndex: number;
+	mint: string;
+	uiTokenAmount: UiTokenAmount;
+	owner?: string;
+	programId?: string;
+}
+
+export interface UiLoadedAddresses {
+	writable: string[];
+	readonly: string[];
+}
+
+export interface UiTransactionReturnData {
+	programId: string;
+	data: [string, "base64"];
+}
+
+export interface UiCompiledInstruction {
+	programIdIndex: number;
+	accounts: number[];
+	data: string;
+	stackHeight: number | null;
+}
+
+export interface ParsedInstruction {
+	program: string;
+	programId: string;
+	parsed: unknown;
+	stackHeight?: number;
+}
+
+export interface UiPartiallyDecodedInstruction {
+	programId: string;
+	accounts: string[];
+	data: string;
+	stackHeight?: number;
+}
+
+export type UiInstruction =
+	| UiCompiledInstruction
+	| ParsedInstruction
+	| UiPartiallyDecodedInstruction;
+
+export interface UiInnerInstructions {
+	index: number;
+	instructions: UiInstruction[];
+}
+
+export interface UiTransactionStatusMeta {
+	err?: TransactionError;
+	status: TransactionResult;
+	fee: bigint;
+	preBalances: bigint[];
+	postBalances: bigint[];
+	innerInstructions?: UiInnerInstructions[];
+	logMessages?: string[];
+	preTokenBalances?: TransactionTokenBalance[];
+	postTokenBalances?: TransactionTokenBalance[];
+	rewards?: Reward[];
+	loadedAddresses?: UiLoadedAddresses;
+	returnData?: UiTransactionReturnData;
+	computeUnitsConsumed?: bigint;
+	costUnits?: bigint;
+}
+
+export interface MessageHeader {
+	numRequiredSignatures: number;
+	numReadonlySignedAccounts: number;
+	numReadonlyUnsignedAccounts: number;
+}
+
+export interface UiAddressTableLookup {
+	accountKey: string;
+	writableIndexes: number[];
+	readonlyIndexes: number[];
+}
+
+export interface UiRawMessage {
+	header: MessageHeader;
+	accountKeys: string[];
+	recentBlockhash: string;
+	instructions: UiCompiledInstruction[];
+	addressTableLookups?: UiAddressTableLookup[];
+}
+
+/** The `ParsedAccount` of a message, renamed to keep it apart from the parsed account data. */
+export interface ParsedMessageAccount {
+	pubkey: string;
+	writable: boolean;
+	signer: boolean;
+	source?: "transaction" | "lookupTable";
+}
+
+export interface UiParsedMessage {
+	accountKeys: ParsedMessageAccount[];
+	recentBlockhash: string;
+	instructions: UiInstruction[];
+	addressTableLookups?: UiAddressTableLookup[];
+}
+
+export type UiMessage = UiParsedMessage | UiRawMessage;
+
+export interface UiTransaction {
+	signatures: string[];
+	message: UiMessage;
+}
+
+export interface UiAccountsList {
+	signatures: string[];
+	accountKeys: ParsedMessageAccount[];
+}
+
+export type EncodedTransaction =
+	| string
+	| [string, TransactionBinaryEncoding]
+	| UiTransaction
+	| UiAccountsList;
+
+export interface EncodedTransactionWithStatusMeta {
+	transaction: EncodedTransaction;
+	meta: UiTransactionStatusMeta | null;
+	version?: TransactionVersion;
+}
+
+export interface EncodedConfirmedTransactionWithStatusMeta
+	extends EncodedTransactionWithStatusMeta {
+	slot: bigint;
+	blockTime?: bigint;
+}
+
+export interface UiConfirmedBlock {
+	previousBlockhash: string;
+	blockhash: string;
+	parentSlot: bigint;
+	transactions?: EncodedTransactionWithStatusMeta[];
+	signatures?: string[];
+	rewards?: Reward[];
+	numRewardPartitions?: bigint;
+	blockTime?: bigint;
+	blockHeight?: bigint;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+	#[wasm_bindgen(typescript_type = "Reward")]
+	pub type JsReward;
+	#[wasm_bindgen(typescript_type = "TransactionTokenBalance")]
+	pub type JsTransactionTokenBalance;
+	#[wasm_bindgen(typescript_type = "UiTransactionStatusMeta")]
+	pub type JsUiTransactionStatusMeta;
+	#[wasm_bindgen(typescript_type = "EncodedTransactionWithStatusMeta")]
+	pub type JsEncodedTransactionWithStatusMeta;
+	#[wasm_bindgen(typescript_type = "EncodedConfirmedTransactionWithStatusMeta")]
+	pub type JsEncodedConfirmedTransactionWithStatusMeta;
+	#[wasm_bindgen(typescript_type = "UiConfirmedBlock")]
+	pub type JsUiConfirmedBlock;
+}
+
+impl JsConvert for Reward {
+	type Js = JsReward;
+}
+
+impl JsConvert for TransactionTokenBalance {
+	type Js = JsTransactionTokenBalance;
+}
+
+impl JsConvert for UiTransactionStatusMeta {
+	type Js = JsUiTransactionStatusMeta;
+}
+
+impl JsConvert for EncodedTransactionWithStatusMeta {
+	type Js = JsEncodedTransactionWithStatusMeta;
+}
+
+impl JsConvert for EncodedConfirmedTransactionWithStatusMeta {
+	type Js = JsEncodedConfirmedTransactionWithStatusMeta;
+}
+
+impl JsConvert for UiConfirmedBlock {
+	type Js = JsUiConfirmedBlock;
+}
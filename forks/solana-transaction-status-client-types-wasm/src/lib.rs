@@ -1,25 +1,38 @@
 //! Core types for solana-transaction-status
 use core::fmt;
+use core::str::FromStr;
+use std::collections::BTreeMap;
+use std::io::Read;
 
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
+use bincode::Options;
+use borsh::BorshDeserialize;
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
 use serde_json::Value;
 use serde_with::DisplayFromStr;
 use serde_with::serde_as;
 use serde_with::skip_serializing_none;
+pub use solana_account_decoder_client_types_wasm::NumberOrString;
+pub use solana_account_decoder_client_types_wasm::ParseEnumError;
+use solana_account_decoder_client_types_wasm::max_base58_length;
 use solana_account_decoder_client_types_wasm::token::UiTokenAmount;
 use solana_clock::Slot;
 use solana_clock::UnixTimestamp;
 use solana_commitment_config::CommitmentConfig;
 use solana_hash::Hash;
+use solana_instruction::TRANSACTION_LEVEL_STACK_HEIGHT;
+use solana_message::Message;
 use solana_message::MessageHeader;
+use solana_message::VersionedMessage;
 use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::v0;
 use solana_message::v0::LoadedAddresses;
 use solana_message::v0::MessageAddressTableLookup;
 use solana_pubkey::Pubkey;
 use solana_reward_info::RewardType;
+use solana_sdk_ids::vote;
 use solana_signature::Signature;
 use solana_transaction::versioned::TransactionVersion;
 use solana_transaction::versioned::VersionedTransaction;
@@ -28,7 +41,16 @@ use solana_transaction_error::TransactionError;
 use solana_transaction_error::TransactionResult;
 use thiserror::Error;
 
+use crate::program_logs::ProgramInvocation;
+use crate::program_logs::parse_logs;
+
+#[cfg(feature = "anchor")]
+pub mod anchor_events;
+pub mod compact;
+#[cfg(feature = "js")]
+pub mod js;
 pub mod option_serializer;
+pub mod program_logs;
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -57,11 +79,37 @@ impl UiTransactionEncoding {
 	}
 }
 
+impl UiTransactionEncoding {
+	/// The name of the encoding used by the RPC.
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::Binary => "binary",
+			Self::Base64 => "base64",
+			Self::Base58 => "base58",
+			Self::Json => "json",
+			Self::JsonParsed => "jsonParsed",
+		}
+	}
+}
+
 impl fmt::Display for UiTransactionEncoding {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		let v = serde_json::to_value(self).map_err(|_| fmt::Error)?;
-		let s = v.as_str().ok_or(fmt::Error)?;
-		write!(f, "{s}")
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for UiTransactionEncoding {
+	type Err = ParseEnumError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"binary" => Ok(Self::Binary),
+			"base64" => Ok(Self::Base64),
+			"base58" => Ok(Self::Base58),
+			"json" => Ok(Self::Json),
+			"jsonParsed" => Ok(Self::JsonParsed),
+			_ => Err(ParseEnumError::new("transaction encoding", s)),
+		}
 	}
 }
 
@@ -80,10 +128,131 @@ impl Default for TransactionDetails {
 	}
 }
 
+impl TransactionDetails {
+	/// The name of the level of detail used by the RPC.
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::Full => "full",
+			Self::Signatures => "signatures",
+			Self::None => "none",
+			Self::Accounts => "accounts",
+		}
+	}
+}
+
+impl fmt::Display for TransactionDetails {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for TransactionDetails {
+	type Err = ParseEnumError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"full" => Ok(Self::Full),
+			"signatures" => Ok(Self::Signatures),
+			"none" => Ok(Self::None),
+			"accounts" => Ok(Self::Accounts),
+			_ => Err(ParseEnumError::new("transaction details", s)),
+		}
+	}
+}
+
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
 pub enum EncodeError {
 	#[error("Encoding does not support transaction version {0}")]
 	UnsupportedTransactionVersion(u8),
+	#[error("jsonParsed encoding requires the instruction parsers from solana-transaction-status")]
+	JsonParsedUnsupported,
+	#[error("the transaction can't be serialized: {0}")]
+	Serialization(String),
+}
+
+/// The error returned when a `Ui*` type can't be converted back into the type
+/// it was created from.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum UiConversionError {
+	#[error("parsed instructions can't be converted to compiled instructions")]
+	ParsedInstruction,
+	#[error("instruction data is not valid base58")]
+	InvalidInstructionData,
+	#[error("return data is not valid base64")]
+	InvalidReturnData,
+	#[error("parsed messages can't be converted since the account indexes are lost")]
+	ParsedMessage,
+	#[error("invalid account key: {0}")]
+	InvalidAccountKey(String),
+	#[error("invalid recent blockhash: {0}")]
+	InvalidBlockhash(String),
+	#[error("transaction version {0} is not supported")]
+	UnsupportedTransactionVersion(u8),
+	#[error("a legacy transaction can't have address table lookups")]
+	VersionMismatch,
+	#[error("the decoded transaction failed sanitization")]
+	InvalidTransaction,
+}
+
+/// The error returned when an [`EncodedTransaction`] can't be decoded.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum TransactionDecodeError {
+	#[error("the accounts encoding doesn't include the transaction")]
+	AccountsEncoding,
+	#[error("the encoded transaction is larger than the limit of {max_size} bytes")]
+	TooLarge { max_size: usize },
+	#[error("the transaction is not valid {0:?}")]
+	InvalidEncoding(TransactionBinaryEncoding),
+	#[error("the transaction couldn't be deserialized: {0}")]
+	InvalidTransaction(String),
+	#[error("the decoded transaction failed sanitization")]
+	Sanitize,
+	#[error(transparent)]
+	Json(#[from] UiConversionError),
+}
+
+/// The largest serialized transaction accepted by the cluster, which is the
+/// data size of a network packet.
+pub use solana_packet::PACKET_DATA_SIZE;
+
+/// The limits applied by [`EncodedTransaction::try_decode_with_config`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransactionDecodeConfig {
+	/// The number of bytes a transaction may exceed [`PACKET_DATA_SIZE`] by.
+	/// Some older rpc nodes returned transactions slightly over the limit.
+	pub size_slack: usize,
+}
+
+impl TransactionDecodeConfig {
+	/// The largest serialized transaction which will be decoded.
+	pub fn max_size(&self) -> usize {
+		PACKET_DATA_SIZE.saturating_add(self.size_slack)
+	}
+
+	/// Whether a `blob` in the `encoding` could decode to at most
+	/// [`max_size`](Self::max_size) bytes, checked before decoding it.
+	fn fits(&self, blob: &str, encoding: TransactionBinaryEncoding) -> bool {
+		let max_size = self.max_size();
+		let max_length = match encoding {
+			TransactionBinaryEncoding::Base58 => max_base58_length(max_size),
+			TransactionBinaryEncoding::Base64 => max_size.div_ceil(3).saturating_mul(4),
+		};
+
+		blob.len() <= max_length
+	}
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BlockDecodeError {
+	#[error("the block doesn't include the full transactions")]
+	MissingTransactions,
+	#[error("transaction {0} of the block can't be decoded")]
+	InvalidTransaction(usize),
+	#[error("the meta of transaction {index} can't be converted: {source}")]
+	InvalidMeta {
+		index: usize,
+		source: UiConversionError,
+	},
 }
 
 #[serde_as]
@@ -98,7 +267,9 @@ pub struct ConfirmedTransactionStatusWithSignature {
 	pub block_time: Option<i64>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// The confirmation status of a transaction. Statuses are ordered from the
+/// least to the most confirmed so `status >= Confirmed` can be checked.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TransactionConfirmationStatus {
 	Processed,
@@ -106,6 +277,36 @@ pub enum TransactionConfirmationStatus {
 	Finalized,
 }
 
+impl TransactionConfirmationStatus {
+	/// The name of the status used by the RPC.
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			Self::Processed => "processed",
+			Self::Confirmed => "confirmed",
+			Self::Finalized => "finalized",
+		}
+	}
+}
+
+impl fmt::Display for TransactionConfirmationStatus {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl FromStr for TransactionConfirmationStatus {
+	type Err = ParseEnumError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"processed" => Ok(Self::Processed),
+			"confirmed" => Ok(Self::Confirmed),
+			"finalized" => Ok(Self::Finalized),
+			_ => Err(ParseEnumError::new("confirmation status", s)),
+		}
+	}
+}
+
 #[serde_as]
 #[skip_serializing_none]
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -115,16 +316,144 @@ pub struct UiConfirmedBlock {
 	pub previous_blockhash: Hash,
 	#[serde_as(as = "DisplayFromStr")]
 	pub blockhash: Hash,
+	#[serde_as(as = "NumberOrString")]
 	pub parent_slot: u64,
 	pub transactions: Option<Vec<EncodedTransactionWithStatusMeta>>,
 	#[serde_as(as = "Option<Vec<DisplayFromStr>>")]
 	pub signatures: Option<Vec<Signature>>,
 	pub rewards: Option<Rewards>,
+	#[serde_as(as = "Option<NumberOrString>")]
+	pub num_reward_partitions: Option<u64>,
+	#[serde_as(as = "Option<NumberOrString>")]
+	pub block_time: Option<i64>,
+	#[serde_as(as = "Option<NumberOrString>")]
+	pub block_height: Option<u64>,
+}
+
+impl UiConfirmedBlock {
+	/// The transactions which succeeded. Transactions without a `meta` are
+	/// skipped.
+	pub fn successful_transactions(
+		&self,
+	) -> impl Iterator<Item = &EncodedTransactionWithStatusMeta> {
+		self.transactions().filter(|transaction| {
+			transaction
+				.meta
+				.as_ref()
+				.is_some_and(|meta| meta.status.is_ok())
+		})
+	}
+
+	/// The transactions which failed. Transactions without a `meta` are
+	/// skipped.
+	pub fn failed_transactions(&self) -> impl Iterator<Item = &EncodedTransactionWithStatusMeta> {
+		self.transactions().filter(|transaction| {
+			transaction
+				.meta
+				.as_ref()
+				.is_some_and(|meta| meta.status.is_err())
+		})
+	}
+
+	/// The transactions which aren't simple vote transactions. See
+	/// [`EncodedTransactionWithStatusMeta::is_simple_vote_transaction`].
+	pub fn non_vote_transactions(&self) -> impl Iterator<Item = &EncodedTransactionWithStatusMeta> {
+		self.transactions()
+			.filter(|transaction| !transaction.is_simple_vote_transaction())
+	}
+
+	pub fn transaction_count_summary(&self) -> TransactionCountSummary {
+		let mut summary = TransactionCountSummary::default();
+
+		for transaction in self.transactions() {
+			summary.total += 1;
+
+			match transaction.meta.as_ref().map(|meta| meta.status.is_ok()) {
+				Some(true) => summary.successful += 1,
+				Some(false) => summary.failed += 1,
+				None => {}
+			}
+
+			if transaction.is_simple_vote_transaction() {
+				summary.vote += 1;
+			} else {
+				summary.non_vote += 1;
+			}
+		}
+
+		summary
+	}
+
+	fn transactions(&self) -> impl Iterator<Item = &EncodedTransactionWithStatusMeta> {
+		self.transactions.iter().flatten()
+	}
+
+	/// Decode every transaction and meta of the block.
+	///
+	/// The block must have been requested with full transaction details and
+	/// an encoding which can be decoded, so `jsonParsed` and `accounts` blocks
+	/// return an error for their first transaction.
+	pub fn decode(&self) -> Result<DecodedConfirmedBlock, BlockDecodeError> {
+		let transactions = self
+			.transactions
+			.as_ref()
+			.ok_or(BlockDecodeError::MissingTransactions)?
+			.iter()
+			.enumerate()
+			.map(|(index, transaction)| {
+				let decoded = transaction
+					.decode_transaction()
+					.ok_or(BlockDecodeError::InvalidTransaction(index))?;
+				let meta = transaction
+					.meta
+					.clone()
+					.map(TransactionStatusMeta::try_from)
+					.transpose()
+					.map_err(|source| BlockDecodeError::InvalidMeta { index, source })?;
+
+				Ok((decoded, meta))
+			})
+			.collect::<Result<_, _>>()?;
+
+		Ok(DecodedConfirmedBlock {
+			previous_blockhash: self.previous_blockhash,
+			blockhash: self.blockhash,
+			parent_slot: self.parent_slot,
+			transactions,
+			rewards: self.rewards.clone(),
+			num_reward_partitions: self.num_reward_partitions,
+			block_time: self.block_time,
+			block_height: self.block_height,
+		})
+	}
+}
+
+/// A block with the transactions decoded from a [`UiConfirmedBlock`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedConfirmedBlock {
+	pub previous_blockhash: Hash,
+	pub blockhash: Hash,
+	pub parent_slot: u64,
+	/// The transactions in the order of the block with their meta when it was
+	/// included.
+	pub transactions: Vec<(VersionedTransaction, Option<TransactionStatusMeta>)>,
+	pub rewards: Option<Rewards>,
 	pub num_reward_partitions: Option<u64>,
 	pub block_time: Option<i64>,
 	pub block_height: Option<u64>,
 }
 
+/// The number of transactions in a block. Transactions without a `meta` are
+/// neither `successful` nor `failed`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransactionCountSummary {
+	pub total: usize,
+	pub successful: usize,
+	pub failed: usize,
+	pub vote: usize,
+	pub non_vote: usize,
+}
+
 /// A duplicate representation of a Transaction for pretty JSON serialization
 #[serde_as]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -186,30 +515,241 @@ pub enum EncodedTransaction {
 	Accounts(UiAccountsList),
 }
 
+impl UiTransaction {
+	/// Rebuild the transaction from the `json` encoding. Messages with
+	/// `address_table_lookups` are decoded as `v0` messages.
+	pub fn decode(&self) -> Option<VersionedTransaction> {
+		self.try_decode(None).ok()
+	}
+
+	/// Rebuild the transaction from a raw `json` message.
+	///
+	/// The `version` is usually taken from the wrapping
+	/// [`EncodedTransactionWithStatusMeta`]. When it's `None` the message is
+	/// decoded as `v0` if it has `address_table_lookups` and as legacy
+	/// otherwise. Parsed messages can't be decoded.
+	pub fn try_decode(
+		&self,
+		version: Option<TransactionVersion>,
+	) -> Result<VersionedTransaction, UiConversionError> {
+		let UiMessage::Raw(message) = &self.message else {
+			return Err(UiConversionError::ParsedMessage);
+		};
+		let header = message.header;
+		let account_keys = message
+			.account_keys
+			.iter()
+			.map(|account_key| {
+				account_key
+					.parse()
+					.map_err(|_| UiConversionError::InvalidAccountKey(account_key.clone()))
+			})
+			.collect::<Result<Vec<Pubkey>, _>>()?;
+		let recent_blockhash = message
+			.recent_blockhash
+			.parse()
+			.map_err(|_| UiConversionError::InvalidBlockhash(message.recent_blockhash.clone()))?;
+		let instructions = message
+			.instructions
+			.iter()
+			.map(|instruction| {
+				let data = bs58::decode(&instruction.data)
+					.into_vec()
+					.map_err(|_| UiConversionError::InvalidInstructionData)?;
+
+				Ok(CompiledInstruction {
+					program_id_index: instruction.program_id_index,
+					accounts: instruction.accounts.clone(),
+					data,
+				})
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let version = version.unwrap_or(if message.address_table_lookups.is_some() {
+			TransactionVersion::Number(0)
+		} else {
+			TransactionVersion::LEGACY
+		});
+		let message = match version {
+			TransactionVersion::Legacy(_)
+				if message
+					.address_table_lookups
+					.as_ref()
+					.is_some_and(|lookups| !lookups.is_empty()) =>
+			{
+				return Err(UiConversionError::VersionMismatch);
+			}
+			TransactionVersion::Legacy(_) => {
+				VersionedMessage::Legacy(Message {
+					header,
+					account_keys,
+					recent_blockhash,
+					instructions,
+				})
+			}
+			TransactionVersion::Number(0) => {
+				VersionedMessage::V0(v0::Message {
+					header,
+					account_keys,
+					recent_blockhash,
+					instructions,
+					address_table_lookups: message
+						.address_table_lookups
+						.iter()
+						.flatten()
+						.map(Into::into)
+						.collect(),
+				})
+			}
+			TransactionVersion::Number(version) => {
+				return Err(UiConversionError::UnsupportedTransactionVersion(version));
+			}
+		};
+		let transaction = VersionedTransaction {
+			signatures: self.signatures.clone(),
+			message,
+		};
+
+		transaction
+			.sanitize()
+			.map_err(|_| UiConversionError::InvalidTransaction)?;
+
+		Ok(transaction)
+	}
+}
+
 impl EncodedTransaction {
+	/// Encode a transaction in the shape returned by the RPC. Every
+	/// transaction version is supported and the `jsonParsed` encoding isn't
+	/// available since it needs the instruction parsers.
+	pub fn encode(
+		transaction: &VersionedTransaction,
+		encoding: UiTransactionEncoding,
+	) -> Result<Self, EncodeError> {
+		Self::encode_with_max_supported_version(transaction, encoding, Some(u8::MAX))
+	}
+
+	/// Encode a transaction while rejecting versions above
+	/// `max_supported_transaction_version`. As with the RPC, `None` only
+	/// accepts legacy transactions.
+	pub fn encode_with_max_supported_version(
+		transaction: &VersionedTransaction,
+		encoding: UiTransactionEncoding,
+		max_supported_transaction_version: Option<u8>,
+	) -> Result<Self, EncodeError> {
+		match transaction.version() {
+			TransactionVersion::Number(version)
+				if max_supported_transaction_version
+					.is_none_or(|max_version| version > max_version) =>
+			{
+				return Err(EncodeError::UnsupportedTransactionVersion(version));
+			}
+			_ => {}
+		}
+
+		let serialize = || {
+			bincode::serialize(transaction)
+				.map_err(|error| EncodeError::Serialization(error.to_string()))
+		};
+		let encoded = match encoding {
+			UiTransactionEncoding::Binary => {
+				Self::LegacyBinary(bs58::encode(serialize()?).into_string())
+			}
+			UiTransactionEncoding::Base58 => {
+				Self::Binary(
+					bs58::encode(serialize()?).into_string(),
+					TransactionBinaryEncoding::Base58,
+				)
+			}
+			UiTransactionEncoding::Base64 => {
+				Self::Binary(
+					BASE64_STANDARD.encode(serialize()?),
+					TransactionBinaryEncoding::Base64,
+				)
+			}
+			UiTransactionEncoding::Json => {
+				Self::Json(UiTransaction {
+					signatures: transaction.signatures.clone(),
+					message: UiMessage::Raw(UiRawMessage::from(&transaction.message)),
+				})
+			}
+			UiTransactionEncoding::JsonParsed => return Err(EncodeError::JsonParsedUnsupported),
+		};
+
+		Ok(encoded)
+	}
+
 	pub fn decode(&self) -> Option<VersionedTransaction> {
+		self.try_decode().ok()
+	}
+
+	/// Decode the transaction, rejecting transactions larger than
+	/// [`PACKET_DATA_SIZE`].
+	pub fn try_decode(&self) -> Result<VersionedTransaction, TransactionDecodeError> {
+		self.try_decode_with_config(TransactionDecodeConfig::default())
+	}
+
+	/// Decode the transaction with the size limits of the `config`.
+	///
+	/// The size of binary encodings is checked before they are decoded and
+	/// the bytes are deserialized with the same limit, so a huge blob from an
+	/// untrusted source is rejected without allocating for it.
+	pub fn try_decode_with_config(
+		&self,
+		config: TransactionDecodeConfig,
+	) -> Result<VersionedTransaction, TransactionDecodeError> {
+		self.decode_with_version(None, config)
+	}
+
+	fn decode_with_version(
+		&self,
+		version: Option<TransactionVersion>,
+		config: TransactionDecodeConfig,
+	) -> Result<VersionedTransaction, TransactionDecodeError> {
+		let max_size = config.max_size();
 		let (blob, encoding) = match self {
-			Self::Json(_) | Self::Accounts(_) => return None,
+			Self::Accounts(_) => return Err(TransactionDecodeError::AccountsEncoding),
+			Self::Json(transaction) => {
+				let transaction = transaction.try_decode(version)?;
+				let size = bincode::serialized_size(&transaction).map_err(|error| {
+					TransactionDecodeError::InvalidTransaction(error.to_string())
+				})?;
+
+				if size > max_size as u64 {
+					return Err(TransactionDecodeError::TooLarge { max_size });
+				}
+
+				return Ok(transaction);
+			}
 			Self::LegacyBinary(blob) => (blob, TransactionBinaryEncoding::Base58),
 			Self::Binary(blob, encoding) => (blob, *encoding),
 		};
 
-		let transaction: Option<VersionedTransaction> = match encoding {
-			TransactionBinaryEncoding::Base58 => {
-				bs58::decode(blob)
-					.into_vec()
-					.ok()
-					.and_then(|bytes| bincode::deserialize(&bytes).ok())
-			}
-			TransactionBinaryEncoding::Base64 => {
-				BASE64_STANDARD
-					.decode(blob)
-					.ok()
-					.and_then(|bytes| bincode::deserialize(&bytes).ok())
-			}
-		};
+		if !config.fits(blob, encoding) {
+			return Err(TransactionDecodeError::TooLarge { max_size });
+		}
+
+		let bytes = match encoding {
+			TransactionBinaryEncoding::Base58 => bs58::decode(blob).into_vec().ok(),
+			TransactionBinaryEncoding::Base64 => BASE64_STANDARD.decode(blob).ok(),
+		}
+		.ok_or(TransactionDecodeError::InvalidEncoding(encoding))?;
+
+		if bytes.len() > max_size {
+			return Err(TransactionDecodeError::TooLarge { max_size });
+		}
 
-		transaction.filter(|transaction| transaction.sanitize().is_ok())
+		let transaction: VersionedTransaction = bincode::options()
+			.with_limit(max_size as u64)
+			.with_fixint_encoding()
+			.allow_trailing_bytes()
+			.deserialize(&bytes)
+			.map_err(|error| TransactionDecodeError::InvalidTransaction(error.to_string()))?;
+
+		transaction
+			.sanitize()
+			.map_err(|_| TransactionDecodeError::Sanitize)?;
+
+		Ok(transaction)
 	}
 }
 
@@ -222,6 +762,203 @@ pub struct EncodedTransactionWithStatusMeta {
 	pub version: Option<TransactionVersion>,
 }
 
+impl EncodedTransactionWithStatusMeta {
+	/// Decode the transaction using the `version` of the wrapper to choose the
+	/// message format of `json` encoded transactions.
+	pub fn decode_transaction(&self) -> Option<VersionedTransaction> {
+		self.try_decode_transaction().ok()
+	}
+
+	/// Decode the transaction like [`EncodedTransaction::try_decode`], using
+	/// the `version` of the wrapper for `json` encoded transactions.
+	pub fn try_decode_transaction(&self) -> Result<VersionedTransaction, TransactionDecodeError> {
+		self.transaction
+			.decode_with_version(self.version.clone(), TransactionDecodeConfig::default())
+	}
+
+	/// The accounts of the transaction in the order referenced by instruction
+	/// account indices. The static keys of the message come first, followed by
+	/// the writable and then the readonly addresses loaded from lookup tables.
+	///
+	/// Returns `None` when the transaction can't be decoded or when it uses
+	/// lookup tables and the `loaded_addresses` of the meta are missing.
+	pub fn account_keys(&self) -> Option<Vec<ParsedAccount>> {
+		match &self.transaction {
+			EncodedTransaction::Json(UiTransaction {
+				message: UiMessage::Parsed(message),
+				..
+			}) => Some(message.account_keys.clone()),
+			EncodedTransaction::Accounts(accounts) => Some(accounts.account_keys.clone()),
+			_ => {
+				let transaction = self.decode_transaction()?;
+				let loaded_addresses = self
+					.meta
+					.as_ref()
+					.and_then(|meta| meta.loaded_addresses.as_ref());
+
+				parse_account_keys(&transaction.message, loaded_addresses)
+			}
+		}
+	}
+
+	/// The pubkeys of the accounts used by the `instruction`. Indices which
+	/// are out of range are skipped.
+	pub fn resolve_instruction_accounts(&self, instruction: &UiCompiledInstruction) -> Vec<Pubkey> {
+		let Some(account_keys) = self.account_keys() else {
+			return vec![];
+		};
+
+		instruction
+			.accounts
+			.iter()
+			.filter_map(|index| account_keys.get(usize::from(*index)))
+			.map(|account| account.pubkey)
+			.collect()
+	}
+
+	/// The top level instructions of the transaction. Returns `None` for the
+	/// `accounts` encoding which doesn't include the instructions.
+	pub fn instructions(&self) -> Option<Vec<UiInstruction>> {
+		match &self.transaction {
+			EncodedTransaction::Json(UiTransaction {
+				message: UiMessage::Parsed(message),
+				..
+			}) => Some(message.instructions.clone()),
+			EncodedTransaction::Json(UiTransaction {
+				message: UiMessage::Raw(message),
+				..
+			}) => {
+				Some(
+					message
+						.instructions
+						.iter()
+						.cloned()
+						.map(UiInstruction::Compiled)
+						.collect(),
+				)
+			}
+			EncodedTransaction::Accounts(_) => None,
+			transaction => {
+				let message = UiRawMessage::from(&transaction.decode()?.message);
+				Some(
+					message
+						.instructions
+						.into_iter()
+						.map(UiInstruction::Compiled)
+						.collect(),
+				)
+			}
+		}
+	}
+
+	/// Whether the transaction only contains a single instruction to the vote
+	/// program. Transactions encoded without their instructions are never
+	/// considered votes.
+	pub fn is_simple_vote_transaction(&self) -> bool {
+		let Some(instructions) = self.instructions() else {
+			return false;
+		};
+		let [instruction] = instructions.as_slice() else {
+			return false;
+		};
+		// Programs are always static keys so the lookup table addresses aren't
+		// needed when the `meta` is missing.
+		let account_keys: Vec<Pubkey> = match self.account_keys() {
+			Some(account_keys) => {
+				account_keys
+					.into_iter()
+					.map(|account| account.pubkey)
+					.collect()
+			}
+			None => {
+				self.decode_transaction()
+					.map(|transaction| transaction.message.static_account_keys().to_vec())
+					.unwrap_or_default()
+			}
+		};
+
+		instruction.program_id(&account_keys) == Some(vote::id())
+	}
+
+	/// The account which paid the fees of the transaction.
+	pub fn fee_payer(&self) -> Option<Pubkey> {
+		match &self.transaction {
+			EncodedTransaction::Json(UiTransaction {
+				message: UiMessage::Parsed(message),
+				..
+			}) => message.account_keys.first().map(|account| account.pubkey),
+			EncodedTransaction::Accounts(accounts) => {
+				accounts.account_keys.first().map(|account| account.pubkey)
+			}
+			_ => {
+				self.decode_transaction()?
+					.message
+					.static_account_keys()
+					.first()
+					.copied()
+			}
+		}
+	}
+}
+
+fn parse_account_keys(
+	message: &VersionedMessage,
+	loaded_addresses: Option<&UiLoadedAddresses>,
+) -> Option<Vec<ParsedAccount>> {
+	let mut account_keys: Vec<ParsedAccount> = message
+		.static_account_keys()
+		.iter()
+		.enumerate()
+		.map(|(index, pubkey)| {
+			ParsedAccount {
+				pubkey: *pubkey,
+				writable: message.is_maybe_writable(index, None),
+				signer: message.is_signer(index),
+				source: Some(ParsedAccountSource::Transaction),
+			}
+		})
+		.collect();
+	let lookups = message.address_table_lookups().unwrap_or_default();
+
+	if lookups.is_empty() {
+		return Some(account_keys);
+	}
+
+	let loaded_addresses = loaded_addresses?;
+	let writable_count: usize = lookups
+		.iter()
+		.map(|lookup| lookup.writable_indexes.len())
+		.sum();
+	let readonly_count: usize = lookups
+		.iter()
+		.map(|lookup| lookup.readonly_indexes.len())
+		.sum();
+
+	if loaded_addresses.writable.len() != writable_count
+		|| loaded_addresses.readonly.len() != readonly_count
+	{
+		return None;
+	}
+
+	let loaded = |pubkeys: &[Pubkey], writable: bool| {
+		pubkeys
+			.iter()
+			.map(move |pubkey| {
+				ParsedAccount {
+					pubkey: *pubkey,
+					writable,
+					signer: false,
+					source: Some(ParsedAccountSource::LookupTable),
+				}
+			})
+			.collect::<Vec<_>>()
+	};
+	account_keys.extend(loaded(&loaded_addresses.writable, true));
+	account_keys.extend(loaded(&loaded_addresses.readonly, false));
+
+	Some(account_keys)
+}
+
 #[serde_as]
 #[skip_serializing_none]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -229,7 +966,9 @@ pub struct EncodedTransactionWithStatusMeta {
 pub struct Reward {
 	#[serde_as(as = "DisplayFromStr")]
 	pub pubkey: Pubkey,
+	#[serde_as(as = "NumberOrString")]
 	pub lamports: i64,
+	#[serde_as(as = "NumberOrString")]
 	pub post_balance: u64, // Account balance in lamports after `lamports` was applied
 	pub reward_type: Option<RewardType>,
 	pub commission: Option<u8>, /* Vote account commission when the reward was credited, only
@@ -251,6 +990,16 @@ pub struct UiAddressTableLookup {
 	pub readonly_indexes: Vec<u8>,
 }
 
+impl From<&UiAddressTableLookup> for MessageAddressTableLookup {
+	fn from(lookup: &UiAddressTableLookup) -> Self {
+		Self {
+			account_key: lookup.account_key,
+			writable_indexes: lookup.writable_indexes.clone(),
+			readonly_indexes: lookup.readonly_indexes.clone(),
+		}
+	}
+}
+
 impl From<&MessageAddressTableLookup> for UiAddressTableLookup {
 	fn from(lookup: &MessageAddressTableLookup) -> Self {
 		Self {
@@ -261,6 +1010,26 @@ impl From<&MessageAddressTableLookup> for UiAddressTableLookup {
 	}
 }
 
+impl From<UiAddressTableLookup> for MessageAddressTableLookup {
+	fn from(lookup: UiAddressTableLookup) -> Self {
+		Self {
+			account_key: lookup.account_key,
+			writable_indexes: lookup.writable_indexes,
+			readonly_indexes: lookup.readonly_indexes,
+		}
+	}
+}
+
+impl From<MessageAddressTableLookup> for UiAddressTableLookup {
+	fn from(lookup: MessageAddressTableLookup) -> Self {
+		Self {
+			account_key: lookup.account_key,
+			writable_indexes: lookup.writable_indexes,
+			readonly_indexes: lookup.readonly_indexes,
+		}
+	}
+}
+
 /// A duplicate representation of TransactionStatusMeta with `err` field
 #[serde_as]
 #[skip_serializing_none]
@@ -269,8 +1038,13 @@ impl From<&MessageAddressTableLookup> for UiAddressTableLookup {
 pub struct UiTransactionStatusMeta {
 	pub err: Option<TransactionError>,
 	pub status: TransactionResult<()>, /* This field is deprecated.  See https://github.com/solana-labs/solana/issues/9302 */
+	#[serde_as(as = "NumberOrString")]
 	pub fee: u64,
+	#[serde(default)]
+	#[serde_as(as = "Vec<NumberOrString>")]
 	pub pre_balances: Vec<u64>,
+	#[serde(default)]
+	#[serde_as(as = "Vec<NumberOrString>")]
 	pub post_balances: Vec<u64>,
 	pub inner_instructions: Option<Vec<UiInnerInstructions>>,
 	pub log_messages: Option<Vec<String>>,
@@ -279,12 +1053,47 @@ pub struct UiTransactionStatusMeta {
 	pub rewards: Option<Rewards>,
 	pub loaded_addresses: Option<UiLoadedAddresses>,
 	pub return_data: Option<UiTransactionReturnData>,
+	#[serde_as(as = "Option<NumberOrString>")]
 	pub compute_units_consumed: Option<u64>,
+	#[serde_as(as = "Option<NumberOrString>")]
 	pub cost_units: Option<u64>,
 }
 
 impl From<TransactionStatusMeta> for UiTransactionStatusMeta {
 	fn from(meta: TransactionStatusMeta) -> Self {
+		Self::from_owned(meta)
+	}
+}
+
+impl TryFrom<UiTransactionStatusMeta> for TransactionStatusMeta {
+	type Error = UiConversionError;
+
+	fn try_from(meta: UiTransactionStatusMeta) -> Result<Self, Self::Error> {
+		Ok(Self {
+			status: meta.status,
+			fee: meta.fee,
+			pre_balances: meta.pre_balances,
+			post_balances: meta.post_balances,
+			inner_instructions: meta
+				.inner_instructions
+				.map(|ixs| ixs.into_iter().map(TryInto::try_into).collect())
+				.transpose()?,
+			log_messages: meta.log_messages,
+			pre_token_balances: meta.pre_token_balances,
+			post_token_balances: meta.post_token_balances,
+			rewards: meta.rewards,
+			loaded_addresses: meta.loaded_addresses.map(Into::into).unwrap_or_default(),
+			return_data: meta.return_data.map(TryInto::try_into).transpose()?,
+			compute_units_consumed: meta.compute_units_consumed,
+			cost_units: meta.cost_units,
+		})
+	}
+}
+
+impl UiTransactionStatusMeta {
+	/// Convert the `meta` by moving its vectors instead of copying them. Only
+	/// the instruction data and the return data are encoded into new strings.
+	pub fn from_owned(meta: TransactionStatusMeta) -> Self {
 		Self {
 			err: meta.status.clone().err(),
 			status: meta.status,
@@ -295,19 +1104,222 @@ impl From<TransactionStatusMeta> for UiTransactionStatusMeta {
 				.inner_instructions
 				.map(|ixs| ixs.into_iter().map(Into::into).collect()),
 			log_messages: meta.log_messages,
-			pre_token_balances: meta
-				.pre_token_balances
-				.map(|balance| balance.into_iter().collect()),
-			post_token_balances: meta
-				.post_token_balances
-				.map(|balance| balance.into_iter().collect()),
+			pre_token_balances: meta.pre_token_balances,
+			post_token_balances: meta.post_token_balances,
 			rewards: meta.rewards,
-			loaded_addresses: Some(UiLoadedAddresses::from(&meta.loaded_addresses)),
+			loaded_addresses: Some(meta.loaded_addresses.into()),
 			return_data: meta.return_data.map(Into::into),
 			compute_units_consumed: meta.compute_units_consumed,
 			cost_units: meta.cost_units,
 		}
 	}
+
+	/// The return data of the transaction when it was set by `program_id`.
+	pub fn return_data_for(&self, program_id: &Pubkey) -> Option<&UiTransactionReturnData> {
+		self.return_data
+			.as_ref()
+			.filter(|return_data| return_data.program_id == *program_id)
+	}
+
+	/// The program invocation tree reconstructed from the `log_messages`. See
+	/// [`parse_logs`].
+	pub fn parsed_logs(&self) -> Vec<ProgramInvocation> {
+		self.log_messages
+			.as_deref()
+			.map(parse_logs)
+			.unwrap_or_default()
+	}
+
+	/// The inner instructions invoked by the top level instruction at
+	/// `index`.
+	pub fn inner_instructions_for(&self, index: u8) -> &[UiInstruction] {
+		self.inner_instructions
+			.iter()
+			.flatten()
+			.find(|inner| inner.index == index)
+			.map(|inner| inner.instructions.as_slice())
+			.unwrap_or_default()
+	}
+
+	/// Every instruction of the transaction in execution order. Each of the
+	/// top level `instructions` is followed by the inner instructions it
+	/// invoked.
+	pub fn flatten_instructions<'a>(
+		&'a self,
+		instructions: &'a [UiInstruction],
+	) -> Vec<FlattenedInstruction<'a>> {
+		let mut flattened = vec![];
+
+		for (outer_index, instruction) in instructions.iter().enumerate() {
+			flattened.push(FlattenedInstruction {
+				outer_index,
+				inner_index: None,
+				stack_height: instruction
+					.stack_height()
+					.or(Some(TRANSACTION_LEVEL_STACK_HEIGHT as u32)),
+				instruction,
+			});
+
+			let Ok(index) = u8::try_from(outer_index) else {
+				continue;
+			};
+
+			flattened.extend(self.inner_instructions_for(index).iter().enumerate().map(
+				|(inner_index, instruction)| {
+					FlattenedInstruction {
+						outer_index,
+						inner_index: Some(inner_index),
+						stack_height: instruction.stack_height(),
+						instruction,
+					}
+				},
+			));
+		}
+
+		flattened
+	}
+
+	/// The top level and inner instructions which invoke `program_id`, in
+	/// execution order.
+	///
+	/// The `account_keys` are used to resolve the program of compiled
+	/// instructions. They must include the loaded addresses, as returned by
+	/// [`EncodedTransactionWithStatusMeta::account_keys`].
+	pub fn find_instructions_by_program<'a>(
+		&'a self,
+		instructions: &'a [UiInstruction],
+		program_id: &Pubkey,
+		account_keys: &[Pubkey],
+	) -> Vec<FlattenedInstruction<'a>> {
+		self.flatten_instructions(instructions)
+			.into_iter()
+			.filter(|flattened| {
+				flattened.instruction.program_id(account_keys).as_ref() == Some(program_id)
+			})
+			.collect()
+	}
+
+	/// The token balance of every account which appears in either the
+	/// `pre_token_balances` or the `post_token_balances`, ordered by account
+	/// index.
+	///
+	/// Accounts created by the transaction only have a `post` balance and
+	/// closed accounts only have a `pre` balance. The missing side counts as
+	/// zero for the `delta_raw`. Unchanged balances are included so filter on
+	/// `delta_raw` when only changes matter.
+	pub fn token_balance_changes(&self) -> Vec<TokenBalanceChange> {
+		let mut balances = BTreeMap::<
+			u8,
+			(
+				Option<&TransactionTokenBalance>,
+				Option<&TransactionTokenBalance>,
+			),
+		>::new();
+
+		for balance in self.pre_token_balances.iter().flatten() {
+			balances.entry(balance.account_index).or_default().0 = Some(balance);
+		}
+
+		for balance in self.post_token_balances.iter().flatten() {
+			balances.entry(balance.account_index).or_default().1 = Some(balance);
+		}
+
+		balances
+			.into_iter()
+			.filter_map(|(account_index, (pre, post))| {
+				let latest = post.or(pre)?;
+				let raw = |balance: Option<&TransactionTokenBalance>| {
+					balance
+						.and_then(|balance| balance.ui_token_amount.raw())
+						.and_then(|raw| i128::try_from(raw).ok())
+						.unwrap_or_default()
+				};
+
+				Some(TokenBalanceChange {
+					account_index,
+					mint: latest.mint,
+					owner: latest.owner,
+					program_id: latest.program_id,
+					delta_raw: raw(post) - raw(pre),
+					pre: pre.map(|balance| balance.ui_token_amount.clone()),
+					post: post.map(|balance| balance.ui_token_amount.clone()),
+				})
+			})
+			.collect()
+	}
+
+	/// The lamport balance change of every account in the transaction.
+	///
+	/// The `static_account_keys` are the account keys from the transaction
+	/// message. They are followed by the writable and then the readonly
+	/// `loaded_addresses` to resolve the account at each balance index.
+	pub fn lamport_changes(&self, static_account_keys: &[Pubkey]) -> Vec<LamportChange> {
+		let loaded_addresses = self.loaded_addresses.as_ref();
+		let account_keys = static_account_keys
+			.iter()
+			.chain(
+				loaded_addresses
+					.into_iter()
+					.flat_map(|loaded| &loaded.writable),
+			)
+			.chain(
+				loaded_addresses
+					.into_iter()
+					.flat_map(|loaded| &loaded.readonly),
+			);
+
+		account_keys
+			.zip(self.pre_balances.iter().zip(&self.post_balances))
+			.enumerate()
+			.map(|(account_index, (pubkey, (&pre, &post)))| {
+				LamportChange {
+					account_index,
+					pubkey: *pubkey,
+					pre,
+					post,
+					delta: i128::from(post) - i128::from(pre),
+				}
+			})
+			.collect()
+	}
+}
+
+/// The token balance of an account before and after a transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenBalanceChange {
+	pub account_index: u8,
+	pub mint: Pubkey,
+	pub owner: Option<Pubkey>,
+	pub program_id: Option<Pubkey>,
+	pub pre: Option<UiTokenAmount>,
+	pub post: Option<UiTokenAmount>,
+	/// The raw `post` amount minus the raw `pre` amount.
+	pub delta_raw: i128,
+}
+
+/// An instruction and its position within the transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlattenedInstruction<'a> {
+	/// The index of the top level instruction.
+	pub outer_index: usize,
+	/// The position within the inner instructions of the top level
+	/// instruction. `None` for the top level instruction itself.
+	pub inner_index: Option<usize>,
+	/// `1` for top level instructions. Inner instructions from before stack
+	/// heights were recorded have no stack height.
+	pub stack_height: Option<u32>,
+	pub instruction: &'a UiInstruction,
+}
+
+/// The lamport balance of an account before and after a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LamportChange {
+	pub account_index: usize,
+	pub pubkey: Pubkey,
+	pub pre: u64,
+	pub post: u64,
+	/// The `post` balance minus the `pre` balance.
+	pub delta: i128,
 }
 
 #[serde_as]
@@ -320,6 +1332,34 @@ pub struct UiTransactionReturnData {
 	pub data: (String, UiReturnDataEncoding),
 }
 
+impl UiTransactionReturnData {
+	/// The base64 decoded data. The rpc strips trailing zero bytes, so this can
+	/// be shorter than the data set by the program.
+	pub fn decode(&self) -> Option<Vec<u8>> {
+		let (data, UiReturnDataEncoding::Base64) = &self.data;
+		BASE64_STANDARD.decode(data).ok()
+	}
+
+	/// Deserialize the data as borsh. The trailing zero bytes stripped by the
+	/// rpc are restored as needed by `T`.
+	pub fn decode_borsh<T: BorshDeserialize>(&self) -> Result<T, ReturnDataDecodeError> {
+		let data = self.decode().ok_or(ReturnDataDecodeError::InvalidBase64)?;
+		let mut reader = data.as_slice().chain(std::io::repeat(0));
+
+		T::deserialize_reader(&mut reader)
+			.map_err(|error| ReturnDataDecodeError::Deserialize(error.to_string()))
+	}
+}
+
+/// The error returned by [`UiTransactionReturnData::decode_borsh`].
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum ReturnDataDecodeError {
+	#[error("return data is not valid base64")]
+	InvalidBase64,
+	#[error("return data could not be deserialized: {0}")]
+	Deserialize(String),
+}
+
 impl From<TransactionReturnData> for UiTransactionReturnData {
 	fn from(return_data: TransactionReturnData) -> Self {
 		Self {
@@ -332,6 +1372,22 @@ impl From<TransactionReturnData> for UiTransactionReturnData {
 	}
 }
 
+impl TryFrom<UiTransactionReturnData> for TransactionReturnData {
+	type Error = UiConversionError;
+
+	fn try_from(return_data: UiTransactionReturnData) -> Result<Self, Self::Error> {
+		let (data, UiReturnDataEncoding::Base64) = return_data.data;
+		let data = BASE64_STANDARD
+			.decode(data)
+			.map_err(|_| UiConversionError::InvalidReturnData)?;
+
+		Ok(Self {
+			program_id: return_data.program_id,
+			data,
+		})
+	}
+}
+
 #[derive(Default, Serialize, Deserialize, Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum UiReturnDataEncoding {
@@ -350,6 +1406,15 @@ pub struct UiLoadedAddresses {
 	pub readonly: Vec<Pubkey>,
 }
 
+impl From<UiLoadedAddresses> for LoadedAddresses {
+	fn from(loaded_addresses: UiLoadedAddresses) -> Self {
+		Self {
+			writable: loaded_addresses.writable,
+			readonly: loaded_addresses.readonly,
+		}
+	}
+}
+
 impl From<&LoadedAddresses> for UiLoadedAddresses {
 	fn from(loaded_addresses: &LoadedAddresses) -> Self {
 		Self {
@@ -359,6 +1424,15 @@ impl From<&LoadedAddresses> for UiLoadedAddresses {
 	}
 }
 
+impl From<LoadedAddresses> for UiLoadedAddresses {
+	fn from(loaded_addresses: LoadedAddresses) -> Self {
+		Self {
+			writable: loaded_addresses.writable,
+			readonly: loaded_addresses.readonly,
+		}
+	}
+}
+
 #[serde_as]
 #[skip_serializing_none]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -398,6 +1472,73 @@ pub struct UiRawMessage {
 	pub address_table_lookups: Option<Vec<UiAddressTableLookup>>,
 }
 
+impl From<&VersionedMessage> for UiRawMessage {
+	fn from(message: &VersionedMessage) -> Self {
+		Self {
+			header: *message.header(),
+			account_keys: message
+				.static_account_keys()
+				.iter()
+				.map(ToString::to_string)
+				.collect(),
+			recent_blockhash: message.recent_blockhash().to_string(),
+			instructions: message
+				.instructions()
+				.iter()
+				.map(|ix| {
+					UiCompiledInstruction::from(ix, Some(TRANSACTION_LEVEL_STACK_HEIGHT as u32))
+				})
+				.collect(),
+			address_table_lookups: message
+				.address_table_lookups()
+				.map(|lookups| lookups.iter().map(Into::into).collect()),
+		}
+	}
+}
+
+impl From<VersionedMessage> for UiRawMessage {
+	fn from(message: VersionedMessage) -> Self {
+		let (header, account_keys, recent_blockhash, instructions, address_table_lookups) =
+			match message {
+				VersionedMessage::Legacy(message) => {
+					(
+						message.header,
+						message.account_keys,
+						message.recent_blockhash,
+						message.instructions,
+						None,
+					)
+				}
+				VersionedMessage::V0(message) => {
+					(
+						message.header,
+						message.account_keys,
+						message.recent_blockhash,
+						message.instructions,
+						Some(message.address_table_lookups),
+					)
+				}
+			};
+
+		Self {
+			header,
+			account_keys: account_keys.iter().map(ToString::to_string).collect(),
+			recent_blockhash: recent_blockhash.to_string(),
+			instructions: instructions
+				.into_iter()
+				.map(|ix| {
+					UiCompiledInstruction::from_owned(
+						ix,
+						Some(TRANSACTION_LEVEL_STACK_HEIGHT as u32),
+					)
+				})
+				.collect(),
+			address_table_lookups: address_table_lookups
+				.map(|lookups| lookups.into_iter().map(Into::into).collect()),
+		}
+	}
+}
+
 /// A duplicate representation of a CompiledInstruction for pretty JSON
 /// serialization
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -418,6 +1559,17 @@ impl UiCompiledInstruction {
 			stack_height,
 		}
 	}
+
+	/// The same as [`UiCompiledInstruction::from`] but the `accounts` are
+	/// moved rather than copied.
+	pub fn from_owned(instruction: CompiledInstruction, stack_height: Option<u32>) -> Self {
+		Self {
+			program_id_index: instruction.program_id_index,
+			accounts: instruction.accounts,
+			data: bs58::encode(&instruction.data).into_string(),
+			stack_height,
+		}
+	}
 }
 
 /// A duplicate representation of an Instruction for pretty JSON serialization
@@ -428,6 +1580,34 @@ pub enum UiInstruction {
 	Parsed(UiParsedInstruction),
 }
 
+impl UiInstruction {
+	/// The program invoked by the instruction. Compiled instructions resolve
+	/// the program from the `account_keys` of the transaction.
+	pub fn program_id(&self, account_keys: &[Pubkey]) -> Option<Pubkey> {
+		match self {
+			Self::Compiled(instruction) => {
+				account_keys
+					.get(usize::from(instruction.program_id_index))
+					.copied()
+			}
+			Self::Parsed(UiParsedInstruction::Parsed(instruction)) => Some(instruction.program_id),
+			Self::Parsed(UiParsedInstruction::PartiallyDecoded(instruction)) => {
+				Some(instruction.program_id)
+			}
+		}
+	}
+
+	pub fn stack_height(&self) -> Option<u32> {
+		match self {
+			Self::Compiled(instruction) => instruction.stack_height,
+			Self::Parsed(UiParsedInstruction::Parsed(instruction)) => instruction.stack_height,
+			Self::Parsed(UiParsedInstruction::PartiallyDecoded(instruction)) => {
+				instruction.stack_height
+			}
+		}
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum UiParsedInstruction {
@@ -477,13 +1657,13 @@ impl From<InnerInstructions> for UiInnerInstructions {
 			index: inner_instructions.index,
 			instructions: inner_instructions
 				.instructions
-				.iter()
+				.into_iter()
 				.map(
 					|InnerInstruction {
 					     instruction: ix,
 					     stack_height,
 					 }| {
-						UiInstruction::Compiled(UiCompiledInstruction::from(ix, *stack_height))
+						UiInstruction::Compiled(UiCompiledInstruction::from_owned(ix, stack_height))
 					},
 				)
 				.collect(),
@@ -491,6 +1671,39 @@ impl From<InnerInstructions> for UiInnerInstructions {
 	}
 }
 
+impl TryFrom<UiInnerInstructions> for InnerInstructions {
+	type Error = UiConversionError;
+
+	fn try_from(inner_instructions: UiInnerInstructions) -> Result<Self, Self::Error> {
+		let instructions = inner_instructions
+			.instructions
+			.into_iter()
+			.map(|instruction| {
+				let UiInstruction::Compiled(instruction) = instruction else {
+					return Err(UiConversionError::ParsedInstruction);
+				};
+				let data = bs58::decode(&instruction.data)
+					.into_vec()
+					.map_err(|_| UiConversionError::InvalidInstructionData)?;
+
+				Ok(InnerInstruction {
+					instruction: CompiledInstruction {
+						program_id_index: instruction.program_id_index,
+						accounts: instruction.accounts,
+						data,
+					},
+					stack_height: instruction.stack_height,
+				})
+			})
+			.collect::<Result<_, _>>()?;
+
+		Ok(Self {
+			index: inner_instructions.index,
+			instructions,
+		})
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct InnerInstructions {
 	/// Transaction instruction index
@@ -581,9 +1794,11 @@ impl From<UiConfirmedBlock> for EncodedConfirmedBlock {
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EncodedConfirmedTransactionWithStatusMeta {
+	#[serde_as(as = "NumberOrString")]
 	pub slot: u64,
 	#[serde(flatten)]
 	pub transaction: EncodedTransactionWithStatusMeta,
+	#[serde_as(as = "Option<NumberOrString>")]
 	pub block_time: Option<i64>,
 }
 
@@ -617,6 +1832,42 @@ impl TransactionStatus {
 		}
 	}
 
+	/// Whether the transaction satisfies `commitment_config` and landed at or
+	/// before `current_slot`.
+	///
+	/// A status reported by a node which is ahead of `current_slot` is
+	/// rejected, since reads made with a `minContextSlot` of `current_slot`
+	/// may not observe the transaction yet.
+	pub fn satisfies_commitment_at(
+		&self,
+		commitment_config: CommitmentConfig,
+		current_slot: Slot,
+	) -> bool {
+		self.slot <= current_slot && self.satisfies_commitment(commitment_config)
+	}
+
+	/// The number of slots between the slot of the transaction and
+	/// `current_slot`. Returns `0` when the transaction landed after
+	/// `current_slot`.
+	pub fn slot_distance(&self, current_slot: Slot) -> Slot {
+		current_slot.saturating_sub(self.slot)
+	}
+
+	/// Whether the transaction is finalized.
+	pub fn is_finalized(&self) -> bool {
+		self.confirmation_status() == TransactionConfirmationStatus::Finalized
+	}
+
+	/// Whether the transaction is confirmed or finalized.
+	pub fn is_confirmed(&self) -> bool {
+		self.confirmation_status() >= TransactionConfirmationStatus::Confirmed
+	}
+
+	/// The error of the transaction when it failed.
+	pub fn error(&self) -> Option<&TransactionError> {
+		self.err.as_ref()
+	}
+
 	// Returns `confirmation_status`, or if is_none, determines the status from
 	// confirmations. Facilitates querying nodes on older software
 	pub fn confirmation_status(&self) -> TransactionConfirmationStatus {
@@ -637,6 +1888,9 @@ impl TransactionStatus {
 
 #[cfg(test)]
 mod test {
+	use rand::Rng;
+	use rand::SeedableRng;
+	use rand::rngs::StdRng;
 	use serde_json::json;
 
 	use super::*;
@@ -655,6 +1909,186 @@ mod test {
 		assert!(unsanitary_transaction.decode().is_none());
 	}
 
+	#[test]
+	fn test_try_decode_errors() {
+		let unsanitary_transaction = EncodedTransaction::Binary(
+			"ju9xZWuDBX4pRxX2oZkTjxU5jB4SSTgEGhX8bQ8PURNzyzqKMPPpNvWihx8zUeFfrbVNoAaEsNKZvGzAnTDy5bhNT9kt6KFCTBixpvrLCzg4M5UdFUQYrn1gdgjXpLHxcaShD81xBNaFDgnA2nkkdHnKtZt4hVSfKAmw3VRZbjrZ7L2fKZBx21CwsGhD6onjM2M3qZW5C8J6d1pj41MxKmZgPBSha3MyKkNLkAGFASK"
+				.to_string(),
+			TransactionBinaryEncoding::Base58,
+		);
+		let max_size = PACKET_DATA_SIZE;
+
+		assert_eq!(
+			unsanitary_transaction.try_decode(),
+			Err(TransactionDecodeError::Sanitize)
+		);
+		assert_eq!(
+			EncodedTransaction::Binary("A".repeat(1_000_000), TransactionBinaryEncoding::Base64)
+				.try_decode(),
+			Err(TransactionDecodeError::TooLarge { max_size })
+		);
+		assert_eq!(
+			EncodedTransaction::LegacyBinary("1".repeat(1_000_000)).try_decode(),
+			Err(TransactionDecodeError::TooLarge { max_size })
+		);
+		assert_eq!(
+			EncodedTransaction::Binary("not base64!".into(), TransactionBinaryEncoding::Base64)
+				.try_decode(),
+			Err(TransactionDecodeError::InvalidEncoding(
+				TransactionBinaryEncoding::Base64
+			))
+		);
+		assert_eq!(
+			EncodedTransaction::Binary("AAAA".into(), TransactionBinaryEncoding::Base64)
+				.try_decode()
+				.map_err(|error| matches!(error, TransactionDecodeError::InvalidTransaction(_))),
+			Err(true)
+		);
+		assert_eq!(
+			EncodedTransaction::Accounts(UiAccountsList {
+				signatures: vec![],
+				account_keys: vec![],
+			})
+			.try_decode(),
+			Err(TransactionDecodeError::AccountsEncoding)
+		);
+	}
+
+	/// Random strings, mostly of `base58` and `base64` characters, never panic
+	/// and blobs too long to hold a transaction are always rejected by length.
+	#[test]
+	fn test_fuzz_decode() {
+		const CHARACTERS: &[u8] =
+			b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz+/=0OIl \0\xff";
+		let mut rng = StdRng::seed_from_u64(409);
+
+		for _ in 0..2_000 {
+			let length = rng.random_range(0..8_192);
+			let blob: String = (0..length)
+				.map(|_| char::from(CHARACTERS[rng.random_range(0..CHARACTERS.len())]))
+				.collect();
+
+			for encoding in [
+				TransactionBinaryEncoding::Base58,
+				TransactionBinaryEncoding::Base64,
+			] {
+				let result = EncodedTransaction::Binary(blob.clone(), encoding).try_decode();
+
+				if blob.len() > max_base58_length(PACKET_DATA_SIZE) {
+					assert_eq!(
+						result,
+						Err(TransactionDecodeError::TooLarge {
+							max_size: PACKET_DATA_SIZE
+						})
+					);
+				}
+			}
+
+			assert!(EncodedTransaction::LegacyBinary(blob).decode().is_none());
+		}
+	}
+
+	#[test]
+	fn test_try_decode_size_slack() {
+		let account_keys = (0..40).map(|_| Pubkey::new_unique()).collect();
+		let message = Message::new_with_compiled_instructions(
+			1,
+			0,
+			0,
+			account_keys,
+			Hash::new_unique(),
+			vec![],
+		);
+		let transaction = VersionedTransaction {
+			signatures: vec![Signature::from([1; 64])],
+			message: VersionedMessage::Legacy(message),
+		};
+		let size = bincode::serialized_size(&transaction).unwrap() as usize;
+		let slack = TransactionDecodeConfig {
+			size_slack: size - PACKET_DATA_SIZE,
+		};
+
+		assert!(size > PACKET_DATA_SIZE);
+
+		for encoding in [
+			UiTransactionEncoding::Base58,
+			UiTransactionEncoding::Base64,
+			UiTransactionEncoding::Json,
+		] {
+			let encoded = EncodedTransaction::encode(&transaction, encoding).unwrap();
+
+			assert_eq!(
+				encoded.try_decode(),
+				Err(TransactionDecodeError::TooLarge {
+					max_size: PACKET_DATA_SIZE
+				}),
+				"{encoding}"
+			);
+			assert_eq!(encoded.decode(), None, "{encoding}");
+			assert_eq!(
+				encoded.try_decode_with_config(slack),
+				Ok(transaction.clone()),
+				"{encoding}"
+			);
+		}
+	}
+
+	#[test]
+	fn test_enums_from_str() {
+		fn assert_round_trip<T>(variants: &[T])
+		where
+			T: FromStr<Err = ParseEnumError>
+				+ fmt::Display
+				+ serde::Serialize
+				+ PartialEq
+				+ fmt::Debug,
+		{
+			for variant in variants {
+				let name = variant.to_string();
+				assert_eq!(serde_json::to_value(variant).unwrap(), name.as_str());
+				assert_eq!(name.parse::<T>().as_ref(), Ok(variant));
+			}
+
+			assert!("unknown".parse::<T>().is_err());
+		}
+
+		assert_round_trip(&[
+			UiTransactionEncoding::Binary,
+			UiTransactionEncoding::Base64,
+			UiTransactionEncoding::Base58,
+			UiTransactionEncoding::Json,
+			UiTransactionEncoding::JsonParsed,
+		]);
+		assert_round_trip(&[
+			TransactionDetails::Full,
+			TransactionDetails::Signatures,
+			TransactionDetails::None,
+			TransactionDetails::Accounts,
+		]);
+		assert_round_trip(&[
+			TransactionConfirmationStatus::Processed,
+			TransactionConfirmationStatus::Confirmed,
+			TransactionConfirmationStatus::Finalized,
+		]);
+		assert_eq!(
+			"jsonparsed".parse::<UiTransactionEncoding>(),
+			Err(ParseEnumError::new("transaction encoding", "jsonparsed"))
+		);
+	}
+
+	#[test]
+	fn test_confirmation_status_order() {
+		assert!(
+			TransactionConfirmationStatus::Finalized >= TransactionConfirmationStatus::Confirmed
+		);
+		assert!(
+			TransactionConfirmationStatus::Confirmed >= TransactionConfirmationStatus::Confirmed
+		);
+		assert!(
+			TransactionConfirmationStatus::Processed < TransactionConfirmationStatus::Confirmed
+		);
+	}
+
 	#[test]
 	fn test_satisfies_commitment() {
 		let status = TransactionStatus {
@@ -668,6 +2102,8 @@ mod test {
 		assert!(status.satisfies_commitment(CommitmentConfig::finalized()));
 		assert!(status.satisfies_commitment(CommitmentConfig::confirmed()));
 		assert!(status.satisfies_commitment(CommitmentConfig::processed()));
+		assert!(status.is_finalized());
+		assert!(status.is_confirmed());
 
 		let status = TransactionStatus {
 			slot: 0,
@@ -680,6 +2116,8 @@ mod test {
 		assert!(!status.satisfies_commitment(CommitmentConfig::finalized()));
 		assert!(status.satisfies_commitment(CommitmentConfig::confirmed()));
 		assert!(status.satisfies_commitment(CommitmentConfig::processed()));
+		assert!(!status.is_finalized());
+		assert!(status.is_confirmed());
 
 		let status = TransactionStatus {
 			slot: 0,
@@ -692,6 +2130,7 @@ mod test {
 		assert!(!status.satisfies_commitment(CommitmentConfig::finalized()));
 		assert!(!status.satisfies_commitment(CommitmentConfig::confirmed()));
 		assert!(status.satisfies_commitment(CommitmentConfig::processed()));
+		assert!(!status.is_confirmed());
 
 		let status = TransactionStatus {
 			slot: 0,
@@ -723,6 +2162,8 @@ mod test {
 			confirmation_status: None,
 		};
 		assert!(status.satisfies_commitment(CommitmentConfig::confirmed()));
+		assert!(status.is_confirmed());
+		assert!(!status.is_finalized());
 
 		let status = TransactionStatus {
 			slot: 0,
@@ -732,6 +2173,23 @@ mod test {
 			confirmation_status: None,
 		};
 		assert!(status.satisfies_commitment(CommitmentConfig::confirmed()));
+		assert!(status.is_finalized());
+
+		// A status from a node ahead of the current slot
+		let status = TransactionStatus {
+			slot: 100,
+			confirmations: Some(2),
+			status: Err(TransactionError::AccountInUse),
+			err: Some(TransactionError::AccountInUse),
+			confirmation_status: Some(TransactionConfirmationStatus::Confirmed),
+		};
+		assert!(status.satisfies_commitment_at(CommitmentConfig::confirmed(), 100));
+		assert!(status.satisfies_commitment_at(CommitmentConfig::processed(), 150));
+		assert!(!status.satisfies_commitment_at(CommitmentConfig::confirmed(), 99));
+		assert!(!status.satisfies_commitment_at(CommitmentConfig::finalized(), 150));
+		assert_eq!(status.slot_distance(132), 32);
+		assert_eq!(status.slot_distance(99), 0);
+		assert_eq!(status.error(), Some(&TransactionError::AccountInUse));
 	}
 
 	#[test]
@@ -765,4 +2223,952 @@ mod test {
                 \"amount\": \"1\",\"decimals\": 0,\"uiAmount\": 1.0,\"uiAmountString\": \"1\"}}";
 		test_serde::<TransactionTokenBalance>(json_input, expected_json_output);
 	}
+
+	fn token_balance(
+		account_index: u8,
+		mint: Pubkey,
+		owner: Pubkey,
+		amount: u64,
+	) -> TransactionTokenBalance {
+		TransactionTokenBalance {
+			account_index,
+			mint,
+			ui_token_amount: UiTokenAmount::from_raw(amount, 6),
+			owner: Some(owner),
+			program_id: None,
+		}
+	}
+
+	#[test]
+	fn test_token_balance_changes() {
+		let mint = Pubkey::new_unique();
+		let owner = Pubkey::new_unique();
+		let closed_owner = Pubkey::new_unique();
+		let meta = UiTransactionStatusMeta {
+			pre_token_balances: Some(vec![
+				token_balance(1, mint, owner, 500),
+				token_balance(3, mint, closed_owner, 0),
+				token_balance(4, mint, owner, 10),
+			]),
+			post_token_balances: Some(vec![
+				token_balance(1, mint, owner, 200),
+				token_balance(2, mint, owner, 300),
+				token_balance(4, mint, owner, 10),
+			]),
+			..UiTransactionStatusMeta::from(TransactionStatusMeta::default())
+		};
+
+		let changes = meta.token_balance_changes();
+		let summary: Vec<_> = changes
+			.iter()
+			.map(|change| {
+				(
+					change.account_index,
+					change.delta_raw,
+					change.pre.is_some(),
+					change.post.is_some(),
+				)
+			})
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				(1, -300, true, true),
+				(2, 300, false, true),
+				(3, 0, true, false),
+				(4, 0, true, true),
+			]
+		);
+		assert_eq!(changes[2].owner, Some(closed_owner));
+		assert_eq!(changes[1].post, Some(UiTokenAmount::from_raw(300, 6)));
+	}
+
+	#[test]
+	fn test_lamport_changes() {
+		let static_keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+		let writable = Pubkey::new_unique();
+		let readonly = Pubkey::new_unique();
+		let meta = UiTransactionStatusMeta {
+			pre_balances: vec![1_000, 0, 50, 7],
+			post_balances: vec![900, 95, 50, 7],
+			loaded_addresses: Some(UiLoadedAddresses {
+				writable: vec![writable],
+				readonly: vec![readonly],
+			}),
+			..UiTransactionStatusMeta::from(TransactionStatusMeta::default())
+		};
+
+		let changes = meta.lamport_changes(&static_keys);
+		let summary: Vec<_> = changes
+			.iter()
+			.map(|change| (change.account_index, change.pubkey, change.delta))
+			.collect();
+		assert_eq!(
+			summary,
+			vec![
+				(0, static_keys[0], -100),
+				(1, static_keys[1], 95),
+				(2, writable, 0),
+				(3, readonly, 0),
+			]
+		);
+	}
+
+	#[test]
+	fn test_ui_transaction_status_meta_round_trip() {
+		let meta = TransactionStatusMeta {
+			fee: 5_000,
+			pre_balances: vec![10_000, 0],
+			post_balances: vec![5_000, 0],
+			inner_instructions: Some(vec![InnerInstructions {
+				index: 0,
+				instructions: vec![InnerInstruction {
+					instruction: CompiledInstruction::new_from_raw_parts(
+						1,
+						vec![2, 3, 4],
+						vec![0, 1],
+					),
+					stack_height: Some(2),
+				}],
+			}]),
+			log_messages: Some(vec!["Program log: hello".into()]),
+			loaded_addresses: LoadedAddresses {
+				writable: vec![Pubkey::new_unique()],
+				readonly: vec![Pubkey::new_unique()],
+			},
+			return_data: Some(TransactionReturnData {
+				program_id: Pubkey::new_unique(),
+				data: vec![1, 2, 3],
+			}),
+			compute_units_consumed: Some(1_000),
+			..TransactionStatusMeta::default()
+		};
+
+		let ui_meta = UiTransactionStatusMeta::from(meta.clone());
+		assert_eq!(TransactionStatusMeta::try_from(ui_meta.clone()), Ok(meta));
+
+		let mut parsed_meta = ui_meta;
+		parsed_meta.inner_instructions.as_mut().unwrap()[0].instructions[0] =
+			UiInstruction::Parsed(UiParsedInstruction::Parsed(ParsedInstruction {
+				program: "spl-memo".into(),
+				program_id: Pubkey::new_unique(),
+				parsed: json!("hello"),
+				stack_height: None,
+			}));
+		assert_eq!(
+			TransactionStatusMeta::try_from(parsed_meta),
+			Err(UiConversionError::ParsedInstruction)
+		);
+
+		let ui_inner_instructions = UiInnerInstructions {
+			index: 1,
+			instructions: vec![UiInstruction::Compiled(UiCompiledInstruction {
+				program_id_index: 0,
+				accounts: vec![],
+				data: "0OIl".into(),
+				stack_height: None,
+			})],
+		};
+		assert_eq!(
+			InnerInstructions::try_from(ui_inner_instructions),
+			Err(UiConversionError::InvalidInstructionData)
+		);
+	}
+
+	fn versioned_transactions() -> [VersionedTransaction; 2] {
+		let header = MessageHeader {
+			num_required_signatures: 1,
+			num_readonly_signed_accounts: 0,
+			num_readonly_unsigned_accounts: 1,
+		};
+		let account_keys = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+		let instructions = vec![CompiledInstruction::new_from_raw_parts(
+			1,
+			vec![1, 2, 3],
+			vec![0, 1],
+		)];
+		let legacy = Message {
+			header,
+			account_keys: account_keys.clone(),
+			recent_blockhash: Hash::new_unique(),
+			instructions: instructions.clone(),
+		};
+		let v0 = v0::Message {
+			header,
+			account_keys,
+			recent_blockhash: Hash::new_unique(),
+			instructions,
+			address_table_lookups: vec![MessageAddressTableLookup {
+				account_key: Pubkey::new_unique(),
+				writable_indexes: vec![],
+				readonly_indexes: vec![4],
+			}],
+		};
+
+		[VersionedMessage::Legacy(legacy), VersionedMessage::V0(v0)].map(|message| {
+			VersionedTransaction {
+				signatures: vec![Signature::from([7; 64])],
+				message,
+			}
+		})
+	}
+
+	#[test]
+	fn test_encoded_transaction_account_keys() {
+		let payer = Pubkey::new_unique();
+		let destination = Pubkey::new_unique();
+		let program_id = Pubkey::new_unique();
+		let loaded_addresses = UiLoadedAddresses {
+			writable: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+			readonly: vec![
+				Pubkey::new_unique(),
+				Pubkey::new_unique(),
+				Pubkey::new_unique(),
+			],
+		};
+		// The writable addresses of every table are loaded before any of the
+		// readonly addresses.
+		let transaction = VersionedTransaction {
+			signatures: vec![Signature::from([3; 64])],
+			message: VersionedMessage::V0(v0::Message {
+				header: MessageHeader {
+					num_required_signatures: 1,
+					num_readonly_signed_accounts: 0,
+					num_readonly_unsigned_accounts: 1,
+				},
+				account_keys: vec![payer, destination, program_id],
+				recent_blockhash: Hash::new_unique(),
+				instructions: vec![CompiledInstruction::new_from_raw_parts(
+					2,
+					vec![],
+					vec![0, 1, 3, 4, 5, 6, 7],
+				)],
+				address_table_lookups: vec![
+					MessageAddressTableLookup {
+						account_key: Pubkey::new_unique(),
+						writable_indexes: vec![3],
+						readonly_indexes: vec![7],
+					},
+					MessageAddressTableLookup {
+						account_key: Pubkey::new_unique(),
+						writable_indexes: vec![0],
+						readonly_indexes: vec![1, 2],
+					},
+				],
+			}),
+		};
+		let account = |pubkey: Pubkey, writable: bool, signer: bool, source| {
+			ParsedAccount {
+				pubkey,
+				writable,
+				signer,
+				source: Some(source),
+			}
+		};
+		let expected = vec![
+			account(payer, true, true, ParsedAccountSource::Transaction),
+			account(destination, true, false, ParsedAccountSource::Transaction),
+			account(program_id, false, false, ParsedAccountSource::Transaction),
+			account(
+				loaded_addresses.writable[0],
+				true,
+				false,
+				ParsedAccountSource::LookupTable,
+			),
+			account(
+				loaded_addresses.writable[1],
+				true,
+				false,
+				ParsedAccountSource::LookupTable,
+			),
+			account(
+				loaded_addresses.readonly[0],
+				false,
+				false,
+				ParsedAccountSource::LookupTable,
+			),
+			account(
+				loaded_addresses.readonly[1],
+				false,
+				false,
+				ParsedAccountSource::LookupTable,
+			),
+			account(
+				loaded_addresses.readonly[2],
+				false,
+				false,
+				ParsedAccountSource::LookupTable,
+			),
+		];
+		let meta = UiTransactionStatusMeta {
+			err: None,
+			status: Ok(()),
+			fee: 5000,
+			pre_balances: vec![10, 20, 1, 0, 0, 0, 0, 0],
+			post_balances: vec![5, 20, 1, 0, 0, 0, 0, 0],
+			inner_instructions: None,
+			log_messages: None,
+			pre_token_balances: None,
+			post_token_balances: None,
+			rewards: None,
+			loaded_addresses: Some(loaded_addresses.clone()),
+			return_data: None,
+			compute_units_consumed: None,
+			cost_units: None,
+		};
+
+		for encoding in [
+			UiTransactionEncoding::Base58,
+			UiTransactionEncoding::Base64,
+			UiTransactionEncoding::Json,
+		] {
+			let encoded = EncodedTransactionWithStatusMeta {
+				transaction: EncodedTransaction::encode(&transaction, encoding).unwrap(),
+				meta: Some(meta.clone()),
+				version: Some(TransactionVersion::Number(0)),
+			};
+			// Read it back the way it arrives from the rpc.
+			let encoded: EncodedTransactionWithStatusMeta =
+				serde_json::from_value(serde_json::to_value(&encoded).unwrap()).unwrap();
+
+			assert_eq!(encoded.account_keys(), Some(expected.clone()), "{encoding}");
+			assert_eq!(encoded.fee_payer(), Some(payer), "{encoding}");
+			assert_eq!(
+				encoded.resolve_instruction_accounts(&UiCompiledInstruction {
+					program_id_index: 2,
+					accounts: vec![0, 1, 3, 4, 5, 6, 7, 8],
+					data: String::new(),
+					stack_height: None,
+				}),
+				vec![
+					payer,
+					destination,
+					loaded_addresses.writable[0],
+					loaded_addresses.writable[1],
+					loaded_addresses.readonly[0],
+					loaded_addresses.readonly[1],
+					loaded_addresses.readonly[2],
+				],
+				"{encoding}"
+			);
+
+			let without_meta = EncodedTransactionWithStatusMeta {
+				meta: None,
+				..encoded
+			};
+			assert_eq!(without_meta.account_keys(), None, "{encoding}");
+			assert_eq!(without_meta.fee_payer(), Some(payer), "{encoding}");
+		}
+
+		let accounts = EncodedTransactionWithStatusMeta {
+			transaction: EncodedTransaction::Accounts(UiAccountsList {
+				signatures: transaction.signatures.clone(),
+				account_keys: expected.clone(),
+			}),
+			meta: None,
+			version: Some(TransactionVersion::Number(0)),
+		};
+		assert_eq!(accounts.account_keys(), Some(expected));
+		assert_eq!(accounts.fee_payer(), Some(payer));
+	}
+
+	#[test]
+	fn test_flatten_instructions() {
+		let token_program = Pubkey::new_unique();
+		let router = Pubkey::new_unique();
+		let account_keys = [Pubkey::new_unique(), router, token_program];
+		let compiled = |program_id_index: u8, stack_height: Option<u32>| {
+			UiInstruction::Compiled(UiCompiledInstruction {
+				program_id_index,
+				accounts: vec![0],
+				data: String::new(),
+				stack_height,
+			})
+		};
+		let parsed = UiInstruction::Parsed(UiParsedInstruction::Parsed(ParsedInstruction {
+			program: "spl-token".to_string(),
+			program_id: token_program,
+			parsed: json!({ "type": "transfer" }),
+			stack_height: Some(3),
+		}));
+		let instructions = [compiled(2, None), compiled(1, Some(1))];
+		let meta = UiTransactionStatusMeta {
+			err: None,
+			status: Ok(()),
+			fee: 5000,
+			pre_balances: vec![],
+			post_balances: vec![],
+			inner_instructions: Some(vec![UiInnerInstructions {
+				index: 1,
+				instructions: vec![compiled(1, Some(2)), parsed.clone(), compiled(2, None)],
+			}]),
+			log_messages: None,
+			pre_token_balances: None,
+			post_token_balances: None,
+			rewards: None,
+			loaded_addresses: None,
+			return_data: None,
+			compute_units_consumed: None,
+			cost_units: None,
+		};
+
+		assert!(meta.inner_instructions_for(0).is_empty());
+		assert_eq!(meta.inner_instructions_for(1).len(), 3);
+
+		let positions = meta
+			.flatten_instructions(&instructions)
+			.iter()
+			.map(|flattened| {
+				(
+					flattened.outer_index,
+					flattened.inner_index,
+					flattened.stack_height,
+				)
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			positions,
+			vec![
+				(0, None, Some(1)),
+				(1, None, Some(1)),
+				(1, Some(0), Some(2)),
+				(1, Some(1), Some(3)),
+				(1, Some(2), None),
+			]
+		);
+
+		let transfers =
+			meta.find_instructions_by_program(&instructions, &token_program, &account_keys);
+		assert_eq!(
+			transfers,
+			vec![
+				FlattenedInstruction {
+					outer_index: 0,
+					inner_index: None,
+					stack_height: Some(1),
+					instruction: &instructions[0],
+				},
+				FlattenedInstruction {
+					outer_index: 1,
+					inner_index: Some(1),
+					stack_height: Some(3),
+					instruction: &parsed,
+				},
+				FlattenedInstruction {
+					outer_index: 1,
+					inner_index: Some(2),
+					stack_height: None,
+					instruction: &meta.inner_instructions_for(1)[2],
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn test_encoded_transaction_instructions() {
+		let transaction = VersionedTransaction {
+			signatures: vec![Signature::from([3; 64])],
+			message: VersionedMessage::Legacy(Message::new_with_compiled_instructions(
+				1,
+				0,
+				1,
+				vec![Pubkey::new_unique(), Pubkey::new_unique()],
+				Hash::new_unique(),
+				vec![CompiledInstruction::new_from_raw_parts(
+					1,
+					vec![1, 2],
+					vec![0],
+				)],
+			)),
+		};
+		let expected = vec![UiInstruction::Compiled(UiCompiledInstruction {
+			program_id_index: 1,
+			accounts: vec![0],
+			data: bs58::encode([1, 2]).into_string(),
+			stack_height: Some(1),
+		})];
+
+		for encoding in [UiTransactionEncoding::Base64, UiTransactionEncoding::Json] {
+			let encoded = EncodedTransactionWithStatusMeta {
+				transaction: EncodedTransaction::encode(&transaction, encoding).unwrap(),
+				meta: None,
+				version: None,
+			};
+			assert_eq!(encoded.instructions(), Some(expected.clone()), "{encoding}");
+		}
+	}
+
+	#[test]
+	fn test_confirmed_block_transactions() {
+		let payer = Pubkey::new_unique();
+		let transaction = |program_ids: &[Pubkey]| {
+			let mut account_keys = vec![payer];
+			account_keys.extend(program_ids);
+			let instructions = (1..=program_ids.len())
+				.map(|index| {
+					CompiledInstruction::new_from_raw_parts(
+						u8::try_from(index).unwrap(),
+						vec![],
+						vec![0],
+					)
+				})
+				.collect();
+
+			VersionedTransaction {
+				signatures: vec![Signature::from([1; 64])],
+				message: VersionedMessage::Legacy(Message::new_with_compiled_instructions(
+					1,
+					0,
+					u8::try_from(program_ids.len()).unwrap(),
+					account_keys,
+					Hash::new_unique(),
+					instructions,
+				)),
+			}
+		};
+		let meta = |status: TransactionResult<()>| {
+			UiTransactionStatusMeta {
+				err: status.clone().err(),
+				status,
+				fee: 5000,
+				pre_balances: vec![],
+				post_balances: vec![],
+				inner_instructions: None,
+				log_messages: None,
+				pre_token_balances: None,
+				post_token_balances: None,
+				rewards: None,
+				loaded_addresses: None,
+				return_data: None,
+				compute_units_consumed: None,
+				cost_units: None,
+			}
+		};
+		let encode = |transaction: &VersionedTransaction,
+		              encoding: UiTransactionEncoding,
+		              meta: Option<UiTransactionStatusMeta>| {
+			EncodedTransactionWithStatusMeta {
+				transaction: EncodedTransaction::encode(transaction, encoding).unwrap(),
+				meta,
+				version: None,
+			}
+		};
+		let vote = transaction(&[vote::id()]);
+		let transfer = transaction(&[Pubkey::new_unique()]);
+		let vote_and_transfer = transaction(&[vote::id(), Pubkey::new_unique()]);
+		let transactions = vec![
+			encode(&vote, UiTransactionEncoding::Base64, Some(meta(Ok(())))),
+			encode(&vote, UiTransactionEncoding::Json, Some(meta(Ok(())))),
+			encode(&transfer, UiTransactionEncoding::Base58, Some(meta(Ok(())))),
+			encode(
+				&transfer,
+				UiTransactionEncoding::Json,
+				Some(meta(Err(TransactionError::AccountInUse))),
+			),
+			encode(&vote_and_transfer, UiTransactionEncoding::Base64, None),
+		];
+		let block = UiConfirmedBlock {
+			previous_blockhash: Hash::new_unique(),
+			blockhash: Hash::new_unique(),
+			parent_slot: 1,
+			transactions: Some(transactions.clone()),
+			signatures: None,
+			rewards: None,
+			num_reward_partitions: None,
+			block_time: None,
+			block_height: None,
+		};
+
+		assert_eq!(
+			block.successful_transactions().collect::<Vec<_>>(),
+			vec![&transactions[0], &transactions[1], &transactions[2]]
+		);
+		assert_eq!(
+			block.failed_transactions().collect::<Vec<_>>(),
+			vec![&transactions[3]]
+		);
+		assert_eq!(
+			block.non_vote_transactions().collect::<Vec<_>>(),
+			vec![&transactions[2], &transactions[3], &transactions[4]]
+		);
+		assert_eq!(
+			block.transaction_count_summary(),
+			TransactionCountSummary {
+				total: 5,
+				successful: 3,
+				failed: 1,
+				vote: 2,
+				non_vote: 3,
+			}
+		);
+
+		let empty = UiConfirmedBlock {
+			transactions: None,
+			..block
+		};
+		assert_eq!(empty.successful_transactions().count(), 0);
+		assert_eq!(
+			empty.transaction_count_summary(),
+			TransactionCountSummary::default()
+		);
+	}
+
+	#[test]
+	fn test_confirmed_block_decode() {
+		let transaction = VersionedTransaction {
+			signatures: vec![Signature::from([1; 64])],
+			message: VersionedMessage::Legacy(Message::new_with_compiled_instructions(
+				1,
+				0,
+				1,
+				vec![Pubkey::new_unique(), Pubkey::new_unique()],
+				Hash::new_unique(),
+				vec![CompiledInstruction::new_from_raw_parts(1, vec![7], vec![0])],
+			)),
+		};
+		let meta = TransactionStatusMeta {
+			fee: 5000,
+			pre_balances: vec![10, 1],
+			post_balances: vec![5, 1],
+			..TransactionStatusMeta::default()
+		};
+		let encode = |encoding: UiTransactionEncoding, meta: Option<UiTransactionStatusMeta>| {
+			EncodedTransactionWithStatusMeta {
+				transaction: EncodedTransaction::encode(&transaction, encoding).unwrap(),
+				meta,
+				version: None,
+			}
+		};
+		let block = UiConfirmedBlock {
+			previous_blockhash: Hash::new_unique(),
+			blockhash: Hash::new_unique(),
+			parent_slot: 1,
+			transactions: Some(vec![
+				encode(UiTransactionEncoding::Base64, Some(meta.clone().into())),
+				encode(UiTransactionEncoding::Json, None),
+			]),
+			signatures: None,
+			rewards: Some(vec![]),
+			num_reward_partitions: None,
+			block_time: Some(1_700_000_000),
+			block_height: Some(2),
+		};
+
+		assert_eq!(
+			block.decode(),
+			Ok(DecodedConfirmedBlock {
+				previous_blockhash: block.previous_blockhash,
+				blockhash: block.blockhash,
+				parent_slot: 1,
+				transactions: vec![
+					(transaction.clone(), Some(meta.clone())),
+					(transaction.clone(), None),
+				],
+				rewards: Some(vec![]),
+				num_reward_partitions: None,
+				block_time: Some(1_700_000_000),
+				block_height: Some(2),
+			})
+		);
+
+		let mut invalid_meta = UiTransactionStatusMeta::from(meta);
+		invalid_meta.return_data = Some(UiTransactionReturnData {
+			program_id: Pubkey::new_unique(),
+			data: ("not base64!".to_string(), UiReturnDataEncoding::Base64),
+		});
+		let accounts = EncodedTransactionWithStatusMeta {
+			transaction: EncodedTransaction::Accounts(UiAccountsList {
+				signatures: transaction.signatures.clone(),
+				account_keys: vec![],
+			}),
+			meta: None,
+			version: None,
+		};
+		let with_transactions = |transactions| {
+			UiConfirmedBlock {
+				transactions,
+				..block.clone()
+			}
+		};
+
+		assert_eq!(
+			with_transactions(Some(vec![
+				encode(UiTransactionEncoding::Base58, None),
+				accounts.clone(),
+				encode(UiTransactionEncoding::Base64, Some(invalid_meta.clone())),
+			]))
+			.decode(),
+			Err(BlockDecodeError::InvalidTransaction(1))
+		);
+		assert_eq!(
+			with_transactions(Some(vec![
+				encode(UiTransactionEncoding::Base58, None),
+				encode(UiTransactionEncoding::Base64, Some(invalid_meta)),
+				accounts,
+			]))
+			.decode(),
+			Err(BlockDecodeError::InvalidMeta {
+				index: 1,
+				source: UiConversionError::InvalidReturnData,
+			})
+		);
+		assert_eq!(
+			with_transactions(None).decode(),
+			Err(BlockDecodeError::MissingTransactions)
+		);
+	}
+
+	#[test]
+	fn test_encoded_transaction_round_trip() {
+		for transaction in versioned_transactions() {
+			for encoding in [
+				UiTransactionEncoding::Binary,
+				UiTransactionEncoding::Base58,
+				UiTransactionEncoding::Base64,
+				UiTransactionEncoding::Json,
+			] {
+				let encoded = EncodedTransaction::encode(&transaction, encoding).unwrap();
+				assert_eq!(encoded.decode(), Some(transaction.clone()), "{encoding}");
+			}
+
+			assert_eq!(
+				EncodedTransaction::encode(&transaction, UiTransactionEncoding::JsonParsed),
+				Err(EncodeError::JsonParsedUnsupported)
+			);
+		}
+	}
+
+	#[test]
+	fn test_encoded_transaction_json_shape() {
+		let [legacy, v0] = versioned_transactions();
+
+		let encoded = EncodedTransaction::encode(&legacy, UiTransactionEncoding::Json).unwrap();
+		let json = serde_json::to_value(&encoded).unwrap();
+		assert!(json["message"].get("addressTableLookups").is_none());
+		assert_eq!(json["message"]["instructions"][0]["stackHeight"], json!(1));
+
+		let encoded = EncodedTransaction::encode(&v0, UiTransactionEncoding::Json).unwrap();
+		let json = serde_json::to_value(&encoded).unwrap();
+		assert_eq!(
+			json["message"]["addressTableLookups"][0]["readonlyIndexes"],
+			json!([4])
+		);
+
+		assert!(
+			EncodedTransaction::encode_with_max_supported_version(
+				&legacy,
+				UiTransactionEncoding::Base64,
+				None
+			)
+			.is_ok()
+		);
+		assert_eq!(
+			EncodedTransaction::encode_with_max_supported_version(
+				&v0,
+				UiTransactionEncoding::Base64,
+				None
+			),
+			Err(EncodeError::UnsupportedTransactionVersion(0))
+		);
+	}
+
+	#[test]
+	fn test_ui_transaction_try_decode() {
+		let [legacy, v0] = versioned_transactions();
+		let EncodedTransaction::Json(ui_legacy) =
+			EncodedTransaction::encode(&legacy, UiTransactionEncoding::Json).unwrap()
+		else {
+			unreachable!()
+		};
+
+		assert_eq!(ui_legacy.try_decode(None), Ok(legacy.clone()));
+		assert_eq!(
+			ui_legacy.try_decode(Some(TransactionVersion::Number(1))),
+			Err(UiConversionError::UnsupportedTransactionVersion(1))
+		);
+
+		// a v0 message without lookups relies on the version of the wrapper
+		let wrapped = EncodedTransactionWithStatusMeta {
+			transaction: EncodedTransaction::Json(ui_legacy.clone()),
+			meta: None,
+			version: Some(TransactionVersion::Number(0)),
+		};
+		let decoded = wrapped.decode_transaction().unwrap();
+		assert_eq!(decoded.version(), TransactionVersion::Number(0));
+		assert_eq!(
+			decoded.message.static_account_keys(),
+			legacy.message.static_account_keys()
+		);
+
+		let EncodedTransaction::Json(ui_v0) =
+			EncodedTransaction::encode(&v0, UiTransactionEncoding::Json).unwrap()
+		else {
+			unreachable!()
+		};
+		assert_eq!(ui_v0.try_decode(None), Ok(v0.clone()));
+
+		// a legacy wrapper version can't drop the lookups of the message
+		let wrapped = EncodedTransactionWithStatusMeta {
+			transaction: EncodedTransaction::Json(ui_v0.clone()),
+			meta: None,
+			version: Some(TransactionVersion::LEGACY),
+		};
+		assert_eq!(
+			wrapped.try_decode_transaction(),
+			Err(TransactionDecodeError::Json(
+				UiConversionError::VersionMismatch
+			))
+		);
+
+		let mut invalid_key = ui_legacy.clone();
+		let UiMessage::Raw(message) = &mut invalid_key.message else {
+			unreachable!()
+		};
+		message.account_keys[0] = "not a key".into();
+		assert_eq!(
+			invalid_key.try_decode(None),
+			Err(UiConversionError::InvalidAccountKey("not a key".into()))
+		);
+
+		let parsed = UiTransaction {
+			signatures: ui_legacy.signatures.clone(),
+			message: UiMessage::Parsed(UiParsedMessage {
+				account_keys: vec![],
+				recent_blockhash: Hash::new_unique(),
+				instructions: vec![],
+				address_table_lookups: None,
+			}),
+		};
+		assert_eq!(
+			parsed.try_decode(None),
+			Err(UiConversionError::ParsedMessage)
+		);
+	}
+
+	#[test]
+	fn test_decode_return_data() {
+		let program_id = Pubkey::new_unique();
+		let return_data = |data: &[u8]| {
+			UiTransactionReturnData {
+				program_id,
+				data: (BASE64_STANDARD.encode(data), UiReturnDataEncoding::Base64),
+			}
+		};
+
+		// The rpc returns `Kg==` for a program which sets `42u64` as return data.
+		let amount = UiTransactionReturnData {
+			program_id,
+			data: ("Kg==".to_string(), UiReturnDataEncoding::Base64),
+		};
+		assert_eq!(amount.decode(), Some(vec![42]));
+		assert_eq!(amount.decode_borsh::<u64>(), Ok(42));
+
+		let quote = (1_000_000_u64, 30_u16, Some(-5_i64), 0_u32);
+		let data = borsh::to_vec(&quote).unwrap();
+		let trimmed = &data[..=data.iter().rposition(|byte| *byte != 0).unwrap()];
+		assert!(trimmed.len() < data.len());
+		assert_eq!(
+			return_data(trimmed).decode_borsh::<(u64, u16, Option<i64>, u32)>(),
+			Ok(quote)
+		);
+		assert_eq!(
+			return_data(&[2]).decode_borsh::<bool>(),
+			Err(ReturnDataDecodeError::Deserialize(
+				"Invalid bool representation: 2".into()
+			))
+		);
+
+		let invalid = UiTransactionReturnData {
+			program_id,
+			data: ("not base64!".to_string(), UiReturnDataEncoding::Base64),
+		};
+		assert_eq!(invalid.decode(), None);
+		assert_eq!(
+			invalid.decode_borsh::<u64>(),
+			Err(ReturnDataDecodeError::InvalidBase64)
+		);
+
+		let meta = UiTransactionStatusMeta {
+			err: None,
+			status: Ok(()),
+			fee: 5000,
+			pre_balances: vec![],
+			post_balances: vec![],
+			inner_instructions: None,
+			log_messages: None,
+			pre_token_balances: None,
+			post_token_balances: None,
+			rewards: None,
+			loaded_addresses: None,
+			return_data: Some(amount.clone()),
+			compute_units_consumed: None,
+			cost_units: None,
+		};
+		assert_eq!(meta.return_data_for(&program_id), Some(&amount));
+		assert_eq!(meta.return_data_for(&Pubkey::new_unique()), None);
+	}
+
+	#[test]
+	fn test_owned_conversions_match_borrowed() {
+		let lookup = MessageAddressTableLookup {
+			account_key: Pubkey::new_unique(),
+			writable_indexes: vec![0, 2],
+			readonly_indexes: vec![1],
+		};
+		let message = VersionedMessage::V0(v0::Message {
+			header: MessageHeader {
+				num_required_signatures: 1,
+				num_readonly_signed_accounts: 0,
+				num_readonly_unsigned_accounts: 1,
+			},
+			account_keys: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+			recent_blockhash: Hash::new_unique(),
+			instructions: vec![CompiledInstruction::new_from_raw_parts(
+				1,
+				vec![1, 2, 3],
+				vec![0, 2],
+			)],
+			address_table_lookups: vec![lookup.clone()],
+		});
+		let inner_instruction = InnerInstruction {
+			instruction: CompiledInstruction::new_from_raw_parts(1, vec![4], vec![0]),
+			stack_height: Some(2),
+		};
+		let meta = TransactionStatusMeta {
+			inner_instructions: Some(vec![InnerInstructions {
+				index: 0,
+				instructions: vec![inner_instruction.clone()],
+			}]),
+			loaded_addresses: LoadedAddresses {
+				writable: vec![Pubkey::new_unique()],
+				readonly: vec![Pubkey::new_unique()],
+			},
+			..TransactionStatusMeta::default()
+		};
+		let ui_meta = UiTransactionStatusMeta::from_owned(meta.clone());
+
+		assert_eq!(
+			UiRawMessage::from(message.clone()),
+			UiRawMessage::from(&message)
+		);
+		assert_eq!(
+			UiAddressTableLookup::from(lookup.clone()),
+			UiAddressTableLookup::from(&lookup)
+		);
+		assert_eq!(
+			ui_meta.loaded_addresses,
+			Some(UiLoadedAddresses::from(&meta.loaded_addresses))
+		);
+		assert_eq!(
+			ui_meta.inner_instructions.unwrap()[0].instructions,
+			vec![UiInstruction::Compiled(UiCompiledInstruction::from(
+				&inner_instruction.instruction,
+				Some(2)
+			))]
+		);
+		assert_eq!(
+			UiTransactionStatusMeta::from(meta.clone()),
+			UiTransactionStatusMeta::from_owned(meta)
+		);
+	}
 }
@@ -104,6 +104,16 @@ impl<T> OptionSerializer<T> {
 		}
 	}
 
+	pub fn and_then<U, F>(self, f: F) -> Option<U>
+	where
+		F: FnOnce(T) -> Option<U>,
+	{
+		match self {
+			OptionSerializer::Some(x) => f(x),
+			_ => None,
+		}
+	}
+
 	pub fn map_or<U, F>(self, default: U, f: F) -> U
 	where
 		F: FnOnce(T) -> U,
@@ -155,6 +165,15 @@ impl<T> OptionSerializer<T> {
 	}
 }
 
+/// Defaults to [`OptionSerializer::Skip`] so that fields annotated with
+/// `#[serde(default, skip_serializing_if = "OptionSerializer::should_skip")]`
+/// are skipped when they are missing.
+impl<T> Default for OptionSerializer<T> {
+	fn default() -> Self {
+		Self::Skip
+	}
+}
+
 impl<T> From<Option<T>> for OptionSerializer<T> {
 	fn from(option: Option<T>) -> Self {
 		match option {
@@ -194,3 +213,54 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for OptionSerializer<T> {
 		Option::deserialize(deserializer).map(Into::into)
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use serde_derive::Deserialize;
+	use serde_derive::Serialize;
+	use serde_json::json;
+
+	use super::*;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Meta {
+		#[serde(default, skip_serializing_if = "OptionSerializer::should_skip")]
+		logs: OptionSerializer<Vec<String>>,
+	}
+
+	#[test]
+	fn test_option_serializer_round_trip() {
+		let cases = [
+			(
+				OptionSerializer::Some(vec!["log".to_string()]),
+				json!({ "logs": ["log"] }),
+			),
+			(OptionSerializer::None, json!({ "logs": null })),
+			(OptionSerializer::Skip, json!({})),
+		];
+
+		for (logs, value) in cases {
+			let meta = Meta { logs };
+			assert_eq!(serde_json::to_value(&meta).unwrap(), value);
+			assert_eq!(serde_json::from_value::<Meta>(value).unwrap(), meta);
+		}
+	}
+
+	#[test]
+	fn test_option_serializer_conversions() {
+		let some = OptionSerializer::Some(2_u8);
+
+		assert_eq!(some.as_ref().map(|value| value * 2), Some(4));
+		assert_eq!(some.clone().and_then(|value| value.checked_sub(3)), None);
+		assert_eq!(
+			OptionSerializer::<u8>::Skip.and_then(|value| Some(value + 1)),
+			None
+		);
+		assert_eq!(OptionSerializer::None.unwrap_or(5), 5);
+		assert_eq!(OptionSerializer::from(Some(2)), some);
+		assert_eq!(OptionSerializer::<u8>::from(None), OptionSerializer::None);
+		assert_eq!(Option::from(some), Some(2));
+		assert_eq!(Option::<u8>::from(OptionSerializer::Skip), None);
+		assert!(OptionSerializer::<u8>::default().is_skip());
+	}
+}
@@ -0,0 +1,301 @@
+//! Reconstruct the program invocation tree from transaction log messages.
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use solana_pubkey::Pubkey;
+
+const LOG_TRUNCATED: &str = "Log truncated";
+
+/// A single program invocation reconstructed from the transaction logs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramInvocation {
+	pub program_id: Pubkey,
+	/// The invocation depth from `invoke [n]`, starting at `1` for top level
+	/// instructions.
+	pub depth: usize,
+	/// The messages logged by this program. `Program log: ` lines have the
+	/// prefix removed and any other unrecognised lines are kept verbatim.
+	pub logs: Vec<String>,
+	/// One entry for each `Program data: ` line with the base64 decoded fields
+	/// of that line.
+	pub data: Vec<Vec<Vec<u8>>>,
+	/// The data set with `Program return: `.
+	pub return_data: Option<Vec<u8>>,
+	pub compute_units: Option<ComputeUnits>,
+	pub status: InvocationStatus,
+	/// The programs invoked by this program, in order.
+	pub invocations: Vec<ProgramInvocation>,
+}
+
+/// The compute units from `consumed X of Y compute units`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComputeUnits {
+	pub consumed: u64,
+	/// The units which were available to the program when it was invoked.
+	pub available: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum InvocationStatus {
+	Success,
+	/// The program failed with the included error message.
+	Failed(String),
+	/// The logs ended before the program completed, usually because they were
+	/// truncated.
+	#[default]
+	Incomplete,
+}
+
+/// Parse the `log_messages` of a transaction into the tree of top level
+/// program invocations.
+///
+/// Truncated logs don't cause an error. Every invocation which hasn't
+/// completed when the logs end is marked as [`InvocationStatus::Incomplete`].
+pub fn parse_logs(logs: &[String]) -> Vec<ProgramInvocation> {
+	let mut invocations = vec![];
+	let mut stack: Vec<ProgramInvocation> = vec![];
+
+	for log in logs {
+		if log == LOG_TRUNCATED {
+			break;
+		}
+
+		match LogLine::parse(log) {
+			LogLine::Invoke { program_id, depth } => {
+				stack.push(ProgramInvocation {
+					program_id,
+					depth,
+					..ProgramInvocation::default()
+				});
+			}
+			LogLine::Success => {
+				complete(&mut stack, &mut invocations, InvocationStatus::Success);
+			}
+			LogLine::Failed(message) => {
+				let status = InvocationStatus::Failed(message.to_string());
+				complete(&mut stack, &mut invocations, status);
+			}
+			LogLine::Consumed(compute_units) => {
+				if let Some(invocation) = stack.last_mut() {
+					invocation.compute_units = Some(compute_units);
+				}
+			}
+			LogLine::Data(data) => {
+				if let Some(invocation) = stack.last_mut() {
+					invocation.data.push(data);
+				}
+			}
+			LogLine::Return(data) => {
+				if let Some(invocation) = stack.last_mut() {
+					invocation.return_data = Some(data);
+				}
+			}
+			LogLine::Log(message) => {
+				if let Some(invocation) = stack.last_mut() {
+					invocation.logs.push(message.to_string());
+				}
+			}
+		}
+	}
+
+	while !stack.is_empty() {
+		complete(&mut stack, &mut invocations, InvocationStatus::Incomplete);
+	}
+
+	invocations
+}
+
+/// Pop the current invocation and attach it to its parent.
+fn complete(
+	stack: &mut Vec<ProgramInvocation>,
+	invocations: &mut Vec<ProgramInvocation>,
+	status: InvocationStatus,
+) {
+	let Some(mut invocation) = stack.pop() else {
+		return;
+	};
+
+	invocation.status = status;
+
+	match stack.last_mut() {
+		Some(parent) => parent.invocations.push(invocation),
+		None => invocations.push(invocation),
+	}
+}
+
+enum LogLine<'a> {
+	Invoke { program_id: Pubkey, depth: usize },
+	Success,
+	Failed(&'a str),
+	Consumed(ComputeUnits),
+	Data(Vec<Vec<u8>>),
+	Return(Vec<u8>),
+	Log(&'a str),
+}
+
+impl<'a> LogLine<'a> {
+	fn parse(log: &'a str) -> Self {
+		Self::parse_program_line(log).unwrap_or(Self::Log(log))
+	}
+
+	fn parse_program_line(log: &'a str) -> Option<Self> {
+		let rest = log.strip_prefix("Program ")?;
+
+		if let Some(message) = rest.strip_prefix("log: ") {
+			return Some(Self::Log(message));
+		}
+
+		if let Some(data) = rest.strip_prefix("data: ") {
+			let data = data
+				.split_whitespace()
+				.map(|field| BASE64_STANDARD.decode(field))
+				.collect::<Result<_, _>>()
+				.ok()?;
+			return Some(Self::Data(data));
+		}
+
+		if let Some(rest) = rest.strip_prefix("return: ") {
+			let (_, data) = rest.split_once(' ')?;
+			return BASE64_STANDARD.decode(data).ok().map(Self::Return);
+		}
+
+		let (program_id, rest) = rest.split_once(' ')?;
+		let program_id: Pubkey = program_id.parse().ok()?;
+
+		if rest == "success" {
+			return Some(Self::Success);
+		}
+
+		if let Some(message) = rest.strip_prefix("failed: ") {
+			return Some(Self::Failed(message));
+		}
+
+		if let Some(depth) = rest
+			.strip_prefix("invoke [")
+			.and_then(|rest| rest.strip_suffix(']'))
+		{
+			return Some(Self::Invoke {
+				program_id,
+				depth: depth.parse().ok()?,
+			});
+		}
+
+		let (consumed, available) = rest
+			.strip_prefix("consumed ")?
+			.strip_suffix(" compute units")?
+			.split_once(" of ")?;
+
+		Some(Self::Consumed(ComputeUnits {
+			consumed: consumed.parse().ok()?,
+			available: available.parse().ok()?,
+		}))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn logs(lines: &[&str]) -> Vec<String> {
+		lines.iter().map(ToString::to_string).collect()
+	}
+
+	#[test]
+	fn test_parse_logs_invocation_tree() {
+		let outer = Pubkey::new_unique();
+		let inner = Pubkey::new_unique();
+		let logs = logs(&[
+			"Program ComputeBudget111111111111111111111111111111 invoke [1]",
+			"Program ComputeBudget111111111111111111111111111111 success",
+			&format!("Program {outer} invoke [1]"),
+			"Program log: Instruction: Deposit",
+			&format!("Program {inner} invoke [2]"),
+			"Program log: Instruction: Transfer",
+			&format!("Program {inner} consumed 4645 of 190240 compute units"),
+			&format!("Program return: {inner} KgAAAAAAAAA="),
+			&format!("Program {inner} success"),
+			"Program data: AQID BAU=",
+			&format!("Program {outer} consumed 14405 of 199850 compute units"),
+			&format!("Program {outer} success"),
+		]);
+
+		let invocations = parse_logs(&logs);
+		assert_eq!(invocations.len(), 2);
+		assert_eq!(
+			invocations[0].program_id.to_string(),
+			"ComputeBudget111111111111111111111111111111"
+		);
+		assert_eq!(invocations[0].status, InvocationStatus::Success);
+		assert_eq!(invocations[0].compute_units, None);
+
+		assert_eq!(
+			invocations[1],
+			ProgramInvocation {
+				program_id: outer,
+				depth: 1,
+				logs: vec!["Instruction: Deposit".to_string()],
+				data: vec![vec![vec![1, 2, 3], vec![4, 5]]],
+				return_data: None,
+				compute_units: Some(ComputeUnits {
+					consumed: 14405,
+					available: 199_850,
+				}),
+				status: InvocationStatus::Success,
+				invocations: vec![ProgramInvocation {
+					program_id: inner,
+					depth: 2,
+					logs: vec!["Instruction: Transfer".to_string()],
+					data: vec![],
+					return_data: Some(vec![42, 0, 0, 0, 0, 0, 0, 0]),
+					compute_units: Some(ComputeUnits {
+						consumed: 4645,
+						available: 190_240,
+					}),
+					status: InvocationStatus::Success,
+					invocations: vec![],
+				}],
+			}
+		);
+	}
+
+	#[test]
+	fn test_parse_logs_failure() {
+		let program_id = Pubkey::new_unique();
+		let logs = logs(&[
+			&format!("Program {program_id} invoke [1]"),
+			"Program log: AnchorError occurred.",
+			&format!("Program {program_id} consumed 3512 of 200000 compute units"),
+			&format!("Program {program_id} failed: custom program error: 0x1771"),
+		]);
+
+		let invocations = parse_logs(&logs);
+		assert_eq!(invocations.len(), 1);
+		assert_eq!(
+			invocations[0].status,
+			InvocationStatus::Failed("custom program error: 0x1771".to_string())
+		);
+		assert_eq!(invocations[0].logs, vec!["AnchorError occurred."]);
+	}
+
+	#[test]
+	fn test_parse_logs_truncated() {
+		let outer = Pubkey::new_unique();
+		let inner = Pubkey::new_unique();
+		let logs = logs(&[
+			&format!("Program {outer} invoke [1]"),
+			&format!("Program {inner} invoke [2]"),
+			"Program log: the logs are about to be cut",
+			"Log truncated",
+		]);
+
+		let invocations = parse_logs(&logs);
+		assert_eq!(invocations.len(), 1);
+		assert_eq!(invocations[0].status, InvocationStatus::Incomplete);
+		assert_eq!(invocations[0].invocations.len(), 1);
+		assert_eq!(
+			invocations[0].invocations[0].status,
+			InvocationStatus::Incomplete
+		);
+		assert!(parse_logs(&[]).is_empty());
+	}
+}
@@ -0,0 +1,104 @@
+//! Count the allocations made while parsing the accounts of a 2000
+//! transaction block, comparing a reserved account key set built for every
+//! transaction with the shared set.
+//!
+//! Run with `cargo bench -p solana-transaction-status-wasm`.
+
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::hint::black_box;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use agave_reserved_account_keys::ReservedAccountKeys;
+use solana_hash::Hash;
+use solana_message::Message;
+use solana_message::MessageHeader;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_pubkey::Pubkey;
+use solana_transaction_status_wasm::parse_accounts::parse_legacy_message_accounts;
+use solana_transaction_status_wasm::parse_accounts::parse_legacy_message_accounts_with_reserved_keys;
+
+const TRANSACTIONS: usize = 2000;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(ptr, layout) }
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn main() {
+	let block = block();
+
+	// Build the shared set before measuring.
+	parse_legacy_message_accounts(&block[0]);
+
+	let (before_allocations, before_time) = measure(|| {
+		for message in &block {
+			let reserved_account_keys = ReservedAccountKeys::new_all_activated().active;
+			black_box(parse_legacy_message_accounts_with_reserved_keys(
+				message,
+				&reserved_account_keys,
+			));
+		}
+	});
+	let (cached_allocations, cached_time) = measure(|| {
+		for message in &block {
+			black_box(parse_legacy_message_accounts(message));
+		}
+	});
+
+	println!("{TRANSACTIONS} transactions");
+	println!("before: {before_allocations:>8} allocations {before_time:>10.2?}");
+	println!("cached: {cached_allocations:>8} allocations {cached_time:>10.2?}");
+}
+
+/// The allocations made and the time taken to parse the block.
+fn measure(parse: impl Fn()) -> (usize, Duration) {
+	let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+	let start = Instant::now();
+	parse();
+	let elapsed = start.elapsed();
+
+	(ALLOCATIONS.load(Ordering::Relaxed) - allocations, elapsed)
+}
+
+fn block() -> Vec<Message> {
+	(0..TRANSACTIONS)
+		.map(|_| {
+			let mut account_keys: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+			account_keys.push(solana_sdk_ids::system_program::id());
+			account_keys.push(solana_sdk_ids::sysvar::clock::id());
+
+			Message {
+				header: MessageHeader {
+					num_required_signatures: 1,
+					num_readonly_signed_accounts: 0,
+					num_readonly_unsigned_accounts: 2,
+				},
+				account_keys,
+				recent_blockhash: Hash::new_unique(),
+				instructions: vec![CompiledInstruction::new_from_raw_parts(
+					6,
+					vec![2, 0, 0, 0],
+					vec![0, 1],
+				)],
+			}
+		})
+		.collect()
+}
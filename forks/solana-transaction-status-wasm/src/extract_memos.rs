@@ -1,9 +1,14 @@
+use serde_json::Value;
 use solana_message::AccountKeys;
 use solana_message::Message;
 use solana_message::SanitizedMessage;
 use solana_message::compiled_instruction::CompiledInstruction;
 use solana_pubkey::Pubkey;
+use solana_transaction::versioned::VersionedTransaction;
 
+use crate::EncodedTransactionWithStatusMeta;
+use crate::UiInstruction;
+use crate::UiParsedInstruction;
 use crate::VersionedTransactionWithStatusMeta;
 use crate::parse_instruction::parse_memo_data;
 
@@ -64,6 +69,70 @@ impl ExtractMemos for VersionedTransactionWithStatusMeta {
 	}
 }
 
+impl ExtractMemos for VersionedTransaction {
+	fn extract_memos(&self) -> Vec<String> {
+		// Programs can't be loaded from lookup tables so the static keys are
+		// enough to find the memo instructions.
+		extract_memos_inner(
+			&AccountKeys::new(self.message.static_account_keys(), None),
+			self.message.instructions(),
+		)
+	}
+}
+
+impl ExtractMemos for EncodedTransactionWithStatusMeta {
+	fn extract_memos(&self) -> Vec<String> {
+		if let Some(transaction) = self.decode_transaction() {
+			return transaction.extract_memos();
+		}
+
+		let account_keys: Vec<Pubkey> = self
+			.account_keys()
+			.unwrap_or_default()
+			.into_iter()
+			.map(|account| account.pubkey)
+			.collect();
+
+		self.instructions()
+			.unwrap_or_default()
+			.iter()
+			.filter(|instruction| {
+				instruction
+					.program_id(&account_keys)
+					.is_some_and(|program_id| is_memo_program(&program_id))
+			})
+			.filter_map(ui_memo_data)
+			.map(|data| extract_and_fmt_memo_data(&data))
+			.collect()
+	}
+}
+
+fn is_memo_program(program_id: &Pubkey) -> bool {
+	*program_id == spl_memo::v1::id() || *program_id == spl_memo::id()
+}
+
+fn ui_memo_data(instruction: &UiInstruction) -> Option<Vec<u8>> {
+	match instruction {
+		UiInstruction::Compiled(instruction) => bs58::decode(&instruction.data).into_vec().ok(),
+		UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(instruction)) => {
+			bs58::decode(&instruction.data).into_vec().ok()
+		}
+		UiInstruction::Parsed(UiParsedInstruction::Parsed(instruction)) => {
+			match &instruction.parsed {
+				Value::String(memo) => Some(memo.clone().into_bytes()),
+				// A memo which isn't valid UTF-8 keeps its raw data next to the lossy text.
+				Value::Object(parsed) => {
+					parsed
+						.get("data")
+						.and_then(Value::as_str)
+						.and_then(|data| bs58::decode(data).into_vec().ok())
+				}
+				_ => None,
+			}
+		}
+	}
+}
+
 enum KeyType<'a> {
 	MemoProgram,
 	OtherProgram,
@@ -84,7 +153,7 @@ fn extract_memos_inner(
 				KeyType::MemoProgram => Some(&ix.data),
 				KeyType::OtherProgram => None,
 				KeyType::Unknown(program_id) => {
-					if **program_id == spl_memo::v1::id() || **program_id == spl_memo::id() {
+					if is_memo_program(program_id) {
 						account_keys[index] = KeyType::MemoProgram;
 						Some(&ix.data)
 					} else {
@@ -100,7 +169,18 @@ fn extract_memos_inner(
 
 #[cfg(test)]
 mod test {
+	use solana_hash::Hash;
+	use solana_message::MessageHeader;
+	use solana_message::VersionedMessage;
+	use solana_message::v0;
+	use solana_signature::Signature;
+
 	use super::*;
+	use crate::EncodableWithMeta;
+	use crate::EncodedTransaction;
+	use crate::TransactionStatusMeta;
+	use crate::UiAccountsList;
+	use crate::UiTransactionEncoding;
 
 	#[test]
 	fn test_extract_memos_inner() {
@@ -142,4 +222,74 @@ mod test {
 			expected_memos
 		);
 	}
+
+	#[test]
+	fn test_extract_memos_from_transaction() {
+		let invalid_memo = [0xff, 0xfe, b'o', b'k'];
+		let transaction = VersionedTransaction {
+			signatures: vec![Signature::from([1; 64])],
+			message: VersionedMessage::V0(v0::Message {
+				header: MessageHeader {
+					num_required_signatures: 1,
+					num_readonly_signed_accounts: 0,
+					num_readonly_unsigned_accounts: 3,
+				},
+				account_keys: vec![
+					Pubkey::new_unique(),
+					spl_memo::v1::id(),
+					Pubkey::new_unique(),
+					spl_memo::id(),
+				],
+				recent_blockhash: Hash::new_unique(),
+				instructions: vec![
+					CompiledInstruction::new_from_raw_parts(1, b"Test memo".to_vec(), vec![]),
+					CompiledInstruction::new_from_raw_parts(2, b"not a memo".to_vec(), vec![]),
+					CompiledInstruction::new_from_raw_parts(3, invalid_memo.to_vec(), vec![0]),
+				],
+				address_table_lookups: vec![],
+			}),
+		};
+		let expected = "[9] Test memo; [4] (unparseable)";
+
+		assert_eq!(
+			extract_and_fmt_memos(&transaction).as_deref(),
+			Some(expected)
+		);
+
+		let meta = TransactionStatusMeta::default();
+
+		for encoding in [
+			UiTransactionEncoding::Base58,
+			UiTransactionEncoding::Base64,
+			UiTransactionEncoding::Json,
+		] {
+			let encoded = EncodedTransactionWithStatusMeta {
+				transaction: transaction.encode_with_meta(encoding, &meta),
+				meta: None,
+				version: Some(transaction.version()),
+			};
+			assert_eq!(
+				extract_and_fmt_memos(&encoded).as_deref(),
+				Some(expected),
+				"{encoding}"
+			);
+		}
+
+		let parsed = EncodedTransactionWithStatusMeta {
+			transaction: transaction.encode_with_meta(UiTransactionEncoding::JsonParsed, &meta),
+			meta: None,
+			version: Some(transaction.version()),
+		};
+		assert_eq!(extract_and_fmt_memos(&parsed).as_deref(), Some(expected));
+
+		let without_memos = EncodedTransactionWithStatusMeta {
+			transaction: EncodedTransaction::Accounts(UiAccountsList {
+				signatures: vec![],
+				account_keys: vec![],
+			}),
+			meta: None,
+			version: None,
+		};
+		assert_eq!(extract_and_fmt_memos(&without_memos), None);
+	}
 }
@@ -2,7 +2,6 @@
 
 use std::collections::HashSet;
 
-use agave_reserved_account_keys::ReservedAccountKeys;
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
 use solana_clock::Slot;
@@ -60,6 +59,7 @@ pub use solana_transaction_status_client_types_wasm::option_serializer;
 use thiserror::Error;
 
 pub use crate::extract_memos::extract_and_fmt_memos;
+use crate::parse_accounts::ALL_ACTIVATED_RESERVED_ACCOUNT_KEYS;
 use crate::parse_accounts::parse_legacy_message_accounts;
 use crate::parse_accounts::parse_v0_message_accounts;
 use crate::parse_instruction::parse;
@@ -72,6 +72,7 @@ pub mod parse_accounts;
 pub mod parse_address_lookup_table;
 pub mod parse_associated_token;
 pub mod parse_bpf_loader;
+pub mod parse_compute_budget;
 pub mod parse_instruction;
 pub mod parse_stake;
 pub mod parse_system;
@@ -577,15 +578,13 @@ impl VersionedTransactionWithStatusMeta {
 		show_rewards: bool,
 	) -> Result<EncodedTransactionWithStatusMeta, EncodeError> {
 		let version = self.validate_version(max_supported_transaction_version)?;
-		let reserved_account_keys = ReservedAccountKeys::new_all_activated();
-
 		let account_keys = match &self.transaction.message {
 			VersionedMessage::Legacy(message) => parse_legacy_message_accounts(message),
 			VersionedMessage::V0(message) => {
 				let loaded_message = LoadedMessage::new_borrowed(
 					message,
 					&self.meta.loaded_addresses,
-					&reserved_account_keys.active,
+					&ALL_ACTIVATED_RESERVED_ACCOUNT_KEYS,
 				);
 				parse_v0_message_accounts(&loaded_message)
 			}
@@ -869,12 +868,11 @@ impl EncodableWithMeta for v0::Message {
 		meta: &TransactionStatusMeta,
 	) -> Self::Encoded {
 		if encoding == UiTransactionEncoding::JsonParsed {
-			let reserved_account_keys = ReservedAccountKeys::new_all_activated();
 			let account_keys = AccountKeys::new(&self.account_keys, Some(&meta.loaded_addresses));
 			let loaded_message = LoadedMessage::new_borrowed(
 				self,
 				&meta.loaded_addresses,
-				&reserved_account_keys.active,
+				&ALL_ACTIVATED_RESERVED_ACCOUNT_KEYS,
 			);
 			UiMessage::Parsed(UiParsedMessage {
 				account_keys: parse_v0_message_accounts(&loaded_message),
@@ -1,16 +1,101 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
 use agave_reserved_account_keys::ReservedAccountKeys;
 use solana_message::Message;
+use solana_message::VersionedMessage;
+use solana_message::v0::LoadedAddresses;
 use solana_message::v0::LoadedMessage;
+use solana_pubkey::Pubkey;
 pub use solana_transaction_status_client_types_wasm::ParsedAccount;
 pub use solana_transaction_status_client_types_wasm::ParsedAccountSource;
+use thiserror::Error;
+
+/// The reserved account keys with every feature activated. Building the set
+/// allocates, so it is built once and shared by every transaction.
+pub(crate) static ALL_ACTIVATED_RESERVED_ACCOUNT_KEYS: LazyLock<HashSet<Pubkey>> =
+	LazyLock::new(|| ReservedAccountKeys::new_all_activated().active);
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseAccountsError {
+	#[error("the loaded addresses of the v0 message address table lookups are missing")]
+	MissingLoadedAddresses,
+
+	#[error(
+		"the loaded addresses don't match the address table lookups, expected {expected_writable} \
+		 writable and {expected_readonly} readonly addresses"
+	)]
+	LoadedAddressesMismatch {
+		expected_writable: usize,
+		expected_readonly: usize,
+	},
+}
+
+/// Parse the accounts of a legacy or v0 `message`.
+///
+/// The accounts of a v0 message include the addresses `loaded` from its
+/// address table lookups, which are found in the `loaded_addresses` of the
+/// transaction status meta. They are required when the message has lookups
+/// and ignored for legacy messages.
+pub fn parse_message_accounts(
+	message: &VersionedMessage,
+	loaded: Option<&LoadedAddresses>,
+) -> Result<Vec<ParsedAccount>, ParseAccountsError> {
+	let message = match message {
+		VersionedMessage::Legacy(message) => return Ok(parse_legacy_message_accounts(message)),
+		VersionedMessage::V0(message) => message,
+	};
+	let empty = LoadedAddresses::default();
+	let loaded = match loaded {
+		Some(loaded) => loaded,
+		None if message.address_table_lookups.is_empty() => &empty,
+		None => return Err(ParseAccountsError::MissingLoadedAddresses),
+	};
+	let (expected_writable, expected_readonly) =
+		message
+			.address_table_lookups
+			.iter()
+			.fold((0, 0), |(writable, readonly), lookup| {
+				(
+					writable + lookup.writable_indexes.len(),
+					readonly + lookup.readonly_indexes.len(),
+				)
+			});
+
+	if loaded.writable.len() != expected_writable || loaded.readonly.len() != expected_readonly {
+		return Err(ParseAccountsError::LoadedAddressesMismatch {
+			expected_writable,
+			expected_readonly,
+		});
+	}
+
+	let loaded_message =
+		LoadedMessage::new_borrowed(message, loaded, &ALL_ACTIVATED_RESERVED_ACCOUNT_KEYS);
+
+	Ok(parse_v0_message_accounts(&loaded_message))
+}
 
 pub fn parse_legacy_message_accounts(message: &Message) -> Vec<ParsedAccount> {
-	let reserved_account_keys = ReservedAccountKeys::new_all_activated().active;
+	parse_legacy_message_accounts_with_reserved_keys(message, &ALL_ACTIVATED_RESERVED_ACCOUNT_KEYS)
+}
+
+/// Parse the accounts of a legacy `message`, demoting the
+/// `reserved_account_keys` to readonly.
+///
+/// [`parse_legacy_message_accounts`] assumes every feature which reserves
+/// keys is active. Historical blocks were processed before some of those
+/// features were activated, so pass the keys which were reserved at the
+/// time, such as the `active` set of a [`ReservedAccountKeys`] built for the
+/// feature set of the block.
+pub fn parse_legacy_message_accounts_with_reserved_keys(
+	message: &Message,
+	reserved_account_keys: &HashSet<Pubkey>,
+) -> Vec<ParsedAccount> {
 	let mut accounts: Vec<ParsedAccount> = vec![];
 	for (i, account_key) in message.account_keys.iter().enumerate() {
 		accounts.push(ParsedAccount {
 			pubkey: *account_key,
-			writable: message.is_maybe_writable(i, Some(&reserved_account_keys)),
+			writable: message.is_maybe_writable(i, Some(reserved_account_keys)),
 			signer: message.is_signer(i),
 			source: Some(ParsedAccountSource::Transaction),
 		});
@@ -41,7 +126,7 @@ mod test {
 	use agave_reserved_account_keys::ReservedAccountKeys;
 	use solana_message::MessageHeader;
 	use solana_message::v0;
-	use solana_message::v0::LoadedAddresses;
+	use solana_message::v0::MessageAddressTableLookup;
 	use solana_pubkey::Pubkey;
 
 	use super::*;
@@ -93,6 +178,47 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn test_parse_legacy_message_accounts_with_reserved_keys() {
+		let payer = Pubkey::new_unique();
+		let clock = solana_sdk_ids::sysvar::clock::id();
+		let custom = Pubkey::new_unique();
+		let message = Message {
+			header: MessageHeader {
+				num_required_signatures: 1,
+				num_readonly_signed_accounts: 0,
+				num_readonly_unsigned_accounts: 0,
+			},
+			account_keys: vec![payer, clock, custom],
+			..Message::default()
+		};
+		let writable = |accounts: Vec<ParsedAccount>| {
+			accounts
+				.into_iter()
+				.map(|account| account.writable)
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(
+			writable(parse_legacy_message_accounts(&message)),
+			vec![true, false, true]
+		);
+		assert_eq!(
+			writable(parse_legacy_message_accounts_with_reserved_keys(
+				&message,
+				&HashSet::new()
+			)),
+			vec![true, true, true]
+		);
+		assert_eq!(
+			writable(parse_legacy_message_accounts_with_reserved_keys(
+				&message,
+				&HashSet::from([custom])
+			)),
+			vec![true, true, false]
+		);
+	}
+
 	#[test]
 	fn test_parse_v0_message_accounts() {
 		let pubkey0 = Pubkey::new_unique();
@@ -160,4 +286,75 @@ mod test {
 			]
 		);
 	}
+
+	#[test]
+	fn test_parse_message_accounts() {
+		let pubkeys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+		let header = MessageHeader {
+			num_required_signatures: 1,
+			num_readonly_signed_accounts: 0,
+			num_readonly_unsigned_accounts: 1,
+		};
+		let legacy = Message {
+			header,
+			account_keys: pubkeys[..2].to_vec(),
+			..Message::default()
+		};
+		let v0_message = v0::Message {
+			header,
+			account_keys: pubkeys[..2].to_vec(),
+			address_table_lookups: vec![MessageAddressTableLookup {
+				account_key: Pubkey::new_unique(),
+				writable_indexes: vec![0],
+				readonly_indexes: vec![1],
+			}],
+			..v0::Message::default()
+		};
+		let loaded = LoadedAddresses {
+			writable: vec![pubkeys[2]],
+			readonly: vec![pubkeys[3]],
+		};
+
+		assert_eq!(
+			parse_message_accounts(&VersionedMessage::Legacy(legacy.clone()), None),
+			Ok(parse_legacy_message_accounts(&legacy))
+		);
+
+		let message = VersionedMessage::V0(v0_message.clone());
+		let accounts = parse_message_accounts(&message, Some(&loaded)).unwrap();
+
+		assert_eq!(
+			accounts
+				.iter()
+				.map(|account| (account.pubkey, account.writable, account.source.clone()))
+				.collect::<Vec<_>>(),
+			vec![
+				(pubkeys[0], true, Some(ParsedAccountSource::Transaction)),
+				(pubkeys[1], false, Some(ParsedAccountSource::Transaction)),
+				(pubkeys[2], true, Some(ParsedAccountSource::LookupTable)),
+				(pubkeys[3], false, Some(ParsedAccountSource::LookupTable)),
+			]
+		);
+		assert_eq!(
+			parse_message_accounts(&message, None),
+			Err(ParseAccountsError::MissingLoadedAddresses)
+		);
+		assert_eq!(
+			parse_message_accounts(&message, Some(&LoadedAddresses::default())),
+			Err(ParseAccountsError::LoadedAddressesMismatch {
+				expected_writable: 1,
+				expected_readonly: 1,
+			})
+		);
+
+		let without_lookups = VersionedMessage::V0(v0::Message {
+			address_table_lookups: vec![],
+			..v0_message
+		});
+
+		assert_eq!(
+			parse_message_accounts(&without_lookups, None).map(|accounts| accounts.len()),
+			Ok(2)
+		);
+	}
 }
@@ -12,6 +12,11 @@ use crate::parse_instruction::ParseInstructionError;
 use crate::parse_instruction::ParsedInstructionEnum;
 use crate::parse_instruction::check_num_accounts;
 
+/// The number of bytes of an upgradeable loader `write` instruction which are
+/// included in the parsed output. Program chunks are close to the transaction
+/// size limit so only the start is kept, alongside the full `length`.
+pub const WRITE_BYTES_PREVIEW_LENGTH: usize = 64;
+
 pub fn parse_bpf_loader(
 	instruction: &CompiledInstruction,
 	account_keys: &AccountKeys,
@@ -84,11 +89,13 @@ pub fn parse_bpf_upgradeable_loader(
 		}
 		UpgradeableLoaderInstruction::Write { offset, bytes } => {
 			check_num_bpf_upgradeable_loader_accounts(&instruction.accounts, 2)?;
+			let preview = &bytes[..bytes.len().min(WRITE_BYTES_PREVIEW_LENGTH)];
 			Ok(ParsedInstructionEnum {
 				instruction_type: "write".to_string(),
 				info: json!({
 					"offset": offset,
-					"bytes": BASE64_STANDARD.encode(bytes),
+					"length": bytes.len(),
+					"bytes": BASE64_STANDARD.encode(preview),
 					"account": account_keys[instruction.accounts[0] as usize].to_string(),
 					"authority": account_keys[instruction.accounts[1] as usize].to_string(),
 				}),
@@ -422,7 +429,8 @@ mod test {
 				instruction_type: "write".to_string(),
 				info: json!({
 					"offset": offset,
-					"bytes": BASE64_STANDARD.encode(&bytes),
+					"length": 99,
+					"bytes": BASE64_STANDARD.encode(&bytes[..WRITE_BYTES_PREVIEW_LENGTH]),
 					"account": buffer_address.to_string(),
 					"authority": authority_address.to_string(),
 				}),
@@ -0,0 +1,193 @@
+use borsh::BorshDeserialize;
+use serde_json::json;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_sdk_ids::compute_budget;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::parse_instruction::ParsableProgram;
+use crate::parse_instruction::ParseInstructionError;
+use crate::parse_instruction::ParsedInstructionEnum;
+
+pub fn parse_compute_budget(
+	instruction: &CompiledInstruction,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+	let compute_budget_instruction = ComputeBudgetInstruction::try_from_slice(&instruction.data)
+		.map_err(|_| {
+			ParseInstructionError::InstructionNotParsable(ParsableProgram::ComputeBudget)
+		})?;
+
+	match compute_budget_instruction {
+		ComputeBudgetInstruction::RequestHeapFrame(bytes) => {
+			Ok(ParsedInstructionEnum {
+				instruction_type: "requestHeapFrame".to_string(),
+				info: json!({ "bytes": bytes }),
+			})
+		}
+		ComputeBudgetInstruction::SetComputeUnitLimit(units) => {
+			Ok(ParsedInstructionEnum {
+				instruction_type: "setComputeUnitLimit".to_string(),
+				info: json!({ "units": units }),
+			})
+		}
+		ComputeBudgetInstruction::SetComputeUnitPrice(micro_lamports) => {
+			Ok(ParsedInstructionEnum {
+				instruction_type: "setComputeUnitPrice".to_string(),
+				info: json!({ "microLamports": micro_lamports }),
+			})
+		}
+		ComputeBudgetInstruction::SetLoadedAccountsDataSizeLimit(bytes) => {
+			Ok(ParsedInstructionEnum {
+				instruction_type: "setLoadedAccountsDataSizeLimit".to_string(),
+				info: json!({ "bytes": bytes }),
+			})
+		}
+		ComputeBudgetInstruction::Unused => {
+			Err(ParseInstructionError::InstructionNotParsable(
+				ParsableProgram::ComputeBudget,
+			))
+		}
+	}
+}
+
+/// The values requested by the compute budget instructions of a transaction.
+/// A field is `None` when the transaction doesn't set it, in which case the
+/// runtime default applies.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeBudgetValues {
+	/// The compute unit limit requested with `setComputeUnitLimit`.
+	pub unit_limit: Option<u32>,
+	/// The price per compute unit in micro-lamports requested with
+	/// `setComputeUnitPrice`.
+	pub unit_price: Option<u64>,
+	/// The heap frame size in bytes requested with `requestHeapFrame`.
+	pub heap_frame: Option<u32>,
+}
+
+/// Read the compute budget values from the top level instructions of the
+/// transaction. Instructions which can't be decoded are skipped.
+pub fn extract_compute_budget(transaction: &VersionedTransaction) -> ComputeBudgetValues {
+	let account_keys = transaction.message.static_account_keys();
+	let mut values = ComputeBudgetValues::default();
+
+	for instruction in transaction.message.instructions() {
+		if account_keys.get(usize::from(instruction.program_id_index))
+			!= Some(&compute_budget::id())
+		{
+			continue;
+		}
+
+		match ComputeBudgetInstruction::try_from_slice(&instruction.data) {
+			Ok(ComputeBudgetInstruction::SetComputeUnitLimit(units)) => {
+				values.unit_limit = Some(units);
+			}
+			Ok(ComputeBudgetInstruction::SetComputeUnitPrice(micro_lamports)) => {
+				values.unit_price = Some(micro_lamports);
+			}
+			Ok(ComputeBudgetInstruction::RequestHeapFrame(bytes)) => {
+				values.heap_frame = Some(bytes);
+			}
+			_ => {}
+		}
+	}
+
+	values
+}
+
+#[cfg(test)]
+mod test {
+	use solana_hash::Hash;
+	use solana_message::AccountKeys;
+	use solana_message::Message;
+	use solana_message::VersionedMessage;
+	use solana_pubkey::Pubkey;
+	use solana_system_interface::instruction as system_instruction;
+
+	use super::*;
+
+	#[test]
+	fn test_parse_compute_budget_ix() {
+		let cases = [
+			(
+				ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+				"setComputeUnitLimit",
+				json!({ "units": 200_000 }),
+			),
+			(
+				ComputeBudgetInstruction::set_compute_unit_price(u64::MAX),
+				"setComputeUnitPrice",
+				json!({ "microLamports": u64::MAX }),
+			),
+			(
+				ComputeBudgetInstruction::request_heap_frame(256 * 1024),
+				"requestHeapFrame",
+				json!({ "bytes": 256 * 1024 }),
+			),
+			(
+				ComputeBudgetInstruction::set_loaded_accounts_data_size_limit(64 * 1024),
+				"setLoadedAccountsDataSizeLimit",
+				json!({ "bytes": 64 * 1024 }),
+			),
+		];
+
+		for (instruction, instruction_type, info) in cases {
+			let message = Message::new(&[instruction], None);
+			assert_eq!(
+				parse_compute_budget(&message.instructions[0]).unwrap(),
+				ParsedInstructionEnum {
+					instruction_type: instruction_type.to_string(),
+					info,
+				}
+			);
+		}
+
+		let message = Message::new(&[ComputeBudgetInstruction::set_compute_unit_limit(1)], None);
+		let parsed = crate::parse_instruction::parse(
+			&compute_budget::id(),
+			&message.instructions[0],
+			&AccountKeys::new(&message.account_keys, None),
+			None,
+		)
+		.unwrap();
+		assert_eq!(parsed.program, "compute-budget");
+
+		let unused = CompiledInstruction::new_from_raw_parts(0, vec![0], vec![]);
+		assert!(parse_compute_budget(&unused).is_err());
+		let truncated = CompiledInstruction::new_from_raw_parts(0, vec![2, 1], vec![]);
+		assert!(parse_compute_budget(&truncated).is_err());
+	}
+
+	#[test]
+	fn test_extract_compute_budget() {
+		let payer = Pubkey::new_unique();
+		let transfer = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+		let transaction = |instructions: &[_]| {
+			VersionedTransaction {
+				signatures: vec![],
+				message: VersionedMessage::Legacy(Message::new_with_blockhash(
+					instructions,
+					Some(&payer),
+					&Hash::default(),
+				)),
+			}
+		};
+
+		assert_eq!(
+			extract_compute_budget(&transaction(std::slice::from_ref(&transfer))),
+			ComputeBudgetValues::default()
+		);
+		assert_eq!(
+			extract_compute_budget(&transaction(&[
+				ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+				ComputeBudgetInstruction::set_compute_unit_price(5_000),
+				ComputeBudgetInstruction::request_heap_frame(64 * 1024),
+				transfer,
+			])),
+			ComputeBudgetValues {
+				unit_limit: Some(300_000),
+				unit_price: Some(5_000),
+				heap_frame: Some(64 * 1024),
+			}
+		);
+	}
+}
@@ -4,6 +4,7 @@ use std::str::from_utf8;
 
 use inflector::Inflector;
 use serde_json::Value;
+use serde_json::json;
 use solana_account_decoder_wasm::parse_token::spl_token_ids;
 use solana_message::AccountKeys;
 use solana_message::compiled_instruction::CompiledInstruction;
@@ -19,6 +20,7 @@ use crate::parse_address_lookup_table::parse_address_lookup_table;
 use crate::parse_associated_token::parse_associated_token;
 use crate::parse_bpf_loader::parse_bpf_loader;
 use crate::parse_bpf_loader::parse_bpf_upgradeable_loader;
+use crate::parse_compute_budget::parse_compute_budget;
 use crate::parse_stake::parse_stake;
 use crate::parse_system::parse_system;
 use crate::parse_token::parse_token;
@@ -38,6 +40,10 @@ static PARSABLE_PROGRAM_IDS: std::sync::LazyLock<HashMap<Pubkey, ParsableProgram
 			(spl_memo::v1::id(), ParsableProgram::SplMemo),
 			(spl_memo::id(), ParsableProgram::SplMemo),
 			(solana_sdk_ids::bpf_loader::id(), ParsableProgram::BpfLoader),
+			(
+				solana_sdk_ids::compute_budget::id(),
+				ParsableProgram::ComputeBudget,
+			),
 			(
 				solana_sdk_ids::bpf_loader_upgradeable::id(),
 				ParsableProgram::BpfUpgradeableLoader,
@@ -88,6 +94,7 @@ pub enum ParsableProgram {
 	SplToken,
 	BpfLoader,
 	BpfUpgradeableLoader,
+	ComputeBudget,
 	Stake,
 	System,
 	Vote,
@@ -109,7 +116,7 @@ pub fn parse(
 		ParsableProgram::SplAssociatedTokenAccount => {
 			serde_json::to_value(parse_associated_token(instruction, account_keys)?)?
 		}
-		ParsableProgram::SplMemo => parse_memo(instruction)?,
+		ParsableProgram::SplMemo => parse_memo(instruction),
 		ParsableProgram::SplToken => serde_json::to_value(parse_token(instruction, account_keys)?)?,
 		ParsableProgram::BpfLoader => {
 			serde_json::to_value(parse_bpf_loader(instruction, account_keys)?)?
@@ -117,6 +124,7 @@ pub fn parse(
 		ParsableProgram::BpfUpgradeableLoader => {
 			serde_json::to_value(parse_bpf_upgradeable_loader(instruction, account_keys)?)?
 		}
+		ParsableProgram::ComputeBudget => serde_json::to_value(parse_compute_budget(instruction)?)?,
 		ParsableProgram::Stake => serde_json::to_value(parse_stake(instruction, account_keys)?)?,
 		ParsableProgram::System => serde_json::to_value(parse_system(instruction, account_keys)?)?,
 		ParsableProgram::Vote => serde_json::to_value(parse_vote(instruction, account_keys)?)?,
@@ -129,10 +137,20 @@ pub fn parse(
 	})
 }
 
-fn parse_memo(instruction: &CompiledInstruction) -> Result<Value, ParseInstructionError> {
-	parse_memo_data(&instruction.data)
-		.map(Value::String)
-		.map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::SplMemo))
+/// Memos are parsed as a string. When the data isn't valid UTF-8 the lossy
+/// conversion is returned instead, with `invalidUtf8` set so it can't be
+/// mistaken for the original memo, and the raw `data` encoded as base58.
+fn parse_memo(instruction: &CompiledInstruction) -> Value {
+	match parse_memo_data(&instruction.data) {
+		Ok(memo) => Value::String(memo),
+		Err(_) => {
+			json!({
+				"memo": String::from_utf8_lossy(&instruction.data),
+				"invalidUtf8": true,
+				"data": bs58::encode(&instruction.data).into_string(),
+			})
+		}
+	}
 }
 
 pub fn parse_memo_data(data: &[u8]) -> Result<String, Utf8Error> {
@@ -155,8 +173,6 @@ pub(crate) fn check_num_accounts(
 
 #[cfg(test)]
 mod test {
-	use serde_json::json;
-
 	use super::*;
 
 	#[test]
@@ -188,6 +204,124 @@ mod test {
 
 		let non_parsable_program_id = Pubkey::from([1; 32]);
 		assert!(parse(&non_parsable_program_id, &memo_instruction, &no_keys, None).is_err());
+
+		let source = Pubkey::new_unique();
+		let destination = Pubkey::new_unique();
+		let message = solana_message::Message::new(
+			&[solana_system_interface::instruction::transfer(
+				&source,
+				&destination,
+				42,
+			)],
+			None,
+		);
+		let account_keys = AccountKeys::new(&message.account_keys, None);
+		let parsed = parse(
+			&system_program::id(),
+			&message.instructions[0],
+			&account_keys,
+			Some(1),
+		)
+		.unwrap();
+		assert_eq!(
+			serde_json::to_value(parsed).unwrap(),
+			json!({
+				"program": "system",
+				"programId": system_program::id().to_string(),
+				"parsed": {
+					"type": "transfer",
+					"info": {
+						"source": source.to_string(),
+						"destination": destination.to_string(),
+						"lamports": 42,
+					},
+				},
+				"stackHeight": 1,
+			})
+		);
+	}
+
+	#[test]
+	fn test_parse_token_programs() {
+		let source = Pubkey::new_unique();
+		let mint = Pubkey::new_unique();
+		let destination = Pubkey::new_unique();
+		let multisig = Pubkey::new_unique();
+		let signers = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+		for program_id in [spl_token::id(), spl_token_2022::id()] {
+			let instruction = spl_token_2022::instruction::transfer_checked(
+				&program_id,
+				&source,
+				&mint,
+				&destination,
+				&multisig,
+				&signers.iter().collect::<Vec<_>>(),
+				1_500,
+				3,
+			)
+			.unwrap();
+			let message = solana_message::Message::new(&[instruction], Some(&signers[0]));
+			let account_keys = AccountKeys::new(&message.account_keys, None);
+			let parsed = parse(&program_id, &message.instructions[0], &account_keys, None).unwrap();
+
+			assert_eq!(parsed.program, "spl-token");
+			assert_eq!(parsed.program_id, program_id);
+			assert_eq!(
+				parsed.parsed,
+				json!({
+					"type": "transferChecked",
+					"info": {
+						"source": source.to_string(),
+						"mint": mint.to_string(),
+						"destination": destination.to_string(),
+						"tokenAmount": {
+							"uiAmount": 1.5,
+							"decimals": 3,
+							"amount": "1500",
+							"uiAmountString": "1.5",
+						},
+						"multisigAuthority": multisig.to_string(),
+						"signers": [signers[0].to_string(), signers[1].to_string()],
+					},
+				})
+			);
+		}
+
+		// token-2022 instructions are only dispatched for the token program ids
+		let instruction =
+			spl_token_2022::extension::transfer_fee::instruction::transfer_checked_with_fee(
+				&spl_token_2022::id(),
+				&source,
+				&mint,
+				&destination,
+				&multisig,
+				&[],
+				100,
+				2,
+				1,
+			)
+			.unwrap();
+		let message = solana_message::Message::new(&[instruction], None);
+		let account_keys = AccountKeys::new(&message.account_keys, None);
+		let parsed = parse(
+			&spl_token_2022::id(),
+			&message.instructions[0],
+			&account_keys,
+			None,
+		)
+		.unwrap();
+		assert_eq!(parsed.parsed["type"], json!("transferCheckedWithFee"));
+		assert_eq!(parsed.parsed["info"]["feeAmount"]["amount"], json!("1"));
+		assert!(
+			parse(
+				&Pubkey::new_unique(),
+				&message.instructions[0],
+				&account_keys,
+				None
+			)
+			.is_err()
+		);
 	}
 
 	#[test]
@@ -198,20 +332,19 @@ mod test {
 				program_id_index: 0,
 				accounts: vec![],
 				data: good_memo.as_bytes().to_vec(),
-			})
-			.unwrap(),
+			}),
 			Value::String(good_memo),
 		);
 
-		let bad_memo = vec![128u8];
+		let bad_memo = vec![b'h', b'i', 128u8];
 		assert!(std::str::from_utf8(&bad_memo).is_err());
-		assert!(
+		assert_eq!(
 			parse_memo(&CompiledInstruction {
 				program_id_index: 0,
 				data: bad_memo,
 				accounts: vec![],
-			})
-			.is_err(),
+			}),
+			json!({ "memo": "hi\u{fffd}", "invalidUtf8": true, "data": "c57V" }),
 		);
 	}
 }
@@ -689,4 +689,28 @@ mod test {
 		message.instructions[0].accounts.pop();
 		assert!(parse_system(&message.instructions[0], &AccountKeys::new(&keys, None)).is_err());
 	}
+
+	#[test]
+	fn test_parse_system_upgrade_nonce_account_ix() {
+		let nonce_pubkey = Pubkey::new_unique();
+
+		let instruction = system_instruction::upgrade_nonce_account(nonce_pubkey);
+		let mut message = Message::new(&[instruction], None);
+		assert_eq!(
+			parse_system(
+				&message.instructions[0],
+				&AccountKeys::new(&message.account_keys, None)
+			)
+			.unwrap(),
+			ParsedInstructionEnum {
+				instruction_type: "upgradeNonce".to_string(),
+				info: json!({
+					"nonceAccount": nonce_pubkey.to_string(),
+				}),
+			}
+		);
+		let keys = message.account_keys.clone();
+		message.instructions[0].accounts.pop();
+		assert!(parse_system(&message.instructions[0], &AccountKeys::new(&keys, None)).is_err());
+	}
 }
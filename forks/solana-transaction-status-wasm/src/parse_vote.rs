@@ -1017,4 +1017,105 @@ mod test {
 		message.instructions[0].accounts.pop();
 		assert!(parse_vote(&message.instructions[0], &AccountKeys::new(&keys, None)).is_err());
 	}
+
+	#[test]
+	fn test_parse_recorded_vote_ix() {
+		// The vote from the `getBlock` example response of the rpc documentation.
+		let account_keys: Vec<Pubkey> = [
+			"3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe",
+			"AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc",
+			"SysvarS1otHashes111111111111111111111111111",
+			"SysvarC1ock11111111111111111111111111111111",
+			"Vote111111111111111111111111111111111111111",
+		]
+		.iter()
+		.map(|key| key.parse().unwrap())
+		.collect();
+		let instruction = CompiledInstruction {
+			program_id_index: 4,
+			accounts: vec![1, 2, 3, 0],
+			data: bs58::decode(
+				"37u9WtQpcm6ULa3WRQHmj49EPs4if7o9f1jSRVZpm2dvihR9C8jY4NqEwXUbLwx15HBSNcP1",
+			)
+			.into_vec()
+			.unwrap(),
+		};
+		assert_eq!(
+			parse_vote(&instruction, &AccountKeys::new(&account_keys, None)).unwrap(),
+			ParsedInstructionEnum {
+				instruction_type: "vote".to_string(),
+				info: json!({
+					"voteAccount": "AjozzgE83A3x1sHNUR64hfH7zaEBWeMaFuAN9kQgujrc",
+					"slotHashesSysvar": "SysvarS1otHashes111111111111111111111111111",
+					"clockSysvar": "SysvarC1ock11111111111111111111111111111111",
+					"voteAuthority": "3UVYmECPPMZSCqWKfENfuoTv51fTDTWicX9xmBD2euKe",
+					"vote": {
+						"slots": [429],
+						"hash": "VcDEExrXLTkaSMdNjmSxPWGUsdZSnb33v69PbpJnAQx",
+						"timestamp": null,
+					},
+				}),
+			}
+		);
+	}
+
+	#[test]
+	fn test_parse_compact_vote_state_update_bytes() {
+		// The compact encoding stores the root followed by the lockout slots as
+		// varint offsets from the previous slot.
+		#[rustfmt::skip]
+		let data = [
+			12, 0, 0, 0, // CompactUpdateVoteState
+			100, 163, 225, 17, 0, 0, 0, 0, // root 300_000_100
+			4, // lockouts
+			5, 4, 1, 3, 4, 2, 1, 1, // (offset, confirmation count)
+			7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+			7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, // hash
+			1, 0, 241, 83, 101, 0, 0, 0, 0, // timestamp 1_700_000_000
+		];
+		let account_keys = [
+			Pubkey::new_from_array([1; 32]),
+			Pubkey::new_from_array([2; 32]),
+		];
+		let instruction = CompiledInstruction {
+			program_id_index: 0,
+			accounts: vec![0, 1],
+			data: data.to_vec(),
+		};
+		let mut vote_state_update = VoteStateUpdate::from(vec![
+			(300_000_105, 4),
+			(300_000_106, 3),
+			(300_000_110, 2),
+			(300_000_111, 1),
+		]);
+		vote_state_update.root = Some(300_000_100);
+		vote_state_update.hash = Hash::new_from_array([7; 32]);
+		vote_state_update.timestamp = Some(1_700_000_000);
+
+		assert_eq!(
+			vote_instruction::compact_update_vote_state(
+				&account_keys[0],
+				&account_keys[1],
+				vote_state_update.clone()
+			)
+			.data,
+			data
+		);
+		assert_eq!(
+			parse_vote(&instruction, &AccountKeys::new(&account_keys, None)).unwrap(),
+			ParsedInstructionEnum {
+				instruction_type: "compactupdatevotestate".to_string(),
+				info: json!({
+					"voteAccount": account_keys[0].to_string(),
+					"voteAuthority": account_keys[1].to_string(),
+					"voteStateUpdate": {
+						"lockouts": vote_state_update.lockouts,
+						"root": 300_000_100,
+						"hash": Hash::new_from_array([7; 32]).to_string(),
+						"timestamp": 1_700_000_000,
+					},
+				}),
+			}
+		);
+	}
 }
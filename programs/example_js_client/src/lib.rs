@@ -0,0 +1,11 @@
+#![doc = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/readme.md"))]
+
+use wasm_bindgen::prelude::*;
+pub use wasm_client_solana::JsSolanaClient;
+use wasm_client_solana::LOCALNET;
+
+/// A client for the validator started with `solana-test-validator`.
+#[wasm_bindgen(js_name = localnetClient)]
+pub fn localnet_client() -> JsSolanaClient {
+	JsSolanaClient::new(LOCALNET)
+}
@@ -0,0 +1,70 @@
+#![cfg(target_arch = "wasm32")]
+
+use example_js_client::localnet_client;
+use js_sys::Reflect;
+use solana_sdk::native_token::sol_to_lamports;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+use wasm_client_solana::LOCALNET;
+use wasm_client_solana::SolanaRpcClient;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn error_code(error: &JsValue) -> Option<String> {
+	Reflect::get(error, &"code".into()).ok()?.as_string()
+}
+
+#[wasm_bindgen_test]
+async fn get_balance_of_new_account() {
+	let client = localnet_client();
+	let pubkey = Keypair::new().pubkey().to_string();
+
+	assert_eq!(client.get_balance(pubkey).await.unwrap(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn get_account_info_of_missing_account() {
+	let client = localnet_client();
+	let pubkey = Keypair::new().pubkey().to_string();
+
+	assert!(client.get_account_info(pubkey).await.unwrap().is_null());
+}
+
+#[wasm_bindgen_test]
+async fn invalid_pubkey() {
+	let client = localnet_client();
+	let error = client.get_balance("invalid".into()).await.unwrap_err();
+
+	assert_eq!(error_code(&error).as_deref(), Some("InvalidPubkey"));
+}
+
+#[wasm_bindgen_test]
+async fn invalid_transaction() {
+	let client = localnet_client();
+	let error = client.send_transaction(vec![1, 2, 3]).await.unwrap_err();
+
+	assert_eq!(error_code(&error).as_deref(), Some("InvalidTransaction"));
+}
+
+#[wasm_bindgen_test]
+async fn get_transaction_of_airdrop() {
+	let rpc = SolanaRpcClient::new(LOCALNET);
+	let pubkey = Keypair::new().pubkey();
+	let signature = rpc
+		.request_airdrop(&pubkey, sol_to_lamports(1.0))
+		.await
+		.unwrap();
+	rpc.confirm_transaction(&signature).await.unwrap();
+
+	let client = localnet_client();
+	let transaction = client.get_transaction(signature.to_string()).await.unwrap();
+	let slot = Reflect::get(&transaction, &"slot".into()).unwrap();
+
+	assert!(slot.as_f64().is_some());
+	assert_eq!(
+		client.get_balance(pubkey.to_string()).await.unwrap(),
+		sol_to_lamports(1.0)
+	);
+}